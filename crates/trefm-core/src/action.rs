@@ -7,6 +7,8 @@
 use fuzzy_matcher::skim::SkimMatcherV2;
 use fuzzy_matcher::FuzzyMatcher;
 
+use crate::nav::filter::byte_indices;
+
 /// Every user-triggerable action in TreFM.
 ///
 /// Variants carry no parameters — context is determined at dispatch time.
@@ -22,23 +24,42 @@ pub enum Action {
     GoHome,
     GoBack,
     GoForward,
+    FollowSymlink,
     Refresh,
     // File Operations
     Copy,
     Paste,
     Delete,
     Rename,
+    DuplicateInPlace,
     Open,
     // View
     ToggleHidden,
     Search,
     SortCycle,
+    ToggleSortDirection,
+    ReverseListing,
+    CategoryFilterCycle,
+    ToggleDirsOnly,
+    FilterPanel,
+    TogglePin,
     // Bookmarks
     BookmarkAdd,
     BookmarkGo,
     // Features
     RecentFiles,
+    RecentDirs,
     DuplicateFiles,
+    DuplicateFilesHere,
+    ContentSearch,
+    GitChanges,
+    OperationLog,
+    Notifications,
+    LogViewer,
+    RunCommand,
+    ExecuteFile,
+    CopyTree,
+    CopyGitPath,
     // Pager
     Pager,
     // Editor
@@ -56,11 +77,21 @@ pub enum Action {
     PanelToggleDual,
     PanelFocusLeft,
     PanelFocusRight,
+    CopyToOtherPanel,
+    MoveToOtherPanel,
+    SwapPanels,
+    SyncPanels,
+    DiffOtherPanel,
     // Tab
     TabNew,
+    OpenInNewTab,
     TabClose,
+    TabCloseOthers,
     TabNext,
     TabPrev,
+    TabRename,
+    TabCopyToOtherPanel,
+    TabMoveToOtherPanel,
     TabSelect1,
     TabSelect2,
     TabSelect3,
@@ -99,6 +130,14 @@ impl ActionCategory {
             Self::Tab => "Tab",
         }
     }
+
+    /// Whether `token`, as typed after `@` in the palette, names this
+    /// category: a case-insensitive prefix of its label (`@nav`, `@file`).
+    pub fn matches_token(self, token: &str) -> bool {
+        self.label()
+            .to_lowercase()
+            .starts_with(&token.to_lowercase())
+    }
 }
 
 /// Metadata for a single action — used by the Command Palette UI.
@@ -188,6 +227,13 @@ impl ActionRegistry {
                 description: "Navigate forward in history",
                 category: ActionCategory::Navigation,
             },
+            ActionDescriptor {
+                action: Action::FollowSymlink,
+                id: "follow_symlink",
+                name: "Follow Symlink",
+                description: "Go to the real location of the selected symlink",
+                category: ActionCategory::Navigation,
+            },
             ActionDescriptor {
                 action: Action::Refresh,
                 id: "refresh",
@@ -224,6 +270,13 @@ impl ActionRegistry {
                 description: "Rename selected file",
                 category: ActionCategory::FileOps,
             },
+            ActionDescriptor {
+                action: Action::DuplicateInPlace,
+                id: "duplicate_in_place",
+                name: "Duplicate",
+                description: "Copy the selected entry next to itself as \"name copy\"",
+                category: ActionCategory::FileOps,
+            },
             ActionDescriptor {
                 action: Action::Open,
                 id: "open",
@@ -253,6 +306,48 @@ impl ActionRegistry {
                 description: "Change sort order",
                 category: ActionCategory::View,
             },
+            ActionDescriptor {
+                action: Action::ToggleSortDirection,
+                id: "toggle_sort_direction",
+                name: "Reverse Sort",
+                description: "Flip ascending/descending for the current sort",
+                category: ActionCategory::View,
+            },
+            ActionDescriptor {
+                action: Action::ReverseListing,
+                id: "reverse_listing",
+                name: "Reverse Listing",
+                description: "Show the current entries in reverse order",
+                category: ActionCategory::View,
+            },
+            ActionDescriptor {
+                action: Action::CategoryFilterCycle,
+                id: "category_filter_cycle",
+                name: "Filter by Category",
+                description: "Show only documents, images, code, archives or media",
+                category: ActionCategory::View,
+            },
+            ActionDescriptor {
+                action: Action::ToggleDirsOnly,
+                id: "toggle_dirs_only",
+                name: "Directories Only",
+                description: "Hide files and list only directories",
+                category: ActionCategory::View,
+            },
+            ActionDescriptor {
+                action: Action::FilterPanel,
+                id: "filter_panel",
+                name: "Filter Panel",
+                description: "Narrow the listing to names matching a query as you type",
+                category: ActionCategory::View,
+            },
+            ActionDescriptor {
+                action: Action::TogglePin,
+                id: "toggle_pin",
+                name: "Pin Entry",
+                description: "Pin or unpin the selected entry at the top of its directory",
+                category: ActionCategory::View,
+            },
             // Bookmarks
             ActionDescriptor {
                 action: Action::BookmarkAdd,
@@ -276,6 +371,13 @@ impl ActionRegistry {
                 description: "Show recently changed files",
                 category: ActionCategory::Feature,
             },
+            ActionDescriptor {
+                action: Action::RecentDirs,
+                id: "recent_dirs",
+                name: "Recent Directories",
+                description: "Switch to a recently visited directory",
+                category: ActionCategory::Feature,
+            },
             ActionDescriptor {
                 action: Action::DuplicateFiles,
                 id: "duplicate_files",
@@ -283,6 +385,76 @@ impl ActionRegistry {
                 description: "Find duplicate files",
                 category: ActionCategory::Feature,
             },
+            ActionDescriptor {
+                action: Action::DuplicateFilesHere,
+                id: "duplicate_files_here",
+                name: "Duplicates Here",
+                description: "Scan the current directory for duplicate files",
+                category: ActionCategory::Feature,
+            },
+            ActionDescriptor {
+                action: Action::ContentSearch,
+                id: "content_search",
+                name: "Content Search",
+                description: "Search file contents recursively",
+                category: ActionCategory::Feature,
+            },
+            ActionDescriptor {
+                action: Action::GitChanges,
+                id: "git_changes",
+                name: "Git Changes",
+                description: "List files with uncommitted changes",
+                category: ActionCategory::Feature,
+            },
+            ActionDescriptor {
+                action: Action::OperationLog,
+                id: "operation_log",
+                name: "Operation Log",
+                description: "Show the history of file operations",
+                category: ActionCategory::Feature,
+            },
+            ActionDescriptor {
+                action: Action::Notifications,
+                id: "notifications",
+                name: "Notifications",
+                description: "Show recent status messages in full",
+                category: ActionCategory::Feature,
+            },
+            ActionDescriptor {
+                action: Action::LogViewer,
+                id: "log_viewer",
+                name: "Log Viewer",
+                description: "Follow the TreFM log file",
+                category: ActionCategory::Feature,
+            },
+            ActionDescriptor {
+                action: Action::RunCommand,
+                id: "run_command",
+                name: "Run Command",
+                description: "Run a shell command on the selected file",
+                category: ActionCategory::Feature,
+            },
+            ActionDescriptor {
+                action: Action::ExecuteFile,
+                id: "execute_file",
+                name: "Execute File",
+                description: "Run the selected executable and show its output",
+                category: ActionCategory::Feature,
+            },
+            ActionDescriptor {
+                action: Action::CopyTree,
+                id: "copy_tree",
+                name: "Copy Tree",
+                description: "Copy the selected directory's tree to the clipboard",
+                category: ActionCategory::Feature,
+            },
+            ActionDescriptor {
+                action: Action::CopyGitPath,
+                id: "copy_git_path",
+                name: "Copy Git Path",
+                description: "Copy the selected path relative to the git repository root",
+                category: ActionCategory::Feature,
+            },
             // Pager
             ActionDescriptor {
                 action: Action::Pager,
@@ -366,6 +538,41 @@ impl ActionRegistry {
                 description: "Switch focus to right panel",
                 category: ActionCategory::Navigation,
             },
+            ActionDescriptor {
+                action: Action::CopyToOtherPanel,
+                id: "copy_to_other_panel",
+                name: "Copy to Other Panel",
+                description: "Copy selected file into the other panel's directory",
+                category: ActionCategory::FileOps,
+            },
+            ActionDescriptor {
+                action: Action::MoveToOtherPanel,
+                id: "move_to_other_panel",
+                name: "Move to Other Panel",
+                description: "Move selected file into the other panel's directory",
+                category: ActionCategory::FileOps,
+            },
+            ActionDescriptor {
+                action: Action::SwapPanels,
+                id: "swap_panels",
+                name: "Swap Panels",
+                description: "Exchange the left and right panels",
+                category: ActionCategory::View,
+            },
+            ActionDescriptor {
+                action: Action::SyncPanels,
+                id: "sync_panels",
+                name: "Sync Panels",
+                description: "Open the current directory in the other panel",
+                category: ActionCategory::View,
+            },
+            ActionDescriptor {
+                action: Action::DiffOtherPanel,
+                id: "diff_other_panel",
+                name: "Diff with Other Panel",
+                description: "Compare the selected file side by side with the same-named file in the other panel",
+                category: ActionCategory::View,
+            },
             // Tab
             ActionDescriptor {
                 action: Action::TabNew,
@@ -374,6 +581,13 @@ impl ActionRegistry {
                 description: "Open a new tab",
                 category: ActionCategory::Tab,
             },
+            ActionDescriptor {
+                action: Action::OpenInNewTab,
+                id: "open_in_new_tab",
+                name: "Open in New Tab",
+                description: "Open the selected directory in a new tab",
+                category: ActionCategory::Tab,
+            },
             ActionDescriptor {
                 action: Action::TabClose,
                 id: "tab_close",
@@ -381,6 +595,13 @@ impl ActionRegistry {
                 description: "Close current tab",
                 category: ActionCategory::Tab,
             },
+            ActionDescriptor {
+                action: Action::TabCloseOthers,
+                id: "tab_close_others",
+                name: "Close Other Tabs",
+                description: "Close every tab except the current one",
+                category: ActionCategory::Tab,
+            },
             ActionDescriptor {
                 action: Action::TabNext,
                 id: "tab_next",
@@ -395,6 +616,27 @@ impl ActionRegistry {
                 description: "Switch to previous tab",
                 category: ActionCategory::Tab,
             },
+            ActionDescriptor {
+                action: Action::TabRename,
+                id: "tab_rename",
+                name: "Rename Tab",
+                description: "Give the current tab a custom name",
+                category: ActionCategory::Tab,
+            },
+            ActionDescriptor {
+                action: Action::TabCopyToOtherPanel,
+                id: "tab_copy_to_other_panel",
+                name: "Copy Tab to Other Panel",
+                description: "Open the current tab in the other panel too",
+                category: ActionCategory::Tab,
+            },
+            ActionDescriptor {
+                action: Action::TabMoveToOtherPanel,
+                id: "tab_move_to_other_panel",
+                name: "Move Tab to Other Panel",
+                description: "Move the current tab to the other panel",
+                category: ActionCategory::Tab,
+            },
             ActionDescriptor {
                 action: Action::TabSelect1,
                 id: "tab_select_1",
@@ -469,14 +711,21 @@ impl ActionRegistry {
 
     /// Fuzzy-searches descriptors by matching against name, description, and id.
     /// Returns results sorted by match score (best first).
+    ///
+    /// A leading `@token` (e.g. `@nav`) keeps only the categories that
+    /// [`ActionCategory::matches_token`] accepts; the rest of the query is
+    /// matched as usual, and an empty rest lists the whole category.
     pub fn fuzzy_search(&self, query: &str) -> Vec<&ActionDescriptor> {
+        let (token, query) = split_category_token(query);
+        let candidates = self
+            .descriptors
+            .iter()
+            .filter(|d| token.is_none_or(|t| d.category.matches_token(t)));
         if query.is_empty() {
-            return self.descriptors.iter().collect();
+            return candidates.collect();
         }
         let matcher = SkimMatcherV2::default();
-        let mut scored: Vec<(i64, &ActionDescriptor)> = self
-            .descriptors
-            .iter()
+        let mut scored: Vec<(i64, &ActionDescriptor)> = candidates
             .filter_map(|d| {
                 let name_score = matcher.fuzzy_match(d.name, query).unwrap_or(0);
                 let desc_score = matcher.fuzzy_match(d.description, query).unwrap_or(0);
@@ -489,7 +738,7 @@ impl ActionRegistry {
                 }
             })
             .collect();
-        scored.sort_by(|a, b| b.0.cmp(&a.0));
+        scored.sort_by_key(|s| std::cmp::Reverse(s.0));
         scored.into_iter().map(|(_, d)| d).collect()
     }
 
@@ -507,6 +756,33 @@ impl ActionRegistry {
     }
 }
 
+/// Byte indices in `descriptor.name` that `query` matches, for highlighting
+/// a [`fuzzy_search`](ActionRegistry::fuzzy_search) result. A leading
+/// `@token` is ignored. Empty when the name itself does not match, such as
+/// when the result was found by its description or id.
+pub fn matched_name_indices(descriptor: &ActionDescriptor, query: &str) -> Vec<usize> {
+    let (_, query) = split_category_token(query);
+    if query.is_empty() {
+        return Vec::new();
+    }
+    SkimMatcherV2::default()
+        .fuzzy_indices(descriptor.name, query)
+        .map(|(_, indices)| byte_indices(descriptor.name, &indices))
+        .unwrap_or_default()
+}
+
+/// Splits a leading `@token` off a palette query: `"@nav up"` gives
+/// `(Some("nav"), "up")`, a query without `@` is returned whole.
+fn split_category_token(query: &str) -> (Option<&str>, &str) {
+    match query.strip_prefix('@') {
+        Some(rest) => {
+            let (token, rest) = rest.split_once(' ').unwrap_or((rest, ""));
+            (Some(token), rest.trim_start())
+        }
+        None => (None, query),
+    }
+}
+
 impl Default for ActionRegistry {
     fn default() -> Self {
         Self::new()
@@ -566,6 +842,58 @@ mod tests {
         assert!(actions.contains(&Action::BookmarkGo));
     }
 
+    #[test]
+    fn category_token_restricts_results() {
+        let registry = ActionRegistry::new();
+
+        let results = registry.fuzzy_search("@nav");
+        assert!(!results.is_empty());
+        assert!(results
+            .iter()
+            .all(|d| d.category == ActionCategory::Navigation));
+        let navigation = registry
+            .all()
+            .iter()
+            .filter(|d| d.category == ActionCategory::Navigation)
+            .count();
+        assert_eq!(results.len(), navigation);
+
+        let results = registry.fuzzy_search("@TAB close");
+        assert!(!results.is_empty());
+        assert!(results.iter().all(|d| d.category == ActionCategory::Tab));
+        assert_eq!(results[0].action, Action::TabClose);
+    }
+
+    #[test]
+    fn matched_name_indices_follow_the_query() {
+        let registry = ActionRegistry::new();
+        let descriptor = registry.descriptor_for(Action::TabClose).unwrap();
+        assert_eq!(descriptor.name, "Close Tab");
+
+        let indices = matched_name_indices(descriptor, "@tab clt");
+        assert_eq!(indices, [0, 1, 6]);
+        assert!(indices.windows(2).all(|w| w[0] < w[1]));
+        assert!(matched_name_indices(descriptor, "@tab").is_empty());
+        assert!(matched_name_indices(descriptor, "zzz").is_empty());
+    }
+
+    #[test]
+    fn unknown_category_token_matches_nothing() {
+        let registry = ActionRegistry::new();
+        assert!(registry.fuzzy_search("@nosuch").is_empty());
+    }
+
+    #[test]
+    fn plain_query_searches_all_categories() {
+        let registry = ActionRegistry::new();
+        let categories: Vec<ActionCategory> = registry
+            .fuzzy_search("panel")
+            .iter()
+            .map(|d| d.category)
+            .collect();
+        assert!(categories.iter().any(|c| *c != categories[0]));
+    }
+
     #[test]
     fn fuzzy_search_no_match() {
         let registry = ActionRegistry::new();
@@ -0,0 +1,131 @@
+//! `trefm` — non-interactive access to trefm-core for scripts.
+//!
+//! ```text
+//! trefm ls [--json] [-a] [PATH]
+//! trefm dups [--json] [--depth N] [--hash ALGO] [--no-hidden] [--exclude NAME]... [--no-default-excludes] [ROOT]
+//! ```
+
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use trefm_core::fs::{scan_duplicates, DuplicateScanOptions, HashAlgo, DEFAULT_SCAN_EXCLUSIONS};
+use trefm_core::json::{directory_json, duplicates_json, list_directory};
+
+const USAGE: &str = "usage: trefm ls [--json] [-a] [PATH]
+       trefm dups [--json] [--depth N] [--hash sha256|blake3|xxh3] [--no-hidden]
+                  [--exclude NAME]... [--no-default-excludes] [ROOT]";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let result = match args.first().map(String::as_str) {
+        Some("ls") => ls(&args[1..]),
+        Some("dups") => dups(&args[1..]),
+        _ => Err(USAGE.to_string()),
+    };
+    match result {
+        Ok(output) => {
+            println!("{output}");
+            ExitCode::SUCCESS
+        }
+        Err(msg) => {
+            eprintln!("{msg}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn ls(args: &[String]) -> Result<String, String> {
+    let mut json = false;
+    let mut show_hidden = false;
+    let mut path = None;
+    for arg in args {
+        match arg.as_str() {
+            "--json" => json = true,
+            "-a" | "--all" => show_hidden = true,
+            flag if flag.starts_with('-') => return Err(format!("unknown flag: {flag}\n{USAGE}")),
+            p if path.is_none() => path = Some(PathBuf::from(p)),
+            _ => return Err(USAGE.to_string()),
+        }
+    }
+    let path = path.unwrap_or_else(|| PathBuf::from("."));
+
+    if json {
+        return directory_json(&path, show_hidden).map_err(|e| format!("trefm: {e}"));
+    }
+    let entries = list_directory(&path, show_hidden).map_err(|e| format!("trefm: {e}"))?;
+    Ok(entries
+        .iter()
+        .map(|e| {
+            if e.is_dir() {
+                format!("{}/", e.name())
+            } else {
+                e.name().to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+/// Same defaults as the TUI's background scanner.
+fn dups(args: &[String]) -> Result<String, String> {
+    let mut json = false;
+    let mut max_depth = 20;
+    let mut algo = HashAlgo::default();
+    let mut show_hidden = true;
+    let mut use_defaults = true;
+    let mut extra: Vec<String> = Vec::new();
+    let mut root = None;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--json" => json = true,
+            "--no-hidden" => show_hidden = false,
+            "--no-default-excludes" => use_defaults = false,
+            "--depth" => {
+                max_depth = iter
+                    .next()
+                    .and_then(|v| v.parse().ok())
+                    .ok_or_else(|| format!("--depth needs a number\n{USAGE}"))?;
+            }
+            "--hash" => {
+                algo = iter
+                    .next()
+                    .and_then(|v| HashAlgo::from_name(v))
+                    .ok_or_else(|| format!("--hash needs sha256, blake3 or xxh3\n{USAGE}"))?;
+            }
+            "--exclude" => {
+                let name = iter
+                    .next()
+                    .ok_or_else(|| format!("--exclude needs a name\n{USAGE}"))?;
+                extra.push(name.clone());
+            }
+            flag if flag.starts_with('-') => return Err(format!("unknown flag: {flag}\n{USAGE}")),
+            p if root.is_none() => root = Some(PathBuf::from(p)),
+            _ => return Err(USAGE.to_string()),
+        }
+    }
+    let root = root.unwrap_or_else(|| PathBuf::from("."));
+
+    let mut exclusions: HashSet<&str> = extra.iter().map(String::as_str).collect();
+    if use_defaults {
+        exclusions.extend(DEFAULT_SCAN_EXCLUSIONS.iter().copied());
+    }
+
+    let cache = scan_duplicates(
+        &root,
+        max_depth,
+        show_hidden,
+        &exclusions,
+        &DuplicateScanOptions {
+            algo,
+            ..DuplicateScanOptions::default()
+        },
+    )
+    .map_err(|e| format!("trefm: {e}"))?;
+    if json {
+        duplicates_json(&cache).map_err(|e| format!("trefm: {e}"))
+    } else {
+        Ok(cache.format_report().trim_end().to_string())
+    }
+}
@@ -1,13 +1,15 @@
 //! Key binding configuration.
 //!
 //! Key bindings map key names (e.g. `"j"`, `"gg"`, `"Enter"`) to [`Action`]
-//! values. The default bindings follow vim conventions.
+//! values. The default bindings follow vim conventions; a `[keymap] profile`
+//! in `keymap.toml` can pick the arrow- and function-key-centric `classic`
+//! set instead (see [`KeymapProfile`]).
 //!
 //! TOML files still use string action identifiers (e.g. `"cursor_down"`);
 //! these are resolved to [`Action`] via [`ActionRegistry::find_by_id`] at load time.
 
 use std::collections::HashMap;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use serde::{Deserialize, Serialize};
 
@@ -23,13 +25,37 @@ pub struct KeyBinding {
     pub action: String,
 }
 
+/// A built-in set of default bindings.
+///
+/// Arrow keys and Enter move and open in every profile; the profile decides
+/// what the remaining keys do.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeymapProfile {
+    /// Single-letter vim-style keys (`j`/`k`/`h`/`l`, `gg`, `G`, ...).
+    #[default]
+    Vim,
+    /// Orthodox file manager keys: Home/End, Backspace, Delete and the
+    /// function keys (F3 view, F5 copy, F8 delete, F10 quit, ...).
+    Classic,
+}
+
 /// Raw TOML representation — deserialized first, then resolved.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct RawKeymap {
+    #[serde(default)]
+    keymap: Option<RawProfile>,
     #[serde(default)]
     bindings: HashMap<String, String>,
 }
 
+/// The `[keymap]` section.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RawProfile {
+    #[serde(default)]
+    profile: KeymapProfile,
+}
+
 /// Complete set of key bindings.
 ///
 /// Stores bindings as a `HashMap<String, Action>` for O(1) lookup.
@@ -41,61 +67,136 @@ pub struct Keymap {
     reverse: HashMap<Action, Vec<String>>,
 }
 
+/// The shipped `config/keymap.toml`.
+const KEYMAP_TEMPLATE: &str = concat!(
+    "# TreFM key bindings: key = \"action_id\". Listed bindings replace the defaults.\n",
+    "# Action IDs are listed in the README (Configuration → Key Bindings).\n",
+    "# To start from a built-in profile instead and only list changes on top of it:\n",
+    "#   [keymap]\n",
+    "#   profile = \"vim\"   # or \"classic\" (Home/End, Backspace, Delete, F1-F10)\n\n",
+    include_str!("../../../../config/keymap.toml"),
+);
+
 impl Default for Keymap {
     fn default() -> Self {
-        let mut bindings = HashMap::new();
-
-        // Navigation
-        bindings.insert("j".to_string(), Action::CursorDown);
-        bindings.insert("k".to_string(), Action::CursorUp);
-        bindings.insert("h".to_string(), Action::GoParent);
-        bindings.insert("l".to_string(), Action::EnterDir);
-        bindings.insert("gg".to_string(), Action::CursorTop);
-        bindings.insert("G".to_string(), Action::CursorBottom);
-        bindings.insert("Enter".to_string(), Action::Open);
-
-        // File operations
-        bindings.insert("y".to_string(), Action::Copy);
-        bindings.insert("d".to_string(), Action::Delete);
-        bindings.insert("p".to_string(), Action::Pager);
-        bindings.insert("r".to_string(), Action::Rename);
-
-        // Toggles and search
-        bindings.insert(".".to_string(), Action::ToggleHidden);
-        bindings.insert("/".to_string(), Action::Search);
-        bindings.insert("s".to_string(), Action::SortCycle);
-
-        // Bookmarks
-        bindings.insert("b".to_string(), Action::BookmarkAdd);
-        bindings.insert("'".to_string(), Action::BookmarkGo);
-
-        // Features
-        bindings.insert("R".to_string(), Action::RecentFiles);
-        bindings.insert("D".to_string(), Action::DuplicateFiles);
-
-        // Panels and misc
-        bindings.insert("q".to_string(), Action::Quit);
-        bindings.insert("?".to_string(), Action::Help);
-
-        // Command palette
-        bindings.insert(":".to_string(), Action::CommandPalette);
-
-        // Dual panel
-        bindings.insert("Tab".to_string(), Action::PanelToggleDual);
-        bindings.insert("1".to_string(), Action::PanelFocusLeft);
-        bindings.insert("2".to_string(), Action::PanelFocusRight);
-
-        // Tabs
-        bindings.insert("t".to_string(), Action::TabNew);
-        bindings.insert("w".to_string(), Action::TabClose);
-        bindings.insert("]".to_string(), Action::TabNext);
-        bindings.insert("[".to_string(), Action::TabPrev);
-
-        let reverse = build_reverse(&bindings);
-        Self { bindings, reverse }
+        Self::for_profile(KeymapProfile::Vim)
     }
 }
 
+/// The [`KeymapProfile::Vim`] bindings.
+fn vim_bindings() -> HashMap<String, Action> {
+    let mut bindings = HashMap::new();
+
+    // Navigation
+    bindings.insert("j".to_string(), Action::CursorDown);
+    bindings.insert("k".to_string(), Action::CursorUp);
+    bindings.insert("h".to_string(), Action::GoParent);
+    bindings.insert("l".to_string(), Action::EnterDir);
+    bindings.insert("gg".to_string(), Action::CursorTop);
+    bindings.insert("G".to_string(), Action::CursorBottom);
+    bindings.insert("Enter".to_string(), Action::Open);
+    bindings.insert("J".to_string(), Action::FollowSymlink);
+
+    // File operations
+    bindings.insert("y".to_string(), Action::Copy);
+    bindings.insert("P".to_string(), Action::Paste);
+    bindings.insert("d".to_string(), Action::Delete);
+    bindings.insert("p".to_string(), Action::Pager);
+    bindings.insert("r".to_string(), Action::Rename);
+    bindings.insert("N".to_string(), Action::DuplicateInPlace);
+
+    // Toggles and search
+    bindings.insert(".".to_string(), Action::ToggleHidden);
+    bindings.insert("/".to_string(), Action::Search);
+    bindings.insert("s".to_string(), Action::SortCycle);
+    bindings.insert("S".to_string(), Action::ToggleSortDirection);
+    bindings.insert("I".to_string(), Action::ReverseListing);
+    bindings.insert("f".to_string(), Action::CategoryFilterCycle);
+    bindings.insert("O".to_string(), Action::ToggleDirsOnly);
+    bindings.insert("|".to_string(), Action::FilterPanel);
+    bindings.insert("*".to_string(), Action::TogglePin);
+
+    // Bookmarks
+    bindings.insert("b".to_string(), Action::BookmarkAdd);
+    bindings.insert("'".to_string(), Action::BookmarkGo);
+
+    // Features
+    bindings.insert("R".to_string(), Action::RecentFiles);
+    bindings.insert("z".to_string(), Action::RecentDirs);
+    bindings.insert("D".to_string(), Action::DuplicateFiles);
+    bindings.insert("H".to_string(), Action::DuplicateFilesHere);
+    bindings.insert("F".to_string(), Action::ContentSearch);
+    bindings.insert("c".to_string(), Action::GitChanges);
+    bindings.insert("L".to_string(), Action::OperationLog);
+    bindings.insert("m".to_string(), Action::Notifications);
+    bindings.insert("V".to_string(), Action::LogViewer);
+    bindings.insert("!".to_string(), Action::RunCommand);
+    bindings.insert("X".to_string(), Action::ExecuteFile);
+    bindings.insert("E".to_string(), Action::CopyTree);
+    bindings.insert("K".to_string(), Action::CopyGitPath);
+
+    // Panels and misc
+    bindings.insert("q".to_string(), Action::Quit);
+    bindings.insert("?".to_string(), Action::Help);
+
+    // Command palette
+    bindings.insert(":".to_string(), Action::CommandPalette);
+
+    // Dual panel
+    bindings.insert("Tab".to_string(), Action::PanelToggleDual);
+    bindings.insert("1".to_string(), Action::PanelFocusLeft);
+    bindings.insert("2".to_string(), Action::PanelFocusRight);
+    bindings.insert("Y".to_string(), Action::CopyToOtherPanel);
+    bindings.insert("M".to_string(), Action::MoveToOtherPanel);
+    bindings.insert("x".to_string(), Action::SwapPanels);
+    bindings.insert("=".to_string(), Action::SyncPanels);
+
+    // Tabs
+    bindings.insert("t".to_string(), Action::TabNew);
+    bindings.insert("w".to_string(), Action::TabClose);
+    bindings.insert("W".to_string(), Action::TabCloseOthers);
+    bindings.insert("]".to_string(), Action::TabNext);
+    bindings.insert("[".to_string(), Action::TabPrev);
+    bindings.insert("T".to_string(), Action::TabRename);
+
+    bindings
+}
+
+/// The [`KeymapProfile::Classic`] bindings.
+fn classic_bindings() -> HashMap<String, Action> {
+    let mut bindings = HashMap::new();
+
+    // Navigation
+    bindings.insert("Home".to_string(), Action::CursorTop);
+    bindings.insert("End".to_string(), Action::CursorBottom);
+    bindings.insert("Backspace".to_string(), Action::GoParent);
+    bindings.insert("Enter".to_string(), Action::Open);
+    bindings.insert("~".to_string(), Action::GoHome);
+
+    // Function keys
+    bindings.insert("F1".to_string(), Action::Help);
+    bindings.insert("F2".to_string(), Action::Rename);
+    bindings.insert("F3".to_string(), Action::Pager);
+    bindings.insert("F4".to_string(), Action::EditFile);
+    bindings.insert("F5".to_string(), Action::CopyToOtherPanel);
+    bindings.insert("F6".to_string(), Action::MoveToOtherPanel);
+    bindings.insert("F8".to_string(), Action::Delete);
+    bindings.insert("F9".to_string(), Action::CommandPalette);
+    bindings.insert("F10".to_string(), Action::Quit);
+    bindings.insert("Delete".to_string(), Action::Delete);
+
+    // Symbols
+    bindings.insert(".".to_string(), Action::ToggleHidden);
+    bindings.insert("/".to_string(), Action::Search);
+    bindings.insert(":".to_string(), Action::CommandPalette);
+    bindings.insert("?".to_string(), Action::Help);
+
+    // Dual panel
+    bindings.insert("Tab".to_string(), Action::PanelToggleDual);
+
+    bindings
+}
+
 /// Builds the reverse mapping from Action → Vec<key string>.
 fn build_reverse(bindings: &HashMap<String, Action>) -> HashMap<Action, Vec<String>> {
     let mut reverse: HashMap<Action, Vec<String>> = HashMap::new();
@@ -131,16 +232,45 @@ impl Keymap {
         Ok(Self::from_raw(raw))
     }
 
+    /// Writes the commented default `keymap.toml` into `dir`.
+    ///
+    /// # Errors
+    ///
+    /// - [`CoreError::AlreadyExists`] if `dir/keymap.toml` exists; it is left untouched.
+    /// - [`CoreError::Io`] if `dir` cannot be created or written.
+    pub fn write_default(dir: &Path) -> CoreResult<PathBuf> {
+        super::write_template(dir, "keymap.toml", KEYMAP_TEMPLATE)
+    }
+
+    /// Writes the default `keymap.toml` into `dir`, replacing an existing one
+    /// after backing it up if it was edited (see [`super::TemplateWrite`]).
+    pub fn write_default_forced(dir: &Path) -> CoreResult<super::TemplateWrite> {
+        super::replace_template(dir, "keymap.toml", KEYMAP_TEMPLATE)
+    }
+
+    /// Returns the built-in bindings of `profile`.
+    pub fn for_profile(profile: KeymapProfile) -> Self {
+        let bindings = match profile {
+            KeymapProfile::Vim => vim_bindings(),
+            KeymapProfile::Classic => classic_bindings(),
+        };
+        let reverse = build_reverse(&bindings);
+        Self { bindings, reverse }
+    }
+
     /// Converts a raw (string-based) keymap into a resolved one.
+    ///
+    /// With a `[keymap]` section the bindings override the chosen profile's;
+    /// without one they are the whole keymap.
     fn from_raw(raw: RawKeymap) -> Self {
         let registry = ActionRegistry::new();
-        let bindings: HashMap<String, Action> = raw
-            .bindings
-            .into_iter()
-            .filter_map(|(key, action_id)| {
-                registry.find_by_id(&action_id).map(|action| (key, action))
-            })
-            .collect();
+        let mut bindings = match raw.keymap {
+            Some(section) => Self::for_profile(section.profile).bindings,
+            None => HashMap::new(),
+        };
+        bindings.extend(raw.bindings.into_iter().filter_map(|(key, action_id)| {
+            registry.find_by_id(&action_id).map(|action| (key, action))
+        }));
         let reverse = build_reverse(&bindings);
         Self { bindings, reverse }
     }
@@ -167,6 +297,29 @@ mod tests {
     use std::fs;
     use tempfile::TempDir;
 
+    #[test]
+    fn write_default_creates_loadable_file() {
+        let tmp = TempDir::new().unwrap();
+        let path = Keymap::write_default(tmp.path()).unwrap();
+
+        let keymap = Keymap::load(&path).unwrap();
+        assert_eq!(keymap.action_for_key("j"), Some(Action::CursorDown));
+        assert_eq!(keymap.action_for_key("V"), Some(Action::LogViewer));
+    }
+
+    #[test]
+    fn write_default_refuses_to_overwrite() {
+        let tmp = TempDir::new().unwrap();
+        fs::write(tmp.path().join("keymap.toml"), "[bindings]\n").unwrap();
+
+        let err = Keymap::write_default(tmp.path()).unwrap_err();
+        assert!(matches!(err, CoreError::AlreadyExists(_)));
+        assert_eq!(
+            fs::read_to_string(tmp.path().join("keymap.toml")).unwrap(),
+            "[bindings]\n"
+        );
+    }
+
     #[test]
     fn default_keymap_has_navigation_keys() {
         let keymap = Keymap::default();
@@ -185,6 +338,7 @@ mod tests {
         let keymap = Keymap::default();
 
         assert_eq!(keymap.action_for_key("y"), Some(Action::Copy));
+        assert_eq!(keymap.action_for_key("P"), Some(Action::Paste));
         assert_eq!(keymap.action_for_key("d"), Some(Action::Delete));
         assert_eq!(keymap.action_for_key("p"), Some(Action::Pager));
         assert_eq!(keymap.action_for_key("r"), Some(Action::Rename));
@@ -205,6 +359,11 @@ mod tests {
 
         assert_eq!(keymap.action_for_key("b"), Some(Action::BookmarkAdd));
         assert_eq!(keymap.action_for_key("'"), Some(Action::BookmarkGo));
+        assert_eq!(keymap.action_for_key("F"), Some(Action::ContentSearch));
+        assert_eq!(keymap.action_for_key("c"), Some(Action::GitChanges));
+        assert_eq!(keymap.action_for_key("L"), Some(Action::OperationLog));
+        assert_eq!(keymap.action_for_key("V"), Some(Action::LogViewer));
+        assert_eq!(keymap.action_for_key("!"), Some(Action::RunCommand));
         assert_eq!(keymap.action_for_key("q"), Some(Action::Quit));
         assert_eq!(keymap.action_for_key("?"), Some(Action::Help));
     }
@@ -218,7 +377,7 @@ mod tests {
     #[test]
     fn action_for_unknown_key_returns_none() {
         let keymap = Keymap::default();
-        assert_eq!(keymap.action_for_key("z"), None);
+        assert_eq!(keymap.action_for_key("Z"), None);
         assert_eq!(keymap.action_for_key(""), None);
         assert_eq!(keymap.action_for_key("Ctrl+X"), None);
     }
@@ -247,6 +406,76 @@ x = "quit"
         assert_eq!(keymap.action_for_key("h"), None);
     }
 
+    #[test]
+    fn classic_profile_is_arrow_centric() {
+        let keymap = Keymap::for_profile(KeymapProfile::Classic);
+
+        assert_eq!(keymap.action_for_key("Home"), Some(Action::CursorTop));
+        assert_eq!(keymap.action_for_key("End"), Some(Action::CursorBottom));
+        assert_eq!(keymap.action_for_key("Backspace"), Some(Action::GoParent));
+        assert_eq!(keymap.action_for_key("F5"), Some(Action::CopyToOtherPanel));
+        assert_eq!(keymap.action_for_key("F10"), Some(Action::Quit));
+        // No vim letters
+        assert_eq!(keymap.action_for_key("j"), None);
+        assert_eq!(keymap.action_for_key("h"), None);
+        assert_eq!(keymap.action_for_key("q"), None);
+    }
+
+    #[test]
+    fn vim_profile_is_the_default() {
+        let keymap = Keymap::for_profile(KeymapProfile::Vim);
+        assert_eq!(keymap.bindings(), Keymap::default().bindings());
+    }
+
+    #[test]
+    fn load_profile_keymap_layers_bindings_on_top() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("keymap.toml");
+        fs::write(
+            &path,
+            r#"
+[keymap]
+profile = "classic"
+
+[bindings]
+F10 = "help"
+q = "quit"
+"#,
+        )
+        .unwrap();
+
+        let keymap = Keymap::load(&path).unwrap();
+
+        assert_eq!(keymap.action_for_key("F10"), Some(Action::Help));
+        assert_eq!(keymap.action_for_key("q"), Some(Action::Quit));
+        assert_eq!(keymap.action_for_key("F5"), Some(Action::CopyToOtherPanel));
+        assert_eq!(keymap.action_for_key("j"), None);
+    }
+
+    #[test]
+    fn load_profile_section_defaults_to_vim() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("keymap.toml");
+        fs::write(&path, "[keymap]\n\n[bindings]\nx = \"quit\"\n").unwrap();
+
+        let keymap = Keymap::load(&path).unwrap();
+
+        assert_eq!(keymap.action_for_key("j"), Some(Action::CursorDown));
+        assert_eq!(keymap.action_for_key("x"), Some(Action::Quit));
+    }
+
+    #[test]
+    fn load_unknown_profile_returns_config_parse() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("keymap.toml");
+        fs::write(&path, "[keymap]\nprofile = \"emacs\"\n").unwrap();
+
+        assert!(matches!(
+            Keymap::load(&path).unwrap_err(),
+            CoreError::ConfigParse(_)
+        ));
+    }
+
     #[test]
     fn load_custom_keymap_ignores_unknown_actions() {
         let tmp = TempDir::new().unwrap();
@@ -0,0 +1,145 @@
+//! Per-directory overrides from `.trefm.toml`.
+//!
+//! A directory may contain a `.trefm.toml` laid out like `default.toml`.
+//! While a panel shows that directory, the keys it sets win over the global
+//! settings, which in turn win over the built-in defaults; keys it leaves out
+//! keep the global value. Only browsing settings ([`LOCAL_KEYS`]) can be
+//! overridden, so a file that arrives with a cloned repository cannot change
+//! the shell or turn off delete confirmation. TreFM only ever reads the file.
+
+use std::path::Path;
+
+use super::settings::Config;
+use crate::error::{CoreError, CoreResult};
+
+/// File name looked up in each directory a panel enters.
+pub const LOCAL_CONFIG_FILE: &str = ".trefm.toml";
+
+/// `(table, key)` pairs a local file may set. Everything else is ignored.
+pub const LOCAL_KEYS: &[(&str, &str)] = &[
+    ("general", "show_hidden"),
+    ("general", "default_sort"),
+    ("general", "sort_dir_first"),
+];
+
+impl Config {
+    /// Returns `base` with the [`LOCAL_KEYS`] set in `local_path` layered on top.
+    ///
+    /// A missing file yields an unchanged copy of `base`.
+    ///
+    /// # Errors
+    ///
+    /// - [`CoreError::PermissionDenied`] if the file is not readable.
+    /// - [`CoreError::ConfigParse`] if the TOML is malformed or a value has the wrong type.
+    pub fn merge_local(base: &Config, local_path: &Path) -> CoreResult<Config> {
+        let content = match std::fs::read_to_string(local_path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(base.clone()),
+            Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied => {
+                return Err(CoreError::PermissionDenied(local_path.to_path_buf()))
+            }
+            Err(e) => return Err(CoreError::Io(e)),
+        };
+        let parse_error = |e: &dyn std::fmt::Display| {
+            CoreError::ConfigParse(format!("{}: {e}", local_path.display()))
+        };
+        let local: toml::Table = toml::from_str(&content).map_err(|e| parse_error(&e))?;
+
+        let mut merged = toml::Value::try_from(base).map_err(|e| parse_error(&e))?;
+        for (table, key) in LOCAL_KEYS {
+            let Some(value) = local.get(*table).and_then(|t| t.get(*key)) else {
+                continue;
+            };
+            if let Some(section) = merged.get_mut(*table).and_then(toml::Value::as_table_mut) {
+                section.insert((*key).to_string(), value.clone());
+            }
+        }
+        merged.try_into().map_err(|e| parse_error(&e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn local_file(tmp: &TempDir, content: &str) -> std::path::PathBuf {
+        let path = tmp.path().join(LOCAL_CONFIG_FILE);
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn partial_local_config_overrides_only_its_keys() {
+        let tmp = TempDir::new().unwrap();
+        let path = local_file(&tmp, "[general]\ndefault_sort = \"date\"\n");
+
+        let merged = Config::merge_local(&Config::default(), &path).unwrap();
+
+        assert_eq!(merged.general.default_sort, "date");
+        assert!(!merged.general.show_hidden);
+        assert!(merged.general.sort_dir_first);
+    }
+
+    #[test]
+    fn local_config_wins_over_global() {
+        let tmp = TempDir::new().unwrap();
+        let path = local_file(&tmp, "[general]\nshow_hidden = false\n");
+        let mut base = Config::default();
+        base.general.show_hidden = true;
+        base.general.default_sort = "size".to_string();
+
+        let merged = Config::merge_local(&base, &path).unwrap();
+
+        assert!(!merged.general.show_hidden);
+        assert_eq!(merged.general.default_sort, "size");
+    }
+
+    #[test]
+    fn missing_local_config_returns_base() {
+        let tmp = TempDir::new().unwrap();
+        let mut base = Config::default();
+        base.general.show_hidden = true;
+
+        let merged = Config::merge_local(&base, &tmp.path().join(LOCAL_CONFIG_FILE)).unwrap();
+
+        assert!(merged.general.show_hidden);
+    }
+
+    #[test]
+    fn keys_outside_allow_list_are_ignored() {
+        let tmp = TempDir::new().unwrap();
+        let path = local_file(
+            &tmp,
+            "[general]\nconfirm_delete = false\nshow_hidden = true\n\n[terminal]\nshell = \"/tmp/evil\"\n",
+        );
+
+        let merged = Config::merge_local(&Config::default(), &path).unwrap();
+
+        assert!(merged.general.show_hidden);
+        assert!(merged.general.confirm_delete);
+        assert_eq!(merged.terminal.shell, Config::default().terminal.shell);
+    }
+
+    #[test]
+    fn wrong_type_is_parse_error() {
+        let tmp = TempDir::new().unwrap();
+        let path = local_file(&tmp, "[general]\nshow_hidden = \"yes\"\n");
+
+        let err = Config::merge_local(&Config::default(), &path).unwrap_err();
+
+        assert!(matches!(err, CoreError::ConfigParse(_)));
+    }
+
+    #[test]
+    fn local_config_file_is_never_written() {
+        let tmp = TempDir::new().unwrap();
+        let content = "[general]\nsort_dir_first = false\n";
+        let path = local_file(&tmp, content);
+
+        Config::merge_local(&Config::default(), &path).unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), content);
+    }
+}
@@ -1,8 +1,163 @@
 //! Configuration management for TreFM.
 //!
 //! User preferences ([`settings::Config`]) and key bindings ([`keymap::Keymap`])
-//! are stored as TOML files and loaded at startup.
+//! are stored as TOML files and loaded at startup. [`validate`] adds a strict
+//! loader that reports unknown keys and mistyped values, and [`local`] layers a
+//! directory's `.trefm.toml` over the global settings.
 
 pub mod keymap;
+pub mod local;
 pub mod settings;
 pub mod theme;
+pub mod validate;
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::error::{CoreError, CoreResult};
+
+/// Writes `contents` to `dir/file_name`, creating `dir` if needed.
+///
+/// Never replaces an existing file: the file is created with `create_new`,
+/// so [`CoreError::AlreadyExists`] is returned if anything is already there.
+pub(crate) fn write_template(dir: &Path, file_name: &str, contents: &str) -> CoreResult<PathBuf> {
+    std::fs::create_dir_all(dir)?;
+    let path = dir.join(file_name);
+    let mut file = std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&path)
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::AlreadyExists => CoreError::AlreadyExists(path.clone()),
+            std::io::ErrorKind::PermissionDenied => CoreError::PermissionDenied(path.clone()),
+            _ => CoreError::Io(e),
+        })?;
+    file.write_all(contents.as_bytes())?;
+    Ok(path)
+}
+
+/// What [`replace_template`] did with `dir/file_name`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplateWrite {
+    /// There was no file, so the template was written.
+    Created(PathBuf),
+    /// The file already held the shipped template and was left alone.
+    Unchanged(PathBuf),
+    /// The file had been edited; it was copied to `backup` and then replaced.
+    Replaced { path: PathBuf, backup: PathBuf },
+}
+
+/// Writes `contents` to `dir/file_name` even if a file is there (`--init --force`).
+///
+/// A file that differs from `contents` is first copied to `file_name.bak`,
+/// or `file_name.bak.N` when earlier backups exist, so user edits are never
+/// lost.
+pub(crate) fn replace_template(
+    dir: &Path,
+    file_name: &str,
+    contents: &str,
+) -> CoreResult<TemplateWrite> {
+    let path = match write_template(dir, file_name, contents) {
+        Ok(path) => return Ok(TemplateWrite::Created(path)),
+        Err(CoreError::AlreadyExists(path)) => path,
+        Err(e) => return Err(e),
+    };
+    if std::fs::read(&path)? == contents.as_bytes() {
+        return Ok(TemplateWrite::Unchanged(path));
+    }
+    let backup = free_backup_path(dir, file_name);
+    std::fs::copy(&path, &backup)?;
+    std::fs::write(&path, contents)?;
+    Ok(TemplateWrite::Replaced { path, backup })
+}
+
+/// `dir/file_name.bak`, or the first `dir/file_name.bak.N` that is free.
+fn free_backup_path(dir: &Path, file_name: &str) -> PathBuf {
+    let first = dir.join(format!("{file_name}.bak"));
+    if !first.exists() {
+        return first;
+    }
+    (1..)
+        .map(|n| dir.join(format!("{file_name}.bak.{n}")))
+        .find(|path| !path.exists())
+        .expect("unbounded range always yields a free name")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn existing_file_is_skipped_without_force() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("a.toml");
+        fs::write(&path, "edited").unwrap();
+
+        let err = write_template(tmp.path(), "a.toml", "shipped").unwrap_err();
+
+        assert!(matches!(err, CoreError::AlreadyExists(ref p) if p == &path));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "edited");
+        assert!(!tmp.path().join("a.toml.bak").exists());
+    }
+
+    #[test]
+    fn force_backs_up_an_edited_file() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("a.toml");
+        fs::write(&path, "edited").unwrap();
+
+        let outcome = replace_template(tmp.path(), "a.toml", "shipped").unwrap();
+
+        let backup = tmp.path().join("a.toml.bak");
+        assert_eq!(
+            outcome,
+            TemplateWrite::Replaced {
+                path: path.clone(),
+                backup: backup.clone()
+            }
+        );
+        assert_eq!(fs::read_to_string(&path).unwrap(), "shipped");
+        assert_eq!(fs::read_to_string(&backup).unwrap(), "edited");
+    }
+
+    #[test]
+    fn force_keeps_earlier_backups() {
+        let tmp = TempDir::new().unwrap();
+        fs::write(tmp.path().join("a.toml"), "second edit").unwrap();
+        fs::write(tmp.path().join("a.toml.bak"), "first edit").unwrap();
+
+        let outcome = replace_template(tmp.path(), "a.toml", "shipped").unwrap();
+
+        let backup = tmp.path().join("a.toml.bak.1");
+        assert!(matches!(outcome, TemplateWrite::Replaced { backup: ref b, .. } if b == &backup));
+        assert_eq!(fs::read_to_string(&backup).unwrap(), "second edit");
+        assert_eq!(
+            fs::read_to_string(tmp.path().join("a.toml.bak")).unwrap(),
+            "first edit"
+        );
+    }
+
+    #[test]
+    fn force_leaves_an_unedited_file_alone() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("a.toml");
+        fs::write(&path, "shipped").unwrap();
+
+        let outcome = replace_template(tmp.path(), "a.toml", "shipped").unwrap();
+
+        assert_eq!(outcome, TemplateWrite::Unchanged(path));
+        assert!(!tmp.path().join("a.toml.bak").exists());
+    }
+
+    #[test]
+    fn force_creates_a_missing_file() {
+        let tmp = TempDir::new().unwrap();
+        let outcome = replace_template(&tmp.path().join("trefm"), "a.toml", "shipped").unwrap();
+
+        let path = tmp.path().join("trefm").join("a.toml");
+        assert_eq!(outcome, TemplateWrite::Created(path.clone()));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "shipped");
+    }
+}
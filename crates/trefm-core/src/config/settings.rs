@@ -2,11 +2,19 @@
 //!
 //! The default configuration matches the values shown in `config/default.toml`.
 
-use std::path::Path;
+use std::collections::{BTreeMap, HashSet};
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
 
 use crate::error::{CoreError, CoreResult};
+use crate::fs::duplicates::DEFAULT_SCAN_EXCLUSIONS;
+use crate::fs::exclude::ExcludeSet;
+use crate::fs::format::SizeUnits;
+use crate::fs::hash::HashAlgo;
+use crate::nav::filter::FuzzyAlgorithm;
 
 /// Top-level application configuration.
 ///
@@ -24,6 +32,16 @@ pub struct Config {
     pub ui: UiConfig,
     #[serde(default)]
     pub terminal: TerminalConfig,
+    #[serde(default)]
+    pub watcher: WatcherConfig,
+    #[serde(default)]
+    pub search: SearchConfig,
+    #[serde(default)]
+    pub safety: SafetyConfig,
+    #[serde(default)]
+    pub scanner: ScannerConfig,
+    #[serde(default)]
+    pub logging: LoggingConfig,
 }
 
 impl Config {
@@ -42,8 +60,31 @@ impl Config {
         })?;
         toml::from_str(&content).map_err(|e| CoreError::ConfigParse(e.to_string()))
     }
+
+    /// Writes the commented default `default.toml` into `dir`.
+    ///
+    /// # Errors
+    ///
+    /// - [`CoreError::AlreadyExists`] if `dir/default.toml` exists; it is left untouched.
+    /// - [`CoreError::Io`] if `dir` cannot be created or written.
+    pub fn write_default(dir: &Path) -> CoreResult<PathBuf> {
+        super::write_template(dir, "default.toml", DEFAULT_TEMPLATE)
+    }
+
+    /// Writes the default `default.toml` into `dir`, replacing an existing one
+    /// after backing it up if it was edited (see [`super::TemplateWrite`]).
+    pub fn write_default_forced(dir: &Path) -> CoreResult<super::TemplateWrite> {
+        super::replace_template(dir, "default.toml", DEFAULT_TEMPLATE)
+    }
 }
 
+/// The shipped `config/default.toml`, every setting at its default value.
+const DEFAULT_TEMPLATE: &str = concat!(
+    "# TreFM settings. Every key is optional: delete a line to use the built-in default.\n",
+    "# Check this file with `trefm-tui --check-config`.\n\n",
+    include_str!("../../../../config/default.toml"),
+);
+
 /// General file-browsing preferences.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GeneralConfig {
@@ -55,6 +96,20 @@ pub struct GeneralConfig {
     pub sort_dir_first: bool,
     #[serde(default = "default_true")]
     pub confirm_delete: bool,
+    #[serde(default = "default_true")]
+    pub confirm_overwrite: bool,
+    #[serde(default)]
+    pub remember_sort: RememberSort,
+    #[serde(default)]
+    pub open_on_enter: OpenOnEnter,
+    /// Commands that open files by extension, overriding `open_on_enter`.
+    #[serde(default)]
+    pub open_with: OpenWith,
+    /// Command for files with no `open_with` entry that the pager cannot
+    /// show, used when `open_on_enter` is `none`. Empty means the platform
+    /// opener.
+    #[serde(default)]
+    pub open_fallback: String,
 }
 
 impl Default for GeneralConfig {
@@ -64,10 +119,63 @@ impl Default for GeneralConfig {
             default_sort: default_sort(),
             sort_dir_first: true,
             confirm_delete: true,
+            confirm_overwrite: true,
+            remember_sort: RememberSort::default(),
+            open_on_enter: OpenOnEnter::default(),
+            open_with: OpenWith::default(),
+            open_fallback: String::new(),
         }
     }
 }
 
+/// Command templates keyed by file extension, as in
+/// `[general.open_with] pdf = "zathura {}"`.
+///
+/// Templates are expanded with [`expand_template`](crate::fs::command::expand_template).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct OpenWith(BTreeMap<String, String>);
+
+impl OpenWith {
+    /// The template for `path`'s extension, compared case-insensitively.
+    pub fn template_for(&self, path: &Path) -> Option<&str> {
+        let ext = path.extension()?.to_str()?;
+        self.0
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(ext))
+            .map(|(_, template)| template.as_str())
+    }
+}
+
+/// What Enter (or `l`) does on a file; directories are always entered.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OpenOnEnter {
+    /// Nothing.
+    #[default]
+    None,
+    /// Show the file in the built-in pager.
+    Pager,
+    /// Open the file in `$EDITOR`.
+    Editor,
+    /// Hand the file to the desktop's default application for its type
+    /// (`xdg-open`, or `open` on macOS).
+    System,
+}
+
+/// Whether sort choices outlive the directory they were made in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RememberSort {
+    /// A panel keeps its sort while browsing; nothing is saved.
+    #[default]
+    Off,
+    /// Each directory gets back the sort last used in it, new ones sort by name.
+    Directory,
+    /// The last sort used anywhere is saved and restored on the next start.
+    Global,
+}
+
 /// File preview pane configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PreviewConfig {
@@ -122,6 +230,32 @@ pub struct UiConfig {
     pub show_icons: bool,
     #[serde(default = "default_date_format")]
     pub date_format: String,
+    /// List a `..` row at the top of each directory; Enter on it goes up.
+    #[serde(default)]
+    pub show_parent_entry: bool,
+    /// In dual-panel mode, preview the inactive panel's selection below the panels.
+    #[serde(default)]
+    pub dual_preview: bool,
+    /// Tabs each panel slot may hold; see [`UiConfig::tab_limit`].
+    #[serde(default = "default_max_tabs")]
+    pub max_tabs: usize,
+    /// How names too wide for the file list are shortened.
+    #[serde(default)]
+    pub name_truncation: NameTruncation,
+    /// `iec` (KiB, MiB, …) or `si` (KB, MB, …) for displayed file sizes.
+    #[serde(default)]
+    pub size_units: SizeUnits,
+    /// `relative` or `absolute` paths in search, recent and duplicate lists.
+    #[serde(default)]
+    pub result_paths: ResultPaths,
+    /// Seconds a status message stays before clearing itself; `0` keeps it
+    /// until the next one replaces it.
+    #[serde(default)]
+    pub status_timeout_secs: u64,
+    /// Plain text instead of colours and icons; also turned on by the
+    /// `NO_COLOR` environment variable.
+    #[serde(default)]
+    pub no_color: bool,
 }
 
 impl Default for UiConfig {
@@ -130,31 +264,361 @@ impl Default for UiConfig {
             panel_ratio: default_panel_ratio(),
             show_icons: true,
             date_format: default_date_format(),
+            show_parent_entry: false,
+            dual_preview: false,
+            max_tabs: default_max_tabs(),
+            name_truncation: NameTruncation::default(),
+            size_units: SizeUnits::default(),
+            result_paths: ResultPaths::default(),
+            status_timeout_secs: 0,
+            no_color: false,
         }
     }
 }
 
+impl UiConfig {
+    /// Upper bound for `max_tabs`; larger values are clamped to it.
+    pub const MAX_TABS_CEILING: usize = 32;
+
+    /// `max_tabs` clamped to `1..=MAX_TABS_CEILING`.
+    pub fn tab_limit(&self) -> usize {
+        self.max_tabs.clamp(1, Self::MAX_TABS_CEILING)
+    }
+
+    /// How long a status message stays, or `None` when it persists.
+    pub fn status_timeout(&self) -> Option<Duration> {
+        (self.status_timeout_secs > 0).then(|| Duration::from_secs(self.status_timeout_secs))
+    }
+
+    /// Whether to draw without colours: `no_color` is set or `NO_COLOR` is
+    /// non-empty, as <https://no-color.org> asks.
+    pub fn no_color_active(&self) -> bool {
+        self.no_color_with(std::env::var_os("NO_COLOR"))
+    }
+
+    fn no_color_with(&self, env: Option<OsString>) -> bool {
+        self.no_color || env.is_some_and(|value| !value.is_empty())
+    }
+}
+
+/// How the file list shortens a name that does not fit its column.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NameTruncation {
+    /// Cut the end and add `…`.
+    #[default]
+    End,
+    /// Keep the start and the extension with `…` between them.
+    Middle,
+    /// Leave the name whole; the panel border clips it.
+    None,
+}
+
+/// How search, recent-file and duplicate lists show each result's path.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ResultPaths {
+    /// Relative to the list's root (the panel directory, or the duplicate
+    /// scan root); paths outside it are shown in full.
+    #[default]
+    Relative,
+    /// The full path.
+    Absolute,
+}
+
+/// Where the embedded terminal panel sits.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TerminalPosition {
+    /// Below the file panels, spanning the full width.
+    #[default]
+    Bottom,
+    /// To the right of the file panels, spanning the full height.
+    Right,
+}
+
 /// Embedded terminal configuration.
+///
+/// `shell = "auto"` launches `$SHELL`, falling back to the platform default.
+/// `height_ratio` is the fraction of the screen the panel takes — its
+/// height at the bottom, its width on the right. When unset,
+/// `height_percent` is used instead.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TerminalConfig {
     #[serde(default = "default_terminal_shell")]
     pub shell: String,
+    /// Extra arguments passed to `shell`, e.g. `["-l"]`.
+    #[serde(default)]
+    pub shell_args: Vec<String>,
+    /// Variables added to the shell's inherited environment.
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
     #[serde(default = "default_true")]
     pub sync_cwd: bool,
+    /// Move the active panel when the shell reports a new cwd via OSC 7.
+    #[serde(default)]
+    pub follow_shell_cwd: bool,
     #[serde(default = "default_terminal_height")]
     pub height_percent: u16,
+    #[serde(default)]
+    pub height_ratio: Option<f32>,
+    #[serde(default)]
+    pub position: TerminalPosition,
+    /// Lines of output kept for scrolling back with Shift+PageUp.
+    #[serde(default = "default_terminal_scrollback")]
+    pub scrollback_lines: usize,
 }
 
 impl Default for TerminalConfig {
     fn default() -> Self {
         Self {
             shell: default_terminal_shell(),
+            shell_args: Vec::new(),
+            env: BTreeMap::new(),
             sync_cwd: true,
+            follow_shell_cwd: false,
             height_percent: default_terminal_height(),
+            height_ratio: None,
+            position: TerminalPosition::default(),
+            scrollback_lines: default_terminal_scrollback(),
+        }
+    }
+}
+
+impl TerminalConfig {
+    /// Returns the share of the screen given to the panel, kept within
+    /// 10–90% so neither side disappears.
+    pub fn size_ratio(&self) -> f32 {
+        let ratio = self
+            .height_ratio
+            .unwrap_or(f32::from(self.height_percent) / 100.0);
+        if ratio.is_finite() {
+            ratio.clamp(0.1, 0.9)
+        } else {
+            0.3
+        }
+    }
+}
+
+/// Directory watcher settings.
+///
+/// `poll_interval_secs` enables a periodic refresh of the current panel for
+/// filesystems where change notifications never arrive (NFS, SMB, sshfs).
+/// `0` disables polling.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WatcherConfig {
+    #[serde(default)]
+    pub poll_interval_secs: u64,
+}
+
+impl WatcherConfig {
+    /// Returns the polling interval, or `None` when polling is disabled.
+    pub fn poll_interval(&self) -> Option<Duration> {
+        (self.poll_interval_secs > 0).then(|| Duration::from_secs(self.poll_interval_secs))
+    }
+}
+
+/// Fuzzy and content search settings.
+///
+/// `exclude` lists glob patterns (`*`, `?`) for entries both searches skip.
+/// A pattern without `/` matches a file or directory name anywhere in the
+/// tree; one with `/` matches the path relative to the search root.
+/// `fuzzy_algorithm` picks how fuzzy and name search rank their results.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchConfig {
+    #[serde(default = "default_search_exclude")]
+    pub exclude: Vec<String>,
+    #[serde(default)]
+    pub fuzzy_algorithm: FuzzyAlgorithm,
+}
+
+impl Default for SearchConfig {
+    fn default() -> Self {
+        Self {
+            exclude: default_search_exclude(),
+            fuzzy_algorithm: FuzzyAlgorithm::default(),
+        }
+    }
+}
+
+impl SearchConfig {
+    /// Compiles `exclude` into an [`ExcludeSet`].
+    pub fn exclude_set(&self) -> ExcludeSet {
+        ExcludeSet::new(&self.exclude)
+    }
+}
+
+/// Guards against losing work by accident.
+///
+/// With `confirm_quit_if_busy`, quitting while a background scan or search
+/// is still running asks for confirmation first. `confirm_scope` picks which
+/// deletes ask before going ahead. Entering a directory with more than
+/// `large_dir_threshold` entries asks first too, since reading and sorting
+/// it can stall the UI (0 = never ask).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SafetyConfig {
+    #[serde(default)]
+    pub confirm_quit_if_busy: bool,
+    #[serde(default)]
+    pub confirm_scope: ConfirmScope,
+    #[serde(default = "default_large_dir_threshold")]
+    pub large_dir_threshold: usize,
+}
+
+impl Default for SafetyConfig {
+    fn default() -> Self {
+        Self {
+            confirm_quit_if_busy: false,
+            confirm_scope: ConfirmScope::default(),
+            large_dir_threshold: default_large_dir_threshold(),
+        }
+    }
+}
+
+/// Which deletes ask for confirmation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfirmScope {
+    /// Every delete.
+    #[default]
+    All,
+    /// Only deleting a directory; files go right away.
+    DirsOnly,
+    /// No delete asks.
+    None,
+}
+
+impl ConfirmScope {
+    /// Whether deleting an entry (a directory when `is_dir`) asks first.
+    pub fn needs_confirm(self, is_dir: bool) -> bool {
+        match self {
+            Self::All => true,
+            Self::DirsOnly => is_dir,
+            Self::None => false,
+        }
+    }
+}
+
+/// Background duplicate scanner settings.
+///
+/// `hash_algo` picks how file contents are compared: `sha256` (default),
+/// `blake3` or `xxh3`. Changing it invalidates the cached scan.
+/// `skip_network_mounts` keeps the scan out of NFS, SMB, sshfs and similar
+/// mounts (see [`crate::fs::mount`]), where hashing is slow.
+/// `current_dir_first` lists the groups with a file under the active
+/// panel's directory before all others in the duplicate view.
+/// `excluded_dirs` names directories the scan never enters. With
+/// `merge_excluded_dirs` (the default) they are added to
+/// [`DEFAULT_SCAN_EXCLUSIONS`]; without it they replace that list.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScannerConfig {
+    #[serde(default)]
+    pub hash_algo: HashAlgo,
+    #[serde(default)]
+    pub skip_network_mounts: bool,
+    #[serde(default)]
+    pub current_dir_first: bool,
+    #[serde(default)]
+    pub excluded_dirs: Vec<String>,
+    #[serde(default = "default_true")]
+    pub merge_excluded_dirs: bool,
+    /// Threads that hash files during a duplicate scan; `0` is one per CPU.
+    #[serde(default)]
+    pub threads: usize,
+    /// Files smaller than this many bytes are left out of duplicate scans.
+    #[serde(default)]
+    pub min_dup_size: u64,
+    /// Memory a duplicate scan tries to stay within, in MiB; `0` is no limit.
+    #[serde(default)]
+    pub max_memory_mb: u64,
+}
+
+impl Default for ScannerConfig {
+    fn default() -> Self {
+        Self {
+            hash_algo: HashAlgo::default(),
+            skip_network_mounts: false,
+            current_dir_first: false,
+            excluded_dirs: Vec::new(),
+            merge_excluded_dirs: true,
+            threads: 0,
+            min_dup_size: 0,
+            max_memory_mb: 0,
+        }
+    }
+}
+
+impl ScannerConfig {
+    /// `max_memory_mb` in bytes, as duplicate scans take it.
+    pub fn memory_budget(&self) -> usize {
+        usize::try_from(self.max_memory_mb.saturating_mul(1024 * 1024)).unwrap_or(usize::MAX)
+    }
+
+    /// Directory names a duplicate scan skips: `excluded_dirs`, plus the
+    /// defaults when `merge_excluded_dirs` is set.
+    pub fn exclusion_set(&self) -> HashSet<&str> {
+        let defaults = DEFAULT_SCAN_EXCLUSIONS
+            .iter()
+            .copied()
+            .filter(|_| self.merge_excluded_dirs);
+        defaults
+            .chain(self.excluded_dirs.iter().map(String::as_str))
+            .collect()
+    }
+}
+
+/// Where the TUI writes its log and how much of it is kept.
+///
+/// `level` is a tracing level (`error`, `warn`, `info`, `debug`, `trace`).
+/// `file` defaults to [`crate::paths::log_file`]. Once it would grow past `max_size_mb`, it is renamed to `file.1`
+/// (older files shift to `.2`, `.3`, …) and only `max_files` rotated files
+/// are kept. `max_size_mb = 0` disables rotation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoggingConfig {
+    #[serde(default = "default_log_file")]
+    pub file: String,
+    #[serde(default = "default_log_level")]
+    pub level: String,
+    #[serde(default = "default_log_max_size_mb")]
+    pub max_size_mb: u64,
+    #[serde(default = "default_log_max_files")]
+    pub max_files: usize,
+}
+
+impl Default for LoggingConfig {
+    fn default() -> Self {
+        Self {
+            file: default_log_file(),
+            level: default_log_level(),
+            max_size_mb: default_log_max_size_mb(),
+            max_files: default_log_max_files(),
         }
     }
 }
 
+fn default_log_file() -> String {
+    crate::paths::log_file().to_string_lossy().into_owned()
+}
+
+fn default_log_level() -> String {
+    "debug".to_string()
+}
+
+fn default_log_max_size_mb() -> u64 {
+    10
+}
+
+fn default_log_max_files() -> usize {
+    3
+}
+
+fn default_search_exclude() -> Vec<String> {
+    [".git", "node_modules", "target"]
+        .into_iter()
+        .map(String::from)
+        .collect()
+}
+
 fn default_terminal_shell() -> String {
     "auto".to_string()
 }
@@ -163,6 +627,10 @@ fn default_terminal_height() -> u16 {
     30
 }
 
+fn default_terminal_scrollback() -> usize {
+    1000
+}
+
 fn default_true() -> bool {
     true
 }
@@ -191,6 +659,14 @@ fn default_date_format() -> String {
     "%Y-%m-%d %H:%M".to_string()
 }
 
+fn default_max_tabs() -> usize {
+    9
+}
+
+fn default_large_dir_threshold() -> usize {
+    100_000
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -205,6 +681,23 @@ mod tests {
         assert_eq!(config.general.default_sort, "name");
         assert!(config.general.sort_dir_first);
         assert!(config.general.confirm_delete);
+        assert!(config.general.confirm_overwrite);
+        assert_eq!(config.general.remember_sort, RememberSort::Off);
+        assert_eq!(config.general.open_on_enter, OpenOnEnter::None);
+        assert_eq!(config.general.open_with, OpenWith::default());
+        assert!(config.general.open_fallback.is_empty());
+    }
+
+    #[test]
+    fn open_with_matches_extension_only() {
+        let open_with: OpenWith = toml::from_str("pdf = \"zathura {}\"").unwrap();
+
+        assert_eq!(
+            open_with.template_for(Path::new("/docs/paper.pdf")),
+            Some("zathura {}")
+        );
+        assert_eq!(open_with.template_for(Path::new("/docs/pdf")), None);
+        assert_eq!(open_with.template_for(Path::new("/docs/notes.txt")), None);
     }
 
     #[test]
@@ -233,6 +726,61 @@ mod tests {
         assert!((config.ui.panel_ratio - 0.4).abs() < f64::EPSILON);
         assert!(config.ui.show_icons);
         assert_eq!(config.ui.date_format, "%Y-%m-%d %H:%M");
+        assert!(!config.ui.show_parent_entry);
+        assert!(!config.ui.dual_preview);
+        assert_eq!(config.ui.tab_limit(), 9);
+    }
+
+    #[test]
+    fn tab_limit_is_clamped() {
+        let mut ui = UiConfig {
+            max_tabs: 0,
+            ..UiConfig::default()
+        };
+        assert_eq!(ui.tab_limit(), 1);
+
+        ui.max_tabs = 1000;
+        assert_eq!(ui.tab_limit(), UiConfig::MAX_TABS_CEILING);
+    }
+
+    #[test]
+    fn name_truncation_from_toml() {
+        let config: Config = toml::from_str("[ui]\nname_truncation = \"middle\"\n").unwrap();
+        assert_eq!(config.ui.name_truncation, NameTruncation::Middle);
+        assert_eq!(Config::default().ui.name_truncation, NameTruncation::End);
+        assert!(toml::from_str::<Config>("[ui]\nname_truncation = \"left\"\n").is_err());
+    }
+
+    #[test]
+    fn size_units_from_toml() {
+        let config: Config = toml::from_str("[ui]\nsize_units = \"si\"\n").unwrap();
+        assert_eq!(config.ui.size_units, SizeUnits::Si);
+        assert_eq!(Config::default().ui.size_units, SizeUnits::Iec);
+    }
+
+    #[test]
+    fn result_paths_from_toml() {
+        let config: Config = toml::from_str("[ui]\nresult_paths = \"absolute\"\n").unwrap();
+        assert_eq!(config.ui.result_paths, ResultPaths::Absolute);
+        assert_eq!(Config::default().ui.result_paths, ResultPaths::Relative);
+    }
+
+    #[test]
+    fn status_timeout_from_toml() {
+        let config: Config = toml::from_str("[ui]\nstatus_timeout_secs = 5\n").unwrap();
+        assert_eq!(config.ui.status_timeout(), Some(Duration::from_secs(5)));
+        assert_eq!(Config::default().ui.status_timeout(), None);
+    }
+
+    #[test]
+    fn no_color_from_config_or_env() {
+        let ui = UiConfig::default();
+        assert!(!ui.no_color_with(None));
+        assert!(!ui.no_color_with(Some(OsString::new())));
+        assert!(ui.no_color_with(Some(OsString::from("1"))));
+
+        let config: Config = toml::from_str("[ui]\nno_color = true\n").unwrap();
+        assert!(config.ui.no_color_with(None));
     }
 
     #[test]
@@ -247,6 +795,12 @@ show_hidden = true
 default_sort = "size"
 sort_dir_first = false
 confirm_delete = false
+remember_sort = "directory"
+open_on_enter = "system"
+open_fallback = "my-opener {}"
+
+[general.open_with]
+pdf = "zathura {}"
 
 [preview]
 enabled = false
@@ -273,6 +827,13 @@ date_format = "%d/%m/%Y"
         assert_eq!(config.general.default_sort, "size");
         assert!(!config.general.sort_dir_first);
         assert!(!config.general.confirm_delete);
+        assert_eq!(config.general.remember_sort, RememberSort::Directory);
+        assert_eq!(config.general.open_on_enter, OpenOnEnter::System);
+        assert_eq!(config.general.open_fallback, "my-opener {}");
+        assert_eq!(
+            config.general.open_with.template_for(Path::new("a.PDF")),
+            Some("zathura {}")
+        );
 
         assert!(!config.preview.enabled);
         assert_eq!(config.preview.max_file_size, "5MB");
@@ -288,6 +849,63 @@ date_format = "%d/%m/%Y"
         assert_eq!(config.ui.date_format, "%d/%m/%Y");
     }
 
+    #[test]
+    fn default_config_logging() {
+        let config = Config::default();
+
+        assert_eq!(
+            config.logging.file,
+            crate::paths::log_file().to_string_lossy()
+        );
+        assert_eq!(config.logging.level, "debug");
+        assert_eq!(config.logging.max_size_mb, 10);
+        assert_eq!(config.logging.max_files, 3);
+    }
+
+    #[test]
+    fn logging_section_parses() {
+        let config: Config = toml::from_str(
+            r#"
+            [logging]
+            file = "/var/tmp/trefm/trefm.log"
+            level = "warn"
+            max_files = 5
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.logging.file, "/var/tmp/trefm/trefm.log");
+        assert_eq!(config.logging.level, "warn");
+        assert_eq!(config.logging.max_size_mb, 10);
+        assert_eq!(config.logging.max_files, 5);
+    }
+
+    #[test]
+    fn write_default_creates_loadable_file() {
+        let tmp = TempDir::new().unwrap();
+        let path = Config::write_default(&tmp.path().join("trefm")).unwrap();
+
+        assert_eq!(path, tmp.path().join("trefm").join("default.toml"));
+        assert!(fs::read_to_string(&path)
+            .unwrap()
+            .starts_with("# TreFM settings."));
+        let config = Config::load_strict(&path).unwrap();
+        assert_eq!(config.general.default_sort, "name");
+    }
+
+    #[test]
+    fn write_default_refuses_to_overwrite() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("default.toml");
+        fs::write(&path, "[general]\nshow_hidden = true\n").unwrap();
+
+        let err = Config::write_default(tmp.path()).unwrap_err();
+        assert!(matches!(err, CoreError::AlreadyExists(ref p) if p == &path));
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "[general]\nshow_hidden = true\n"
+        );
+    }
+
     #[test]
     fn load_partial_toml_uses_defaults() {
         let tmp = TempDir::new().unwrap();
@@ -351,8 +969,260 @@ show_hidden = true
     fn default_config_terminal() {
         let config = Config::default();
         assert_eq!(config.terminal.shell, "auto");
+        assert!(config.terminal.shell_args.is_empty());
         assert!(config.terminal.sync_cwd);
+        assert!(!config.terminal.follow_shell_cwd);
         assert_eq!(config.terminal.height_percent, 30);
+        assert_eq!(config.terminal.scrollback_lines, 1000);
+        assert_eq!(config.terminal.position, TerminalPosition::Bottom);
+        assert!((config.terminal.size_ratio() - 0.3).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn terminal_height_ratio_overrides_percent() {
+        let config: Config = toml::from_str(
+            r#"
+            [terminal]
+            height_percent = 50
+            height_ratio = 0.4
+            position = "right"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.terminal.position, TerminalPosition::Right);
+        assert!((config.terminal.size_ratio() - 0.4).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn terminal_shell_and_args_parse() {
+        let config: Config = toml::from_str(
+            r#"
+            [terminal]
+            shell = "/usr/bin/fish"
+            shell_args = ["-l", "--private"]
+
+            [terminal.env]
+            TREFM_THEME = "dark"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.terminal.env["TREFM_THEME"], "dark");
+        assert_eq!(config.terminal.shell, "/usr/bin/fish");
+        assert_eq!(config.terminal.shell_args, ["-l", "--private"]);
+    }
+
+    #[test]
+    fn terminal_size_ratio_is_clamped() {
+        let tiny = TerminalConfig {
+            height_ratio: Some(0.01),
+            ..TerminalConfig::default()
+        };
+        let huge = TerminalConfig {
+            height_percent: 100,
+            ..TerminalConfig::default()
+        };
+        let nan = TerminalConfig {
+            height_ratio: Some(f32::NAN),
+            ..TerminalConfig::default()
+        };
+        assert!((tiny.size_ratio() - 0.1).abs() < f32::EPSILON);
+        assert!((huge.size_ratio() - 0.9).abs() < f32::EPSILON);
+        assert!((nan.size_ratio() - 0.3).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn default_config_watcher_disables_polling() {
+        let config = Config::default();
+        assert_eq!(config.watcher.poll_interval_secs, 0);
+        assert!(config.watcher.poll_interval().is_none());
+    }
+
+    #[test]
+    fn watcher_poll_interval_from_toml() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("config.toml");
+        fs::write(&path, "[watcher]\npoll_interval_secs = 5\n").unwrap();
+
+        let config = Config::load(&path).unwrap();
+        assert_eq!(config.watcher.poll_interval(), Some(Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn watcher_zero_interval_disables_polling() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("config.toml");
+        fs::write(&path, "[watcher]\npoll_interval_secs = 0\n").unwrap();
+
+        let config = Config::load(&path).unwrap();
+        assert!(config.watcher.poll_interval().is_none());
+    }
+
+    #[test]
+    fn default_config_search_excludes_common_dirs() {
+        let config = Config::default();
+        let set = config.search.exclude_set();
+        assert!(set.is_excluded(Path::new("web/node_modules")));
+        assert!(set.is_excluded(Path::new(".git")));
+        assert!(set.is_excluded(Path::new("target")));
+        assert!(!set.is_excluded(Path::new("src")));
+    }
+
+    #[test]
+    fn search_exclude_from_toml() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("config.toml");
+        fs::write(&path, "[search]\nexclude = [\"dist\", \"*.log\"]\n").unwrap();
+
+        let config = Config::load(&path).unwrap();
+        assert_eq!(config.search.exclude, vec!["dist", "*.log"]);
+        assert!(config
+            .search
+            .exclude_set()
+            .is_excluded(Path::new("a/b.log")));
+    }
+
+    #[test]
+    fn fuzzy_algorithm_from_toml() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("config.toml");
+        fs::write(&path, "[search]\nfuzzy_algorithm = \"clangd\"\n").unwrap();
+
+        let config = Config::load(&path).unwrap();
+        assert_eq!(config.search.fuzzy_algorithm, FuzzyAlgorithm::Clangd);
+        assert_eq!(
+            Config::default().search.fuzzy_algorithm,
+            FuzzyAlgorithm::Skim
+        );
+    }
+
+    #[test]
+    fn safety_defaults_to_quitting_immediately() {
+        assert!(!Config::default().safety.confirm_quit_if_busy);
+    }
+
+    #[test]
+    fn safety_confirm_quit_from_toml() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("config.toml");
+        fs::write(&path, "[safety]\nconfirm_quit_if_busy = true\n").unwrap();
+
+        let config = Config::load(&path).unwrap();
+        assert!(config.safety.confirm_quit_if_busy);
+    }
+
+    #[test]
+    fn large_dir_threshold_defaults_to_100k() {
+        assert_eq!(Config::default().safety.large_dir_threshold, 100_000);
+    }
+
+    #[test]
+    fn large_dir_threshold_from_toml() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("config.toml");
+        fs::write(&path, "[safety]\nlarge_dir_threshold = 0\n").unwrap();
+
+        let safety = Config::load(&path).unwrap().safety;
+        assert_eq!(safety.large_dir_threshold, 0);
+        assert!(!safety.confirm_quit_if_busy);
+    }
+
+    #[test]
+    fn confirm_scope_defaults_to_all() {
+        let scope = Config::default().safety.confirm_scope;
+        assert_eq!(scope, ConfirmScope::All);
+        assert!(scope.needs_confirm(false));
+        assert!(scope.needs_confirm(true));
+    }
+
+    #[test]
+    fn confirm_scope_dirs_only_from_toml() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("config.toml");
+        fs::write(&path, "[safety]\nconfirm_scope = \"dirs_only\"\n").unwrap();
+
+        let scope = Config::load(&path).unwrap().safety.confirm_scope;
+        assert_eq!(scope, ConfirmScope::DirsOnly);
+        assert!(!scope.needs_confirm(false));
+        assert!(scope.needs_confirm(true));
+    }
+
+    #[test]
+    fn confirm_scope_none_never_confirms() {
+        assert!(!ConfirmScope::None.needs_confirm(false));
+        assert!(!ConfirmScope::None.needs_confirm(true));
+    }
+
+    #[test]
+    fn scanner_hash_algo_from_toml() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("config.toml");
+        fs::write(&path, "[scanner]\nhash_algo = \"blake3\"\n").unwrap();
+
+        let config = Config::load(&path).unwrap();
+        assert_eq!(config.scanner.hash_algo, HashAlgo::Blake3);
+        assert_eq!(Config::default().scanner.hash_algo, HashAlgo::Sha256);
+        assert!(!config.scanner.skip_network_mounts);
+        assert!(!config.scanner.current_dir_first);
+    }
+
+    #[test]
+    fn scanner_exclusions_default_to_builtin_set() {
+        let expected: HashSet<&str> = DEFAULT_SCAN_EXCLUSIONS.iter().copied().collect();
+        assert_eq!(ScannerConfig::default().exclusion_set(), expected);
+    }
+
+    #[test]
+    fn scanner_excluded_dirs_extend_defaults() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("config.toml");
+        fs::write(&path, "[scanner]\nexcluded_dirs = [\"dist\", \".git\"]\n").unwrap();
+
+        let config = Config::load(&path).unwrap();
+        let set = config.scanner.exclusion_set();
+
+        assert!(config.scanner.merge_excluded_dirs);
+        assert_eq!(set.len(), DEFAULT_SCAN_EXCLUSIONS.len() + 1);
+        assert!(set.contains("dist"));
+        assert!(set.contains("node_modules"));
+    }
+
+    #[test]
+    fn scanner_excluded_dirs_replace_defaults_without_merge() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("config.toml");
+        fs::write(
+            &path,
+            "[scanner]\nexcluded_dirs = [\"dist\", \"build\"]\nmerge_excluded_dirs = false\n",
+        )
+        .unwrap();
+
+        let config = Config::load(&path).unwrap();
+
+        assert_eq!(
+            config.scanner.exclusion_set(),
+            HashSet::from(["dist", "build"])
+        );
+    }
+
+    #[test]
+    fn scanner_replace_with_empty_list_excludes_nothing() {
+        let scanner = ScannerConfig {
+            merge_excluded_dirs: false,
+            ..ScannerConfig::default()
+        };
+        assert!(scanner.exclusion_set().is_empty());
+    }
+
+    #[test]
+    fn scanner_unknown_hash_algo_is_rejected() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("config.toml");
+        fs::write(&path, "[scanner]\nhash_algo = \"md5\"\n").unwrap();
+
+        assert!(matches!(
+            Config::load(&path),
+            Err(CoreError::ConfigParse(_))
+        ));
     }
 
     #[test]
@@ -2,8 +2,10 @@
 //!
 //! Colors are stored as strings (e.g. `"blue"`, `"#ff5500"`) and converted
 //! to [`ratatui::style::Color`] at render time via [`parse_color`].
+//! [`Theme::plain`] is the no-colour theme used when `NO_COLOR` or
+//! `[ui] no_color` is set.
 
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use serde::{Deserialize, Serialize};
 
@@ -30,6 +32,16 @@ pub struct Theme {
     pub tab: TabTheme,
 }
 
+/// `syntax_theme` value that turns preview highlighting off.
+pub const NO_SYNTAX_THEME: &str = "none";
+
+/// The shipped `config/theme.toml`.
+const THEME_TEMPLATE: &str = concat!(
+    "# TreFM colours: names (\"blue\", \"dark_gray\", \"reset\", …) or hex \"#rrggbb\".\n",
+    "# Every key is optional: delete a line to use the built-in colour.\n\n",
+    include_str!("../../../../config/theme.toml"),
+);
+
 impl Theme {
     /// Loads a theme from a TOML file at `path`.
     pub fn load(path: &Path) -> CoreResult<Self> {
@@ -41,6 +53,84 @@ impl Theme {
         toml::from_str(&content).map_err(|e| CoreError::ConfigParse(e.to_string()))
     }
 
+    /// Writes the commented default `theme.toml` into `dir`.
+    ///
+    /// # Errors
+    ///
+    /// - [`CoreError::AlreadyExists`] if `dir/theme.toml` exists; it is left untouched.
+    /// - [`CoreError::Io`] if `dir` cannot be created or written.
+    pub fn write_default(dir: &Path) -> CoreResult<PathBuf> {
+        super::write_template(dir, "theme.toml", THEME_TEMPLATE)
+    }
+
+    /// Writes the default `theme.toml` into `dir`, replacing an existing one
+    /// after backing it up if it was edited (see [`super::TemplateWrite`]).
+    pub fn write_default_forced(dir: &Path) -> CoreResult<super::TemplateWrite> {
+        super::replace_template(dir, "theme.toml", THEME_TEMPLATE)
+    }
+
+    /// A theme that leaves every colour to the terminal and turns syntax
+    /// highlighting off.
+    pub fn plain() -> Self {
+        let reset = || "reset".to_string();
+        Self {
+            panel: PanelTheme {
+                dir_fg: reset(),
+                symlink_fg: reset(),
+                hidden_fg: reset(),
+                selected_fg: reset(),
+                inactive_border_fg: reset(),
+            },
+            statusbar: StatusBarTheme {
+                bg: reset(),
+                position_fg: reset(),
+                hidden_fg: reset(),
+                message_fg: reset(),
+                branch_clean_fg: reset(),
+                branch_dirty_fg: reset(),
+                success_fg: reset(),
+                warning_fg: reset(),
+                error_fg: reset(),
+            },
+            breadcrumb: BreadcrumbTheme {
+                bg: reset(),
+                home_fg: reset(),
+                separator_fg: reset(),
+                component_fg: reset(),
+            },
+            preview: PreviewTheme {
+                border_fg: reset(),
+                line_number_fg: reset(),
+                dir_title_fg: reset(),
+                error_fg: reset(),
+                truncation_fg: reset(),
+                syntax_theme: NO_SYNTAX_THEME.to_string(),
+            },
+            popup: PopupTheme {
+                border_fg: reset(),
+                match_fg: reset(),
+            },
+            git: GitTheme {
+                modified_fg: reset(),
+                added_fg: reset(),
+                deleted_fg: reset(),
+                renamed_fg: reset(),
+                untracked_fg: reset(),
+                ignored_fg: reset(),
+            },
+            terminal: TerminalTheme {
+                border_fg: reset(),
+                title_fg: reset(),
+            },
+            tab: TabTheme {
+                active_fg: reset(),
+                active_bg: reset(),
+                inactive_fg: reset(),
+                inactive_bg: reset(),
+            },
+        }
+    }
+
     /// Saves the theme to a TOML file at `path`.
     pub fn save(&self, path: &Path) -> CoreResult<()> {
         if let Some(parent) = path.parent() {
@@ -60,6 +150,13 @@ pub struct PanelTheme {
     pub symlink_fg: String,
     pub hidden_fg: String,
     pub selected_fg: String,
+    /// Border of the panel that does not have focus.
+    #[serde(default = "default_inactive_border_fg")]
+    pub inactive_border_fg: String,
+}
+
+fn default_inactive_border_fg() -> String {
+    "dark_gray".to_string()
 }
 
 impl Default for PanelTheme {
@@ -69,6 +166,7 @@ impl Default for PanelTheme {
             symlink_fg: "cyan".to_string(),
             hidden_fg: "dark_gray".to_string(),
             selected_fg: "yellow".to_string(),
+            inactive_border_fg: default_inactive_border_fg(),
         }
     }
 }
@@ -82,6 +180,25 @@ pub struct StatusBarTheme {
     pub message_fg: String,
     pub branch_clean_fg: String,
     pub branch_dirty_fg: String,
+    /// Status messages reporting a finished operation.
+    #[serde(default = "default_success_fg")]
+    pub success_fg: String,
+    #[serde(default = "default_warning_fg")]
+    pub warning_fg: String,
+    #[serde(default = "default_error_fg")]
+    pub error_fg: String,
+}
+
+fn default_success_fg() -> String {
+    "green".to_string()
+}
+
+fn default_warning_fg() -> String {
+    "yellow".to_string()
+}
+
+fn default_error_fg() -> String {
+    "red".to_string()
 }
 
 impl Default for StatusBarTheme {
@@ -93,6 +210,9 @@ impl Default for StatusBarTheme {
             message_fg: "magenta".to_string(),
             branch_clean_fg: "green".to_string(),
             branch_dirty_fg: "yellow".to_string(),
+            success_fg: default_success_fg(),
+            warning_fg: default_warning_fg(),
+            error_fg: default_error_fg(),
         }
     }
 }
@@ -145,12 +265,20 @@ impl Default for PreviewTheme {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PopupTheme {
     pub border_fg: String,
+    /// Characters a fuzzy query matched in search and palette results.
+    #[serde(default = "default_match_fg")]
+    pub match_fg: String,
+}
+
+fn default_match_fg() -> String {
+    "cyan".to_string()
 }
 
 impl Default for PopupTheme {
     fn default() -> Self {
         Self {
             border_fg: "yellow".to_string(),
+            match_fg: default_match_fg(),
         }
     }
 }
@@ -257,6 +385,28 @@ mod tests {
     use std::fs;
     use tempfile::TempDir;
 
+    #[test]
+    fn write_default_creates_loadable_file() {
+        let tmp = TempDir::new().unwrap();
+        let path = Theme::write_default(tmp.path()).unwrap();
+
+        let theme = Theme::load(&path).unwrap();
+        assert_eq!(theme.panel.dir_fg, Theme::default().panel.dir_fg);
+    }
+
+    #[test]
+    fn write_default_refuses_to_overwrite() {
+        let tmp = TempDir::new().unwrap();
+        fs::write(tmp.path().join("theme.toml"), "# mine\n").unwrap();
+
+        let err = Theme::write_default(tmp.path()).unwrap_err();
+        assert!(matches!(err, CoreError::AlreadyExists(_)));
+        assert_eq!(
+            fs::read_to_string(tmp.path().join("theme.toml")).unwrap(),
+            "# mine\n"
+        );
+    }
+
     #[test]
     fn default_theme_panel() {
         let theme = Theme::default();
@@ -293,6 +443,7 @@ mod tests {
     fn default_theme_popup() {
         let theme = Theme::default();
         assert_eq!(theme.popup.border_fg, "yellow");
+        assert_eq!(theme.popup.match_fg, "cyan");
     }
 
     #[test]
@@ -399,6 +550,8 @@ ignored_fg = "dark_gray"
         let theme = Theme::load(&path).unwrap();
         assert_eq!(theme.panel.dir_fg, "#00ff00");
         assert_eq!(theme.preview.syntax_theme, "Monokai");
+        assert_eq!(theme.statusbar.error_fg, "red");
+        assert_eq!(theme.statusbar.success_fg, "green");
     }
 
     #[test]
@@ -461,6 +614,22 @@ selected_fg = "yellow"
         assert_eq!(parse_color("light_yellow"), Color::LightYellow);
     }
 
+    #[test]
+    fn plain_theme_uses_terminal_colors() {
+        let value = toml::Value::try_from(Theme::plain()).unwrap();
+        let groups = value.as_table().unwrap();
+        for (group, fields) in groups {
+            for (key, color) in fields.as_table().unwrap() {
+                let color = color.as_str().unwrap();
+                if key == "syntax_theme" {
+                    assert_eq!(color, NO_SYNTAX_THEME);
+                } else {
+                    assert_eq!(parse_color(color), Color::Reset, "{group}.{key}");
+                }
+            }
+        }
+    }
+
     #[test]
     fn parse_color_grey_alias() {
         assert_eq!(parse_color("grey"), Color::Gray);
@@ -0,0 +1,277 @@
+//! Strict loading of `default.toml`.
+//!
+//! [`Config::load`] ignores keys it does not know, so a typo such as
+//! `show_hiden` silently leaves the default in place.
+//! [`Config::load_strict`] reports that key instead, and names the key
+//! and line of any value with the wrong type.
+
+use std::fmt;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use super::settings::Config;
+
+/// Tables that take arbitrary keys (e.g. environment variable names or
+/// file extensions).
+const OPEN_TABLES: &[&str] = &["terminal.env", "general.open_with"];
+
+/// Why [`Config::load_strict`] rejected a file.
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigError {
+    /// The file could not be read.
+    #[error("cannot read {}: {source}", path.display())]
+    Read {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    /// The file is not valid TOML.
+    #[error("{}{message}", LinePrefix(*line))]
+    Syntax {
+        line: Option<usize>,
+        message: String,
+    },
+
+    /// A key that no setting uses.
+    #[error("{}unknown key `{key}` (expected one of: {})", LinePrefix(*line), expected.join(", "))]
+    UnknownKey {
+        key: String,
+        line: Option<usize>,
+        /// Keys the enclosing table does accept.
+        expected: Vec<String>,
+    },
+
+    /// A known key whose value has the wrong type or is out of range.
+    #[error("{}`{key}`: {message}", LinePrefix(*line))]
+    InvalidValue {
+        key: String,
+        line: Option<usize>,
+        message: String,
+    },
+}
+
+/// Renders `line N: ` for errors that know their line.
+struct LinePrefix(Option<usize>);
+
+impl fmt::Display for LinePrefix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(line) => write!(f, "line {line}: "),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Config {
+    /// Loads configuration from `path`, rejecting unknown keys and
+    /// wrongly typed values.
+    ///
+    /// Unknown keys are reported in file order, the first one wins.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ConfigError`] naming the offending key and, where known,
+    /// its 1-based line.
+    pub fn load_strict(path: &Path) -> Result<Self, ConfigError> {
+        let content = std::fs::read_to_string(path).map_err(|source| ConfigError::Read {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        Self::parse_strict(&content)
+    }
+
+    /// Like [`Config::load_strict`], for TOML already in memory.
+    pub fn parse_strict(content: &str) -> Result<Self, ConfigError> {
+        let doc = toml_edit::ImDocument::parse(content).map_err(|e| ConfigError::Syntax {
+            line: e.span().map(|span| line_of(content, span)),
+            message: e.message().to_string(),
+        })?;
+
+        let schema = toml::Value::try_from(schema_sample()).expect("Config serializes to TOML");
+        if let Some(schema) = schema.as_table() {
+            let mut unknown = Vec::new();
+            collect_unknown(doc.as_table(), schema, "", &mut unknown);
+            unknown.sort_by_key(|(_, span, _)| span.as_ref().map_or(usize::MAX, |s| s.start));
+            if let Some((key, span, expected)) = unknown.into_iter().next() {
+                return Err(ConfigError::UnknownKey {
+                    key,
+                    line: span.map(|span| line_of(content, span)),
+                    expected,
+                });
+            }
+        }
+
+        serde_path_to_error::deserialize(toml::Deserializer::new(content)).map_err(|e| {
+            let key = e.path().to_string();
+            let inner = e.into_inner();
+            ConfigError::InvalidValue {
+                key,
+                line: inner.span().map(|span| line_of(content, span)),
+                message: inner.message().to_string(),
+            }
+        })
+    }
+}
+
+/// A config with every optional field filled in, so its serialized form
+/// lists every key a file may use.
+fn schema_sample() -> Config {
+    let mut sample = Config::default();
+    sample.terminal.height_ratio = Some(0.3);
+    sample
+}
+
+/// An unknown key: dotted path, span of the key, keys its table accepts.
+type Unknown = (String, Option<Range<usize>>, Vec<String>);
+
+fn collect_unknown(
+    table: &dyn toml_edit::TableLike,
+    schema: &toml::Table,
+    prefix: &str,
+    out: &mut Vec<Unknown>,
+) {
+    if OPEN_TABLES.contains(&prefix) {
+        return;
+    }
+    for (name, item) in table.iter() {
+        let path = if prefix.is_empty() {
+            name.to_string()
+        } else {
+            format!("{prefix}.{name}")
+        };
+        let Some(expected) = schema.get(name) else {
+            let span = table.key(name).and_then(toml_edit::Key::span);
+            out.push((path, span, schema.keys().cloned().collect()));
+            continue;
+        };
+        if let (Some(sub), Some(sub_schema)) = (item.as_table_like(), expected.as_table()) {
+            collect_unknown(sub, sub_schema, &path, out);
+        }
+    }
+}
+
+/// 1-based line number of the start of `span`.
+fn line_of(content: &str, span: Range<usize>) -> usize {
+    let end = span.start.min(content.len());
+    content.as_bytes()[..end]
+        .iter()
+        .filter(|&&b| b == b'\n')
+        .count()
+        + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid_file_loads() {
+        let config = Config::parse_strict(
+            r#"
+[general]
+show_hidden = true
+
+[terminal]
+height_ratio = 0.4
+
+[terminal.env]
+EDITOR = "hx"
+"#,
+        )
+        .unwrap();
+        assert!(config.general.show_hidden);
+        assert_eq!(config.terminal.env["EDITOR"], "hx");
+    }
+
+    #[test]
+    fn open_with_takes_any_extension() {
+        let config = Config::parse_strict(
+            r#"
+[general.open_with]
+pdf = "zathura {}"
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.general.open_with.template_for(Path::new("a.pdf")),
+            Some("zathura {}")
+        );
+    }
+
+    #[test]
+    fn unknown_key_names_key_and_line() {
+        let err = Config::parse_strict(
+            r#"
+[general]
+show_hidden = true
+sort_dir_frist = false
+"#,
+        )
+        .unwrap_err();
+        match &err {
+            ConfigError::UnknownKey {
+                key,
+                line,
+                expected,
+            } => {
+                assert_eq!(key, "general.sort_dir_frist");
+                assert_eq!(*line, Some(4));
+                assert!(expected.contains(&"sort_dir_first".to_string()));
+            }
+            other => panic!("unexpected error: {other:?}"),
+        }
+        assert!(err
+            .to_string()
+            .starts_with("line 4: unknown key `general.sort_dir_frist` (expected one of: "));
+    }
+
+    #[test]
+    fn unknown_table_is_reported() {
+        let err = Config::parse_strict("[previwe]\nenabled = false\n").unwrap_err();
+        assert!(matches!(
+            err,
+            ConfigError::UnknownKey { ref key, line: Some(1), .. } if key == "previwe"
+        ));
+    }
+
+    #[test]
+    fn wrong_type_names_key_and_line() {
+        let err = Config::parse_strict(
+            r#"
+[ui]
+panel_ratio = 0.5
+show_icons = "yes"
+"#,
+        )
+        .unwrap_err();
+        match &err {
+            ConfigError::InvalidValue { key, line, message } => {
+                assert_eq!(key, "ui.show_icons");
+                assert_eq!(*line, Some(4));
+                assert!(message.contains("expected a boolean"), "{message}");
+            }
+            other => panic!("unexpected error: {other:?}"),
+        }
+        assert!(err.to_string().starts_with("line 4: `ui.show_icons`: "));
+    }
+
+    #[test]
+    fn syntax_error_has_line() {
+        let err = Config::parse_strict("[general]\nshow_hidden = \n").unwrap_err();
+        assert!(matches!(err, ConfigError::Syntax { line: Some(2), .. }));
+    }
+
+    #[test]
+    fn shipped_default_config_is_valid() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../config/default.toml");
+        Config::load_strict(&path).unwrap();
+    }
+
+    #[test]
+    fn missing_file_is_read_error() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let err = Config::load_strict(&tmp.path().join("nope.toml")).unwrap_err();
+        assert!(matches!(err, ConfigError::Read { .. }));
+    }
+}
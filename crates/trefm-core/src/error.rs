@@ -19,6 +19,15 @@ pub enum CoreError {
     #[error("permission denied: {0}")]
     PermissionDenied(PathBuf),
 
+    /// The destination path already exists and may not be overwritten.
+    #[error("already exists: {0}")]
+    AlreadyExists(PathBuf),
+
+    /// Another program has the file open and the OS refused to delete or
+    /// move it (Windows sharing and lock violations).
+    #[error("file is in use by another program: {0}")]
+    FileInUse(PathBuf),
+
     /// A directory was expected but the path points to a file.
     #[error("not a directory: {0}")]
     NotADirectory(PathBuf),
@@ -27,6 +36,10 @@ pub enum CoreError {
     #[error("invalid name: {0}")]
     InvalidName(String),
 
+    /// A command template is empty or cannot be split into arguments.
+    #[error("invalid command: {0}")]
+    InvalidCommand(String),
+
     /// Failed to parse a TOML configuration file.
     #[error("config parse error: {0}")]
     ConfigParse(String),
@@ -68,12 +81,33 @@ mod tests {
         assert_eq!(err.to_string(), "permission denied: /secret");
     }
 
+    #[test]
+    fn file_in_use_displays_path() {
+        let err = CoreError::FileInUse(PathBuf::from("C:\\report.xlsx"));
+        assert_eq!(
+            err.to_string(),
+            "file is in use by another program: C:\\report.xlsx"
+        );
+    }
+
     #[test]
     fn not_a_directory_displays_path() {
         let err = CoreError::NotADirectory(PathBuf::from("/some/file.txt"));
         assert_eq!(err.to_string(), "not a directory: /some/file.txt");
     }
 
+    #[test]
+    fn already_exists_displays_path() {
+        let err = CoreError::AlreadyExists(PathBuf::from("/dest/file.txt"));
+        assert_eq!(err.to_string(), "already exists: /dest/file.txt");
+    }
+
+    #[test]
+    fn invalid_command_displays_message() {
+        let err = CoreError::InvalidCommand("empty command".to_string());
+        assert_eq!(err.to_string(), "invalid command: empty command");
+    }
+
     #[test]
     fn invalid_name_displays_message() {
         let err = CoreError::InvalidName("bad/name".to_string());
@@ -101,6 +135,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::unnecessary_literal_unwrap)]
     fn core_result_ok() {
         let result: CoreResult<i32> = Ok(42);
         assert!(result.is_ok());
@@ -0,0 +1,198 @@
+//! Broad file categories for filtering a listing.
+//!
+//! [`categorize`] looks at the extension first. A file whose extension is
+//! missing or unknown gets a second chance from its first bytes: common
+//! image, archive, media and PDF signatures are recognised, as is a `#!`
+//! script line. Anything else,
+//! including directories, is [`FileCategory::Other`].
+
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use crate::fs::entry::FileEntry;
+
+/// A broad kind of file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FileCategory {
+    Documents,
+    Images,
+    Code,
+    Archives,
+    Media,
+    Other,
+}
+
+impl FileCategory {
+    /// Every category, in the order a filter cycles through them.
+    pub const ALL: [Self; 6] = [
+        Self::Documents,
+        Self::Images,
+        Self::Code,
+        Self::Archives,
+        Self::Media,
+        Self::Other,
+    ];
+
+    /// Name shown in the UI.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Documents => "Documents",
+            Self::Images => "Images",
+            Self::Code => "Code",
+            Self::Archives => "Archives",
+            Self::Media => "Media",
+            Self::Other => "Other",
+        }
+    }
+
+    /// Category for a lowercase extension, if it is a known one.
+    pub fn from_extension(ext: &str) -> Option<Self> {
+        EXTENSIONS
+            .iter()
+            .find(|(_, exts)| exts.contains(&ext))
+            .map(|(category, _)| *category)
+    }
+}
+
+const EXTENSIONS: &[(FileCategory, &[&str])] = &[
+    (
+        FileCategory::Documents,
+        &[
+            "txt", "md", "markdown", "rst", "pdf", "doc", "docx", "odt", "rtf", "xls", "xlsx",
+            "ods", "csv", "ppt", "pptx", "odp", "epub", "tex",
+        ],
+    ),
+    (
+        FileCategory::Images,
+        &[
+            "png", "jpg", "jpeg", "gif", "bmp", "webp", "ico", "tiff", "tif", "svg", "heic",
+            "avif", "raw", "psd",
+        ],
+    ),
+    (
+        FileCategory::Code,
+        &[
+            "rs", "py", "js", "ts", "jsx", "tsx", "go", "c", "h", "cpp", "hpp", "cc", "java", "kt",
+            "swift", "rb", "php", "cs", "sh", "bash", "zsh", "fish", "lua", "pl", "scala", "hs",
+            "ml", "ex", "exs", "clj", "sql", "html", "css", "scss", "json", "toml", "yaml", "yml",
+            "xml", "vue", "svelte", "zig", "dart",
+        ],
+    ),
+    (
+        FileCategory::Archives,
+        &[
+            "zip", "tar", "gz", "tgz", "bz2", "xz", "zst", "7z", "rar", "lz4", "iso", "dmg", "deb",
+            "rpm", "jar",
+        ],
+    ),
+    (
+        FileCategory::Media,
+        &[
+            "mp3", "flac", "wav", "ogg", "opus", "m4a", "aac", "mp4", "mkv", "avi", "mov", "webm",
+            "wmv", "flv", "m4v",
+        ],
+    ),
+];
+
+/// File signatures checked when the extension does not decide.
+const SIGNATURES: &[(&[u8], FileCategory)] = &[
+    (b"\x89PNG\r\n\x1a\n", FileCategory::Images),
+    (b"\xff\xd8\xff", FileCategory::Images),
+    (b"GIF8", FileCategory::Images),
+    (b"%PDF-", FileCategory::Documents),
+    (b"PK\x03\x04", FileCategory::Archives),
+    (b"\x1f\x8b", FileCategory::Archives),
+    (b"BZh", FileCategory::Archives),
+    (b"\xfd7zXZ\x00", FileCategory::Archives),
+    (b"7z\xbc\xaf\x27\x1c", FileCategory::Archives),
+    (b"Rar!\x1a\x07", FileCategory::Archives),
+    (b"\x28\xb5\x2f\xfd", FileCategory::Archives),
+    (b"ID3", FileCategory::Media),
+    (b"fLaC", FileCategory::Media),
+    (b"OggS", FileCategory::Media),
+    (b"\x1a\x45\xdf\xa3", FileCategory::Media),
+    (b"#!", FileCategory::Code),
+];
+
+/// Longest signature in [`SIGNATURES`].
+const SIGNATURE_LEN: usize = 8;
+
+/// The category of `entry`. Directories are always [`FileCategory::Other`].
+pub fn categorize(entry: &FileEntry) -> FileCategory {
+    if entry.is_dir() {
+        return FileCategory::Other;
+    }
+    categorize_path(entry.path())
+}
+
+/// The category of the file at `path`, by extension and then by content.
+///
+/// A file that cannot be read is categorised by its extension alone.
+pub fn categorize_path(path: &Path) -> FileCategory {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .and_then(|ext| FileCategory::from_extension(&ext.to_lowercase()))
+        .or_else(|| sniff(path))
+        .unwrap_or(FileCategory::Other)
+}
+
+/// Matches the first bytes of `path` against [`SIGNATURES`].
+fn sniff(path: &Path) -> Option<FileCategory> {
+    let mut header = Vec::with_capacity(SIGNATURE_LEN);
+    File::open(path)
+        .ok()?
+        .take(SIGNATURE_LEN as u64)
+        .read_to_end(&mut header)
+        .ok()?;
+    SIGNATURES
+        .iter()
+        .find(|(magic, _)| header.starts_with(magic))
+        .map(|(_, category)| *category)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn source_files_are_code() {
+        assert_eq!(categorize_path(Path::new("main.rs")), FileCategory::Code);
+        assert_eq!(categorize_path(Path::new("script.py")), FileCategory::Code);
+    }
+
+    #[test]
+    fn pictures_are_images() {
+        assert_eq!(categorize_path(Path::new("a.png")), FileCategory::Images);
+        assert_eq!(categorize_path(Path::new("B.JPG")), FileCategory::Images);
+    }
+
+    #[test]
+    fn unknown_extension_is_other() {
+        assert_eq!(categorize_path(Path::new("data.qwzx")), FileCategory::Other);
+    }
+
+    #[test]
+    fn content_decides_when_extension_does_not() {
+        let tmp = TempDir::new().unwrap();
+        let archive = tmp.path().join("backup");
+        fs::write(&archive, b"PK\x03\x04rest of zip").unwrap();
+        let notes = tmp.path().join("notes");
+        fs::write(&notes, "plain words").unwrap();
+
+        assert_eq!(categorize_path(&archive), FileCategory::Archives);
+        assert_eq!(categorize_path(&notes), FileCategory::Other);
+    }
+
+    #[test]
+    fn directories_are_other() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path().join("photos.png");
+        fs::create_dir(&dir).unwrap();
+        let entry = FileEntry::new(dir.clone(), &fs::metadata(&dir).unwrap());
+
+        assert_eq!(categorize(&entry), FileCategory::Other);
+    }
+}
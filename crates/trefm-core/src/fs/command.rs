@@ -0,0 +1,327 @@
+//! One-shot shell commands run against a file.
+//!
+//! A command template such as `wc -l {}` is split into arguments with
+//! [`split_args`] and every `{}` is then replaced by the target path, so a
+//! path containing spaces or quotes stays a single argument and is never
+//! re-parsed by a shell. [`run_command`] executes the result and captures
+//! its output. [`executable_argv`] builds the argument vector for running a
+//! file itself, after checking that it may be run.
+
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use crate::error::{CoreError, CoreResult};
+
+/// Placeholder replaced by the target path in a command template.
+pub const PATH_PLACEHOLDER: &str = "{}";
+
+/// Captured result of [`run_command`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandOutput {
+    /// Exit code, or `None` if the process was killed by a signal.
+    pub code: Option<i32>,
+    /// Stdout lines followed by stderr lines.
+    pub lines: Vec<String>,
+}
+
+impl CommandOutput {
+    pub fn success(&self) -> bool {
+        self.code == Some(0)
+    }
+}
+
+/// Splits a command line into arguments, shell-style.
+///
+/// Whitespace separates arguments. Single quotes keep their contents
+/// verbatim, double quotes allow `\"` and `\\` escapes, and a backslash
+/// outside quotes escapes the next character. No expansion of any kind is
+/// performed. Fails on an unterminated quote or a trailing backslash.
+pub fn split_args(input: &str) -> CoreResult<Vec<String>> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_arg = false;
+    let mut chars = input.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                in_arg = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(ch) => current.push(ch),
+                        None => return Err(invalid("unterminated single quote")),
+                    }
+                }
+            }
+            '"' => {
+                in_arg = true;
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(ch @ ('"' | '\\')) => current.push(ch),
+                            Some(ch) => {
+                                current.push('\\');
+                                current.push(ch);
+                            }
+                            None => return Err(invalid("unterminated double quote")),
+                        },
+                        Some(ch) => current.push(ch),
+                        None => return Err(invalid("unterminated double quote")),
+                    }
+                }
+            }
+            '\\' => match chars.next() {
+                Some(ch) => {
+                    in_arg = true;
+                    current.push(ch);
+                }
+                None => return Err(invalid("trailing backslash")),
+            },
+            ch if ch.is_whitespace() => {
+                if in_arg {
+                    args.push(std::mem::take(&mut current));
+                    in_arg = false;
+                }
+            }
+            ch => {
+                in_arg = true;
+                current.push(ch);
+            }
+        }
+    }
+    if in_arg {
+        args.push(current);
+    }
+    Ok(args)
+}
+
+/// Expands `template` for `path` into an argument vector.
+///
+/// Every `{}` inside an argument is replaced by the path. If the template
+/// has no `{}` at all, the path is appended as the last argument.
+pub fn expand_template(template: &str, path: &Path) -> CoreResult<Vec<String>> {
+    let args = split_args(template)?;
+    if args.is_empty() {
+        return Err(invalid("empty command"));
+    }
+    let path = path.to_string_lossy();
+    if !args.iter().any(|a| a.contains(PATH_PLACEHOLDER)) {
+        let mut args = args;
+        args.push(path.into_owned());
+        return Ok(args);
+    }
+    Ok(args
+        .iter()
+        .map(|a| a.replace(PATH_PLACEHOLDER, &path))
+        .collect())
+}
+
+/// Runs `argv` in `cwd` with stdin closed and waits for it to finish.
+pub fn run_command(argv: &[String], cwd: &Path) -> CoreResult<CommandOutput> {
+    let (program, rest) = argv.split_first().ok_or_else(|| invalid("empty command"))?;
+    let output = Command::new(program)
+        .args(rest)
+        .current_dir(cwd)
+        .stdin(Stdio::null())
+        .output()?;
+    let lines = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .chain(String::from_utf8_lossy(&output.stderr).lines())
+        .map(str::to_string)
+        .collect();
+    Ok(CommandOutput {
+        code: output.status.code(),
+        lines,
+    })
+}
+
+/// Whether `path` is a regular file that may be run.
+///
+/// On Unix any of the executable permission bits must be set. Elsewhere the
+/// extension decides (`exe`, `bat`, `cmd`, `com`).
+pub fn is_executable(path: &Path) -> bool {
+    let Ok(metadata) = std::fs::metadata(path) else {
+        return false;
+    };
+    if !metadata.is_file() {
+        return false;
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        metadata.permissions().mode() & 0o111 != 0
+    }
+    #[cfg(not(unix))]
+    {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| ["exe", "bat", "cmd", "com"].contains(&ext.to_lowercase().as_str()))
+    }
+}
+
+/// Argument vector that runs the file at `path` with no arguments.
+///
+/// The program is the file's absolute path, so it is never looked up on
+/// `$PATH` instead.
+///
+/// # Errors
+///
+/// - [`CoreError::InvalidCommand`] if the file is not executable.
+/// - [`CoreError::Io`] if the path cannot be resolved.
+pub fn executable_argv(path: &Path) -> CoreResult<Vec<String>> {
+    if !is_executable(path) {
+        let name = path.file_name().unwrap_or(path.as_os_str());
+        return Err(CoreError::InvalidCommand(format!(
+            "{} is not executable",
+            name.to_string_lossy()
+        )));
+    }
+    let program = path.canonicalize()?;
+    Ok(vec![program.to_string_lossy().into_owned()])
+}
+
+fn invalid(msg: &str) -> CoreError {
+    CoreError::InvalidCommand(msg.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn split_args_on_whitespace() {
+        assert_eq!(
+            split_args("  ls  -la\t/tmp ").unwrap(),
+            ["ls", "-la", "/tmp"]
+        );
+    }
+
+    #[test]
+    fn split_args_honours_quotes_and_escapes() {
+        assert_eq!(
+            split_args(r#"echo 'a b' "c \"d\"" e\ f '' x"#).unwrap(),
+            ["echo", "a b", "c \"d\"", "e f", "", "x"]
+        );
+    }
+
+    #[test]
+    fn split_args_keeps_shell_metacharacters_literal() {
+        assert_eq!(
+            split_args("echo $HOME;rm *").unwrap(),
+            ["echo", "$HOME;rm", "*"]
+        );
+    }
+
+    #[test]
+    fn split_args_rejects_unterminated_input() {
+        assert!(split_args("echo 'oops").is_err());
+        assert!(split_args("echo \"oops").is_err());
+        assert!(split_args("echo oops\\").is_err());
+    }
+
+    #[test]
+    fn expand_template_keeps_path_with_spaces_as_one_arg() {
+        let path = PathBuf::from("/tmp/my files/report v2.txt");
+        assert_eq!(
+            expand_template("wc -l {}", &path).unwrap(),
+            ["wc", "-l", "/tmp/my files/report v2.txt"]
+        );
+    }
+
+    #[test]
+    fn expand_template_replaces_placeholder_inside_arg() {
+        let path = PathBuf::from("/a b/c");
+        assert_eq!(
+            expand_template("cp {} '{}.bak'", &path).unwrap(),
+            ["cp", "/a b/c", "/a b/c.bak"]
+        );
+    }
+
+    #[test]
+    fn expand_template_appends_path_without_placeholder() {
+        let path = PathBuf::from("/x y");
+        assert_eq!(expand_template("file", &path).unwrap(), ["file", "/x y"]);
+    }
+
+    #[test]
+    fn expand_template_refuses_empty_command() {
+        let path = PathBuf::from("/tmp/a");
+        for template in ["", "   ", "\t"] {
+            assert!(matches!(
+                expand_template(template, &path),
+                Err(CoreError::InvalidCommand(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn run_command_captures_output_and_status() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        std::fs::write(tmp.path().join("a b.txt"), "one\ntwo\n").unwrap();
+        let argv = expand_template("cat {}", Path::new("a b.txt")).unwrap();
+        let output = run_command(&argv, tmp.path()).unwrap();
+        assert!(output.success());
+        assert_eq!(output.lines, ["one", "two"]);
+
+        let argv = split_args("cat missing.txt").unwrap();
+        let output = run_command(&argv, tmp.path()).unwrap();
+        assert!(!output.success());
+        assert!(!output.lines.is_empty());
+    }
+
+    #[cfg(unix)]
+    fn write_script(dir: &Path, name: &str, mode: u32) -> PathBuf {
+        use std::os::unix::fs::PermissionsExt;
+        let path = dir.join(name);
+        std::fs::write(&path, "#!/bin/sh\necho hello from $0\n").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(mode)).unwrap();
+        path
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn executable_bit_is_detected() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        assert!(is_executable(&write_script(tmp.path(), "run.sh", 0o755)));
+        assert!(is_executable(&write_script(tmp.path(), "owner.sh", 0o700)));
+        assert!(!is_executable(&write_script(tmp.path(), "plain.sh", 0o644)));
+        assert!(!is_executable(tmp.path()));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn executable_argv_runs_the_file_itself() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let script = write_script(tmp.path(), "run.sh", 0o755);
+        let argv = executable_argv(&script).unwrap();
+        assert_eq!(argv, [script.canonicalize().unwrap().to_string_lossy()]);
+
+        let output = run_command(&argv, tmp.path()).unwrap();
+        assert!(output.success());
+        assert_eq!(output.lines.len(), 1);
+        assert!(output.lines[0].starts_with("hello from /"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn executable_argv_refuses_non_executable_file() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let script = write_script(tmp.path(), "plain.sh", 0o644);
+        match executable_argv(&script) {
+            Err(CoreError::InvalidCommand(msg)) => assert_eq!(msg, "plain.sh is not executable"),
+            other => panic!("unexpected result: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn run_command_reports_missing_program() {
+        let argv = vec!["trefm-no-such-program".to_string()];
+        assert!(matches!(
+            run_command(&argv, Path::new(".")),
+            Err(CoreError::Io(_))
+        ));
+    }
+}
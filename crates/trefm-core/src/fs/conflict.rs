@@ -0,0 +1,431 @@
+//! Conflict resolution for pasting files into a directory.
+//!
+//! A [`PasteQueue`] walks the list of sources one by one. Sources whose
+//! destination is free are scheduled directly; when a destination already
+//! exists the queue stops and waits for a [`ConflictChoice`]. The "all"
+//! choices switch the queue's [`ConflictPolicy`] so later conflicts are
+//! resolved without asking again.
+//!
+//! The queue only *plans* the work — call [`execute_paste_step`] on each
+//! [`PasteStep`] with the queue's [`PasteMode`] to touch the filesystem.
+
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+
+use crate::error::CoreResult;
+use crate::fs::ops::{copy_file_checked, move_file_checked};
+
+/// The user's answer to a single paste conflict.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictChoice {
+    /// Replace the existing destination.
+    Overwrite,
+    /// Leave the existing destination alone and skip this source.
+    Skip,
+    /// Paste under a new, unused name next to the existing one.
+    Rename,
+    /// Overwrite this and every later conflict.
+    OverwriteAll,
+    /// Skip this and every later conflict.
+    SkipAll,
+}
+
+/// How conflicts are handled when they are reached.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictPolicy {
+    /// Stop and ask for a [`ConflictChoice`].
+    Ask,
+    /// Overwrite without asking.
+    OverwriteAll,
+    /// Skip without asking.
+    SkipAll,
+}
+
+/// A single planned paste operation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PasteStep {
+    /// Paste `src` to `dest`, which does not exist yet.
+    Copy { src: PathBuf, dest: PathBuf },
+    /// Paste `src` to `dest`, replacing what is there.
+    Replace { src: PathBuf, dest: PathBuf },
+    /// Leave `src` unpasted.
+    Skip(PathBuf),
+}
+
+/// Whether pasted files are copied or moved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PasteMode {
+    Copy,
+    Move,
+}
+
+/// Plans a paste of `sources` into `dest_dir`, pausing on each conflict.
+#[derive(Debug, Clone)]
+pub struct PasteQueue {
+    dest_dir: PathBuf,
+    remaining: VecDeque<PathBuf>,
+    conflict: Option<(PathBuf, PathBuf)>,
+    policy: ConflictPolicy,
+    steps: Vec<PasteStep>,
+    mode: PasteMode,
+}
+
+impl PasteQueue {
+    /// Creates a queue and advances it to the first conflict (if any).
+    pub fn new(sources: Vec<PathBuf>, dest_dir: &Path) -> Self {
+        Self {
+            dest_dir: dest_dir.to_path_buf(),
+            remaining: sources.into(),
+            conflict: None,
+            policy: ConflictPolicy::Ask,
+            steps: Vec::new(),
+            mode: PasteMode::Copy,
+        }
+        .advance()
+    }
+
+    /// Sets whether the planned steps copy (the default) or move their sources.
+    pub fn with_mode(self, mode: PasteMode) -> Self {
+        Self { mode, ..self }
+    }
+
+    /// Whether the planned steps copy or move their sources.
+    pub fn mode(&self) -> PasteMode {
+        self.mode
+    }
+
+    /// The source and destination of the conflict waiting for a decision.
+    pub fn pending_conflict(&self) -> Option<(&Path, &Path)> {
+        self.conflict
+            .as_ref()
+            .map(|(src, dest)| (src.as_path(), dest.as_path()))
+    }
+
+    /// Number of sources not yet planned, including the pending conflict.
+    pub fn remaining(&self) -> usize {
+        self.remaining.len() + usize::from(self.conflict.is_some())
+    }
+
+    /// Returns `true` once every source has a planned step.
+    pub fn is_done(&self) -> bool {
+        self.conflict.is_none() && self.remaining.is_empty()
+    }
+
+    /// The steps planned so far, in source order.
+    pub fn steps(&self) -> &[PasteStep] {
+        &self.steps
+    }
+
+    /// Consumes the queue, returning its planned steps.
+    pub fn into_steps(self) -> Vec<PasteStep> {
+        self.steps
+    }
+
+    /// Applies `choice` to the pending conflict and advances to the next one.
+    ///
+    /// Returns the queue unchanged when there is no pending conflict.
+    pub fn resolve(self, choice: ConflictChoice) -> Self {
+        let Some((src, dest)) = self.conflict.clone() else {
+            return self;
+        };
+        let policy = match choice {
+            ConflictChoice::OverwriteAll => ConflictPolicy::OverwriteAll,
+            ConflictChoice::SkipAll => ConflictPolicy::SkipAll,
+            _ => self.policy,
+        };
+        let step = match choice {
+            ConflictChoice::Overwrite | ConflictChoice::OverwriteAll => overwrite_step(src, dest),
+            ConflictChoice::Skip | ConflictChoice::SkipAll => PasteStep::Skip(src),
+            ConflictChoice::Rename => PasteStep::Copy {
+                src,
+                dest: unique_destination(&dest),
+            },
+        };
+        let mut steps = self.steps;
+        steps.push(step);
+        Self {
+            conflict: None,
+            policy,
+            steps,
+            ..self
+        }
+        .advance()
+    }
+
+    /// Plans sources until one needs a decision or the queue is empty.
+    fn advance(self) -> Self {
+        let Self {
+            dest_dir,
+            mut remaining,
+            policy,
+            mut steps,
+            mode,
+            ..
+        } = self;
+        let mut conflict = None;
+
+        while let Some(src) = remaining.pop_front() {
+            let Some(name) = src.file_name() else {
+                steps.push(PasteStep::Skip(src));
+                continue;
+            };
+            let dest = dest_dir.join(name);
+            if std::fs::symlink_metadata(&dest).is_err() {
+                steps.push(PasteStep::Copy { src, dest });
+                continue;
+            }
+            match policy {
+                ConflictPolicy::OverwriteAll => steps.push(overwrite_step(src, dest)),
+                ConflictPolicy::SkipAll => steps.push(PasteStep::Skip(src)),
+                ConflictPolicy::Ask => {
+                    conflict = Some((src, dest));
+                    break;
+                }
+            }
+        }
+
+        Self {
+            dest_dir,
+            remaining,
+            conflict,
+            policy,
+            steps,
+            mode,
+        }
+    }
+}
+
+/// Overwriting a file with itself would destroy it, so that case is skipped.
+fn overwrite_step(src: PathBuf, dest: PathBuf) -> PasteStep {
+    if src == dest {
+        PasteStep::Skip(src)
+    } else {
+        PasteStep::Replace { src, dest }
+    }
+}
+
+/// Returns a path next to `path` that does not exist yet.
+///
+/// `report.pdf` becomes `report (1).pdf`, then `report (2).pdf`, and so on.
+/// Dotfiles and names without an extension get the suffix appended.
+pub fn unique_destination(path: &Path) -> PathBuf {
+    let parent = path.parent().unwrap_or_else(|| Path::new(""));
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let (stem, ext) = match name.rfind('.') {
+        Some(idx) if idx > 0 => (&name[..idx], &name[idx..]),
+        _ => (name.as_str(), ""),
+    };
+
+    (1..)
+        .map(|n| parent.join(format!("{stem} ({n}){ext}")))
+        .find(|candidate| std::fs::symlink_metadata(candidate).is_err())
+        .expect("unbounded iterator always yields a free name")
+}
+
+/// Executes a single planned step.
+///
+/// Returns `Ok(false)` for [`PasteStep::Skip`] and `Ok(true)` when something
+/// was pasted.
+///
+/// # Errors
+///
+/// Propagates errors from [`copy_file_checked`] / [`move_file_checked`].
+pub fn execute_paste_step(step: &PasteStep, mode: PasteMode) -> CoreResult<bool> {
+    let (src, dest, overwrite) = match step {
+        PasteStep::Copy { src, dest } => (src, dest, false),
+        PasteStep::Replace { src, dest } => (src, dest, true),
+        PasteStep::Skip(_) => return Ok(false),
+    };
+    match mode {
+        PasteMode::Copy => copy_file_checked(src, dest, overwrite)?,
+        PasteMode::Move => move_file_checked(src, dest, overwrite)?,
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    /// Creates `src/` with the given files and `dest/` with the given files.
+    fn setup(src_files: &[&str], dest_files: &[&str]) -> (TempDir, Vec<PathBuf>, PathBuf) {
+        let tmp = TempDir::new().unwrap();
+        let src_dir = tmp.path().join("src");
+        let dest_dir = tmp.path().join("dest");
+        fs::create_dir_all(&src_dir).unwrap();
+        fs::create_dir_all(&dest_dir).unwrap();
+        let sources = src_files
+            .iter()
+            .map(|name| {
+                let p = src_dir.join(name);
+                fs::write(&p, format!("new {name}")).unwrap();
+                p
+            })
+            .collect();
+        for name in dest_files {
+            fs::write(dest_dir.join(name), format!("old {name}")).unwrap();
+        }
+        (tmp, sources, dest_dir)
+    }
+
+    #[test]
+    fn no_conflicts_plans_all_copies() {
+        let (_tmp, sources, dest) = setup(&["a.txt", "b.txt"], &[]);
+        let queue = PasteQueue::new(sources.clone(), &dest);
+
+        assert!(queue.is_done());
+        assert_eq!(
+            queue.steps(),
+            &[
+                PasteStep::Copy {
+                    src: sources[0].clone(),
+                    dest: dest.join("a.txt"),
+                },
+                PasteStep::Copy {
+                    src: sources[1].clone(),
+                    dest: dest.join("b.txt"),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn stops_at_first_conflict() {
+        let (_tmp, sources, dest) = setup(&["a.txt", "b.txt", "c.txt"], &["b.txt"]);
+        let queue = PasteQueue::new(sources.clone(), &dest);
+
+        assert!(!queue.is_done());
+        assert_eq!(queue.steps().len(), 1);
+        assert_eq!(queue.remaining(), 2);
+        let (src, target) = queue.pending_conflict().unwrap();
+        assert_eq!(src, sources[1]);
+        assert_eq!(target, dest.join("b.txt"));
+    }
+
+    #[test]
+    fn overwrite_and_skip_apply_to_one_conflict() {
+        let (_tmp, sources, dest) = setup(&["a.txt", "b.txt"], &["a.txt", "b.txt"]);
+        let queue = PasteQueue::new(sources.clone(), &dest).resolve(ConflictChoice::Overwrite);
+        assert!(
+            queue.pending_conflict().is_some(),
+            "second conflict still asks"
+        );
+
+        let queue = queue.resolve(ConflictChoice::Skip);
+        assert!(queue.is_done());
+        assert_eq!(
+            queue.steps(),
+            &[
+                PasteStep::Replace {
+                    src: sources[0].clone(),
+                    dest: dest.join("a.txt"),
+                },
+                PasteStep::Skip(sources[1].clone()),
+            ]
+        );
+    }
+
+    #[test]
+    fn overwrite_all_resolves_later_conflicts() {
+        let (_tmp, sources, dest) = setup(&["a.txt", "b.txt", "c.txt"], &["a.txt", "c.txt"]);
+        let queue = PasteQueue::new(sources.clone(), &dest).resolve(ConflictChoice::OverwriteAll);
+
+        assert!(queue.is_done());
+        assert!(matches!(queue.steps()[0], PasteStep::Replace { .. }));
+        assert!(matches!(queue.steps()[1], PasteStep::Copy { .. }));
+        assert!(matches!(queue.steps()[2], PasteStep::Replace { .. }));
+    }
+
+    #[test]
+    fn skip_all_resolves_later_conflicts() {
+        let (_tmp, sources, dest) = setup(&["a.txt", "b.txt", "c.txt"], &["a.txt", "c.txt"]);
+        let queue = PasteQueue::new(sources.clone(), &dest).resolve(ConflictChoice::SkipAll);
+
+        assert!(queue.is_done());
+        assert_eq!(queue.steps()[0], PasteStep::Skip(sources[0].clone()));
+        assert!(matches!(queue.steps()[1], PasteStep::Copy { .. }));
+        assert_eq!(queue.steps()[2], PasteStep::Skip(sources[2].clone()));
+    }
+
+    #[test]
+    fn rename_picks_a_free_name() {
+        let (_tmp, sources, dest) = setup(&["a.txt"], &["a.txt", "a (1).txt"]);
+        let queue = PasteQueue::new(sources.clone(), &dest).resolve(ConflictChoice::Rename);
+
+        assert_eq!(
+            queue.steps(),
+            &[PasteStep::Copy {
+                src: sources[0].clone(),
+                dest: dest.join("a (2).txt"),
+            }]
+        );
+    }
+
+    #[test]
+    fn overwrite_onto_itself_is_skipped() {
+        let (_tmp, sources, _dest) = setup(&["a.txt"], &[]);
+        let src_dir = sources[0].parent().unwrap().to_path_buf();
+        let queue = PasteQueue::new(sources.clone(), &src_dir).resolve(ConflictChoice::Overwrite);
+
+        assert_eq!(queue.steps(), &[PasteStep::Skip(sources[0].clone())]);
+    }
+
+    #[test]
+    fn resolve_without_conflict_is_noop() {
+        let (_tmp, sources, dest) = setup(&["a.txt"], &[]);
+        let queue = PasteQueue::new(sources, &dest);
+        let resolved = queue.clone().resolve(ConflictChoice::Overwrite);
+        assert_eq!(resolved.steps(), queue.steps());
+    }
+
+    #[test]
+    fn mode_survives_conflict_resolution() {
+        let (_tmp, sources, dest) = setup(&["a.txt"], &["a.txt"]);
+        let queue = PasteQueue::new(sources, &dest).with_mode(PasteMode::Move);
+        assert_eq!(queue.mode(), PasteMode::Move);
+
+        let queue = queue.resolve(ConflictChoice::Skip);
+        assert!(queue.is_done());
+        assert_eq!(queue.mode(), PasteMode::Move);
+    }
+
+    #[test]
+    fn execute_steps_copies_replaces_and_skips() {
+        let (_tmp, sources, dest) = setup(&["a.txt", "b.txt", "c.txt"], &["b.txt", "c.txt"]);
+        let queue = PasteQueue::new(sources, &dest)
+            .resolve(ConflictChoice::Overwrite)
+            .resolve(ConflictChoice::Skip);
+
+        let pasted = queue
+            .steps()
+            .iter()
+            .filter(|s| execute_paste_step(s, PasteMode::Copy).unwrap())
+            .count();
+
+        assert_eq!(pasted, 2);
+        assert_eq!(fs::read_to_string(dest.join("a.txt")).unwrap(), "new a.txt");
+        assert_eq!(fs::read_to_string(dest.join("b.txt")).unwrap(), "new b.txt");
+        assert_eq!(fs::read_to_string(dest.join("c.txt")).unwrap(), "old c.txt");
+    }
+
+    #[test]
+    fn unique_destination_without_extension() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("Makefile");
+        fs::write(&path, "").unwrap();
+        assert_eq!(unique_destination(&path), tmp.path().join("Makefile (1)"));
+    }
+
+    #[test]
+    fn unique_destination_dotfile_keeps_name() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join(".env");
+        fs::write(&path, "").unwrap();
+        assert_eq!(unique_destination(&path), tmp.path().join(".env (1)"));
+    }
+}
@@ -0,0 +1,369 @@
+//! Line-based text diffs.
+//!
+//! [`text_diff`] compares two texts line by line and returns the old text's
+//! lines interleaved with what changed, like a unified diff without hunk
+//! headers. [`side_by_side`] lays that sequence out in two columns: lines
+//! both sides share sit on one row, and a run of removed lines is paired row
+//! by row with the added lines that replace it.
+
+use std::fs;
+use std::path::Path;
+
+use crate::error::{CoreError, CoreResult};
+use crate::fs::preview::{is_binary, strip_ansi_escapes};
+
+/// Largest file [`file_diff`] will read.
+pub const MAX_DIFF_FILE_BYTES: u64 = 8 * 1024 * 1024;
+
+/// Most cells in the LCS table, i.e. changed old lines times changed new
+/// lines once the common start and end are trimmed. Past this the changed
+/// middle is reported as removed wholesale and added back.
+const MAX_DIFF_CELLS: usize = 16 * 1024 * 1024;
+
+/// One line of a diff.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffLine {
+    /// In both texts.
+    Same(String),
+    /// Only in the old text.
+    Removed(String),
+    /// Only in the new text.
+    Added(String),
+}
+
+/// A line on one side of a [`DiffRow`], with its 1-based line number.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffCell {
+    pub number: usize,
+    pub text: String,
+}
+
+/// One row of a side-by-side diff.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffRow {
+    /// The old text's line, `None` where the new side added one.
+    pub left: Option<DiffCell>,
+    /// The new text's line, `None` where the old side lost one.
+    pub right: Option<DiffCell>,
+    /// `false` only for lines both texts share.
+    pub changed: bool,
+}
+
+/// Diff `old` against `new` line by line, keeping the longest common run
+/// of lines. Within a changed block removed lines come before added ones.
+pub fn text_diff(old: &str, new: &str) -> Vec<DiffLine> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_mid = &old[prefix..old.len() - suffix];
+    let new_mid = &new[prefix..new.len() - suffix];
+
+    let mut diff: Vec<DiffLine> = old[..prefix]
+        .iter()
+        .map(|line| DiffLine::Same(line.to_string()))
+        .collect();
+    diff.extend(diff_middle(old_mid, new_mid));
+    diff.extend(
+        old[old.len() - suffix..]
+            .iter()
+            .map(|line| DiffLine::Same(line.to_string())),
+    );
+    diff
+}
+
+/// LCS diff of the part of both texts between their common start and end.
+fn diff_middle(old: &[&str], new: &[&str]) -> Vec<DiffLine> {
+    let removed = |line: &&str| DiffLine::Removed(line.to_string());
+    let added = |line: &&str| DiffLine::Added(line.to_string());
+    if old.len().saturating_mul(new.len()) > MAX_DIFF_CELLS {
+        return old
+            .iter()
+            .map(removed)
+            .chain(new.iter().map(added))
+            .collect();
+    }
+
+    // lcs[i * width + j]: length of the LCS of old[i..] and new[j..].
+    let width = new.len() + 1;
+    let mut lcs = vec![0u32; (old.len() + 1) * width];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i * width + j] = if old[i] == new[j] {
+                lcs[(i + 1) * width + j + 1] + 1
+            } else {
+                lcs[(i + 1) * width + j].max(lcs[i * width + j + 1])
+            };
+        }
+    }
+
+    let mut diff = Vec::with_capacity(old.len().max(new.len()));
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            diff.push(DiffLine::Same(old[i].to_string()));
+            i += 1;
+            j += 1;
+        } else if lcs[(i + 1) * width + j] >= lcs[i * width + j + 1] {
+            diff.push(removed(&old[i]));
+            i += 1;
+        } else {
+            diff.push(added(&new[j]));
+            j += 1;
+        }
+    }
+    diff.extend(old[i..].iter().map(removed));
+    diff.extend(new[j..].iter().map(added));
+    diff
+}
+
+/// Lay `diff` out in two columns, old on the left and new on the right.
+///
+/// Between two shared lines, the n-th removed line shares a row with the
+/// n-th added line; whichever side has more lines gets rows of its own.
+pub fn side_by_side(diff: &[DiffLine]) -> Vec<DiffRow> {
+    let mut rows = Vec::with_capacity(diff.len());
+    let mut removed: Vec<DiffCell> = Vec::new();
+    let mut added: Vec<DiffCell> = Vec::new();
+    let (mut old_number, mut new_number) = (0, 0);
+
+    for line in diff {
+        match line {
+            DiffLine::Same(text) => {
+                flush_changes(&mut rows, &mut removed, &mut added);
+                old_number += 1;
+                new_number += 1;
+                rows.push(DiffRow {
+                    left: Some(DiffCell {
+                        number: old_number,
+                        text: text.clone(),
+                    }),
+                    right: Some(DiffCell {
+                        number: new_number,
+                        text: text.clone(),
+                    }),
+                    changed: false,
+                });
+            }
+            DiffLine::Removed(text) => {
+                old_number += 1;
+                removed.push(DiffCell {
+                    number: old_number,
+                    text: text.clone(),
+                });
+            }
+            DiffLine::Added(text) => {
+                new_number += 1;
+                added.push(DiffCell {
+                    number: new_number,
+                    text: text.clone(),
+                });
+            }
+        }
+    }
+    flush_changes(&mut rows, &mut removed, &mut added);
+    rows
+}
+
+/// Pair up a changed block's removed and added lines into rows.
+fn flush_changes(rows: &mut Vec<DiffRow>, removed: &mut Vec<DiffCell>, added: &mut Vec<DiffCell>) {
+    let count = removed.len().max(added.len());
+    let mut removed = removed.drain(..);
+    let mut added = added.drain(..);
+    for _ in 0..count {
+        rows.push(DiffRow {
+            left: removed.next(),
+            right: added.next(),
+            changed: true,
+        });
+    }
+}
+
+/// Diff the text files at `old` and `new`, with ANSI escapes stripped from
+/// each line as in the pager.
+///
+/// # Errors
+///
+/// Returns [`CoreError::NotFound`] if either path is not a file.
+/// Returns [`CoreError::InvalidName`] if either file is binary or larger
+/// than [`MAX_DIFF_FILE_BYTES`].
+/// Returns [`CoreError::Io`] on I/O failures.
+pub fn file_diff(old: &Path, new: &Path) -> CoreResult<Vec<DiffLine>> {
+    let old = read_diffable(old)?;
+    let new = read_diffable(new)?;
+    Ok(text_diff(&old, &new)
+        .into_iter()
+        .map(|line| match line {
+            DiffLine::Same(text) => DiffLine::Same(strip_ansi_escapes(&text)),
+            DiffLine::Removed(text) => DiffLine::Removed(strip_ansi_escapes(&text)),
+            DiffLine::Added(text) => DiffLine::Added(strip_ansi_escapes(&text)),
+        })
+        .collect())
+}
+
+fn read_diffable(path: &Path) -> CoreResult<String> {
+    if !path.is_file() {
+        return Err(CoreError::NotFound(path.to_path_buf()));
+    }
+    if fs::metadata(path)?.len() > MAX_DIFF_FILE_BYTES {
+        return Err(CoreError::InvalidName(
+            "file is too large to diff".to_string(),
+        ));
+    }
+    if is_binary(path)? {
+        return Err(CoreError::InvalidName(
+            "binary file cannot be diffed".to_string(),
+        ));
+    }
+    Ok(String::from_utf8_lossy(&fs::read(path)?).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn same(s: &str) -> DiffLine {
+        DiffLine::Same(s.to_string())
+    }
+
+    fn removed(s: &str) -> DiffLine {
+        DiffLine::Removed(s.to_string())
+    }
+
+    fn added(s: &str) -> DiffLine {
+        DiffLine::Added(s.to_string())
+    }
+
+    fn cell(number: usize, text: &str) -> Option<DiffCell> {
+        Some(DiffCell {
+            number,
+            text: text.to_string(),
+        })
+    }
+
+    #[test]
+    fn identical_texts_are_all_same() {
+        let diff = text_diff("a\nb\n", "a\nb\n");
+        assert_eq!(diff, [same("a"), same("b")]);
+    }
+
+    #[test]
+    fn changed_line_is_removed_then_added() {
+        let diff = text_diff("a\nb\nc\n", "a\nB\nc\n");
+        assert_eq!(diff, [same("a"), removed("b"), added("B"), same("c")]);
+    }
+
+    #[test]
+    fn keeps_longest_common_lines() {
+        let diff = text_diff("x\na\nb\nc", "a\nb\ny\nc\nz");
+        assert_eq!(
+            diff,
+            [
+                removed("x"),
+                same("a"),
+                same("b"),
+                added("y"),
+                same("c"),
+                added("z"),
+            ]
+        );
+    }
+
+    #[test]
+    fn empty_old_text_is_all_added() {
+        assert_eq!(text_diff("", "a\nb"), [added("a"), added("b")]);
+    }
+
+    #[test]
+    fn side_by_side_pairs_replacements_and_leaves_extras_unpaired() {
+        let diff = [
+            same("keep"),
+            removed("old 1"),
+            removed("old 2"),
+            added("new 1"),
+            same("tail"),
+            added("extra"),
+        ];
+
+        let rows = side_by_side(&diff);
+
+        assert_eq!(
+            rows,
+            [
+                DiffRow {
+                    left: cell(1, "keep"),
+                    right: cell(1, "keep"),
+                    changed: false,
+                },
+                DiffRow {
+                    left: cell(2, "old 1"),
+                    right: cell(2, "new 1"),
+                    changed: true,
+                },
+                DiffRow {
+                    left: cell(3, "old 2"),
+                    right: None,
+                    changed: true,
+                },
+                DiffRow {
+                    left: cell(4, "tail"),
+                    right: cell(3, "tail"),
+                    changed: false,
+                },
+                DiffRow {
+                    left: None,
+                    right: cell(4, "extra"),
+                    changed: true,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn side_by_side_pairs_a_block_whatever_its_order() {
+        let rows = side_by_side(&[added("new"), removed("old")]);
+
+        assert_eq!(
+            rows,
+            [DiffRow {
+                left: cell(1, "old"),
+                right: cell(1, "new"),
+                changed: true,
+            }]
+        );
+    }
+
+    #[test]
+    fn file_diff_reads_both_files() {
+        let tmp = TempDir::new().unwrap();
+        let old = tmp.path().join("old.txt");
+        let new = tmp.path().join("new.txt");
+        std::fs::write(&old, "one\ntwo\n").unwrap();
+        std::fs::write(&new, "one\n\x1b[31mthree\x1b[0m\n").unwrap();
+
+        let diff = file_diff(&old, &new).unwrap();
+
+        assert_eq!(diff, [same("one"), removed("two"), added("three")]);
+    }
+
+    #[test]
+    fn file_diff_rejects_binary_files() {
+        let tmp = TempDir::new().unwrap();
+        let old = tmp.path().join("old.bin");
+        let new = tmp.path().join("new.txt");
+        std::fs::write(&old, [0u8, 1, 2]).unwrap();
+        std::fs::write(&new, "text").unwrap();
+
+        assert!(matches!(
+            file_diff(&old, &new),
+            Err(CoreError::InvalidName(_))
+        ));
+    }
+}
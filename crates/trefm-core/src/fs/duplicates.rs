@@ -3,12 +3,85 @@
 //! Provides [`DuplicateCache`] for storing and retrieving duplicate file
 //! scan results on disk as JSON. The cache supports validation (removing
 //! entries for files that no longer exist) and immutable updates.
+//! [`DuplicateIgnoreList`] holds groups and files the user has chosen to
+//! hide from the duplicate view.
 
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::path::{Path, PathBuf};
 
 use serde::{Deserialize, Serialize};
 
-use super::ops::DuplicateGroup;
+use super::hash::HashAlgo;
+use super::ops::{find_duplicate_files_with_algo, DuplicateGroup, DuplicateScanOptions};
+use crate::error::{CoreError, CoreResult};
+
+/// Directory names skipped by duplicate scans unless the caller says otherwise.
+pub const DEFAULT_SCAN_EXCLUSIONS: &[&str] = &[
+    ".git",
+    "node_modules",
+    "target",
+    ".build",
+    "Library",
+    ".Trash",
+    ".cache",
+    "__pycache__",
+    "venv",
+    ".venv",
+];
+
+/// Runs a duplicate scan under `root` and returns the result as a cache
+/// stamped with the scan root and time. `options` carries the excluded
+/// roots, hash algorithm, thread count, minimum size and memory budget.
+///
+/// # Errors
+///
+/// Same as [`find_duplicate_files_with_algo`].
+pub fn scan_duplicates(
+    root: &Path,
+    max_depth: usize,
+    show_hidden: bool,
+    excluded_dirs: &HashSet<&str>,
+    options: &DuplicateScanOptions,
+) -> CoreResult<DuplicateCache> {
+    let groups =
+        find_duplicate_files_with_algo(root, max_depth, show_hidden, excluded_dirs, options)?;
+    let mut cache = DuplicateCache::from(groups);
+    cache.scan_root = Some(root.to_path_buf());
+    Ok(cache)
+}
+
+/// Order of groups in the duplicate view. Every criterion puts the largest
+/// value first and falls back to file size for ties.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DuplicateSort {
+    /// Size of a single file in the group (scan order).
+    #[default]
+    Size,
+    /// Space freed by keeping one copy: `size * (count - 1)`.
+    Reclaimable,
+    /// Number of files in the group.
+    Count,
+}
+
+impl DuplicateSort {
+    /// Returns the criterion that follows this one when toggling.
+    pub fn next(self) -> Self {
+        match self {
+            Self::Size => Self::Reclaimable,
+            Self::Reclaimable => Self::Count,
+            Self::Count => Self::Size,
+        }
+    }
+
+    /// Short label shown in the duplicate view.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Size => "size",
+            Self::Reclaimable => "reclaimable",
+            Self::Count => "count",
+        }
+    }
+}
 
 /// Cached information about a single file in a duplicate group.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -26,6 +99,13 @@ pub struct CachedDuplicateGroup {
     pub files: Vec<CachedFileInfo>,
 }
 
+impl CachedDuplicateGroup {
+    /// Returns `true` if any file of the group lies under `dir`, at any depth.
+    pub fn has_file_under(&self, dir: &Path) -> bool {
+        self.files.iter().any(|f| f.path.starts_with(dir))
+    }
+}
+
 /// Persistent cache of duplicate file scan results.
 ///
 /// Stored as JSON at `~/.config/trefm/duplicates.json`. All mutation
@@ -112,10 +192,226 @@ impl DuplicateCache {
             .sum()
     }
 
+    /// Returns the cache with its groups reordered by `criterion`.
+    pub fn sorted_by(self, criterion: DuplicateSort) -> Self {
+        let mut groups = self.groups;
+        groups.sort_by(|a, b| {
+            let key = |g: &CachedDuplicateGroup| match criterion {
+                DuplicateSort::Size => g.size,
+                DuplicateSort::Reclaimable => g.size * (g.files.len() as u64).saturating_sub(1),
+                DuplicateSort::Count => g.files.len() as u64,
+            };
+            key(b).cmp(&key(a)).then(b.size.cmp(&a.size))
+        });
+        Self { groups, ..self }
+    }
+
     /// Returns `true` if no duplicate groups are stored.
     pub fn is_empty(&self) -> bool {
         self.groups.is_empty()
     }
+
+    /// Updates the cache for a set of changed paths without a full rescan.
+    ///
+    /// Each changed path is first removed from whatever group held it.
+    /// Paths that still exist as regular files are then hashed — only if a
+    /// group with the same size exists, using that group's algorithm — and
+    /// re-added to the group whose digest matches. Groups left with fewer
+    /// than two files are dropped, and paths outside `scan_root` are ignored.
+    ///
+    /// Because the cache only remembers files that already have a
+    /// duplicate, a changed file can only join an existing group; two new
+    /// copies of a previously unique file are found by the next full scan.
+    pub fn update_incremental(self, changed: &[PathBuf]) -> Self {
+        self.update_incremental_with(changed, |algo, path| algo.hash_file(path))
+    }
+
+    fn update_incremental_with<F>(self, changed: &[PathBuf], mut hash: F) -> Self
+    where
+        F: FnMut(HashAlgo, &Path) -> CoreResult<String>,
+    {
+        let changed: Vec<&PathBuf> = changed
+            .iter()
+            .filter(|p| {
+                self.scan_root
+                    .as_ref()
+                    .is_none_or(|root| p.starts_with(root))
+            })
+            .collect();
+        if changed.is_empty() {
+            return self;
+        }
+
+        let mut groups: Vec<CachedDuplicateGroup> = self
+            .groups
+            .into_iter()
+            .map(|group| CachedDuplicateGroup {
+                files: group
+                    .files
+                    .into_iter()
+                    .filter(|f| !changed.contains(&&f.path))
+                    .collect(),
+                ..group
+            })
+            .collect();
+
+        for path in changed {
+            let Ok(metadata) = std::fs::symlink_metadata(path) else {
+                continue;
+            };
+            if !metadata.is_file() {
+                continue;
+            }
+            let size = metadata.len();
+            // Digest per algorithm, computed on first use for this path.
+            let mut digests: HashMap<HashAlgo, Option<String>> = HashMap::new();
+            for group in groups.iter_mut().filter(|g| g.size == size) {
+                let Some(algo) = HashAlgo::of_digest(&group.hash) else {
+                    continue;
+                };
+                let digest = digests.entry(algo).or_insert_with(|| hash(algo, path).ok());
+                if digest.as_deref() == Some(group.hash.as_str()) {
+                    group.files.push(CachedFileInfo {
+                        path: path.clone(),
+                        name: path
+                            .file_name()
+                            .map(|n| crate::nfc_string(&n.to_string_lossy()))
+                            .unwrap_or_default(),
+                        size,
+                    });
+                    break;
+                }
+            }
+        }
+
+        groups.retain(|g| g.files.len() >= 2);
+        Self { groups, ..self }
+    }
+
+    /// Returns the groups left after applying `ignored`: groups whose hash
+    /// is listed are dropped, listed paths are removed from the remaining
+    /// groups, and any group that falls below 2 files is dropped.
+    pub fn filtered(&self, ignored: &DuplicateIgnoreList) -> Vec<CachedDuplicateGroup> {
+        if ignored.is_empty() {
+            return self.groups.clone();
+        }
+        self.groups
+            .iter()
+            .filter(|g| !ignored.is_hash_ignored(&g.hash))
+            .filter_map(|g| {
+                let files: Vec<CachedFileInfo> = g
+                    .files
+                    .iter()
+                    .filter(|f| !ignored.is_path_ignored(&f.path))
+                    .cloned()
+                    .collect();
+                if files.len() >= 2 {
+                    Some(CachedDuplicateGroup {
+                        size: g.size,
+                        hash: g.hash.clone(),
+                        files,
+                    })
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Returns `true` if any group was hashed with an algorithm other than
+    /// `algo`, in which case the cache should be rebuilt rather than mixed.
+    pub fn is_stale_for(&self, algo: HashAlgo) -> bool {
+        self.groups
+            .iter()
+            .any(|g| HashAlgo::of_digest(&g.hash) != Some(algo))
+    }
+
+    /// Formats the groups as a plain-text report: a summary line with the
+    /// reclaimable byte count, then each group with its files indented.
+    pub fn format_report(&self) -> String {
+        let mut out = format!(
+            "{} duplicate group(s), {} file(s), {} bytes reclaimable\n",
+            self.groups.len(),
+            self.total_files(),
+            self.total_wasted()
+        );
+        for group in &self.groups {
+            out.push_str(&format!(
+                "\n{} bytes x {} ({})\n",
+                group.size,
+                group.files.len(),
+                group.hash
+            ));
+            for file in &group.files {
+                out.push_str(&format!("  {}\n", file.path.display()));
+            }
+        }
+        out
+    }
+}
+
+/// Content hashes and paths hidden from the duplicate view.
+///
+/// Stored as TOML at `~/.config/trefm/dup-ignore.toml`. Hashes use the same
+/// `algo:hex` form as [`CachedDuplicateGroup::hash`], so an entry only
+/// matches groups produced with the same algorithm.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DuplicateIgnoreList {
+    pub hashes: BTreeSet<String>,
+    pub paths: BTreeSet<PathBuf>,
+}
+
+impl DuplicateIgnoreList {
+    /// Loads the list from a TOML file. Returns an empty list on any error.
+    pub fn load(path: &Path) -> Self {
+        match std::fs::read_to_string(path) {
+            Ok(contents) => toml::from_str(&contents).unwrap_or_default(),
+            Err(_) => Self::default(),
+        }
+    }
+
+    /// Saves the list to a TOML file, creating parent directories as needed.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CoreError::Io`] if the file cannot be written.
+    pub fn save(&self, path: &Path) -> CoreResult<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let contents =
+            toml::to_string_pretty(self).map_err(|e| CoreError::ConfigParse(e.to_string()))?;
+        std::fs::write(path, contents)?;
+        Ok(())
+    }
+
+    /// Returns a new list that also ignores groups with `hash`.
+    pub fn with_hash(self, hash: impl Into<String>) -> Self {
+        let mut hashes = self.hashes;
+        hashes.insert(hash.into());
+        Self { hashes, ..self }
+    }
+
+    /// Returns a new list that also ignores the file at `path`.
+    pub fn with_path(self, path: impl Into<PathBuf>) -> Self {
+        let mut paths = self.paths;
+        paths.insert(path.into());
+        Self { paths, ..self }
+    }
+
+    pub fn is_hash_ignored(&self, hash: &str) -> bool {
+        self.hashes.contains(hash)
+    }
+
+    pub fn is_path_ignored(&self, path: &Path) -> bool {
+        self.paths.contains(path)
+    }
+
+    /// Returns `true` if nothing is ignored.
+    pub fn is_empty(&self) -> bool {
+        self.hashes.is_empty() && self.paths.is_empty()
+    }
 }
 
 impl From<Vec<DuplicateGroup>> for DuplicateCache {
@@ -458,4 +754,388 @@ mod tests {
         assert_eq!(loaded.scan_root, Some(PathBuf::from("/home/user")));
         assert_eq!(loaded.scanned_at, Some("now".to_string()));
     }
+
+    fn write_dup_tree() -> TempDir {
+        let tmp = TempDir::new().unwrap();
+        let root = tmp.path();
+        fs::create_dir_all(root.join("sub/deeper")).unwrap();
+        fs::create_dir_all(root.join("node_modules")).unwrap();
+        fs::write(root.join("a.txt"), "duplicate!").unwrap();
+        fs::write(root.join("sub/b.txt"), "duplicate!").unwrap();
+        fs::write(root.join("sub/deeper/c.txt"), "duplicate!").unwrap();
+        fs::write(root.join("x.bin"), "pair").unwrap();
+        fs::write(root.join("sub/y.bin"), "pair").unwrap();
+        fs::write(root.join("node_modules/z.bin"), "pair").unwrap();
+        fs::write(root.join("unique.txt"), "only one").unwrap();
+        tmp
+    }
+
+    #[test]
+    fn scan_duplicates_reports_groups_and_reclaimable_bytes() {
+        let tmp = write_dup_tree();
+        let exclusions: HashSet<&str> = DEFAULT_SCAN_EXCLUSIONS.iter().copied().collect();
+        let cache = scan_duplicates(
+            tmp.path(),
+            20,
+            true,
+            &exclusions,
+            &DuplicateScanOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(cache.scan_root.as_deref(), Some(tmp.path()));
+        assert_eq!(cache.groups.len(), 2);
+        let mut sizes: Vec<(u64, usize)> = cache
+            .groups
+            .iter()
+            .map(|g| (g.size, g.files.len()))
+            .collect();
+        sizes.sort();
+        // node_modules/z.bin is excluded, so "pair" has two copies only
+        assert_eq!(sizes, vec![(4, 2), (10, 3)]);
+        assert_eq!(cache.total_wasted(), 4 + 10 * 2);
+
+        let report = cache.format_report();
+        assert!(report.starts_with("2 duplicate group(s), 5 file(s), 24 bytes reclaimable"));
+        assert!(!report.contains("unique.txt"));
+    }
+
+    #[test]
+    fn scan_duplicates_skips_excluded_roots() {
+        let tmp = write_dup_tree();
+        let exclusions: HashSet<&str> = DEFAULT_SCAN_EXCLUSIONS.iter().copied().collect();
+        let skipped = vec![tmp.path().join("sub")];
+        let cache = scan_duplicates(
+            tmp.path(),
+            20,
+            true,
+            &exclusions,
+            &DuplicateScanOptions {
+                excluded_roots: skipped,
+                ..DuplicateScanOptions::default()
+            },
+        )
+        .unwrap();
+
+        // Only a.txt and x.bin remain outside sub/, and they differ
+        assert!(cache.groups.is_empty());
+    }
+
+    #[test]
+    fn scan_duplicates_honors_depth() {
+        let tmp = write_dup_tree();
+        let exclusions: HashSet<&str> = DEFAULT_SCAN_EXCLUSIONS.iter().copied().collect();
+        let cache = scan_duplicates(
+            tmp.path(),
+            1,
+            true,
+            &exclusions,
+            &DuplicateScanOptions::default(),
+        )
+        .unwrap();
+        let total: usize = cache.groups.iter().map(|g| g.files.len()).sum();
+        // sub/deeper/c.txt is beyond depth 1
+        assert_eq!(total, 4);
+    }
+
+    #[test]
+    fn scan_duplicates_without_exclusions_includes_everything() {
+        let tmp = write_dup_tree();
+        let cache = scan_duplicates(
+            tmp.path(),
+            20,
+            true,
+            &HashSet::new(),
+            &DuplicateScanOptions {
+                algo: HashAlgo::Xxh3,
+                ..DuplicateScanOptions::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(cache.total_files(), 6);
+    }
+
+    #[test]
+    fn format_report_empty_cache() {
+        let report = DuplicateCache::default().format_report();
+        assert_eq!(
+            report,
+            "0 duplicate group(s), 0 file(s), 0 bytes reclaimable\n"
+        );
+    }
+
+    #[test]
+    fn cache_from_other_algo_is_stale() {
+        let tmp = write_dup_tree();
+        let exclusions: HashSet<&str> = DEFAULT_SCAN_EXCLUSIONS.iter().copied().collect();
+        let cache = scan_duplicates(
+            tmp.path(),
+            20,
+            true,
+            &exclusions,
+            &DuplicateScanOptions {
+                algo: HashAlgo::Blake3,
+                ..DuplicateScanOptions::default()
+            },
+        )
+        .unwrap();
+
+        assert!(!cache.is_stale_for(HashAlgo::Blake3));
+        assert!(cache.is_stale_for(HashAlgo::Sha256));
+        assert!(cache.is_stale_for(HashAlgo::Xxh3));
+    }
+
+    #[test]
+    fn legacy_untagged_cache_counts_as_sha256() {
+        let cache = sample_cache();
+        assert!(!cache.is_stale_for(HashAlgo::Sha256));
+        assert!(cache.is_stale_for(HashAlgo::Blake3));
+        assert!(!DuplicateCache::default().is_stale_for(HashAlgo::Xxh3));
+    }
+
+    /// Scans `tmp` with SHA-256 and returns the cache.
+    fn scanned(tmp: &TempDir) -> DuplicateCache {
+        scan_duplicates(
+            tmp.path(),
+            20,
+            true,
+            &HashSet::new(),
+            &DuplicateScanOptions::default(),
+        )
+        .unwrap()
+    }
+
+    /// Runs an incremental update, counting hashed paths.
+    fn update_counting(
+        cache: DuplicateCache,
+        changed: &[PathBuf],
+    ) -> (DuplicateCache, Vec<PathBuf>) {
+        let mut hashed = Vec::new();
+        let cache = cache.update_incremental_with(changed, |algo, path| {
+            hashed.push(path.to_path_buf());
+            algo.hash_file(path)
+        });
+        (cache, hashed)
+    }
+
+    fn incremental_tree() -> TempDir {
+        let tmp = TempDir::new().unwrap();
+        fs::write(tmp.path().join("a1.txt"), "group a").unwrap();
+        fs::write(tmp.path().join("a2.txt"), "group a").unwrap();
+        fs::write(tmp.path().join("b1.txt"), "group bb").unwrap();
+        fs::write(tmp.path().join("b2.txt"), "group bb").unwrap();
+        tmp
+    }
+
+    fn group_of<'a>(cache: &'a DuplicateCache, name: &str) -> &'a CachedDuplicateGroup {
+        cache
+            .groups
+            .iter()
+            .find(|g| g.files.iter().any(|f| f.name == name))
+            .unwrap()
+    }
+
+    #[test]
+    fn incremental_adds_new_duplicate_to_matching_group() {
+        let tmp = incremental_tree();
+        let cache = scanned(&tmp);
+        let new_file = tmp.path().join("a3.txt");
+        fs::write(&new_file, "group a").unwrap();
+
+        let (cache, hashed) = update_counting(cache, std::slice::from_ref(&new_file));
+
+        // Only the new file was hashed; the other groups were left alone
+        assert_eq!(hashed, vec![new_file]);
+        assert_eq!(group_of(&cache, "a1.txt").files.len(), 3);
+        assert_eq!(group_of(&cache, "b1.txt").files.len(), 2);
+    }
+
+    #[test]
+    fn incremental_skips_hashing_when_no_group_has_the_size() {
+        let tmp = incremental_tree();
+        let cache = scanned(&tmp);
+        let unrelated = tmp.path().join("other.txt");
+        fs::write(&unrelated, "a completely different size").unwrap();
+
+        let (cache, hashed) = update_counting(cache, &[unrelated]);
+        assert!(hashed.is_empty());
+        assert_eq!(cache.total_files(), 4);
+    }
+
+    #[test]
+    fn incremental_same_size_different_content_is_not_added() {
+        let tmp = incremental_tree();
+        let cache = scanned(&tmp);
+        let lookalike = tmp.path().join("a9.txt");
+        fs::write(&lookalike, "group z").unwrap();
+
+        let (cache, hashed) = update_counting(cache, std::slice::from_ref(&lookalike));
+        assert_eq!(hashed, vec![lookalike]);
+        assert_eq!(cache.total_files(), 4);
+    }
+
+    #[test]
+    fn incremental_drops_deleted_file_and_its_group() {
+        let tmp = incremental_tree();
+        let cache = scanned(&tmp);
+        let gone = tmp.path().join("b2.txt");
+        fs::remove_file(&gone).unwrap();
+
+        let (cache, hashed) = update_counting(cache, &[gone]);
+        assert!(hashed.is_empty());
+        assert_eq!(cache.groups.len(), 1);
+        assert_eq!(group_of(&cache, "a1.txt").files.len(), 2);
+    }
+
+    #[test]
+    fn incremental_rehashes_modified_file() {
+        let tmp = incremental_tree();
+        let cache = scanned(&tmp);
+        let edited = tmp.path().join("a2.txt");
+        fs::write(&edited, "GROUP A").unwrap();
+
+        let (cache, hashed) = update_counting(cache, std::slice::from_ref(&edited));
+        // Same size, so it is re-hashed; the digest no longer matches a1
+        assert_eq!(hashed, vec![edited]);
+        assert_eq!(cache.groups.len(), 1);
+        assert_eq!(group_of(&cache, "b1.txt").files.len(), 2);
+    }
+
+    #[test]
+    fn incremental_ignores_paths_outside_scan_root() {
+        let tmp = incremental_tree();
+        let other = TempDir::new().unwrap();
+        let outside = other.path().join("a1.txt");
+        fs::write(&outside, "group a").unwrap();
+
+        let (cache, hashed) = update_counting(scanned(&tmp), &[outside]);
+        assert!(hashed.is_empty());
+        assert_eq!(cache.total_files(), 4);
+    }
+
+    #[test]
+    fn filtered_drops_ignored_hashes() {
+        let ignored = DuplicateIgnoreList::default().with_hash("abc123");
+        let groups = sample_cache().filtered(&ignored);
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].hash, "def456");
+    }
+
+    #[test]
+    fn filtered_removes_ignored_paths_and_small_groups() {
+        let ignored = DuplicateIgnoreList::default()
+            .with_path("/tmp/a.txt")
+            .with_path("/tmp/x.txt");
+        let groups = sample_cache().filtered(&ignored);
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].hash, "abc123");
+        assert_eq!(groups[0].files.len(), 2);
+        assert!(groups[0].files.iter().all(|f| f.name != "a.txt"));
+    }
+
+    #[test]
+    fn filtered_with_empty_list_keeps_everything() {
+        let cache = sample_cache();
+        assert_eq!(
+            cache.filtered(&DuplicateIgnoreList::default()),
+            cache.groups
+        );
+    }
+
+    #[test]
+    fn ignore_list_save_load_round_trip() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("nested").join("dup-ignore.toml");
+        let list = DuplicateIgnoreList::default()
+            .with_hash("sha256:abc")
+            .with_path("/tmp/keep.bin");
+
+        list.save(&path).unwrap();
+        assert_eq!(DuplicateIgnoreList::load(&path), list);
+    }
+
+    #[test]
+    fn ignore_list_load_missing_or_invalid_is_empty() {
+        let tmp = TempDir::new().unwrap();
+        assert!(DuplicateIgnoreList::load(&tmp.path().join("missing.toml")).is_empty());
+
+        let path = tmp.path().join("bad.toml");
+        fs::write(&path, "hashes = 3").unwrap();
+        assert!(DuplicateIgnoreList::load(&path).is_empty());
+    }
+
+    #[test]
+    fn ignore_list_parses_hand_written_toml() {
+        let list: DuplicateIgnoreList = toml::from_str(r#"paths = ["/data/a.iso"]"#).unwrap();
+        assert!(list.hashes.is_empty());
+        assert!(list.is_path_ignored(Path::new("/data/a.iso")));
+    }
+
+    /// One 1000-byte pair next to ten copies of a 300-byte file.
+    fn crafted_cache() -> DuplicateCache {
+        let group = |size: u64, hash: &str, count: usize| CachedDuplicateGroup {
+            size,
+            hash: hash.to_string(),
+            files: (0..count)
+                .map(|i| CachedFileInfo {
+                    path: PathBuf::from(format!("/tmp/{hash}/{i}")),
+                    name: i.to_string(),
+                    size,
+                })
+                .collect(),
+        };
+        DuplicateCache {
+            groups: vec![
+                group(1000, "large", 2),
+                group(300, "small", 10),
+                group(500, "mid", 3),
+            ],
+            ..Default::default()
+        }
+    }
+
+    fn hashes(cache: &DuplicateCache) -> Vec<&str> {
+        cache.groups.iter().map(|g| g.hash.as_str()).collect()
+    }
+
+    #[test]
+    fn sorted_by_size_puts_largest_file_first() {
+        let cache = crafted_cache().sorted_by(DuplicateSort::Size);
+        assert_eq!(hashes(&cache), ["large", "mid", "small"]);
+    }
+
+    #[test]
+    fn sorted_by_reclaimable_differs_from_size() {
+        // small: 300 * 9 = 2700, large: 1000 * 1 = 1000, mid: 500 * 2 = 1000
+        let cache = crafted_cache().sorted_by(DuplicateSort::Reclaimable);
+        assert_eq!(hashes(&cache), ["small", "large", "mid"]);
+        assert_ne!(
+            hashes(&cache),
+            hashes(&crafted_cache().sorted_by(DuplicateSort::Size))
+        );
+    }
+
+    #[test]
+    fn sorted_by_count_puts_most_copies_first() {
+        let cache = crafted_cache().sorted_by(DuplicateSort::Count);
+        assert_eq!(hashes(&cache), ["small", "mid", "large"]);
+    }
+
+    #[test]
+    fn has_file_under_matches_whole_components() {
+        let cache = crafted_cache();
+        let large = &cache.groups[0];
+        assert!(large.has_file_under(Path::new("/tmp")));
+        assert!(large.has_file_under(Path::new("/tmp/large")));
+        assert!(!large.has_file_under(Path::new("/tmp/lar")));
+        assert!(!large.has_file_under(Path::new("/tmp/small")));
+    }
+
+    #[test]
+    fn duplicate_sort_next_cycles() {
+        let sort = DuplicateSort::default();
+        assert_eq!(sort, DuplicateSort::Size);
+        assert_eq!(sort.next().next().next(), DuplicateSort::Size);
+        assert_eq!(sort.next().label(), "reclaimable");
+    }
 }
@@ -1,6 +1,12 @@
 //! File entry representation.
+//!
+//! Entries listed by [`read_directory`](crate::fs::ops::read_directory) know
+//! their type from the directory listing alone; size and modification time
+//! are read from disk the first time something asks for them. Listing a huge
+//! directory sorted by name therefore costs no `stat` per file.
 
 use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
 use std::time::SystemTime;
 
 use unicode_normalization::UnicodeNormalization;
@@ -9,7 +15,8 @@ use unicode_normalization::UnicodeNormalization;
 ///
 /// `FileEntry` is immutable — create new instances via [`FileEntry::new`]
 /// rather than mutating existing ones. Directory sizes are reported as `0`;
-/// use an async size calculator for accurate directory sizes.
+/// use an async size calculator for accurate directory sizes. Size and
+/// modification time may be loaded lazily, see the module docs.
 ///
 /// # Examples
 ///
@@ -22,8 +29,79 @@ use unicode_normalization::UnicodeNormalization;
 /// assert_eq!(entry.name(), "Cargo.toml");
 /// assert!(!entry.is_dir());
 /// ```
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "json",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "EntryRepr", from = "EntryRepr")
+)]
 pub struct FileEntry {
+    path: PathBuf,
+    name: String,
+    stat: OnceLock<Stat>,
+    is_dir: bool,
+    is_hidden: bool,
+    is_symlink: bool,
+    is_parent_link: bool,
+}
+
+/// The part of an entry that needs a `stat` call.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Stat {
+    size: u64,
+    modified: Option<SystemTime>,
+}
+
+impl Stat {
+    fn from_metadata(metadata: &std::fs::Metadata) -> Self {
+        Self {
+            size: if metadata.is_dir() { 0 } else { metadata.len() },
+            modified: metadata.modified().ok(),
+        }
+    }
+
+    /// Reads `path` without following a final symlink, like the directory
+    /// listing does. A file that vanished reads as size `0` and no time.
+    fn read(path: &Path) -> Self {
+        #[cfg(any(test, feature = "stat-counter"))]
+        STAT_CALLS.with(|calls| calls.set(calls.get() + 1));
+        std::fs::symlink_metadata(path)
+            .map(|metadata| Self::from_metadata(&metadata))
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(any(test, feature = "stat-counter"))]
+thread_local! {
+    /// Lazy `stat` calls made on this thread, for tests.
+    pub(crate) static STAT_CALLS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
+/// Lazy metadata reads made on this thread so far (feature `stat-counter`).
+#[cfg(any(test, feature = "stat-counter"))]
+pub fn stat_calls() -> usize {
+    STAT_CALLS.with(|calls| calls.get())
+}
+
+/// Entries are equal when every field is, which loads both stats.
+impl PartialEq for FileEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.path == other.path
+            && self.name == other.name
+            && self.is_dir == other.is_dir
+            && self.is_hidden == other.is_hidden
+            && self.is_symlink == other.is_symlink
+            && self.is_parent_link == other.is_parent_link
+            && self.stat() == other.stat()
+    }
+}
+
+impl Eq for FileEntry {}
+
+/// The serialised form of a [`FileEntry`], with size and time filled in.
+#[cfg(feature = "json")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct EntryRepr {
     path: PathBuf,
     name: String,
     size: u64,
@@ -31,6 +109,50 @@ pub struct FileEntry {
     is_dir: bool,
     is_hidden: bool,
     is_symlink: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    is_parent_link: bool,
+}
+
+#[cfg(feature = "json")]
+impl From<FileEntry> for EntryRepr {
+    fn from(entry: FileEntry) -> Self {
+        let Stat { size, modified } = entry.stat();
+        Self {
+            path: entry.path,
+            name: entry.name,
+            size,
+            modified,
+            is_dir: entry.is_dir,
+            is_hidden: entry.is_hidden,
+            is_symlink: entry.is_symlink,
+            is_parent_link: entry.is_parent_link,
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl From<EntryRepr> for FileEntry {
+    fn from(repr: EntryRepr) -> Self {
+        Self {
+            path: repr.path,
+            name: repr.name,
+            stat: OnceLock::from(Stat {
+                size: repr.size,
+                modified: repr.modified,
+            }),
+            is_dir: repr.is_dir,
+            is_hidden: repr.is_hidden,
+            is_symlink: repr.is_symlink,
+            is_parent_link: repr.is_parent_link,
+        }
+    }
+}
+
+/// NFC-normalised last component of `path`.
+fn entry_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().nfc().collect::<String>())
+        .unwrap_or_default()
 }
 
 impl FileEntry {
@@ -39,23 +161,40 @@ impl FileEntry {
     /// Hidden files are detected by a leading `.` in the file name.
     /// Directory sizes are set to `0`.
     pub fn new(path: PathBuf, metadata: &std::fs::Metadata) -> Self {
-        let name = path
-            .file_name()
-            .map(|n| n.to_string_lossy().nfc().collect::<String>())
-            .unwrap_or_default();
+        let name = entry_name(&path);
         let is_hidden = name.starts_with('.');
 
         Self {
             path,
             name,
-            size: if metadata.is_dir() { 0 } else { metadata.len() },
-            modified: metadata.modified().ok(),
+            stat: OnceLock::from(Stat::from_metadata(metadata)),
             is_dir: metadata.is_dir(),
             is_hidden,
             is_symlink: metadata.is_symlink(),
+            is_parent_link: false,
         }
     }
 
+    /// Creates a `FileEntry` from a directory listing without reading its
+    /// metadata: the type comes from the listing (on Linux, the dirent) and
+    /// size and modification time are read on first use.
+    pub fn from_dir_entry(dir_entry: &std::fs::DirEntry) -> std::io::Result<Self> {
+        let file_type = dir_entry.file_type()?;
+        let path = dir_entry.path();
+        let name = entry_name(&path);
+        let is_hidden = name.starts_with('.');
+
+        Ok(Self {
+            path,
+            name,
+            stat: OnceLock::new(),
+            is_dir: file_type.is_dir(),
+            is_hidden,
+            is_symlink: file_type.is_symlink(),
+            is_parent_link: false,
+        })
+    }
+
     /// Creates a `FileEntry` from remote SFTP metadata without `std::fs::Metadata`.
     ///
     /// This is used when listing directories on a remote SSH/SFTP server.
@@ -73,11 +212,31 @@ impl FileEntry {
         Self {
             path,
             name,
-            size: if is_dir { 0 } else { size },
-            modified,
+            stat: OnceLock::from(Stat {
+                size: if is_dir { 0 } else { size },
+                modified,
+            }),
             is_dir,
             is_hidden,
             is_symlink,
+            is_parent_link: false,
+        }
+    }
+
+    /// Creates the synthetic `..` row that leads to `parent`.
+    ///
+    /// Nothing is read from disk: size and modification time are empty, and
+    /// [`is_parent_link`](Self::is_parent_link) lets callers keep file
+    /// operations away from it.
+    pub fn parent_link(parent: PathBuf) -> Self {
+        Self {
+            path: parent,
+            name: "..".to_string(),
+            stat: OnceLock::from(Stat::default()),
+            is_dir: true,
+            is_hidden: false,
+            is_symlink: false,
+            is_parent_link: true,
         }
     }
 
@@ -91,14 +250,30 @@ impl FileEntry {
         &self.name
     }
 
-    /// Returns the file size in bytes. Always `0` for directories.
+    /// Returns the file size in bytes. Always `0` for directories, which
+    /// never need a `stat` for it.
     pub fn size(&self) -> u64 {
-        self.size
+        if self.is_dir {
+            return 0;
+        }
+        self.stat().size
     }
 
     /// Returns the last-modified time, if available.
     pub fn modified(&self) -> Option<SystemTime> {
-        self.modified
+        self.stat().modified
+    }
+
+    /// Size and time, read from disk the first time they are needed.
+    fn stat(&self) -> Stat {
+        *self.stat.get_or_init(|| Stat::read(&self.path))
+    }
+
+    /// Reads size and modification time now if they are not loaded yet, so
+    /// that later sorts by size or date see the entry as it was at this
+    /// point rather than as it is on disk by then.
+    pub fn load_metadata(&self) {
+        self.stat();
     }
 
     /// Returns `true` if this entry is a directory.
@@ -115,6 +290,20 @@ impl FileEntry {
     pub fn is_symlink(&self) -> bool {
         self.is_symlink
     }
+
+    /// Where this entry links to, as written in the link, or `None` if it is
+    /// not a symlink or the link cannot be read. The target may not exist.
+    pub fn symlink_target(&self) -> Option<PathBuf> {
+        if !self.is_symlink {
+            return None;
+        }
+        std::fs::read_link(&self.path).ok()
+    }
+
+    /// Returns `true` for the synthetic `..` row from [`FileEntry::parent_link`].
+    pub fn is_parent_link(&self) -> bool {
+        self.is_parent_link
+    }
 }
 
 #[cfg(test)]
@@ -157,6 +346,18 @@ mod tests {
         assert!(!entry.is_symlink());
     }
 
+    #[test]
+    fn parent_link_is_a_synthetic_directory() {
+        let entry = FileEntry::parent_link(PathBuf::from("/home/user"));
+
+        assert_eq!(entry.name(), "..");
+        assert_eq!(entry.path(), Path::new("/home/user"));
+        assert!(entry.is_dir());
+        assert!(entry.is_parent_link());
+        assert!(!entry.is_hidden());
+        assert_eq!(entry.modified(), None);
+    }
+
     #[test]
     fn file_entry_hidden_file() {
         let tmp = TempDir::new().unwrap();
@@ -201,6 +402,17 @@ mod tests {
 
         assert!(entry.is_symlink());
         assert_eq!(entry.name(), "link.txt");
+        assert_eq!(entry.symlink_target(), Some(target));
+    }
+
+    #[test]
+    fn regular_file_has_no_symlink_target() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("plain.txt");
+        fs::write(&path, "data").unwrap();
+        let entry = FileEntry::new(path.clone(), &fs::metadata(&path).unwrap());
+
+        assert_eq!(entry.symlink_target(), None);
     }
 
     #[test]
@@ -333,4 +545,89 @@ mod tests {
         // Directory size is always 0 per implementation
         assert_eq!(entry.size(), 0);
     }
+
+    #[test]
+    fn file_entry_serializes_core_fields() {
+        let tmp = TempDir::new().unwrap();
+        let file_path = tmp.path().join("data.bin");
+        fs::write(&file_path, "12345").unwrap();
+        let entry = FileEntry::new(file_path.clone(), &fs::metadata(&file_path).unwrap());
+
+        let value = serde_json::to_value(&entry).unwrap();
+        assert_eq!(value["name"], "data.bin");
+        assert_eq!(value["size"], 5);
+        assert_eq!(value["is_dir"], false);
+    }
+
+    #[test]
+    fn file_entry_json_round_trip() {
+        let tmp = TempDir::new().unwrap();
+        let dir_path = tmp.path().join("subdir");
+        fs::create_dir(&dir_path).unwrap();
+        let entry = FileEntry::new(dir_path.clone(), &fs::metadata(&dir_path).unwrap());
+
+        let json = serde_json::to_string(&entry).unwrap();
+        let back: FileEntry = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, entry);
+        assert!(back.is_dir());
+    }
+
+    fn sorted_stat_calls(field: crate::nav::filter::SortField) -> usize {
+        let tmp = TempDir::new().unwrap();
+        for i in 0..20 {
+            fs::write(tmp.path().join(format!("f{i:02}.txt")), "x".repeat(i)).unwrap();
+        }
+        fs::create_dir(tmp.path().join("sub")).unwrap();
+
+        let before = STAT_CALLS.with(|calls| calls.get());
+        let entries = crate::fs::ops::read_directory(tmp.path()).unwrap();
+        let sorted = crate::nav::filter::sort_entries(
+            &entries,
+            field,
+            crate::nav::filter::SortDirection::Ascending,
+            true,
+        );
+        assert_eq!(sorted.len(), 21);
+        STAT_CALLS.with(|calls| calls.get()) - before
+    }
+
+    #[test]
+    fn name_sort_reads_no_metadata() {
+        assert_eq!(sorted_stat_calls(crate::nav::filter::SortField::Name), 0);
+    }
+
+    #[test]
+    fn size_sort_reads_each_file_once() {
+        assert_eq!(sorted_stat_calls(crate::nav::filter::SortField::Size), 20);
+    }
+
+    #[test]
+    fn lazy_entry_matches_eager_entry() {
+        let tmp = TempDir::new().unwrap();
+        let file_path = tmp.path().join("data.bin");
+        fs::write(&file_path, "12345").unwrap();
+
+        let lazy = crate::fs::ops::read_directory(tmp.path())
+            .unwrap()
+            .remove(0);
+        let eager = FileEntry::new(file_path.clone(), &fs::metadata(&file_path).unwrap());
+        assert_eq!(lazy.size(), 5);
+        assert_eq!(lazy, eager);
+    }
+
+    #[test]
+    fn loaded_metadata_outlives_the_file() {
+        let tmp = TempDir::new().unwrap();
+        let file_path = tmp.path().join("gone.txt");
+        fs::write(&file_path, "12345").unwrap();
+        let entry = crate::fs::ops::read_directory(tmp.path())
+            .unwrap()
+            .remove(0);
+
+        entry.load_metadata();
+        fs::remove_file(&file_path).unwrap();
+
+        assert_eq!(entry.size(), 5);
+        assert!(entry.modified().is_some());
+    }
 }
@@ -0,0 +1,149 @@
+//! Exclusion patterns shared by the search front-ends.
+//!
+//! An [`ExcludeSet`] holds simple glob patterns (`*` and `?`). A pattern
+//! without a `/` is matched against each entry's file name, so a plain
+//! directory name like `node_modules` skips that directory anywhere in the
+//! tree. A pattern containing `/` is matched against the whole path relative
+//! to the search root, where `*` does not cross a `/` (e.g. `docs/gen*`).
+
+use std::path::Path;
+
+/// A set of glob patterns for paths that searches should skip.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExcludeSet {
+    patterns: Vec<String>,
+}
+
+impl ExcludeSet {
+    /// Builds a set from the given patterns. Empty patterns are dropped and
+    /// leading/trailing slashes are ignored.
+    pub fn new<I, S>(patterns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let patterns = patterns
+            .into_iter()
+            .map(|p| p.as_ref().trim_matches('/').to_string())
+            .filter(|p| !p.is_empty())
+            .collect();
+        Self { patterns }
+    }
+
+    pub fn patterns(&self) -> &[String] {
+        &self.patterns
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    /// Returns `true` if `rel_path` (relative to the search root) matches
+    /// any pattern.
+    pub fn is_excluded(&self, rel_path: &Path) -> bool {
+        if self.patterns.is_empty() {
+            return false;
+        }
+        let name = rel_path
+            .file_name()
+            .map(|n| n.to_string_lossy())
+            .unwrap_or_default();
+        let full = rel_path
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+
+        self.patterns.iter().any(|pattern| {
+            if pattern.contains('/') {
+                glob_match(pattern, &full)
+            } else {
+                glob_match(pattern, &name)
+            }
+        })
+    }
+}
+
+/// Matches `text` against a glob with `*` (any run of non-`/` characters)
+/// and `?` (any single non-`/` character).
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let p: Vec<char> = pattern.chars().collect();
+    let t: Vec<char> = text.chars().collect();
+    let (mut pi, mut ti) = (0, 0);
+    // Position of the last `*` in the pattern and the text index it absorbed up to.
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while ti < t.len() {
+        match p.get(pi) {
+            Some('*') => {
+                backtrack = Some((pi, ti));
+                pi += 1;
+            }
+            Some('?') if t[ti] != '/' => {
+                pi += 1;
+                ti += 1;
+            }
+            Some(&c) if c == t[ti] => {
+                pi += 1;
+                ti += 1;
+            }
+            _ => match backtrack {
+                // Let the last `*` swallow one more character, unless it's a separator.
+                Some((star_pi, star_ti)) if t[star_ti] != '/' => {
+                    backtrack = Some((star_pi, star_ti + 1));
+                    pi = star_pi + 1;
+                    ti = star_ti + 1;
+                }
+                _ => return false,
+            },
+        }
+    }
+
+    p[pi..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_name_matches_anywhere_in_tree() {
+        let set = ExcludeSet::new(["node_modules"]);
+        assert!(set.is_excluded(Path::new("node_modules")));
+        assert!(set.is_excluded(Path::new("web/node_modules")));
+        assert!(!set.is_excluded(Path::new("node_modules_old")));
+        assert!(!set.is_excluded(Path::new("src/main.rs")));
+    }
+
+    #[test]
+    fn wildcards_match_file_names() {
+        let set = ExcludeSet::new(["*.min.js", "build?"]);
+        assert!(set.is_excluded(Path::new("dist/app.min.js")));
+        assert!(!set.is_excluded(Path::new("dist/app.js")));
+        assert!(set.is_excluded(Path::new("build1")));
+        assert!(!set.is_excluded(Path::new("build")));
+    }
+
+    #[test]
+    fn slash_patterns_match_relative_path() {
+        let set = ExcludeSet::new(["docs/gen*"]);
+        assert!(set.is_excluded(Path::new("docs/generated")));
+        assert!(!set.is_excluded(Path::new("other/docs/generated")));
+        assert!(!set.is_excluded(Path::new("generated")));
+    }
+
+    #[test]
+    fn star_does_not_cross_separator() {
+        assert!(glob_match("a*c", "abbc"));
+        assert!(!glob_match("a*c", "ab/c"));
+        assert!(glob_match("a/*", "a/b"));
+        assert!(!glob_match("a/*", "a/b/c"));
+    }
+
+    #[test]
+    fn empty_set_excludes_nothing() {
+        let set = ExcludeSet::new(["", "/"]);
+        assert!(set.is_empty());
+        assert!(!set.is_excluded(Path::new("anything")));
+    }
+}
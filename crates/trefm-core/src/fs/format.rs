@@ -0,0 +1,133 @@
+//! Human-readable file sizes and paths.
+//!
+//! [`human_size`] prints sizes in either binary (IEC: KiB, MiB, … in steps
+//! of 1024) or decimal (SI: KB, MB, … in steps of 1000) units, chosen by
+//! the `ui.size_units` setting. Sizes below one unit are exact byte counts;
+//! larger ones have one decimal place.
+//!
+//! [`relative_display_path`] shortens a path in a result list to the part
+//! below the list's root.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+/// Unit system for [`human_size`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SizeUnits {
+    /// Powers of 1024: KiB, MiB, GiB, TiB.
+    #[default]
+    Iec,
+    /// Powers of 1000: KB, MB, GB, TB.
+    Si,
+}
+
+impl SizeUnits {
+    fn base(self) -> f64 {
+        match self {
+            Self::Iec => 1024.0,
+            Self::Si => 1000.0,
+        }
+    }
+
+    fn labels(self) -> [&'static str; 4] {
+        match self {
+            Self::Iec => ["KiB", "MiB", "GiB", "TiB"],
+            Self::Si => ["KB", "MB", "GB", "TB"],
+        }
+    }
+}
+
+/// Formats `bytes` as e.g. `512 B`, `1.5 KiB` or `2.0 GB`.
+pub fn human_size(bytes: u64, units: SizeUnits) -> String {
+    let base = units.base();
+    if (bytes as f64) < base {
+        return format!("{bytes} B");
+    }
+    let labels = units.labels();
+    let mut value = bytes as f64 / base;
+    let mut label = labels[0];
+    for next in &labels[1..] {
+        if value < base {
+            break;
+        }
+        value /= base;
+        label = next;
+    }
+    format!("{value:.1} {label}")
+}
+
+/// `path` relative to `root` when it lies under it, otherwise the whole
+/// `path`. `root` itself is shown as `.`.
+pub fn relative_display_path(path: &Path, root: &Path) -> String {
+    match path.strip_prefix(root) {
+        Ok(rel) if rel.as_os_str().is_empty() => ".".to_string(),
+        Ok(rel) => rel.display().to_string(),
+        Err(_) => path.display().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bytes_below_one_unit_are_exact() {
+        assert_eq!(human_size(0, SizeUnits::Iec), "0 B");
+        assert_eq!(human_size(1023, SizeUnits::Iec), "1023 B");
+        assert_eq!(human_size(999, SizeUnits::Si), "999 B");
+    }
+
+    #[test]
+    fn iec_boundaries() {
+        assert_eq!(human_size(1000, SizeUnits::Iec), "1000 B");
+        assert_eq!(human_size(1024, SizeUnits::Iec), "1.0 KiB");
+        assert_eq!(human_size(1536, SizeUnits::Iec), "1.5 KiB");
+        assert_eq!(human_size(1024 * 1024, SizeUnits::Iec), "1.0 MiB");
+        assert_eq!(
+            human_size(5 * 1024 * 1024 * 1024, SizeUnits::Iec),
+            "5.0 GiB"
+        );
+    }
+
+    #[test]
+    fn si_boundaries() {
+        assert_eq!(human_size(1000, SizeUnits::Si), "1.0 KB");
+        assert_eq!(human_size(1023, SizeUnits::Si), "1.0 KB");
+        assert_eq!(human_size(1024, SizeUnits::Si), "1.0 KB");
+        assert_eq!(human_size(1_500_000, SizeUnits::Si), "1.5 MB");
+        assert_eq!(human_size(2_000_000_000, SizeUnits::Si), "2.0 GB");
+    }
+
+    #[test]
+    fn nested_path_is_relative_to_root() {
+        let root = Path::new("/home/me/project");
+        assert_eq!(
+            relative_display_path(Path::new("/home/me/project/src/a/main.rs"), root),
+            "src/a/main.rs"
+        );
+        assert_eq!(relative_display_path(root, root), ".");
+    }
+
+    #[test]
+    fn path_outside_root_stays_absolute() {
+        let root = Path::new("/home/me/project");
+        assert_eq!(
+            relative_display_path(Path::new("/home/me/projects/x.txt"), root),
+            "/home/me/projects/x.txt"
+        );
+        assert_eq!(
+            relative_display_path(Path::new("/etc/hosts"), root),
+            "/etc/hosts"
+        );
+    }
+
+    #[test]
+    fn largest_unit_keeps_growing() {
+        assert_eq!(
+            human_size(3 * 1024_u64.pow(5), SizeUnits::Iec),
+            "3072.0 TiB"
+        );
+    }
+}
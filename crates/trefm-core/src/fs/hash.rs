@@ -0,0 +1,177 @@
+//! Content hashing for duplicate detection.
+//!
+//! Digests are tagged with the algorithm that produced them
+//! (`sha256:…`, `blake3:…`, `xxh3:…`) so results from different
+//! algorithms are never compared with each other. An untagged digest is a
+//! SHA-256 hex string from a cache written before tagging existed.
+
+use std::fmt;
+use std::io::Write;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::error::CoreResult;
+
+/// Hash algorithm used to compare file contents.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HashAlgo {
+    /// Cryptographic, slowest; the historical default.
+    #[default]
+    Sha256,
+    /// Cryptographic and much faster than SHA-256.
+    Blake3,
+    /// Non-cryptographic 128-bit XXH3; fastest.
+    Xxh3,
+}
+
+impl HashAlgo {
+    pub const ALL: [HashAlgo; 3] = [HashAlgo::Sha256, HashAlgo::Blake3, HashAlgo::Xxh3];
+
+    /// Tag used in digests and config files.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Sha256 => "sha256",
+            Self::Blake3 => "blake3",
+            Self::Xxh3 => "xxh3",
+        }
+    }
+
+    /// Parses an algorithm name as written in config (`sha256`, `blake3`, `xxh3`).
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|a| a.name() == name)
+    }
+
+    /// Returns the algorithm that produced a tagged digest. Untagged digests
+    /// are treated as SHA-256.
+    pub fn of_digest(digest: &str) -> Option<Self> {
+        match digest.split_once(':') {
+            Some((tag, _)) => Self::from_name(tag),
+            None => Some(Self::Sha256),
+        }
+    }
+
+    /// Hashes `bytes` and returns a tagged hex digest.
+    pub fn digest(self, bytes: &[u8]) -> String {
+        let hex = match self {
+            Self::Sha256 => format!("{:x}", Sha256::digest(bytes)),
+            Self::Blake3 => blake3::hash(bytes).to_hex().to_string(),
+            Self::Xxh3 => format!("{:032x}", xxhash_rust::xxh3::xxh3_128(bytes)),
+        };
+        format!("{}:{hex}", self.name())
+    }
+
+    /// Streams the file at `path` through the hash and returns its tagged
+    /// digest. Memory use does not grow with the file size.
+    pub fn hash_file(self, path: &Path) -> CoreResult<String> {
+        let mut file = std::fs::File::open(path)?;
+        let mut hasher = StreamHasher::new(self);
+        std::io::copy(&mut file, &mut hasher)?;
+        Ok(hasher.finish())
+    }
+}
+
+/// Incremental form of [`HashAlgo::digest`], fed through [`Write`].
+enum StreamHasher {
+    Sha256(Sha256),
+    Blake3(Box<blake3::Hasher>),
+    Xxh3(Box<xxhash_rust::xxh3::Xxh3>),
+}
+
+impl StreamHasher {
+    fn new(algo: HashAlgo) -> Self {
+        match algo {
+            HashAlgo::Sha256 => Self::Sha256(Sha256::new()),
+            HashAlgo::Blake3 => Self::Blake3(Box::default()),
+            HashAlgo::Xxh3 => Self::Xxh3(Box::default()),
+        }
+    }
+
+    /// The tagged hex digest of everything written so far.
+    fn finish(self) -> String {
+        let (algo, hex) = match self {
+            Self::Sha256(h) => (HashAlgo::Sha256, format!("{:x}", h.finalize())),
+            Self::Blake3(h) => (HashAlgo::Blake3, h.finalize().to_hex().to_string()),
+            Self::Xxh3(h) => (HashAlgo::Xxh3, format!("{:032x}", h.digest128())),
+        };
+        format!("{}:{hex}", algo.name())
+    }
+}
+
+impl Write for StreamHasher {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self {
+            Self::Sha256(h) => h.update(buf),
+            Self::Blake3(h) => {
+                h.update(buf);
+            }
+            Self::Xxh3(h) => h.update(buf),
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl fmt::Display for HashAlgo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn digests_are_tagged_with_algo() {
+        assert!(HashAlgo::Sha256.digest(b"x").starts_with("sha256:"));
+        assert!(HashAlgo::Blake3.digest(b"x").starts_with("blake3:"));
+        assert!(HashAlgo::Xxh3.digest(b"x").starts_with("xxh3:"));
+    }
+
+    #[test]
+    fn sha256_digest_matches_known_value() {
+        assert_eq!(
+            HashAlgo::Sha256.digest(b"abc"),
+            "sha256:ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn streamed_file_hash_matches_digest() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let path = tmp.path().join("big.bin");
+        let bytes: Vec<u8> = (0..200_000u32).map(|i| (i % 251) as u8).collect();
+        std::fs::write(&path, &bytes).unwrap();
+
+        for algo in HashAlgo::ALL {
+            assert_eq!(
+                algo.hash_file(&path).unwrap(),
+                algo.digest(&bytes),
+                "{algo}"
+            );
+        }
+    }
+
+    #[test]
+    fn of_digest_reads_tag() {
+        for algo in HashAlgo::ALL {
+            assert_eq!(HashAlgo::of_digest(&algo.digest(b"data")), Some(algo));
+        }
+        assert_eq!(HashAlgo::of_digest("abc123"), Some(HashAlgo::Sha256));
+        assert_eq!(HashAlgo::of_digest("md5:abc"), None);
+    }
+
+    #[test]
+    fn from_name_round_trips() {
+        for algo in HashAlgo::ALL {
+            assert_eq!(HashAlgo::from_name(algo.name()), Some(algo));
+        }
+        assert_eq!(HashAlgo::from_name("crc32"), None);
+    }
+}
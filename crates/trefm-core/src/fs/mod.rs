@@ -2,14 +2,44 @@
 //!
 //! This module provides the core types for representing file entries
 //! ([`entry::FileEntry`]) and performing directory reads ([`ops::read_directory`]),
-//! text file previews ([`preview::TextPreview`]), and directory tree snapshots
-//! ([`preview::TreeEntry`]).
+//! text file previews ([`preview::TextPreview`]), line diffs
+//! ([`diff::text_diff`]), directory tree snapshots
+//! ([`preview::TreeEntry`]), recursive content search
+//! ([`search::search_file_contents`]), network mount detection
+//! ([`mount::MountTable`]), file categories ([`category::FileCategory`]), and
+//! human-readable sizes ([`format::human_size`]).
 
+pub mod category;
+pub mod command;
+pub mod conflict;
+pub mod diff;
 pub mod duplicates;
 pub mod entry;
+pub mod exclude;
+pub mod format;
+pub mod hash;
+pub mod mount;
 pub mod ops;
 pub mod preview;
+pub mod search;
+mod size_buckets;
 
-pub use duplicates::{CachedDuplicateGroup, CachedFileInfo, DuplicateCache};
-pub use ops::DuplicateGroup;
-pub use preview::{ImageInfo, TextPreview, TreeEntry};
+pub use category::{categorize, FileCategory};
+pub use command::{
+    executable_argv, expand_template, is_executable, run_command, split_args, CommandOutput,
+};
+pub use conflict::{ConflictChoice, ConflictPolicy, PasteMode, PasteQueue, PasteStep};
+pub use diff::{file_diff, side_by_side, text_diff, DiffCell, DiffLine, DiffRow};
+pub use duplicates::{
+    scan_duplicates, CachedDuplicateGroup, CachedFileInfo, DuplicateCache, DuplicateIgnoreList,
+    DuplicateSort, DEFAULT_SCAN_EXCLUSIONS,
+};
+pub use exclude::ExcludeSet;
+pub use format::{human_size, relative_display_path, SizeUnits};
+pub use hash::HashAlgo;
+pub use mount::MountTable;
+pub use ops::{DuplicateGroup, DuplicateScanOptions};
+pub use preview::{tree_to_text, ImageInfo, TextPreview, TreeEntry, TreeIndent};
+pub use search::{
+    search_file_names, ContentMatch, ContentSearchOptions, ContentSearchResult, SearchProgress,
+};
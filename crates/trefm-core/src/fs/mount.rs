@@ -0,0 +1,224 @@
+//! Which filesystem a path lives on.
+//!
+//! A [`MountTable`] is a snapshot of the system's mounts: `/proc/mounts` on
+//! Linux, the output of `mount` on other Unix systems. Each path belongs to
+//! the mount with the longest matching mount point. Filesystems listed in
+//! [`NETWORK_FS_TYPES`] (NFS, SMB, sshfs, …) count as network mounts, which
+//! the duplicate scanner can skip because hashing over the network is slow.
+//! Where no mount list is available the table is empty and nothing is
+//! classified as a network mount.
+
+use std::path::{Path, PathBuf};
+
+/// Filesystem types reached over the network. FUSE types are matched on
+/// the part after `fuse.` (`fuse.sshfs` → `sshfs`).
+pub const NETWORK_FS_TYPES: &[&str] = &[
+    "nfs",
+    "nfs4",
+    "cifs",
+    "smbfs",
+    "smb3",
+    "afpfs",
+    "webdav",
+    "davfs",
+    "sshfs",
+    "rclone",
+    "9p",
+    "ncpfs",
+    "afs",
+    "ceph",
+    "glusterfs",
+];
+
+/// A mount point and the type of filesystem mounted there.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mount {
+    pub mount_point: PathBuf,
+    pub fs_type: String,
+}
+
+impl Mount {
+    /// Whether [`NETWORK_FS_TYPES`] lists this mount's filesystem type.
+    pub fn is_network(&self) -> bool {
+        let fs_type = self.fs_type.strip_prefix("fuse.").unwrap_or(&self.fs_type);
+        NETWORK_FS_TYPES.contains(&fs_type)
+    }
+}
+
+/// The system's mounts at the time [`MountTable::load`] ran.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MountTable {
+    mounts: Vec<Mount>,
+}
+
+impl MountTable {
+    /// Reads the current mount list; an unreadable list yields an empty table.
+    pub fn load() -> Self {
+        #[cfg(target_os = "linux")]
+        {
+            std::fs::read_to_string("/proc/mounts")
+                .map(|content| Self::parse_proc_mounts(&content))
+                .unwrap_or_default()
+        }
+        #[cfg(all(unix, not(target_os = "linux")))]
+        {
+            std::process::Command::new("mount")
+                .output()
+                .map(|out| Self::parse_mount_output(&String::from_utf8_lossy(&out.stdout)))
+                .unwrap_or_default()
+        }
+        #[cfg(not(unix))]
+        {
+            Self::default()
+        }
+    }
+
+    /// Parses the Linux `/proc/mounts` format:
+    /// `device mount_point fs_type options dump pass`, with spaces in paths
+    /// written as `\040`.
+    pub fn parse_proc_mounts(content: &str) -> Self {
+        let mounts = content
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.split_whitespace();
+                let _device = fields.next()?;
+                let mount_point = unescape_octal(fields.next()?);
+                let fs_type = fields.next()?.to_string();
+                Some(Mount {
+                    mount_point: PathBuf::from(mount_point),
+                    fs_type,
+                })
+            })
+            .collect();
+        Self { mounts }
+    }
+
+    /// Parses BSD/macOS `mount` output:
+    /// `device on /mount/point (fs_type, options…)`.
+    pub fn parse_mount_output(content: &str) -> Self {
+        let mounts = content
+            .lines()
+            .filter_map(|line| {
+                let (_device, rest) = line.split_once(" on ")?;
+                let (mount_point, rest) = rest.rsplit_once(" (")?;
+                let fs_type = rest.split([',', ')']).next()?.trim().to_string();
+                Some(Mount {
+                    mount_point: PathBuf::from(mount_point),
+                    fs_type,
+                })
+            })
+            .collect();
+        Self { mounts }
+    }
+
+    pub fn mounts(&self) -> &[Mount] {
+        &self.mounts
+    }
+
+    /// The mount `path` lives on: the one with the longest mount point that
+    /// `path` starts with. `path` should be absolute and canonical.
+    pub fn mount_for(&self, path: &Path) -> Option<&Mount> {
+        self.mounts
+            .iter()
+            .filter(|m| path.starts_with(&m.mount_point))
+            .max_by_key(|m| m.mount_point.components().count())
+    }
+
+    /// Whether `path` lives on a network filesystem.
+    pub fn is_network(&self, path: &Path) -> bool {
+        self.mount_for(path).is_some_and(Mount::is_network)
+    }
+
+    /// Mount points of every network filesystem, for skipping in scans.
+    pub fn network_mount_points(&self) -> Vec<PathBuf> {
+        self.mounts
+            .iter()
+            .filter(|m| m.is_network())
+            .map(|m| m.mount_point.clone())
+            .collect()
+    }
+}
+
+/// Decodes the `\ooo` escapes `/proc/mounts` uses for spaces, tabs and backslashes.
+fn unescape_octal(field: &str) -> String {
+    let bytes = field.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escape = bytes.get(i + 1..i + 4).filter(|_| bytes[i] == b'\\');
+        match escape
+            .and_then(|digits| std::str::from_utf8(digits).ok())
+            .and_then(|digits| u8::from_str_radix(digits, 8).ok())
+        {
+            Some(byte) => {
+                out.push(byte);
+                i += 4;
+            }
+            None => {
+                out.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROC_MOUNTS: &str = "\
+/dev/sda1 / ext4 rw,relatime 0 0
+proc /proc proc rw,nosuid 0 0
+server:/export /mnt/nfs nfs4 rw,vers=4.2 0 0
+//nas/share /mnt/My\\040Share cifs rw 0 0
+user@host:/ /home/me/remote fuse.sshfs rw 0 0
+";
+
+    #[test]
+    fn proc_mounts_are_classified() {
+        let table = MountTable::parse_proc_mounts(PROC_MOUNTS);
+
+        assert!(!table.is_network(Path::new("/home/me/notes.txt")));
+        assert!(table.is_network(Path::new("/mnt/nfs/photos")));
+        assert!(table.is_network(Path::new("/mnt/My Share/doc.pdf")));
+        assert!(table.is_network(Path::new("/home/me/remote/src")));
+        assert_eq!(
+            table.network_mount_points(),
+            vec![
+                PathBuf::from("/mnt/nfs"),
+                PathBuf::from("/mnt/My Share"),
+                PathBuf::from("/home/me/remote"),
+            ]
+        );
+    }
+
+    #[test]
+    fn longest_mount_point_wins() {
+        let table = MountTable::parse_proc_mounts(PROC_MOUNTS);
+        let mount = table.mount_for(Path::new("/mnt/nfs/a")).unwrap();
+        assert_eq!(mount.fs_type, "nfs4");
+        let mount = table.mount_for(Path::new("/mnt/nfsother")).unwrap();
+        assert_eq!(mount.fs_type, "ext4");
+    }
+
+    #[test]
+    fn bsd_mount_output_is_parsed() {
+        let output = "\
+/dev/disk3s1s1 on / (apfs, sealed, local, read-only, journaled)
+//me@nas._smb._tcp.local/Media on /Volumes/Media Files (smbfs, nodev, nosuid, mounted by me)
+";
+        let table = MountTable::parse_mount_output(output);
+
+        assert!(!table.is_network(Path::new("/Users/me")));
+        assert!(table.is_network(Path::new("/Volumes/Media Files/movie.mkv")));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn temp_dir_is_not_a_network_mount() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let path = tmp.path().canonicalize().unwrap();
+        assert!(!MountTable::load().is_network(&path));
+    }
+}
@@ -1,24 +1,40 @@
 //! Directory reading operations.
 
-use std::collections::{HashMap, HashSet};
-use std::path::Path;
-
-use sha2::{Digest, Sha256};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashSet};
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 use crate::error::{CoreError, CoreResult};
 use crate::fs::entry::FileEntry;
+use crate::fs::hash::HashAlgo;
+use crate::fs::size_buckets::SizeBuckets;
 
 /// A group of files with identical content.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
 pub struct DuplicateGroup {
     /// File size in bytes (shared by all files in the group).
     pub size: u64,
-    /// SHA-256 hex digest of the file content.
+    /// Hex digest of the file content, tagged with its algorithm (`sha256:…`).
     pub hash: String,
     /// Files that share the same content (always 2+).
     pub files: Vec<FileEntry>,
 }
 
+/// Counts the entries directly in `path`, stopping at `limit`.
+///
+/// Only the directory listing is read, not each entry's metadata, so this
+/// is cheap enough to run before deciding whether to [`read_directory`].
+///
+/// # Errors
+///
+/// - [`CoreError::Io`] — the directory cannot be read.
+pub fn count_entries(path: &Path, limit: usize) -> CoreResult<usize> {
+    Ok(std::fs::read_dir(path)?.take(limit).count())
+}
+
 /// Reads the immediate contents of a directory and returns them as [`FileEntry`] values.
 ///
 /// The returned entries are **unsorted**. Use [`crate::nav::filter::sort_entries`]
@@ -65,26 +81,69 @@ pub fn read_directory(path: &Path) -> CoreResult<Vec<FileEntry>> {
             Ok(e) => e,
             Err(_) => continue,
         };
-        let metadata = match dir_entry.metadata() {
-            Ok(m) => m,
+        match FileEntry::from_dir_entry(&dir_entry) {
+            Ok(entry) => entries.push(entry),
             Err(_) => continue,
-        };
-        entries.push(FileEntry::new(dir_entry.path(), &metadata));
+        }
     }
 
     Ok(entries)
 }
 
+/// Which source attributes [`copy_file_with_attrs`] carries over to the copy.
+///
+/// With every flag off the copy gets fresh timestamps and the default
+/// permissions for newly created files.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CopyAttrs {
+    /// Keep the modification (and access) time.
+    pub mtime: bool,
+    /// Keep the permission bits.
+    pub mode: bool,
+    /// Keep the owner and group. Silently skipped when not permitted
+    /// (e.g. for non-root users on most Unix systems).
+    pub ownership: bool,
+}
+
+impl CopyAttrs {
+    /// Preserve everything that can be preserved.
+    pub const ALL: Self = Self {
+        mtime: true,
+        mode: true,
+        ownership: true,
+    };
+}
+
 /// Copies a file or directory recursively to the destination path.
 ///
 /// If `src` is a file, it is copied directly. If `src` is a directory,
-/// it is copied recursively including all contents.
+/// it is copied recursively including all contents. File permissions are
+/// kept (as with `std::fs::copy`); timestamps are not — use
+/// [`copy_file_with_attrs`] for that.
 ///
 /// # Errors
 ///
 /// - [`CoreError::NotFound`] if `src` does not exist.
 /// - [`CoreError::Io`] for any I/O failure during copy.
 pub fn copy_file(src: &Path, dest: &Path) -> CoreResult<()> {
+    copy_entry(src, dest, None)
+}
+
+/// Like [`copy_file`], but applies the requested [`CopyAttrs`] to every
+/// copied file and directory. Directory attributes are applied after their
+/// contents are copied so the preserved mtime sticks. Symlinks are copied
+/// as links and left untouched.
+///
+/// # Errors
+///
+/// - [`CoreError::NotFound`] if `src` does not exist.
+/// - [`CoreError::Io`] for any I/O failure during copy or while applying
+///   timestamps/permissions.
+pub fn copy_file_with_attrs(src: &Path, dest: &Path, attrs: CopyAttrs) -> CoreResult<()> {
+    copy_entry(src, dest, Some(attrs))
+}
+
+fn copy_entry(src: &Path, dest: &Path, attrs: Option<CopyAttrs>) -> CoreResult<()> {
     let meta = std::fs::symlink_metadata(src).map_err(|e| {
         if e.kind() == std::io::ErrorKind::NotFound {
             CoreError::NotFound(src.to_path_buf())
@@ -94,7 +153,7 @@ pub fn copy_file(src: &Path, dest: &Path) -> CoreResult<()> {
     })?;
 
     if meta.is_dir() {
-        copy_dir_recursive(src, dest, 0)?;
+        copy_dir_recursive(src, dest, 0, attrs)?;
     } else {
         if let Some(parent) = dest.parent() {
             std::fs::create_dir_all(parent)?;
@@ -107,22 +166,188 @@ pub fn copy_file(src: &Path, dest: &Path) -> CoreResult<()> {
             #[cfg(not(unix))]
             std::fs::copy(src, dest)?;
         } else {
+            copy_regular_file(src, dest, &meta, attrs)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Copies file contents, then applies `attrs` if given.
+fn copy_regular_file(
+    src: &Path,
+    dest: &Path,
+    meta: &std::fs::Metadata,
+    attrs: Option<CopyAttrs>,
+) -> CoreResult<()> {
+    match attrs {
+        Some(a) if !a.mode => {
+            // std::fs::copy always copies permissions; stream the contents
+            // into a freshly created file instead so it gets default ones.
+            let mut reader = std::fs::File::open(src)?;
+            let mut writer = std::fs::File::create(dest)?;
+            std::io::copy(&mut reader, &mut writer)?;
+        }
+        _ => {
             std::fs::copy(src, dest)?;
         }
     }
+    if let Some(a) = attrs {
+        apply_attrs(dest, meta, a)?;
+    }
+    Ok(())
+}
 
+/// Applies the requested source attributes to an already-copied path.
+fn apply_attrs(dest: &Path, src_meta: &std::fs::Metadata, attrs: CopyAttrs) -> CoreResult<()> {
+    #[cfg(unix)]
+    if attrs.ownership {
+        use std::os::unix::fs::MetadataExt;
+        match std::os::unix::fs::chown(dest, Some(src_meta.uid()), Some(src_meta.gid())) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied => {}
+            Err(e) => return Err(CoreError::Io(e)),
+        }
+    }
+    if attrs.mtime {
+        // Not File::set_times: Windows needs a writable handle for that,
+        // which a directory or a read-only copy cannot give.
+        filetime::set_file_times(
+            dest,
+            filetime::FileTime::from_last_access_time(src_meta),
+            filetime::FileTime::from_last_modification_time(src_meta),
+        )?;
+    }
+    // Permissions last: a read-only mode would otherwise block the steps above.
+    if attrs.mode {
+        std::fs::set_permissions(dest, src_meta.permissions())?;
+    }
     Ok(())
 }
 
+/// Copies `src` to `dest`, refusing to clobber an existing destination.
+///
+/// Unlike [`copy_file`], an existing `dest` is never merged into: with
+/// `overwrite == false` the call fails, and with `overwrite == true` the copy
+/// is made next to `dest` first and only then takes its place, so a failed
+/// copy leaves the old destination (file or whole directory) as it was.
+///
+/// # Errors
+///
+/// - [`CoreError::AlreadyExists`] if `dest` exists and `overwrite` is false,
+///   or if `src` and `dest` are the same entry.
+/// - [`CoreError::InvalidName`] if one of `src` and an existing `dest` is
+///   inside the other.
+/// - Any error from [`copy_file`] or [`delete_file`].
+pub fn copy_file_checked(src: &Path, dest: &Path, overwrite: bool) -> CoreResult<()> {
+    if !prepare_destination(src, dest, overwrite)? {
+        return copy_file(src, dest);
+    }
+    let staged = temp_sibling(dest);
+    if let Err(e) = copy_file(src, &staged) {
+        let _ = delete_file(&staged);
+        return Err(e);
+    }
+    replace_with(dest, || Ok(std::fs::rename(&staged, dest)?)).inspect_err(|_| {
+        let _ = delete_file(&staged);
+    })
+}
+
+/// Moves `src` to `dest` with the same overwrite rules as [`copy_file_checked`].
+///
+/// # Errors
+///
+/// - [`CoreError::AlreadyExists`] if `dest` exists and `overwrite` is false,
+///   or if `src` and `dest` are the same entry.
+/// - [`CoreError::InvalidName`] if one of `src` and an existing `dest` is
+///   inside the other.
+/// - Any error from [`move_file`] or [`delete_file`].
+pub fn move_file_checked(src: &Path, dest: &Path, overwrite: bool) -> CoreResult<()> {
+    if !prepare_destination(src, dest, overwrite)? {
+        return move_file(src, dest);
+    }
+    replace_with(dest, || move_file(src, dest))
+}
+
+/// Checks that `src` may go to `dest`; returns whether an existing `dest`
+/// has to be replaced.
+fn prepare_destination(src: &Path, dest: &Path, overwrite: bool) -> CoreResult<bool> {
+    if std::fs::symlink_metadata(src).is_err() {
+        return Err(CoreError::NotFound(src.to_path_buf()));
+    }
+    if std::fs::symlink_metadata(dest).is_err() {
+        return Ok(false);
+    }
+    // Compare real locations so `./a` and `a`, or a symlinked parent, match.
+    let src_real = canonical_entry(src)?;
+    let dest_real = canonical_entry(dest)?;
+    if !overwrite || src_real == dest_real {
+        return Err(CoreError::AlreadyExists(dest.to_path_buf()));
+    }
+    if src_real.starts_with(&dest_real) || dest_real.starts_with(&src_real) {
+        return Err(CoreError::InvalidName(format!(
+            "cannot replace {} with {}: one is inside the other",
+            dest.display(),
+            src.display()
+        )));
+    }
+    Ok(true)
+}
+
+/// `path` with its parent directory resolved; the entry itself (which may
+/// be a symlink) is not followed.
+fn canonical_entry(path: &Path) -> CoreResult<PathBuf> {
+    let name = path
+        .file_name()
+        .ok_or_else(|| CoreError::InvalidName(path.display().to_string()))?;
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    Ok(std::fs::canonicalize(parent)?.join(name))
+}
+
+/// A hidden name next to `path` that does not exist yet.
+fn temp_sibling(path: &Path) -> PathBuf {
+    let parent = path.parent().unwrap_or_else(|| Path::new(""));
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    (0..)
+        .map(|n| parent.join(format!(".{name}.trefm-tmp{n}")))
+        .find(|candidate| std::fs::symlink_metadata(candidate).is_err())
+        .expect("unbounded iterator always yields a free name")
+}
+
+/// Sets the existing `dest` aside, lets `place` put the new entry there and
+/// then deletes the old one. If `place` fails the old `dest` is put back.
+fn replace_with(dest: &Path, place: impl FnOnce() -> CoreResult<()>) -> CoreResult<()> {
+    let backup = temp_sibling(dest);
+    std::fs::rename(dest, &backup).map_err(|e| in_use_or_io(e, dest))?;
+    if let Err(e) = place() {
+        if std::fs::symlink_metadata(dest).is_ok() {
+            let _ = delete_file(dest);
+        }
+        let _ = std::fs::rename(&backup, dest);
+        return Err(e);
+    }
+    delete_file(&backup)
+}
+
 /// Maximum recursion depth for copy_dir_recursive to prevent symlink loops.
 const MAX_COPY_DEPTH: usize = 64;
 
-fn copy_dir_recursive(src: &Path, dest: &Path, depth: usize) -> CoreResult<()> {
+fn copy_dir_recursive(
+    src: &Path,
+    dest: &Path,
+    depth: usize,
+    attrs: Option<CopyAttrs>,
+) -> CoreResult<()> {
     if depth > MAX_COPY_DEPTH {
-        return Err(CoreError::Io(std::io::Error::new(
-            std::io::ErrorKind::Other,
-            format!("maximum recursion depth ({MAX_COPY_DEPTH}) exceeded during copy"),
-        )));
+        return Err(CoreError::Io(std::io::Error::other(format!(
+            "maximum recursion depth ({MAX_COPY_DEPTH}) exceeded during copy"
+        ))));
     }
 
     std::fs::create_dir_all(dest)?;
@@ -143,23 +368,55 @@ fn copy_dir_recursive(src: &Path, dest: &Path, depth: usize) -> CoreResult<()> {
             #[cfg(not(unix))]
             std::fs::copy(&entry_path, &target)?;
         } else if ft.is_dir() {
-            copy_dir_recursive(&entry_path, &target, depth + 1)?;
+            copy_dir_recursive(&entry_path, &target, depth + 1, attrs)?;
         } else {
-            std::fs::copy(&entry_path, &target)?;
+            copy_regular_file(&entry_path, &target, &entry.metadata()?, attrs)?;
         }
     }
 
+    if let Some(a) = attrs {
+        apply_attrs(dest, &std::fs::metadata(src)?, a)?;
+    }
+
     Ok(())
 }
 
+/// Windows `ERROR_SHARING_VIOLATION`: another process has the file open
+/// without sharing delete access.
+const ERROR_SHARING_VIOLATION: i32 = 32;
+/// Windows `ERROR_LOCK_VIOLATION`: another process has locked part of the file.
+const ERROR_LOCK_VIOLATION: i32 = 33;
+
+/// Whether `e` carries one of the Windows error codes for a file that is
+/// open or locked elsewhere. The codes mean something else on Unix (32 is
+/// `EPIPE`), so callers check `cfg!(windows)` first.
+fn is_sharing_violation(e: &std::io::Error) -> bool {
+    matches!(
+        e.raw_os_error(),
+        Some(ERROR_SHARING_VIOLATION | ERROR_LOCK_VIOLATION)
+    )
+}
+
+/// `e` as [`CoreError::FileInUse`] for `path` if it is a Windows sharing
+/// violation, otherwise as [`CoreError::Io`].
+fn in_use_or_io(e: std::io::Error, path: &Path) -> CoreError {
+    if cfg!(windows) && is_sharing_violation(&e) {
+        CoreError::FileInUse(path.to_path_buf())
+    } else {
+        CoreError::Io(e)
+    }
+}
+
 /// Moves a file or directory to a new location.
 ///
 /// Attempts a fast `rename` first. If rename fails (e.g. cross-device),
-/// falls back to copy + delete.
+/// falls back to copy + delete. A file another program holds open on
+/// Windows is reported without attempting the copy.
 ///
 /// # Errors
 ///
 /// - [`CoreError::NotFound`] if `src` does not exist.
+/// - [`CoreError::FileInUse`] if Windows refuses because `src` is open.
 /// - [`CoreError::Io`] for any I/O failure.
 pub fn move_file(src: &Path, dest: &Path) -> CoreResult<()> {
     // Use symlink_metadata to avoid TOCTOU and handle symlinks correctly
@@ -169,6 +426,9 @@ pub fn move_file(src: &Path, dest: &Path) -> CoreResult<()> {
 
     match std::fs::rename(src, dest) {
         Ok(()) => Ok(()),
+        Err(e) if cfg!(windows) && is_sharing_violation(&e) => {
+            Err(CoreError::FileInUse(src.to_path_buf()))
+        }
         Err(_) => {
             copy_file(src, dest)?;
             delete_file(src)?;
@@ -182,6 +442,7 @@ pub fn move_file(src: &Path, dest: &Path) -> CoreResult<()> {
 /// # Errors
 ///
 /// - [`CoreError::NotFound`] if `path` does not exist.
+/// - [`CoreError::FileInUse`] if Windows refuses because the file is open.
 /// - [`CoreError::Io`] for any I/O failure during deletion.
 pub fn delete_file(path: &Path) -> CoreResult<()> {
     // Use symlink_metadata: does NOT follow symlinks, avoids TOCTOU
@@ -193,14 +454,13 @@ pub fn delete_file(path: &Path) -> CoreResult<()> {
         }
     })?;
 
-    if meta.is_dir() {
-        std::fs::remove_dir_all(path)?;
+    let removed = if meta.is_dir() {
+        std::fs::remove_dir_all(path)
     } else {
         // Handles both regular files and symlinks
-        std::fs::remove_file(path)?;
-    }
-
-    Ok(())
+        std::fs::remove_file(path)
+    };
+    removed.map_err(|e| in_use_or_io(e, path))
 }
 
 /// Recursively finds recently modified files under `path`, sorted newest-first.
@@ -313,6 +573,98 @@ pub fn rename_file(path: &Path, new_name: &str) -> CoreResult<()> {
     Ok(())
 }
 
+/// Renames like [`rename_file`], but refuses to replace an existing entry
+/// unless `overwrite` is true, in which case that entry is deleted first.
+///
+/// A new name that still refers to `path` itself — a change of case on a
+/// case-insensitive filesystem, for instance — is not a conflict.
+///
+/// # Errors
+///
+/// - [`CoreError::AlreadyExists`] if the new name is taken and `overwrite` is false.
+/// - Any error from [`rename_file`] or [`delete_file`].
+pub fn rename_file_checked(path: &Path, new_name: &str, overwrite: bool) -> CoreResult<()> {
+    if std::fs::symlink_metadata(path).is_err() {
+        return Err(CoreError::NotFound(path.to_path_buf()));
+    }
+    if !is_valid_filename(new_name) {
+        return Err(CoreError::InvalidName(new_name.to_string()));
+    }
+    let dest = path.with_file_name(new_name);
+    if std::fs::symlink_metadata(&dest).is_ok() && !is_same_entry(path, &dest) {
+        if !overwrite {
+            return Err(CoreError::AlreadyExists(dest));
+        }
+        delete_file(&dest)?;
+    }
+    rename_file(path, new_name)
+}
+
+/// Copies a file or directory next to itself and returns the new path.
+///
+/// The copy takes the first free name of `name copy`, `name copy 2`,
+/// `name copy 3`, …; a file keeps its extension last (`report copy.txt`).
+///
+/// # Errors
+///
+/// - [`CoreError::NotFound`] if `path` does not exist.
+/// - [`CoreError::InvalidName`] if `path` has no parent or file name.
+/// - Any error from [`copy_file`].
+pub fn duplicate_in_place(path: &Path) -> CoreResult<PathBuf> {
+    let meta =
+        std::fs::symlink_metadata(path).map_err(|_| CoreError::NotFound(path.to_path_buf()))?;
+    let parent = path
+        .parent()
+        .ok_or_else(|| CoreError::InvalidName("no parent directory".to_string()))?;
+    let name = path
+        .file_name()
+        .ok_or_else(|| CoreError::InvalidName(path.display().to_string()))?
+        .to_string_lossy();
+    let mut attempt = 1;
+    let dest = loop {
+        let candidate = parent.join(copy_name(&name, meta.is_dir(), attempt));
+        if std::fs::symlink_metadata(&candidate).is_err() {
+            break candidate;
+        }
+        attempt += 1;
+    };
+    copy_file(path, &dest)?;
+    Ok(dest)
+}
+
+/// The `attempt`-th name tried for a copy of `name`: `name copy`, then
+/// `name copy 2` and so on. Unless `is_dir`, the extension stays last;
+/// dotfiles like `.bashrc` have none.
+fn copy_name(name: &str, is_dir: bool, attempt: usize) -> String {
+    let suffix = match attempt {
+        1 => " copy".to_string(),
+        n => format!(" copy {n}"),
+    };
+    let dot = name
+        .rfind('.')
+        .filter(|&dot| !is_dir && dot > 0 && dot + 1 < name.len());
+    match dot {
+        Some(dot) => format!("{}{suffix}{}", &name[..dot], &name[dot..]),
+        None => format!("{name}{suffix}"),
+    }
+}
+
+/// Whether `a` and `b` are names for the same directory entry.
+fn is_same_entry(a: &Path, b: &Path) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        match (std::fs::symlink_metadata(a), std::fs::symlink_metadata(b)) {
+            (Ok(a), Ok(b)) => a.dev() == b.dev() && a.ino() == b.ino(),
+            _ => false,
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        a == b
+    }
+}
+
 /// Maximum file size for duplicate detection (100 MB).
 const MAX_HASH_FILE_SIZE: u64 = 100 * 1024 * 1024;
 
@@ -321,8 +673,8 @@ const MAX_HASH_FILE_SIZE: u64 = 100 * 1024 * 1024;
 /// Walks directories up to `max_depth` levels deep. Files are grouped by
 /// content: first by size, then by SHA-256 hash. Only groups with two or
 /// more identical files are returned, sorted by file size descending (largest
-/// duplicates first). Files larger than 100 MB and unreadable files are
-/// silently skipped.
+/// duplicates first, then by hash). Files larger than 100 MB and unreadable
+/// files are silently skipped. Hashing uses one thread per CPU.
 ///
 /// # Errors
 ///
@@ -343,48 +695,7 @@ pub fn find_duplicate_files(
     let mut files = Vec::new();
     collect_files_recursive(path, max_depth, show_hidden, &mut files);
 
-    // Phase 1: Group by file size
-    let mut size_groups: HashMap<u64, Vec<FileEntry>> = HashMap::new();
-    for entry in files {
-        size_groups.entry(entry.size()).or_default().push(entry);
-    }
-
-    // Phase 2: For size groups with 2+ files, sub-group by hash
-    let mut duplicate_groups = Vec::new();
-
-    for (size, entries) in &size_groups {
-        if entries.len() < 2 || *size > MAX_HASH_FILE_SIZE {
-            continue;
-        }
-
-        let mut hash_groups: HashMap<String, Vec<FileEntry>> = HashMap::new();
-        for entry in entries {
-            match compute_file_hash(entry.path()) {
-                Ok(hash) => {
-                    hash_groups.entry(hash).or_default().push(entry.clone());
-                }
-                Err(_) => {
-                    tracing::warn!("failed to hash file: {}", entry.path().display());
-                }
-            }
-        }
-
-        // Phase 3: Keep only groups with 2+ files
-        for (hash, group_files) in hash_groups {
-            if group_files.len() >= 2 {
-                duplicate_groups.push(DuplicateGroup {
-                    size: *size,
-                    hash,
-                    files: group_files,
-                });
-            }
-        }
-    }
-
-    // Sort by size descending
-    duplicate_groups.sort_by(|a, b| b.size.cmp(&a.size));
-
-    Ok(duplicate_groups)
+    Ok(group_duplicates(files, HashAlgo::Sha256, 0))
 }
 
 /// Finds groups of duplicate files under `path`, skipping excluded directories.
@@ -397,6 +708,45 @@ pub fn find_duplicate_files_with_exclusions(
     max_depth: usize,
     show_hidden: bool,
     excluded_dirs: &HashSet<&str>,
+) -> CoreResult<Vec<DuplicateGroup>> {
+    find_duplicate_files_with_algo(
+        path,
+        max_depth,
+        show_hidden,
+        excluded_dirs,
+        &DuplicateScanOptions::default(),
+    )
+}
+
+/// Tuning knobs for [`find_duplicate_files_with_algo`] and
+/// [`scan_duplicates`](super::duplicates::scan_duplicates).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DuplicateScanOptions {
+    /// Directories at or below any of these (e.g. network mount points) are
+    /// not entered; if the scan root itself is below one, nothing is found.
+    pub excluded_roots: Vec<PathBuf>,
+    /// Algorithm used to compare contents. Group hashes are tagged with it.
+    pub algo: HashAlgo,
+    /// Number of hashing threads, `0` meaning one per CPU. The groups come
+    /// out the same whatever the thread count.
+    pub threads: usize,
+    /// Files smaller than this (bytes) are dropped before anything is compared.
+    pub min_size: u64,
+    /// Memory budget (bytes) for the size groups built during the walk. When
+    /// non-zero, files that are alone in their size spill to a temporary file
+    /// once the groups outgrow it; the result is the same either way.
+    pub max_memory: usize,
+}
+
+/// Like [`find_duplicate_files_with_exclusions`], tuned by `options`
+/// (hash algorithm, excluded roots, thread count, minimum size and memory
+/// budget).
+pub fn find_duplicate_files_with_algo(
+    path: &Path,
+    max_depth: usize,
+    show_hidden: bool,
+    excluded_dirs: &HashSet<&str>,
+    options: &DuplicateScanOptions,
 ) -> CoreResult<Vec<DuplicateGroup>> {
     if !path.exists() {
         return Err(CoreError::NotFound(path.to_path_buf()));
@@ -405,60 +755,137 @@ pub fn find_duplicate_files_with_exclusions(
         return Err(CoreError::NotADirectory(path.to_path_buf()));
     }
 
-    let mut files = Vec::new();
-    collect_files_with_exclusions(path, max_depth, show_hidden, excluded_dirs, &mut files);
+    let mut buckets = SizeBuckets::new(options.max_memory);
+    let exclusions = ScanExclusions {
+        dirs: excluded_dirs,
+        roots: &options.excluded_roots,
+    };
+    collect_files_with_exclusions(path, max_depth, show_hidden, &exclusions, &mut |entry| {
+        if entry.size() >= options.min_size {
+            buckets.push(entry);
+        }
+    });
+
+    Ok(group_sized(
+        buckets.into_groups(),
+        options.algo,
+        options.threads,
+    ))
+}
 
-    // Phase 1: Group by file size
-    let mut size_groups: HashMap<u64, Vec<FileEntry>> = HashMap::new();
+/// Groups files by size, then by content hash, keeping groups of 2+ files
+/// sorted by size descending (largest duplicates first), then by hash.
+/// Files in a group keep the order they were found in.
+fn group_duplicates(files: Vec<FileEntry>, algo: HashAlgo, threads: usize) -> Vec<DuplicateGroup> {
+    let mut size_groups: BTreeMap<u64, Vec<FileEntry>> = BTreeMap::new();
     for entry in files {
         size_groups.entry(entry.size()).or_default().push(entry);
     }
+    group_sized(size_groups, algo, threads)
+}
 
-    // Phase 2: For size groups with 2+ files, sub-group by hash
-    let mut duplicate_groups = Vec::new();
-
-    for (size, entries) in &size_groups {
-        if entries.len() < 2 || *size > MAX_HASH_FILE_SIZE {
-            continue;
+/// [`group_duplicates`] for files already grouped by size.
+fn group_sized(
+    size_groups: BTreeMap<u64, Vec<FileEntry>>,
+    algo: HashAlgo,
+    threads: usize,
+) -> Vec<DuplicateGroup> {
+    // Hash every file that shares its size with another one
+    let candidates: Vec<&FileEntry> = size_groups
+        .iter()
+        .filter(|(size, entries)| entries.len() >= 2 && **size <= MAX_HASH_FILE_SIZE)
+        .flat_map(|(_, entries)| entries)
+        .collect();
+    let hashes = hash_files(&candidates, algo, threads);
+
+    // Keep only groups with 2+ files
+    let mut hash_groups: BTreeMap<(Reverse<u64>, String), Vec<FileEntry>> = BTreeMap::new();
+    for (entry, hash) in candidates.into_iter().zip(hashes) {
+        if let Some(hash) = hash {
+            hash_groups
+                .entry((Reverse(entry.size()), hash))
+                .or_default()
+                .push(entry.clone());
         }
+    }
+    hash_groups
+        .into_iter()
+        .filter(|(_, files)| files.len() >= 2)
+        .map(|((Reverse(size), hash), files)| DuplicateGroup { size, hash, files })
+        .collect()
+}
 
-        let mut hash_groups: HashMap<String, Vec<FileEntry>> = HashMap::new();
-        for entry in entries {
-            match compute_file_hash(entry.path()) {
-                Ok(hash) => {
-                    hash_groups.entry(hash).or_default().push(entry.clone());
-                }
-                Err(_) => {
-                    tracing::warn!("failed to hash file: {}", entry.path().display());
-                }
-            }
-        }
+/// Number of hashing threads for `jobs` files when `threads` were asked
+/// for: `0` means one per CPU, and there are never more threads than files.
+fn hash_thread_count(threads: usize, jobs: usize) -> usize {
+    let threads = match threads {
+        0 => std::thread::available_parallelism().map_or(1, NonZeroUsize::get),
+        n => n,
+    };
+    threads.min(jobs).max(1)
+}
 
-        // Phase 3: Keep only groups with 2+ files
-        for (hash, group_files) in hash_groups {
-            if group_files.len() >= 2 {
-                duplicate_groups.push(DuplicateGroup {
-                    size: *size,
-                    hash,
-                    files: group_files,
-                });
+/// Hashes `files` on up to `threads` threads (see [`hash_thread_count`]).
+///
+/// Workers take the next file from a shared counter, so a few large files
+/// do not hold up the rest. The result is in the order of `files`; `None`
+/// marks a file that could not be read.
+fn hash_files(files: &[&FileEntry], algo: HashAlgo, threads: usize) -> Vec<Option<String>> {
+    let hash = |entry: &FileEntry| match algo.hash_file(entry.path()) {
+        Ok(hash) => Some(hash),
+        Err(_) => {
+            tracing::warn!("failed to hash file: {}", entry.path().display());
+            None
+        }
+    };
+    let threads = hash_thread_count(threads, files.len());
+    if threads == 1 {
+        return files.iter().map(|entry| hash(entry)).collect();
+    }
+
+    let next = AtomicUsize::new(0);
+    let mut hashes = vec![None; files.len()];
+    std::thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut done = Vec::new();
+                    loop {
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(entry) = files.get(index) else {
+                            break;
+                        };
+                        done.push((index, hash(entry)));
+                    }
+                    done
+                })
+            })
+            .collect();
+        for worker in workers {
+            for (index, hash) in worker.join().expect("hash worker panicked") {
+                hashes[index] = hash;
             }
         }
-    }
-
-    // Sort by size descending
-    duplicate_groups.sort_by(|a, b| b.size.cmp(&a.size));
+    });
+    hashes
+}
 
-    Ok(duplicate_groups)
+/// Directory names and absolute roots a duplicate scan does not enter.
+struct ScanExclusions<'a> {
+    dirs: &'a HashSet<&'a str>,
+    roots: &'a [PathBuf],
 }
 
 fn collect_files_with_exclusions(
     dir: &Path,
     depth_remaining: usize,
     show_hidden: bool,
-    excluded_dirs: &HashSet<&str>,
-    out: &mut Vec<FileEntry>,
+    exclusions: &ScanExclusions<'_>,
+    out: &mut dyn FnMut(FileEntry),
 ) {
+    if exclusions.roots.iter().any(|root| dir.starts_with(root)) {
+        return;
+    }
     let read_dir = match std::fs::read_dir(dir) {
         Ok(rd) => rd,
         Err(_) => return,
@@ -486,7 +913,7 @@ fn collect_files_with_exclusions(
         }
 
         if metadata.is_dir() {
-            if excluded_dirs.contains(name.as_str()) {
+            if exclusions.dirs.contains(name.as_str()) {
                 continue;
             }
             if depth_remaining > 0 {
@@ -494,24 +921,16 @@ fn collect_files_with_exclusions(
                     &entry_path,
                     depth_remaining - 1,
                     show_hidden,
-                    excluded_dirs,
+                    exclusions,
                     out,
                 );
             }
         } else {
-            out.push(FileEntry::new(entry_path, &metadata));
+            out(FileEntry::new(entry_path, &metadata));
         }
     }
 }
 
-/// Computes the SHA-256 hash of a file and returns it as a hex string.
-fn compute_file_hash(path: &Path) -> CoreResult<String> {
-    let content = std::fs::read(path)?;
-    let mut hasher = Sha256::new();
-    hasher.update(&content);
-    Ok(format!("{:x}", hasher.finalize()))
-}
-
 fn is_valid_filename(name: &str) -> bool {
     if name.is_empty() || name == "." || name == ".." {
         return false;
@@ -548,6 +967,18 @@ mod tests {
         assert!(names.contains(&"subdir"));
     }
 
+    #[test]
+    fn count_entries_stops_at_limit() {
+        let tmp = TempDir::new().unwrap();
+        for i in 0..5 {
+            fs::write(tmp.path().join(format!("f{i}")), "").unwrap();
+        }
+
+        assert_eq!(count_entries(tmp.path(), 3).unwrap(), 3);
+        assert_eq!(count_entries(tmp.path(), 10).unwrap(), 5);
+        assert!(count_entries(&tmp.path().join("missing"), 10).is_err());
+    }
+
     #[test]
     fn read_directory_empty() {
         let tmp = TempDir::new().unwrap();
@@ -729,6 +1160,114 @@ mod tests {
         assert_eq!(fs::read_to_string(&dest).unwrap(), "data");
     }
 
+    // --- copy_file_with_attrs tests ---
+
+    #[cfg(unix)]
+    fn make_aged_file(path: &Path, mode: u32) -> std::time::SystemTime {
+        use std::os::unix::fs::PermissionsExt;
+        let old = std::time::SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_000_000_000);
+        fs::write(path, "data").unwrap();
+        fs::File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_modified(old)
+            .unwrap();
+        fs::set_permissions(path, fs::Permissions::from_mode(mode)).unwrap();
+        old
+    }
+
+    #[cfg(unix)]
+    fn mtime_delta(path: &Path, expected: std::time::SystemTime) -> std::time::Duration {
+        let actual = fs::metadata(path).unwrap().modified().unwrap();
+        actual
+            .duration_since(expected)
+            .unwrap_or_else(|e| e.duration())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn copy_file_with_attrs_preserves_mtime_and_mode() {
+        use std::os::unix::fs::PermissionsExt;
+        let tmp = TempDir::new().unwrap();
+        let src = tmp.path().join("src.sh");
+        let dest = tmp.path().join("dest.sh");
+        let old = make_aged_file(&src, 0o751);
+
+        copy_file_with_attrs(&src, &dest, CopyAttrs::ALL).unwrap();
+
+        assert_eq!(fs::read_to_string(&dest).unwrap(), "data");
+        assert!(mtime_delta(&dest, old) < std::time::Duration::from_secs(2));
+        let mode = fs::metadata(&dest).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode, 0o751);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn copy_file_with_attrs_without_preservation_uses_fresh_attrs() {
+        use std::os::unix::fs::PermissionsExt;
+        let tmp = TempDir::new().unwrap();
+        let src = tmp.path().join("src.sh");
+        let dest = tmp.path().join("dest.sh");
+        let old = make_aged_file(&src, 0o751);
+
+        copy_file_with_attrs(&src, &dest, CopyAttrs::default()).unwrap();
+
+        assert!(mtime_delta(&dest, old) > std::time::Duration::from_secs(60));
+        let mode = fs::metadata(&dest).unwrap().permissions().mode() & 0o777;
+        assert_ne!(mode, 0o751);
+        assert_eq!(mode & 0o111, 0, "new files never get exec bits by default");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn copy_file_with_attrs_preserves_directory_mtime() {
+        let tmp = TempDir::new().unwrap();
+        let src_dir = tmp.path().join("src_dir");
+        fs::create_dir(&src_dir).unwrap();
+        let old = make_aged_file(&src_dir.join("a.txt"), 0o644);
+        fs::File::open(&src_dir).unwrap().set_modified(old).unwrap();
+
+        let dest_dir = tmp.path().join("dest_dir");
+        copy_file_with_attrs(
+            &src_dir,
+            &dest_dir,
+            CopyAttrs {
+                mtime: true,
+                ..CopyAttrs::default()
+            },
+        )
+        .unwrap();
+
+        assert!(mtime_delta(&dest_dir, old) < std::time::Duration::from_secs(2));
+        assert!(mtime_delta(&dest_dir.join("a.txt"), old) < std::time::Duration::from_secs(2));
+    }
+
+    #[test]
+    fn copy_file_with_attrs_preserves_mtime_of_read_only_file() {
+        let tmp = TempDir::new().unwrap();
+        let src = tmp.path().join("src.txt");
+        let dest = tmp.path().join("dest.txt");
+        fs::write(&src, "data").unwrap();
+        let old = std::time::SystemTime::now() - std::time::Duration::from_secs(3600);
+        fs::OpenOptions::new()
+            .write(true)
+            .open(&src)
+            .unwrap()
+            .set_modified(old)
+            .unwrap();
+        let mut perms = fs::metadata(&src).unwrap().permissions();
+        perms.set_readonly(true);
+        fs::set_permissions(&src, perms).unwrap();
+
+        copy_file_with_attrs(&src, &dest, CopyAttrs::ALL).unwrap();
+
+        let actual = fs::metadata(&dest).unwrap().modified().unwrap();
+        let delta = actual.duration_since(old).unwrap_or_else(|e| e.duration());
+        assert!(delta < std::time::Duration::from_secs(2));
+        assert!(fs::metadata(&dest).unwrap().permissions().readonly());
+    }
+
     // --- move_file tests ---
 
     #[test]
@@ -767,6 +1306,171 @@ mod tests {
         assert!(matches!(result.unwrap_err(), CoreError::NotFound(_)));
     }
 
+    // --- copy_file_checked / move_file_checked tests ---
+
+    #[test]
+    fn copy_file_checked_refuses_existing_dest() {
+        let tmp = TempDir::new().unwrap();
+        let src = tmp.path().join("src.txt");
+        let dest = tmp.path().join("dest.txt");
+        fs::write(&src, "new").unwrap();
+        fs::write(&dest, "old").unwrap();
+
+        let result = copy_file_checked(&src, &dest, false);
+        assert!(matches!(result.unwrap_err(), CoreError::AlreadyExists(_)));
+        assert_eq!(fs::read_to_string(&dest).unwrap(), "old");
+    }
+
+    #[test]
+    fn copy_file_checked_overwrite_replaces_directory() {
+        let tmp = TempDir::new().unwrap();
+        let src = tmp.path().join("src");
+        let dest = tmp.path().join("dest");
+        fs::create_dir(&src).unwrap();
+        fs::write(src.join("a.txt"), "new").unwrap();
+        fs::create_dir(&dest).unwrap();
+        fs::write(dest.join("stale.txt"), "old").unwrap();
+
+        copy_file_checked(&src, &dest, true).unwrap();
+
+        assert_eq!(fs::read_to_string(dest.join("a.txt")).unwrap(), "new");
+        assert!(
+            !dest.join("stale.txt").exists(),
+            "directory must not be merged"
+        );
+    }
+
+    #[test]
+    fn copy_file_checked_onto_itself_is_refused() {
+        let tmp = TempDir::new().unwrap();
+        let src = tmp.path().join("same.txt");
+        fs::write(&src, "keep").unwrap();
+
+        let result = copy_file_checked(&src, &src, true);
+        assert!(matches!(result.unwrap_err(), CoreError::AlreadyExists(_)));
+        assert_eq!(fs::read_to_string(&src).unwrap(), "keep");
+    }
+
+    #[test]
+    fn copy_file_checked_overwrite_leaves_no_staging_entries() {
+        let tmp = TempDir::new().unwrap();
+        let src = tmp.path().join("src.txt");
+        let dest = tmp.path().join("dest.txt");
+        fs::write(&src, "new").unwrap();
+        fs::write(&dest, "old").unwrap();
+
+        copy_file_checked(&src, &dest, true).unwrap();
+
+        assert_eq!(fs::read_to_string(&dest).unwrap(), "new");
+        let mut names: Vec<_> = fs::read_dir(tmp.path())
+            .unwrap()
+            .map(|e| e.unwrap().file_name())
+            .collect();
+        names.sort();
+        assert_eq!(names, ["dest.txt", "src.txt"]);
+    }
+
+    #[test]
+    fn copy_file_checked_onto_an_alias_of_itself_is_refused() {
+        let tmp = TempDir::new().unwrap();
+        let src = tmp.path().join("same.txt");
+        fs::write(&src, "keep").unwrap();
+
+        let result = copy_file_checked(&tmp.path().join(".").join("same.txt"), &src, true);
+        assert!(matches!(result.unwrap_err(), CoreError::AlreadyExists(_)));
+        assert_eq!(fs::read_to_string(&src).unwrap(), "keep");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn copy_file_checked_through_a_symlinked_parent_is_refused() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path().join("dir");
+        fs::create_dir(&dir).unwrap();
+        fs::write(dir.join("same.txt"), "keep").unwrap();
+        std::os::unix::fs::symlink(&dir, tmp.path().join("link")).unwrap();
+
+        let result = copy_file_checked(
+            &tmp.path().join("link").join("same.txt"),
+            &dir.join("same.txt"),
+            true,
+        );
+        assert!(matches!(result.unwrap_err(), CoreError::AlreadyExists(_)));
+        assert_eq!(fs::read_to_string(dir.join("same.txt")).unwrap(), "keep");
+    }
+
+    #[test]
+    fn overwriting_a_directory_with_its_own_child_is_refused() {
+        let tmp = TempDir::new().unwrap();
+        let outer = tmp.path().join("foo");
+        let inner = outer.join("foo");
+        fs::create_dir_all(&inner).unwrap();
+        fs::write(inner.join("data.txt"), "precious").unwrap();
+
+        let copied = copy_file_checked(&inner, &outer, true);
+        assert!(matches!(copied.unwrap_err(), CoreError::InvalidName(_)));
+        let moved = move_file_checked(&inner, &outer, true);
+        assert!(matches!(moved.unwrap_err(), CoreError::InvalidName(_)));
+
+        assert_eq!(
+            fs::read_to_string(inner.join("data.txt")).unwrap(),
+            "precious"
+        );
+    }
+
+    #[test]
+    fn overwriting_a_directory_inside_the_source_is_refused() {
+        let tmp = TempDir::new().unwrap();
+        let src = tmp.path().join("foo");
+        let dest = src.join("sub").join("foo");
+        fs::create_dir_all(&dest).unwrap();
+        fs::write(src.join("a.txt"), "a").unwrap();
+
+        let result = copy_file_checked(&src, &dest, true);
+        assert!(matches!(result.unwrap_err(), CoreError::InvalidName(_)));
+        assert!(dest.is_dir());
+        assert_eq!(fs::read_to_string(src.join("a.txt")).unwrap(), "a");
+    }
+
+    #[test]
+    fn move_file_checked_refuses_existing_dest() {
+        let tmp = TempDir::new().unwrap();
+        let src = tmp.path().join("src.txt");
+        let dest = tmp.path().join("dest.txt");
+        fs::write(&src, "new").unwrap();
+        fs::write(&dest, "old").unwrap();
+
+        let result = move_file_checked(&src, &dest, false);
+        assert!(matches!(result.unwrap_err(), CoreError::AlreadyExists(_)));
+        assert!(src.exists());
+    }
+
+    #[test]
+    fn move_file_checked_overwrite_replaces_dest() {
+        let tmp = TempDir::new().unwrap();
+        let src = tmp.path().join("src.txt");
+        let dest = tmp.path().join("dest.txt");
+        fs::write(&src, "new").unwrap();
+        fs::write(&dest, "old").unwrap();
+
+        move_file_checked(&src, &dest, true).unwrap();
+        assert!(!src.exists());
+        assert_eq!(fs::read_to_string(&dest).unwrap(), "new");
+    }
+
+    #[test]
+    fn replace_with_failure_restores_dest() {
+        let tmp = TempDir::new().unwrap();
+        let dest = tmp.path().join("dest.txt");
+        fs::write(&dest, "old").unwrap();
+
+        let result = replace_with(&dest, || Err(CoreError::InvalidName("boom".to_string())));
+
+        assert!(result.is_err());
+        assert_eq!(fs::read_to_string(&dest).unwrap(), "old");
+        assert_eq!(fs::read_dir(tmp.path()).unwrap().count(), 1);
+    }
+
     // --- delete_file tests ---
 
     #[test]
@@ -801,6 +1505,58 @@ mod tests {
         assert!(matches!(result.unwrap_err(), CoreError::NotFound(_)));
     }
 
+    #[test]
+    fn sharing_violation_codes_are_recognised() {
+        let sharing = std::io::Error::from_raw_os_error(ERROR_SHARING_VIOLATION);
+        let lock = std::io::Error::from_raw_os_error(ERROR_LOCK_VIOLATION);
+        let denied = std::io::Error::from_raw_os_error(5);
+        let custom = std::io::Error::other("busy");
+
+        assert!(is_sharing_violation(&sharing));
+        assert!(is_sharing_violation(&lock));
+        assert!(!is_sharing_violation(&denied));
+        assert!(!is_sharing_violation(&custom));
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn sharing_violation_maps_to_file_in_use() {
+        let e = std::io::Error::from_raw_os_error(ERROR_SHARING_VIOLATION);
+        let err = in_use_or_io(e, Path::new("C:\\open.txt"));
+        assert!(matches!(err, CoreError::FileInUse(p) if p == Path::new("C:\\open.txt")));
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn delete_file_open_without_sharing_is_file_in_use() {
+        use std::os::windows::fs::OpenOptionsExt;
+
+        let tmp = TempDir::new().unwrap();
+        let file = tmp.path().join("open.txt");
+        fs::write(&file, "content").unwrap();
+        let _handle = fs::OpenOptions::new()
+            .read(true)
+            .share_mode(0)
+            .open(&file)
+            .unwrap();
+
+        let result = delete_file(&file);
+
+        assert!(matches!(result.unwrap_err(), CoreError::FileInUse(p) if p == file));
+        assert!(file.exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn same_error_codes_stay_io_errors_on_unix() {
+        // 32 is EPIPE here, not a sharing violation.
+        let e = std::io::Error::from_raw_os_error(ERROR_SHARING_VIOLATION);
+        assert!(matches!(
+            in_use_or_io(e, Path::new("/tmp/x")),
+            CoreError::Io(_)
+        ));
+    }
+
     // --- rename_file tests ---
 
     #[test]
@@ -900,6 +1656,121 @@ mod tests {
         assert_eq!(fs::read_to_string(&new_path).unwrap(), "hello");
     }
 
+    // --- rename_file_checked tests ---
+
+    #[test]
+    fn rename_file_checked_refuses_existing_name() {
+        let tmp = TempDir::new().unwrap();
+        let src = tmp.path().join("draft.txt");
+        let dest = tmp.path().join("final.txt");
+        fs::write(&src, "new").unwrap();
+        fs::write(&dest, "old").unwrap();
+
+        let result = rename_file_checked(&src, "final.txt", false);
+        assert!(matches!(result.unwrap_err(), CoreError::AlreadyExists(p) if p == dest));
+        assert_eq!(fs::read_to_string(&src).unwrap(), "new");
+        assert_eq!(fs::read_to_string(&dest).unwrap(), "old");
+    }
+
+    #[test]
+    fn rename_file_checked_overwrite_replaces_existing() {
+        let tmp = TempDir::new().unwrap();
+        let src = tmp.path().join("draft.txt");
+        let dest = tmp.path().join("final");
+        fs::write(&src, "new").unwrap();
+        fs::create_dir(&dest).unwrap();
+        fs::write(dest.join("stale.txt"), "old").unwrap();
+
+        rename_file_checked(&src, "final", true).unwrap();
+
+        assert!(!src.exists());
+        assert_eq!(fs::read_to_string(&dest).unwrap(), "new");
+    }
+
+    #[test]
+    fn rename_file_checked_free_name_needs_no_overwrite() {
+        let tmp = TempDir::new().unwrap();
+        let src = tmp.path().join("a.txt");
+        fs::write(&src, "a").unwrap();
+
+        rename_file_checked(&src, "b.txt", false).unwrap();
+
+        assert_eq!(fs::read_to_string(tmp.path().join("b.txt")).unwrap(), "a");
+    }
+
+    #[test]
+    fn rename_file_checked_to_own_name_is_not_a_conflict() {
+        let tmp = TempDir::new().unwrap();
+        let src = tmp.path().join("same.txt");
+        fs::write(&src, "keep").unwrap();
+
+        rename_file_checked(&src, "same.txt", true).unwrap();
+
+        assert_eq!(fs::read_to_string(&src).unwrap(), "keep");
+    }
+
+    // --- duplicate_in_place tests ---
+
+    #[test]
+    fn copy_name_keeps_the_extension_last() {
+        assert_eq!(copy_name("report.txt", false, 1), "report copy.txt");
+        assert_eq!(copy_name("report.txt", false, 2), "report copy 2.txt");
+        assert_eq!(copy_name("archive.tar.gz", false, 1), "archive.tar copy.gz");
+    }
+
+    #[test]
+    fn copy_name_without_extension_appends() {
+        assert_eq!(copy_name("Makefile", false, 1), "Makefile copy");
+        assert_eq!(copy_name(".bashrc", false, 3), ".bashrc copy 3");
+        assert_eq!(copy_name("v1.2", true, 1), "v1.2 copy");
+    }
+
+    #[test]
+    fn duplicate_in_place_copies_next_to_the_file() {
+        let tmp = TempDir::new().unwrap();
+        let src = tmp.path().join("notes.md");
+        fs::write(&src, "hello").unwrap();
+
+        let dest = duplicate_in_place(&src).unwrap();
+
+        assert_eq!(dest, tmp.path().join("notes copy.md"));
+        assert_eq!(fs::read_to_string(&dest).unwrap(), "hello");
+        assert!(src.exists());
+    }
+
+    #[test]
+    fn duplicate_in_place_avoids_taken_names() {
+        let tmp = TempDir::new().unwrap();
+        let src = tmp.path().join("notes.md");
+        fs::write(&src, "hello").unwrap();
+        fs::write(tmp.path().join("notes copy.md"), "").unwrap();
+        fs::write(tmp.path().join("notes copy 2.md"), "").unwrap();
+
+        let dest = duplicate_in_place(&src).unwrap();
+
+        assert_eq!(dest, tmp.path().join("notes copy 3.md"));
+    }
+
+    #[test]
+    fn duplicate_in_place_copies_directories() {
+        let tmp = TempDir::new().unwrap();
+        let src = tmp.path().join("photos");
+        fs::create_dir(&src).unwrap();
+        fs::write(src.join("a.png"), "png").unwrap();
+
+        let dest = duplicate_in_place(&src).unwrap();
+
+        assert_eq!(dest, tmp.path().join("photos copy"));
+        assert!(dest.join("a.png").exists());
+    }
+
+    #[test]
+    fn duplicate_in_place_missing_source_is_not_found() {
+        let tmp = TempDir::new().unwrap();
+        let result = duplicate_in_place(&tmp.path().join("gone.txt"));
+        assert!(matches!(result, Err(CoreError::NotFound(_))));
+    }
+
     // --- find_recent_files tests ---
 
     #[test]
@@ -1241,19 +2112,177 @@ mod tests {
     }
 
     #[test]
-    fn compute_file_hash_returns_consistent_hash() {
+    fn sha256_file_hash_returns_consistent_hash() {
         let tmp = TempDir::new().unwrap();
         let file1 = tmp.path().join("f1.txt");
         let file2 = tmp.path().join("f2.txt");
         fs::write(&file1, "identical content").unwrap();
         fs::write(&file2, "identical content").unwrap();
 
-        let hash1 = compute_file_hash(&file1).unwrap();
-        let hash2 = compute_file_hash(&file2).unwrap();
+        let hash1 = HashAlgo::Sha256.hash_file(&file1).unwrap();
+        let hash2 = HashAlgo::Sha256.hash_file(&file2).unwrap();
 
         assert_eq!(hash1, hash2);
         assert!(!hash1.is_empty());
-        // SHA-256 hex digest is 64 characters
-        assert_eq!(hash1.len(), 64);
+        // "sha256:" tag followed by a 64-character hex digest
+        assert_eq!(hash1.strip_prefix("sha256:").unwrap().len(), 64);
+    }
+
+    /// Each group as its size, hash and file paths, for comparing scans.
+    fn group_summary(groups: &[DuplicateGroup]) -> Vec<(u64, String, Vec<PathBuf>)> {
+        groups
+            .iter()
+            .map(|g| {
+                let paths = g.files.iter().map(|f| f.path().to_path_buf()).collect();
+                (g.size, g.hash.clone(), paths)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn parallel_hashing_matches_sequential() {
+        let tmp = TempDir::new().unwrap();
+        for dir in ["a", "b", "c"] {
+            let dir = tmp.path().join(dir);
+            fs::create_dir(&dir).unwrap();
+            for i in 0..8 {
+                fs::write(dir.join(format!("same{i}.txt")), format!("content {i}")).unwrap();
+                fs::write(dir.join(format!("pair{i}.txt")), format!("twin {}", i % 2)).unwrap();
+            }
+        }
+        let scan = |threads| {
+            find_duplicate_files_with_algo(
+                tmp.path(),
+                5,
+                false,
+                &HashSet::new(),
+                &DuplicateScanOptions {
+                    algo: HashAlgo::Xxh3,
+                    threads,
+                    ..DuplicateScanOptions::default()
+                },
+            )
+            .unwrap()
+        };
+
+        let sequential = group_summary(&scan(1));
+        assert_eq!(sequential.len(), 10);
+        for threads in [0, 2, 4, 16] {
+            assert_eq!(
+                group_summary(&scan(threads)),
+                sequential,
+                "{threads} threads"
+            );
+        }
+    }
+
+    #[test]
+    fn files_below_min_size_are_skipped() {
+        let tmp = TempDir::new().unwrap();
+        fs::write(tmp.path().join("tiny1.txt"), "abc").unwrap();
+        fs::write(tmp.path().join("tiny2.txt"), "abc").unwrap();
+        fs::write(tmp.path().join("edge1.txt"), "abcd").unwrap();
+        fs::write(tmp.path().join("edge2.txt"), "abcd").unwrap();
+        fs::write(tmp.path().join("empty1.txt"), "").unwrap();
+        fs::write(tmp.path().join("empty2.txt"), "").unwrap();
+        let scan = |min_size| {
+            find_duplicate_files_with_algo(
+                tmp.path(),
+                5,
+                false,
+                &HashSet::new(),
+                &DuplicateScanOptions {
+                    threads: 1,
+                    min_size,
+                    ..DuplicateScanOptions::default()
+                },
+            )
+            .unwrap()
+        };
+
+        let groups = scan(4);
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].size, 4);
+        assert!(groups.iter().flat_map(|g| &g.files).all(|f| f.size() >= 4));
+        assert_eq!(scan(0).len(), 3);
+        assert!(scan(5).is_empty());
+    }
+
+    #[test]
+    fn memory_budget_does_not_change_results() {
+        let tmp = TempDir::new().unwrap();
+        for dir in ["a", "b"] {
+            let dir = tmp.path().join(dir);
+            fs::create_dir(&dir).unwrap();
+            for i in 0..12 {
+                fs::write(dir.join(format!("lone{i}.txt")), "y".repeat(i + 20)).unwrap();
+                fs::write(dir.join(format!("pair{i}.txt")), format!("twin {}", i % 3)).unwrap();
+            }
+        }
+        let scan = |max_memory| {
+            find_duplicate_files_with_algo(
+                tmp.path(),
+                5,
+                false,
+                &HashSet::new(),
+                &DuplicateScanOptions {
+                    threads: 1,
+                    max_memory,
+                    ..DuplicateScanOptions::default()
+                },
+            )
+            .unwrap()
+        };
+        let paths = |groups: &[DuplicateGroup]| -> Vec<Vec<PathBuf>> {
+            groups
+                .iter()
+                .map(|g| g.files.iter().map(|f| f.path().to_path_buf()).collect())
+                .collect()
+        };
+
+        let unlimited = scan(0);
+        assert!(!unlimited.is_empty());
+        for max_memory in [1, 512, 4096] {
+            assert_eq!(
+                paths(&scan(max_memory)),
+                paths(&unlimited),
+                "{max_memory} bytes"
+            );
+        }
+    }
+
+    #[test]
+    fn hash_thread_count_follows_the_setting() {
+        assert_eq!(hash_thread_count(1, 100), 1);
+        assert_eq!(hash_thread_count(4, 100), 4);
+        assert_eq!(hash_thread_count(8, 3), 3);
+        assert_eq!(hash_thread_count(4, 0), 1);
+        let cpus = std::thread::available_parallelism().map_or(1, NonZeroUsize::get);
+        assert_eq!(hash_thread_count(0, 1000), cpus.min(1000));
+    }
+
+    #[test]
+    fn identical_files_group_under_every_algo() {
+        let tmp = TempDir::new().unwrap();
+        fs::write(tmp.path().join("a.txt"), "same bytes").unwrap();
+        fs::write(tmp.path().join("b.txt"), "same bytes").unwrap();
+        fs::write(tmp.path().join("c.txt"), "diff bytes").unwrap();
+
+        for algo in HashAlgo::ALL {
+            let groups = find_duplicate_files_with_algo(
+                tmp.path(),
+                5,
+                false,
+                &HashSet::new(),
+                &DuplicateScanOptions {
+                    algo,
+                    ..DuplicateScanOptions::default()
+                },
+            )
+            .unwrap();
+            assert_eq!(groups.len(), 1, "{algo}");
+            assert_eq!(groups[0].files.len(), 2, "{algo}");
+            assert_eq!(HashAlgo::of_digest(&groups[0].hash), Some(algo));
+        }
     }
 }
@@ -18,7 +18,7 @@ const BINARY_CHECK_SIZE: usize = 8192;
 ///
 /// Handles CSI sequences (`\x1b[...`), OSC sequences (`\x1b]...\x07`),
 /// single-character escape codes, and stray control characters.
-fn strip_ansi_escapes(s: &str) -> String {
+pub(crate) fn strip_ansi_escapes(s: &str) -> String {
     let mut result = String::with_capacity(s.len());
     let mut chars = s.chars().peekable();
 
@@ -91,6 +91,46 @@ pub struct TreeEntry {
     pub depth: usize,
 }
 
+/// How [`TreeEntry::to_text`] indents one nesting level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TreeIndent {
+    /// This many spaces per level.
+    Spaces(usize),
+    /// One tab per level.
+    Tab,
+}
+
+impl TreeIndent {
+    fn unit(self) -> String {
+        match self {
+            Self::Spaces(n) => " ".repeat(n),
+            Self::Tab => "\t".to_string(),
+        }
+    }
+}
+
+impl TreeEntry {
+    /// The entry as one line of a text tree: indented by its depth, with a
+    /// trailing `/` on directories.
+    pub fn to_text(&self, indent: TreeIndent) -> String {
+        let slash = if self.is_dir { "/" } else { "" };
+        format!("{}{}{slash}", indent.unit().repeat(self.depth), self.name)
+    }
+}
+
+/// A text tree with `root` on the first line and `entries` (as returned by
+/// [`read_directory_tree`]) indented one level below it, one per line.
+pub fn tree_to_text(root: &str, entries: &[TreeEntry], indent: TreeIndent) -> String {
+    let unit = indent.unit();
+    let mut text = format!("{root}/\n");
+    for entry in entries {
+        text.push_str(&unit);
+        text.push_str(&entry.to_text(indent));
+        text.push('\n');
+    }
+    text
+}
+
 /// Reads a text preview of the file at `path`, returning at most `max_lines`.
 ///
 /// Returns an error if the file is binary (contains null bytes in the first
@@ -736,6 +776,55 @@ mod tests {
         assert_ne!(e1, e2);
     }
 
+    #[test]
+    fn tree_entry_to_text_indents_by_depth() {
+        let entry = TreeEntry {
+            name: "src".to_string(),
+            is_dir: true,
+            depth: 2,
+        };
+        assert_eq!(entry.to_text(TreeIndent::Spaces(2)), "    src/");
+        assert_eq!(entry.to_text(TreeIndent::Tab), "\t\tsrc/");
+    }
+
+    #[test]
+    fn tree_to_text_renders_known_tree() {
+        let tmp = TempDir::new().unwrap();
+        fs::create_dir_all(tmp.path().join("src/bin")).unwrap();
+        fs::write(tmp.path().join("src/bin/cli.rs"), "").unwrap();
+        fs::write(tmp.path().join("src/lib.rs"), "").unwrap();
+        fs::write(tmp.path().join("README.md"), "").unwrap();
+        fs::write(tmp.path().join("Cargo.toml"), "").unwrap();
+
+        let entries = read_directory_tree(tmp.path(), 5, 100).unwrap();
+        let text = tree_to_text("project", &entries, TreeIndent::Spaces(2));
+
+        assert_eq!(
+            text,
+            "project/\n  src/\n    bin/\n      cli.rs\n    lib.rs\n  Cargo.toml\n  README.md\n"
+        );
+    }
+
+    #[test]
+    fn tree_to_text_stays_within_read_bounds() {
+        let tmp = TempDir::new().unwrap();
+        fs::create_dir_all(tmp.path().join("a/b/c")).unwrap();
+        for i in 0..10 {
+            fs::write(tmp.path().join(format!("f{i}.txt")), "").unwrap();
+        }
+
+        let shallow = read_directory_tree(tmp.path(), 1, 100).unwrap();
+        let text = tree_to_text("root", &shallow, TreeIndent::Tab);
+        assert!(text.contains("\n\t\tb/\n"));
+        assert!(!text.contains("c/"));
+
+        let few = read_directory_tree(tmp.path(), 5, 4).unwrap();
+        assert_eq!(
+            tree_to_text("root", &few, TreeIndent::Tab).lines().count(),
+            5
+        );
+    }
+
     // === strip_ansi_escapes tests ===
 
     #[test]
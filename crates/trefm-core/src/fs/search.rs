@@ -0,0 +1,566 @@
+//! Recursive file content and file name search.
+//!
+//! [`search_file_contents`] walks a directory tree and scans every text file
+//! line by line for a case-insensitive substring. The walk reports progress
+//! after each file and checks a shared cancel flag, so a front-end can run it
+//! on a background thread, show a live "files scanned / matches" counter and
+//! stop it early — in which case the matches found so far are returned.
+//!
+//! [`search_file_names`] walks the same way and fuzzy-matches entry names
+//! with the options' [`FuzzyAlgorithm`].
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::error::{CoreError, CoreResult};
+use crate::fs::entry::FileEntry;
+use crate::fs::exclude::ExcludeSet;
+use crate::nav::filter::{fuzzy_filter_with, FuzzyAlgorithm, FuzzyMatch};
+
+/// Files larger than this are skipped (10 MB).
+const MAX_SEARCH_FILE_SIZE: u64 = 10 * 1024 * 1024;
+
+/// Number of leading bytes checked for a NUL to detect binary files.
+const BINARY_CHECK_SIZE: usize = 8192;
+
+/// Matched lines longer than this are truncated (in characters).
+const MAX_LINE_CHARS: usize = 200;
+
+/// Tuning knobs for [`search_file_contents`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContentSearchOptions {
+    /// Descend into and scan dot-files and dot-directories.
+    pub show_hidden: bool,
+    /// Maximum directory depth below the root.
+    pub max_depth: usize,
+    /// Maximum number of matches kept in the result. Matches past this are
+    /// still counted in [`ContentSearchResult::total_matches`].
+    pub max_results: usize,
+    /// Files and directories to skip. Excluded directories are not entered.
+    pub exclude: ExcludeSet,
+    /// How [`search_file_names`] ranks names; content search ignores it.
+    pub fuzzy_algorithm: FuzzyAlgorithm,
+}
+
+impl Default for ContentSearchOptions {
+    fn default() -> Self {
+        Self {
+            show_hidden: false,
+            max_depth: 20,
+            max_results: 1000,
+            exclude: ExcludeSet::default(),
+            fuzzy_algorithm: FuzzyAlgorithm::default(),
+        }
+    }
+}
+
+/// A single matching line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContentMatch {
+    /// File containing the match.
+    pub path: PathBuf,
+    /// 1-based line number.
+    pub line_number: usize,
+    /// The matching line, trimmed and truncated for display.
+    pub line: String,
+}
+
+/// Snapshot of a running search, passed to the progress callback.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SearchProgress {
+    /// Files read so far (binary and oversized files are not counted).
+    pub files_scanned: usize,
+    /// Matching lines found so far.
+    pub matches: usize,
+}
+
+/// Outcome of [`search_file_contents`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ContentSearchResult {
+    /// Matches in walk order, capped at [`ContentSearchOptions::max_results`].
+    pub matches: Vec<ContentMatch>,
+    /// Files read before the search finished or was cancelled.
+    pub files_scanned: usize,
+    /// Every matching line seen, including those past the cap.
+    pub total_matches: usize,
+    /// `true` if the cancel flag stopped the walk early.
+    pub cancelled: bool,
+}
+
+impl ContentSearchResult {
+    /// Progress counters corresponding to this result.
+    pub fn progress(&self) -> SearchProgress {
+        SearchProgress {
+            files_scanned: self.files_scanned,
+            matches: self.total_matches,
+        }
+    }
+}
+
+/// Searches text files under `root` for lines containing `query`
+/// (case-insensitive).
+///
+/// Directory entries are visited in name order. `on_progress` is called once
+/// after every scanned file. `cancel` is checked before each file; once it is
+/// set the walk stops and the partial result is returned with
+/// [`ContentSearchResult::cancelled`] set. Entries matching
+/// [`ContentSearchOptions::exclude`], binary files, files over 10 MB and
+/// unreadable entries are skipped silently. An empty query matches nothing.
+///
+/// # Errors
+///
+/// - [`CoreError::NotFound`] — `root` does not exist.
+/// - [`CoreError::NotADirectory`] — `root` is not a directory.
+pub fn search_file_contents<F>(
+    root: &Path,
+    query: &str,
+    options: &ContentSearchOptions,
+    cancel: &AtomicBool,
+    mut on_progress: F,
+) -> CoreResult<ContentSearchResult>
+where
+    F: FnMut(SearchProgress),
+{
+    if !root.exists() {
+        return Err(CoreError::NotFound(root.to_path_buf()));
+    }
+    if !root.is_dir() {
+        return Err(CoreError::NotADirectory(root.to_path_buf()));
+    }
+
+    let mut result = ContentSearchResult::default();
+    if query.is_empty() {
+        return Ok(result);
+    }
+
+    let needle = query.to_lowercase();
+    let mut search = Search {
+        root,
+        needle: &needle,
+        options,
+        cancel,
+        on_progress: &mut on_progress,
+        result: &mut result,
+    };
+    search.walk(root, 0);
+
+    Ok(result)
+}
+
+/// Fuzzy-matches `query` against the names of files and directories under
+/// `root`, best match first.
+///
+/// Honours `show_hidden`, `max_depth` and `exclude` like
+/// [`search_file_contents`]; at most `max_results` matches are returned.
+/// Symlinks are listed but not followed. An empty query matches nothing.
+///
+/// # Errors
+///
+/// - [`CoreError::NotFound`] — `root` does not exist.
+/// - [`CoreError::NotADirectory`] — `root` is not a directory.
+pub fn search_file_names(
+    root: &Path,
+    query: &str,
+    options: &ContentSearchOptions,
+) -> CoreResult<Vec<FuzzyMatch>> {
+    if !root.exists() {
+        return Err(CoreError::NotFound(root.to_path_buf()));
+    }
+    if !root.is_dir() {
+        return Err(CoreError::NotADirectory(root.to_path_buf()));
+    }
+    if query.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut entries = Vec::new();
+    collect_entries(root, root, 0, options, &mut entries);
+    let mut matches = fuzzy_filter_with(&entries, query, options.fuzzy_algorithm);
+    matches.truncate(options.max_results);
+    Ok(matches)
+}
+
+fn collect_entries(
+    root: &Path,
+    dir: &Path,
+    depth: usize,
+    options: &ContentSearchOptions,
+    out: &mut Vec<FileEntry>,
+) {
+    let mut entries: Vec<_> = match std::fs::read_dir(dir) {
+        Ok(rd) => rd.filter_map(Result::ok).collect(),
+        Err(_) => return,
+    };
+    entries.sort_by_key(|e| e.file_name());
+
+    for entry in entries {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if !options.show_hidden && name.starts_with('.') {
+            continue;
+        }
+        let path = entry.path();
+        let rel_path = path.strip_prefix(root).unwrap_or(&path);
+        if options.exclude.is_excluded(rel_path) {
+            continue;
+        }
+        // DirEntry::metadata does not follow symlinks.
+        let metadata = match entry.metadata() {
+            Ok(m) => m,
+            Err(_) => continue,
+        };
+        let is_dir = metadata.is_dir();
+        out.push(FileEntry::new(path.clone(), &metadata));
+        if is_dir && depth < options.max_depth {
+            collect_entries(root, &path, depth + 1, options, out);
+        }
+    }
+}
+
+/// Walk state shared across the recursion.
+struct Search<'a, F: FnMut(SearchProgress)> {
+    root: &'a Path,
+    needle: &'a str,
+    options: &'a ContentSearchOptions,
+    cancel: &'a AtomicBool,
+    on_progress: &'a mut F,
+    result: &'a mut ContentSearchResult,
+}
+
+impl<F: FnMut(SearchProgress)> Search<'_, F> {
+    fn walk(&mut self, dir: &Path, depth: usize) {
+        let mut entries: Vec<_> = match std::fs::read_dir(dir) {
+            Ok(rd) => rd.filter_map(Result::ok).collect(),
+            Err(_) => return,
+        };
+        entries.sort_by_key(|e| e.file_name());
+
+        for entry in entries {
+            if self.is_cancelled() {
+                return;
+            }
+
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if !self.options.show_hidden && name.starts_with('.') {
+                continue;
+            }
+
+            // file_type() does not follow symlinks, which avoids loops.
+            let file_type = match entry.file_type() {
+                Ok(ft) => ft,
+                Err(_) => continue,
+            };
+            let path = entry.path();
+            let rel_path = path.strip_prefix(self.root).unwrap_or(&path);
+            if self.options.exclude.is_excluded(rel_path) {
+                continue;
+            }
+
+            if file_type.is_dir() {
+                if depth < self.options.max_depth {
+                    self.walk(&path, depth + 1);
+                }
+            } else if file_type.is_file() && self.scan_file(&path) {
+                (self.on_progress)(self.result.progress());
+            }
+        }
+    }
+
+    fn is_cancelled(&mut self) -> bool {
+        if self.cancel.load(Ordering::Relaxed) {
+            self.result.cancelled = true;
+        }
+        self.result.cancelled
+    }
+
+    /// Scans one file; returns `false` if it was skipped.
+    fn scan_file(&mut self, path: &Path) -> bool {
+        match std::fs::metadata(path) {
+            Ok(m) if m.len() <= MAX_SEARCH_FILE_SIZE => {}
+            _ => return false,
+        }
+        let content = match std::fs::read(path) {
+            Ok(c) => c,
+            Err(_) => return false,
+        };
+        if content[..content.len().min(BINARY_CHECK_SIZE)].contains(&0) {
+            return false;
+        }
+
+        self.result.files_scanned += 1;
+        let text = String::from_utf8_lossy(&content);
+        for (idx, line) in text.lines().enumerate() {
+            if !line.to_lowercase().contains(self.needle) {
+                continue;
+            }
+            self.result.total_matches += 1;
+            if self.result.matches.len() < self.options.max_results {
+                self.result.matches.push(ContentMatch {
+                    path: path.to_path_buf(),
+                    line_number: idx + 1,
+                    line: display_line(line),
+                });
+            }
+        }
+        true
+    }
+}
+
+fn display_line(line: &str) -> String {
+    let trimmed = line.trim();
+    if trimmed.chars().count() > MAX_LINE_CHARS {
+        let mut s: String = trimmed.chars().take(MAX_LINE_CHARS).collect();
+        s.push('…');
+        s
+    } else {
+        trimmed.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn run(root: &Path, query: &str) -> ContentSearchResult {
+        let cancel = AtomicBool::new(false);
+        search_file_contents(
+            root,
+            query,
+            &ContentSearchOptions::default(),
+            &cancel,
+            |_| {},
+        )
+        .unwrap()
+    }
+
+    fn sample_tree() -> TempDir {
+        let tmp = TempDir::new().unwrap();
+        fs::write(
+            tmp.path().join("a.txt"),
+            "hello world\nnothing here\nHELLO again",
+        )
+        .unwrap();
+        fs::create_dir(tmp.path().join("sub")).unwrap();
+        fs::write(tmp.path().join("sub").join("b.rs"), "fn hello() {}\n").unwrap();
+        fs::write(tmp.path().join("c.txt"), "no match").unwrap();
+        tmp
+    }
+
+    #[test]
+    fn finds_matches_case_insensitively_with_line_numbers() {
+        let tmp = sample_tree();
+        let result = run(tmp.path(), "hello");
+
+        assert_eq!(result.total_matches, 3);
+        assert_eq!(result.files_scanned, 3);
+        assert!(!result.cancelled);
+        let a: Vec<usize> = result
+            .matches
+            .iter()
+            .filter(|m| m.path.ends_with("a.txt"))
+            .map(|m| m.line_number)
+            .collect();
+        assert_eq!(a, vec![1, 3]);
+        assert!(result
+            .matches
+            .iter()
+            .any(|m| m.path.ends_with("b.rs") && m.line == "fn hello() {}"));
+    }
+
+    #[test]
+    fn empty_query_matches_nothing() {
+        let tmp = sample_tree();
+        let result = run(tmp.path(), "");
+        assert!(result.matches.is_empty());
+        assert_eq!(result.files_scanned, 0);
+    }
+
+    #[test]
+    fn skips_binary_and_hidden_files() {
+        let tmp = TempDir::new().unwrap();
+        fs::write(tmp.path().join("bin.dat"), b"needle\x00\x01").unwrap();
+        fs::write(tmp.path().join(".hidden"), "needle").unwrap();
+        fs::write(tmp.path().join("text.txt"), "needle").unwrap();
+
+        let result = run(tmp.path(), "needle");
+
+        assert_eq!(result.files_scanned, 1);
+        assert_eq!(result.matches.len(), 1);
+        assert!(result.matches[0].path.ends_with("text.txt"));
+    }
+
+    #[test]
+    fn caps_kept_matches_but_counts_all() {
+        let tmp = TempDir::new().unwrap();
+        fs::write(tmp.path().join("many.txt"), "x\n".repeat(10)).unwrap();
+        let cancel = AtomicBool::new(false);
+        let options = ContentSearchOptions {
+            max_results: 4,
+            ..ContentSearchOptions::default()
+        };
+
+        let result = search_file_contents(tmp.path(), "x", &options, &cancel, |_| {}).unwrap();
+
+        assert_eq!(result.matches.len(), 4);
+        assert_eq!(result.total_matches, 10);
+    }
+
+    #[test]
+    fn progress_callback_fires_once_per_file() {
+        let tmp = sample_tree();
+        let cancel = AtomicBool::new(false);
+        let mut seen = Vec::new();
+
+        let result = search_file_contents(
+            tmp.path(),
+            "hello",
+            &ContentSearchOptions::default(),
+            &cancel,
+            |p| seen.push(p),
+        )
+        .unwrap();
+
+        assert_eq!(seen.len(), 3);
+        let counts: Vec<usize> = seen.iter().map(|p| p.files_scanned).collect();
+        assert_eq!(counts, vec![1, 2, 3]);
+        assert_eq!(seen.last().copied(), Some(result.progress()));
+    }
+
+    #[test]
+    fn cancel_returns_partial_results() {
+        let tmp = TempDir::new().unwrap();
+        for i in 0..5 {
+            fs::write(tmp.path().join(format!("f{i}.txt")), "needle").unwrap();
+        }
+        let cancel = AtomicBool::new(false);
+
+        let result = search_file_contents(
+            tmp.path(),
+            "needle",
+            &ContentSearchOptions::default(),
+            &cancel,
+            |p| {
+                if p.files_scanned == 2 {
+                    cancel.store(true, Ordering::Relaxed);
+                }
+            },
+        )
+        .unwrap();
+
+        assert!(result.cancelled);
+        assert_eq!(result.files_scanned, 2);
+        assert_eq!(result.matches.len(), 2);
+        assert!(result.matches[0].path.ends_with("f0.txt"));
+        assert!(result.matches[1].path.ends_with("f1.txt"));
+    }
+
+    #[test]
+    fn excluded_directories_are_never_searched() {
+        let tmp = sample_tree();
+        fs::create_dir_all(tmp.path().join("node_modules").join("pkg")).unwrap();
+        fs::write(
+            tmp.path().join("node_modules").join("pkg").join("index.js"),
+            "hello",
+        )
+        .unwrap();
+        fs::write(tmp.path().join("bundle.min.js"), "hello").unwrap();
+        let cancel = AtomicBool::new(false);
+        let options = ContentSearchOptions {
+            exclude: ExcludeSet::new(["node_modules", "*.min.js"]),
+            ..ContentSearchOptions::default()
+        };
+
+        let result = search_file_contents(tmp.path(), "hello", &options, &cancel, |_| {}).unwrap();
+
+        assert_eq!(result.files_scanned, 3);
+        assert!(result
+            .matches
+            .iter()
+            .all(|m| !m.path.starts_with(tmp.path().join("node_modules"))
+                && !m.path.ends_with("bundle.min.js")));
+    }
+
+    #[test]
+    fn nonexistent_root_returns_not_found() {
+        let tmp = TempDir::new().unwrap();
+        let cancel = AtomicBool::new(false);
+        let err = search_file_contents(
+            &tmp.path().join("nope"),
+            "x",
+            &ContentSearchOptions::default(),
+            &cancel,
+            |_| {},
+        )
+        .unwrap_err();
+        assert!(matches!(err, CoreError::NotFound(_)));
+    }
+
+    #[test]
+    fn long_lines_are_truncated() {
+        let tmp = TempDir::new().unwrap();
+        let long = format!("needle {}", "a".repeat(500));
+        fs::write(tmp.path().join("long.txt"), &long).unwrap();
+
+        let result = run(tmp.path(), "needle");
+
+        assert_eq!(result.matches[0].line.chars().count(), MAX_LINE_CHARS + 1);
+        assert!(result.matches[0].line.ends_with('…'));
+    }
+
+    // === search_file_names ===
+
+    #[test]
+    fn name_search_ranks_best_match_first() {
+        let tmp = TempDir::new().unwrap();
+        fs::write(tmp.path().join("main.rs"), "").unwrap();
+        fs::create_dir(tmp.path().join("src")).unwrap();
+        fs::write(tmp.path().join("src").join("my_app_main.rs"), "").unwrap();
+        fs::write(tmp.path().join("notes.txt"), "").unwrap();
+
+        let matches =
+            search_file_names(tmp.path(), "main", &ContentSearchOptions::default()).unwrap();
+        let names: Vec<&str> = matches.iter().map(|m| m.entry().name()).collect();
+        assert_eq!(names.len(), 2);
+        assert_eq!(names[0], "main.rs");
+        assert!(names.contains(&"my_app_main.rs"));
+        assert!(matches[0].score() >= matches[1].score());
+    }
+
+    #[test]
+    fn name_search_matches_directories_and_honours_exclusions() {
+        let tmp = TempDir::new().unwrap();
+        fs::create_dir(tmp.path().join("docs")).unwrap();
+        fs::create_dir(tmp.path().join("target")).unwrap();
+        fs::write(tmp.path().join("target").join("docs.html"), "").unwrap();
+        let options = ContentSearchOptions {
+            exclude: ExcludeSet::new(["target"]),
+            ..ContentSearchOptions::default()
+        };
+
+        let matches = search_file_names(tmp.path(), "docs", &options).unwrap();
+        assert_eq!(matches.len(), 1);
+        assert!(matches[0].entry().is_dir());
+    }
+
+    #[test]
+    fn name_search_caps_results_and_ignores_empty_query() {
+        let tmp = TempDir::new().unwrap();
+        for i in 0..5 {
+            fs::write(tmp.path().join(format!("file{i}.txt")), "").unwrap();
+        }
+        let options = ContentSearchOptions {
+            max_results: 3,
+            ..ContentSearchOptions::default()
+        };
+        assert_eq!(
+            search_file_names(tmp.path(), "file", &options)
+                .unwrap()
+                .len(),
+            3
+        );
+        assert!(search_file_names(tmp.path(), "", &options)
+            .unwrap()
+            .is_empty());
+    }
+}
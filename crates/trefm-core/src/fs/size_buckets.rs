@@ -0,0 +1,318 @@
+//! Files grouped by size while a duplicate scan walks the tree.
+//!
+//! Only files that share their size with another one can be duplicates, so
+//! [`SizeBuckets`] files every entry under its size as it is found instead
+//! of collecting the whole tree first. With a memory budget, buckets still
+//! holding a single file are spilled to a temporary file whenever the
+//! estimated footprint goes over it: most of those files never meet a second
+//! one of the same size. A spilled file is read back only if one does.
+//!
+//! The budget is a target rather than a hard cap — buckets of two or more
+//! files always stay in memory, and if no spill file can be created the
+//! scan carries on without one.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Seek, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::fs::entry::FileEntry;
+
+/// Estimated bytes for a bucket besides its entries.
+const BUCKET_COST: usize = 64;
+/// Estimated bytes to remember that a size was spilled.
+const SPILLED_SIZE_COST: usize = 16;
+
+/// Scanned files keyed by size; see the module docs.
+pub(crate) struct SizeBuckets {
+    buckets: BTreeMap<u64, Vec<(usize, FileEntry)>>,
+    next_index: usize,
+    /// Bytes to stay under, `0` for no limit.
+    budget: usize,
+    used: usize,
+    spill_at: usize,
+    spill: Option<Spill>,
+    spilled_sizes: HashSet<u64>,
+    /// Spilled sizes that another file turned up for.
+    reclaim: HashSet<u64>,
+    spilled_files: usize,
+}
+
+impl SizeBuckets {
+    /// Empty buckets that try to stay within `budget` bytes (`0`: unlimited).
+    pub(crate) fn new(budget: usize) -> Self {
+        Self {
+            buckets: BTreeMap::new(),
+            next_index: 0,
+            budget,
+            used: 0,
+            spill_at: budget,
+            spill: None,
+            spilled_sizes: HashSet::new(),
+            reclaim: HashSet::new(),
+            spilled_files: 0,
+        }
+    }
+
+    /// Files `entry` under its size, spilling single-file buckets if that
+    /// takes the estimate over the budget.
+    pub(crate) fn push(&mut self, entry: FileEntry) {
+        let size = entry.size();
+        if self.spilled_sizes.contains(&size) {
+            self.reclaim.insert(size);
+        }
+        self.used += entry_cost(&entry);
+        let bucket = self.buckets.entry(size).or_insert_with(|| {
+            self.used += BUCKET_COST;
+            Vec::new()
+        });
+        bucket.push((self.next_index, entry));
+        self.next_index += 1;
+
+        if self.budget > 0 && self.used > self.spill_at {
+            self.spill_singles();
+        }
+    }
+
+    /// Every bucket, spilled files that found a partner included, with the
+    /// files of each in the order they were pushed. Files spilled while
+    /// alone in their size and never matched are left out, since they
+    /// cannot have a duplicate.
+    pub(crate) fn into_groups(mut self) -> BTreeMap<u64, Vec<FileEntry>> {
+        if let Some(spill) = self.spill.take() {
+            // A spill that cannot be read back loses only files that were
+            // alone in their size when spilled.
+            let _ = spill.read_back(&self.reclaim, &mut self.buckets);
+        }
+        self.buckets
+            .into_iter()
+            .map(|(size, mut files)| {
+                files.sort_by_key(|(index, _)| *index);
+                (size, files.into_iter().map(|(_, entry)| entry).collect())
+            })
+            .collect()
+    }
+
+    /// Moves every single-file bucket to the spill file.
+    fn spill_singles(&mut self) {
+        if self.spill.is_none() {
+            match Spill::create() {
+                Ok(spill) => self.spill = Some(spill),
+                Err(_) => {
+                    self.budget = 0;
+                    return;
+                }
+            }
+        }
+        let Some(spill) = self.spill.as_mut() else {
+            return;
+        };
+        let singles: Vec<u64> = self
+            .buckets
+            .iter()
+            .filter(|(_, files)| files.len() == 1)
+            .map(|(size, _)| *size)
+            .collect();
+        for size in singles {
+            let (index, entry) = &self.buckets[&size][0];
+            if spill.write(size, *index, entry.path()).is_err() {
+                self.budget = 0;
+                return;
+            }
+            let cost = entry_cost(entry);
+            self.buckets.remove(&size);
+            self.used -= cost + BUCKET_COST;
+            if self.spilled_sizes.insert(size) {
+                self.used += SPILLED_SIZE_COST;
+            }
+            self.spilled_files += 1;
+        }
+        // Buckets of two or more cannot be spilled, so wait for half a
+        // budget of growth before looking again.
+        self.spill_at = self.used.max(self.budget) + self.budget / 2;
+    }
+
+    #[cfg(test)]
+    fn spilled_files(&self) -> usize {
+        self.spilled_files
+    }
+}
+
+/// Rough heap and inline bytes held for one entry.
+fn entry_cost(entry: &FileEntry) -> usize {
+    std::mem::size_of::<(usize, FileEntry)>() + entry.path().as_os_str().len() + entry.name().len()
+}
+
+/// Temporary file of `(size, index, path)` records, removed on drop.
+struct Spill {
+    path: PathBuf,
+    writer: BufWriter<File>,
+}
+
+impl Spill {
+    fn create() -> io::Result<Self> {
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+        let path = std::env::temp_dir().join(format!(
+            "trefm-dup-spill-{}-{}",
+            std::process::id(),
+            NEXT.fetch_add(1, Ordering::Relaxed)
+        ));
+        let file = File::options()
+            .read(true)
+            .write(true)
+            .create_new(true)
+            .open(&path)?;
+        Ok(Self {
+            path,
+            writer: BufWriter::new(file),
+        })
+    }
+
+    fn write(&mut self, size: u64, index: usize, path: &Path) -> io::Result<()> {
+        let bytes = path_bytes(path);
+        self.writer.write_all(&size.to_le_bytes())?;
+        self.writer.write_all(&(index as u64).to_le_bytes())?;
+        self.writer.write_all(&(bytes.len() as u64).to_le_bytes())?;
+        self.writer.write_all(&bytes)
+    }
+
+    /// Puts the records whose size is in `wanted` back into `buckets`,
+    /// re-reading their metadata. Files that changed size or vanished since
+    /// are dropped.
+    fn read_back(
+        mut self,
+        wanted: &HashSet<u64>,
+        buckets: &mut BTreeMap<u64, Vec<(usize, FileEntry)>>,
+    ) -> io::Result<()> {
+        if wanted.is_empty() {
+            return Ok(());
+        }
+        self.writer.flush()?;
+        let mut file = self.writer.get_ref().try_clone()?;
+        file.rewind()?;
+        let mut reader = BufReader::new(file);
+        while let Some(size) = read_u64(&mut reader)? {
+            let index = read_u64(&mut reader)?.ok_or(io::ErrorKind::UnexpectedEof)? as usize;
+            let len = read_u64(&mut reader)?.ok_or(io::ErrorKind::UnexpectedEof)? as usize;
+            let mut bytes = vec![0; len];
+            reader.read_exact(&mut bytes)?;
+            if !wanted.contains(&size) {
+                continue;
+            }
+            let path = path_from_bytes(bytes);
+            let Ok(metadata) = std::fs::symlink_metadata(&path) else {
+                continue;
+            };
+            let entry = FileEntry::new(path, &metadata);
+            if entry.size() == size {
+                buckets.entry(size).or_default().push((index, entry));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Drop for Spill {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// The next little-endian `u64`, or `None` at a clean end of file.
+fn read_u64(reader: &mut impl Read) -> io::Result<Option<u64>> {
+    let mut buf = [0; 8];
+    match reader.read_exact(&mut buf) {
+        Ok(()) => Ok(Some(u64::from_le_bytes(buf))),
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(unix)]
+fn path_bytes(path: &Path) -> Vec<u8> {
+    use std::os::unix::ffi::OsStrExt;
+    path.as_os_str().as_bytes().to_vec()
+}
+
+#[cfg(unix)]
+fn path_from_bytes(bytes: Vec<u8>) -> PathBuf {
+    use std::os::unix::ffi::OsStringExt;
+    PathBuf::from(std::ffi::OsString::from_vec(bytes))
+}
+
+#[cfg(not(unix))]
+fn path_bytes(path: &Path) -> Vec<u8> {
+    path.to_string_lossy().into_owned().into_bytes()
+}
+
+#[cfg(not(unix))]
+fn path_from_bytes(bytes: Vec<u8>) -> PathBuf {
+    PathBuf::from(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn entry(tmp: &TempDir, name: &str, content: &str) -> FileEntry {
+        let path = tmp.path().join(name);
+        fs::write(&path, content).unwrap();
+        FileEntry::new(path.clone(), &fs::metadata(&path).unwrap())
+    }
+
+    /// Sizes and names of the buckets that could hold duplicates.
+    fn names(groups: &BTreeMap<u64, Vec<FileEntry>>) -> Vec<(u64, Vec<String>)> {
+        groups
+            .iter()
+            .filter(|(_, files)| files.len() >= 2)
+            .map(|(size, files)| {
+                let names = files.iter().map(|f| f.name().to_string()).collect();
+                (*size, names)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn spilled_singles_rejoin_their_bucket_in_order() {
+        let tmp = TempDir::new().unwrap();
+        let files = [
+            entry(&tmp, "a1", "a"),
+            entry(&tmp, "bb1", "bb"),
+            entry(&tmp, "ccc", "ccc"),
+            entry(&tmp, "a2", "a"),
+            entry(&tmp, "bb2", "bb"),
+        ];
+        let mut unlimited = SizeBuckets::new(0);
+        let mut tiny = SizeBuckets::new(1);
+        for file in &files {
+            unlimited.push(file.clone());
+            tiny.push(file.clone());
+        }
+
+        assert_eq!(unlimited.spilled_files(), 0);
+        assert!(tiny.spilled_files() >= 3);
+        let expected = names(&unlimited.into_groups());
+        assert_eq!(names(&tiny.into_groups()), expected);
+        assert_eq!(
+            expected,
+            vec![
+                (1, vec!["a1".to_string(), "a2".to_string()]),
+                (2, vec!["bb1".to_string(), "bb2".to_string()]),
+            ]
+        );
+    }
+
+    #[test]
+    fn unmatched_spilled_files_stay_on_disk() {
+        let tmp = TempDir::new().unwrap();
+        let mut buckets = SizeBuckets::new(1);
+        buckets.push(entry(&tmp, "lonely", "xyz"));
+        buckets.push(entry(&tmp, "other", "12345"));
+
+        assert_eq!(buckets.spilled_files(), 2);
+        assert!(buckets.into_groups().is_empty());
+    }
+}
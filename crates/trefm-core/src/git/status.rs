@@ -29,6 +29,21 @@ pub enum GitFileStatus {
     Unchanged,
 }
 
+impl GitFileStatus {
+    /// One-character marker as shown by `git status --short`-like listings.
+    pub fn short_code(self) -> &'static str {
+        match self {
+            Self::Modified => "M",
+            Self::Added => "A",
+            Self::Deleted => "D",
+            Self::Renamed => "R",
+            Self::Untracked => "?",
+            Self::Ignored => "!",
+            Self::Unchanged => " ",
+        }
+    }
+}
+
 /// Returns `true` if the given path is inside a git repository.
 pub fn is_git_repo(path: &Path) -> bool {
     find_repo_root(path).is_some()
@@ -43,6 +58,71 @@ pub fn find_repo_root(path: &Path) -> Option<PathBuf> {
         .and_then(|repo| repo.workdir().map(|w| w.to_path_buf()))
 }
 
+/// Memoised [`find_repo_root`] results, keyed by the directory looked up.
+///
+/// Misses are kept too, so a directory outside any repository is searched
+/// only once. Entries go stale when a `.git` is created or removed; see
+/// [`RepoRootCache::invalidate`] and [`RepoRootCache::clear`].
+#[derive(Debug, Clone, Default)]
+pub struct RepoRootCache {
+    roots: HashMap<PathBuf, Option<PathBuf>>,
+}
+
+impl RepoRootCache {
+    /// The repository root for `dir`, searched for on the first lookup.
+    pub fn find(&mut self, dir: &Path) -> Option<PathBuf> {
+        self.roots
+            .entry(dir.to_path_buf())
+            .or_insert_with(|| find_repo_root(dir))
+            .clone()
+    }
+
+    /// Like [`find`](Self::find), but a directory that is not cached yet is
+    /// searched without being remembered.
+    pub fn get_or_find(&self, dir: &Path) -> Option<PathBuf> {
+        match self.roots.get(dir) {
+            Some(root) => root.clone(),
+            None => find_repo_root(dir),
+        }
+    }
+
+    /// Whether a lookup for `dir` is cached, found or not.
+    pub fn is_cached(&self, dir: &Path) -> bool {
+        self.roots.contains_key(dir)
+    }
+
+    /// Forgets what a `.git` appearing or disappearing at any of `changed`
+    /// may affect: every directory at or below the one holding it. Paths
+    /// not named `.git` are ignored.
+    pub fn invalidate(&mut self, changed: &[PathBuf]) {
+        for changed in changed {
+            if changed.file_name().is_some_and(|name| name == ".git") {
+                if let Some(parent) = changed.parent() {
+                    self.roots.retain(|dir, _| !dir.starts_with(parent));
+                }
+            }
+        }
+    }
+
+    /// Forgets every lookup.
+    pub fn clear(&mut self) {
+        self.roots.clear();
+    }
+}
+
+/// `path` relative to `repo_root`, with `/` separators as git writes paths.
+///
+/// Returns `None` if `path` is not below `repo_root`. Both paths should be
+/// canonical.
+pub fn repo_relative_path(path: &Path, repo_root: &Path) -> Option<String> {
+    let rel = path.strip_prefix(repo_root).ok()?;
+    let parts: Vec<_> = rel.iter().map(|part| part.to_string_lossy()).collect();
+    if parts.is_empty() {
+        return None;
+    }
+    Some(parts.join("/"))
+}
+
 /// Returns the git status of every file in the repository rooted at `repo_root`.
 ///
 /// Keys are **absolute paths**. Files that are clean (unchanged) are not
@@ -74,6 +154,44 @@ pub fn get_file_statuses(repo_root: &Path) -> CoreResult<HashMap<PathBuf, GitFil
     Ok(map)
 }
 
+/// Returns the immediate children of `dir` that git ignores.
+///
+/// [`get_file_statuses`] omits ignored files, so this is meant to be merged
+/// into its result for the directory being displayed. Only the entries of
+/// `dir` are checked (via `status_should_ignore`), which keeps the cost
+/// independent of how large the ignored trees are. Returned paths are
+/// `dir.join(name)`, matching the paths produced by directory listings.
+///
+/// # Errors
+///
+/// Returns [`CoreError::Git`] if `repo_root` is not a valid git repository,
+/// or an I/O error if `dir` cannot be read.
+pub fn get_ignored_paths(repo_root: &Path, dir: &Path) -> CoreResult<Vec<PathBuf>> {
+    let repo = Repository::open(repo_root).map_err(|e| CoreError::Git(e.message().to_string()))?;
+    let Some(workdir) = repo.workdir() else {
+        return Ok(Vec::new());
+    };
+    let workdir = workdir.canonicalize()?;
+    let canon_dir = dir.canonicalize()?;
+    let Ok(rel_dir) = canon_dir.strip_prefix(&workdir) else {
+        return Ok(Vec::new());
+    };
+
+    let mut ignored = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name();
+        let is_ignored = repo
+            .status_should_ignore(&rel_dir.join(&name))
+            .map_err(|e| CoreError::Git(e.message().to_string()))?;
+        if is_ignored {
+            ignored.push(dir.join(name));
+        }
+    }
+
+    Ok(ignored)
+}
+
 /// Looks up the status of a single path in a pre-computed status map.
 ///
 /// Returns [`GitFileStatus::Unchanged`] when the path is not present in the
@@ -88,6 +206,21 @@ pub fn get_status_for_path(
         .unwrap_or(GitFileStatus::Unchanged)
 }
 
+/// Extracts the files with uncommitted changes from a status map.
+///
+/// Everything except [`GitFileStatus::Unchanged`] and
+/// [`GitFileStatus::Ignored`] counts as a change. The result is sorted by
+/// path so listings are stable across refreshes.
+pub fn changed_files(statuses: &HashMap<PathBuf, GitFileStatus>) -> Vec<(PathBuf, GitFileStatus)> {
+    let mut changed: Vec<(PathBuf, GitFileStatus)> = statuses
+        .iter()
+        .filter(|(_, status)| !matches!(status, GitFileStatus::Unchanged | GitFileStatus::Ignored))
+        .map(|(path, status)| (path.clone(), *status))
+        .collect();
+    changed.sort_by(|a, b| a.0.cmp(&b.0));
+    changed
+}
+
 /// Maps `git2::Status` bit-flags to our simplified [`GitFileStatus`].
 fn map_git2_status(status: git2::Status) -> GitFileStatus {
     if status.is_ignored() {
@@ -220,6 +353,76 @@ mod tests {
         assert!(find_repo_root(tmp.path()).is_none());
     }
 
+    // --- RepoRootCache tests ---
+
+    #[test]
+    fn repo_root_cache_reuses_lookups() {
+        let (tmp, _repo) = setup_git_repo();
+        let subdir = tmp.path().join("src");
+        fs::create_dir(&subdir).unwrap();
+        let mut cache = RepoRootCache::default();
+
+        let root = cache.find(tmp.path());
+        assert!(root.is_some());
+        assert_eq!(cache.find(&subdir), root);
+        assert!(cache.is_cached(tmp.path()) && cache.is_cached(&subdir));
+
+        // Cached answers survive the repository disappearing.
+        fs::remove_dir_all(tmp.path().join(".git")).unwrap();
+        assert_eq!(cache.find(&subdir), root);
+        assert_eq!(cache.get_or_find(tmp.path()), root);
+
+        cache.invalidate(&[tmp.path().join(".git")]);
+        assert!(!cache.is_cached(&subdir));
+        assert_eq!(cache.find(&subdir), None);
+    }
+
+    #[test]
+    fn repo_root_cache_keeps_misses() {
+        let tmp = TempDir::new().unwrap();
+        let mut cache = RepoRootCache::default();
+
+        assert_eq!(cache.find(tmp.path()), None);
+        assert!(cache.is_cached(tmp.path()));
+
+        Repository::init(tmp.path()).unwrap();
+        assert_eq!(cache.find(tmp.path()), None);
+
+        cache.invalidate(&[tmp.path().join("notes.txt")]);
+        assert!(cache.is_cached(tmp.path()));
+
+        cache.clear();
+        assert!(cache.find(tmp.path()).is_some());
+    }
+
+    // --- repo_relative_path tests ---
+
+    #[test]
+    fn repo_relative_path_of_nested_file() {
+        let (tmp, _repo) = setup_git_repo();
+        let nested = tmp.path().join("src").join("deep");
+        fs::create_dir_all(&nested).unwrap();
+        fs::write(nested.join("main.rs"), "").unwrap();
+
+        let root = find_repo_root(&nested).unwrap().canonicalize().unwrap();
+        let file = nested.join("main.rs").canonicalize().unwrap();
+
+        assert_eq!(
+            repo_relative_path(&file, &root).as_deref(),
+            Some("src/deep/main.rs")
+        );
+    }
+
+    #[test]
+    fn repo_relative_path_outside_root_is_none() {
+        let root = Path::new("/home/me/project");
+        assert_eq!(
+            repo_relative_path(Path::new("/home/me/other/a.txt"), root),
+            None
+        );
+        assert_eq!(repo_relative_path(root, root), None);
+    }
+
     // --- get_file_statuses tests ---
 
     #[test]
@@ -315,6 +518,48 @@ mod tests {
         assert_eq!(statuses.get(&file_path), Some(&GitFileStatus::Added));
     }
 
+    // --- get_ignored_paths tests ---
+
+    #[test]
+    fn get_ignored_paths_includes_ignored_file() {
+        let (tmp, repo) = setup_git_repo();
+        commit_file(&repo, &tmp.path().join(".gitignore"), "*.log\n");
+        fs::write(tmp.path().join("debug.log"), "noise").unwrap();
+
+        let ignored = get_ignored_paths(tmp.path(), tmp.path()).unwrap();
+        assert!(ignored.contains(&tmp.path().join("debug.log")));
+    }
+
+    #[test]
+    fn get_ignored_paths_excludes_tracked_file() {
+        let (tmp, repo) = setup_git_repo();
+        commit_file(&repo, &tmp.path().join(".gitignore"), "*.log\n");
+        let tracked = tmp.path().join("main.rs");
+        commit_file(&repo, &tracked, "fn main() {}");
+
+        let ignored = get_ignored_paths(tmp.path(), tmp.path()).unwrap();
+        assert!(!ignored.contains(&tracked));
+        assert!(!ignored.contains(&tmp.path().join(".gitignore")));
+    }
+
+    #[test]
+    fn get_ignored_paths_in_subdirectory() {
+        let (tmp, repo) = setup_git_repo();
+        commit_file(&repo, &tmp.path().join(".gitignore"), "build/\n");
+        let sub = tmp.path().join("sub");
+        fs::create_dir_all(sub.join("build")).unwrap();
+        fs::write(sub.join("keep.txt"), "x").unwrap();
+
+        let ignored = get_ignored_paths(tmp.path(), &sub).unwrap();
+        assert_eq!(ignored, vec![sub.join("build")]);
+    }
+
+    #[test]
+    fn get_ignored_paths_not_a_repo_returns_error() {
+        let tmp = TempDir::new().unwrap();
+        assert!(get_ignored_paths(tmp.path(), tmp.path()).is_err());
+    }
+
     // --- get_status_for_path tests ---
 
     #[test]
@@ -478,4 +723,68 @@ mod tests {
             GitFileStatus::Modified
         );
     }
+
+    // --- changed_files ---
+
+    #[test]
+    fn changed_files_keeps_modified_and_untracked() {
+        let mut statuses = HashMap::new();
+        statuses.insert(PathBuf::from("/repo/b.rs"), GitFileStatus::Untracked);
+        statuses.insert(PathBuf::from("/repo/a.rs"), GitFileStatus::Modified);
+        statuses.insert(PathBuf::from("/repo/c.rs"), GitFileStatus::Added);
+
+        let changed = changed_files(&statuses);
+
+        assert_eq!(
+            changed,
+            vec![
+                (PathBuf::from("/repo/a.rs"), GitFileStatus::Modified),
+                (PathBuf::from("/repo/b.rs"), GitFileStatus::Untracked),
+                (PathBuf::from("/repo/c.rs"), GitFileStatus::Added),
+            ]
+        );
+    }
+
+    #[test]
+    fn changed_files_excludes_clean_and_ignored() {
+        let mut statuses = HashMap::new();
+        statuses.insert(PathBuf::from("/repo/clean.rs"), GitFileStatus::Unchanged);
+        statuses.insert(PathBuf::from("/repo/target"), GitFileStatus::Ignored);
+        statuses.insert(PathBuf::from("/repo/gone.rs"), GitFileStatus::Deleted);
+
+        let changed = changed_files(&statuses);
+
+        assert_eq!(
+            changed,
+            vec![(PathBuf::from("/repo/gone.rs"), GitFileStatus::Deleted)]
+        );
+    }
+
+    #[test]
+    fn changed_files_from_real_repo() {
+        let (tmp, repo) = setup_git_repo();
+        let root = tmp.path().canonicalize().unwrap();
+        commit_file(&repo, &root.join("clean.txt"), "clean");
+        commit_file(&repo, &root.join("edited.txt"), "v1");
+        fs::write(root.join("edited.txt"), "v2").unwrap();
+        fs::write(root.join("new.txt"), "new").unwrap();
+
+        let statuses = get_file_statuses(&root).unwrap();
+        let changed = changed_files(&statuses);
+
+        assert_eq!(
+            changed,
+            vec![
+                (root.join("edited.txt"), GitFileStatus::Modified),
+                (root.join("new.txt"), GitFileStatus::Untracked),
+            ]
+        );
+    }
+
+    #[test]
+    fn short_code_matches_git_letters() {
+        assert_eq!(GitFileStatus::Modified.short_code(), "M");
+        assert_eq!(GitFileStatus::Untracked.short_code(), "?");
+        assert_eq!(GitFileStatus::Unchanged.short_code(), " ");
+    }
 }
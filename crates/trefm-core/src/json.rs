@@ -0,0 +1,128 @@
+//! JSON output for scripting and other frontends.
+//!
+//! Available with the `json` feature, which also derives `Serialize` and
+//! `Deserialize` for [`FileEntry`] and [`DuplicateGroup`](crate::DuplicateGroup).
+//! Recent-file results are plain `Vec<FileEntry>` and serialize the same way.
+
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::error::CoreResult;
+use crate::fs::duplicates::{CachedDuplicateGroup, DuplicateCache};
+use crate::fs::entry::FileEntry;
+use crate::fs::ops::read_directory;
+use crate::nav::filter::{filter_hidden, sort_entries, SortDirection, SortField};
+
+/// Serializes any value to pretty-printed JSON.
+pub fn to_json<T: Serialize + ?Sized>(value: &T) -> CoreResult<String> {
+    serde_json::to_string_pretty(value).map_err(|e| std::io::Error::from(e).into())
+}
+
+/// Lists a directory the way the file panel does (directories first, then
+/// by name), without hidden entries unless `show_hidden` is set.
+///
+/// # Errors
+///
+/// Same as [`read_directory`].
+pub fn list_directory(path: &Path, show_hidden: bool) -> CoreResult<Vec<FileEntry>> {
+    let entries = read_directory(path)?;
+    let visible = filter_hidden(&entries, show_hidden);
+    Ok(sort_entries(
+        &visible,
+        SortField::Name,
+        SortDirection::Ascending,
+        true,
+    ))
+}
+
+/// Reads a directory and returns its entries as a JSON array.
+///
+/// # Errors
+///
+/// Same as [`read_directory`].
+pub fn directory_json(path: &Path, show_hidden: bool) -> CoreResult<String> {
+    to_json(&list_directory(path, show_hidden)?)
+}
+
+/// JSON shape of a duplicate scan: the groups plus precomputed totals.
+#[derive(Serialize)]
+struct DuplicateSummary<'a> {
+    scan_root: Option<&'a Path>,
+    total_files: usize,
+    reclaimable_bytes: u64,
+    groups: &'a [CachedDuplicateGroup],
+}
+
+/// Serializes a duplicate scan result with its file count and reclaimable bytes.
+pub fn duplicates_json(cache: &DuplicateCache) -> CoreResult<String> {
+    to_json(&DuplicateSummary {
+        scan_root: cache.scan_root.as_deref(),
+        total_files: cache.total_files(),
+        reclaimable_bytes: cache.total_wasted(),
+        groups: &cache.groups,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn directory_json_lists_visible_entries() {
+        let tmp = TempDir::new().unwrap();
+        fs::write(tmp.path().join("a.txt"), "abc").unwrap();
+        fs::write(tmp.path().join(".hidden"), "").unwrap();
+        fs::create_dir(tmp.path().join("sub")).unwrap();
+
+        let json = directory_json(tmp.path(), false).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let names: Vec<&str> = value
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|e| e["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, vec!["sub", "a.txt"]);
+    }
+
+    #[test]
+    fn directory_json_includes_hidden_when_asked() {
+        let tmp = TempDir::new().unwrap();
+        fs::write(tmp.path().join(".hidden"), "").unwrap();
+
+        let entries: Vec<FileEntry> =
+            serde_json::from_str(&directory_json(tmp.path(), true).unwrap()).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert!(entries[0].is_hidden());
+    }
+
+    #[test]
+    fn directory_json_missing_dir_errors() {
+        let tmp = TempDir::new().unwrap();
+        assert!(directory_json(&tmp.path().join("nope"), false).is_err());
+    }
+
+    #[test]
+    fn duplicates_json_includes_totals() {
+        let tmp = TempDir::new().unwrap();
+        fs::write(tmp.path().join("a.txt"), "same").unwrap();
+        fs::write(tmp.path().join("b.txt"), "same").unwrap();
+        let cache = crate::fs::duplicates::scan_duplicates(
+            tmp.path(),
+            5,
+            true,
+            &Default::default(),
+            &crate::fs::DuplicateScanOptions::default(),
+        )
+        .unwrap();
+
+        let value: serde_json::Value =
+            serde_json::from_str(&duplicates_json(&cache).unwrap()).unwrap();
+        assert_eq!(value["total_files"], 2);
+        assert_eq!(value["reclaimable_bytes"], 4);
+        assert_eq!(value["groups"].as_array().unwrap().len(), 1);
+    }
+}
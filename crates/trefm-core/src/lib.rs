@@ -11,8 +11,10 @@
 //! - [`git`] — Git integration: file-level status and branch information via `git2`.
 //! - [`nav`] — Navigation logic: panels, history, bookmarks, sorting, filtering, and fuzzy search.
 //! - [`config`] — User-facing configuration (TOML-based settings, keymaps).
+//! - [`paths`] — Per-user config, cache and data directories (XDG with legacy fallbacks).
 //! - [`event`] — Event and command types for UI ↔ Core communication.
 //! - [`error`] — Unified error type ([`CoreError`]) and result alias ([`CoreResult`]).
+//! - `json` — JSON output of entries and scan results for scripting (feature `json`).
 
 pub mod action;
 pub mod config;
@@ -20,27 +22,35 @@ pub mod error;
 pub mod event;
 pub mod fs;
 pub mod git;
+#[cfg(feature = "json")]
+pub mod json;
 pub mod nav;
+pub mod paths;
 pub mod remote;
 
 pub use error::{CoreError, CoreResult};
 pub use event::{Command, Event};
 pub use fs::entry::FileEntry;
 pub use fs::ops::{
-    copy_file, delete_file, find_duplicate_files, find_duplicate_files_with_exclusions,
-    find_recent_files, move_file, read_directory, rename_file,
+    copy_file, copy_file_checked, copy_file_with_attrs, delete_file, duplicate_in_place,
+    find_duplicate_files, find_duplicate_files_with_algo, find_duplicate_files_with_exclusions,
+    find_recent_files, move_file, move_file_checked, read_directory, rename_file,
+    rename_file_checked, CopyAttrs, DuplicateScanOptions,
+};
+pub use fs::{
+    CachedDuplicateGroup, CachedFileInfo, DuplicateCache, DuplicateGroup, DuplicateIgnoreList,
+    DuplicateSort, HashAlgo, ImageInfo,
 };
-pub use fs::{CachedDuplicateGroup, CachedFileInfo, DuplicateCache, DuplicateGroup, ImageInfo};
 pub use nav::bookmarks::Bookmarks;
 pub use nav::filter::{
-    filter_by_extension, filter_hidden, fuzzy_filter, sort_entries, FuzzyMatch, SortDirection,
-    SortField,
+    filter_by_extension, filter_dirs_only, filter_fuzzy_name, filter_hidden, fuzzy_filter,
+    fuzzy_filter_with, sort_entries, FuzzyAlgorithm, FuzzyMatch, SortDirection, SortField,
 };
-pub use nav::history::History;
+pub use nav::history::{History, HistoryEntry};
 pub use nav::panel::{Panel, SinglePanel};
 
 pub use action::{Action, ActionCategory, ActionDescriptor, ActionRegistry};
-pub use config::keymap::Keymap;
+pub use config::keymap::{Keymap, KeymapProfile};
 pub use config::settings::Config;
 pub use config::theme::{parse_color, Theme};
 pub use remote::sftp::{RemoteSession, SftpConfig, SftpError};
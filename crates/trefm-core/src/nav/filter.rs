@@ -1,12 +1,15 @@
 //! Sorting and filtering for file entries.
 
+use fuzzy_matcher::clangd::ClangdMatcher;
 use fuzzy_matcher::skim::SkimMatcherV2;
 use fuzzy_matcher::FuzzyMatcher;
+use serde::{Deserialize, Serialize};
 
 use crate::fs::entry::FileEntry;
 
 /// The field by which entries are compared.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum SortField {
     /// Sort alphabetically by name (case-insensitive).
     Name,
@@ -18,8 +21,28 @@ pub enum SortField {
     Type,
 }
 
+impl SortField {
+    /// Name used for this field in config files (`default_sort`).
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Name => "name",
+            Self::Size => "size",
+            Self::Date => "date",
+            Self::Type => "type",
+        }
+    }
+
+    /// Parses a [`label`](Self::label), ignoring ASCII case.
+    pub fn from_label(label: &str) -> Option<Self> {
+        [Self::Name, Self::Size, Self::Date, Self::Type]
+            .into_iter()
+            .find(|field| field.label().eq_ignore_ascii_case(label))
+    }
+}
+
 /// Sort order.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum SortDirection {
     /// Smallest / earliest / A–Z first.
     Ascending,
@@ -81,14 +104,15 @@ fn extension_lower(entry: &FileEntry) -> String {
 }
 
 /// A file entry paired with its fuzzy match score and the byte indices
-/// in the entry name that matched the query.
+/// in the entry name that matched the query, for highlighting.
 #[derive(Debug, Clone)]
 pub struct FuzzyMatch {
     /// The matching file entry.
     entry: FileEntry,
     /// Match score (higher is better). `0` when the query was empty.
     score: i64,
-    /// Byte indices within `entry.name()` that contributed to the match.
+    /// Byte indices within `entry.name()` that contributed to the match,
+    /// in increasing order.
     matched_indices: Vec<usize>,
 }
 
@@ -105,18 +129,58 @@ impl FuzzyMatch {
         self.score
     }
 
-    /// Byte indices in the entry name that matched the query.
+    /// Byte indices in the entry name that matched the query, in
+    /// increasing order. Each is the start of a character, so the name can
+    /// be split at it. Empty when the query was empty.
     #[must_use]
     pub fn matched_indices(&self) -> &[usize] {
         &self.matched_indices
     }
 }
 
-/// Performs fuzzy matching of `query` against each entry's file name.
+/// How [`fuzzy_filter_with`] scores a name against the query.
+///
+/// Both keep the query's characters in order and report which bytes
+/// matched; they differ in how they rank the candidates.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FuzzyAlgorithm {
+    /// skim's Smith-Waterman-style scorer: rewards runs of consecutive
+    /// characters wherever they fall in the name.
+    #[default]
+    Skim,
+    /// clangd's scorer: strongly prefers matches at word starts (after
+    /// `_`, `-`, `.` or a camelCase hump) over ones scattered inside words.
+    Clangd,
+}
+
+impl FuzzyAlgorithm {
+    /// A matcher for this algorithm. Both use smart case: the query
+    /// matches case-sensitively only if it has an uppercase letter.
+    pub(crate) fn matcher(self) -> Box<dyn FuzzyMatcher> {
+        match self {
+            Self::Skim => Box::new(SkimMatcherV2::default()),
+            Self::Clangd => Box::new(ClangdMatcher::default().smart_case()),
+        }
+    }
+}
+
+/// Performs fuzzy matching of `query` against each entry's file name with
+/// the default [`FuzzyAlgorithm`].
 ///
 /// Returns a `Vec<FuzzyMatch>` sorted by score (highest first).
 /// When `query` is empty every entry is returned with a score of `0`.
 pub fn fuzzy_filter(entries: &[FileEntry], query: &str) -> Vec<FuzzyMatch> {
+    fuzzy_filter_with(entries, query, FuzzyAlgorithm::default())
+}
+
+/// [`fuzzy_filter`] scoring with `algorithm`. Entries with equal scores
+/// keep their input order.
+pub fn fuzzy_filter_with(
+    entries: &[FileEntry],
+    query: &str,
+    algorithm: FuzzyAlgorithm,
+) -> Vec<FuzzyMatch> {
     if query.is_empty() {
         return entries
             .iter()
@@ -128,7 +192,7 @@ pub fn fuzzy_filter(entries: &[FileEntry], query: &str) -> Vec<FuzzyMatch> {
             .collect();
     }
 
-    let matcher = SkimMatcherV2::default();
+    let matcher = algorithm.matcher();
 
     let mut matches: Vec<FuzzyMatch> = entries
         .iter()
@@ -138,15 +202,31 @@ pub fn fuzzy_filter(entries: &[FileEntry], query: &str) -> Vec<FuzzyMatch> {
                 .map(|(score, indices)| FuzzyMatch {
                     entry: e.clone(),
                     score,
-                    matched_indices: indices,
+                    matched_indices: byte_indices(e.name(), &indices),
                 })
         })
         .collect();
 
-    matches.sort_by(|a, b| b.score.cmp(&a.score));
+    matches.sort_by_key(|m| std::cmp::Reverse(m.score));
     matches
 }
 
+/// Turns the character positions a matcher reports into byte offsets in
+/// `text`, sorted and without duplicates.
+pub(crate) fn byte_indices(text: &str, char_indices: &[usize]) -> Vec<usize> {
+    let mut wanted = char_indices.to_vec();
+    wanted.sort_unstable();
+    wanted.dedup();
+    let mut wanted = wanted.into_iter().peekable();
+    let mut bytes = Vec::with_capacity(char_indices.len());
+    for (position, (offset, _)) in text.char_indices().enumerate() {
+        if wanted.next_if_eq(&position).is_some() {
+            bytes.push(offset);
+        }
+    }
+    bytes
+}
+
 /// Filters entries whose file extension matches any of the given `extensions`.
 ///
 /// Extension comparison is case-insensitive. Directories always pass the
@@ -178,6 +258,29 @@ pub fn filter_hidden(entries: &[FileEntry], show_hidden: bool) -> Vec<FileEntry>
     entries.iter().filter(|e| !e.is_hidden()).cloned().collect()
 }
 
+/// Keeps only directory entries, including the synthetic `..` row.
+///
+/// Returns a new `Vec` — the input is never mutated.
+pub fn filter_dirs_only(entries: &[FileEntry]) -> Vec<FileEntry> {
+    entries.iter().filter(|e| e.is_dir()).cloned().collect()
+}
+
+/// Keeps the entries whose name fuzzy-matches `query`, in their original
+/// order, plus the synthetic `..` row. An empty query keeps everything.
+///
+/// Unlike [`fuzzy_filter`], nothing is re-ordered by score.
+pub fn filter_fuzzy_name(entries: &[FileEntry], query: &str) -> Vec<FileEntry> {
+    if query.is_empty() {
+        return entries.to_vec();
+    }
+    let matcher = SkimMatcherV2::default();
+    entries
+        .iter()
+        .filter(|e| e.is_parent_link() || matcher.fuzzy_match(e.name(), query).is_some())
+        .cloned()
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -505,6 +608,95 @@ mod tests {
         assert!(!matches[0].matched_indices().is_empty());
     }
 
+    #[test]
+    fn matched_indices_point_at_the_query_characters() {
+        let tmp = TempDir::new().unwrap();
+        fs::write(tmp.path().join("hello_world.txt"), "").unwrap();
+        let entries = crate::fs::ops::read_directory(tmp.path()).unwrap();
+
+        for algorithm in [FuzzyAlgorithm::Skim, FuzzyAlgorithm::Clangd] {
+            let matches = fuzzy_filter_with(&entries, "hwt", algorithm);
+            let indices = matches[0].matched_indices();
+            assert_eq!(indices, [0, 6, 12], "{algorithm:?}");
+            assert!(indices.windows(2).all(|w| w[0] < w[1]), "{algorithm:?}");
+        }
+    }
+
+    #[test]
+    fn matched_indices_are_byte_offsets_in_wide_names() {
+        let tmp = TempDir::new().unwrap();
+        fs::write(tmp.path().join("작업_노트.md"), "").unwrap();
+        let entries = crate::fs::ops::read_directory(tmp.path()).unwrap();
+
+        let matches = fuzzy_filter(&entries, "노트");
+
+        let name = matches[0].entry().name();
+        let indices = matches[0].matched_indices();
+        assert_eq!(indices, [7, 10]);
+        let matched: String = indices
+            .iter()
+            .map(|&i| name[i..].chars().next().unwrap())
+            .collect();
+        assert_eq!(matched, "노트");
+    }
+
+    #[test]
+    fn byte_indices_sorts_and_skips_out_of_range_positions() {
+        assert_eq!(byte_indices("ab한c", &[3, 0, 2, 2, 9]), [0, 2, 5]);
+    }
+
+    fn ranked_names(entries: &[FileEntry], query: &str, algorithm: FuzzyAlgorithm) -> Vec<String> {
+        fuzzy_filter_with(entries, query, algorithm)
+            .iter()
+            .map(|m| m.entry().name().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn clangd_prefers_word_boundary_over_scattered_match() {
+        let tmp = TempDir::new().unwrap();
+        fs::write(tmp.path().join("digits.txt"), "").unwrap();
+        fs::write(tmp.path().join("tree_view.rs"), "").unwrap();
+        let mut entries = crate::fs::ops::read_directory(tmp.path()).unwrap();
+        entries.sort_by(|a, b| a.name().cmp(b.name()));
+
+        // "ts" runs together in the middle of "digits"; in "tree_view.rs"
+        // each letter starts a word.
+        assert_eq!(
+            ranked_names(&entries, "ts", FuzzyAlgorithm::Skim),
+            ["digits.txt", "tree_view.rs"]
+        );
+        assert_eq!(
+            ranked_names(&entries, "ts", FuzzyAlgorithm::Clangd),
+            ["tree_view.rs", "digits.txt"]
+        );
+    }
+
+    #[test]
+    fn clangd_reports_matched_indices() {
+        let tmp = TempDir::new().unwrap();
+        fs::write(tmp.path().join("main_panel.rs"), "").unwrap();
+        let entries = crate::fs::ops::read_directory(tmp.path()).unwrap();
+
+        let matches = fuzzy_filter_with(&entries, "mp", FuzzyAlgorithm::Clangd);
+
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].matched_indices(), [0, 5]);
+    }
+
+    #[test]
+    fn clangd_is_case_insensitive_for_lowercase_queries() {
+        let tmp = TempDir::new().unwrap();
+        fs::write(tmp.path().join("MyFile.txt"), "").unwrap();
+        let entries = crate::fs::ops::read_directory(tmp.path()).unwrap();
+
+        assert_eq!(
+            fuzzy_filter_with(&entries, "myfile", FuzzyAlgorithm::Clangd).len(),
+            1
+        );
+        assert!(fuzzy_filter_with(&entries, "MYFILE", FuzzyAlgorithm::Clangd).is_empty());
+    }
+
     #[test]
     fn fuzzy_filter_does_not_mutate_input() {
         let tmp = TempDir::new().unwrap();
@@ -693,4 +885,102 @@ mod tests {
         assert_eq!(filtered.len(), 1);
         assert_eq!(filtered[0].name(), "src");
     }
+
+    // =====================================================
+    // filter_dirs_only tests
+    // =====================================================
+
+    #[test]
+    fn filter_dirs_only_keeps_directories_of_mixed_list() {
+        let tmp = TempDir::new().unwrap();
+        let entries = create_test_entries(&tmp);
+
+        let filtered = filter_dirs_only(&entries);
+
+        let mut names: Vec<&str> = filtered.iter().map(|e| e.name()).collect();
+        names.sort_unstable();
+        assert_eq!(names, vec!["docs", "src"]);
+        assert!(filtered.iter().all(|e| e.is_dir()));
+        assert_eq!(entries.len(), 5);
+    }
+
+    #[test]
+    fn filter_dirs_only_excludes_files() {
+        let tmp = TempDir::new().unwrap();
+        fs::write(tmp.path().join("a.txt"), "").unwrap();
+        fs::write(tmp.path().join(".hidden"), "").unwrap();
+        let entries = crate::fs::ops::read_directory(tmp.path()).unwrap();
+
+        assert!(filter_dirs_only(&entries).is_empty());
+    }
+
+    #[test]
+    fn filter_dirs_only_keeps_parent_link() {
+        let tmp = TempDir::new().unwrap();
+        fs::write(tmp.path().join("a.txt"), "").unwrap();
+        let entries: Vec<FileEntry> = std::iter::once(FileEntry::parent_link(
+            tmp.path().parent().unwrap().to_path_buf(),
+        ))
+        .chain(crate::fs::ops::read_directory(tmp.path()).unwrap())
+        .collect();
+
+        let filtered = filter_dirs_only(&entries);
+
+        assert_eq!(filtered.len(), 1);
+        assert!(filtered[0].is_parent_link());
+    }
+
+    #[test]
+    fn filter_fuzzy_name_keeps_listing_order() {
+        let tmp = TempDir::new().unwrap();
+        let entries = sort_entries(
+            &create_test_entries(&tmp),
+            SortField::Name,
+            SortDirection::Descending,
+            false,
+        );
+
+        let filtered = filter_fuzzy_name(&entries, "r");
+
+        let names: Vec<&str> = filtered.iter().map(|e| e.name()).collect();
+        assert_eq!(names, vec!["src", "cherry.md", "apple.rs"]);
+    }
+
+    #[test]
+    fn filter_fuzzy_name_empty_query_keeps_all() {
+        let tmp = TempDir::new().unwrap();
+        let entries = create_test_entries(&tmp);
+
+        assert_eq!(filter_fuzzy_name(&entries, "").len(), entries.len());
+    }
+
+    #[test]
+    fn filter_fuzzy_name_keeps_parent_link() {
+        let tmp = TempDir::new().unwrap();
+        let entries: Vec<FileEntry> = std::iter::once(FileEntry::parent_link(
+            tmp.path().parent().unwrap().to_path_buf(),
+        ))
+        .chain(create_test_entries(&tmp))
+        .collect();
+
+        let filtered = filter_fuzzy_name(&entries, "bnn");
+
+        assert_eq!(filtered.len(), 2);
+        assert!(filtered[0].is_parent_link());
+        assert_eq!(filtered[1].name(), "banana.txt");
+    }
+
+    #[test]
+    fn sort_field_label_round_trips() {
+        for field in [
+            SortField::Name,
+            SortField::Size,
+            SortField::Date,
+            SortField::Type,
+        ] {
+            assert_eq!(SortField::from_label(field.label()), Some(field));
+        }
+        assert_eq!(SortField::from_label("Size"), Some(SortField::Size));
+        assert_eq!(SortField::from_label("colour"), None);
+    }
 }
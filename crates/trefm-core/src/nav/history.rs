@@ -1,7 +1,19 @@
 //! Navigation history with back/forward support.
+//!
+//! Each [`HistoryEntry`] remembers the entry that was selected when the
+//! directory was left, so stepping back or forward can put the cursor on it
+//! again.
 
 use std::path::PathBuf;
 
+/// A directory in the history and the entry selected when it was left.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistoryEntry {
+    pub dir: PathBuf,
+    /// Path of the selected entry, if any.
+    pub selected: Option<PathBuf>,
+}
+
 /// Immutable navigation history with back/forward stacks.
 ///
 /// Every mutation returns a **new** `History` instance, following the
@@ -10,8 +22,8 @@ use std::path::PathBuf;
 /// semantics as a web browser).
 #[derive(Debug, Clone, Default)]
 pub struct History {
-    back_stack: Vec<PathBuf>,
-    forward_stack: Vec<PathBuf>,
+    back_stack: Vec<HistoryEntry>,
+    forward_stack: Vec<HistoryEntry>,
 }
 
 impl History {
@@ -24,46 +36,55 @@ impl History {
     ///
     /// Returns a new `History`.
     pub fn push(&self, path: PathBuf) -> Self {
+        self.push_with_selected(path, None)
+    }
+
+    /// Like [`push`](Self::push), also recording the entry that was selected
+    /// in `path`.
+    pub fn push_with_selected(&self, path: PathBuf, selected: Option<PathBuf>) -> Self {
         let mut back_stack = self.back_stack.clone();
-        back_stack.push(path);
+        back_stack.push(HistoryEntry {
+            dir: path,
+            selected,
+        });
         Self {
             back_stack,
             forward_stack: Vec::new(),
         }
     }
 
-    /// Go back one step. Returns the new History and the path to navigate to,
-    /// or `None` if the back stack is empty.
-    pub fn go_back(&self) -> Option<(Self, PathBuf)> {
+    /// Go back one step. Returns the new History and the entry to navigate
+    /// to, or `None` if the back stack is empty.
+    pub fn go_back(&self) -> Option<(Self, HistoryEntry)> {
         if self.back_stack.is_empty() {
             return None;
         }
         let mut back_stack = self.back_stack.clone();
-        let path = back_stack.pop()?;
+        let entry = back_stack.pop()?;
         let mut forward_stack = self.forward_stack.clone();
-        forward_stack.push(path.clone());
+        forward_stack.push(entry.clone());
         let new_history = Self {
             back_stack,
             forward_stack,
         };
-        Some((new_history, path))
+        Some((new_history, entry))
     }
 
-    /// Go forward one step. Returns the new History and the path to navigate to,
-    /// or `None` if the forward stack is empty.
-    pub fn go_forward(&self) -> Option<(Self, PathBuf)> {
+    /// Go forward one step. Returns the new History and the entry to
+    /// navigate to, or `None` if the forward stack is empty.
+    pub fn go_forward(&self) -> Option<(Self, HistoryEntry)> {
         if self.forward_stack.is_empty() {
             return None;
         }
         let mut forward_stack = self.forward_stack.clone();
-        let path = forward_stack.pop()?;
+        let entry = forward_stack.pop()?;
         let mut back_stack = self.back_stack.clone();
-        back_stack.push(path.clone());
+        back_stack.push(entry.clone());
         let new_history = Self {
             back_stack,
             forward_stack,
         };
-        Some((new_history, path))
+        Some((new_history, entry))
     }
 
     /// Returns `true` if there is at least one entry on the back stack.
@@ -112,7 +133,7 @@ mod tests {
         let history = history.push(PathBuf::from("/home"));
 
         let (new_history, path) = history.go_back().unwrap();
-        assert_eq!(path, PathBuf::from("/home"));
+        assert_eq!(path.dir, PathBuf::from("/home"));
         assert!(!new_history.can_go_back());
         assert!(new_history.can_go_forward());
     }
@@ -131,7 +152,7 @@ mod tests {
         let (history, _) = history.go_back().unwrap();
         let (history, path) = history.go_forward().unwrap();
 
-        assert_eq!(path, PathBuf::from("/home"));
+        assert_eq!(path.dir, PathBuf::from("/home"));
         assert!(history.can_go_back());
         assert!(!history.can_go_forward());
     }
@@ -164,13 +185,13 @@ mod tests {
         let history = history.push(PathBuf::from("/c"));
 
         let (history, path) = history.go_back().unwrap();
-        assert_eq!(path, PathBuf::from("/c"));
+        assert_eq!(path.dir, PathBuf::from("/c"));
 
         let (history, path) = history.go_back().unwrap();
-        assert_eq!(path, PathBuf::from("/b"));
+        assert_eq!(path.dir, PathBuf::from("/b"));
 
         let (history, path) = history.go_back().unwrap();
-        assert_eq!(path, PathBuf::from("/a"));
+        assert_eq!(path.dir, PathBuf::from("/a"));
 
         assert!(history.go_back().is_none());
     }
@@ -182,20 +203,39 @@ mod tests {
         let history = history.push(PathBuf::from("/b"));
 
         let (history, path_b) = history.go_back().unwrap();
-        assert_eq!(path_b, PathBuf::from("/b"));
+        assert_eq!(path_b.dir, PathBuf::from("/b"));
 
         let (history, path_a) = history.go_back().unwrap();
-        assert_eq!(path_a, PathBuf::from("/a"));
+        assert_eq!(path_a.dir, PathBuf::from("/a"));
 
         let (history, fwd_a) = history.go_forward().unwrap();
-        assert_eq!(fwd_a, PathBuf::from("/a"));
+        assert_eq!(fwd_a.dir, PathBuf::from("/a"));
 
         let (history, fwd_b) = history.go_forward().unwrap();
-        assert_eq!(fwd_b, PathBuf::from("/b"));
+        assert_eq!(fwd_b.dir, PathBuf::from("/b"));
 
         assert!(!history.can_go_forward());
     }
 
+    #[test]
+    fn selection_travels_with_the_entry() {
+        let history = History::new()
+            .push_with_selected(PathBuf::from("/a"), Some(PathBuf::from("/a/notes.txt")));
+
+        let (history, entry) = history.go_back().unwrap();
+        assert_eq!(entry.selected, Some(PathBuf::from("/a/notes.txt")));
+
+        let (_, entry) = history.go_forward().unwrap();
+        assert_eq!(entry.selected, Some(PathBuf::from("/a/notes.txt")));
+    }
+
+    #[test]
+    fn plain_push_records_no_selection() {
+        let history = History::new().push(PathBuf::from("/a"));
+        let (_, entry) = history.go_back().unwrap();
+        assert_eq!(entry.selected, None);
+    }
+
     #[test]
     fn default_is_same_as_new() {
         let h1 = History::new();
@@ -2,9 +2,14 @@
 //!
 //! This module contains the [`panel::Panel`] trait, the [`panel::SinglePanel`]
 //! implementation, navigation [`history::History`], [`bookmarks::Bookmarks`],
-//! and entry [`filter`]ing/sorting (including fuzzy search).
+//! entry [`filter`]ing/sorting (including fuzzy search), remembered sorts
+//! ([`sort_memory::SortMemory`]), [`pins::Pins`] and recently visited
+//! directories ([`recent_dirs::RecentDirs`]).
 
 pub mod bookmarks;
 pub mod filter;
 pub mod history;
 pub mod panel;
+pub mod pins;
+pub mod recent_dirs;
+pub mod sort_memory;
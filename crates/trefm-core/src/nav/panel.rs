@@ -7,7 +7,7 @@
 use std::path::{Path, PathBuf};
 
 use crate::fs::entry::FileEntry;
-use crate::nav::history::History;
+use crate::nav::history::{History, HistoryEntry};
 
 /// Trait defining the interface for a file panel.
 ///
@@ -97,10 +97,30 @@ impl SinglePanel {
         self.with_selection(last)
     }
 
+    /// Reverses the displayed order of the entries without re-sorting,
+    /// keeping the cursor on the same entry. A leading `..` row stays on top.
+    pub fn with_reversed_entries(self) -> Self {
+        let pinned = usize::from(self.entries.first().is_some_and(FileEntry::is_parent_link));
+        let mut entries = self.entries;
+        entries[pinned..].reverse();
+        let selected_index = if entries.is_empty() || self.selected_index < pinned {
+            self.selected_index
+        } else {
+            entries.len() - 1 - (self.selected_index - pinned)
+        };
+        Self {
+            entries,
+            selected_index,
+            ..self
+        }
+    }
+
     /// Navigates backward in history. Returns `None` if there is no history.
     ///
-    /// The caller is responsible for reading the returned path and updating entries.
-    pub fn go_back(self) -> Option<(Self, PathBuf)> {
+    /// The caller is responsible for reading the returned directory and
+    /// updating entries, then putting the cursor back on the recorded
+    /// selection.
+    pub fn go_back(self) -> Option<(Self, HistoryEntry)> {
         let (new_history, path) = self.history.go_back()?;
         let panel = Self {
             history: new_history,
@@ -111,7 +131,7 @@ impl SinglePanel {
     }
 
     /// Navigates forward in history. Returns `None` if there is no forward entry.
-    pub fn go_forward(self) -> Option<(Self, PathBuf)> {
+    pub fn go_forward(self) -> Option<(Self, HistoryEntry)> {
         let (new_history, path) = self.history.go_forward()?;
         let panel = Self {
             history: new_history,
@@ -175,7 +195,10 @@ impl Panel for SinglePanel {
     }
 
     fn with_directory(self, path: PathBuf, entries: Vec<FileEntry>) -> Self {
-        let new_history = self.history.push(self.current_dir.clone());
+        let selected = self.selected_entry().map(|e| e.path().to_path_buf());
+        let new_history = self
+            .history
+            .push_with_selected(self.current_dir.clone(), selected);
         Self {
             current_dir: path,
             entries,
@@ -390,8 +413,9 @@ mod tests {
         let entries2 = make_entries(&tmp2, &["b.txt"]);
         let panel = panel.with_directory(tmp2.path().to_path_buf(), entries2);
 
-        let (panel, path) = panel.go_back().unwrap();
-        assert_eq!(path, tmp1.path());
+        let (panel, entry) = panel.go_back().unwrap();
+        assert_eq!(entry.dir, tmp1.path());
+        assert_eq!(entry.selected, Some(tmp1.path().join("a.txt")));
         assert_eq!(panel.selected_index(), 0);
     }
 
@@ -417,8 +441,8 @@ mod tests {
         let (panel, _) = panel.go_back().unwrap();
         assert!(panel.can_go_forward());
 
-        let (panel, path) = panel.go_forward().unwrap();
-        assert_eq!(path, tmp1.path());
+        let (panel, entry) = panel.go_forward().unwrap();
+        assert_eq!(entry.dir, tmp1.path());
         assert!(!panel.can_go_forward());
     }
 
@@ -458,4 +482,58 @@ mod tests {
         assert_eq!(panel.current_dir(), tmp.path());
         assert_eq!(panel.entries().len(), 2);
     }
+
+    fn names(panel: &SinglePanel) -> Vec<String> {
+        panel
+            .entries()
+            .iter()
+            .map(|e| e.name().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn with_reversed_entries_flips_order() {
+        let tmp = TempDir::new().unwrap();
+        let mut entries = make_entries(&tmp, &["a.txt", "b.txt", "c.txt"]);
+        entries.sort_by(|a, b| a.name().cmp(b.name()));
+        let panel = SinglePanel::new(tmp.path().to_path_buf(), entries);
+
+        let reversed = panel.clone().with_reversed_entries();
+        assert_eq!(names(&reversed), ["c.txt", "b.txt", "a.txt"]);
+        assert_eq!(names(&reversed.with_reversed_entries()), names(&panel));
+    }
+
+    #[test]
+    fn with_reversed_entries_cursor_follows_entry() {
+        let tmp = TempDir::new().unwrap();
+        let mut entries = make_entries(&tmp, &["a.txt", "b.txt", "c.txt", "d.txt"]);
+        entries.sort_by(|a, b| a.name().cmp(b.name()));
+        let panel = SinglePanel::new(tmp.path().to_path_buf(), entries).with_selection(1);
+        assert_eq!(panel.selected_entry().unwrap().name(), "b.txt");
+
+        let panel = panel.with_reversed_entries();
+        assert_eq!(panel.selected_index(), 2);
+        assert_eq!(panel.selected_entry().unwrap().name(), "b.txt");
+    }
+
+    #[test]
+    fn with_reversed_entries_keeps_parent_row_on_top() {
+        let tmp = TempDir::new().unwrap();
+        let mut entries = make_entries(&tmp, &["a.txt", "b.txt"]);
+        entries.sort_by(|a, b| a.name().cmp(b.name()));
+        entries.insert(0, FileEntry::parent_link(PathBuf::from("/")));
+        let panel = SinglePanel::new(tmp.path().to_path_buf(), entries).with_selection(2);
+
+        let panel = panel.with_reversed_entries();
+        assert_eq!(names(&panel), ["..", "b.txt", "a.txt"]);
+        assert_eq!(panel.selected_entry().unwrap().name(), "b.txt");
+    }
+
+    #[test]
+    fn with_reversed_entries_on_empty_panel() {
+        let tmp = TempDir::new().unwrap();
+        let panel = SinglePanel::new(tmp.path().to_path_buf(), vec![]).with_reversed_entries();
+        assert!(panel.entries().is_empty());
+        assert_eq!(panel.selected_index(), 0);
+    }
 }
@@ -0,0 +1,221 @@
+//! Entries pinned to the top of their directory.
+//!
+//! [`Pins`] records, for each directory, the names the user pinned there in
+//! the order they were pinned. A listing shows those entries first — after
+//! the synthetic `..` row — whatever the sort, and the rest keep their
+//! sorted order. It is serialised to TOML the same way as
+//! [`SortMemory`](super::sort_memory::SortMemory).
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{CoreError, CoreResult};
+use crate::fs::entry::FileEntry;
+
+/// Pinned entry names, keyed by directory path.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct Pins {
+    #[serde(default)]
+    dirs: BTreeMap<String, Vec<String>>,
+}
+
+impl Pins {
+    /// Names pinned in `dir`, in pin order.
+    #[must_use]
+    pub fn for_dir(&self, dir: &Path) -> &[String] {
+        self.dirs
+            .get(dir.to_string_lossy().as_ref())
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Whether `path` is pinned in its parent directory.
+    #[must_use]
+    pub fn is_pinned(&self, path: &Path) -> bool {
+        match (path.parent(), path.file_name()) {
+            (Some(dir), Some(name)) => self
+                .for_dir(dir)
+                .iter()
+                .any(|pinned| pinned.as_str() == name.to_string_lossy()),
+            _ => false,
+        }
+    }
+
+    /// Return a new `Pins` with `path` unpinned if it was pinned, else pinned
+    /// after the entries already pinned in its directory.
+    ///
+    /// A path without a parent or file name (such as `/`) is left as is.
+    #[must_use]
+    pub fn toggled(self, path: &Path) -> Self {
+        let (Some(dir), Some(name)) = (path.parent(), path.file_name()) else {
+            return self;
+        };
+        let key = dir.to_string_lossy().into_owned();
+        let name = name.to_string_lossy().into_owned();
+        let mut dirs = self.dirs;
+        let names = dirs.entry(key.clone()).or_default();
+        match names.iter().position(|pinned| *pinned == name) {
+            Some(index) => {
+                names.remove(index);
+                if names.is_empty() {
+                    dirs.remove(&key);
+                }
+            }
+            None => names.push(name),
+        }
+        Self { dirs }
+    }
+
+    /// `entries` of `dir` with the pinned ones moved to the front in pin
+    /// order. A leading `..` row stays first and the other entries keep
+    /// their relative order.
+    #[must_use]
+    pub fn pinned_first(&self, dir: &Path, entries: &[FileEntry]) -> Vec<FileEntry> {
+        let names = self.for_dir(dir);
+        if names.is_empty() {
+            return entries.to_vec();
+        }
+        let (parent, rest) = match entries.split_first() {
+            Some((first, rest)) if first.is_parent_link() => (Some(first), rest),
+            _ => (None, entries),
+        };
+        let pinned = names
+            .iter()
+            .filter_map(|name| rest.iter().find(|e| e.name() == name.as_str()));
+        let unpinned = rest
+            .iter()
+            .filter(|e| !names.iter().any(|name| name.as_str() == e.name()));
+        parent
+            .into_iter()
+            .chain(pinned)
+            .chain(unpinned)
+            .cloned()
+            .collect()
+    }
+
+    /// Load pins from a TOML file.
+    ///
+    /// Returns an error if the file cannot be read or parsed.
+    pub fn load_from_file(path: &Path) -> CoreResult<Self> {
+        let content = std::fs::read_to_string(path)?;
+        toml::from_str(&content).map_err(|e| CoreError::ConfigParse(e.to_string()))
+    }
+
+    /// Persist pins to a TOML file, creating parent directories.
+    pub fn save_to_file(&self, path: &Path) -> CoreResult<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let content =
+            toml::to_string_pretty(self).map_err(|e| CoreError::ConfigParse(e.to_string()))?;
+        std::fs::write(path, content)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn names(entries: &[FileEntry]) -> Vec<&str> {
+        entries.iter().map(|e| e.name()).collect()
+    }
+
+    fn listing(tmp: &TempDir) -> Vec<FileEntry> {
+        for name in ["a.txt", "b.txt", "README.md", "z.txt"] {
+            fs::write(tmp.path().join(name), "").unwrap();
+        }
+        let mut entries = crate::fs::ops::read_directory(tmp.path()).unwrap();
+        entries.sort_by(|a, b| a.name().cmp(b.name()));
+        entries
+    }
+
+    #[test]
+    fn toggling_pins_and_unpins() {
+        let path = Path::new("/project/README.md");
+        let pins = Pins::default().toggled(path);
+
+        assert!(pins.is_pinned(path));
+        assert_eq!(pins.for_dir(Path::new("/project")), ["README.md"]);
+        assert!(!pins.is_pinned(Path::new("/other/README.md")));
+
+        let pins = pins.toggled(path);
+        assert!(!pins.is_pinned(path));
+        assert_eq!(pins, Pins::default());
+    }
+
+    #[test]
+    fn pinned_entries_come_first_in_pin_order() {
+        let tmp = TempDir::new().unwrap();
+        let entries = listing(&tmp);
+        let pins = Pins::default()
+            .toggled(&tmp.path().join("z.txt"))
+            .toggled(&tmp.path().join("README.md"));
+
+        let arranged = pins.pinned_first(tmp.path(), &entries);
+
+        assert_eq!(names(&arranged), ["z.txt", "README.md", "a.txt", "b.txt"]);
+    }
+
+    #[test]
+    fn unpinned_remainder_keeps_its_order() {
+        let tmp = TempDir::new().unwrap();
+        let mut entries = listing(&tmp);
+        entries.reverse();
+        let pins = Pins::default().toggled(&tmp.path().join("README.md"));
+
+        let arranged = pins.pinned_first(tmp.path(), &entries);
+
+        assert_eq!(names(&arranged), ["README.md", "z.txt", "b.txt", "a.txt"]);
+    }
+
+    #[test]
+    fn parent_link_stays_first() {
+        let tmp = TempDir::new().unwrap();
+        let entries: Vec<FileEntry> =
+            std::iter::once(FileEntry::parent_link(tmp.path().parent().unwrap().into()))
+                .chain(listing(&tmp))
+                .collect();
+        let pins = Pins::default().toggled(&tmp.path().join("b.txt"));
+
+        let arranged = pins.pinned_first(tmp.path(), &entries);
+
+        assert!(arranged[0].is_parent_link());
+        assert_eq!(
+            names(&arranged[1..]),
+            ["b.txt", "README.md", "a.txt", "z.txt"]
+        );
+    }
+
+    #[test]
+    fn missing_pinned_entry_is_skipped() {
+        let tmp = TempDir::new().unwrap();
+        let entries = listing(&tmp);
+        let pins = Pins::default().toggled(&tmp.path().join("gone.txt"));
+
+        let arranged = pins.pinned_first(tmp.path(), &entries);
+
+        assert_eq!(names(&arranged), names(&entries));
+    }
+
+    #[test]
+    fn save_and_load_roundtrip() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("nested").join("pins.toml");
+        let pins = Pins::default()
+            .toggled(Path::new("/home/user/README.md"))
+            .toggled(Path::new("/home/user/notes"));
+
+        pins.save_to_file(&path).unwrap();
+        let loaded = Pins::load_from_file(&path).unwrap();
+
+        assert_eq!(loaded, pins);
+        assert_eq!(
+            loaded.for_dir(Path::new("/home/user")),
+            ["README.md", "notes"]
+        );
+    }
+}
@@ -0,0 +1,210 @@
+//! Directories visited recently, most recent first.
+//!
+//! [`RecentDirs`] lists each directory once: visiting one that is already
+//! listed moves it to the front, and only the newest [`RecentDirs::LIMIT`]
+//! are kept. Unlike [`History`](super::history::History) it is about where
+//! the user has been rather than how they got there, and it is serialised to
+//! TOML the same way as [`Pins`](super::pins::Pins).
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{CoreError, CoreResult};
+use crate::nav::filter::{byte_indices, FuzzyAlgorithm};
+
+/// Recently visited directory paths, newest first.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct RecentDirs {
+    #[serde(default)]
+    dirs: Vec<String>,
+}
+
+/// A recent directory whose path matched a query.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecentDirMatch {
+    pub path: PathBuf,
+    /// Byte indices in the path's display string that matched the query,
+    /// in increasing order.
+    pub matched_indices: Vec<usize>,
+}
+
+impl RecentDirs {
+    /// Most directories kept; the oldest visit drops off past this.
+    pub const LIMIT: usize = 50;
+
+    /// The directories, most recently visited first.
+    pub fn dirs(&self) -> impl Iterator<Item = &Path> {
+        self.dirs.iter().map(Path::new)
+    }
+
+    /// Return a new `RecentDirs` with `dir` at the front, removed from
+    /// wherever it was before.
+    #[must_use]
+    pub fn visited(self, dir: &Path) -> Self {
+        let dir = dir.to_string_lossy().into_owned();
+        let mut dirs = self.dirs;
+        if dirs.first() == Some(&dir) {
+            return Self { dirs };
+        }
+        dirs.retain(|known| *known != dir);
+        dirs.insert(0, dir);
+        dirs.truncate(Self::LIMIT);
+        Self { dirs }
+    }
+
+    /// The directories whose path fuzzy-matches `query`, best match first
+    /// and most recent first among equals. `skip` (usually the current
+    /// directory) is left out. An empty query keeps every directory in
+    /// visit order.
+    pub fn matching(
+        &self,
+        query: &str,
+        algorithm: FuzzyAlgorithm,
+        skip: &Path,
+    ) -> Vec<RecentDirMatch> {
+        let candidates = self.dirs.iter().filter(|dir| Path::new(dir) != skip);
+        if query.is_empty() {
+            return candidates
+                .map(|dir| RecentDirMatch {
+                    path: PathBuf::from(dir),
+                    matched_indices: Vec::new(),
+                })
+                .collect();
+        }
+        let matcher = algorithm.matcher();
+        let mut scored: Vec<(i64, RecentDirMatch)> = candidates
+            .filter_map(|dir| {
+                let (score, indices) = matcher.fuzzy_indices(dir, query)?;
+                Some((
+                    score,
+                    RecentDirMatch {
+                        path: PathBuf::from(dir),
+                        matched_indices: byte_indices(dir, &indices),
+                    },
+                ))
+            })
+            .collect();
+        scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+        scored.into_iter().map(|(_, m)| m).collect()
+    }
+
+    /// Load recent directories from a TOML file.
+    ///
+    /// Returns an error if the file cannot be read or parsed.
+    pub fn load_from_file(path: &Path) -> CoreResult<Self> {
+        let content = std::fs::read_to_string(path)?;
+        toml::from_str(&content).map_err(|e| CoreError::ConfigParse(e.to_string()))
+    }
+
+    /// Persist recent directories to a TOML file, creating parent directories.
+    pub fn save_to_file(&self, path: &Path) -> CoreResult<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let content =
+            toml::to_string_pretty(self).map_err(|e| CoreError::ConfigParse(e.to_string()))?;
+        std::fs::write(path, content)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn recent(paths: &[&str]) -> RecentDirs {
+        paths
+            .iter()
+            .rev()
+            .fold(RecentDirs::default(), |recent, path| {
+                recent.visited(Path::new(path))
+            })
+    }
+
+    fn listed(recent: &RecentDirs) -> Vec<&Path> {
+        recent.dirs().collect()
+    }
+
+    #[test]
+    fn newest_visit_comes_first() {
+        let recent = recent(&["/c", "/b", "/a"]);
+
+        assert_eq!(
+            listed(&recent),
+            [Path::new("/c"), Path::new("/b"), Path::new("/a")]
+        );
+    }
+
+    #[test]
+    fn revisiting_moves_to_front_without_duplicating() {
+        let recent = recent(&["/c", "/b", "/a"]).visited(Path::new("/a"));
+
+        assert_eq!(
+            listed(&recent),
+            [Path::new("/a"), Path::new("/c"), Path::new("/b")]
+        );
+        let same = recent.clone().visited(Path::new("/a"));
+        assert_eq!(same, recent);
+    }
+
+    #[test]
+    fn oldest_visits_drop_off_past_the_limit() {
+        let recent = (0..RecentDirs::LIMIT + 5).fold(RecentDirs::default(), |recent, i| {
+            recent.visited(Path::new(&format!("/dir{i}")))
+        });
+
+        let dirs = listed(&recent);
+        assert_eq!(dirs.len(), RecentDirs::LIMIT);
+        assert_eq!(
+            dirs[0],
+            Path::new(&format!("/dir{}", RecentDirs::LIMIT + 4))
+        );
+        assert_eq!(dirs[RecentDirs::LIMIT - 1], Path::new("/dir5"));
+    }
+
+    #[test]
+    fn matching_filters_by_fuzzy_query() {
+        let recent = recent(&["/home/me/projects/trefm", "/etc/nginx", "/home/me/photos"]);
+
+        let paths: Vec<PathBuf> = recent
+            .matching("prtr", FuzzyAlgorithm::Skim, Path::new("/"))
+            .into_iter()
+            .map(|m| m.path)
+            .collect();
+
+        assert_eq!(paths, [PathBuf::from("/home/me/projects/trefm")]);
+    }
+
+    #[test]
+    fn matching_skips_the_given_dir_and_keeps_order_for_empty_query() {
+        let recent = recent(&["/a", "/b", "/c"]);
+
+        let matches = recent.matching("", FuzzyAlgorithm::Skim, Path::new("/a"));
+
+        let paths: Vec<&Path> = matches.iter().map(|m| m.path.as_path()).collect();
+        assert_eq!(paths, [Path::new("/b"), Path::new("/c")]);
+        assert!(matches.iter().all(|m| m.matched_indices.is_empty()));
+    }
+
+    #[test]
+    fn matched_indices_point_into_the_path() {
+        let recent = recent(&["/srv/logs"]);
+
+        let matches = recent.matching("logs", FuzzyAlgorithm::Skim, Path::new("/"));
+
+        assert_eq!(matches[0].matched_indices, [5, 6, 7, 8]);
+    }
+
+    #[test]
+    fn save_and_load_roundtrip() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("nested").join("recent_dirs.toml");
+        let recent = recent(&["/home/user/notes", "/tmp"]);
+
+        recent.save_to_file(&path).unwrap();
+
+        assert_eq!(RecentDirs::load_from_file(&path).unwrap(), recent);
+    }
+}
@@ -0,0 +1,145 @@
+//! Remembered sort choices.
+//!
+//! [`SortMemory`] records the sort the user last picked, either for each
+//! directory or once for everything, so it can be restored when a panel
+//! returns to a directory or TreFM starts again. It is serialised to TOML
+//! the same way as [`Bookmarks`](super::bookmarks::Bookmarks).
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use super::filter::{SortDirection, SortField};
+use crate::error::{CoreError, CoreResult};
+
+/// A sort field together with its direction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SavedSort {
+    pub field: SortField,
+    pub direction: SortDirection,
+}
+
+impl SavedSort {
+    /// Name, ascending — what a panel starts with.
+    pub const DEFAULT: Self = Self {
+        field: SortField::Name,
+        direction: SortDirection::Ascending,
+    };
+}
+
+/// Last-used sorts: one global choice and one per directory.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct SortMemory {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    global: Option<SavedSort>,
+    /// Keyed by directory path.
+    #[serde(default)]
+    dirs: BTreeMap<String, SavedSort>,
+}
+
+impl SortMemory {
+    /// The sort last picked anywhere, if any.
+    #[must_use]
+    pub fn global(&self) -> Option<SavedSort> {
+        self.global
+    }
+
+    /// The sort last picked in `dir`, if any.
+    #[must_use]
+    pub fn for_dir(&self, dir: &Path) -> Option<SavedSort> {
+        self.dirs.get(dir.to_string_lossy().as_ref()).copied()
+    }
+
+    /// Return a new `SortMemory` with `sort` as the global choice.
+    #[must_use]
+    pub fn with_global(self, sort: SavedSort) -> Self {
+        Self {
+            global: Some(sort),
+            ..self
+        }
+    }
+
+    /// Return a new `SortMemory` remembering `sort` for `dir`.
+    #[must_use]
+    pub fn with_dir(self, dir: &Path, sort: SavedSort) -> Self {
+        let mut dirs = self.dirs;
+        dirs.insert(dir.to_string_lossy().into_owned(), sort);
+        Self { dirs, ..self }
+    }
+
+    /// Load remembered sorts from a TOML file.
+    ///
+    /// Returns an error if the file cannot be read or parsed.
+    pub fn load_from_file(path: &Path) -> CoreResult<Self> {
+        let content = std::fs::read_to_string(path)?;
+        toml::from_str(&content).map_err(|e| CoreError::ConfigParse(e.to_string()))
+    }
+
+    /// Persist remembered sorts to a TOML file, creating parent directories.
+    pub fn save_to_file(&self, path: &Path) -> CoreResult<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let content =
+            toml::to_string_pretty(self).map_err(|e| CoreError::ConfigParse(e.to_string()))?;
+        std::fs::write(path, content)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const SIZE_DESC: SavedSort = SavedSort {
+        field: SortField::Size,
+        direction: SortDirection::Descending,
+    };
+
+    #[test]
+    fn remembers_per_directory() {
+        let memory = SortMemory::default().with_dir(Path::new("/photos"), SIZE_DESC);
+
+        assert_eq!(memory.for_dir(Path::new("/photos")), Some(SIZE_DESC));
+        assert_eq!(memory.for_dir(Path::new("/music")), None);
+        assert_eq!(memory.global(), None);
+    }
+
+    #[test]
+    fn later_choice_replaces_earlier() {
+        let memory = SortMemory::default()
+            .with_global(SIZE_DESC)
+            .with_global(SavedSort::DEFAULT);
+
+        assert_eq!(memory.global(), Some(SavedSort::DEFAULT));
+    }
+
+    #[test]
+    fn save_and_load_roundtrip() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("nested").join("sort.toml");
+        let memory = SortMemory::default()
+            .with_global(SIZE_DESC)
+            .with_dir(Path::new("/home/user/photos"), SavedSort::DEFAULT);
+
+        memory.save_to_file(&path).unwrap();
+        let loaded = SortMemory::load_from_file(&path).unwrap();
+
+        assert_eq!(loaded, memory);
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.contains("field = \"size\""), "{content}");
+    }
+
+    #[test]
+    fn load_malformed_file_is_parse_error() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("sort.toml");
+        std::fs::write(&path, "[global]\nfield = \"colour\"\n").unwrap();
+
+        let err = SortMemory::load_from_file(&path).unwrap_err();
+
+        assert!(matches!(err, CoreError::ConfigParse(_)));
+    }
+}
@@ -0,0 +1,131 @@
+//! Per-user directories for TreFM's files.
+//!
+//! Each directory follows the [XDG Base Directory] spec: when
+//! `$XDG_CONFIG_HOME`, `$XDG_CACHE_HOME` or `$XDG_DATA_HOME` holds an
+//! absolute path, TreFM uses its `trefm` subdirectory. Otherwise it falls
+//! back to where the files have always lived, `~/.config/trefm` (and
+//! `/tmp` for the log), so existing installs keep finding them.
+//!
+//! [XDG Base Directory]: https://specifications.freedesktop.org/basedir-spec/latest/
+
+use std::ffi::OsString;
+use std::path::PathBuf;
+
+const APP_DIR: &str = "trefm";
+
+/// Settings, key bindings and themes (`default.toml`, `keymap.toml`, …).
+pub fn config_dir() -> PathBuf {
+    config_dir_from(&env_var)
+}
+
+/// Files that can be rebuilt at any time, such as the duplicate scan cache.
+pub fn cache_dir() -> PathBuf {
+    cache_dir_from(&env_var)
+}
+
+/// State the user built up: bookmarks, ignored duplicates, known SSH hosts.
+pub fn data_dir() -> PathBuf {
+    data_dir_from(&env_var)
+}
+
+/// Default TUI log file: `trefm.log` in the cache directory when
+/// `$XDG_CACHE_HOME` is set, else `/tmp/trefm.log`.
+pub fn log_file() -> PathBuf {
+    log_file_from(&env_var)
+}
+
+fn env_var(name: &str) -> Option<OsString> {
+    std::env::var_os(name)
+}
+
+fn config_dir_from(env: &dyn Fn(&str) -> Option<OsString>) -> PathBuf {
+    xdg_dir(env, "XDG_CONFIG_HOME").unwrap_or_else(|| legacy_dir(env))
+}
+
+fn cache_dir_from(env: &dyn Fn(&str) -> Option<OsString>) -> PathBuf {
+    xdg_dir(env, "XDG_CACHE_HOME").unwrap_or_else(|| legacy_dir(env))
+}
+
+fn data_dir_from(env: &dyn Fn(&str) -> Option<OsString>) -> PathBuf {
+    xdg_dir(env, "XDG_DATA_HOME").unwrap_or_else(|| legacy_dir(env))
+}
+
+fn log_file_from(env: &dyn Fn(&str) -> Option<OsString>) -> PathBuf {
+    xdg_dir(env, "XDG_CACHE_HOME")
+        .unwrap_or_else(|| PathBuf::from("/tmp"))
+        .join("trefm.log")
+}
+
+/// `$<var>/trefm`, if `var` is set to an absolute path. The spec says
+/// relative values are invalid and must be ignored.
+fn xdg_dir(env: &dyn Fn(&str) -> Option<OsString>, var: &str) -> Option<PathBuf> {
+    env(var)
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_absolute())
+        .map(|dir| dir.join(APP_DIR))
+}
+
+/// `~/.config/trefm`, or `/.config/trefm` when `$HOME` is unset.
+fn legacy_dir(env: &dyn Fn(&str) -> Option<OsString>) -> PathBuf {
+    env("HOME")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("/"))
+        .join(".config")
+        .join(APP_DIR)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn env(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<OsString> {
+        let vars: HashMap<String, OsString> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), OsString::from(v)))
+            .collect();
+        move |name| vars.get(name).cloned()
+    }
+
+    #[test]
+    fn xdg_vars_select_their_directories() {
+        let env = env(&[
+            ("HOME", "/home/alice"),
+            ("XDG_CONFIG_HOME", "/xdg/config"),
+            ("XDG_CACHE_HOME", "/xdg/cache"),
+            ("XDG_DATA_HOME", "/xdg/data"),
+        ]);
+        assert_eq!(config_dir_from(&env), PathBuf::from("/xdg/config/trefm"));
+        assert_eq!(cache_dir_from(&env), PathBuf::from("/xdg/cache/trefm"));
+        assert_eq!(data_dir_from(&env), PathBuf::from("/xdg/data/trefm"));
+        assert_eq!(
+            log_file_from(&env),
+            PathBuf::from("/xdg/cache/trefm/trefm.log")
+        );
+    }
+
+    #[test]
+    fn absent_vars_fall_back_to_legacy_paths() {
+        let env = env(&[("HOME", "/home/alice")]);
+        let legacy = PathBuf::from("/home/alice/.config/trefm");
+        assert_eq!(config_dir_from(&env), legacy);
+        assert_eq!(cache_dir_from(&env), legacy);
+        assert_eq!(data_dir_from(&env), legacy);
+        assert_eq!(log_file_from(&env), PathBuf::from("/tmp/trefm.log"));
+    }
+
+    #[test]
+    fn relative_xdg_value_is_ignored() {
+        let env = env(&[("HOME", "/home/alice"), ("XDG_CONFIG_HOME", "relative/dir")]);
+        assert_eq!(
+            config_dir_from(&env),
+            PathBuf::from("/home/alice/.config/trefm")
+        );
+    }
+
+    #[test]
+    fn missing_home_falls_back_to_root() {
+        let env = env(&[]);
+        assert_eq!(config_dir_from(&env), PathBuf::from("/.config/trefm"));
+    }
+}
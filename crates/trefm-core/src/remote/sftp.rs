@@ -57,7 +57,7 @@ pub enum SftpError {
     #[error("session disconnected")]
     Disconnected,
 
-    #[error("host key verification failed for {host}:{port}\nExpected: {expected}\nReceived: {received}\n\nWARNING: Host key has changed! This could indicate a man-in-the-middle attack.\nIf you trust this new key, remove the old entry from ~/.config/trefm/known_hosts")]
+    #[error("host key verification failed for {host}:{port}\nExpected: {expected}\nReceived: {received}\n\nWARNING: Host key has changed! This could indicate a man-in-the-middle attack.\nIf you trust this new key, remove the old entry from known_hosts in the TreFM data directory ($XDG_DATA_HOME/trefm or ~/.config/trefm)")]
     HostKeyMismatch {
         host: String,
         port: u16,
@@ -103,14 +103,7 @@ impl SshHandler {
             return Ok(path.clone());
         }
 
-        let home = std::env::var("HOME").map_err(|_| {
-            std::io::Error::new(
-                std::io::ErrorKind::NotFound,
-                "HOME environment variable not set",
-            )
-        })?;
-        let config_dir = PathBuf::from(home).join(".config").join("trefm");
-        Ok(config_dir.join("known_hosts"))
+        Ok(crate::paths::data_dir().join("known_hosts"))
     }
 
     /// Computes the SHA-256 fingerprint of a public key.
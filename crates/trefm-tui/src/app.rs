@@ -1,21 +1,56 @@
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use trefm_core::action::ActionRegistry;
 use trefm_core::config::keymap::Keymap;
-use trefm_core::config::settings::Config;
+use trefm_core::config::local::LOCAL_CONFIG_FILE;
+use trefm_core::config::settings::{
+    Config, ConfirmScope, NameTruncation, OpenOnEnter, OpenWith, RememberSort, ResultPaths,
+};
 use trefm_core::config::theme::Theme;
+use trefm_core::error::CoreError;
 use trefm_core::event::Command;
+use trefm_core::fs::category::{categorize, FileCategory};
+use trefm_core::fs::command::{expand_template, is_executable, CommandOutput};
+use trefm_core::fs::conflict::{
+    execute_paste_step, ConflictChoice, PasteMode, PasteQueue, PasteStep,
+};
+use trefm_core::fs::diff::{file_diff, side_by_side, DiffLine, DiffRow};
 use trefm_core::fs::entry::FileEntry;
-use trefm_core::fs::ops::{find_recent_files, read_directory};
+use trefm_core::fs::exclude::ExcludeSet;
+use trefm_core::fs::format::SizeUnits;
+use trefm_core::fs::ops::{
+    count_entries, delete_file, duplicate_in_place, find_recent_files, read_directory,
+    rename_file_checked,
+};
+use trefm_core::fs::preview::{is_binary, is_image, read_directory_tree, tree_to_text, TreeIndent};
+use trefm_core::fs::search::{ContentMatch, ContentSearchResult, SearchProgress};
 use trefm_core::git::branch::{get_branch_info, BranchInfo};
-use trefm_core::git::status::{find_repo_root, get_file_statuses, GitFileStatus};
+use trefm_core::git::status::{
+    changed_files, get_file_statuses, get_ignored_paths, repo_relative_path, GitFileStatus,
+    RepoRootCache,
+};
 use trefm_core::nav::bookmarks::Bookmarks;
-use trefm_core::nav::filter::{fuzzy_filter, sort_entries, FuzzyMatch, SortDirection, SortField};
+use trefm_core::nav::filter::{
+    filter_dirs_only, filter_fuzzy_name, fuzzy_filter_with, sort_entries, FuzzyAlgorithm,
+    FuzzyMatch, SortDirection, SortField,
+};
+use trefm_core::nav::history::HistoryEntry;
 use trefm_core::nav::panel::{Panel, SinglePanel};
-use trefm_core::{CachedDuplicateGroup, DuplicateCache};
+use trefm_core::nav::pins::Pins;
+use trefm_core::nav::recent_dirs::{RecentDirMatch, RecentDirs};
+use trefm_core::nav::sort_memory::{SavedSort, SortMemory};
+use trefm_core::{CachedDuplicateGroup, DuplicateCache, DuplicateIgnoreList, DuplicateSort};
 
 use crate::background::ScanStatus;
+use crate::notifications::{NotificationLevel, Notifications};
+use crate::oplog::{OperationKind, OperationLog};
+use crate::terminal_emu::layout::TerminalLayout;
+use crate::terminal_emu::selection::Selection;
+use crate::ui::panel::EntryMarkers;
 use crate::ui::remote_connect::ConnectFormState;
 
 /// Application mode — determines how input is routed.
@@ -24,6 +59,8 @@ pub enum AppMode {
     Normal,
     Search(String),
     Rename(String),
+    /// Naming the current tab — the string is the name being typed.
+    RenameTab(String),
     Confirm(ConfirmAction),
     Help,
     /// Adding a bookmark — the string is the label being typed.
@@ -32,10 +69,32 @@ pub enum AppMode {
     BookmarkList {
         selected: usize,
     },
+    /// Typing a name filter that narrows the active panel as it changes.
+    Filter(String),
     /// Viewing recently changed files.
     RecentFiles,
+    /// Switching to a recently visited directory — `query` filters the
+    /// list and `selected` is the cursor index within the matches.
+    RecentDirs {
+        query: String,
+        selected: usize,
+    },
     /// Viewing duplicate files.
     DuplicateFiles,
+    /// Typing a content search query.
+    ContentSearchInput(String),
+    /// Viewing (possibly still arriving) content search results.
+    ContentSearch,
+    /// Viewing files with uncommitted git changes.
+    GitChanges,
+    /// Viewing the operation log — `scroll` is the index of the top row, newest first.
+    OperationLog {
+        scroll: usize,
+    },
+    /// Viewing recent status messages — `scroll` is the index of the top one, newest first.
+    Notifications {
+        scroll: usize,
+    },
     /// Sort field selection popup — `selected` is the cursor index (0..4).
     SortSelect {
         selected: usize,
@@ -44,15 +103,28 @@ pub enum AppMode {
     Pager {
         scroll: usize,
     },
+    /// Following the log file in the pager view; `scroll` works as in [`AppMode::Pager`].
+    LogViewer {
+        scroll: usize,
+    },
+    /// Side-by-side diff of the selected file against the same-named file
+    /// in the other panel; `scroll` works as in [`AppMode::Pager`].
+    Diff {
+        scroll: usize,
+    },
     /// Command Palette — fuzzy-search actions to execute.
     CommandPalette {
         query: String,
         selected: usize,
     },
+    /// Typing a command template to run on the selected file.
+    RunCommand(String),
     /// Remote connection form.
     RemoteConnect,
     /// Terminal mode — keyboard input goes to embedded terminal.
     Terminal,
+    /// Selecting a rectangle of terminal text to copy.
+    TerminalSelect(Selection),
 }
 
 /// What action is pending user confirmation.
@@ -60,6 +132,124 @@ pub enum AppMode {
 pub enum ConfirmAction {
     Delete(Vec<PathBuf>),
     DeleteDuplicate(PathBuf),
+    /// A paste destination already exists; carries the rest of the paste.
+    PasteConflict(PasteQueue),
+    /// Quit requested while background operations are still running.
+    Quit,
+    /// Closing every tab but the active one in the active panel.
+    CloseOtherTabs,
+    /// Running an executable file; its output goes to the pager.
+    Execute(PathBuf),
+    /// Renaming `src` to `name` would replace an existing entry.
+    RenameOverwrite {
+        src: PathBuf,
+        name: String,
+    },
+    /// Entering a directory with more than `large_dir_threshold` entries.
+    EnterLargeDir {
+        dir: PathBuf,
+        landing: DirLanding,
+    },
+}
+
+/// Where [`App::enter_dir_checked`] shows the directory it enters.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DirLanding {
+    /// The active panel, cursor at the top.
+    Here,
+    /// The active panel, cursor on this entry when the directory lists it.
+    Select(PathBuf),
+    /// The active panel's parent, cursor on the directory just left.
+    Parent,
+    /// A new tab in the active panel.
+    NewTab,
+    /// The inactive panel; the active one keeps the focus.
+    OtherPanel,
+}
+
+/// A duplicate scan limited to one directory's subtree.
+///
+/// While present, the duplicate view shows its groups instead of the
+/// global cache; opening the view with `D` drops it. It is never saved.
+#[derive(Debug, Clone, Default)]
+pub struct LocalDuplicateScan {
+    /// Identifies the scan. Messages carrying another id come from a
+    /// superseded scan and are ignored.
+    pub id: u64,
+    pub root: PathBuf,
+    /// The background scan has not reported completion yet.
+    pub running: bool,
+    pub cache: DuplicateCache,
+}
+
+/// State of the recursive content search.
+///
+/// The search itself runs on a background thread; results and progress are
+/// fed back through the `with_content_search_*` methods.
+#[derive(Debug, Clone, Default)]
+pub struct ContentSearchState {
+    /// Identifies the current search. Messages carrying another id come
+    /// from a superseded search and are ignored.
+    pub id: u64,
+    pub query: String,
+    pub results: Vec<ContentMatch>,
+    pub progress: SearchProgress,
+    pub selected: usize,
+    /// The background search has not reported completion yet.
+    pub running: bool,
+    /// The search was stopped before it walked the whole tree.
+    pub cancelled: bool,
+    cancel: Option<Arc<AtomicBool>>,
+}
+
+impl ContentSearchState {
+    /// Progress line, e.g. `"searching… 2,340 files, 15 matches"`.
+    pub fn status_line(&self) -> String {
+        let counts = format!(
+            "{} files, {} matches",
+            format_count(self.progress.files_scanned),
+            format_count(self.progress.matches)
+        );
+        if self.running {
+            format!("searching… {counts}")
+        } else if self.cancelled {
+            format!("stopped — {counts}")
+        } else {
+            counts
+        }
+    }
+}
+
+/// Levels below the selected directory that a copied tree goes down.
+pub const TREE_COPY_MAX_DEPTH: usize = 4;
+/// Entries after which a copied tree stops.
+pub const TREE_COPY_MAX_ENTRIES: usize = 500;
+
+/// Formats a count with thousands separators (`2340` → `"2,340"`).
+fn format_count(n: usize) -> String {
+    let digits = n.to_string();
+    let mut out = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            out.push(',');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// An external program the event loop should start for a file.
+///
+/// [`App`] cannot suspend the terminal itself, so it records the request and
+/// the event loop in `main.rs` carries it out.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Launch {
+    /// Open the file in `$EDITOR`, suspending the TUI until it exits.
+    Editor(PathBuf),
+    /// Hand the file to the system opener, which picks an app by file type.
+    System(PathBuf),
+    /// Start a configured opener (`argv`) without waiting for it.
+    Detached(Vec<String>),
 }
 
 /// Context for an active remote SSH/SFTP session.
@@ -75,33 +265,59 @@ pub struct RemoteContext {
 ///
 /// SinglePanel handles cursor, entries, and navigation history.
 /// This wrapper adds view concerns: hidden file toggle, sort field/direction.
+/// A directory's `.trefm.toml` overrides the panel's own hidden-file and
+/// sort settings while the panel shows that directory.
 #[derive(Debug, Clone)]
 pub struct PanelState {
     pub(crate) inner: SinglePanel,
     show_hidden: bool,
     sort_field: SortField,
     sort_direction: SortDirection,
+    /// Settings from the current directory's `.trefm.toml`, if it has one.
+    local: Option<View>,
+    /// Whether listings start with a synthetic `..` row.
+    parent_entry: bool,
+    /// Only files of this category are listed; directories always are.
+    category: Option<FileCategory>,
+    /// Whether files are hidden so that only directories are listed.
+    dirs_only: bool,
+    /// Entries listed first, after the `..` row, whatever the sort.
+    pins: Pins,
+    /// Only entries whose name fuzzy-matches this are listed. Cleared when
+    /// the panel changes directory.
+    name_filter: String,
+}
+
+/// Hidden-file and sort settings a listing is built with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct View {
+    show_hidden: bool,
+    sort_field: SortField,
+    dirs_first: bool,
 }
 
 impl PanelState {
     /// Creates a new panel state from a directory path.
     pub fn from_dir(path: &Path) -> anyhow::Result<Self> {
         let current_dir = path.canonicalize()?;
-        let raw_entries = read_directory(&current_dir)?;
-        let sorted = sort_entries(
-            &raw_entries,
-            SortField::Name,
-            SortDirection::Ascending,
-            true,
-        );
-        let visible = filter_hidden(&sorted, false);
-        let inner = SinglePanel::new(current_dir, visible);
-
-        Ok(Self {
-            inner,
+        let panel = Self {
+            inner: SinglePanel::new(current_dir.clone(), Vec::new()),
             show_hidden: false,
             sort_field: SortField::Name,
             sort_direction: SortDirection::Ascending,
+            local: None,
+            parent_entry: false,
+            category: None,
+            dirs_only: false,
+            pins: Pins::default(),
+            name_filter: String::new(),
+        };
+        let local = panel.local_view(&current_dir);
+        let visible = panel.list(&current_dir, local)?;
+        Ok(Self {
+            inner: panel.inner.with_entries(visible),
+            local,
+            ..panel
         })
     }
 
@@ -117,22 +333,154 @@ impl PanelState {
         self.inner.selected_index()
     }
 
+    /// The entry under the cursor; `None` on the `..` row, which is not a
+    /// real entry to operate on.
     pub fn selected_entry(&self) -> Option<&FileEntry> {
-        self.inner.selected_entry()
+        self.inner
+            .selected_entry()
+            .filter(|entry| !entry.is_parent_link())
+    }
+
+    /// Whether the cursor is on the synthetic `..` row.
+    pub fn on_parent_entry(&self) -> bool {
+        self.inner
+            .selected_entry()
+            .is_some_and(FileEntry::is_parent_link)
     }
 
     pub fn show_hidden(&self) -> bool {
-        self.show_hidden
+        self.view(self.local).show_hidden
     }
 
     pub fn sort_field(&self) -> SortField {
-        self.sort_field
+        self.view(self.local).sort_field
     }
 
     pub fn sort_direction(&self) -> SortDirection {
         self.sort_direction
     }
 
+    pub fn category_filter(&self) -> Option<FileCategory> {
+        self.category
+    }
+
+    pub fn dirs_only(&self) -> bool {
+        self.dirs_only
+    }
+
+    pub fn name_filter(&self) -> &str {
+        &self.name_filter
+    }
+
+    /// `local` if a directory-local config applies, else the panel's own settings.
+    fn view(&self, local: Option<View>) -> View {
+        local.unwrap_or(View {
+            show_hidden: self.show_hidden,
+            sort_field: self.sort_field,
+            dirs_first: true,
+        })
+    }
+
+    /// Reads `dir/.trefm.toml` on top of the panel's own settings.
+    ///
+    /// Returns `None` when there is no such file, or when it cannot be
+    /// parsed (a warning is logged and the panel's settings apply).
+    fn local_view(&self, dir: &Path) -> Option<View> {
+        let path = dir.join(LOCAL_CONFIG_FILE);
+        if !path.is_file() {
+            return None;
+        }
+        let mut base = Config::default();
+        base.general.show_hidden = self.show_hidden;
+        base.general.default_sort = self.sort_field.label().to_string();
+        match Config::merge_local(&base, &path) {
+            Ok(merged) => Some(View {
+                show_hidden: merged.general.show_hidden,
+                sort_field: SortField::from_label(&merged.general.default_sort)
+                    .unwrap_or(self.sort_field),
+                dirs_first: merged.general.sort_dir_first,
+            }),
+            Err(e) => {
+                tracing::warn!("Ignoring {}: {e}", path.display());
+                None
+            }
+        }
+    }
+
+    /// Reads, sorts and filters `dir` using `local` or the panel's settings.
+    fn list(&self, dir: &Path, local: Option<View>) -> anyhow::Result<Vec<FileEntry>> {
+        let view = self.view(local);
+        let raw_entries = read_directory(dir)?;
+        load_sort_metadata(&raw_entries, view.sort_field);
+        let sorted = sort_entries(
+            &raw_entries,
+            view.sort_field,
+            self.sort_direction,
+            view.dirs_first,
+        );
+        let visible = self.filter_listing(&filter_hidden(&sorted, view.show_hidden));
+        Ok(self.with_parent_link(dir, self.pins.pinned_first(dir, &visible)))
+    }
+
+    /// Applies the category, directories-only and name filters to `entries`.
+    fn filter_listing(&self, entries: &[FileEntry]) -> Vec<FileEntry> {
+        let visible = filter_category(entries, self.category);
+        let visible = if self.dirs_only {
+            filter_dirs_only(&visible)
+        } else {
+            visible
+        };
+        filter_fuzzy_name(&visible, &self.name_filter)
+    }
+
+    /// Prepends the `..` row to `entries` when enabled and `dir` has a parent.
+    fn with_parent_link(&self, dir: &Path, entries: Vec<FileEntry>) -> Vec<FileEntry> {
+        match dir.parent() {
+            Some(parent) if self.parent_entry => {
+                std::iter::once(FileEntry::parent_link(parent.to_path_buf()))
+                    .chain(entries)
+                    .collect()
+            }
+            _ => entries,
+        }
+    }
+
+    /// Loaded entries without the `..` row.
+    fn real_entries(&self) -> Vec<FileEntry> {
+        self.entries()
+            .iter()
+            .filter(|entry| !entry.is_parent_link())
+            .cloned()
+            .collect()
+    }
+
+    /// Show or hide the `..` row, keeping the cursor on its entry.
+    pub fn with_parent_entry(&self, show: bool) -> Self {
+        let panel = Self {
+            parent_entry: show,
+            ..self.clone()
+        };
+        let entries = panel.with_parent_link(self.current_dir(), self.real_entries());
+        panel.with_relisted(entries)
+    }
+
+    /// Installs `entries`, keeping the cursor on the previously selected entry.
+    fn with_relisted(self, entries: Vec<FileEntry>) -> Self {
+        let selected_index = self
+            .inner
+            .selected_entry()
+            .and_then(|sel| entries.iter().position(|e| e.path() == sel.path()))
+            .unwrap_or(self.inner.selected_index());
+        Self {
+            inner: self
+                .inner
+                .clone()
+                .with_entries(entries)
+                .with_selection(selected_index),
+            ..self
+        }
+    }
+
     /// Move cursor down by one.
     pub fn with_cursor_down(self) -> Self {
         Self {
@@ -176,45 +524,42 @@ impl PanelState {
     /// Navigate into a directory, producing a new PanelState.
     pub fn navigate_to(&self, path: &Path) -> anyhow::Result<Self> {
         let current_dir = path.canonicalize()?;
-        let raw_entries = read_directory(&current_dir)?;
-        let sorted = sort_entries(&raw_entries, self.sort_field, self.sort_direction, true);
-        let visible = filter_hidden(&sorted, self.show_hidden);
+        let unfiltered = self.without_name_filter();
+        let local = unfiltered.local_view(&current_dir);
+        let visible = unfiltered.list(&current_dir, local)?;
         let inner = self.inner.clone().with_directory(current_dir, visible);
 
         Ok(Self {
             inner,
-            show_hidden: self.show_hidden,
-            sort_field: self.sort_field,
-            sort_direction: self.sort_direction,
+            local,
+            ..unfiltered
         })
     }
 
-    /// Go up to the parent directory.
+    /// This panel with the name filter cleared, entries untouched.
+    fn without_name_filter(&self) -> Self {
+        Self {
+            name_filter: String::new(),
+            ..self.clone()
+        }
+    }
+
+    /// Go up to the parent directory, with the cursor on the directory just
+    /// left when the parent lists it.
     pub fn go_up(&self) -> anyhow::Result<Self> {
-        let parent = self
-            .inner
-            .current_dir()
-            .parent()
-            .unwrap_or(self.inner.current_dir())
-            .to_path_buf();
-        self.navigate_to(&parent)
+        let child = self.inner.current_dir();
+        let parent = child.parent().unwrap_or(child).to_path_buf();
+        let panel = self.navigate_to(&parent)?;
+        match panel.entries().iter().position(|e| e.path() == child) {
+            Some(idx) => Ok(panel.with_cursor_to(idx)),
+            None => Ok(panel),
+        }
     }
 
     /// Go back in navigation history.
     pub fn go_back(&self) -> anyhow::Result<Option<Self>> {
         match self.inner.clone().go_back() {
-            Some((new_inner, path)) => {
-                let raw_entries = read_directory(&path)?;
-                let sorted = sort_entries(&raw_entries, self.sort_field, self.sort_direction, true);
-                let visible = filter_hidden(&sorted, self.show_hidden);
-                let new_inner = new_inner.with_entries(visible);
-                Ok(Some(Self {
-                    inner: new_inner,
-                    show_hidden: self.show_hidden,
-                    sort_field: self.sort_field,
-                    sort_direction: self.sort_direction,
-                }))
-            }
+            Some((new_inner, entry)) => Ok(Some(self.revisit(new_inner, &entry)?)),
             None => Ok(None),
         }
     }
@@ -222,56 +567,133 @@ impl PanelState {
     /// Go forward in navigation history.
     pub fn go_forward(&self) -> anyhow::Result<Option<Self>> {
         match self.inner.clone().go_forward() {
-            Some((new_inner, path)) => {
-                let raw_entries = read_directory(&path)?;
-                let sorted = sort_entries(&raw_entries, self.sort_field, self.sort_direction, true);
-                let visible = filter_hidden(&sorted, self.show_hidden);
-                let new_inner = new_inner.with_entries(visible);
-                Ok(Some(Self {
-                    inner: new_inner,
-                    show_hidden: self.show_hidden,
-                    sort_field: self.sort_field,
-                    sort_direction: self.sort_direction,
-                }))
-            }
+            Some((new_inner, entry)) => Ok(Some(self.revisit(new_inner, &entry)?)),
             None => Ok(None),
         }
     }
 
+    /// Relists the directory a history step moved `inner` to, with the
+    /// cursor on the entry selected when it was left. If that entry is gone
+    /// the cursor starts at the top.
+    fn revisit(&self, inner: SinglePanel, entry: &HistoryEntry) -> anyhow::Result<Self> {
+        let path = entry.dir.as_path();
+        let unfiltered = self.without_name_filter();
+        let local = unfiltered.local_view(path);
+        let visible = unfiltered.list(path, local)?;
+        let selected_index = entry
+            .selected
+            .as_ref()
+            .and_then(|selected| visible.iter().position(|e| e.path() == selected))
+            .unwrap_or(0);
+        Ok(Self {
+            inner: inner.with_entries(visible).with_selection(selected_index),
+            local,
+            ..unfiltered
+        })
+    }
+
     /// Refresh the current directory listing.
+    ///
+    /// The cursor stays on the previously selected entry if it still exists,
+    /// otherwise the old index is kept (clamped to the new bounds).
+    /// `.trefm.toml` is read again, so edits to it take effect.
     pub fn refresh(&self) -> anyhow::Result<Self> {
-        let raw_entries = read_directory(self.inner.current_dir())?;
-        let sorted = sort_entries(&raw_entries, self.sort_field, self.sort_direction, true);
-        let visible = filter_hidden(&sorted, self.show_hidden);
-        let inner = self.inner.clone().with_entries(visible);
+        let local = self.local_view(self.inner.current_dir());
+        let visible = self.list(self.inner.current_dir(), local)?;
+        let selected_index = self
+            .inner
+            .selected_entry()
+            .and_then(|sel| visible.iter().position(|e| e.path() == sel.path()))
+            .unwrap_or(self.inner.selected_index());
+        let inner = self
+            .inner
+            .clone()
+            .with_entries(visible)
+            .with_selection(selected_index);
 
         Ok(Self {
             inner,
-            show_hidden: self.show_hidden,
-            sort_field: self.sort_field,
-            sort_direction: self.sort_direction,
+            local,
+            ..self.clone()
         })
     }
 
     /// Toggle hidden files visibility, reloading entries.
+    ///
+    /// The toggle also replaces a `.trefm.toml` value for the current directory.
     pub fn with_toggle_hidden(&self) -> anyhow::Result<Self> {
-        let new_show = !self.show_hidden;
-        let raw_entries = read_directory(self.inner.current_dir())?;
-        let sorted = sort_entries(&raw_entries, self.sort_field, self.sort_direction, true);
-        let visible = filter_hidden(&sorted, new_show);
-        let inner = self.inner.clone().with_entries(visible);
+        let new_show = !self.show_hidden();
+        let local = self.local.map(|l| View {
+            show_hidden: new_show,
+            ..l
+        });
+        let toggled = Self {
+            show_hidden: new_show,
+            local,
+            ..self.clone()
+        };
+        let visible = toggled.list(self.inner.current_dir(), local)?;
 
         Ok(Self {
-            inner,
-            show_hidden: new_show,
-            sort_field: self.sort_field,
-            sort_direction: self.sort_direction,
+            inner: self.inner.clone().with_entries(visible),
+            ..toggled
         })
     }
 
+    /// Replace the panel's own hidden-files setting, reloading entries.
+    ///
+    /// Unlike [`with_toggle_hidden`](Self::with_toggle_hidden), a
+    /// `.trefm.toml` value for the current directory keeps precedence.
+    pub fn with_show_hidden(&self, show: bool) -> anyhow::Result<Self> {
+        if show == self.show_hidden {
+            return Ok(self.clone());
+        }
+        let based = Self {
+            show_hidden: show,
+            ..self.clone()
+        };
+        let local = self
+            .local
+            .and_then(|_| based.local_view(self.inner.current_dir()));
+        let visible = based.list(self.inner.current_dir(), local)?;
+        Ok(Self { local, ..based }.with_relisted(visible))
+    }
+
+    /// List only files of `category` (all files for `None`), reloading entries.
+    pub fn with_category_filter(&self, category: Option<FileCategory>) -> anyhow::Result<Self> {
+        let filtered = Self {
+            category,
+            ..self.clone()
+        };
+        let visible = filtered.list(self.inner.current_dir(), self.local)?;
+        Ok(filtered.with_relisted(visible))
+    }
+
+    /// List only directories when `dirs_only` is set, reloading entries.
+    pub fn with_dirs_only(&self, dirs_only: bool) -> anyhow::Result<Self> {
+        let filtered = Self {
+            dirs_only,
+            ..self.clone()
+        };
+        let visible = filtered.list(self.inner.current_dir(), self.local)?;
+        Ok(filtered.with_relisted(visible))
+    }
+
+    /// List only entries whose name fuzzy-matches `query` (everything for
+    /// an empty one), reloading entries and keeping the cursor on the
+    /// selected entry while it stays listed.
+    pub fn with_name_filter(&self, query: &str) -> anyhow::Result<Self> {
+        let filtered = Self {
+            name_filter: query.to_string(),
+            ..self.clone()
+        };
+        let visible = filtered.list(self.inner.current_dir(), self.local)?;
+        Ok(filtered.with_relisted(visible))
+    }
+
     /// Change sort field, cycling through options.
-    pub fn with_next_sort(&self) -> anyhow::Result<Self> {
-        let next_field = match self.sort_field {
+    pub fn with_next_sort(&self) -> Self {
+        let next_field = match self.sort_field() {
             SortField::Name => SortField::Size,
             SortField::Size => SortField::Date,
             SortField::Date => SortField::Type,
@@ -281,19 +703,99 @@ impl PanelState {
         self.with_sort(next_field, self.sort_direction)
     }
 
-    /// Apply a specific sort field and direction.
-    pub fn with_sort(&self, field: SortField, direction: SortDirection) -> anyhow::Result<Self> {
-        let raw_entries = read_directory(self.inner.current_dir())?;
-        let sorted = sort_entries(&raw_entries, field, direction, true);
-        let visible = filter_hidden(&sorted, self.show_hidden);
-        let inner = self.inner.clone().with_entries(visible);
+    /// Reverse the listed entries in place; the next reload sorts them again.
+    ///
+    /// Pinned entries stay on top in pin order.
+    pub fn with_reversed_entries(&self) -> Self {
+        let reversed = Self {
+            inner: self.inner.clone().with_reversed_entries(),
+            ..self.clone()
+        };
+        let entries = self
+            .pins
+            .pinned_first(self.current_dir(), reversed.entries());
+        reversed.with_relisted(entries)
+    }
+
+    /// Use `pins` for this and later listings, re-sorting the entries
+    /// already loaded. The cursor stays on its entry.
+    pub fn with_pins(&self, pins: Pins) -> Self {
+        let pinned = Self {
+            pins,
+            ..self.clone()
+        };
+        pinned.with_base_sort(self.sort_field, self.sort_direction)
+    }
 
-        Ok(Self {
-            inner,
-            show_hidden: self.show_hidden,
+    /// Flip between ascending and descending, keeping the sort field.
+    pub fn with_toggled_direction(&self) -> Self {
+        let direction = match self.sort_direction {
+            SortDirection::Ascending => SortDirection::Descending,
+            SortDirection::Descending => SortDirection::Ascending,
+        };
+        self.with_sort(self.sort_field(), direction)
+    }
+
+    /// Apply a specific sort field and direction, re-sorting the entries
+    /// already loaded. The cursor stays on its entry.
+    ///
+    /// Like [`with_toggle_hidden`](Self::with_toggle_hidden), this replaces
+    /// a `.trefm.toml` sort for the current directory.
+    pub fn with_sort(&self, field: SortField, direction: SortDirection) -> Self {
+        let local = self.local.map(|l| View {
+            sort_field: field,
+            ..l
+        });
+        Self {
             sort_field: field,
             sort_direction: direction,
-        })
+            ..self.clone()
+        }
+        .with_resorted(local)
+    }
+
+    /// Replace the panel's own sort, re-sorting the entries already loaded.
+    ///
+    /// Unlike [`with_sort`](Self::with_sort), a `.trefm.toml` sort for the
+    /// current directory keeps precedence. The cursor stays on its entry.
+    pub fn with_base_sort(&self, field: SortField, direction: SortDirection) -> Self {
+        let based = Self {
+            sort_field: field,
+            sort_direction: direction,
+            ..self.clone()
+        };
+        let local = self
+            .local
+            .and_then(|_| based.local_view(self.inner.current_dir()));
+        based.with_resorted(local)
+    }
+
+    /// Sorts the loaded entries again under `local` or the panel's settings.
+    ///
+    /// The directory is not read again. Switching to a size or date sort
+    /// reads the metadata the listing skipped; after that it stays put.
+    fn with_resorted(self, local: Option<View>) -> Self {
+        let view = self.view(local);
+        let entries = self.real_entries();
+        load_sort_metadata(&entries, view.sort_field);
+        let sorted = sort_entries(
+            &entries,
+            view.sort_field,
+            self.sort_direction,
+            view.dirs_first,
+        );
+        let dir = self.current_dir().to_path_buf();
+        let sorted = self.with_parent_link(&dir, self.pins.pinned_first(&dir, &sorted));
+        Self { local, ..self }.with_relisted(sorted)
+    }
+}
+
+/// Loads the size and date of `entries` when `field` sorts by them, so
+/// that sorting the listing again later does not depend on what happened on
+/// disk since. Name and type sorts read no metadata.
+fn load_sort_metadata(entries: &[FileEntry], field: SortField) {
+    if matches!(field, SortField::Size | SortField::Date) {
+        entries.iter().for_each(FileEntry::load_metadata);
     }
 }
 
@@ -306,26 +808,59 @@ fn filter_hidden(entries: &[FileEntry], show_hidden: bool) -> Vec<FileEntry> {
     }
 }
 
+/// Keeps directories and the files of `category`; everything for `None`.
+fn filter_category(entries: &[FileEntry], category: Option<FileCategory>) -> Vec<FileEntry> {
+    match category {
+        Some(category) => entries
+            .iter()
+            .filter(|e| e.is_dir() || categorize(e) == category)
+            .cloned()
+            .collect(),
+        None => entries.to_vec(),
+    }
+}
+
+/// Whether a status message set at `set_at` is due to be cleared at `now`
+/// under `timeout`. Without a timeout messages never expire.
+fn status_expired(set_at: Option<Instant>, timeout: Option<Duration>, now: Instant) -> bool {
+    match (set_at, timeout) {
+        (Some(set_at), Some(timeout)) => now.saturating_duration_since(set_at) >= timeout,
+        _ => false,
+    }
+}
+
+/// Whether the pager can show `path`: an image, or a file without null bytes.
+fn is_viewable(path: &Path) -> bool {
+    is_image(path) || !is_binary(path).unwrap_or(false)
+}
+
 /// Loads git file statuses for the directory, returning `None` if not in a git repo.
-fn load_git_statuses(dir: &Path) -> Option<HashMap<PathBuf, GitFileStatus>> {
-    let repo_root = find_repo_root(dir)?;
-    get_file_statuses(&repo_root).ok()
+///
+/// Ignored entries of `dir` itself are merged in as [`GitFileStatus::Ignored`]
+/// so the panel can dim them.
+fn load_git_statuses(
+    dir: &Path,
+    repo_roots: &mut RepoRootCache,
+) -> Option<HashMap<PathBuf, GitFileStatus>> {
+    let repo_root = repo_roots.find(dir)?;
+    let mut statuses = get_file_statuses(&repo_root).ok()?;
+    if let Ok(ignored) = get_ignored_paths(&repo_root, dir) {
+        for path in ignored {
+            statuses.entry(path).or_insert(GitFileStatus::Ignored);
+        }
+    }
+    Some(statuses)
 }
 
 /// Loads git branch info for the directory, returning `None` if not in a git repo.
-fn load_branch_info(dir: &Path) -> Option<BranchInfo> {
-    let repo_root = find_repo_root(dir)?;
+fn load_branch_info(dir: &Path, repo_roots: &mut RepoRootCache) -> Option<BranchInfo> {
+    let repo_root = repo_roots.find(dir)?;
     get_branch_info(&repo_root).ok().flatten()
 }
 
-/// Returns the path to the bookmarks file (~/.config/trefm/bookmarks.toml).
+/// Returns the path to the bookmarks file (`bookmarks.toml` in the data directory).
 fn bookmarks_path() -> PathBuf {
-    let config_dir = std::env::var("HOME")
-        .map(PathBuf::from)
-        .unwrap_or_else(|_| PathBuf::from("/"))
-        .join(".config")
-        .join("trefm");
-    config_dir.join("bookmarks.toml")
+    trefm_core::paths::data_dir().join("bookmarks.toml")
 }
 
 /// Loads bookmarks from disk, returning an empty set on any error.
@@ -348,13 +883,38 @@ fn save_bookmarks(bookmarks: &Bookmarks) {
     }
 }
 
+/// Returns the path to the remembered-sort file (`sort.toml` in the data directory).
+fn sort_memory_path() -> PathBuf {
+    trefm_core::paths::data_dir().join("sort.toml")
+}
+
+/// Returns the path to the pinned-entries file (`pins.toml` in the data directory).
+fn pins_path() -> PathBuf {
+    trefm_core::paths::data_dir().join("pins.toml")
+}
+
+/// Returns the path to the recent-directories file (`recent_dirs.toml` in the data directory).
+fn recent_dirs_path() -> PathBuf {
+    trefm_core::paths::data_dir().join("recent_dirs.toml")
+}
+
 /// A single tab's state — panel + git info + display label.
 #[derive(Debug, Clone)]
 pub struct TabEntry {
     pub panel: PanelState,
     pub git_statuses: Option<HashMap<PathBuf, GitFileStatus>>,
     pub branch_info: Option<BranchInfo>,
+    /// Name of the tab's current directory.
     pub label: String,
+    /// Name set by the user; kept when the tab changes directory.
+    pub custom_label: Option<String>,
+}
+
+impl TabEntry {
+    /// The custom name if one is set, else the directory name.
+    pub fn display_label(&self) -> &str {
+        self.custom_label.as_deref().unwrap_or(&self.label)
+    }
 }
 
 /// A group of tabs within a single panel slot.
@@ -388,9 +948,10 @@ impl TabGroup {
         &self.tabs
     }
 
-    /// Add a new tab (max 9). Returns a new TabGroup with the new tab active.
-    pub fn with_new_tab(self, entry: TabEntry) -> Self {
-        if self.tabs.len() >= 9 {
+    /// Add a new tab unless the group already holds `max_tabs`. Returns a
+    /// new TabGroup with the new tab active.
+    pub fn with_new_tab(self, entry: TabEntry, max_tabs: usize) -> Self {
+        if self.tabs.len() >= max_tabs {
             return self;
         }
         let mut tabs = self.tabs;
@@ -461,6 +1022,40 @@ impl TabGroup {
         tabs[self.active_tab] = entry;
         Self { tabs, ..self }
     }
+
+    /// Apply `f` to the panel of every tab.
+    pub fn with_each_panel(self, f: impl Fn(&PanelState) -> PanelState) -> Self {
+        let tabs = self
+            .tabs
+            .into_iter()
+            .map(|tab| TabEntry {
+                panel: f(&tab.panel),
+                ..tab
+            })
+            .collect();
+        Self { tabs, ..self }
+    }
+
+    /// Keep only the active tab, which becomes tab 0.
+    pub fn with_only_active(self) -> Self {
+        let mut tabs = self.tabs;
+        let active = tabs.swap_remove(self.active_tab);
+        Self {
+            tabs: vec![active],
+            active_tab: 0,
+        }
+    }
+
+    /// Remove the active tab and hand it back. A group's last tab stays put
+    /// and `None` is returned.
+    pub fn take_active(self) -> (Self, Option<TabEntry>) {
+        if self.tabs.len() <= 1 {
+            return (self, None);
+        }
+        let entry = self.active_tab().clone();
+        let active = self.active_tab;
+        (self.with_closed_tab(active), Some(entry))
+    }
 }
 
 /// Top-level application state. Immutable transitions via `with_*` methods.
@@ -472,6 +1067,12 @@ pub struct App {
     dual_mode: bool,
     should_quit: bool,
     status_message: Option<String>,
+    /// Severity of the status message, which picks its colour.
+    status_level: NotificationLevel,
+    /// When the status message was set.
+    status_set_at: Option<Instant>,
+    /// How long a status message stays (`ui.status_timeout_secs`); `None` keeps it.
+    status_timeout: Option<Duration>,
     /// Fuzzy search results — populated when in Search mode.
     search_results: Vec<FuzzyMatch>,
     /// Cursor index within the search results list.
@@ -484,28 +1085,111 @@ pub struct App {
     recent_selected: usize,
     /// Persistent duplicate file cache — loaded from disk on startup.
     duplicate_cache: DuplicateCache,
-    /// Flat cursor index across all files in all duplicate groups.
+    /// Bumped whenever `duplicate_cache` is replaced, so background results
+    /// computed from an older cache can be told apart.
+    duplicate_generation: u64,
+    /// Flat cursor index across all files in all visible duplicate groups.
     duplicate_selected: usize,
+    /// Groups and files hidden from the duplicate view.
+    duplicate_ignore: DuplicateIgnoreList,
+    /// Where the ignore list is persisted.
+    duplicate_ignore_path: PathBuf,
+    /// Order of groups in the duplicate view.
+    duplicate_sort: DuplicateSort,
+    /// Whether groups under the active panel's directory are listed first.
+    duplicate_current_dir_first: bool,
     /// Current status of the background duplicate scanner.
     scan_status: ScanStatus,
+    /// Scan of the panel's directory shown in place of the global cache.
+    local_duplicates: Option<LocalDuplicateScan>,
+    /// Id of the most recently started local duplicate scan.
+    local_scan_id: u64,
+    /// Recursive content search — query, progress and results.
+    content_search: ContentSearchState,
+    /// Patterns skipped by fuzzy and content search.
+    search_exclude: ExcludeSet,
+    /// Scorer used to rank fuzzy search results.
+    fuzzy_algorithm: FuzzyAlgorithm,
+    /// Files with uncommitted changes — populated when in GitChanges mode.
+    git_changes: Vec<(PathBuf, GitFileStatus)>,
+    /// Cursor index within the git changes list.
+    git_changes_selected: usize,
+    /// Repository roots of the directories shown so far.
+    repo_roots: RepoRootCache,
     /// Key bindings.
     keymap: Keymap,
     /// Action registry for Command Palette.
     action_registry: ActionRegistry,
     /// UI theme.
     theme: Theme,
-    /// Whether to show Nerd Font icons.
-    show_icons: bool,
+    /// How the file list marks directories and symlinks.
+    entry_markers: EntryMarkers,
+    /// Whether dual mode previews the inactive panel's selection.
+    dual_preview: bool,
+    /// How the file list shortens names that do not fit.
+    name_truncation: NameTruncation,
+    /// How file sizes are displayed.
+    size_units: SizeUnits,
+    /// How result lists show paths.
+    result_paths: ResultPaths,
+    /// Most tabs a panel slot may hold (`ui.max_tabs`, clamped).
+    max_tabs: usize,
     /// Lines loaded for pager mode.
     pager_lines: Vec<String>,
     /// File path for the pager (for syntax highlighting).
     pager_file: Option<PathBuf>,
+    /// Pager header override (command output has no file name).
+    pager_title: Option<String>,
+    /// Rows of the side-by-side diff view.
+    diff_rows: Vec<DiffRow>,
+    /// The left (old) and right (new) files of the diff view.
+    diff_paths: Option<(PathBuf, PathBuf)>,
     /// Active remote session context, if connected.
     remote_context: Option<RemoteContext>,
     /// State of the remote connection form.
     connect_form: ConnectFormState,
     /// Whether the terminal panel is visible.
     terminal_visible: bool,
+    /// Where the terminal panel is drawn and how large it is.
+    terminal_layout: TerminalLayout,
+    /// Paths yanked for the next paste.
+    clipboard: Vec<PathBuf>,
+    /// Whether quitting with operations in flight asks for confirmation.
+    confirm_quit_if_busy: bool,
+    /// Which deletes ask for confirmation.
+    confirm_scope: ConfirmScope,
+    /// Entering a directory with more entries asks first (0 = never).
+    large_dir_threshold: usize,
+    /// Number of background operations (scans, searches) still running.
+    in_flight_ops: usize,
+    /// History of file operations performed this session.
+    operation_log: OperationLog,
+    /// Recent status messages, newest last.
+    notifications: Notifications,
+    /// Whether sort choices are remembered per directory or globally.
+    remember_sort: RememberSort,
+    /// Remembered sorts, consulted when a panel changes directory.
+    sort_memory: SortMemory,
+    /// Where the remembered sorts are persisted.
+    sort_memory_path: PathBuf,
+    /// Entries pinned to the top of their directory.
+    pins: Pins,
+    /// Where the pins are persisted.
+    pins_path: PathBuf,
+    /// Directories the active panel has visited, newest first.
+    recent_dirs: RecentDirs,
+    /// Where the recent directories are saved on quit.
+    recent_dirs_path: PathBuf,
+    /// What Enter does on a file.
+    open_on_enter: OpenOnEnter,
+    /// Per-extension openers that take precedence over `open_on_enter`.
+    open_with: OpenWith,
+    /// Opener for files the pager cannot show; empty means the system one.
+    open_fallback: String,
+    /// Whether renames and pastes ask before replacing an existing entry.
+    confirm_overwrite: bool,
+    /// External program waiting to be started by the event loop.
+    pending_launch: Option<Launch>,
 }
 
 /// Returns the path to the project config directory.
@@ -515,12 +1199,8 @@ fn config_dir() -> PathBuf {
     if local.exists() {
         return local;
     }
-    // Fall back to ~/.config/trefm
-    std::env::var("HOME")
-        .map(PathBuf::from)
-        .unwrap_or_else(|_| PathBuf::from("/"))
-        .join(".config")
-        .join("trefm")
+    // Fall back to $XDG_CONFIG_HOME/trefm or ~/.config/trefm
+    trefm_core::paths::config_dir()
 }
 
 impl App {
@@ -528,22 +1208,68 @@ impl App {
     pub fn new(start_dir: &Path) -> anyhow::Result<Self> {
         let panel = PanelState::from_dir(start_dir)?;
         let panel_right = panel.clone();
-        let git_statuses_init = load_git_statuses(panel.current_dir());
-        let branch_info_init = load_branch_info(panel.current_dir());
+        let mut repo_roots = RepoRootCache::default();
+        let git_statuses_init = load_git_statuses(panel.current_dir(), &mut repo_roots);
+        let branch_info_init = load_branch_info(panel.current_dir(), &mut repo_roots);
         let bookmarks = load_bookmarks();
+        let duplicate_ignore_path = crate::background::ignore_list_path();
+        let duplicate_ignore = DuplicateIgnoreList::load(&duplicate_ignore_path);
 
         let cfg_dir = config_dir();
 
         // Load keymap with fallback to defaults
         let keymap = Keymap::load(&cfg_dir.join("keymap.toml")).unwrap_or_default();
 
-        // Load theme with fallback to defaults
-        let theme = Theme::load(&cfg_dir.join("theme.toml")).unwrap_or_default();
+        // Load icons, search exclusions and safety flags from config with fallback to defaults
+        let config = Config::load(&cfg_dir.join("default.toml")).unwrap_or_default();
+        let no_color = config.ui.no_color_active();
+        let entry_markers = EntryMarkers::from_settings(config.ui.show_icons, no_color);
 
-        // Load show_icons from config with fallback to default
-        let show_icons = Config::load(&cfg_dir.join("default.toml"))
-            .map(|c| c.ui.show_icons)
-            .unwrap_or(true);
+        // Load theme with fallback to defaults; no-colour mode ignores it
+        let theme = if no_color {
+            Theme::plain()
+        } else {
+            Theme::load(&cfg_dir.join("theme.toml")).unwrap_or_default()
+        };
+        let search_exclude = config.search.exclude_set();
+        let fuzzy_algorithm = config.search.fuzzy_algorithm;
+        let confirm_quit_if_busy = config.safety.confirm_quit_if_busy;
+        let confirm_scope = config.safety.confirm_scope;
+        let large_dir_threshold = config.safety.large_dir_threshold;
+        let terminal_layout = TerminalLayout::from_config(&config.terminal);
+
+        let remember_sort = config.general.remember_sort;
+        let sort_memory_path = sort_memory_path();
+        let sort_memory = match remember_sort {
+            RememberSort::Off => SortMemory::default(),
+            _ => SortMemory::load_from_file(&sort_memory_path).unwrap_or_default(),
+        };
+        let restored = match remember_sort {
+            RememberSort::Off => None,
+            RememberSort::Directory => sort_memory.for_dir(panel.current_dir()),
+            RememberSort::Global => sort_memory.global(),
+        };
+        let (panel, panel_right) = match restored {
+            Some(sort) => (
+                panel.with_base_sort(sort.field, sort.direction),
+                panel_right.with_base_sort(sort.field, sort.direction),
+            ),
+            None => (panel, panel_right),
+        };
+        let show_parent_entry = config.ui.show_parent_entry;
+        let panel = panel.with_parent_entry(show_parent_entry);
+        let panel_right = panel_right.with_parent_entry(show_parent_entry);
+        let show_hidden = config.general.show_hidden;
+        let panel = panel.with_show_hidden(show_hidden)?;
+        let panel_right = panel_right.with_show_hidden(show_hidden)?;
+        let pins_path = pins_path();
+        let pins = Pins::load_from_file(&pins_path).unwrap_or_default();
+        let panel = panel.with_pins(pins.clone());
+        let panel_right = panel_right.with_pins(pins.clone());
+        let recent_dirs_path = recent_dirs_path();
+        let recent_dirs = RecentDirs::load_from_file(&recent_dirs_path)
+            .unwrap_or_default()
+            .visited(panel.current_dir());
 
         let label = panel
             .current_dir()
@@ -556,12 +1282,14 @@ impl App {
             git_statuses: git_statuses_init,
             branch_info: branch_info_init,
             label: label.clone(),
+            custom_label: None,
         };
         let tab_entry_right = TabEntry {
             panel: panel_right,
             git_statuses: None,
             branch_info: None,
             label,
+            custom_label: None,
         };
 
         Ok(Self {
@@ -571,23 +1299,67 @@ impl App {
             dual_mode: false,
             should_quit: false,
             status_message: None,
+            status_level: NotificationLevel::Info,
+            status_set_at: None,
+            status_timeout: config.ui.status_timeout(),
             search_results: Vec::new(),
             search_selected: 0,
             bookmarks,
             recent_results: Vec::new(),
             recent_selected: 0,
             duplicate_cache: DuplicateCache::default(),
+            duplicate_generation: 0,
             duplicate_selected: 0,
+            duplicate_ignore,
+            duplicate_ignore_path,
+            duplicate_sort: DuplicateSort::default(),
+            duplicate_current_dir_first: config.scanner.current_dir_first,
             scan_status: ScanStatus::Idle,
+            local_duplicates: None,
+            local_scan_id: 0,
+            content_search: ContentSearchState::default(),
+            search_exclude,
+            fuzzy_algorithm,
+            git_changes: Vec::new(),
+            git_changes_selected: 0,
+            repo_roots,
             keymap,
             action_registry: ActionRegistry::new(),
             theme,
-            show_icons,
+            entry_markers,
+            dual_preview: config.ui.dual_preview,
+            name_truncation: config.ui.name_truncation,
+            size_units: config.ui.size_units,
+            result_paths: config.ui.result_paths,
+            max_tabs: config.ui.tab_limit(),
             pager_lines: Vec::new(),
             pager_file: None,
+            pager_title: None,
+            diff_rows: Vec::new(),
+            diff_paths: None,
             remote_context: None,
             connect_form: ConnectFormState::default(),
             terminal_visible: false,
+            terminal_layout,
+            clipboard: Vec::new(),
+            confirm_quit_if_busy,
+            confirm_scope,
+            large_dir_threshold,
+            in_flight_ops: 0,
+            operation_log: OperationLog::default(),
+            notifications: Notifications::default(),
+            remember_sort,
+            sort_memory,
+            sort_memory_path,
+            pins,
+            pins_path,
+            recent_dirs,
+            recent_dirs_path,
+            open_on_enter: config.general.open_on_enter,
+            open_with: config.general.open_with.clone(),
+            open_fallback: config.general.open_fallback.clone(),
+            confirm_overwrite: config.general.confirm_overwrite,
+            pending_launch: None,
         })
     }
 
@@ -604,10 +1376,38 @@ impl App {
         self.should_quit
     }
 
+    pub fn in_flight_ops(&self) -> usize {
+        self.in_flight_ops
+    }
+
+    /// Takes the external program requested by the last command, if any.
+    pub fn take_launch(self) -> (Self, Option<Launch>) {
+        let launch = self.pending_launch.clone();
+        (
+            Self {
+                pending_launch: None,
+                ..self
+            },
+            launch,
+        )
+    }
+
+    pub fn operation_log(&self) -> &OperationLog {
+        &self.operation_log
+    }
+
+    pub fn notifications(&self) -> &Notifications {
+        &self.notifications
+    }
+
     pub fn status_message(&self) -> Option<&str> {
         self.status_message.as_deref()
     }
 
+    pub fn status_level(&self) -> NotificationLevel {
+        self.status_level
+    }
+
     pub fn git_statuses(&self) -> Option<&HashMap<PathBuf, GitFileStatus>> {
         self.tab_groups[self.active_panel]
             .active_tab()
@@ -640,6 +1440,16 @@ impl App {
         &self.tab_groups[1].active_tab().panel
     }
 
+    /// Returns the panel that does not have focus.
+    pub fn inactive_panel(&self) -> &PanelState {
+        &self.tab_groups[1 - self.active_panel].active_tab().panel
+    }
+
+    /// The entry under the inactive panel's cursor, if any.
+    pub fn inactive_selected_entry(&self) -> Option<&FileEntry> {
+        self.inactive_panel().selected_entry()
+    }
+
     pub fn left_git_statuses(&self) -> Option<&HashMap<PathBuf, GitFileStatus>> {
         self.tab_groups[0].active_tab().git_statuses.as_ref()
     }
@@ -686,8 +1496,18 @@ impl App {
         self.recent_selected
     }
 
-    pub fn duplicate_results(&self) -> &[CachedDuplicateGroup] {
-        &self.duplicate_cache.groups
+    /// Duplicate groups as shown in the view, with ignored entries removed.
+    ///
+    /// With `scanner.current_dir_first`, groups that have a file under the
+    /// active panel's directory come first; each part keeps the current
+    /// sort order.
+    pub fn duplicate_results(&self) -> Vec<CachedDuplicateGroup> {
+        let mut groups = self.shown_duplicates().filtered(&self.duplicate_ignore);
+        if self.duplicate_current_dir_first && !self.is_remote() {
+            let dir = self.panel().current_dir();
+            groups.sort_by_key(|g| !g.has_file_under(dir));
+        }
+        groups
     }
 
     pub fn duplicate_selected(&self) -> usize {
@@ -698,10 +1518,44 @@ impl App {
         &self.duplicate_cache
     }
 
+    /// Generation of [`duplicate_cache`](Self::duplicate_cache), for tagging
+    /// background work started from it.
+    pub fn duplicate_generation(&self) -> u64 {
+        self.duplicate_generation
+    }
+
+    pub fn local_duplicate_scan(&self) -> Option<&LocalDuplicateScan> {
+        self.local_duplicates.as_ref()
+    }
+
+    /// The local scan's results while there is one, else the global cache.
+    pub fn shown_duplicates(&self) -> &DuplicateCache {
+        match &self.local_duplicates {
+            Some(scan) => &scan.cache,
+            None => &self.duplicate_cache,
+        }
+    }
+
     pub fn scan_status(&self) -> &ScanStatus {
         &self.scan_status
     }
 
+    pub fn content_search(&self) -> &ContentSearchState {
+        &self.content_search
+    }
+
+    pub fn search_exclude(&self) -> &ExcludeSet {
+        &self.search_exclude
+    }
+
+    pub fn git_changes(&self) -> &[(PathBuf, GitFileStatus)] {
+        &self.git_changes
+    }
+
+    pub fn git_changes_selected(&self) -> usize {
+        self.git_changes_selected
+    }
+
     pub fn keymap(&self) -> &Keymap {
         &self.keymap
     }
@@ -714,8 +1568,25 @@ impl App {
         &self.theme
     }
 
-    pub fn show_icons(&self) -> bool {
-        self.show_icons
+    pub fn entry_markers(&self) -> EntryMarkers {
+        self.entry_markers
+    }
+
+    pub fn name_truncation(&self) -> NameTruncation {
+        self.name_truncation
+    }
+
+    pub fn size_units(&self) -> SizeUnits {
+        self.size_units
+    }
+
+    pub fn result_paths(&self) -> ResultPaths {
+        self.result_paths
+    }
+
+    /// Whether dual mode previews the inactive panel's selection.
+    pub fn dual_preview(&self) -> bool {
+        self.dual_preview
     }
 
     pub fn pager_lines(&self) -> &[String] {
@@ -726,6 +1597,29 @@ impl App {
         self.pager_file.as_deref()
     }
 
+    pub fn pager_title(&self) -> Option<&str> {
+        self.pager_title.as_deref()
+    }
+
+    pub fn diff_rows(&self) -> &[DiffRow] {
+        &self.diff_rows
+    }
+
+    /// The left (old) and right (new) files of the diff view.
+    pub fn diff_paths(&self) -> Option<(&Path, &Path)> {
+        self.diff_paths
+            .as_ref()
+            .map(|(left, right)| (left.as_path(), right.as_path()))
+    }
+
+    /// Number of lines the current pager-style view scrolls through.
+    pub fn pager_len(&self) -> usize {
+        match self.mode {
+            AppMode::Diff { .. } => self.diff_rows.len(),
+            _ => self.pager_lines.len(),
+        }
+    }
+
     /// Returns `true` if the app is browsing a remote server.
     pub fn is_remote(&self) -> bool {
         self.remote_context.is_some()
@@ -741,6 +1635,39 @@ impl App {
         &self.connect_form
     }
 
+    /// Scroll offset of the pager view, shared by the file pager, the log
+    /// viewer and the diff view.
+    pub fn pager_scroll(&self) -> Option<usize> {
+        match self.mode {
+            AppMode::Pager { scroll }
+            | AppMode::LogViewer { scroll }
+            | AppMode::Diff { scroll } => Some(scroll),
+            _ => None,
+        }
+    }
+
+    /// Move the pager view to `scroll`, staying in the current pager-style mode.
+    pub fn with_pager_scroll(self, scroll: usize) -> Self {
+        let mode = match self.mode {
+            AppMode::Pager { .. } => AppMode::Pager { scroll },
+            AppMode::LogViewer { .. } => AppMode::LogViewer { scroll },
+            AppMode::Diff { .. } => AppMode::Diff { scroll },
+            _ => return self,
+        };
+        self.with_mode(mode)
+    }
+
+    /// Show the tailed log `lines` in the log viewer, scrolled to `scroll`.
+    pub fn with_log_lines(self, title: String, lines: Vec<String>, scroll: usize) -> Self {
+        Self {
+            mode: AppMode::LogViewer { scroll },
+            pager_lines: lines,
+            pager_file: None,
+            pager_title: Some(title),
+            ..self
+        }
+    }
+
     /// Enter pager mode for the currently selected file.
     /// Returns self unchanged if the selection is a directory or binary.
     pub fn enter_pager(self) -> Self {
@@ -763,20 +1690,163 @@ impl App {
                 mode: AppMode::Pager { scroll: 0 },
                 pager_lines: preview.lines,
                 pager_file: Some(path.to_path_buf()),
+                pager_title: None,
                 ..self
             },
             Err(e) => self.with_status(format!("Error reading file: {e}")),
         }
     }
 
-    /// Replace the duplicate cache (immutable transition).
+    /// Diff the selected file against the file of the same name in the
+    /// other panel (dual mode only), left panel's file as the old side.
+    pub fn enter_diff_with_other_panel(self) -> Self {
+        if !self.dual_mode {
+            return self.with_status("Other panel is only available in dual mode".to_string());
+        }
+        let entry = match self.panel().selected_entry() {
+            Some(e) if !e.is_dir() => e,
+            _ => return self.with_status("No file selected".to_string()),
+        };
+        let other = self.inactive_panel().current_dir().join(entry.name());
+        if !other.is_file() {
+            let msg = format!("No {} in the other panel", entry.name());
+            return self.with_status(msg);
+        }
+        let (left, right) = if self.active_panel == 0 {
+            (entry.path().to_path_buf(), other)
+        } else {
+            (other, entry.path().to_path_buf())
+        };
+
+        match file_diff(&left, &right) {
+            Ok(diff) if diff.iter().all(|line| matches!(line, DiffLine::Same(_))) => {
+                self.with_status("Files are identical".to_string())
+            }
+            Ok(diff) => Self {
+                mode: AppMode::Diff { scroll: 0 },
+                diff_rows: side_by_side(&diff),
+                diff_paths: Some((left, right)),
+                ..self
+            },
+            Err(e) => self.with_status(format!("Error: {e}")),
+        }
+    }
+
+    /// Expand a command template for the selected entry.
+    /// `Err` carries the status message to show instead.
+    pub fn run_command_argv(&self, template: &str) -> Result<Vec<String>, String> {
+        if self.is_remote() {
+            return Err("Run command not supported in remote mode".to_string());
+        }
+        let entry = self
+            .panel()
+            .selected_entry()
+            .ok_or_else(|| "No file selected".to_string())?;
+        expand_template(template, entry.path()).map_err(|e| format!("Error: {e}"))
+    }
+
+    /// The selected directory as a text tree for the clipboard, at most
+    /// [`TREE_COPY_MAX_DEPTH`] levels deep and [`TREE_COPY_MAX_ENTRIES`]
+    /// entries long. The error is a message for the status bar.
+    pub fn selected_tree_text(&self) -> Result<String, String> {
+        if self.is_remote() {
+            return Err("Copy tree not supported in remote mode".to_string());
+        }
+        let entry = match self.panel().selected_entry() {
+            Some(e) if e.is_dir() && !e.is_parent_link() => e,
+            _ => return Err("No directory selected".to_string()),
+        };
+        let entries = read_directory_tree(entry.path(), TREE_COPY_MAX_DEPTH, TREE_COPY_MAX_ENTRIES)
+            .map_err(|e| format!("Error: {e}"))?;
+        let mut text = tree_to_text(entry.name(), &entries, TreeIndent::Spaces(2));
+        if entries.len() >= TREE_COPY_MAX_ENTRIES {
+            text.push_str(&format!("… (stopped at {TREE_COPY_MAX_ENTRIES} entries)\n"));
+        }
+        Ok(text)
+    }
+
+    /// The selected entry's path relative to its git repository root, for
+    /// the clipboard. The error is a message for the status bar.
+    pub fn selected_git_path(&self) -> Result<String, String> {
+        if self.is_remote() {
+            return Err("Copy git path not supported in remote mode".to_string());
+        }
+        let entry = match self.panel().selected_entry() {
+            Some(e) if !e.is_parent_link() => e,
+            _ => return Err("No file selected".to_string()),
+        };
+        let root = self
+            .repo_roots
+            .get_or_find(self.panel().current_dir())
+            .ok_or_else(|| "Not in a git repository".to_string())?;
+        let root = root.canonicalize().unwrap_or(root);
+        repo_relative_path(entry.path(), &root).ok_or_else(|| "Not in a git repository".to_string())
+    }
+
+    /// Ask to run the selected file. Directories and files without execute
+    /// permission are refused with a status message instead.
+    pub fn request_execute(self) -> Self {
+        if self.is_remote() {
+            return self.with_status("Execute not supported in remote mode".to_string());
+        }
+        let Some(entry) = self.panel().selected_entry() else {
+            return self.with_status("No file selected".to_string());
+        };
+        if entry.is_dir() {
+            return self.with_status("Cannot execute a directory".to_string());
+        }
+        if !is_executable(entry.path()) {
+            let msg = format!("{} is not executable", entry.name());
+            return self.with_status(msg);
+        }
+        let path = entry.path().to_path_buf();
+        self.with_mode(AppMode::Confirm(ConfirmAction::Execute(path)))
+    }
+
+    /// Show the output of a finished command: one line goes to the status
+    /// bar, more open the pager (only if nothing else has the screen).
+    pub fn with_command_output(self, command: String, output: CommandOutput) -> Self {
+        let exit = match output.code {
+            Some(0) => String::new(),
+            Some(code) => format!(" [exit {code}]"),
+            None => " [killed]".to_string(),
+        };
+        match output.lines.as_slice() {
+            [] => self.with_status(format!("{command}: done{exit}")),
+            [line] => self.with_status(format!("{command}: {line}{exit}")),
+            lines if matches!(self.mode, AppMode::Normal) => Self {
+                mode: AppMode::Pager { scroll: 0 },
+                pager_lines: lines.to_vec(),
+                pager_file: None,
+                pager_title: Some(format!("$ {command}{exit}")),
+                ..self
+            },
+            lines => {
+                let count = lines.len();
+                self.with_status(format!("{command}: {count} lines of output{exit}"))
+            }
+        }
+    }
+
+    /// Replace the duplicate cache, keeping the current sort order.
     pub fn with_duplicate_cache(self, cache: DuplicateCache) -> Self {
         Self {
-            duplicate_cache: cache,
+            duplicate_cache: cache.sorted_by(self.duplicate_sort),
+            duplicate_generation: self.duplicate_generation.wrapping_add(1),
             ..self
         }
     }
 
+    /// Take a cache computed in the background from generation `base` of
+    /// the duplicate cache (validation, incremental update). Ignored when
+    /// the cache has been replaced since, e.g. by a full scan.
+    pub fn with_derived_duplicates(self, base: u64, cache: DuplicateCache) -> Self {
+        if base != self.duplicate_generation {
+            return self;
+        }
+        self.with_duplicate_cache(cache)
+    }
+
     /// Update the scan status (immutable transition).
     pub fn with_scan_status(self, status: ScanStatus) -> Self {
         Self {
@@ -791,15 +1861,24 @@ impl App {
     }
 
     /// Transition to a new panel state for the active panel, refreshing git info.
+    ///
+    /// If the panel moved to another directory, its remembered sort is applied.
     pub fn with_panel(self, panel: PanelState) -> Self {
+        let panel = self.restore_sort(panel);
         let idx = self.active_panel;
         let is_remote = self.is_remote();
+        let recent_dirs = if !is_remote && panel.current_dir() != self.panel().current_dir() {
+            self.recent_dirs.visited(panel.current_dir())
+        } else {
+            self.recent_dirs
+        };
+        let mut repo_roots = self.repo_roots;
         let (git_statuses, branch_info) = if is_remote {
             (None, None)
         } else {
             (
-                load_git_statuses(panel.current_dir()),
-                load_branch_info(panel.current_dir()),
+                load_git_statuses(panel.current_dir(), &mut repo_roots),
+                load_branch_info(panel.current_dir(), &mut repo_roots),
             )
         };
         let label = panel
@@ -812,19 +1891,75 @@ impl App {
             git_statuses,
             branch_info,
             label,
+            custom_label: self.tab_groups[idx].active_tab().custom_label.clone(),
         };
         let mut tab_groups = self.tab_groups;
         tab_groups[idx] = tab_groups[idx].clone().with_updated_active(entry);
-        Self { tab_groups, ..self }
+        Self {
+            tab_groups,
+            repo_roots,
+            recent_dirs,
+            ..self
+        }
+    }
+
+    /// Install a panel whose sort the user just changed, remembering the
+    /// choice when `remember_sort` is on.
+    pub fn with_sorted_panel(self, panel: PanelState) -> Self {
+        if self.remember_sort == RememberSort::Off || self.is_remote() {
+            return self.with_panel(panel);
+        }
+        let sort = SavedSort {
+            field: panel.sort_field(),
+            direction: panel.sort_direction(),
+        };
+        let sort_memory = match self.remember_sort {
+            RememberSort::Global => self.sort_memory.clone().with_global(sort),
+            _ => self.sort_memory.clone().with_dir(panel.current_dir(), sort),
+        };
+        if let Err(e) = sort_memory.save_to_file(&self.sort_memory_path) {
+            tracing::warn!("Failed to save {}: {e}", self.sort_memory_path.display());
+        }
+        Self {
+            sort_memory,
+            ..self
+        }
+        .with_panel(panel)
+    }
+
+    /// Applies the remembered sort to `panel` if it left the active panel's directory.
+    ///
+    /// In `directory` mode a directory without a remembered sort gets the
+    /// default (name, ascending).
+    fn restore_sort(&self, panel: PanelState) -> PanelState {
+        if self.is_remote() || panel.current_dir() == self.panel().current_dir() {
+            return panel;
+        }
+        let sort = match self.remember_sort {
+            RememberSort::Off => return panel,
+            RememberSort::Directory => self
+                .sort_memory
+                .for_dir(panel.current_dir())
+                .unwrap_or(SavedSort::DEFAULT),
+            RememberSort::Global => match self.sort_memory.global() {
+                Some(sort) => sort,
+                None => return panel,
+            },
+        };
+        if panel.sort_field == sort.field && panel.sort_direction == sort.direction {
+            return panel;
+        }
+        panel.with_base_sort(sort.field, sort.direction)
     }
 
     /// Toggle dual panel mode.
     pub fn with_toggle_dual_mode(self) -> Self {
         let entering_dual = !self.dual_mode;
         if entering_dual {
+            let mut repo_roots = self.repo_roots;
             let right_tab = self.tab_groups[1].active_tab();
-            let git_statuses = load_git_statuses(right_tab.panel.current_dir());
-            let branch_info = load_branch_info(right_tab.panel.current_dir());
+            let git_statuses = load_git_statuses(right_tab.panel.current_dir(), &mut repo_roots);
+            let branch_info = load_branch_info(right_tab.panel.current_dir(), &mut repo_roots);
             let label = right_tab
                 .panel
                 .current_dir()
@@ -836,12 +1971,14 @@ impl App {
                 git_statuses,
                 branch_info,
                 label,
+                custom_label: right_tab.custom_label.clone(),
             };
             let mut tab_groups = self.tab_groups;
             tab_groups[1] = tab_groups[1].clone().with_updated_active(entry);
             Self {
                 dual_mode: true,
                 tab_groups,
+                repo_roots,
                 ..self
             }
         } else {
@@ -863,6 +2000,30 @@ impl App {
         }
     }
 
+    /// Exchange the left and right tab groups. Focus stays on the same side.
+    pub fn with_swapped_panels(self) -> Self {
+        if !self.dual_mode {
+            return self;
+        }
+        let [left, right] = self.tab_groups;
+        Self {
+            tab_groups: [right, left],
+            ..self
+        }
+    }
+
+    /// Point the inactive panel at the active panel's directory.
+    pub fn with_synced_panels(self) -> Self {
+        if !self.dual_mode {
+            return self;
+        }
+        let dir = self.panel().current_dir().to_path_buf();
+        if self.inactive_panel().current_dir() == dir {
+            return self;
+        }
+        self.enter_dir_checked(&dir, DirLanding::OtherPanel)
+    }
+
     /// Mark the app for quitting.
     pub fn with_quit(self) -> Self {
         Self {
@@ -871,10 +2032,55 @@ impl App {
         }
     }
 
-    /// Set a status message.
+    /// Handle a quit request from the user.
+    ///
+    /// Quits immediately unless `confirm_quit_if_busy` is set and background
+    /// operations are still running, in which case it asks first.
+    pub fn request_quit(self) -> Self {
+        if self.confirm_quit_if_busy && self.in_flight_ops > 0 {
+            self.with_mode(AppMode::Confirm(ConfirmAction::Quit))
+        } else {
+            self.with_quit()
+        }
+    }
+
+    /// Record that a background operation has started.
+    pub fn with_operation_started(self) -> Self {
+        Self {
+            in_flight_ops: self.in_flight_ops + 1,
+            ..self
+        }
+    }
+
+    /// Record that a background operation has finished.
+    pub fn with_operation_finished(self) -> Self {
+        Self {
+            in_flight_ops: self.in_flight_ops.saturating_sub(1),
+            ..self
+        }
+    }
+
+    /// Set a status message, recording it in the notifications with a
+    /// level guessed from its wording.
     pub fn with_status(self, msg: String) -> Self {
+        let level = NotificationLevel::of_message(&msg);
+        self.push_notification(level, msg)
+    }
+
+    /// Set a status message reporting that an operation succeeded.
+    pub fn with_success(self, msg: String) -> Self {
+        self.push_notification(NotificationLevel::Success, msg)
+    }
+
+    /// Record `msg` in the notifications and show it in the status bar.
+    pub fn push_notification(self, level: NotificationLevel, msg: String) -> Self {
+        let mut notifications = self.notifications;
+        notifications.push(level, msg.clone());
         Self {
+            notifications,
             status_message: Some(msg),
+            status_level: level,
+            status_set_at: Some(Instant::now()),
             ..self
         }
     }
@@ -883,10 +2089,21 @@ impl App {
     pub fn with_clear_status(self) -> Self {
         Self {
             status_message: None,
+            status_set_at: None,
             ..self
         }
     }
 
+    /// Clear the status message if it has outlived `ui.status_timeout_secs`
+    /// at `now`.
+    pub fn with_expired_status_cleared(self, now: Instant) -> Self {
+        if status_expired(self.status_set_at, self.status_timeout, now) {
+            self.with_clear_status()
+        } else {
+            self
+        }
+    }
+
     /// Set the remote context (immutable transition).
     pub fn with_remote_context(self, ctx: Option<RemoteContext>) -> Self {
         Self {
@@ -900,6 +2117,10 @@ impl App {
         self.terminal_visible
     }
 
+    pub fn terminal_layout(&self) -> TerminalLayout {
+        self.terminal_layout
+    }
+
     /// Set terminal visibility (immutable transition).
     pub fn with_terminal_visible(self, visible: bool) -> Self {
         Self {
@@ -908,25 +2129,83 @@ impl App {
         }
     }
 
+    /// Move the terminal selection cursor, clamped to a `rows` x `cols` screen.
+    /// No-op outside terminal selection mode.
+    pub fn terminal_selection_moved(self, d_row: i32, d_col: i32, rows: u16, cols: u16) -> Self {
+        match self.mode {
+            AppMode::TerminalSelect(sel) => Self {
+                mode: AppMode::TerminalSelect(sel.moved(d_row, d_col, rows, cols)),
+                ..self
+            },
+            _ => self,
+        }
+    }
+
+    /// Start a fresh terminal selection at the current cursor.
+    pub fn terminal_selection_restarted(self) -> Self {
+        match self.mode {
+            AppMode::TerminalSelect(sel) => Self {
+                mode: AppMode::TerminalSelect(sel.restarted()),
+                ..self
+            },
+            _ => self,
+        }
+    }
+
+    /// Move the active panel to the directory the embedded shell reported.
+    /// Ignored for remote panels and for paths that are not local directories
+    /// (e.g. the shell is inside an ssh session).
+    pub fn follow_terminal_cwd(self, dir: &Path) -> Self {
+        if self.is_remote() || !dir.is_dir() {
+            return self;
+        }
+        let same = dir
+            .canonicalize()
+            .is_ok_and(|d| d == self.panel().current_dir());
+        if same {
+            return self;
+        }
+        match self.panel().navigate_to(dir) {
+            Ok(new_panel) => self.with_panel(new_panel),
+            Err(_) => self,
+        }
+    }
+
     /// Open a new tab duplicating the current directory.
     pub fn with_new_tab(self) -> Self {
         let idx = self.active_panel;
         let current = self.tab_groups[idx].active_tab();
-        if self.tab_groups[idx].tab_count() >= 9 {
-            return self.with_status("Maximum 9 tabs reached".to_string());
+        let max_tabs = self.max_tabs;
+        if self.tab_groups[idx].tab_count() >= max_tabs {
+            return self.with_status(format!("Maximum {max_tabs} tabs reached"));
         }
         let new_entry = TabEntry {
             panel: current.panel.clone(),
             git_statuses: current.git_statuses.clone(),
             branch_info: current.branch_info.clone(),
             label: current.label.clone(),
+            custom_label: None,
         };
         let mut tab_groups = self.tab_groups;
-        tab_groups[idx] = tab_groups[idx].clone().with_new_tab(new_entry);
+        tab_groups[idx] = tab_groups[idx].clone().with_new_tab(new_entry, max_tabs);
         let tab_num = tab_groups[idx].active_tab_index() + 1;
         Self { tab_groups, ..self }.with_status(format!("Tab {} opened", tab_num))
     }
 
+    /// Open the directory under the cursor in a new tab and switch to it.
+    /// The current tab keeps its place.
+    pub fn open_in_new_tab(self) -> Self {
+        let dir = match self.panel().selected_entry() {
+            Some(e) if e.is_dir() => e.path().to_path_buf(),
+            _ => return self.with_status("Not a directory".to_string()),
+        };
+        let max_tabs = self.max_tabs;
+        if self.active_tab_group().tab_count() >= max_tabs {
+            return self.with_status(format!("Maximum {max_tabs} tabs reached"));
+        }
+        self.enter_dir_checked(&dir, DirLanding::NewTab)
+    }
+
     /// Close the current tab.
     pub fn with_close_tab(self) -> Self {
         let idx = self.active_panel;
@@ -972,6 +2251,125 @@ impl App {
         Self { tab_groups, ..self }
     }
 
+    /// Close every tab except the active one, asking first when more than
+    /// two would close.
+    pub fn request_close_other_tabs(self) -> Self {
+        match self.active_tab_group().tab_count() - 1 {
+            0 => self.with_status("No other tabs to close".to_string()),
+            1 | 2 => self.close_other_tabs(),
+            _ => self.with_mode(AppMode::Confirm(ConfirmAction::CloseOtherTabs)),
+        }
+    }
+
+    /// Close every tab except the active one in the active panel.
+    pub fn close_other_tabs(self) -> Self {
+        let idx = self.active_panel;
+        let closed = self.tab_groups[idx].tab_count() - 1;
+        let mut tab_groups = self.tab_groups;
+        tab_groups[idx] = tab_groups[idx].clone().with_only_active();
+        Self {
+            tab_groups,
+            mode: AppMode::Normal,
+            ..self
+        }
+        .with_status(format!("Closed {closed} tab(s)"))
+    }
+
+    /// Copy the active tab into the other panel's tab group (dual mode only).
+    pub fn with_tab_copied_to_other_panel(self) -> Self {
+        self.transfer_tab_to_other_panel(false)
+    }
+
+    /// Move the active tab into the other panel's tab group (dual mode only).
+    pub fn with_tab_moved_to_other_panel(self) -> Self {
+        self.transfer_tab_to_other_panel(true)
+    }
+
+    /// Inserts the active tab after the other group's active tab and makes
+    /// it active there. Focus stays on this panel.
+    fn transfer_tab_to_other_panel(self, remove: bool) -> Self {
+        if !self.dual_mode {
+            return self.with_status("Other panel is only available in dual mode".to_string());
+        }
+        let idx = self.active_panel;
+        let other = 1 - idx;
+        let max_tabs = self.max_tabs;
+        if self.tab_groups[other].tab_count() >= max_tabs {
+            return self.with_status(format!("Other panel is full ({max_tabs} tabs)"));
+        }
+        let mut tab_groups = self.tab_groups;
+        let entry = if remove {
+            let (group, entry) = tab_groups[idx].clone().take_active();
+            let Some(entry) = entry else {
+                return Self { tab_groups, ..self }
+                    .with_status("Cannot move the last tab".to_string());
+            };
+            tab_groups[idx] = group;
+            entry
+        } else {
+            TabEntry {
+                custom_label: None,
+                ..tab_groups[idx].active_tab().clone()
+            }
+        };
+        tab_groups[other] = tab_groups[other].clone().with_new_tab(entry, max_tabs);
+        let msg = if remove {
+            "Tab moved to other panel"
+        } else {
+            "Tab copied to other panel"
+        };
+        Self { tab_groups, ..self }.with_status(msg.to_string())
+    }
+
+    /// Append a character to the name being typed in RenameTab mode.
+    pub fn rename_tab_push_char(self, c: char) -> Self {
+        match &self.mode {
+            AppMode::RenameTab(name) => {
+                let name = format!("{name}{c}");
+                self.with_mode(AppMode::RenameTab(name))
+            }
+            _ => self,
+        }
+    }
+
+    /// Remove the last character of the name in RenameTab mode.
+    pub fn rename_tab_pop_char(self) -> Self {
+        match &self.mode {
+            AppMode::RenameTab(name) => {
+                let mut name = name.clone();
+                name.pop();
+                self.with_mode(AppMode::RenameTab(name))
+            }
+            _ => self,
+        }
+    }
+
+    /// Name the current tab; an empty name goes back to the directory name.
+    pub fn rename_tab_confirm(self) -> Self {
+        let AppMode::RenameTab(name) = self.mode.clone() else {
+            return self;
+        };
+        let name = name.trim();
+        let custom_label = (!name.is_empty()).then(|| name.to_string());
+        let msg = match &custom_label {
+            Some(name) => format!("Tab renamed to '{name}'"),
+            None => "Tab name reset".to_string(),
+        };
+        let idx = self.active_panel;
+        let entry = TabEntry {
+            custom_label,
+            ..self.tab_groups[idx].active_tab().clone()
+        };
+        let mut tab_groups = self.tab_groups;
+        tab_groups[idx] = tab_groups[idx].clone().with_updated_active(entry);
+        Self {
+            tab_groups,
+            mode: AppMode::Normal,
+            ..self
+        }
+        .with_status(msg)
+    }
+
     /// Update the connection form state (immutable transition).
     pub fn with_connect_form(self, form: ConnectFormState) -> Self {
         Self {
@@ -988,11 +2386,13 @@ impl App {
         let current_tab = self.tab_groups[idx].active_tab();
         let sorted = sort_entries(
             &entries,
-            current_tab.panel.sort_field(),
+            current_tab.panel.sort_field,
             current_tab.panel.sort_direction(),
             true,
         );
-        let visible = filter_hidden(&sorted, current_tab.panel.show_hidden());
+        let visible = current_tab
+            .panel
+            .filter_listing(&filter_hidden(&sorted, current_tab.panel.show_hidden));
         let inner = current_tab
             .panel
             .inner
@@ -1000,9 +2400,8 @@ impl App {
             .with_directory(path, visible);
         let panel = PanelState {
             inner,
-            show_hidden: current_tab.panel.show_hidden,
-            sort_field: current_tab.panel.sort_field,
-            sort_direction: current_tab.panel.sort_direction,
+            local: None,
+            ..current_tab.panel.clone()
         };
         let label = panel
             .current_dir()
@@ -1014,86 +2413,443 @@ impl App {
             git_statuses: None,
             branch_info: None,
             label,
+            custom_label: current_tab.custom_label.clone(),
         };
         let mut tab_groups = self.tab_groups;
         tab_groups[idx] = tab_groups[idx].clone().with_updated_active(entry);
         Self { tab_groups, ..self }
     }
 
-    /// Handle a core Command by producing a new App state.
-    pub fn handle_command(self, cmd: Command) -> Self {
-        match cmd {
-            Command::CursorDown => {
-                let new_panel = self.panel().clone().with_cursor_down();
-                self.with_panel(new_panel)
-            }
-            Command::CursorUp => {
-                let new_panel = self.panel().clone().with_cursor_up();
-                self.with_panel(new_panel)
-            }
-            Command::Enter => self.handle_enter(),
-            Command::GoUp => self.handle_go_up(),
-            Command::GoBack => self.handle_go_back(),
-            Command::GoForward => self.handle_go_forward(),
-            Command::ToggleHidden => self.handle_toggle_hidden(),
-            Command::Refresh => self.handle_refresh(),
-            Command::SetSort(_, _) => self.handle_next_sort(),
-            _ => self,
-        }
+    /// Paths currently held for pasting.
+    pub fn clipboard(&self) -> &[PathBuf] {
+        &self.clipboard
     }
 
-    /// Append a character to the search query and re-filter.
-    pub fn search_push_char(self, c: char) -> Self {
-        let query = match &self.mode {
-            AppMode::Search(q) => format!("{q}{c}"),
-            _ => return self,
-        };
-        let results = fuzzy_filter(self.panel().entries(), &query);
+    /// Append a file operation to the operation log.
+    pub fn with_operation_logged(
+        self,
+        kind: OperationKind,
+        targets: Vec<PathBuf>,
+        result: Result<(), String>,
+    ) -> Self {
+        let mut operation_log = self.operation_log;
+        operation_log.record(kind, targets, result);
         Self {
-            mode: AppMode::Search(query),
-            search_results: results,
-            search_selected: 0,
+            operation_log,
             ..self
         }
     }
 
-    /// Remove the last character from the search query and re-filter.
-    pub fn search_pop_char(self) -> Self {
-        let query = match &self.mode {
-            AppMode::Search(q) => {
-                let mut q = q.clone();
-                q.pop();
-                q
-            }
-            _ => return self,
+    /// Scroll the operation log view down by one line.
+    pub fn operation_log_scroll_down(self) -> Self {
+        let AppMode::OperationLog { scroll } = self.mode else {
+            return self;
         };
-        let results = fuzzy_filter(self.panel().entries(), &query);
-        Self {
-            mode: AppMode::Search(query),
-            search_results: results,
-            search_selected: 0,
-            ..self
-        }
+        let max = self.operation_log.len().saturating_sub(1);
+        self.with_mode(AppMode::OperationLog {
+            scroll: (scroll + 1).min(max),
+        })
     }
 
-    /// Move the search result cursor down.
-    pub fn search_move_down(self) -> Self {
-        if self.search_results.is_empty() {
+    /// Scroll the notifications view down by one message.
+    pub fn notifications_scroll_down(self) -> Self {
+        let AppMode::Notifications { scroll } = self.mode else {
             return self;
-        }
-        let max = self.search_results.len() - 1;
-        let next = if self.search_selected >= max {
-            max
-        } else {
-            self.search_selected + 1
         };
-        Self {
-            search_selected: next,
-            ..self
-        }
+        let max = self.notifications.len().saturating_sub(1);
+        self.with_mode(AppMode::Notifications {
+            scroll: (scroll + 1).min(max),
+        })
     }
 
-    /// Move the search result cursor up.
+    /// Scroll the notifications view up by one message.
+    pub fn notifications_scroll_up(self) -> Self {
+        let AppMode::Notifications { scroll } = self.mode else {
+            return self;
+        };
+        self.with_mode(AppMode::Notifications {
+            scroll: scroll.saturating_sub(1),
+        })
+    }
+
+    /// Scroll the operation log view up by one line.
+    pub fn operation_log_scroll_up(self) -> Self {
+        let AppMode::OperationLog { scroll } = self.mode else {
+            return self;
+        };
+        self.with_mode(AppMode::OperationLog {
+            scroll: scroll.saturating_sub(1),
+        })
+    }
+
+    /// Delete the selected entry, asking first when `confirm_scope` says
+    /// so for its kind.
+    pub fn request_delete(self) -> Self {
+        let Some(entry) = self.panel().selected_entry() else {
+            return self;
+        };
+        let path = entry.path().to_path_buf();
+        if self.confirm_scope.needs_confirm(entry.is_dir()) {
+            self.with_mode(AppMode::Confirm(ConfirmAction::Delete(vec![path])))
+        } else {
+            self.delete_paths(&[path])
+        }
+    }
+
+    /// Delete the given paths, stopping at the first failure.
+    pub fn delete_paths(self, paths: &[PathBuf]) -> Self {
+        let mut app = self.with_mode(AppMode::Normal);
+        for path in paths {
+            let result = delete_file(path).map_err(|e| e.to_string());
+            app = app.with_operation_logged(
+                OperationKind::Delete,
+                vec![path.clone()],
+                result.clone(),
+            );
+            if let Err(e) = result {
+                return app
+                    .handle_refresh()
+                    .with_status(format!("Delete failed: {e}"));
+            }
+        }
+        let msg = format!("Deleted {} item(s)", paths.len());
+        match app.panel().refresh() {
+            Ok(new_panel) => app.with_panel(new_panel).with_success(msg),
+            Err(e) => app.with_status(format!("Error refreshing: {e}")),
+        }
+    }
+
+    /// Copy the selected entry next to itself under a free name and put the
+    /// cursor on the copy.
+    pub fn duplicate_in_place(self) -> Self {
+        if self.is_remote() {
+            return self.with_status("Duplicate not supported in remote mode".to_string());
+        }
+        let src = match self.panel().selected_entry() {
+            Some(e) if !e.is_parent_link() => e.path().to_path_buf(),
+            _ => return self.with_status("No file selected".to_string()),
+        };
+        let result = duplicate_in_place(&src);
+        let targets = match &result {
+            Ok(dest) => vec![src.clone(), dest.clone()],
+            Err(_) => vec![src.clone()],
+        };
+        let app = self.with_operation_logged(
+            OperationKind::Copy,
+            targets,
+            result.as_ref().map(|_| ()).map_err(|e| e.to_string()),
+        );
+        let dest = match result {
+            Ok(dest) => dest,
+            Err(e) => return app.with_status(format!("Duplicate failed: {e}")),
+        };
+        match app.panel().refresh() {
+            Ok(new_panel) => {
+                let idx = new_panel
+                    .entries()
+                    .iter()
+                    .position(|e| e.path() == dest)
+                    .unwrap_or(0);
+                let name = dest
+                    .file_name()
+                    .map(|n| n.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                app.with_panel(new_panel.with_cursor_to(idx))
+                    .with_success(format!("Duplicated as {name}"))
+            }
+            Err(e) => app.with_status(format!("Error refreshing: {e}")),
+        }
+    }
+
+    /// Append a character to the new name being typed in Rename mode.
+    pub fn rename_push_char(self, c: char) -> Self {
+        match &self.mode {
+            AppMode::Rename(name) => {
+                let name = format!("{name}{c}");
+                self.with_mode(AppMode::Rename(name))
+            }
+            _ => self,
+        }
+    }
+
+    /// Remove the last character of the new name in Rename mode.
+    pub fn rename_pop_char(self) -> Self {
+        match &self.mode {
+            AppMode::Rename(name) => {
+                let mut name = name.clone();
+                name.pop();
+                self.with_mode(AppMode::Rename(name))
+            }
+            _ => self,
+        }
+    }
+
+    /// Rename the selected entry to the name typed in Rename mode.
+    ///
+    /// Pauses in [`ConfirmAction::RenameOverwrite`] when the name is taken
+    /// and `general.confirm_overwrite` is on.
+    pub fn rename_confirm(self) -> Self {
+        let AppMode::Rename(name) = self.mode.clone() else {
+            return self;
+        };
+        let app = self.with_mode(AppMode::Normal);
+        let name = name.trim();
+        let Some(src) = app.panel().selected_entry().map(|e| e.path().to_path_buf()) else {
+            return app;
+        };
+        if name.is_empty() {
+            return app;
+        }
+        let overwrite = !app.confirm_overwrite;
+        app.apply_rename(src, name, overwrite)
+    }
+
+    /// Rename after the user agreed to replace the existing entry.
+    pub fn rename_overwrite(self, src: PathBuf, name: &str) -> Self {
+        self.with_mode(AppMode::Normal)
+            .apply_rename(src, name, true)
+    }
+
+    fn apply_rename(self, src: PathBuf, name: &str, overwrite: bool) -> Self {
+        let dest = src.with_file_name(name);
+        let result = rename_file_checked(&src, name, overwrite);
+        if let Err(CoreError::AlreadyExists(_)) = result {
+            return self.with_mode(AppMode::Confirm(ConfirmAction::RenameOverwrite {
+                src,
+                name: name.to_string(),
+            }));
+        }
+        let result = result.map_err(|e| e.to_string());
+        let app = self.with_operation_logged(
+            OperationKind::Rename,
+            vec![src, dest.clone()],
+            result.clone(),
+        );
+        match result {
+            Ok(()) => app.reveal(&dest).with_success(format!("Renamed to {name}")),
+            Err(e) => app.with_status(format!("Rename failed: {e}")),
+        }
+    }
+
+    /// Put the selected entry on the clipboard.
+    pub fn yank_selected(self) -> Self {
+        let Some(entry) = self.panel().selected_entry() else {
+            return self;
+        };
+        let msg = format!("Yanked: {}", entry.name());
+        let path = entry.path().to_path_buf();
+        Self {
+            clipboard: vec![path],
+            ..self
+        }
+        .with_status(msg)
+    }
+
+    /// Paste the clipboard into the current directory.
+    ///
+    /// Pauses in [`ConfirmAction::PasteConflict`] whenever a destination
+    /// already exists.
+    pub fn paste(self) -> Self {
+        if self.clipboard.is_empty() {
+            return self.with_status("Nothing to paste".to_string());
+        }
+        let queue = PasteQueue::new(self.clipboard.clone(), self.panel().current_dir());
+        self.continue_paste(queue)
+    }
+
+    /// Copy the selected entry into the other panel's directory (dual mode only).
+    pub fn copy_to_other_panel(self) -> Self {
+        self.transfer_to_other_panel(PasteMode::Copy)
+    }
+
+    /// Move the selected entry into the other panel's directory (dual mode only).
+    pub fn move_to_other_panel(self) -> Self {
+        self.transfer_to_other_panel(PasteMode::Move)
+    }
+
+    /// Pastes the selected entry into the inactive panel's directory,
+    /// with the same conflict prompts as [`App::paste`].
+    fn transfer_to_other_panel(self, mode: PasteMode) -> Self {
+        if !self.dual_mode {
+            return self.with_status("Other panel is only available in dual mode".to_string());
+        }
+        if self.is_remote() {
+            return self.with_status("Not supported in remote mode".to_string());
+        }
+        let Some(src) = self
+            .panel()
+            .selected_entry()
+            .map(|e| e.path().to_path_buf())
+        else {
+            return self;
+        };
+        let dest_dir = self.inactive_panel().current_dir().to_path_buf();
+        if dest_dir == self.panel().current_dir() {
+            return self.with_status("Both panels show the same directory".to_string());
+        }
+        let queue = PasteQueue::new(vec![src], &dest_dir).with_mode(mode);
+        self.continue_paste(queue)
+    }
+
+    /// Apply the user's choice to the pending paste conflict.
+    pub fn paste_resolve(self, choice: ConflictChoice) -> Self {
+        let queue = match &self.mode {
+            AppMode::Confirm(ConfirmAction::PasteConflict(q)) => q.clone(),
+            _ => return self,
+        };
+        self.continue_paste(queue.resolve(choice))
+    }
+
+    /// Either prompts for the next conflict or runs the finished plan.
+    fn continue_paste(self, queue: PasteQueue) -> Self {
+        let queue = if self.confirm_overwrite {
+            queue
+        } else {
+            queue.resolve(ConflictChoice::OverwriteAll)
+        };
+        if !queue.is_done() {
+            return self.with_mode(AppMode::Confirm(ConfirmAction::PasteConflict(queue)));
+        }
+
+        let mode = queue.mode();
+        let (kind, noun, verb) = match mode {
+            PasteMode::Copy => (OperationKind::Copy, "Paste", "Pasted"),
+            PasteMode::Move => (OperationKind::Move, "Move", "Moved"),
+        };
+        let mut app = self.with_mode(AppMode::Normal);
+        let steps = queue.into_steps();
+        let mut pasted = 0;
+        for step in &steps {
+            let targets = match step {
+                PasteStep::Copy { src, dest } | PasteStep::Replace { src, dest } => {
+                    vec![src.clone(), dest.clone()]
+                }
+                PasteStep::Skip(_) => continue,
+            };
+            match execute_paste_step(step, mode) {
+                Ok(_) => {
+                    pasted += 1;
+                    app = app.with_operation_logged(kind, targets, Ok(()));
+                }
+                Err(e) => {
+                    return app
+                        .with_operation_logged(kind, targets, Err(e.to_string()))
+                        .handle_refresh()
+                        .refresh_other_panel()
+                        .with_status(format!("{noun} failed: {e}"))
+                }
+            }
+        }
+        let skipped = steps
+            .iter()
+            .filter(|s| matches!(s, PasteStep::Skip(_)))
+            .count();
+        let msg = if skipped > 0 {
+            format!("{verb} {pasted} item(s), skipped {skipped}")
+        } else {
+            format!("{verb} {pasted} item(s)")
+        };
+        app.handle_refresh().refresh_other_panel().with_success(msg)
+    }
+
+    /// Re-reads the inactive panel's directory when it is on screen.
+    fn refresh_other_panel(self) -> Self {
+        if !self.dual_mode {
+            return self;
+        }
+        let active = self.active_panel;
+        self.with_active_panel(1 - active)
+            .handle_refresh()
+            .with_active_panel(active)
+    }
+
+    /// Handle a core Command by producing a new App state.
+    pub fn handle_command(self, cmd: Command) -> Self {
+        match cmd {
+            Command::CursorDown => {
+                let new_panel = self.panel().clone().with_cursor_down();
+                self.with_panel(new_panel)
+            }
+            Command::CursorUp => {
+                let new_panel = self.panel().clone().with_cursor_up();
+                self.with_panel(new_panel)
+            }
+            Command::Enter => self.handle_enter(),
+            Command::GoUp => self.handle_go_up(),
+            Command::GoBack => self.handle_go_back(),
+            Command::GoForward => self.handle_go_forward(),
+            Command::ToggleHidden => self.handle_toggle_hidden(),
+            Command::Refresh => self.with_repo_roots_cleared().handle_refresh(),
+            Command::SetSort(_, _) => self.handle_next_sort(),
+            _ => self,
+        }
+    }
+
+    /// Append a character to the search query and re-filter.
+    pub fn search_push_char(self, c: char) -> Self {
+        let query = match &self.mode {
+            AppMode::Search(q) => format!("{q}{c}"),
+            _ => return self,
+        };
+        let results = self.fuzzy_results(&query);
+        Self {
+            mode: AppMode::Search(query),
+            search_results: results,
+            search_selected: 0,
+            ..self
+        }
+    }
+
+    /// Remove the last character from the search query and re-filter.
+    pub fn search_pop_char(self) -> Self {
+        let query = match &self.mode {
+            AppMode::Search(q) => {
+                let mut q = q.clone();
+                q.pop();
+                q
+            }
+            _ => return self,
+        };
+        let results = self.fuzzy_results(&query);
+        Self {
+            mode: AppMode::Search(query),
+            search_results: results,
+            search_selected: 0,
+            ..self
+        }
+    }
+
+    /// Fuzzy-matches the active panel's entries, leaving out excluded ones.
+    fn fuzzy_results(&self, query: &str) -> Vec<FuzzyMatch> {
+        let candidates: Vec<FileEntry> = self
+            .panel()
+            .entries()
+            .iter()
+            .filter(|e| !e.is_parent_link())
+            .filter(|e| !self.search_exclude.is_excluded(Path::new(e.name())))
+            .cloned()
+            .collect();
+        fuzzy_filter_with(&candidates, query, self.fuzzy_algorithm)
+    }
+
+    /// Move the search result cursor down.
+    pub fn search_move_down(self) -> Self {
+        if self.search_results.is_empty() {
+            return self;
+        }
+        let max = self.search_results.len() - 1;
+        let next = if self.search_selected >= max {
+            max
+        } else {
+            self.search_selected + 1
+        };
+        Self {
+            search_selected: next,
+            ..self
+        }
+    }
+
+    /// Move the search result cursor up.
     pub fn search_move_up(self) -> Self {
         let next = self.search_selected.saturating_sub(1);
         Self {
@@ -1124,10 +2880,7 @@ impl App {
         };
 
         if entry.is_dir() {
-            match app.panel().navigate_to(entry.path()) {
-                Ok(new_panel) => app.with_panel(new_panel),
-                Err(e) => app.with_status(format!("Error: {e}")),
-            }
+            app.enter_dir_checked(entry.path(), DirLanding::Here)
         } else {
             // Select the matching entry in the panel
             let idx = app
@@ -1154,9 +2907,9 @@ impl App {
         Self {
             mode: AppMode::Normal,
             bookmarks,
-            status_message: Some(format!("Bookmark '{label}' added")),
             ..self
         }
+        .with_success(format!("Bookmark '{label}' added"))
     }
 
     /// Navigate to the selected bookmark.
@@ -1171,12 +2924,13 @@ impl App {
             None => return self.with_mode(AppMode::Normal),
         };
 
-        let app = self.with_mode(AppMode::Normal);
-        match app.panel().navigate_to(&path) {
-            Ok(new_panel) => app
-                .with_panel(new_panel)
-                .with_status(format!("Jumped to '{label}'")),
-            Err(e) => app.with_status(format!("Error: {e}")),
+        let app = self
+            .with_mode(AppMode::Normal)
+            .enter_dir_checked(&path, DirLanding::Here);
+        if matches!(app.mode, AppMode::Normal) && app.is_in(&path) {
+            app.with_status(format!("Jumped to '{label}'"))
+        } else {
+            app
         }
     }
 
@@ -1199,9 +2953,9 @@ impl App {
                 selected: new_selected,
             },
             bookmarks,
-            status_message: Some(format!("Bookmark '{label}' removed")),
             ..self
         }
+        .with_success(format!("Bookmark '{label}' removed"))
     }
 
     /// Scan for recently changed files and enter RecentFiles mode.
@@ -1246,137 +3000,478 @@ impl App {
 
     /// Confirm the selected recent file — navigate to its parent directory and select it.
     pub fn recent_confirm(self) -> Self {
-        let entry = match self.recent_results.get(self.recent_selected) {
-            Some(e) => e.clone(),
-            None => {
-                return Self {
-                    mode: AppMode::Normal,
-                    recent_results: Vec::new(),
-                    recent_selected: 0,
-                    ..self
-                }
-            }
-        };
-
-        let parent = match entry.path().parent() {
-            Some(p) => p.to_path_buf(),
-            None => {
-                return Self {
-                    mode: AppMode::Normal,
-                    recent_results: Vec::new(),
-                    recent_selected: 0,
-                    ..self
-                }
-            }
-        };
-
+        let path = self
+            .recent_results
+            .get(self.recent_selected)
+            .map(|e| e.path().to_path_buf());
         let app = Self {
             mode: AppMode::Normal,
             recent_results: Vec::new(),
             recent_selected: 0,
             ..self
         };
-
-        match app.panel().navigate_to(&parent) {
-            Ok(new_panel) => {
-                let idx = new_panel
-                    .entries()
-                    .iter()
-                    .position(|e| e.path() == entry.path())
-                    .unwrap_or(0);
-                let new_panel = new_panel.with_cursor_to(idx);
-                app.with_panel(new_panel)
-            }
-            Err(e) => app.with_status(format!("Error: {e}")),
+        match path {
+            Some(p) => app.reveal(&p),
+            None => app,
         }
     }
 
-    /// Enter DuplicateFiles mode showing cached results instantly.
-    pub fn show_duplicate_files(self) -> Self {
-        Self {
-            mode: AppMode::DuplicateFiles,
-            duplicate_selected: 0,
-            ..self
-        }
+    /// Recently visited directories matching `query`, best first, leaving
+    /// out the active panel's own directory.
+    pub fn recent_dir_matches(&self, query: &str) -> Vec<RecentDirMatch> {
+        self.recent_dirs
+            .matching(query, self.fuzzy_algorithm, self.panel().current_dir())
     }
 
-    /// Request deletion of the currently selected duplicate file.
-    pub fn duplicate_delete_selected(self) -> Self {
-        let mut idx = 0;
-        let mut target_path = None;
-        for group in &self.duplicate_cache.groups {
-            for file in &group.files {
-                if idx == self.duplicate_selected {
-                    target_path = Some(file.path.clone());
-                    break;
-                }
-                idx += 1;
-            }
-            if target_path.is_some() {
-                break;
-            }
-        }
+    /// Append a character to the recent directories query.
+    pub fn recent_dirs_push_char(self, c: char) -> Self {
+        let AppMode::RecentDirs { query, .. } = &self.mode else {
+            return self;
+        };
+        let query = format!("{query}{c}");
+        self.with_mode(AppMode::RecentDirs { query, selected: 0 })
+    }
 
-        match target_path {
-            Some(path) => Self {
-                mode: AppMode::Confirm(ConfirmAction::DeleteDuplicate(path)),
-                ..self
-            },
-            None => self,
-        }
+    /// Remove the last character from the recent directories query.
+    pub fn recent_dirs_pop_char(self) -> Self {
+        let AppMode::RecentDirs { query, .. } = &self.mode else {
+            return self;
+        };
+        let mut query = query.clone();
+        query.pop();
+        self.with_mode(AppMode::RecentDirs { query, selected: 0 })
     }
 
-    /// Move the duplicate files cursor down (flat index).
-    pub fn duplicate_move_down(self) -> Self {
-        let total: usize = self
-            .duplicate_cache
-            .groups
-            .iter()
-            .map(|g| g.files.len())
-            .sum();
-        if total == 0 {
+    /// Move the recent directories cursor down.
+    pub fn recent_dirs_move_down(self) -> Self {
+        let AppMode::RecentDirs { query, selected } = &self.mode else {
             return self;
-        }
-        let max = total - 1;
-        let next = if self.duplicate_selected >= max {
-            max
-        } else {
-            self.duplicate_selected + 1
         };
-        Self {
-            duplicate_selected: next,
-            ..self
-        }
+        let max = self.recent_dir_matches(query).len().saturating_sub(1);
+        let selected = (selected + 1).min(max);
+        let query = query.clone();
+        self.with_mode(AppMode::RecentDirs { query, selected })
     }
 
-    /// Move the duplicate files cursor up.
-    pub fn duplicate_move_up(self) -> Self {
-        let next = self.duplicate_selected.saturating_sub(1);
+    /// Move the recent directories cursor up.
+    pub fn recent_dirs_move_up(self) -> Self {
+        let AppMode::RecentDirs { query, selected } = &self.mode else {
+            return self;
+        };
+        let selected = selected.saturating_sub(1);
+        let query = query.clone();
+        self.with_mode(AppMode::RecentDirs { query, selected })
+    }
+
+    /// Navigate to the selected recent directory.
+    pub fn recent_dirs_confirm(self) -> Self {
+        let AppMode::RecentDirs { query, selected } = &self.mode else {
+            return self;
+        };
+        let target = self
+            .recent_dir_matches(query)
+            .into_iter()
+            .nth(*selected)
+            .map(|m| m.path);
+        let app = self.with_mode(AppMode::Normal);
+        let Some(path) = target else {
+            return app;
+        };
+        app.enter_dir_checked(&path, DirLanding::Here)
+    }
+
+    /// Saves the recent directories; called on quit.
+    pub fn save_recent_dirs(&self) {
+        if let Err(e) = self.recent_dirs.save_to_file(&self.recent_dirs_path) {
+            tracing::warn!("Failed to save {}: {e}", self.recent_dirs_path.display());
+        }
+    }
+
+    /// Begin a new content search and switch to the results view.
+    ///
+    /// Any search still running is told to stop. The caller spawns the
+    /// background worker using the returned app's `content_search().id`.
+    pub fn content_search_start(self, query: String, cancel: Arc<AtomicBool>) -> Self {
+        if let Some(old) = &self.content_search.cancel {
+            old.store(true, Ordering::Relaxed);
+        }
+        let content_search = ContentSearchState {
+            id: self.content_search.id + 1,
+            query,
+            running: true,
+            cancel: Some(cancel),
+            ..ContentSearchState::default()
+        };
         Self {
-            duplicate_selected: next,
+            mode: AppMode::ContentSearch,
+            content_search,
             ..self
         }
     }
 
-    /// Confirm the selected duplicate file — navigate to its parent directory.
-    pub fn duplicate_confirm(self) -> Self {
+    /// Update the live counters of the running search.
+    pub fn with_content_search_progress(self, id: u64, progress: SearchProgress) -> Self {
+        if id != self.content_search.id || !self.content_search.running {
+            return self;
+        }
+        Self {
+            content_search: ContentSearchState {
+                progress,
+                ..self.content_search
+            },
+            ..self
+        }
+    }
+
+    /// Store the final (or partial, if cancelled) result of a search.
+    pub fn with_content_search_result(self, id: u64, result: ContentSearchResult) -> Self {
+        if id != self.content_search.id {
+            return self;
+        }
+        let selected = self
+            .content_search
+            .selected
+            .min(result.matches.len().saturating_sub(1));
+        Self {
+            content_search: ContentSearchState {
+                progress: result.progress(),
+                results: result.matches,
+                cancelled: result.cancelled,
+                running: false,
+                cancel: None,
+                selected,
+                ..self.content_search
+            },
+            ..self
+        }
+    }
+
+    /// Record a failed search.
+    pub fn with_content_search_error(self, id: u64, message: String) -> Self {
+        if id != self.content_search.id {
+            return self;
+        }
+        Self {
+            content_search: ContentSearchState {
+                running: false,
+                cancel: None,
+                ..self.content_search
+            },
+            ..self
+        }
+        .with_status(format!("Content search failed: {message}"))
+    }
+
+    /// Stop the running search (partial results are kept), or close the
+    /// results view if the search has already finished.
+    pub fn content_search_stop(self) -> Self {
+        match (&self.content_search.cancel, self.content_search.running) {
+            (Some(cancel), true) => {
+                cancel.store(true, Ordering::Relaxed);
+                self.with_status("Stopping search…".to_string())
+            }
+            _ => Self {
+                mode: AppMode::Normal,
+                ..self
+            },
+        }
+    }
+
+    /// Move the content search cursor down.
+    pub fn content_search_move_down(self) -> Self {
+        let max = self.content_search.results.len().saturating_sub(1);
+        let next = (self.content_search.selected + 1).min(max);
+        Self {
+            content_search: ContentSearchState {
+                selected: next,
+                ..self.content_search
+            },
+            ..self
+        }
+    }
+
+    /// Move the content search cursor up.
+    pub fn content_search_move_up(self) -> Self {
+        let next = self.content_search.selected.saturating_sub(1);
+        Self {
+            content_search: ContentSearchState {
+                selected: next,
+                ..self.content_search
+            },
+            ..self
+        }
+    }
+
+    /// Jump to the file of the selected match — navigate to its parent and
+    /// select it. A running search keeps going in the background.
+    pub fn content_search_confirm(self) -> Self {
+        let path = match self
+            .content_search
+            .results
+            .get(self.content_search.selected)
+        {
+            Some(m) => m.path.clone(),
+            None => return self.with_mode(AppMode::Normal),
+        };
+        self.with_mode(AppMode::Normal).reveal(&path)
+    }
+
+    /// Collect files with uncommitted changes in the current repository and
+    /// enter GitChanges mode.
+    pub fn load_git_changes(self) -> Self {
+        let Some(root) = self.repo_roots.get_or_find(self.panel().current_dir()) else {
+            return self.with_status("Not inside a git repository".to_string());
+        };
+        match get_file_statuses(&root) {
+            Ok(statuses) => Self {
+                mode: AppMode::GitChanges,
+                git_changes: changed_files(&statuses),
+                git_changes_selected: 0,
+                ..self
+            },
+            Err(e) => self.with_status(format!("Error reading git status: {e}")),
+        }
+    }
+
+    /// Move the git changes cursor down.
+    pub fn git_changes_move_down(self) -> Self {
+        let max = self.git_changes.len().saturating_sub(1);
+        Self {
+            git_changes_selected: (self.git_changes_selected + 1).min(max),
+            ..self
+        }
+    }
+
+    /// Move the git changes cursor up.
+    pub fn git_changes_move_up(self) -> Self {
+        Self {
+            git_changes_selected: self.git_changes_selected.saturating_sub(1),
+            ..self
+        }
+    }
+
+    /// Jump to the selected changed file.
+    pub fn git_changes_confirm(self) -> Self {
+        let path = self
+            .git_changes
+            .get(self.git_changes_selected)
+            .map(|(p, _)| p.clone());
+        let app = Self {
+            mode: AppMode::Normal,
+            git_changes: Vec::new(),
+            git_changes_selected: 0,
+            ..self
+        };
+        match path {
+            Some(p) => app.reveal(&p),
+            None => app,
+        }
+    }
+
+    /// Navigate to `path`'s parent directory and put the cursor on it.
+    fn reveal(self, path: &Path) -> Self {
+        let Some(parent) = path.parent() else {
+            return self;
+        };
+        self.enter_dir_checked(parent, DirLanding::Select(path.to_path_buf()))
+    }
+
+    /// Enter DuplicateFiles mode showing cached results instantly.
+    pub fn show_duplicate_files(self) -> Self {
+        Self {
+            mode: AppMode::DuplicateFiles,
+            duplicate_selected: 0,
+            local_duplicates: None,
+            ..self
+        }
+    }
+
+    /// Open the duplicate view for a new scan of the active panel's
+    /// directory. The caller spawns the scan with the returned
+    /// [`LocalDuplicateScan::id`].
+    pub fn start_local_duplicate_scan(self) -> Self {
+        if self.is_remote() {
+            return self.with_status("Duplicate scan not supported in remote mode".to_string());
+        }
+        let root = self.panel().current_dir().to_path_buf();
+        let id = self.local_scan_id + 1;
+        let msg = format!("Scanning {} for duplicates", root.display());
+        let scan = LocalDuplicateScan {
+            id,
+            cache: DuplicateCache {
+                scan_root: Some(root.clone()),
+                ..DuplicateCache::default()
+            },
+            root,
+            running: true,
+        };
+        Self {
+            mode: AppMode::DuplicateFiles,
+            duplicate_selected: 0,
+            local_duplicates: Some(scan),
+            local_scan_id: id,
+            ..self
+        }
+        .with_operation_started()
+        .with_status(msg)
+    }
+
+    /// Install the result of local scan `id`, unless a newer scan or the
+    /// global view replaced it.
+    pub fn with_local_duplicates(self, id: u64, cache: DuplicateCache) -> Self {
+        let Some(scan) = self.local_duplicates.clone().filter(|s| s.id == id) else {
+            return self;
+        };
+        let msg = format!(
+            "{} duplicate group(s) under {}",
+            cache.groups.len(),
+            scan.root.display()
+        );
+        let scan = LocalDuplicateScan {
+            running: false,
+            cache: cache.sorted_by(self.duplicate_sort),
+            ..scan
+        };
+        Self {
+            local_duplicates: Some(scan),
+            ..self
+        }
+        .with_status(msg)
+    }
+
+    /// Record a failed local scan `id`.
+    pub fn with_local_duplicate_error(self, id: u64, message: String) -> Self {
+        let Some(scan) = self.local_duplicates.clone().filter(|s| s.id == id) else {
+            return self;
+        };
+        Self {
+            local_duplicates: Some(LocalDuplicateScan {
+                running: false,
+                ..scan
+            }),
+            ..self
+        }
+        .with_status(format!("Duplicate scan failed: {message}"))
+    }
+
+    /// Drop a deleted file from the local scan's results.
+    pub fn with_local_duplicate_removed(self, path: &Path) -> Self {
+        let local_duplicates = self.local_duplicates.map(|scan| LocalDuplicateScan {
+            cache: scan.cache.remove_file(path),
+            ..scan
+        });
+        Self {
+            local_duplicates,
+            ..self
+        }
+    }
+
+    pub fn duplicate_sort(&self) -> DuplicateSort {
+        self.duplicate_sort
+    }
+
+    /// Switch the duplicate view to the next sort order and move the cursor
+    /// back to the top.
+    pub fn duplicate_cycle_sort(self) -> Self {
+        let duplicate_sort = self.duplicate_sort.next();
+        let duplicate_cache = self.duplicate_cache.sorted_by(duplicate_sort);
+        let local_duplicates = self.local_duplicates.map(|scan| LocalDuplicateScan {
+            cache: scan.cache.sorted_by(duplicate_sort),
+            ..scan
+        });
+        Self {
+            duplicate_sort,
+            duplicate_cache,
+            local_duplicates,
+            duplicate_selected: 0,
+            ..self
+        }
+        .with_status(format!("Duplicates sorted by {}", duplicate_sort.label()))
+    }
+
+    /// Returns the visible group and file path under the duplicate cursor.
+    fn selected_duplicate(&self) -> Option<(CachedDuplicateGroup, PathBuf)> {
         let mut idx = 0;
-        let mut target_path = None;
-        for group in &self.duplicate_cache.groups {
+        for group in self.duplicate_results() {
             for file in &group.files {
                 if idx == self.duplicate_selected {
-                    target_path = Some(file.path.clone());
-                    break;
+                    let path = file.path.clone();
+                    return Some((group, path));
                 }
                 idx += 1;
             }
-            if target_path.is_some() {
-                break;
-            }
         }
+        None
+    }
+
+    /// Request deletion of the currently selected duplicate file.
+    pub fn duplicate_delete_selected(self) -> Self {
+        match self.selected_duplicate() {
+            Some((_, path)) => Self {
+                mode: AppMode::Confirm(ConfirmAction::DeleteDuplicate(path)),
+                ..self
+            },
+            None => self,
+        }
+    }
+
+    /// Hide the selected file's group from the duplicate view for good by
+    /// adding its content hash to the ignore list and saving it.
+    pub fn duplicate_ignore_selected(self) -> Self {
+        let Some((group, _)) = self.selected_duplicate() else {
+            return self;
+        };
+        let duplicate_ignore = self.duplicate_ignore.clone().with_hash(group.hash);
+        let message = match duplicate_ignore.save(&self.duplicate_ignore_path) {
+            Ok(()) => format!("Ignoring group of {} file(s)", group.files.len()),
+            Err(e) => format!("Ignored for this session, could not save: {e}"),
+        };
+        let total: usize = self
+            .shown_duplicates()
+            .filtered(&duplicate_ignore)
+            .iter()
+            .map(|g| g.files.len())
+            .sum();
+        let duplicate_selected = self.duplicate_selected.min(total.saturating_sub(1));
+        Self {
+            duplicate_ignore,
+            duplicate_selected,
+            ..self
+        }
+        .with_status(message)
+    }
+
+    /// Move the duplicate files cursor down (flat index).
+    pub fn duplicate_move_down(self) -> Self {
+        let total: usize = self.duplicate_results().iter().map(|g| g.files.len()).sum();
+        if total == 0 {
+            return self;
+        }
+        let max = total - 1;
+        let next = if self.duplicate_selected >= max {
+            max
+        } else {
+            self.duplicate_selected + 1
+        };
+        Self {
+            duplicate_selected: next,
+            ..self
+        }
+    }
+
+    /// Move the duplicate files cursor up.
+    pub fn duplicate_move_up(self) -> Self {
+        let next = self.duplicate_selected.saturating_sub(1);
+        Self {
+            duplicate_selected: next,
+            ..self
+        }
+    }
 
-        let file_path = match target_path {
-            Some(p) => p,
+    /// Confirm the selected duplicate file — navigate to its parent directory.
+    pub fn duplicate_confirm(self) -> Self {
+        let file_path = match self.selected_duplicate() {
+            Some((_, p)) => p,
             None => {
                 return Self {
                     mode: AppMode::Normal,
@@ -1403,39 +3498,150 @@ impl App {
             ..self
         };
 
-        match app.panel().navigate_to(&parent) {
-            Ok(new_panel) => {
-                let cursor_idx = new_panel
-                    .entries()
-                    .iter()
-                    .position(|e| e.path() == file_path)
-                    .unwrap_or(0);
-                let new_panel = new_panel.with_cursor_to(cursor_idx);
-                app.with_panel(new_panel)
-            }
-            Err(e) => app.with_status(format!("Error: {e}")),
-        }
+        app.enter_dir_checked(&parent, DirLanding::Select(file_path))
     }
 
     fn handle_enter(self) -> Self {
+        if self.panel().on_parent_entry() {
+            return self.handle_go_up();
+        }
         let entry = match self.panel().selected_entry() {
             Some(e) if e.is_dir() => e.clone(),
-            _ => return self,
+            Some(e) => {
+                let path = e.path().to_path_buf();
+                return self.open_file(path);
+            }
+            None => return self,
         };
 
-        match self.panel().navigate_to(entry.path()) {
-            Ok(new_panel) => self.with_panel(new_panel),
-            Err(e) => self.with_status(format!("Error: {e}")),
+        self.enter_dir_checked(entry.path(), DirLanding::Here)
+    }
+
+    /// Enter `dir` as `landing` says, asking first when it holds more than
+    /// `large_dir_threshold` entries. Every user-driven move to another
+    /// directory goes through here.
+    pub fn enter_dir_checked(self, dir: &Path, landing: DirLanding) -> Self {
+        let panel = match landing {
+            DirLanding::OtherPanel => self.inactive_panel(),
+            _ => self.panel(),
+        };
+        let shown = dir.canonicalize().is_ok_and(|d| d == panel.current_dir());
+        if !shown && self.is_large_dir(dir) {
+            let dir = dir.to_path_buf();
+            return self.with_mode(AppMode::Confirm(ConfirmAction::EnterLargeDir {
+                dir,
+                landing,
+            }));
         }
+        self.enter_dir(dir, landing)
     }
 
-    fn handle_go_up(self) -> Self {
-        match self.panel().go_up() {
-            Ok(new_panel) => self.with_panel(new_panel),
-            Err(e) => self.with_status(format!("Error: {e}")),
+    /// Enter `dir` as `landing` says, whatever its size.
+    pub fn enter_dir(self, dir: &Path, landing: DirLanding) -> Self {
+        let active = self.active_panel;
+        let app = match landing {
+            DirLanding::OtherPanel => self.with_active_panel(1 - active),
+            _ => self,
+        }
+        .with_mode(AppMode::Normal);
+        let entered = match landing {
+            DirLanding::Parent => app.panel().go_up(),
+            _ => app.panel().navigate_to(dir),
+        };
+        let new_panel = match entered {
+            Ok(new_panel) => new_panel,
+            Err(e) => {
+                return app
+                    .with_active_panel(active)
+                    .with_status(format!("Error: {e}"))
+            }
+        };
+        match landing {
+            DirLanding::Select(path) => {
+                let idx = new_panel.entries().iter().position(|e| e.path() == path);
+                let new_panel = match idx {
+                    Some(idx) => new_panel.with_cursor_to(idx),
+                    None => new_panel,
+                };
+                app.with_panel(new_panel)
+            }
+            DirLanding::NewTab => app.with_new_tab().with_panel(new_panel),
+            DirLanding::OtherPanel => app.with_panel(new_panel).with_active_panel(active),
+            DirLanding::Here | DirLanding::Parent => app.with_panel(new_panel),
+        }
+    }
+
+    /// Whether the active panel is showing `dir`.
+    fn is_in(&self, dir: &Path) -> bool {
+        dir.canonicalize()
+            .is_ok_and(|d| d == self.panel().current_dir())
+    }
+
+    /// Whether `dir` holds more than `large_dir_threshold` entries. Only
+    /// the listing is counted, and never past the threshold.
+    fn is_large_dir(&self, dir: &Path) -> bool {
+        let threshold = self.large_dir_threshold;
+        if threshold == 0 || self.is_remote() {
+            return false;
+        }
+        count_entries(dir, threshold.saturating_add(1)).is_ok_and(|count| count > threshold)
+    }
+
+    /// Entry count past which entering a directory asks first (0 = never).
+    pub fn large_dir_threshold(&self) -> usize {
+        self.large_dir_threshold
+    }
+
+    /// Opens a file with its `open_with` command if its extension has one,
+    /// otherwise as `open_on_enter` says. When that is `none`, files the
+    /// pager cannot show (binary, non-image) go to `open_fallback`.
+    fn open_file(self, path: PathBuf) -> Self {
+        if let Some(template) = self.open_with.template_for(&path) {
+            let template = template.to_string();
+            return self.launch_template(&template, &path);
+        }
+        match self.open_on_enter {
+            OpenOnEnter::None if !is_viewable(&path) => {
+                if self.open_fallback.trim().is_empty() {
+                    Self {
+                        pending_launch: Some(Launch::System(path)),
+                        ..self
+                    }
+                } else {
+                    let template = self.open_fallback.clone();
+                    self.launch_template(&template, &path)
+                }
+            }
+            OpenOnEnter::None => self,
+            OpenOnEnter::Pager => self.enter_pager(),
+            OpenOnEnter::Editor => Self {
+                pending_launch: Some(Launch::Editor(path)),
+                ..self
+            },
+            OpenOnEnter::System => Self {
+                pending_launch: Some(Launch::System(path)),
+                ..self
+            },
+        }
+    }
+
+    /// Queues `template` expanded for `path` as a detached launch.
+    fn launch_template(self, template: &str, path: &Path) -> Self {
+        match expand_template(template, path) {
+            Ok(argv) => Self {
+                pending_launch: Some(Launch::Detached(argv)),
+                ..self
+            },
+            Err(e) => self.with_status(format!("Open failed: {e}")),
         }
     }
 
+    fn handle_go_up(self) -> Self {
+        let dir = self.panel().current_dir();
+        let parent = dir.parent().unwrap_or(dir).to_path_buf();
+        self.enter_dir_checked(&parent, DirLanding::Parent)
+    }
+
     fn handle_go_back(self) -> Self {
         match self.panel().go_back() {
             Ok(Some(new_panel)) => self.with_panel(new_panel),
@@ -1459,6 +3665,22 @@ impl App {
         }
     }
 
+    /// Forget the cached repository roots that `changed` paths may have
+    /// made stale (a `.git` created or removed).
+    pub fn with_repo_roots_invalidated(self, changed: &[PathBuf]) -> Self {
+        let mut repo_roots = self.repo_roots;
+        repo_roots.invalidate(changed);
+        Self { repo_roots, ..self }
+    }
+
+    /// Forget every cached repository root.
+    fn with_repo_roots_cleared(self) -> Self {
+        Self {
+            repo_roots: RepoRootCache::default(),
+            ..self
+        }
+    }
+
     fn handle_refresh(self) -> Self {
         match self.panel().refresh() {
             Ok(new_panel) => self.with_panel(new_panel),
@@ -1466,18 +3688,187 @@ impl App {
         }
     }
 
-    fn handle_next_sort(self) -> Self {
-        match self.panel().with_next_sort() {
+    /// Steps the active panel's filter: all files, then each
+    /// [`FileCategory`] in turn, then all files again.
+    pub fn cycle_category_filter(self) -> Self {
+        if self.is_remote() {
+            return self.with_status("Category filter not supported in remote mode".to_string());
+        }
+        let panel = self.panel();
+        let next = match panel.category_filter() {
+            None => Some(FileCategory::ALL[0]),
+            Some(current) => FileCategory::ALL
+                .iter()
+                .skip_while(|c| **c != current)
+                .nth(1)
+                .copied(),
+        };
+        match panel.with_category_filter(next) {
             Ok(new_panel) => {
-                let msg = format!("Sort: {:?}", new_panel.sort_field());
+                let msg = match next {
+                    Some(category) => format!("Showing {} only", category.label()),
+                    None => "Showing all files".to_string(),
+                };
                 self.with_panel(new_panel).with_status(msg)
             }
             Err(e) => self.with_status(format!("Error: {e}")),
         }
     }
-}
 
-#[cfg(test)]
+    /// Toggles listing only directories in the active panel.
+    pub fn toggle_dirs_only(self) -> Self {
+        if self.is_remote() {
+            return self
+                .with_status("Directories-only filter not supported in remote mode".to_string());
+        }
+        let dirs_only = !self.panel().dirs_only();
+        match self.panel().with_dirs_only(dirs_only) {
+            Ok(new_panel) => {
+                let msg = if dirs_only {
+                    "Showing directories only"
+                } else {
+                    "Showing all entries"
+                };
+                self.with_panel(new_panel).with_status(msg.to_string())
+            }
+            Err(e) => self.with_status(format!("Error: {e}")),
+        }
+    }
+
+    /// Starts typing a name filter for the active panel, picking up the one
+    /// already set.
+    pub fn start_filter(self) -> Self {
+        if self.is_remote() {
+            return self.with_status("Filter not supported in remote mode".to_string());
+        }
+        let query = self.panel().name_filter().to_string();
+        self.with_mode(AppMode::Filter(query))
+    }
+
+    /// Append a character to the name filter and narrow the panel.
+    pub fn filter_push_char(self, c: char) -> Self {
+        let query = match &self.mode {
+            AppMode::Filter(q) => format!("{q}{c}"),
+            _ => return self,
+        };
+        self.apply_name_filter(&query)
+            .with_mode(AppMode::Filter(query))
+    }
+
+    /// Remove the last character from the name filter and widen the panel.
+    pub fn filter_pop_char(self) -> Self {
+        let query = match &self.mode {
+            AppMode::Filter(q) => {
+                let mut q = q.clone();
+                q.pop();
+                q
+            }
+            _ => return self,
+        };
+        self.apply_name_filter(&query)
+            .with_mode(AppMode::Filter(query))
+    }
+
+    /// Stops typing and keeps the panel filtered.
+    pub fn accept_filter(self) -> Self {
+        self.with_mode(AppMode::Normal)
+    }
+
+    /// Drops the name filter, listing everything again.
+    pub fn clear_filter(self) -> Self {
+        let app = self.with_mode(AppMode::Normal);
+        if app.panel().name_filter().is_empty() {
+            return app;
+        }
+        app.apply_name_filter("")
+    }
+
+    /// Relists the active panel through `query`.
+    fn apply_name_filter(self, query: &str) -> Self {
+        match self.panel().with_name_filter(query) {
+            Ok(new_panel) => self.with_panel(new_panel),
+            Err(e) => self.with_status(format!("Error: {e}")),
+        }
+    }
+
+    /// Goes to the real location of the selected symlink: into it when it
+    /// resolves to a directory, otherwise to the target's directory with the
+    /// cursor on the target.
+    pub fn follow_symlink(self) -> Self {
+        if self.is_remote() {
+            return self.with_status("Following symlinks not supported in remote mode".to_string());
+        }
+        let Some(entry) = self.panel().selected_entry() else {
+            return self;
+        };
+        let name = entry.name().to_string();
+        let Some(target) = entry.symlink_target() else {
+            return self.with_status(format!("{name} is not a symlink"));
+        };
+        match std::fs::canonicalize(entry.path()) {
+            Ok(real) if real.is_dir() => self.enter_dir_checked(&real, DirLanding::Here),
+            Ok(real) => self.reveal(&real),
+            Err(_) => self.with_status(format!("Broken symlink: {name} -> {}", target.display())),
+        }
+    }
+
+    /// Pins the selected entry to the top of its directory, or unpins it,
+    /// and saves the pins.
+    pub fn toggle_pin(self) -> Self {
+        if self.is_remote() {
+            return self.with_status("Pinning not supported in remote mode".to_string());
+        }
+        let Some(entry) = self.panel().selected_entry() else {
+            return self.with_status("Nothing to pin".to_string());
+        };
+        let path = entry.path().to_path_buf();
+        let pins = self.pins.clone().toggled(&path);
+        if let Err(e) = pins.save_to_file(&self.pins_path) {
+            tracing::warn!("Failed to save {}: {e}", self.pins_path.display());
+        }
+        let msg = if pins.is_pinned(&path) {
+            format!("Pinned {}", entry.name())
+        } else {
+            format!("Unpinned {}", entry.name())
+        };
+        let tab_groups = self
+            .tab_groups
+            .clone()
+            .map(|group| group.with_each_panel(|panel| panel.with_pins(pins.clone())));
+        Self {
+            pins,
+            tab_groups,
+            ..self
+        }
+        .with_status(msg)
+    }
+
+    /// Show the active panel's entries in the opposite order they are listed in.
+    pub fn reverse_listing(self) -> Self {
+        let panel = self.panel().with_reversed_entries();
+        self.with_panel(panel)
+            .with_status("Listing reversed".to_string())
+    }
+
+    fn handle_next_sort(self) -> Self {
+        let new_panel = self.panel().with_next_sort();
+        let msg = format!("Sort: {:?}", new_panel.sort_field());
+        self.with_sorted_panel(new_panel).with_status(msg)
+    }
+
+    /// Reverse the active panel's sort order without changing its field.
+    pub fn toggle_sort_direction(self) -> Self {
+        let new_panel = self.panel().with_toggled_direction();
+        let msg = format!(
+            "Sort: {:?} {:?}",
+            new_panel.sort_field(),
+            new_panel.sort_direction()
+        );
+        self.with_sorted_panel(new_panel).with_status(msg)
+    }
+}
+
+#[cfg(test)]
 mod tests {
     use super::*;
     use std::fs;
@@ -1550,6 +3941,132 @@ mod tests {
         assert!(app.status_message().is_none());
     }
 
+    #[test]
+    fn with_status_records_a_notification() {
+        let (_tmp, app) = setup_app();
+        let app = app
+            .with_status("Copied".to_string())
+            .with_status("Error: permission denied".to_string());
+
+        let entries = app.notifications().entries();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].message, "Copied");
+        assert_eq!(entries[0].level, NotificationLevel::Info);
+        assert_eq!(entries[1].level, NotificationLevel::Error);
+        assert_eq!(app.status_message(), Some("Error: permission denied"));
+    }
+
+    #[test]
+    fn status_level_follows_the_latest_message() {
+        let (_tmp, app) = setup_app();
+        assert_eq!(app.status_level(), NotificationLevel::Info);
+
+        let app = app.with_success("Copied".to_string());
+        assert_eq!(app.status_level(), NotificationLevel::Success);
+        assert_eq!(
+            app.notifications().entries()[0].level,
+            NotificationLevel::Success
+        );
+
+        let app = app.with_status("Copy failed: no clipboard".to_string());
+        assert_eq!(app.status_level(), NotificationLevel::Error);
+        let app = app.with_status("Paste not supported in remote mode".to_string());
+        assert_eq!(app.status_level(), NotificationLevel::Warning);
+    }
+
+    #[test]
+    fn failed_paste_sets_error_level() {
+        let (tmp, app) = setup_app();
+        let app = App {
+            clipboard: vec![tmp.path().join("missing.txt")],
+            ..app
+        }
+        .paste();
+
+        assert!(app.status_message().unwrap().starts_with("Paste failed"));
+        assert_eq!(app.status_level(), NotificationLevel::Error);
+    }
+
+    #[test]
+    fn push_notification_shows_the_latest_and_keeps_the_cap() {
+        let (_tmp, app) = setup_app();
+        let app = App {
+            notifications: Notifications::with_capacity(2),
+            ..app
+        };
+
+        let app = ["one", "two", "three"].iter().fold(app, |app, msg| {
+            app.push_notification(NotificationLevel::Warning, msg.to_string())
+        });
+
+        let messages: Vec<&str> = app
+            .notifications()
+            .entries()
+            .iter()
+            .map(|n| n.message.as_str())
+            .collect();
+        assert_eq!(messages, ["two", "three"]);
+        assert_eq!(app.status_message(), Some("three"));
+    }
+
+    #[test]
+    fn notifications_scroll_is_clamped() {
+        let (_tmp, app) = setup_app();
+        let app = app
+            .with_status("a".to_string())
+            .with_status("b".to_string())
+            .with_mode(AppMode::Notifications { scroll: 0 });
+
+        let app = app.notifications_scroll_down().notifications_scroll_down();
+        assert!(matches!(app.mode(), AppMode::Notifications { scroll: 1 }));
+        let app = app.notifications_scroll_up().notifications_scroll_up();
+        assert!(matches!(app.mode(), AppMode::Notifications { scroll: 0 }));
+    }
+
+    #[test]
+    fn status_expires_once_the_timeout_has_passed() {
+        let set_at = Instant::now();
+        let timeout = Some(Duration::from_secs(3));
+
+        assert!(!status_expired(Some(set_at), timeout, set_at));
+        assert!(!status_expired(
+            Some(set_at),
+            timeout,
+            set_at + Duration::from_millis(2999)
+        ));
+        assert!(status_expired(
+            Some(set_at),
+            timeout,
+            set_at + Duration::from_secs(3)
+        ));
+    }
+
+    #[test]
+    fn status_without_timeout_or_message_never_expires() {
+        let now = Instant::now();
+        let later = now + Duration::from_secs(3600);
+
+        assert!(!status_expired(Some(now), None, later));
+        assert!(!status_expired(None, Some(Duration::from_secs(1)), later));
+    }
+
+    #[test]
+    fn expired_status_is_cleared() {
+        let (_tmp, app) = setup_app();
+        let app = App {
+            status_timeout: Some(Duration::from_secs(2)),
+            ..app
+        }
+        .with_status("hello".to_string());
+        let now = Instant::now();
+
+        let app = app.with_expired_status_cleared(now);
+        assert_eq!(app.status_message(), Some("hello"));
+
+        let app = app.with_expired_status_cleared(now + Duration::from_secs(5));
+        assert!(app.status_message().is_none());
+    }
+
     // --- Command handling: cursor ---
 
     #[test]
@@ -1592,1011 +4109,3551 @@ mod tests {
     }
 
     #[test]
-    fn handle_go_up() {
+    fn entering_large_dir_asks_first() {
         let (tmp, app) = setup_app();
-        let original_dir = app.panel().current_dir().to_path_buf();
-        let app = app.handle_command(Command::GoUp);
-        // Parent should be different from original (unless at root)
-        let parent = tmp.path().parent();
-        if parent.is_some() {
-            assert_ne!(app.panel().current_dir(), original_dir.as_path());
-        }
+        fs::write(tmp.path().join("gamma").join("more.txt"), "y").unwrap();
+        let app = App {
+            large_dir_threshold: 1,
+            ..app
+        };
+
+        let app = app.handle_command(Command::Enter);
+
+        let gamma = tmp.path().join("gamma");
+        assert!(matches!(
+            app.mode(),
+            AppMode::Confirm(ConfirmAction::EnterLargeDir { dir, landing: DirLanding::Here })
+                if *dir == gamma
+        ));
+        assert_eq!(app.panel().current_dir(), tmp.path());
+
+        let app = app.enter_dir(&gamma, DirLanding::Here);
+        assert!(matches!(app.mode(), AppMode::Normal));
+        assert_eq!(app.panel().current_dir(), gamma);
     }
 
     #[test]
-    fn handle_toggle_hidden() {
+    fn small_dir_enters_without_asking() {
         let (tmp, app) = setup_app();
-        // Create a hidden file
-        fs::write(tmp.path().join(".hidden"), "secret").unwrap();
+        let app = App {
+            large_dir_threshold: 1,
+            ..app
+        };
 
-        let count_before = app.panel().entries().len();
-        let app = app.handle_command(Command::ToggleHidden);
-        let count_after = app.panel().entries().len();
-        // After toggling hidden, should see more entries (the hidden file)
-        assert!(count_after > count_before);
+        let app = app.handle_command(Command::Enter);
+
+        assert!(matches!(app.mode(), AppMode::Normal));
+        assert_eq!(app.panel().current_dir(), tmp.path().join("gamma"));
     }
 
     #[test]
-    fn handle_refresh_preserves_dir() {
-        let (_tmp, app) = setup_app();
-        let dir = app.panel().current_dir().to_path_buf();
-        let app = app.handle_command(Command::Refresh);
-        assert_eq!(app.panel().current_dir(), dir.as_path());
+    fn zero_large_dir_threshold_never_asks() {
+        let (tmp, app) = setup_app();
+        fs::write(tmp.path().join("gamma").join("more.txt"), "y").unwrap();
+        let app = App {
+            large_dir_threshold: 0,
+            ..app
+        };
+
+        let app = app.handle_command(Command::Enter);
+
+        assert!(matches!(app.mode(), AppMode::Normal));
+        assert_eq!(app.panel().current_dir(), tmp.path().join("gamma"));
     }
 
     #[test]
-    fn handle_set_sort_changes_sort() {
-        let (_tmp, app) = setup_app();
-        let app = app.handle_command(Command::SetSort(SortField::Size, SortDirection::Ascending));
-        // sort_field should change
-        assert_ne!(app.panel().sort_field(), SortField::Name);
-        // status message should mention sort
-        assert!(app.status_message().is_some());
+    fn new_tab_into_large_dir_asks_first() {
+        let (tmp, app) = setup_app();
+        fs::write(tmp.path().join("gamma").join("more.txt"), "y").unwrap();
+        let app = App {
+            large_dir_threshold: 1,
+            ..app
+        };
+
+        let app = app.open_in_new_tab();
+
+        let gamma = tmp.path().join("gamma");
+        let AppMode::Confirm(ConfirmAction::EnterLargeDir { dir, landing }) = app.mode().clone()
+        else {
+            panic!("expected a confirmation, got {:?}", app.mode());
+        };
+        assert_eq!(dir, gamma);
+        assert_eq!(landing, DirLanding::NewTab);
+        assert_eq!(app.active_tab_group().tab_count(), 1);
+
+        let app = app.enter_dir(&dir, landing);
+        let group = app.active_tab_group();
+        assert_eq!(group.tab_count(), 2);
+        assert_eq!(app.panel().current_dir(), gamma);
+        assert_eq!(group.tabs()[0].panel.current_dir(), tmp.path());
     }
 
     #[test]
-    fn handle_go_back_with_no_history() {
-        let (_tmp, app) = setup_app();
-        let dir = app.panel().current_dir().to_path_buf();
-        let app = app.handle_command(Command::GoBack);
-        // Should stay in same directory
-        assert_eq!(app.panel().current_dir(), dir.as_path());
+    fn going_up_into_large_dir_asks_and_keeps_the_cursor() {
+        let (tmp, app) = setup_app();
+        let app = app.handle_command(Command::Enter);
+        let app = App {
+            large_dir_threshold: 2,
+            ..app
+        };
+
+        let app = app.handle_command(Command::GoUp);
+
+        let AppMode::Confirm(ConfirmAction::EnterLargeDir { dir, landing }) = app.mode().clone()
+        else {
+            panic!("expected a confirmation, got {:?}", app.mode());
+        };
+        assert_eq!(dir, tmp.path());
+        assert_eq!(landing, DirLanding::Parent);
+
+        let app = app.enter_dir(&dir, landing);
+        assert_eq!(app.panel().current_dir(), tmp.path());
+        assert_eq!(app.panel().selected_entry().unwrap().name(), "gamma");
     }
 
     #[test]
-    fn handle_go_forward_with_no_history() {
-        let (_tmp, app) = setup_app();
-        let dir = app.panel().current_dir().to_path_buf();
-        let app = app.handle_command(Command::GoForward);
-        assert_eq!(app.panel().current_dir(), dir.as_path());
-    }
+    fn bookmark_jump_into_large_dir_asks_first() {
+        let (tmp, app) = setup_app();
+        let gamma = tmp.path().join("gamma");
+        fs::write(gamma.join("more.txt"), "y").unwrap();
+        let app = App {
+            bookmarks: app.bookmarks().clone().with_bookmark("big", &gamma),
+            large_dir_threshold: 1,
+            ..app
+        };
+        let idx = app
+            .bookmarks()
+            .iter()
+            .position(|(k, _)| k == "big")
+            .unwrap();
 
-    // --- PanelState tests ---
+        let app = app.bookmark_jump(idx);
 
-    #[test]
-    fn panel_state_from_dir() {
-        let tmp = TempDir::new().unwrap();
-        fs::write(tmp.path().join("file.txt"), "").unwrap();
-        let panel = PanelState::from_dir(tmp.path()).unwrap();
-        assert_eq!(panel.selected_index(), 0);
-        assert!(!panel.entries().is_empty());
+        assert!(matches!(
+            app.mode(),
+            AppMode::Confirm(ConfirmAction::EnterLargeDir { dir, landing: DirLanding::Here })
+                if *dir == gamma
+        ));
+        assert_eq!(app.panel().current_dir(), tmp.path());
     }
 
     #[test]
-    fn panel_state_cursor_movements() {
-        let tmp = TempDir::new().unwrap();
-        fs::write(tmp.path().join("a.txt"), "").unwrap();
-        fs::write(tmp.path().join("b.txt"), "").unwrap();
-        fs::write(tmp.path().join("c.txt"), "").unwrap();
-        let panel = PanelState::from_dir(tmp.path()).unwrap();
+    fn relisting_the_shown_dir_never_asks() {
+        let (tmp, app) = setup_app();
+        let app = App {
+            large_dir_threshold: 1,
+            ..app
+        };
 
-        let panel = panel.with_cursor_down();
-        assert_eq!(panel.selected_index(), 1);
+        let app =
+            app.enter_dir_checked(tmp.path(), DirLanding::Select(tmp.path().join("beta.txt")));
 
-        let panel = panel.with_cursor_down();
-        assert_eq!(panel.selected_index(), 2);
+        assert!(matches!(app.mode(), AppMode::Normal));
+        assert_eq!(app.panel().selected_entry().unwrap().name(), "beta.txt");
+    }
 
-        let panel = panel.with_cursor_up();
-        assert_eq!(panel.selected_index(), 1);
+    /// App with Enter set to `mode` and the cursor on `alpha.txt`.
+    fn setup_open_on_enter(mode: OpenOnEnter) -> (TempDir, App, PathBuf) {
+        let (tmp, app) = setup_app();
+        let app = App {
+            open_on_enter: mode,
+            ..app
+        }
+        .handle_command(Command::CursorDown);
+        let file = app.panel().selected_entry().unwrap().path().to_path_buf();
+        assert!(file.ends_with("alpha.txt"));
+        (tmp, app, file)
+    }
 
-        let panel = panel.with_cursor_top();
-        assert_eq!(panel.selected_index(), 0);
+    #[test]
+    fn enter_on_file_does_nothing_by_default() {
+        let (_tmp, app, _file) = setup_open_on_enter(OpenOnEnter::None);
+        let dir = app.panel().current_dir().to_path_buf();
 
-        let panel = panel.with_cursor_bottom();
-        assert_eq!(panel.selected_index(), 2);
+        let (app, launch) = app.handle_command(Command::Enter).take_launch();
+
+        assert!(matches!(app.mode(), AppMode::Normal));
+        assert_eq!(launch, None);
+        assert_eq!(app.panel().current_dir(), dir);
     }
 
     #[test]
-    fn panel_state_show_hidden_default_false() {
-        let tmp = TempDir::new().unwrap();
-        fs::write(tmp.path().join("visible.txt"), "").unwrap();
-        let panel = PanelState::from_dir(tmp.path()).unwrap();
-        assert!(!panel.show_hidden());
+    fn enter_on_file_opens_pager() {
+        let (_tmp, app, file) = setup_open_on_enter(OpenOnEnter::Pager);
+
+        let (app, launch) = app.handle_command(Command::Enter).take_launch();
+
+        assert!(matches!(app.mode(), AppMode::Pager { .. }));
+        assert_eq!(app.pager_file(), Some(file.as_path()));
+        assert_eq!(launch, None);
     }
 
     #[test]
-    fn panel_state_toggle_hidden_shows_hidden_files() {
-        let tmp = TempDir::new().unwrap();
-        fs::write(tmp.path().join(".hidden"), "").unwrap();
-        fs::write(tmp.path().join("visible.txt"), "").unwrap();
-        let panel = PanelState::from_dir(tmp.path()).unwrap();
+    fn enter_on_file_requests_editor() {
+        let (_tmp, app, file) = setup_open_on_enter(OpenOnEnter::Editor);
 
-        assert_eq!(panel.entries().len(), 1); // only visible.txt
-        let panel = panel.with_toggle_hidden().unwrap();
-        assert_eq!(panel.entries().len(), 2); // both files
-        assert!(panel.show_hidden());
+        let (app, launch) = app.handle_command(Command::Enter).take_launch();
+
+        assert_eq!(launch, Some(Launch::Editor(file)));
+        assert!(matches!(app.mode(), AppMode::Normal));
+        assert_eq!(app.take_launch().1, None);
     }
 
     #[test]
-    fn panel_state_sort_field_default_name() {
-        let tmp = TempDir::new().unwrap();
-        fs::write(tmp.path().join("a.txt"), "").unwrap();
-        let panel = PanelState::from_dir(tmp.path()).unwrap();
-        assert_eq!(panel.sort_field(), SortField::Name);
+    fn enter_on_file_requests_system_opener() {
+        let (_tmp, app, file) = setup_open_on_enter(OpenOnEnter::System);
+
+        let (_app, launch) = app.handle_command(Command::Enter).take_launch();
+
+        assert_eq!(launch, Some(Launch::System(file)));
+    }
+
+    /// App with `open_with` and `open_fallback` set and a binary
+    /// `archive.bin` next to the usual files.
+    fn setup_open_with(open_with: &str, open_fallback: &str) -> (TempDir, App) {
+        let (tmp, app) = setup_app();
+        fs::write(tmp.path().join("archive.bin"), b"PK\x03\x04\x00\x00").unwrap();
+        let app = App {
+            open_with: toml::from_str(open_with).unwrap(),
+            open_fallback: open_fallback.to_string(),
+            ..app.handle_refresh()
+        };
+        (tmp, app)
     }
 
     #[test]
-    fn panel_state_next_sort_cycles() {
-        let tmp = TempDir::new().unwrap();
-        fs::write(tmp.path().join("a.txt"), "").unwrap();
-        let panel = PanelState::from_dir(tmp.path()).unwrap();
+    fn unmapped_binary_file_goes_to_system_opener() {
+        let (tmp, app) = setup_open_with("", "");
 
-        let panel = panel.with_next_sort().unwrap();
-        assert_eq!(panel.sort_field(), SortField::Size);
+        let app = select(app, "archive.bin").handle_command(Command::Enter);
 
-        let panel = panel.with_next_sort().unwrap();
-        assert_eq!(panel.sort_field(), SortField::Date);
+        let path = tmp.path().canonicalize().unwrap().join("archive.bin");
+        assert_eq!(app.take_launch().1, Some(Launch::System(path)));
+    }
 
-        let panel = panel.with_next_sort().unwrap();
-        assert_eq!(panel.sort_field(), SortField::Type);
+    #[test]
+    fn unmapped_binary_file_uses_configured_fallback() {
+        let (tmp, app) = setup_open_with("", "handler --open {}");
 
-        let panel = panel.with_next_sort().unwrap();
-        assert_eq!(panel.sort_field(), SortField::Name);
+        let app = select(app, "archive.bin").handle_command(Command::Enter);
+
+        let path = tmp.path().canonicalize().unwrap().join("archive.bin");
+        let argv = vec![
+            "handler".to_string(),
+            "--open".to_string(),
+            path.to_string_lossy().into_owned(),
+        ];
+        assert_eq!(app.take_launch().1, Some(Launch::Detached(argv)));
     }
 
     #[test]
-    fn panel_state_navigate_to_subdir() {
-        let tmp = TempDir::new().unwrap();
-        fs::create_dir(tmp.path().join("subdir")).unwrap();
-        fs::write(tmp.path().join("subdir").join("file.txt"), "").unwrap();
-        let panel = PanelState::from_dir(tmp.path()).unwrap();
+    fn mapped_extension_bypasses_fallback_and_open_on_enter() {
+        let (tmp, app) = setup_open_with("BIN = \"unzip -l\"\ntxt = \"view\"", "handler {}");
+        let app = App {
+            open_on_enter: OpenOnEnter::Pager,
+            ..app
+        };
+        let dir = tmp.path().canonicalize().unwrap();
+
+        let app = select(app, "archive.bin").handle_command(Command::Enter);
+        let (app, launch) = app.take_launch();
+        let argv = vec![
+            "unzip".to_string(),
+            "-l".to_string(),
+            dir.join("archive.bin").to_string_lossy().into_owned(),
+        ];
+        assert_eq!(launch, Some(Launch::Detached(argv)));
+
+        let app = select(app, "alpha.txt").handle_command(Command::Enter);
+        assert!(matches!(app.mode(), AppMode::Normal));
+        let argv = vec![
+            "view".to_string(),
+            dir.join("alpha.txt").to_string_lossy().into_owned(),
+        ];
+        assert_eq!(app.take_launch().1, Some(Launch::Detached(argv)));
+    }
 
-        let subdir = tmp.path().join("subdir");
-        let panel = panel.navigate_to(&subdir).unwrap();
-        assert_eq!(panel.entries().len(), 1);
+    #[test]
+    fn text_file_ignores_fallback() {
+        let (_tmp, app) = setup_open_with("", "handler {}");
+
+        let app = select(app, "alpha.txt").handle_command(Command::Enter);
+
+        assert_eq!(app.take_launch().1, None);
     }
 
     #[test]
-    fn panel_state_refresh() {
+    fn selected_tree_text_lists_the_directory() {
+        let (_tmp, app) = setup_app();
+
+        let text = select(app, "gamma").selected_tree_text().unwrap();
+
+        assert_eq!(text, "gamma/\n  inside.txt\n");
+    }
+
+    #[test]
+    fn selected_tree_text_notes_when_bounded() {
+        let (tmp, app) = setup_app();
+        for i in 0..TREE_COPY_MAX_ENTRIES + 5 {
+            fs::write(tmp.path().join("gamma").join(format!("{i:04}.txt")), "").unwrap();
+        }
+
+        let text = select(app, "gamma").selected_tree_text().unwrap();
+
+        assert_eq!(text.lines().count(), TREE_COPY_MAX_ENTRIES + 2);
+        assert!(text.ends_with("… (stopped at 500 entries)\n"));
+    }
+
+    #[test]
+    fn selected_tree_text_needs_a_directory() {
+        let (_tmp, app) = setup_app();
+        let err = select(app, "alpha.txt").selected_tree_text().unwrap_err();
+        assert_eq!(err, "No directory selected");
+    }
+
+    #[test]
+    fn selected_git_path_is_relative_to_the_repo_root() {
+        let (tmp, app) = setup_app();
+        git2::Repository::init(tmp.path()).unwrap();
+
+        let app = select(app, "gamma").handle_command(Command::Enter);
+
+        assert_eq!(app.selected_git_path().unwrap(), "gamma/inside.txt");
+    }
+
+    #[test]
+    fn selected_git_path_outside_a_repo_is_an_error() {
+        let (_tmp, app) = setup_app();
+        let err = select(app, "alpha.txt").selected_git_path().unwrap_err();
+        assert_eq!(err, "Not in a git repository");
+    }
+
+    #[test]
+    fn enter_on_directory_ignores_open_on_enter() {
+        let (_tmp, app, _file) = setup_open_on_enter(OpenOnEnter::Editor);
+        let app = app.handle_command(Command::CursorUp);
+
+        let (app, launch) = app.handle_command(Command::Enter).take_launch();
+
+        assert!(app.panel().current_dir().ends_with("gamma"));
+        assert_eq!(launch, None);
+    }
+
+    #[test]
+    fn parent_entry_listed_only_when_enabled() {
         let tmp = TempDir::new().unwrap();
         fs::write(tmp.path().join("a.txt"), "").unwrap();
         let panel = PanelState::from_dir(tmp.path()).unwrap();
-        assert_eq!(panel.entries().len(), 1);
+        assert!(panel.entries().iter().all(|e| !e.is_parent_link()));
 
-        // Add a file then refresh
-        fs::write(tmp.path().join("b.txt"), "").unwrap();
-        let panel = panel.refresh().unwrap();
+        let panel = panel.with_parent_entry(true);
         assert_eq!(panel.entries().len(), 2);
+        assert_eq!(panel.entries()[0].name(), "..");
+        assert_eq!(panel.selected_entry().unwrap().name(), "a.txt");
+
+        let panel = panel.with_cursor_top();
+        assert!(panel.on_parent_entry());
+        assert!(panel.selected_entry().is_none());
+
+        let panel = panel.with_next_sort();
+        assert!(panel.entries()[0].is_parent_link());
+
+        let panel = panel.with_parent_entry(false);
+        assert_eq!(panel.entries().len(), 1);
+        assert!(!panel.on_parent_entry());
+    }
+
+    #[test]
+    fn root_has_no_parent_entry() {
+        let panel = PanelState::from_dir(Path::new("/"))
+            .unwrap()
+            .with_parent_entry(true);
+        assert!(panel.entries().iter().all(|e| !e.is_parent_link()));
+    }
+
+    #[test]
+    fn enter_on_parent_entry_goes_up() {
+        let (tmp, app) = setup_app();
+        let panel = app.panel().with_parent_entry(true);
+        let app = app.with_panel(panel).handle_command(Command::Enter);
+        assert!(app.panel().current_dir().ends_with("gamma"));
+        assert!(app.panel().on_parent_entry());
+
+        let app = app.handle_command(Command::Enter);
+        assert_eq!(
+            app.panel().current_dir(),
+            tmp.path().canonicalize().unwrap()
+        );
+    }
+
+    #[test]
+    fn handle_go_up() {
+        let (tmp, app) = setup_app();
+        let original_dir = app.panel().current_dir().to_path_buf();
+        let app = app.handle_command(Command::GoUp);
+        // Parent should be different from original (unless at root)
+        let parent = tmp.path().parent();
+        if parent.is_some() {
+            assert_ne!(app.panel().current_dir(), original_dir.as_path());
+        }
+    }
+
+    #[test]
+    fn category_filter_cycles_and_keeps_directories() {
+        let (tmp, app) = setup_app();
+        fs::write(tmp.path().join("main.rs"), "fn main() {}").unwrap();
+        let app = app.handle_refresh();
+        let names = |app: &App| -> Vec<String> {
+            app.panel()
+                .entries()
+                .iter()
+                .map(|e| e.name().to_string())
+                .collect()
+        };
+
+        let app = app.cycle_category_filter();
+        assert_eq!(app.panel().category_filter(), Some(FileCategory::Documents));
+        assert_eq!(names(&app), ["gamma", "alpha.txt", "beta.txt"]);
+
+        let app = app.cycle_category_filter().cycle_category_filter();
+        assert_eq!(app.panel().category_filter(), Some(FileCategory::Code));
+        assert_eq!(names(&app), ["gamma", "main.rs"]);
+        assert_eq!(app.status_message(), Some("Showing Code only"));
+
+        let app = (0..4).fold(app, |app, _| app.cycle_category_filter());
+        assert_eq!(app.panel().category_filter(), None);
+        assert_eq!(names(&app).len(), 4);
+        assert_eq!(app.status_message(), Some("Showing all files"));
+    }
+
+    #[test]
+    fn category_filter_survives_navigation() {
+        let (_tmp, app) = setup_app();
+        let app = app.cycle_category_filter().cycle_category_filter();
+        let app = app.handle_command(Command::Enter);
+        assert_eq!(app.panel().category_filter(), Some(FileCategory::Images));
+        assert!(app.panel().entries().is_empty());
+    }
+
+    #[test]
+    fn dirs_only_hides_files_and_composes_with_hidden() {
+        let (tmp, app) = setup_app();
+        fs::create_dir(tmp.path().join(".cache")).unwrap();
+        fs::write(tmp.path().join(".env"), "x").unwrap();
+        let app = app.handle_refresh();
+        let names = |app: &App| -> Vec<String> {
+            app.panel()
+                .entries()
+                .iter()
+                .map(|e| e.name().to_string())
+                .collect()
+        };
+
+        let app = app.toggle_dirs_only();
+        assert!(app.panel().dirs_only());
+        assert_eq!(names(&app), ["gamma"]);
+        assert_eq!(app.status_message(), Some("Showing directories only"));
+
+        let app = app.handle_command(Command::ToggleHidden);
+        assert_eq!(names(&app), [".cache", "gamma"]);
+
+        let app = app.toggle_dirs_only();
+        assert!(!app.panel().dirs_only());
+        assert_eq!(names(&app).len(), 5);
+        assert_eq!(app.status_message(), Some("Showing all entries"));
+    }
+
+    #[test]
+    fn dirs_only_keeps_sort_and_survives_navigation() {
+        let (tmp, app) = setup_app();
+        fs::create_dir(tmp.path().join("delta")).unwrap();
+        fs::create_dir(tmp.path().join("gamma/sub")).unwrap();
+        let app = app
+            .handle_refresh()
+            .toggle_sort_direction()
+            .toggle_dirs_only();
+        let names: Vec<&str> = app.panel().entries().iter().map(|e| e.name()).collect();
+        assert_eq!(names, ["gamma", "delta"]);
+
+        let app = select(app, "gamma").handle_command(Command::Enter);
+        assert!(app.panel().dirs_only());
+        let names: Vec<&str> = app.panel().entries().iter().map(|e| e.name()).collect();
+        assert_eq!(names, ["sub"]);
+    }
+
+    /// App whose pins are persisted into a separate temp dir.
+    fn with_pins_file(app: App, state: &TempDir) -> App {
+        App {
+            pins_path: state.path().join("pins.toml"),
+            ..app
+        }
+    }
+
+    fn type_filter(app: App, query: &str) -> App {
+        query
+            .chars()
+            .fold(app.start_filter(), |app, c| app.filter_push_char(c))
+    }
+
+    #[test]
+    fn typing_a_filter_narrows_the_panel() {
+        let (_tmp, app) = setup_app();
+
+        let app = type_filter(app, "t");
+        assert!(matches!(app.mode(), AppMode::Filter(q) if q == "t"));
+        assert_eq!(entry_names(&app), ["alpha.txt", "beta.txt"]);
+
+        let app = app.filter_push_char('x');
+        assert!(matches!(app.mode(), AppMode::Filter(q) if q == "tx"));
+        assert_eq!(entry_names(&app), ["alpha.txt", "beta.txt"]);
+
+        let app = app
+            .filter_pop_char()
+            .filter_pop_char()
+            .filter_push_char('l');
+        assert_eq!(entry_names(&app), ["alpha.txt"]);
+    }
+
+    #[test]
+    fn clearing_the_filter_restores_the_listing_and_cursor() {
+        let (_tmp, app) = setup_app();
+        let app = select(type_filter(app, "t"), "beta.txt");
+
+        let app = app.clear_filter();
+
+        assert!(matches!(app.mode(), AppMode::Normal));
+        assert_eq!(entry_names(&app), ["gamma", "alpha.txt", "beta.txt"]);
+        assert_eq!(app.panel().selected_entry().unwrap().name(), "beta.txt");
+    }
+
+    #[test]
+    fn accepted_filter_stays_until_the_panel_changes_directory() {
+        let (_tmp, app) = setup_app();
+        let app = type_filter(app, "gam").accept_filter();
+
+        assert!(matches!(app.mode(), AppMode::Normal));
+        assert_eq!(entry_names(&app), ["gamma"]);
+
+        let app = app.handle_command(Command::Enter);
+        assert_eq!(app.panel().name_filter(), "");
+        assert_eq!(entry_names(&app), ["inside.txt"]);
+    }
+
+    fn entry_names(app: &App) -> Vec<String> {
+        app.panel()
+            .entries()
+            .iter()
+            .map(|e| e.name().to_string())
+            .collect()
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn follow_symlink_enters_the_real_directory() {
+        let (tmp, app) = setup_app();
+        let elsewhere = TempDir::new().unwrap();
+        fs::create_dir(elsewhere.path().join("real")).unwrap();
+        std::os::unix::fs::symlink(elsewhere.path().join("real"), tmp.path().join("link")).unwrap();
+
+        let app = select(app.handle_refresh(), "link").follow_symlink();
+
+        assert_eq!(
+            app.panel().current_dir(),
+            elsewhere.path().join("real").canonicalize().unwrap()
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn follow_symlink_to_file_selects_the_target() {
+        let (tmp, app) = setup_app();
+        std::os::unix::fs::symlink(tmp.path().join("gamma/inside.txt"), tmp.path().join("link"))
+            .unwrap();
+
+        let app = select(app.handle_refresh(), "link").follow_symlink();
+
+        assert_eq!(
+            app.panel().current_dir(),
+            tmp.path().join("gamma").canonicalize().unwrap()
+        );
+        assert_eq!(app.panel().selected_entry().unwrap().name(), "inside.txt");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn follow_dangling_symlink_reports_the_missing_target() {
+        let (tmp, app) = setup_app();
+        std::os::unix::fs::symlink("missing", tmp.path().join("link")).unwrap();
+
+        let app = app.handle_refresh();
+        let dir = app.panel().current_dir().to_path_buf();
+
+        let app = select(app, "link").follow_symlink();
+
+        assert_eq!(app.panel().current_dir(), dir);
+        assert_eq!(
+            app.status_message(),
+            Some("Broken symlink: link -> missing")
+        );
+    }
+
+    #[test]
+    fn follow_symlink_on_plain_entry_says_so() {
+        let (_tmp, app) = setup_app();
+        let app = select(app, "gamma").follow_symlink();
+
+        assert_eq!(app.status_message(), Some("gamma is not a symlink"));
+    }
+
+    #[test]
+    fn pinned_entry_is_listed_first_and_saved() {
+        let (_tmp, app) = setup_app();
+        let state = TempDir::new().unwrap();
+        let app = with_pins_file(app, &state);
+
+        let app = select(app, "beta.txt").toggle_pin();
+        assert_eq!(entry_names(&app), ["beta.txt", "gamma", "alpha.txt"]);
+        assert_eq!(app.panel().selected_entry().unwrap().name(), "beta.txt");
+        assert_eq!(app.status_message(), Some("Pinned beta.txt"));
+        let saved = Pins::load_from_file(&state.path().join("pins.toml")).unwrap();
+        assert!(saved.is_pinned(&app.panel().current_dir().join("beta.txt")));
+
+        let app = app.toggle_pin();
+        assert_eq!(entry_names(&app), ["gamma", "alpha.txt", "beta.txt"]);
+        assert_eq!(app.status_message(), Some("Unpinned beta.txt"));
+    }
+
+    #[test]
+    fn sorting_applies_only_to_unpinned_entries() {
+        let (tmp, app) = setup_app();
+        let state = TempDir::new().unwrap();
+        fs::write(tmp.path().join("delta.txt"), "dddd").unwrap();
+        let app = with_pins_file(app.handle_refresh(), &state);
+        let app = select(app, "alpha.txt").toggle_pin();
+        let app = select(app, "gamma").toggle_pin();
+
+        let app = app.toggle_sort_direction();
+        assert_eq!(
+            entry_names(&app),
+            ["alpha.txt", "gamma", "delta.txt", "beta.txt"]
+        );
+
+        let app = app.handle_command(Command::SetSort(SortField::Size, SortDirection::Descending));
+        assert_eq!(
+            entry_names(&app),
+            ["alpha.txt", "gamma", "delta.txt", "beta.txt"]
+        );
+
+        let app = app.reverse_listing();
+        assert_eq!(
+            entry_names(&app),
+            ["alpha.txt", "gamma", "beta.txt", "delta.txt"]
+        );
+    }
+
+    #[test]
+    fn pins_reach_other_panels_and_survive_navigation() {
+        let (_tmp, app) = setup_app();
+        let state = TempDir::new().unwrap();
+        let app = with_pins_file(app, &state).with_toggle_dual_mode();
+        let app = select(app, "beta.txt").toggle_pin();
+
+        let right = app.right_panel().entries();
+        assert_eq!(right[0].name(), "beta.txt");
+
+        let app = select(app, "gamma").handle_command(Command::Enter);
+        let app = app.handle_command(Command::GoUp);
+        assert_eq!(entry_names(&app)[0], "beta.txt");
+    }
+
+    #[test]
+    fn handle_toggle_hidden() {
+        let (tmp, app) = setup_app();
+        // Create a hidden file
+        fs::write(tmp.path().join(".hidden"), "secret").unwrap();
+
+        let count_before = app.panel().entries().len();
+        let app = app.handle_command(Command::ToggleHidden);
+        let count_after = app.panel().entries().len();
+        // After toggling hidden, should see more entries (the hidden file)
+        assert!(count_after > count_before);
+    }
+
+    #[test]
+    fn handle_refresh_preserves_dir() {
+        let (_tmp, app) = setup_app();
+        let dir = app.panel().current_dir().to_path_buf();
+        let app = app.handle_command(Command::Refresh);
+        assert_eq!(app.panel().current_dir(), dir.as_path());
+    }
+
+    #[test]
+    fn handle_set_sort_changes_sort() {
+        let (_tmp, app) = setup_app();
+        let app = app.handle_command(Command::SetSort(SortField::Size, SortDirection::Ascending));
+        // sort_field should change
+        assert_ne!(app.panel().sort_field(), SortField::Name);
+        // status message should mention sort
+        assert!(app.status_message().is_some());
+    }
+
+    /// App remembering sorts in `mode`, persisted into a separate temp dir.
+    fn with_remember_sort(app: App, mode: RememberSort, state: &TempDir) -> App {
+        App {
+            remember_sort: mode,
+            sort_memory: SortMemory::default(),
+            sort_memory_path: state.path().join("sort.toml"),
+            ..app
+        }
+    }
+
+    #[test]
+    fn remembered_sort_is_restored_per_directory() {
+        let (_tmp, app) = setup_app();
+        let state = TempDir::new().unwrap();
+        let app = with_remember_sort(app, RememberSort::Directory, &state);
+
+        let app = app.handle_command(Command::Enter);
+        let app = app.handle_command(Command::SetSort(SortField::Size, SortDirection::Ascending));
+        assert_eq!(app.panel().sort_field(), SortField::Size);
+
+        let app = app.handle_command(Command::GoUp);
+        assert_eq!(app.panel().sort_field(), SortField::Name);
+
+        let app = app.handle_command(Command::Enter);
+        assert_eq!(app.panel().sort_field(), SortField::Size);
+        assert!(state.path().join("sort.toml").exists());
+    }
+
+    #[test]
+    fn unvisited_directory_uses_default_sort() {
+        let (tmp, app) = setup_app();
+        let state = TempDir::new().unwrap();
+        let app = with_remember_sort(app, RememberSort::Directory, &state);
+
+        let app = app.handle_command(Command::SetSort(SortField::Size, SortDirection::Ascending));
+        let app = app.handle_command(Command::Enter);
+
+        assert_eq!(
+            app.panel().current_dir(),
+            tmp.path().join("gamma").canonicalize().unwrap()
+        );
+        assert_eq!(app.panel().sort_field(), SortField::Name);
+        assert_eq!(app.panel().sort_direction(), SortDirection::Ascending);
+    }
+
+    #[test]
+    fn global_sort_memory_follows_across_directories() {
+        let (_tmp, app) = setup_app();
+        let state = TempDir::new().unwrap();
+        let app = with_remember_sort(app, RememberSort::Global, &state);
+
+        let app = app.handle_command(Command::SetSort(SortField::Size, SortDirection::Ascending));
+        let app = app.handle_command(Command::Enter);
+        assert_eq!(app.panel().sort_field(), SortField::Size);
+
+        let saved = SortMemory::load_from_file(&state.path().join("sort.toml")).unwrap();
+        assert_eq!(saved.global().map(|s| s.field), Some(SortField::Size));
+    }
+
+    #[test]
+    fn sort_is_not_saved_when_memory_is_off() {
+        let (_tmp, app) = setup_app();
+        let state = TempDir::new().unwrap();
+        let app = with_remember_sort(app, RememberSort::Off, &state);
+
+        let _app = app.handle_command(Command::SetSort(SortField::Size, SortDirection::Ascending));
+
+        assert!(!state.path().join("sort.toml").exists());
+    }
+
+    #[test]
+    fn handle_go_back_with_no_history() {
+        let (_tmp, app) = setup_app();
+        let dir = app.panel().current_dir().to_path_buf();
+        let app = app.handle_command(Command::GoBack);
+        // Should stay in same directory
+        assert_eq!(app.panel().current_dir(), dir.as_path());
+    }
+
+    #[test]
+    fn handle_go_forward_with_no_history() {
+        let (_tmp, app) = setup_app();
+        let dir = app.panel().current_dir().to_path_buf();
+        let app = app.handle_command(Command::GoForward);
+        assert_eq!(app.panel().current_dir(), dir.as_path());
+    }
+
+    #[test]
+    fn go_back_restores_the_previous_selection() {
+        let (tmp, app) = setup_app();
+        let app = select(app, "beta.txt");
+
+        let panel = app.panel().navigate_to(&tmp.path().join("gamma")).unwrap();
+        let app = app.with_panel(panel).handle_command(Command::GoBack);
+
+        assert_eq!(app.panel().selected_entry().unwrap().name(), "beta.txt");
+    }
+
+    #[test]
+    fn go_back_starts_at_top_when_selection_vanished() {
+        let (tmp, app) = setup_app();
+        let app = select(app, "beta.txt");
+
+        let panel = app.panel().navigate_to(&tmp.path().join("gamma")).unwrap();
+        fs::remove_file(tmp.path().join("beta.txt")).unwrap();
+        let app = app.with_panel(panel).handle_command(Command::GoBack);
+
+        assert_eq!(app.panel().selected_index(), 0);
+    }
+
+    // --- PanelState tests ---
+
+    #[test]
+    fn panel_state_from_dir() {
+        let tmp = TempDir::new().unwrap();
+        fs::write(tmp.path().join("file.txt"), "").unwrap();
+        let panel = PanelState::from_dir(tmp.path()).unwrap();
+        assert_eq!(panel.selected_index(), 0);
+        assert!(!panel.entries().is_empty());
+    }
+
+    #[test]
+    fn panel_state_cursor_movements() {
+        let tmp = TempDir::new().unwrap();
+        fs::write(tmp.path().join("a.txt"), "").unwrap();
+        fs::write(tmp.path().join("b.txt"), "").unwrap();
+        fs::write(tmp.path().join("c.txt"), "").unwrap();
+        let panel = PanelState::from_dir(tmp.path()).unwrap();
+
+        let panel = panel.with_cursor_down();
+        assert_eq!(panel.selected_index(), 1);
+
+        let panel = panel.with_cursor_down();
+        assert_eq!(panel.selected_index(), 2);
+
+        let panel = panel.with_cursor_up();
+        assert_eq!(panel.selected_index(), 1);
+
+        let panel = panel.with_cursor_top();
+        assert_eq!(panel.selected_index(), 0);
+
+        let panel = panel.with_cursor_bottom();
+        assert_eq!(panel.selected_index(), 2);
+    }
+
+    #[test]
+    fn panel_state_show_hidden_default_false() {
+        let tmp = TempDir::new().unwrap();
+        fs::write(tmp.path().join("visible.txt"), "").unwrap();
+        let panel = PanelState::from_dir(tmp.path()).unwrap();
+        assert!(!panel.show_hidden());
+    }
+
+    #[test]
+    fn panel_state_toggle_hidden_shows_hidden_files() {
+        let tmp = TempDir::new().unwrap();
+        fs::write(tmp.path().join(".hidden"), "").unwrap();
+        fs::write(tmp.path().join("visible.txt"), "").unwrap();
+        let panel = PanelState::from_dir(tmp.path()).unwrap();
+
+        assert_eq!(panel.entries().len(), 1); // only visible.txt
+        let panel = panel.with_toggle_hidden().unwrap();
+        assert_eq!(panel.entries().len(), 2); // both files
+        assert!(panel.show_hidden());
+    }
+
+    #[test]
+    fn panel_state_with_show_hidden_sets_the_default() {
+        let tmp = TempDir::new().unwrap();
+        fs::write(tmp.path().join(".hidden"), "").unwrap();
+        fs::write(tmp.path().join("visible.txt"), "").unwrap();
+        let panel = PanelState::from_dir(tmp.path()).unwrap();
+
+        let shown = panel.with_show_hidden(true).unwrap();
+        assert!(shown.show_hidden());
+        assert_eq!(shown.entries().len(), 2);
+
+        let hidden = shown.with_show_hidden(false).unwrap();
+        assert!(!hidden.show_hidden());
+        assert_eq!(hidden.entries().len(), 1);
+    }
+
+    #[test]
+    fn show_hidden_survives_navigation() {
+        let tmp = TempDir::new().unwrap();
+        let sub = tmp.path().join("sub");
+        fs::create_dir(&sub).unwrap();
+        fs::write(sub.join(".dotfile"), "").unwrap();
+        let panel = PanelState::from_dir(tmp.path())
+            .unwrap()
+            .with_show_hidden(true)
+            .unwrap();
+
+        let panel = panel.navigate_to(&sub).unwrap();
+        assert!(panel.show_hidden());
+        assert!(panel.entries().iter().any(|e| e.name() == ".dotfile"));
+
+        let panel = panel.go_up().unwrap().with_toggle_hidden().unwrap();
+        let panel = panel.navigate_to(&sub).unwrap();
+        assert!(!panel.show_hidden());
+        assert!(panel.entries().is_empty());
+    }
+
+    #[test]
+    fn go_up_selects_the_directory_just_left() {
+        let tmp = TempDir::new().unwrap();
+        for name in ["a", "b", "c"] {
+            fs::create_dir(tmp.path().join(name)).unwrap();
+        }
+        let panel = PanelState::from_dir(tmp.path()).unwrap();
+
+        let panel = panel.navigate_to(&tmp.path().join("b")).unwrap();
+        let panel = panel.go_up().unwrap();
+
+        assert_eq!(panel.selected_entry().unwrap().name(), "b");
+    }
+
+    #[test]
+    fn go_up_keeps_cursor_at_top_when_child_is_not_listed() {
+        let tmp = TempDir::new().unwrap();
+        fs::create_dir(tmp.path().join("a")).unwrap();
+        fs::create_dir(tmp.path().join(".hidden")).unwrap();
+        let panel = PanelState::from_dir(tmp.path()).unwrap();
+
+        let panel = panel.navigate_to(&tmp.path().join(".hidden")).unwrap();
+        let panel = panel.go_up().unwrap();
+
+        assert!(!panel.show_hidden());
+        assert_eq!(panel.selected_entry().unwrap().name(), "a");
+    }
+
+    #[test]
+    fn with_show_hidden_keeps_local_value() {
+        let tmp = TempDir::new().unwrap();
+        fs::write(
+            tmp.path().join(".trefm.toml"),
+            "[general]\nshow_hidden = false\n",
+        )
+        .unwrap();
+        let panel = PanelState::from_dir(tmp.path())
+            .unwrap()
+            .with_show_hidden(true)
+            .unwrap();
+        assert!(!panel.show_hidden());
+    }
+
+    #[test]
+    fn local_config_applies_only_inside_its_directory() {
+        let tmp = TempDir::new().unwrap();
+        let project = tmp.path().join("project");
+        fs::create_dir(&project).unwrap();
+        fs::write(project.join(".env"), "").unwrap();
+        fs::write(
+            project.join(".trefm.toml"),
+            "[general]\nshow_hidden = true\ndefault_sort = \"size\"\n",
+        )
+        .unwrap();
+        let panel = PanelState::from_dir(tmp.path()).unwrap();
+
+        let panel = panel.navigate_to(&project).unwrap();
+        assert!(panel.show_hidden());
+        assert_eq!(panel.sort_field(), SortField::Size);
+        assert!(panel.entries().iter().any(|e| e.name() == ".env"));
+
+        let panel = panel.go_up().unwrap();
+        assert!(!panel.show_hidden());
+        assert_eq!(panel.sort_field(), SortField::Name);
+
+        let panel = panel.go_back().unwrap().unwrap();
+        assert!(panel.show_hidden());
+    }
+
+    #[test]
+    fn toggle_hidden_replaces_local_value() {
+        let tmp = TempDir::new().unwrap();
+        fs::write(
+            tmp.path().join(".trefm.toml"),
+            "[general]\nshow_hidden = true\n",
+        )
+        .unwrap();
+        let panel = PanelState::from_dir(tmp.path()).unwrap();
+        assert!(panel.show_hidden());
+
+        let panel = panel.with_toggle_hidden().unwrap();
+        assert!(!panel.show_hidden());
+        assert!(panel.entries().is_empty());
+    }
+
+    #[test]
+    fn panel_state_sort_field_default_name() {
+        let tmp = TempDir::new().unwrap();
+        fs::write(tmp.path().join("a.txt"), "").unwrap();
+        let panel = PanelState::from_dir(tmp.path()).unwrap();
+        assert_eq!(panel.sort_field(), SortField::Name);
+    }
+
+    #[test]
+    fn panel_state_next_sort_cycles() {
+        let tmp = TempDir::new().unwrap();
+        fs::write(tmp.path().join("a.txt"), "").unwrap();
+        let panel = PanelState::from_dir(tmp.path()).unwrap();
+
+        let panel = panel.with_next_sort();
+        assert_eq!(panel.sort_field(), SortField::Size);
+
+        let panel = panel.with_next_sort();
+        assert_eq!(panel.sort_field(), SortField::Date);
+
+        let panel = panel.with_next_sort();
+        assert_eq!(panel.sort_field(), SortField::Type);
+
+        let panel = panel.with_next_sort();
+        assert_eq!(panel.sort_field(), SortField::Name);
+    }
+
+    #[test]
+    fn panel_state_toggled_direction_reverses_entries() {
+        let tmp = TempDir::new().unwrap();
+        for name in ["a.txt", "b.txt", "c.txt"] {
+            fs::write(tmp.path().join(name), "").unwrap();
+        }
+        let panel = PanelState::from_dir(tmp.path()).unwrap();
+        assert_eq!(panel.entries()[0].name(), "a.txt");
+
+        let panel = panel.with_toggled_direction();
+        assert_eq!(panel.sort_field(), SortField::Name);
+        assert_eq!(panel.sort_direction(), SortDirection::Descending);
+        assert_eq!(panel.entries()[0].name(), "c.txt");
+
+        let panel = panel.with_toggled_direction();
+        assert_eq!(panel.sort_direction(), SortDirection::Ascending);
+        assert_eq!(panel.entries()[0].name(), "a.txt");
+    }
+
+    #[test]
+    fn resorting_uses_the_loaded_entries() {
+        let tmp = TempDir::new().unwrap();
+        fs::write(tmp.path().join("big.txt"), "xxxxxxxx").unwrap();
+        fs::write(tmp.path().join("small.txt"), "x").unwrap();
+        let panel = PanelState::from_dir(tmp.path())
+            .unwrap()
+            .with_sort(SortField::Date, SortDirection::Ascending);
+        // Changes on disk after the listing must not show up in a re-sort.
+        fs::remove_file(tmp.path().join("big.txt")).unwrap();
+        fs::write(tmp.path().join("new.txt"), "").unwrap();
+
+        let panel = panel.with_sort(SortField::Size, SortDirection::Descending);
+
+        let names: Vec<&str> = panel.entries().iter().map(|e| e.name()).collect();
+        assert_eq!(names, ["big.txt", "small.txt"]);
+        let panel = panel.with_toggled_direction().with_next_sort();
+        let names: Vec<&str> = panel.entries().iter().map(|e| e.name()).collect();
+        assert_eq!(panel.sort_field(), SortField::Date);
+        assert_eq!(names.len(), 2);
+    }
+
+    /// Lazy metadata reads made while running `f`.
+    fn stat_calls_during<T>(f: impl FnOnce() -> T) -> (T, usize) {
+        let before = trefm_core::fs::entry::stat_calls();
+        let value = f();
+        (value, trefm_core::fs::entry::stat_calls() - before)
+    }
+
+    #[test]
+    fn name_sorted_listing_reads_no_metadata() {
+        let tmp = TempDir::new().unwrap();
+        for i in 0..10 {
+            fs::write(tmp.path().join(format!("f{i}.txt")), "x".repeat(i)).unwrap();
+        }
+        fs::create_dir(tmp.path().join("sub")).unwrap();
+
+        let (panel, calls) = stat_calls_during(|| PanelState::from_dir(tmp.path()).unwrap());
+        assert_eq!(panel.sort_field(), SortField::Name);
+        assert_eq!(panel.entries().len(), 11);
+        assert_eq!(calls, 0);
+
+        let (panel, calls) =
+            stat_calls_during(|| panel.with_sort(SortField::Size, SortDirection::Descending));
+        assert_eq!(calls, 11);
+        let (_, calls) =
+            stat_calls_during(|| panel.with_sort(SortField::Date, SortDirection::Ascending));
+        assert_eq!(calls, 0);
+    }
+
+    #[test]
+    fn sort_keeps_cursor_on_entry() {
+        let (_tmp, app) = setup_app();
+        let app = select(app, "beta.txt");
+
+        let panel = app
+            .panel()
+            .with_sort(SortField::Name, SortDirection::Descending);
+
+        assert_eq!(panel.selected_entry().unwrap().name(), "beta.txt");
+    }
+
+    #[test]
+    fn reverse_listing_keeps_cursor_on_entry() {
+        let (_tmp, app) = setup_app();
+        let app = select(app, "alpha.txt");
+        let before: Vec<String> = app
+            .panel()
+            .entries()
+            .iter()
+            .map(|e| e.name().to_string())
+            .collect();
+
+        let app = app.reverse_listing();
+        let after: Vec<String> = app
+            .panel()
+            .entries()
+            .iter()
+            .map(|e| e.name().to_string())
+            .collect();
+        let mut expected = before.clone();
+        expected.reverse();
+        assert_eq!(after, expected);
+        assert_eq!(app.panel().selected_entry().unwrap().name(), "alpha.txt");
+        assert_eq!(app.panel().sort_direction(), SortDirection::Ascending);
+        assert_eq!(app.status_message(), Some("Listing reversed"));
+    }
+
+    #[test]
+    fn toggle_sort_direction_keeps_field_and_reports() {
+        let (_tmp, app) = setup_app();
+        let app = app.toggle_sort_direction();
+        assert_eq!(app.panel().sort_field(), SortField::Name);
+        assert_eq!(app.panel().sort_direction(), SortDirection::Descending);
+        assert_eq!(app.status_message(), Some("Sort: Name Descending"));
+    }
+
+    #[test]
+    fn panel_state_navigate_to_subdir() {
+        let tmp = TempDir::new().unwrap();
+        fs::create_dir(tmp.path().join("subdir")).unwrap();
+        fs::write(tmp.path().join("subdir").join("file.txt"), "").unwrap();
+        let panel = PanelState::from_dir(tmp.path()).unwrap();
+
+        let subdir = tmp.path().join("subdir");
+        let panel = panel.navigate_to(&subdir).unwrap();
+        assert_eq!(panel.entries().len(), 1);
+    }
+
+    #[test]
+    fn panel_state_refresh() {
+        let tmp = TempDir::new().unwrap();
+        fs::write(tmp.path().join("a.txt"), "").unwrap();
+        let panel = PanelState::from_dir(tmp.path()).unwrap();
+        assert_eq!(panel.entries().len(), 1);
+
+        // Add a file then refresh
+        fs::write(tmp.path().join("b.txt"), "").unwrap();
+        let panel = panel.refresh().unwrap();
+        assert_eq!(panel.entries().len(), 2);
+    }
+
+    #[test]
+    fn panel_state_refresh_preserves_selected_entry() {
+        let tmp = TempDir::new().unwrap();
+        fs::write(tmp.path().join("b.txt"), "").unwrap();
+        fs::write(tmp.path().join("c.txt"), "").unwrap();
+        let panel = PanelState::from_dir(tmp.path()).unwrap().with_cursor_to(1);
+        assert_eq!(panel.selected_entry().unwrap().name(), "c.txt");
+
+        // A new entry sorting before the cursor shifts indices
+        fs::write(tmp.path().join("a.txt"), "").unwrap();
+        let panel = panel.refresh().unwrap();
+        assert_eq!(panel.entries().len(), 3);
+        assert_eq!(panel.selected_entry().unwrap().name(), "c.txt");
+    }
+
+    #[test]
+    fn panel_state_refresh_keeps_index_when_selected_removed() {
+        let tmp = TempDir::new().unwrap();
+        for name in ["a.txt", "b.txt", "c.txt"] {
+            fs::write(tmp.path().join(name), "").unwrap();
+        }
+        let panel = PanelState::from_dir(tmp.path()).unwrap().with_cursor_to(1);
+
+        fs::remove_file(tmp.path().join("b.txt")).unwrap();
+        let panel = panel.refresh().unwrap();
+        assert_eq!(panel.selected_index(), 1);
+        assert_eq!(panel.selected_entry().unwrap().name(), "c.txt");
+    }
+
+    // --- filter_hidden ---
+
+    #[test]
+    fn filter_hidden_hides_dotfiles() {
+        let tmp = TempDir::new().unwrap();
+        fs::write(tmp.path().join(".hidden"), "").unwrap();
+        fs::write(tmp.path().join("visible.txt"), "").unwrap();
+        let entries = trefm_core::fs::ops::read_directory(tmp.path()).unwrap();
+
+        let filtered = filter_hidden(&entries, false);
+        assert_eq!(filtered.len(), 1);
+        assert_eq!(filtered[0].name(), "visible.txt");
+    }
+
+    #[test]
+    fn filter_hidden_shows_all_when_true() {
+        let tmp = TempDir::new().unwrap();
+        fs::write(tmp.path().join(".hidden"), "").unwrap();
+        fs::write(tmp.path().join("visible.txt"), "").unwrap();
+        let entries = trefm_core::fs::ops::read_directory(tmp.path()).unwrap();
+
+        let filtered = filter_hidden(&entries, true);
+        assert_eq!(filtered.len(), 2);
+    }
+
+    // --- Immutability checks ---
+
+    #[test]
+    fn app_with_mode_does_not_mutate_original() {
+        let (_tmp, app) = setup_app();
+        // Rust ownership prevents accessing after move,
+        // but we verify the returned app has the new mode
+        let app = app.with_mode(AppMode::Help);
+        assert!(matches!(app.mode(), AppMode::Help));
+        let app = app.with_mode(AppMode::Normal);
+        assert!(matches!(app.mode(), AppMode::Normal));
+    }
+
+    #[test]
+    fn unrecognized_command_returns_self() {
+        let (_tmp, app) = setup_app();
+        let dir = app.panel().current_dir().to_path_buf();
+        let idx = app.panel().selected_index();
+
+        // CopyFiles is not handled — should return self unchanged
+        let app = app.handle_command(Command::CopyFiles(vec![], PathBuf::new()));
+        assert_eq!(app.panel().current_dir(), dir.as_path());
+        assert_eq!(app.panel().selected_index(), idx);
+    }
+
+    // --- Git integration tests ---
+
+    #[test]
+    fn app_in_git_repo_has_git_statuses() {
+        let tmp = TempDir::new().unwrap();
+        let repo = git2::Repository::init(tmp.path()).unwrap();
+
+        // Configure and create initial commit
+        {
+            let mut config = repo.config().unwrap();
+            config.set_str("user.name", "Test").unwrap();
+            config.set_str("user.email", "t@t.com").unwrap();
+        }
+        {
+            let sig = git2::Signature::now("Test", "t@t.com").unwrap();
+            let tree_id = repo.index().unwrap().write_tree().unwrap();
+            let tree = repo.find_tree(tree_id).unwrap();
+            repo.commit(Some("HEAD"), &sig, &sig, "init", &tree, &[])
+                .unwrap();
+        }
+
+        fs::write(tmp.path().join("file.txt"), "content").unwrap();
+
+        let app = App::new(tmp.path()).unwrap();
+        assert!(
+            app.git_statuses().is_some(),
+            "app in git repo should have git_statuses"
+        );
+    }
+
+    #[test]
+    fn app_in_git_repo_has_branch_info() {
+        let tmp = TempDir::new().unwrap();
+        let repo = git2::Repository::init(tmp.path()).unwrap();
+
+        {
+            let mut config = repo.config().unwrap();
+            config.set_str("user.name", "Test").unwrap();
+            config.set_str("user.email", "t@t.com").unwrap();
+        }
+        {
+            let sig = git2::Signature::now("Test", "t@t.com").unwrap();
+            let tree_id = repo.index().unwrap().write_tree().unwrap();
+            let tree = repo.find_tree(tree_id).unwrap();
+            repo.commit(Some("HEAD"), &sig, &sig, "init", &tree, &[])
+                .unwrap();
+        }
+
+        let app = App::new(tmp.path()).unwrap();
+        assert!(
+            app.branch_info().is_some(),
+            "app in git repo should have branch_info"
+        );
+        let info = app.branch_info().unwrap();
+        assert!(!info.name.is_empty());
+    }
+
+    #[test]
+    fn app_not_in_git_repo_has_no_git_statuses() {
+        let (_tmp, app) = setup_app();
+        // setup_app creates a plain temp dir, not a git repo
+        assert!(app.git_statuses().is_none());
+    }
+
+    #[test]
+    fn app_not_in_git_repo_has_no_branch_info() {
+        let (_tmp, app) = setup_app();
+        assert!(app.branch_info().is_none());
+    }
+
+    #[test]
+    fn app_navigation_refreshes_git_state() {
+        let tmp = TempDir::new().unwrap();
+        let repo = git2::Repository::init(tmp.path()).unwrap();
+
+        {
+            let mut config = repo.config().unwrap();
+            config.set_str("user.name", "Test").unwrap();
+            config.set_str("user.email", "t@t.com").unwrap();
+        }
+        {
+            let sig = git2::Signature::now("Test", "t@t.com").unwrap();
+            let tree_id = repo.index().unwrap().write_tree().unwrap();
+            let tree = repo.find_tree(tree_id).unwrap();
+            repo.commit(Some("HEAD"), &sig, &sig, "init", &tree, &[])
+                .unwrap();
+        }
+
+        fs::create_dir(tmp.path().join("subdir")).unwrap();
+        fs::write(tmp.path().join("subdir").join("child.txt"), "").unwrap();
+
+        let app = App::new(tmp.path()).unwrap();
+        assert!(app.git_statuses().is_some());
+
+        // Navigate into subdir — git state should still be present (same repo)
+        let app = app.handle_command(Command::Enter);
+        // After enter, if the selected entry was the dir, we should still have git info
+        if app.git_statuses().is_some() {
+            assert!(app.branch_info().is_some());
+        }
+    }
+
+    // --- load_git_statuses / load_branch_info tests ---
+
+    #[test]
+    fn load_git_statuses_non_repo_returns_none() {
+        let tmp = TempDir::new().unwrap();
+        assert!(load_git_statuses(tmp.path(), &mut RepoRootCache::default()).is_none());
+    }
+
+    #[test]
+    fn load_branch_info_non_repo_returns_none() {
+        let tmp = TempDir::new().unwrap();
+        assert!(load_branch_info(tmp.path(), &mut RepoRootCache::default()).is_none());
+    }
+
+    #[test]
+    fn load_git_statuses_in_repo_returns_some() {
+        let tmp = TempDir::new().unwrap();
+        let repo = git2::Repository::init(tmp.path()).unwrap();
+
+        {
+            let mut config = repo.config().unwrap();
+            config.set_str("user.name", "Test").unwrap();
+            config.set_str("user.email", "t@t.com").unwrap();
+        }
+        {
+            let sig = git2::Signature::now("Test", "t@t.com").unwrap();
+            let tree_id = repo.index().unwrap().write_tree().unwrap();
+            let tree = repo.find_tree(tree_id).unwrap();
+            repo.commit(Some("HEAD"), &sig, &sig, "init", &tree, &[])
+                .unwrap();
+        }
+
+        assert!(load_git_statuses(tmp.path(), &mut RepoRootCache::default()).is_some());
+    }
+
+    #[test]
+    fn load_git_statuses_marks_ignored_entries() {
+        let tmp = TempDir::new().unwrap();
+        git2::Repository::init(tmp.path()).unwrap();
+        std::fs::write(tmp.path().join(".gitignore"), "*.log\n").unwrap();
+        std::fs::write(tmp.path().join("app.log"), "").unwrap();
+
+        let statuses = load_git_statuses(tmp.path(), &mut RepoRootCache::default()).unwrap();
+        assert_eq!(
+            statuses.get(&tmp.path().join("app.log")),
+            Some(&GitFileStatus::Ignored)
+        );
+        assert_eq!(
+            statuses.get(&tmp.path().join(".gitignore")),
+            Some(&GitFileStatus::Untracked)
+        );
+    }
+
+    #[test]
+    fn load_branch_info_in_repo_returns_some() {
+        let tmp = TempDir::new().unwrap();
+        let repo = git2::Repository::init(tmp.path()).unwrap();
+
+        {
+            let mut config = repo.config().unwrap();
+            config.set_str("user.name", "Test").unwrap();
+            config.set_str("user.email", "t@t.com").unwrap();
+        }
+        {
+            let sig = git2::Signature::now("Test", "t@t.com").unwrap();
+            let tree_id = repo.index().unwrap().write_tree().unwrap();
+            let tree = repo.find_tree(tree_id).unwrap();
+            repo.commit(Some("HEAD"), &sig, &sig, "init", &tree, &[])
+                .unwrap();
+        }
+
+        let info = load_branch_info(tmp.path(), &mut RepoRootCache::default());
+        assert!(info.is_some());
+    }
+
+    // =====================================================
+    // Search mode tests
+    // =====================================================
+
+    #[test]
+    fn search_push_char_updates_query() {
+        let (_tmp, app) = setup_app();
+        let app = app.with_mode(AppMode::Search(String::new()));
+        let app = app.search_push_char('a');
+        assert!(matches!(app.mode(), AppMode::Search(q) if q == "a"));
+    }
+
+    #[test]
+    fn search_push_multiple_chars() {
+        let (_tmp, app) = setup_app();
+        let app = app.with_mode(AppMode::Search(String::new()));
+        let app = app.search_push_char('a');
+        let app = app.search_push_char('l');
+        let app = app.search_push_char('p');
+        assert!(matches!(app.mode(), AppMode::Search(q) if q == "alp"));
+    }
+
+    #[test]
+    fn search_push_char_filters_results() {
+        let (_tmp, app) = setup_app();
+        let app = app.with_mode(AppMode::Search(String::new()));
+        let app = app.search_push_char('a');
+        let app = app.search_push_char('l');
+        let app = app.search_push_char('p');
+        let app = app.search_push_char('h');
+        let app = app.search_push_char('a');
+        // "alpha" should match "alpha.txt"
+        assert!(!app.search_results().is_empty());
+        assert_eq!(app.search_results()[0].entry().name(), "alpha.txt");
+    }
+
+    #[test]
+    fn search_results_skip_excluded_entries() {
+        let (tmp, app) = setup_app();
+        fs::create_dir(tmp.path().join("node_modules")).unwrap();
+        fs::write(tmp.path().join("notes.txt"), "n").unwrap();
+        let app = App {
+            search_exclude: ExcludeSet::new(["node_modules"]),
+            ..app
+        };
+        let app = app.handle_refresh();
+        let app = app.with_mode(AppMode::Search(String::new()));
+        let app = app.search_push_char('n');
+        let names: Vec<&str> = app
+            .search_results()
+            .iter()
+            .map(|m| m.entry().name())
+            .collect();
+        assert!(names.contains(&"notes.txt"));
+        assert!(!names.contains(&"node_modules"));
+    }
+
+    #[test]
+    fn search_pop_char_removes_last() {
+        let (_tmp, app) = setup_app();
+        let app = app.with_mode(AppMode::Search("abc".to_string()));
+        let app = app.search_pop_char();
+        assert!(matches!(app.mode(), AppMode::Search(q) if q == "ab"));
+    }
+
+    #[test]
+    fn search_pop_char_empty_query_stays_empty() {
+        let (_tmp, app) = setup_app();
+        let app = app.with_mode(AppMode::Search(String::new()));
+        let app = app.search_pop_char();
+        assert!(matches!(app.mode(), AppMode::Search(q) if q.is_empty()));
+    }
+
+    #[test]
+    fn search_empty_query_returns_all_entries() {
+        let (_tmp, app) = setup_app();
+        let total_entries = app.panel().entries().len();
+        let app = app.with_mode(AppMode::Search(String::new()));
+        // Push then pop to trigger filtering with empty query
+        let app = app.search_push_char('x');
+        let app = app.search_pop_char();
+        // Empty query should show all entries
+        assert_eq!(app.search_results().len(), total_entries);
+    }
+
+    #[test]
+    fn search_no_match_returns_empty_results() {
+        let (_tmp, app) = setup_app();
+        let app = app.with_mode(AppMode::Search(String::new()));
+        let app = app.search_push_char('z');
+        let app = app.search_push_char('z');
+        let app = app.search_push_char('z');
+        assert!(app.search_results().is_empty());
+    }
+
+    #[test]
+    fn search_move_down_increments_selected() {
+        let (_tmp, app) = setup_app();
+        let app = app.with_mode(AppMode::Search(String::new()));
+        // Push a query that matches multiple entries
+        let app = app.search_push_char('a');
+        assert_eq!(app.search_selected(), 0);
+        if app.search_results().len() > 1 {
+            let app = app.search_move_down();
+            assert_eq!(app.search_selected(), 1);
+        }
+    }
+
+    #[test]
+    fn search_move_up_decrements_selected() {
+        let (_tmp, app) = setup_app();
+        let app = app.with_mode(AppMode::Search(String::new()));
+        let app = app.search_push_char('a');
+        if app.search_results().len() > 1 {
+            let app = app.search_move_down();
+            assert_eq!(app.search_selected(), 1);
+            let app = app.search_move_up();
+            assert_eq!(app.search_selected(), 0);
+        }
+    }
+
+    #[test]
+    fn search_move_up_at_zero_stays_zero() {
+        let (_tmp, app) = setup_app();
+        let app = app.with_mode(AppMode::Search(String::new()));
+        let app = app.search_push_char('a');
+        assert_eq!(app.search_selected(), 0);
+        let app = app.search_move_up();
+        assert_eq!(app.search_selected(), 0);
+    }
+
+    #[test]
+    fn search_move_down_empty_results_stays_zero() {
+        let (_tmp, app) = setup_app();
+        let app = app.with_mode(AppMode::Search(String::new()));
+        let app = app.search_push_char('z');
+        let app = app.search_push_char('z');
+        let app = app.search_push_char('z');
+        assert!(app.search_results().is_empty());
+        let app = app.search_move_down();
+        assert_eq!(app.search_selected(), 0);
+    }
+
+    #[test]
+    fn search_confirm_exits_to_normal() {
+        let (_tmp, app) = setup_app();
+        let app = app.with_mode(AppMode::Search(String::new()));
+        let app = app.search_push_char('a');
+        let app = app.search_confirm();
+        assert!(matches!(app.mode(), AppMode::Normal));
+        assert!(app.search_results().is_empty());
+        assert_eq!(app.search_selected(), 0);
+    }
+
+    #[test]
+    fn search_confirm_empty_results_returns_to_normal() {
+        let (_tmp, app) = setup_app();
+        let app = app.with_mode(AppMode::Search("zzz".to_string()));
+        let app = app.search_confirm();
+        assert!(matches!(app.mode(), AppMode::Normal));
+    }
+
+    #[test]
+    fn search_confirm_navigates_to_directory() {
+        let (_tmp, app) = setup_app();
+        let original_dir = app.panel().current_dir().to_path_buf();
+        let app = app.with_mode(AppMode::Search(String::new()));
+        // Search for "gamma" (the subdirectory)
+        let app = app.search_push_char('g');
+        let app = app.search_push_char('a');
+        let app = app.search_push_char('m');
+        if !app.search_results().is_empty() && app.search_results()[0].entry().is_dir() {
+            let app = app.search_confirm();
+            assert!(matches!(app.mode(), AppMode::Normal));
+            // Should have navigated into gamma directory
+            assert_ne!(app.panel().current_dir(), original_dir.as_path());
+        }
+    }
+
+    #[test]
+    fn search_push_char_resets_selected_to_zero() {
+        let (_tmp, app) = setup_app();
+        let app = app.with_mode(AppMode::Search(String::new()));
+        let app = app.search_push_char('a');
+        if app.search_results().len() > 1 {
+            let app = app.search_move_down();
+            assert_eq!(app.search_selected(), 1);
+            // New char should reset selection
+            let app = app.search_push_char('l');
+            assert_eq!(app.search_selected(), 0);
+        }
+    }
+
+    #[test]
+    fn search_push_char_not_in_search_mode_is_noop() {
+        let (_tmp, app) = setup_app();
+        let dir = app.panel().current_dir().to_path_buf();
+        let app = app.search_push_char('a');
+        // Should not change anything since not in search mode
+        assert!(matches!(app.mode(), AppMode::Normal));
+        assert_eq!(app.panel().current_dir(), dir.as_path());
+    }
+
+    // =====================================================
+    // Bookmark tests
+    // =====================================================
+
+    #[test]
+    fn app_new_has_bookmarks() {
+        let (_tmp, app) = setup_app();
+        // bookmarks() should return a valid Bookmarks (possibly empty)
+        let _bm = app.bookmarks();
+    }
+
+    #[test]
+    fn bookmark_add_saves_current_dir() {
+        let (_tmp, app) = setup_app();
+        let dir = app.panel().current_dir().to_path_buf();
+        let app = app.with_mode(AppMode::BookmarkAdd("test_bm".to_string()));
+        let app = app.bookmark_add("test_bm");
+
+        assert!(matches!(app.mode(), AppMode::Normal));
+        assert!(app.bookmarks().contains("test_bm"));
+        assert_eq!(app.bookmarks().get("test_bm"), Some(&dir));
+        assert!(app.status_message().is_some());
+    }
+
+    #[test]
+    fn bookmark_add_empty_label_shows_error() {
+        let (_tmp, app) = setup_app();
+        let app = app.with_mode(AppMode::BookmarkAdd(String::new()));
+        let app = app.bookmark_add("");
+
+        assert!(matches!(app.mode(), AppMode::Normal));
+        assert!(app.status_message().unwrap().contains("empty"));
+    }
+
+    #[test]
+    fn bookmark_jump_navigates_to_path() {
+        let (tmp, app) = setup_app();
+        let subdir = tmp.path().join("gamma");
+        // Add a bookmark pointing to the gamma subdir
+        let bookmarks = app.bookmarks().clone().with_bookmark("sub", &subdir);
+        // Create app with this bookmark
+        let app = App {
+            bookmarks,
+            mode: AppMode::BookmarkList { selected: 0 },
+            ..app
+        };
+        // Find which index "sub" is at
+        let labels: Vec<String> = app.bookmarks().iter().map(|(k, _)| k.clone()).collect();
+        let idx = labels.iter().position(|l| l == "sub").unwrap();
+        let app = app.bookmark_jump(idx);
+
+        assert!(matches!(app.mode(), AppMode::Normal));
+        assert!(app.status_message().unwrap().contains("Jumped"));
+    }
+
+    #[test]
+    fn bookmark_jump_invalid_index_returns_to_normal() {
+        let (_tmp, app) = setup_app();
+        let app = app.with_mode(AppMode::BookmarkList { selected: 0 });
+        // Jump to index 999 — should be out of bounds for empty bookmarks
+        let app = app.bookmark_jump(999);
+        assert!(matches!(app.mode(), AppMode::Normal));
+    }
+
+    #[test]
+    fn bookmark_delete_removes_entry() {
+        let (_tmp, app) = setup_app();
+        let bookmarks = app
+            .bookmarks()
+            .clone()
+            .with_bookmark("to_delete", "/tmp/del");
+        let app = App {
+            bookmarks,
+            mode: AppMode::BookmarkList { selected: 0 },
+            ..app
+        };
+        let labels: Vec<String> = app.bookmarks().iter().map(|(k, _)| k.clone()).collect();
+        let idx = labels.iter().position(|l| l == "to_delete").unwrap();
+        let app = app.bookmark_delete(idx);
+
+        assert!(!app.bookmarks().contains("to_delete"));
+        assert!(app.status_message().unwrap().contains("removed"));
+    }
+
+    #[test]
+    fn bookmark_delete_invalid_index_is_noop() {
+        let (_tmp, app) = setup_app();
+        let app = app.with_mode(AppMode::BookmarkList { selected: 0 });
+        let count_before = app.bookmarks().len();
+        let app = app.bookmark_delete(999);
+        assert_eq!(app.bookmarks().len(), count_before);
+    }
+
+    #[test]
+    fn bookmark_delete_adjusts_selected_index() {
+        let (_tmp, app) = setup_app();
+        let bookmarks = app
+            .bookmarks()
+            .clone()
+            .with_bookmark("aaa", "/aaa")
+            .with_bookmark("bbb", "/bbb");
+        let app = App {
+            bookmarks,
+            mode: AppMode::BookmarkList { selected: 1 },
+            ..app
+        };
+        // Delete last item (index 1) — selected should adjust
+        let app = app.bookmark_delete(1);
+        if let AppMode::BookmarkList { selected } = app.mode() {
+            assert!(*selected < app.bookmarks().len() || app.bookmarks().is_empty());
+        }
+    }
+
+    #[test]
+    fn bookmark_mode_transitions() {
+        let (_tmp, app) = setup_app();
+        // Enter BookmarkAdd mode
+        let app = app.with_mode(AppMode::BookmarkAdd(String::new()));
+        assert!(matches!(app.mode(), AppMode::BookmarkAdd(_)));
+
+        // Exit back to normal
+        let app = app.with_mode(AppMode::Normal);
+        assert!(matches!(app.mode(), AppMode::Normal));
+
+        // Enter BookmarkList mode
+        let app = app.with_mode(AppMode::BookmarkList { selected: 0 });
+        assert!(matches!(app.mode(), AppMode::BookmarkList { .. }));
+    }
+
+    // =====================================================
+    // Recent directories tests
+    // =====================================================
+
+    /// App whose recent directories start at its own directory and are
+    /// saved into a separate temp dir.
+    fn with_recent_dirs_file(app: App, state: &TempDir) -> App {
+        App {
+            recent_dirs: RecentDirs::default().visited(app.panel().current_dir()),
+            recent_dirs_path: state.path().join("recent_dirs.toml"),
+            ..app
+        }
+    }
+
+    fn visit(app: App, dir: &Path) -> App {
+        let panel = app.panel().navigate_to(dir).unwrap();
+        app.with_panel(panel)
+    }
+
+    fn recent_paths(app: &App, query: &str) -> Vec<PathBuf> {
+        app.recent_dir_matches(query)
+            .into_iter()
+            .map(|m| m.path)
+            .collect()
+    }
+
+    #[test]
+    fn navigating_records_recent_dirs_without_the_current_one() {
+        let (tmp, app) = setup_app();
+        let state = TempDir::new().unwrap();
+        let root = tmp.path().to_path_buf();
+        let gamma = root.join("gamma");
+        let app = with_recent_dirs_file(app, &state);
+
+        let app = visit(app, &gamma);
+        assert_eq!(recent_paths(&app, ""), [root.as_path()]);
+
+        let app = visit(app, &root);
+        assert_eq!(recent_paths(&app, ""), [gamma.as_path()]);
+        let app = visit(app, &gamma);
+        assert_eq!(recent_paths(&app, ""), [root]);
+    }
+
+    #[test]
+    fn recent_dirs_switcher_filters_and_jumps() {
+        let (tmp, app) = setup_app();
+        let state = TempDir::new().unwrap();
+        let root = tmp.path().to_path_buf();
+        fs::create_dir(root.join("projects")).unwrap();
+        fs::create_dir(root.join("photos")).unwrap();
+        let app = with_recent_dirs_file(app, &state);
+        let app = visit(app, &root.join("projects"));
+        let app = visit(app, &root.join("photos"));
+        let app = visit(app, &root);
+
+        let app = app.with_mode(AppMode::RecentDirs {
+            query: String::new(),
+            selected: 0,
+        });
+        let app = "prj"
+            .chars()
+            .fold(app, |app, c| app.recent_dirs_push_char(c));
+        assert!(matches!(app.mode(), AppMode::RecentDirs { query, .. } if query == "prj"));
+        assert_eq!(recent_paths(&app, "prj"), [root.join("projects")]);
+
+        let app = app.recent_dirs_confirm();
+        assert!(matches!(app.mode(), AppMode::Normal));
+        assert_eq!(app.panel().current_dir(), root.join("projects"));
+    }
+
+    #[test]
+    fn recent_dirs_cursor_stays_within_matches() {
+        let (tmp, app) = setup_app();
+        let state = TempDir::new().unwrap();
+        let app = with_recent_dirs_file(app, &state);
+        let app = visit(app, &tmp.path().join("gamma"));
+        let app = app.with_mode(AppMode::RecentDirs {
+            query: String::new(),
+            selected: 0,
+        });
+
+        let app = app.recent_dirs_move_down().recent_dirs_move_down();
+        assert!(matches!(
+            app.mode(),
+            AppMode::RecentDirs { selected: 0, .. }
+        ));
+        let app = app.recent_dirs_push_char('x').recent_dirs_pop_char();
+        assert!(matches!(app.mode(), AppMode::RecentDirs { query, .. } if query.is_empty()));
+    }
+
+    #[test]
+    fn recent_dirs_are_saved() {
+        let (tmp, app) = setup_app();
+        let state = TempDir::new().unwrap();
+        let app = with_recent_dirs_file(app, &state);
+        let app = visit(app, &tmp.path().join("gamma"));
+
+        app.save_recent_dirs();
+
+        let saved = RecentDirs::load_from_file(&state.path().join("recent_dirs.toml")).unwrap();
+        let dirs: Vec<&Path> = saved.dirs().collect();
+        assert_eq!(dirs, [tmp.path().join("gamma").as_path(), tmp.path()]);
+    }
+
+    // =====================================================
+    // Recent files tests
+    // =====================================================
+
+    #[test]
+    fn load_recent_files_enters_recent_mode() {
+        let (_tmp, app) = setup_app();
+        let app = app.load_recent_files();
+        assert!(matches!(app.mode(), AppMode::RecentFiles));
+    }
+
+    #[test]
+    fn load_recent_files_populates_results() {
+        let (_tmp, app) = setup_app();
+        let app = app.load_recent_files();
+        // setup_app creates alpha.txt, beta.txt, and gamma/inside.txt
+        assert!(!app.recent_results().is_empty());
+    }
+
+    #[test]
+    fn load_recent_files_resets_selected() {
+        let (_tmp, app) = setup_app();
+        let app = app.load_recent_files();
+        assert_eq!(app.recent_selected(), 0);
+    }
+
+    #[test]
+    fn recent_move_down_increments_selected() {
+        let (_tmp, app) = setup_app();
+        let app = app.load_recent_files();
+        if app.recent_results().len() > 1 {
+            let app = app.recent_move_down();
+            assert_eq!(app.recent_selected(), 1);
+        }
+    }
+
+    #[test]
+    fn recent_move_up_decrements_selected() {
+        let (_tmp, app) = setup_app();
+        let app = app.load_recent_files();
+        if app.recent_results().len() > 1 {
+            let app = app.recent_move_down();
+            assert_eq!(app.recent_selected(), 1);
+            let app = app.recent_move_up();
+            assert_eq!(app.recent_selected(), 0);
+        }
+    }
+
+    #[test]
+    fn recent_move_up_at_zero_stays_zero() {
+        let (_tmp, app) = setup_app();
+        let app = app.load_recent_files();
+        let app = app.recent_move_up();
+        assert_eq!(app.recent_selected(), 0);
+    }
+
+    #[test]
+    fn recent_move_down_empty_results_stays_zero() {
+        let tmp = TempDir::new().unwrap();
+        let app = App::new(tmp.path()).unwrap();
+        let app = app.load_recent_files();
+        assert!(app.recent_results().is_empty());
+        let app = app.recent_move_down();
+        assert_eq!(app.recent_selected(), 0);
+    }
+
+    #[test]
+    fn recent_confirm_exits_to_normal() {
+        let (_tmp, app) = setup_app();
+        let app = app.load_recent_files();
+        let app = app.recent_confirm();
+        assert!(matches!(app.mode(), AppMode::Normal));
+        assert!(app.recent_results().is_empty());
+        assert_eq!(app.recent_selected(), 0);
+    }
+
+    #[test]
+    fn recent_confirm_navigates_to_parent_dir() {
+        let (_tmp, app) = setup_app();
+        let app = app.load_recent_files();
+        // Find inside.txt (nested file) in results
+        let nested_idx = app
+            .recent_results()
+            .iter()
+            .position(|e| e.name() == "inside.txt");
+        if let Some(idx) = nested_idx {
+            let mut app = app;
+            for _ in 0..idx {
+                app = app.recent_move_down();
+            }
+            let app = app.recent_confirm();
+            assert!(matches!(app.mode(), AppMode::Normal));
+            // Should have navigated into gamma dir
+            assert!(app.panel().current_dir().ends_with("gamma"));
+        }
+    }
+
+    #[test]
+    fn recent_confirm_empty_results_returns_to_normal() {
+        let tmp = TempDir::new().unwrap();
+        let app = App::new(tmp.path()).unwrap();
+        let app = app.load_recent_files();
+        let app = app.recent_confirm();
+        assert!(matches!(app.mode(), AppMode::Normal));
+    }
+
+    #[test]
+    fn recent_mode_transition() {
+        let (_tmp, app) = setup_app();
+        let app = app.with_mode(AppMode::RecentFiles);
+        assert!(matches!(app.mode(), AppMode::RecentFiles));
+        let app = app.with_mode(AppMode::Normal);
+        assert!(matches!(app.mode(), AppMode::Normal));
+    }
+
+    // =====================================================
+    // Duplicate files tests (cache-based)
+    // =====================================================
+
+    use trefm_core::{
+        CachedDuplicateGroup, CachedFileInfo, DuplicateCache, DuplicateIgnoreList, DuplicateSort,
+    };
+
+    fn sample_duplicate_cache() -> DuplicateCache {
+        DuplicateCache {
+            groups: vec![CachedDuplicateGroup {
+                size: 17,
+                hash: "abc".to_string(),
+                files: vec![
+                    CachedFileInfo {
+                        path: std::path::PathBuf::from("/tmp/file_a.txt"),
+                        name: "file_a.txt".to_string(),
+                        size: 17,
+                    },
+                    CachedFileInfo {
+                        path: std::path::PathBuf::from("/tmp/file_b.txt"),
+                        name: "file_b.txt".to_string(),
+                        size: 17,
+                    },
+                ],
+            }],
+            scanned_at: None,
+            scan_root: None,
+        }
+    }
+
+    #[test]
+    fn show_duplicate_files_enters_mode() {
+        let (_tmp, app) = setup_app();
+        let app = app.with_duplicate_cache(sample_duplicate_cache());
+        let app = app.show_duplicate_files();
+        assert!(matches!(app.mode(), AppMode::DuplicateFiles));
+        assert_eq!(app.duplicate_selected(), 0);
+    }
+
+    /// `root` holding `a.txt` and `sub/b.txt` with equal content.
+    fn local_duplicate_tree(root: &Path) -> DuplicateCache {
+        fs::create_dir_all(root.join("sub")).unwrap();
+        fs::write(root.join("a.txt"), "twin").unwrap();
+        fs::write(root.join("sub/b.txt"), "twin").unwrap();
+        crate::background::scan_under(root, &Default::default()).unwrap()
+    }
+
+    #[test]
+    fn local_duplicate_scan_replaces_view_but_not_global_cache() {
+        let (tmp, app) = setup_app();
+        let cache_file = tmp.path().join("duplicates.json");
+        let global = sample_duplicate_cache();
+        global.save(&cache_file);
+        let saved_before = fs::read_to_string(&cache_file).unwrap();
+        let app = app
+            .with_duplicate_cache(global)
+            .start_local_duplicate_scan();
+
+        let scan = app.local_duplicate_scan().unwrap();
+        assert!(scan.running);
+        assert!(matches!(app.mode(), AppMode::DuplicateFiles));
+        assert!(app.duplicate_results().is_empty());
+        assert_eq!(app.in_flight_ops(), 1);
+
+        let id = scan.id;
+        let local = local_duplicate_tree(&tmp.path().join("gamma"));
+        let app = app
+            .with_operation_finished()
+            .with_local_duplicates(id, local);
+
+        let results = app.duplicate_results();
+        assert_eq!(results.len(), 1);
+        assert!(results[0]
+            .files
+            .iter()
+            .all(|f| f.path.starts_with(tmp.path())));
+        assert!(!app.local_duplicate_scan().unwrap().running);
+        assert_eq!(
+            app.duplicate_cache().groups,
+            sample_duplicate_cache().groups
+        );
+        assert_eq!(fs::read_to_string(&cache_file).unwrap(), saved_before);
+
+        let app = app.show_duplicate_files();
+        assert!(app.local_duplicate_scan().is_none());
+        assert_eq!(app.duplicate_results()[0].hash, "abc");
+    }
+
+    #[test]
+    fn stale_local_duplicate_result_is_ignored() {
+        let (tmp, app) = setup_app();
+        let app = app.start_local_duplicate_scan();
+        let stale = app.local_duplicate_scan().unwrap().id;
+        let app = app.start_local_duplicate_scan();
+
+        let app = app.with_local_duplicates(stale, local_duplicate_tree(tmp.path()));
+
+        assert!(app.local_duplicate_scan().unwrap().running);
+        assert!(app.duplicate_results().is_empty());
+    }
+
+    #[test]
+    fn deleting_from_local_duplicates_updates_the_view() {
+        let (tmp, app) = setup_app();
+        let app = app.start_local_duplicate_scan();
+        let id = app.local_duplicate_scan().unwrap().id;
+        let app = app.with_local_duplicates(id, local_duplicate_tree(tmp.path()));
+
+        let app = app.with_local_duplicate_removed(&tmp.path().join("a.txt"));
+
+        assert!(app.duplicate_results().is_empty());
+    }
+
+    #[test]
+    fn show_duplicate_files_shows_cached_results() {
+        let (_tmp, app) = setup_app();
+        let app = app.with_duplicate_cache(sample_duplicate_cache());
+        let app = app.show_duplicate_files();
+        assert!(!app.duplicate_results().is_empty());
+        assert_eq!(app.duplicate_results()[0].files.len(), 2);
+    }
+
+    #[test]
+    fn duplicate_move_down_increments() {
+        let (_tmp, app) = setup_app();
+        let app = app
+            .with_duplicate_cache(sample_duplicate_cache())
+            .show_duplicate_files();
+        let app = app.duplicate_move_down();
+        assert_eq!(app.duplicate_selected(), 1);
+    }
+
+    #[test]
+    fn duplicate_move_up_decrements() {
+        let (_tmp, app) = setup_app();
+        let app = app
+            .with_duplicate_cache(sample_duplicate_cache())
+            .show_duplicate_files();
+        let app = app.duplicate_move_down();
+        assert_eq!(app.duplicate_selected(), 1);
+        let app = app.duplicate_move_up();
+        assert_eq!(app.duplicate_selected(), 0);
+    }
+
+    #[test]
+    fn duplicate_move_up_at_zero_stays() {
+        let (_tmp, app) = setup_app();
+        let app = app
+            .with_duplicate_cache(sample_duplicate_cache())
+            .show_duplicate_files();
+        let app = app.duplicate_move_up();
+        assert_eq!(app.duplicate_selected(), 0);
+    }
+
+    #[test]
+    fn duplicate_move_down_empty_stays() {
+        let (_tmp, app) = setup_app();
+        let app = app.show_duplicate_files();
+        assert!(app.duplicate_results().is_empty());
+        let app = app.duplicate_move_down();
+        assert_eq!(app.duplicate_selected(), 0);
+    }
+
+    #[test]
+    fn duplicate_confirm_exits_to_normal() {
+        let (tmp, app) = setup_app();
+        let cache = DuplicateCache {
+            groups: vec![CachedDuplicateGroup {
+                size: 3,
+                hash: "h".to_string(),
+                files: vec![
+                    CachedFileInfo {
+                        path: tmp.path().join("alpha.txt"),
+                        name: "alpha.txt".to_string(),
+                        size: 3,
+                    },
+                    CachedFileInfo {
+                        path: tmp.path().join("beta.txt"),
+                        name: "beta.txt".to_string(),
+                        size: 3,
+                    },
+                ],
+            }],
+            scanned_at: None,
+            scan_root: None,
+        };
+        let app = app.with_duplicate_cache(cache).show_duplicate_files();
+        let app = app.duplicate_confirm();
+        assert!(matches!(app.mode(), AppMode::Normal));
+        assert_eq!(app.duplicate_selected(), 0);
+    }
+
+    #[test]
+    fn duplicate_confirm_navigates_to_parent() {
+        let (tmp, app) = setup_app();
+        let cache = DuplicateCache {
+            groups: vec![CachedDuplicateGroup {
+                size: 3,
+                hash: "h".to_string(),
+                files: vec![
+                    CachedFileInfo {
+                        path: tmp.path().join("alpha.txt"),
+                        name: "alpha.txt".to_string(),
+                        size: 3,
+                    },
+                    CachedFileInfo {
+                        path: tmp.path().join("beta.txt"),
+                        name: "beta.txt".to_string(),
+                        size: 3,
+                    },
+                ],
+            }],
+            scanned_at: None,
+            scan_root: None,
+        };
+        let app = app.with_duplicate_cache(cache).show_duplicate_files();
+        let app = app.duplicate_confirm();
+        assert!(matches!(app.mode(), AppMode::Normal));
+        assert_eq!(
+            app.panel().current_dir().canonicalize().unwrap(),
+            tmp.path().canonicalize().unwrap()
+        );
+    }
+
+    #[test]
+    fn duplicate_confirm_empty_results_returns_normal() {
+        let (_tmp, app) = setup_app();
+        let app = app.show_duplicate_files();
+        let app = app.duplicate_confirm();
+        assert!(matches!(app.mode(), AppMode::Normal));
+    }
+
+    #[test]
+    fn duplicate_mode_transition() {
+        let (_tmp, app) = setup_app();
+        let app = app.with_mode(AppMode::DuplicateFiles);
+        assert!(matches!(app.mode(), AppMode::DuplicateFiles));
+        let app = app.with_mode(AppMode::Normal);
+        assert!(matches!(app.mode(), AppMode::Normal));
+    }
+
+    #[test]
+    fn stale_derived_duplicates_are_ignored() {
+        let (_tmp, app) = setup_app();
+        let app = app.with_duplicate_cache(sample_duplicate_cache());
+        let base = app.duplicate_generation();
+        // A full scan lands while an incremental update of `base` runs
+        let app = app.with_duplicate_cache(DuplicateCache::default());
+
+        let app = app.with_derived_duplicates(base, sample_duplicate_cache());
+        assert!(app.duplicate_cache().is_empty());
+
+        let base = app.duplicate_generation();
+        let app = app.with_derived_duplicates(base, sample_duplicate_cache());
+        assert_eq!(app.duplicate_cache().groups.len(), 1);
+        assert_ne!(app.duplicate_generation(), base);
+    }
+
+    #[test]
+    fn with_duplicate_cache_replaces_cache() {
+        let (_tmp, app) = setup_app();
+        assert!(app.duplicate_cache().is_empty());
+        let app = app.with_duplicate_cache(sample_duplicate_cache());
+        assert!(!app.duplicate_cache().is_empty());
+        assert_eq!(app.duplicate_cache().groups.len(), 1);
+    }
+
+    #[test]
+    fn with_scan_status_updates_status() {
+        let (_tmp, app) = setup_app();
+        assert_eq!(app.scan_status(), &ScanStatus::Idle);
+        let app = app.with_scan_status(ScanStatus::Scanning);
+        assert_eq!(app.scan_status(), &ScanStatus::Scanning);
+    }
+
+    #[test]
+    fn duplicate_delete_selected_enters_confirm() {
+        let (_tmp, app) = setup_app();
+        let app = app
+            .with_duplicate_cache(sample_duplicate_cache())
+            .show_duplicate_files();
+        let app = app.duplicate_delete_selected();
+        match app.mode() {
+            AppMode::Confirm(ConfirmAction::DeleteDuplicate(path)) => {
+                assert!(path.to_str().unwrap().contains("file_a.txt"));
+            }
+            other => panic!("Expected Confirm(DeleteDuplicate), got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn duplicate_delete_selected_empty_is_noop() {
+        let (_tmp, app) = setup_app();
+        let app = app.show_duplicate_files();
+        let app = app.duplicate_delete_selected();
+        assert!(matches!(app.mode(), AppMode::DuplicateFiles));
+    }
+
+    /// Two groups — the sample group and a second one hashed "def" — with
+    /// the ignore list redirected into the temp dir.
+    fn setup_duplicate_ignore() -> (TempDir, App) {
+        let (tmp, app) = setup_app();
+        let mut cache = sample_duplicate_cache();
+        let mut second = cache.groups[0].clone();
+        second.hash = "def".to_string();
+        for file in &mut second.files {
+            file.path = std::path::PathBuf::from("/tmp/other").join(&file.name);
+        }
+        cache.groups.push(second);
+        let app = App {
+            duplicate_ignore: DuplicateIgnoreList::default(),
+            duplicate_ignore_path: tmp.path().join("dup-ignore.toml"),
+            ..app
+        };
+        let app = app.with_duplicate_cache(cache).show_duplicate_files();
+        (tmp, app)
+    }
+
+    #[test]
+    fn duplicate_results_exclude_ignored_hashes() {
+        let (_tmp, app) = setup_duplicate_ignore();
+        let app = App {
+            duplicate_ignore: DuplicateIgnoreList::default().with_hash("abc"),
+            ..app
+        };
+        let results = app.duplicate_results();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].hash, "def");
+    }
+
+    #[test]
+    fn duplicate_ignore_selected_hides_group_and_persists() {
+        let (tmp, app) = setup_duplicate_ignore();
+        let app = app.duplicate_move_down().duplicate_move_down();
+        let app = app.duplicate_ignore_selected();
+
+        assert_eq!(app.duplicate_results().len(), 1);
+        assert_eq!(app.duplicate_results()[0].hash, "abc");
+        assert_eq!(app.duplicate_selected(), 1);
+        assert!(app.status_message().unwrap().contains("Ignoring"));
+
+        let saved = DuplicateIgnoreList::load(&tmp.path().join("dup-ignore.toml"));
+        assert!(saved.is_hash_ignored("def"));
+        assert_eq!(saved, app.duplicate_ignore);
+    }
+
+    #[test]
+    fn duplicate_delete_selected_skips_ignored_groups() {
+        let (_tmp, app) = setup_duplicate_ignore();
+        let app = app.duplicate_ignore_selected().duplicate_delete_selected();
+        assert!(matches!(
+            app.mode(),
+            AppMode::Confirm(ConfirmAction::DeleteDuplicate(p)) if p.starts_with("/tmp/other")
+        ));
+    }
+
+    #[test]
+    fn duplicate_cycle_sort_reorders_groups() {
+        let (_tmp, app) = setup_app();
+        let mut cache = sample_duplicate_cache();
+        let mut many = cache.groups[0].clone();
+        many.hash = "many".to_string();
+        many.size = 5;
+        many.files = (0..10)
+            .map(|i| CachedFileInfo {
+                path: std::path::PathBuf::from(format!("/tmp/many/{i}")),
+                name: i.to_string(),
+                size: 5,
+            })
+            .collect();
+        cache.groups.push(many);
+        let app = app
+            .with_duplicate_cache(cache)
+            .show_duplicate_files()
+            .duplicate_move_down();
+        assert_eq!(app.duplicate_results()[0].hash, "abc");
+
+        let app = app.duplicate_cycle_sort();
+        assert_eq!(app.duplicate_sort(), DuplicateSort::Reclaimable);
+        assert_eq!(app.duplicate_results()[0].hash, "many");
+        assert_eq!(app.duplicate_selected(), 0);
+        assert!(app.status_message().unwrap().contains("reclaimable"));
+    }
+
+    fn group_at(hash: &str, size: u64, dir: &Path) -> CachedDuplicateGroup {
+        CachedDuplicateGroup {
+            size,
+            hash: hash.to_string(),
+            files: ["a", "b"]
+                .iter()
+                .map(|name| CachedFileInfo {
+                    path: dir.join(name),
+                    name: name.to_string(),
+                    size,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn current_dir_first_lists_local_groups_before_others() {
+        let (tmp, app) = setup_app();
+        let elsewhere = Path::new("/elsewhere");
+        let cache = DuplicateCache {
+            groups: vec![
+                group_at("big", 900, elsewhere),
+                group_at("local-small", 10, tmp.path()),
+                group_at("mid", 500, elsewhere),
+                group_at("local-nested", 50, &tmp.path().join("gamma")),
+            ],
+            ..Default::default()
+        };
+        let hashes = |app: &App| -> Vec<String> {
+            app.duplicate_results()
+                .into_iter()
+                .map(|g| g.hash)
+                .collect()
+        };
+
+        let app = app.with_duplicate_cache(cache);
+        assert_eq!(hashes(&app), ["big", "mid", "local-nested", "local-small"]);
+
+        let app = App {
+            duplicate_current_dir_first: true,
+            ..app
+        };
+        assert_eq!(hashes(&app), ["local-nested", "local-small", "big", "mid"]);
+    }
+
+    #[test]
+    fn with_duplicate_cache_keeps_sort_order() {
+        let (_tmp, app) = setup_app();
+        let app = app.duplicate_cycle_sort().duplicate_cycle_sort();
+        let mut cache = sample_duplicate_cache();
+        let mut triple = cache.groups[0].clone();
+        triple.hash = "triple".to_string();
+        triple.size = 1;
+        triple.files.push(triple.files[0].clone());
+        cache.groups.push(triple);
+
+        let app = app.with_duplicate_cache(cache);
+        assert_eq!(app.duplicate_sort(), DuplicateSort::Count);
+        assert_eq!(app.duplicate_results()[0].hash, "triple");
+    }
+
+    #[test]
+    fn duplicate_ignore_selected_empty_is_noop() {
+        let (tmp, app) = setup_app();
+        let app = App {
+            duplicate_ignore_path: tmp.path().join("dup-ignore.toml"),
+            ..app
+        };
+        let app = app.show_duplicate_files().duplicate_ignore_selected();
+        assert!(!tmp.path().join("dup-ignore.toml").exists());
+        assert!(app.status_message().is_none());
+    }
+
+    // =====================================================
+    // Yank / paste tests
+    // =====================================================
+
+    /// Two sibling directories; `src` holds `a.txt`, the app starts in `dest`.
+    fn setup_paste(dest_files: &[&str]) -> (TempDir, App, PathBuf) {
+        let tmp = TempDir::new().unwrap();
+        let src_dir = tmp.path().join("src");
+        let dest_dir = tmp.path().join("dest");
+        fs::create_dir(&src_dir).unwrap();
+        fs::create_dir(&dest_dir).unwrap();
+        fs::write(src_dir.join("a.txt"), "new").unwrap();
+        for name in dest_files {
+            fs::write(dest_dir.join(name), "old").unwrap();
+        }
+        let app = App::new(&src_dir).unwrap().yank_selected();
+        let dest_panel = app.panel().navigate_to(&dest_dir).unwrap();
+        let app = app.with_panel(dest_panel);
+        (tmp, app, dest_dir)
+    }
+
+    #[test]
+    fn yank_selected_sets_clipboard() {
+        let (_tmp, app) = setup_app();
+        let selected = app.panel().selected_entry().unwrap().path().to_path_buf();
+        let app = app.yank_selected();
+        assert_eq!(app.clipboard(), &[selected]);
+        assert!(app.status_message().unwrap().starts_with("Yanked"));
+    }
+
+    #[test]
+    fn paste_with_empty_clipboard_reports_status() {
+        let (_tmp, app) = setup_app();
+        let app = app.paste();
+        assert_eq!(app.status_message(), Some("Nothing to paste"));
+        assert!(matches!(app.mode(), AppMode::Normal));
+    }
+
+    #[test]
+    fn paste_without_conflict_copies_file() {
+        let (_tmp, app, dest) = setup_paste(&[]);
+        let app = app.paste();
+        assert!(matches!(app.mode(), AppMode::Normal));
+        assert_eq!(fs::read_to_string(dest.join("a.txt")).unwrap(), "new");
+        assert!(app.panel().entries().iter().any(|e| e.name() == "a.txt"));
+    }
+
+    #[test]
+    fn paste_conflict_enters_confirm_mode() {
+        let (_tmp, app, dest) = setup_paste(&["a.txt"]);
+        let app = app.paste();
+        assert!(matches!(
+            app.mode(),
+            AppMode::Confirm(ConfirmAction::PasteConflict(_))
+        ));
+        assert_eq!(fs::read_to_string(dest.join("a.txt")).unwrap(), "old");
+    }
+
+    #[test]
+    fn paste_resolve_overwrite_replaces_file() {
+        let (_tmp, app, dest) = setup_paste(&["a.txt"]);
+        let app = app.paste().paste_resolve(ConflictChoice::Overwrite);
+        assert!(matches!(app.mode(), AppMode::Normal));
+        assert_eq!(fs::read_to_string(dest.join("a.txt")).unwrap(), "new");
+    }
+
+    #[test]
+    fn paste_without_confirm_overwrite_replaces_silently() {
+        let (_tmp, app, dest) = setup_paste(&["a.txt"]);
+        let app = App {
+            confirm_overwrite: false,
+            ..app
+        }
+        .paste();
+        assert!(matches!(app.mode(), AppMode::Normal));
+        assert_eq!(fs::read_to_string(dest.join("a.txt")).unwrap(), "new");
     }
 
-    // --- filter_hidden ---
-
     #[test]
-    fn filter_hidden_hides_dotfiles() {
-        let tmp = TempDir::new().unwrap();
-        fs::write(tmp.path().join(".hidden"), "").unwrap();
-        fs::write(tmp.path().join("visible.txt"), "").unwrap();
-        let entries = trefm_core::fs::ops::read_directory(tmp.path()).unwrap();
+    fn paste_resolve_skip_keeps_existing() {
+        let (_tmp, app, dest) = setup_paste(&["a.txt"]);
+        let app = app.paste().paste_resolve(ConflictChoice::Skip);
+        assert_eq!(fs::read_to_string(dest.join("a.txt")).unwrap(), "old");
+        assert_eq!(app.status_message(), Some("Pasted 0 item(s), skipped 1"));
+    }
 
-        let filtered = filter_hidden(&entries, false);
-        assert_eq!(filtered.len(), 1);
-        assert_eq!(filtered[0].name(), "visible.txt");
+    #[test]
+    fn paste_resolve_rename_keeps_both() {
+        let (_tmp, app, dest) = setup_paste(&["a.txt"]);
+        let _app = app.paste().paste_resolve(ConflictChoice::Rename);
+        assert_eq!(fs::read_to_string(dest.join("a.txt")).unwrap(), "old");
+        assert_eq!(fs::read_to_string(dest.join("a (1).txt")).unwrap(), "new");
     }
 
     #[test]
-    fn filter_hidden_shows_all_when_true() {
+    fn paste_resolve_outside_conflict_is_noop() {
+        let (_tmp, app) = setup_app();
+        let app = app.paste_resolve(ConflictChoice::Overwrite);
+        assert!(matches!(app.mode(), AppMode::Normal));
+        assert!(app.status_message().is_none());
+    }
+
+    /// Dual mode with the left panel in `src` (holding `a.txt`) and the
+    /// right panel in `dest`; the left panel is focused.
+    fn setup_dual_transfer(dest_files: &[&str]) -> (TempDir, App, PathBuf, PathBuf) {
         let tmp = TempDir::new().unwrap();
-        fs::write(tmp.path().join(".hidden"), "").unwrap();
-        fs::write(tmp.path().join("visible.txt"), "").unwrap();
-        let entries = trefm_core::fs::ops::read_directory(tmp.path()).unwrap();
+        let src_dir = tmp.path().join("src");
+        let dest_dir = tmp.path().join("dest");
+        fs::create_dir(&src_dir).unwrap();
+        fs::create_dir(&dest_dir).unwrap();
+        fs::write(src_dir.join("a.txt"), "new").unwrap();
+        for name in dest_files {
+            fs::write(dest_dir.join(name), "old").unwrap();
+        }
+        let app = App::new(&src_dir)
+            .unwrap()
+            .with_toggle_dual_mode()
+            .with_active_panel(1);
+        let dest_panel = app.panel().navigate_to(&dest_dir).unwrap();
+        let app = app.with_panel(dest_panel).with_active_panel(0);
+        (tmp, app, src_dir, dest_dir)
+    }
 
-        let filtered = filter_hidden(&entries, true);
-        assert_eq!(filtered.len(), 2);
+    #[test]
+    fn copy_to_other_panel_copies_into_its_directory() {
+        let (_tmp, app, src, dest) = setup_dual_transfer(&[]);
+        let app = app.copy_to_other_panel();
+
+        assert!(matches!(app.mode(), AppMode::Normal));
+        assert_eq!(fs::read_to_string(dest.join("a.txt")).unwrap(), "new");
+        assert!(src.join("a.txt").exists());
+        assert!(app
+            .right_panel()
+            .entries()
+            .iter()
+            .any(|e| e.name() == "a.txt"));
+        assert_eq!(app.active_panel_index(), 0);
     }
 
-    // --- Immutability checks ---
+    #[test]
+    fn move_to_other_panel_removes_source() {
+        let (_tmp, app, src, dest) = setup_dual_transfer(&[]);
+        let app = app.move_to_other_panel();
+
+        assert_eq!(fs::read_to_string(dest.join("a.txt")).unwrap(), "new");
+        assert!(!src.join("a.txt").exists());
+        assert!(app.left_panel().entries().is_empty());
+        assert!(app
+            .right_panel()
+            .entries()
+            .iter()
+            .any(|e| e.name() == "a.txt"));
+        assert_eq!(app.status_message(), Some("Moved 1 item(s)"));
+        assert_eq!(
+            app.operation_log().entries().back().unwrap().kind,
+            OperationKind::Move
+        );
+    }
 
     #[test]
-    fn app_with_mode_does_not_mutate_original() {
-        let (_tmp, app) = setup_app();
-        // Rust ownership prevents accessing after move,
-        // but we verify the returned app has the new mode
-        let app = app.with_mode(AppMode::Help);
-        assert!(matches!(app.mode(), AppMode::Help));
-        let app = app.with_mode(AppMode::Normal);
+    fn move_to_other_panel_conflict_asks_first() {
+        let (_tmp, app, src, dest) = setup_dual_transfer(&["a.txt"]);
+        let app = app.move_to_other_panel();
+        assert!(matches!(
+            app.mode(),
+            AppMode::Confirm(ConfirmAction::PasteConflict(_))
+        ));
+
+        let app = app.paste_resolve(ConflictChoice::Overwrite);
+        assert_eq!(fs::read_to_string(dest.join("a.txt")).unwrap(), "new");
+        assert!(!src.join("a.txt").exists());
         assert!(matches!(app.mode(), AppMode::Normal));
     }
 
     #[test]
-    fn unrecognized_command_returns_self() {
-        let (_tmp, app) = setup_app();
-        let dir = app.panel().current_dir().to_path_buf();
-        let idx = app.panel().selected_index();
+    fn transfer_to_other_panel_requires_dual_mode() {
+        let (_tmp, app, src, dest) = setup_dual_transfer(&[]);
+        let app = app.with_toggle_dual_mode().copy_to_other_panel();
 
-        // CopyFiles is not handled — should return self unchanged
-        let app = app.handle_command(Command::CopyFiles(vec![], PathBuf::new()));
-        assert_eq!(app.panel().current_dir(), dir.as_path());
-        assert_eq!(app.panel().selected_index(), idx);
+        assert!(!dest.join("a.txt").exists());
+        assert!(src.join("a.txt").exists());
+        assert_eq!(
+            app.status_message(),
+            Some("Other panel is only available in dual mode")
+        );
     }
 
-    // --- Git integration tests ---
+    #[test]
+    fn inactive_selected_entry_follows_focus() {
+        let (_tmp, app, _src, _dest) = setup_dual_transfer(&["b.txt"]);
+        let name = |app: &App| app.inactive_selected_entry().map(|e| e.name().to_string());
+
+        assert_eq!(name(&app).as_deref(), Some("b.txt"));
+        assert_eq!(name(&app.with_active_panel(1)).as_deref(), Some("a.txt"));
+    }
 
     #[test]
-    fn app_in_git_repo_has_git_statuses() {
-        let tmp = TempDir::new().unwrap();
-        let repo = git2::Repository::init(tmp.path()).unwrap();
+    fn move_tab_to_other_panel_transfers_it() {
+        let (_tmp, app, src, _dest) = setup_dual_transfer(&[]);
+        let app = app.with_new_tab().with_tab_moved_to_other_panel();
+
+        assert_eq!(app.tab_group(0).tab_count(), 1);
+        let right = app.tab_group(1);
+        assert_eq!(right.tab_count(), 2);
+        assert_eq!(right.active_tab_index(), 1);
+        assert_eq!(
+            right.active_tab().panel.current_dir(),
+            src.canonicalize().unwrap()
+        );
+        assert_eq!(app.active_panel_index(), 0);
+    }
 
-        // Configure and create initial commit
-        {
-            let mut config = repo.config().unwrap();
-            config.set_str("user.name", "Test").unwrap();
-            config.set_str("user.email", "t@t.com").unwrap();
-        }
-        {
-            let sig = git2::Signature::now("Test", "t@t.com").unwrap();
-            let tree_id = repo.index().unwrap().write_tree().unwrap();
-            let tree = repo.find_tree(tree_id).unwrap();
-            repo.commit(Some("HEAD"), &sig, &sig, "init", &tree, &[])
-                .unwrap();
-        }
+    #[test]
+    fn copy_tab_to_other_panel_keeps_source() {
+        let (_tmp, app, src, _dest) = setup_dual_transfer(&[]);
+        let app = app.with_tab_copied_to_other_panel();
 
-        fs::write(tmp.path().join("file.txt"), "content").unwrap();
+        assert_eq!(app.tab_group(0).tab_count(), 1);
+        assert_eq!(app.tab_group(1).tab_count(), 2);
+        assert_eq!(app.right_panel().current_dir(), src.canonicalize().unwrap());
+    }
 
-        let app = App::new(tmp.path()).unwrap();
-        assert!(
-            app.git_statuses().is_some(),
-            "app in git repo should have git_statuses"
-        );
+    #[test]
+    fn move_last_tab_is_refused() {
+        let (_tmp, app, _src, _dest) = setup_dual_transfer(&[]);
+        let app = app.with_tab_moved_to_other_panel();
+
+        assert_eq!(app.tab_group(0).tab_count(), 1);
+        assert_eq!(app.tab_group(1).tab_count(), 1);
+        assert_eq!(app.status_message(), Some("Cannot move the last tab"));
     }
 
     #[test]
-    fn app_in_git_repo_has_branch_info() {
-        let tmp = TempDir::new().unwrap();
-        let repo = git2::Repository::init(tmp.path()).unwrap();
+    fn tab_transfer_respects_max_tabs() {
+        let (_tmp, app, _src, _dest) = setup_dual_transfer(&[]);
+        let app = App { max_tabs: 1, ..app };
+        let app = app.with_tab_copied_to_other_panel();
 
-        {
-            let mut config = repo.config().unwrap();
-            config.set_str("user.name", "Test").unwrap();
-            config.set_str("user.email", "t@t.com").unwrap();
-        }
-        {
-            let sig = git2::Signature::now("Test", "t@t.com").unwrap();
-            let tree_id = repo.index().unwrap().write_tree().unwrap();
-            let tree = repo.find_tree(tree_id).unwrap();
-            repo.commit(Some("HEAD"), &sig, &sig, "init", &tree, &[])
-                .unwrap();
-        }
+        assert_eq!(app.tab_group(1).tab_count(), 1);
+        assert_eq!(app.status_message(), Some("Other panel is full (1 tabs)"));
+    }
 
-        let app = App::new(tmp.path()).unwrap();
-        assert!(
-            app.branch_info().is_some(),
-            "app in git repo should have branch_info"
-        );
-        let info = app.branch_info().unwrap();
-        assert!(!info.name.is_empty());
+    #[test]
+    fn swap_panels_exchanges_directories() {
+        let (_tmp, app, src, dest) = setup_dual_transfer(&[]);
+        let app = app.with_swapped_panels();
+
+        assert_eq!(app.left_panel().current_dir(), dest.canonicalize().unwrap());
+        assert_eq!(app.right_panel().current_dir(), src.canonicalize().unwrap());
+        assert_eq!(app.active_panel_index(), 0);
     }
 
     #[test]
-    fn app_not_in_git_repo_has_no_git_statuses() {
-        let (_tmp, app) = setup_app();
-        // setup_app creates a plain temp dir, not a git repo
-        assert!(app.git_statuses().is_none());
+    fn sync_panels_points_other_panel_at_active_directory() {
+        let (_tmp, app, src, _dest) = setup_dual_transfer(&[]);
+        let app = app.with_synced_panels();
+
+        let src = src.canonicalize().unwrap();
+        assert_eq!(app.left_panel().current_dir(), src);
+        assert_eq!(app.right_panel().current_dir(), src);
+        assert!(app
+            .right_panel()
+            .entries()
+            .iter()
+            .any(|e| e.name() == "a.txt"));
+        assert_eq!(app.active_panel_index(), 0);
     }
 
     #[test]
-    fn app_not_in_git_repo_has_no_branch_info() {
-        let (_tmp, app) = setup_app();
-        assert!(app.branch_info().is_none());
+    fn diff_other_panel_puts_left_file_on_the_left() {
+        let (_tmp, app, src, dest) = setup_dual_transfer(&["a.txt"]);
+        fs::write(src.join("a.txt"), "same\nleft\n").unwrap();
+        fs::write(dest.join("a.txt"), "same\nright\nextra\n").unwrap();
+
+        let app = app.with_active_panel(1).enter_diff_with_other_panel();
+
+        assert!(matches!(app.mode(), AppMode::Diff { scroll: 0 }));
+        let (left, right) = app.diff_paths().unwrap();
+        assert_eq!(left.parent().unwrap(), src.canonicalize().unwrap());
+        assert_eq!(right.parent().unwrap(), dest.canonicalize().unwrap());
+        let sides: Vec<(Option<&str>, Option<&str>)> = app
+            .diff_rows()
+            .iter()
+            .map(|row| {
+                (
+                    row.left.as_ref().map(|c| c.text.as_str()),
+                    row.right.as_ref().map(|c| c.text.as_str()),
+                )
+            })
+            .collect();
+        assert_eq!(
+            sides,
+            [
+                (Some("same"), Some("same")),
+                (Some("left"), Some("right")),
+                (None, Some("extra")),
+            ]
+        );
+        assert_eq!(app.pager_len(), 3);
+        assert_eq!(app.with_pager_scroll(2).pager_scroll(), Some(2));
     }
 
     #[test]
-    fn app_navigation_refreshes_git_state() {
-        let tmp = TempDir::new().unwrap();
-        let repo = git2::Repository::init(tmp.path()).unwrap();
+    fn diff_other_panel_reports_identical_files() {
+        let (_tmp, app, src, dest) = setup_dual_transfer(&["a.txt"]);
+        fs::write(dest.join("a.txt"), fs::read(src.join("a.txt")).unwrap()).unwrap();
 
-        {
-            let mut config = repo.config().unwrap();
-            config.set_str("user.name", "Test").unwrap();
-            config.set_str("user.email", "t@t.com").unwrap();
-        }
-        {
-            let sig = git2::Signature::now("Test", "t@t.com").unwrap();
-            let tree_id = repo.index().unwrap().write_tree().unwrap();
-            let tree = repo.find_tree(tree_id).unwrap();
-            repo.commit(Some("HEAD"), &sig, &sig, "init", &tree, &[])
-                .unwrap();
-        }
+        let app = app.enter_diff_with_other_panel();
 
-        fs::create_dir(tmp.path().join("subdir")).unwrap();
-        fs::write(tmp.path().join("subdir").join("child.txt"), "").unwrap();
+        assert!(matches!(app.mode(), AppMode::Normal));
+        assert_eq!(app.status_message(), Some("Files are identical"));
+    }
 
-        let app = App::new(tmp.path()).unwrap();
-        assert!(app.git_statuses().is_some());
+    #[test]
+    fn diff_other_panel_needs_a_counterpart_in_dual_mode() {
+        let (_tmp, app, _src, _dest) = setup_dual_transfer(&[]);
+        let app = app.enter_diff_with_other_panel();
+        assert!(matches!(app.mode(), AppMode::Normal));
+        assert_eq!(app.status_message(), Some("No a.txt in the other panel"));
 
-        // Navigate into subdir — git state should still be present (same repo)
-        let app = app.handle_command(Command::Enter);
-        // After enter, if the selected entry was the dir, we should still have git info
-        if app.git_statuses().is_some() {
-            assert!(app.branch_info().is_some());
-        }
+        let (_tmp, app) = setup_app();
+        let app = app
+            .handle_command(Command::CursorDown)
+            .enter_diff_with_other_panel();
+        assert_eq!(
+            app.status_message(),
+            Some("Other panel is only available in dual mode")
+        );
     }
 
-    // --- load_git_statuses / load_branch_info tests ---
+    // =====================================================
+    // Tab tests
+    // =====================================================
 
     #[test]
-    fn load_git_statuses_non_repo_returns_none() {
-        let tmp = TempDir::new().unwrap();
-        assert!(load_git_statuses(tmp.path()).is_none());
+    fn new_tab_stops_at_configured_limit() {
+        let (_tmp, app) = setup_app();
+        let app = App { max_tabs: 3, ..app };
+        let app = app.with_new_tab().with_new_tab();
+        assert_eq!(app.active_tab_group().tab_count(), 3);
+
+        let app = app.with_new_tab();
+        assert_eq!(app.active_tab_group().tab_count(), 3);
+        assert_eq!(app.status_message(), Some("Maximum 3 tabs reached"));
     }
 
     #[test]
-    fn load_branch_info_non_repo_returns_none() {
-        let tmp = TempDir::new().unwrap();
-        assert!(load_branch_info(tmp.path()).is_none());
+    fn open_in_new_tab_opens_selected_dir_in_new_tab() {
+        let (tmp, app) = setup_app();
+        assert_eq!(app.panel().selected_entry().unwrap().name(), "gamma");
+
+        let app = app.open_in_new_tab();
+
+        let group = app.active_tab_group();
+        assert_eq!(group.tab_count(), 2);
+        assert_eq!(group.active_tab_index(), 1);
+        assert_eq!(app.panel().current_dir(), tmp.path().join("gamma"));
+        assert_eq!(group.active_tab().label, "gamma");
+        assert_eq!(group.tabs()[0].panel.current_dir(), tmp.path());
     }
 
     #[test]
-    fn load_git_statuses_in_repo_returns_some() {
-        let tmp = TempDir::new().unwrap();
-        let repo = git2::Repository::init(tmp.path()).unwrap();
+    fn open_in_new_tab_on_file_does_nothing() {
+        let (tmp, app) = setup_app();
+        let app = app.handle_command(Command::CursorDown);
+        assert!(!app.panel().selected_entry().unwrap().is_dir());
 
-        {
-            let mut config = repo.config().unwrap();
-            config.set_str("user.name", "Test").unwrap();
-            config.set_str("user.email", "t@t.com").unwrap();
-        }
-        {
-            let sig = git2::Signature::now("Test", "t@t.com").unwrap();
-            let tree_id = repo.index().unwrap().write_tree().unwrap();
-            let tree = repo.find_tree(tree_id).unwrap();
-            repo.commit(Some("HEAD"), &sig, &sig, "init", &tree, &[])
-                .unwrap();
-        }
+        let app = app.open_in_new_tab();
 
-        assert!(load_git_statuses(tmp.path()).is_some());
+        assert_eq!(app.active_tab_group().tab_count(), 1);
+        assert_eq!(app.panel().current_dir(), tmp.path());
+        assert_eq!(app.status_message(), Some("Not a directory"));
     }
 
     #[test]
-    fn load_branch_info_in_repo_returns_some() {
-        let tmp = TempDir::new().unwrap();
-        let repo = git2::Repository::init(tmp.path()).unwrap();
+    fn open_in_new_tab_respects_tab_limit() {
+        let (tmp, app) = setup_app();
+        let app = App { max_tabs: 1, ..app };
 
-        {
-            let mut config = repo.config().unwrap();
-            config.set_str("user.name", "Test").unwrap();
-            config.set_str("user.email", "t@t.com").unwrap();
-        }
-        {
-            let sig = git2::Signature::now("Test", "t@t.com").unwrap();
-            let tree_id = repo.index().unwrap().write_tree().unwrap();
-            let tree = repo.find_tree(tree_id).unwrap();
-            repo.commit(Some("HEAD"), &sig, &sig, "init", &tree, &[])
-                .unwrap();
-        }
+        let app = app.open_in_new_tab();
 
-        let info = load_branch_info(tmp.path());
-        assert!(info.is_some());
+        assert_eq!(app.active_tab_group().tab_count(), 1);
+        assert_eq!(app.panel().current_dir(), tmp.path());
+        assert_eq!(app.status_message(), Some("Maximum 1 tabs reached"));
     }
 
-    // =====================================================
-    // Search mode tests
-    // =====================================================
-
     #[test]
-    fn search_push_char_updates_query() {
+    fn renamed_tab_shows_custom_label() {
         let (_tmp, app) = setup_app();
-        let app = app.with_mode(AppMode::Search(String::new()));
-        let app = app.search_push_char('a');
-        assert!(matches!(app.mode(), AppMode::Search(q) if q == "a"));
+        let app = app
+            .with_mode(AppMode::RenameTab(String::new()))
+            .rename_tab_push_char('w')
+            .rename_tab_push_char('i')
+            .rename_tab_push_char('p')
+            .rename_tab_confirm();
+
+        assert!(matches!(app.mode(), AppMode::Normal));
+        assert_eq!(app.active_tab_group().active_tab().display_label(), "wip");
     }
 
     #[test]
-    fn search_push_multiple_chars() {
+    fn custom_tab_label_survives_directory_change() {
         let (_tmp, app) = setup_app();
-        let app = app.with_mode(AppMode::Search(String::new()));
-        let app = app.search_push_char('a');
-        let app = app.search_push_char('l');
-        let app = app.search_push_char('p');
-        assert!(matches!(app.mode(), AppMode::Search(q) if q == "alp"));
+        let app = app
+            .with_mode(AppMode::RenameTab("work".to_string()))
+            .rename_tab_confirm()
+            .handle_command(Command::Enter);
+
+        let tab = app.active_tab_group().active_tab();
+        assert_eq!(tab.label, "gamma");
+        assert_eq!(tab.display_label(), "work");
     }
 
     #[test]
-    fn search_push_char_filters_results() {
+    fn empty_tab_name_falls_back_to_directory() {
         let (_tmp, app) = setup_app();
-        let app = app.with_mode(AppMode::Search(String::new()));
-        let app = app.search_push_char('a');
-        let app = app.search_push_char('l');
-        let app = app.search_push_char('p');
-        let app = app.search_push_char('h');
-        let app = app.search_push_char('a');
-        // "alpha" should match "alpha.txt"
-        assert!(!app.search_results().is_empty());
-        assert_eq!(app.search_results()[0].entry().name(), "alpha.txt");
+        let app = app
+            .with_mode(AppMode::RenameTab("work".to_string()))
+            .rename_tab_confirm()
+            .with_mode(AppMode::RenameTab("  ".to_string()))
+            .rename_tab_confirm();
+
+        let tab = app.active_tab_group().active_tab();
+        assert_eq!(tab.display_label(), tab.label);
+        assert_eq!(app.status_message(), Some("Tab name reset"));
     }
 
     #[test]
-    fn search_pop_char_removes_last() {
+    fn with_only_active_keeps_active_tab_at_index_zero() {
         let (_tmp, app) = setup_app();
-        let app = app.with_mode(AppMode::Search("abc".to_string()));
-        let app = app.search_pop_char();
-        assert!(matches!(app.mode(), AppMode::Search(q) if q == "ab"));
+        let app = app
+            .with_new_tab()
+            .with_new_tab()
+            .with_select_tab(1)
+            .with_mode(AppMode::RenameTab("keep".to_string()))
+            .rename_tab_confirm();
+
+        let group = app.active_tab_group().clone().with_only_active();
+        assert_eq!(group.tab_count(), 1);
+        assert_eq!(group.active_tab_index(), 0);
+        assert_eq!(group.active_tab().display_label(), "keep");
     }
 
     #[test]
-    fn search_pop_char_empty_query_stays_empty() {
+    fn close_other_tabs_asks_when_many_would_close() {
         let (_tmp, app) = setup_app();
-        let app = app.with_mode(AppMode::Search(String::new()));
-        let app = app.search_pop_char();
-        assert!(matches!(app.mode(), AppMode::Search(q) if q.is_empty()));
+        let app = app.with_new_tab().with_new_tab();
+        let app = app.request_close_other_tabs();
+        assert_eq!(app.active_tab_group().tab_count(), 1);
+        assert_eq!(app.status_message(), Some("Closed 2 tab(s)"));
+
+        let app = app.with_new_tab().with_new_tab().with_new_tab();
+        let app = app.request_close_other_tabs();
+        assert!(matches!(
+            app.mode(),
+            AppMode::Confirm(ConfirmAction::CloseOtherTabs)
+        ));
+        assert_eq!(app.active_tab_group().tab_count(), 4);
+
+        let app = app.close_other_tabs();
+        assert!(matches!(app.mode(), AppMode::Normal));
+        assert_eq!(app.active_tab_group().tab_count(), 1);
+        assert_eq!(app.active_tab_group().active_tab_index(), 0);
     }
 
     #[test]
-    fn search_empty_query_returns_all_entries() {
+    fn lowered_limit_keeps_existing_tabs_usable() {
         let (_tmp, app) = setup_app();
-        let total_entries = app.panel().entries().len();
-        let app = app.with_mode(AppMode::Search(String::new()));
-        // Push then pop to trigger filtering with empty query
-        let app = app.search_push_char('x');
-        let app = app.search_pop_char();
-        // Empty query should show all entries
-        assert_eq!(app.search_results().len(), total_entries);
+        let app = app.with_new_tab().with_new_tab().with_new_tab();
+        let app = App { max_tabs: 2, ..app };
+
+        let app = app.with_new_tab();
+        assert_eq!(app.active_tab_group().tab_count(), 4);
+
+        let app = app.with_select_tab(0).with_next_tab();
+        assert_eq!(app.active_tab_group().active_tab_index(), 1);
+        let app = app.with_close_tab();
+        assert_eq!(app.active_tab_group().tab_count(), 3);
+    }
+
+    // =====================================================
+    // Content search tests
+    // =====================================================
+
+    fn content_match(path: &Path, line_number: usize) -> ContentMatch {
+        ContentMatch {
+            path: path.to_path_buf(),
+            line_number,
+            line: "aaa".to_string(),
+        }
     }
 
     #[test]
-    fn search_no_match_returns_empty_results() {
-        let (_tmp, app) = setup_app();
-        let app = app.with_mode(AppMode::Search(String::new()));
-        let app = app.search_push_char('z');
-        let app = app.search_push_char('z');
-        let app = app.search_push_char('z');
-        assert!(app.search_results().is_empty());
+    fn format_count_inserts_thousands_separators() {
+        assert_eq!(format_count(0), "0");
+        assert_eq!(format_count(999), "999");
+        assert_eq!(format_count(2340), "2,340");
+        assert_eq!(format_count(1234567), "1,234,567");
     }
 
     #[test]
-    fn search_move_down_increments_selected() {
+    fn content_search_start_enters_running_results_view() {
         let (_tmp, app) = setup_app();
-        let app = app.with_mode(AppMode::Search(String::new()));
-        // Push a query that matches multiple entries
-        let app = app.search_push_char('a');
-        assert_eq!(app.search_selected(), 0);
-        if app.search_results().len() > 1 {
-            let app = app.search_move_down();
-            assert_eq!(app.search_selected(), 1);
-        }
+        let app = app.content_search_start("aaa".into(), Arc::new(AtomicBool::new(false)));
+        assert!(matches!(app.mode(), AppMode::ContentSearch));
+        assert!(app.content_search().running);
+        assert_eq!(app.content_search().id, 1);
+        assert_eq!(
+            app.content_search().status_line(),
+            "searching… 0 files, 0 matches"
+        );
     }
 
     #[test]
-    fn search_move_up_decrements_selected() {
+    fn content_search_start_cancels_previous_search() {
         let (_tmp, app) = setup_app();
-        let app = app.with_mode(AppMode::Search(String::new()));
-        let app = app.search_push_char('a');
-        if app.search_results().len() > 1 {
-            let app = app.search_move_down();
-            assert_eq!(app.search_selected(), 1);
-            let app = app.search_move_up();
-            assert_eq!(app.search_selected(), 0);
-        }
+        let first = Arc::new(AtomicBool::new(false));
+        let app = app.content_search_start("a".into(), first.clone());
+        let app = app.content_search_start("b".into(), Arc::new(AtomicBool::new(false)));
+        assert!(first.load(Ordering::Relaxed));
+        assert_eq!(app.content_search().id, 2);
     }
 
     #[test]
-    fn search_move_up_at_zero_stays_zero() {
+    fn content_search_progress_updates_status_line() {
         let (_tmp, app) = setup_app();
-        let app = app.with_mode(AppMode::Search(String::new()));
-        let app = app.search_push_char('a');
-        assert_eq!(app.search_selected(), 0);
-        let app = app.search_move_up();
-        assert_eq!(app.search_selected(), 0);
+        let app = app
+            .content_search_start("x".into(), Arc::new(AtomicBool::new(false)))
+            .with_content_search_progress(
+                1,
+                SearchProgress {
+                    files_scanned: 2340,
+                    matches: 15,
+                },
+            );
+        assert_eq!(
+            app.content_search().status_line(),
+            "searching… 2,340 files, 15 matches"
+        );
     }
 
     #[test]
-    fn search_move_down_empty_results_stays_zero() {
+    fn content_search_ignores_messages_from_stale_search() {
         let (_tmp, app) = setup_app();
-        let app = app.with_mode(AppMode::Search(String::new()));
-        let app = app.search_push_char('z');
-        let app = app.search_push_char('z');
-        let app = app.search_push_char('z');
-        assert!(app.search_results().is_empty());
-        let app = app.search_move_down();
-        assert_eq!(app.search_selected(), 0);
+        let app = app
+            .content_search_start("a".into(), Arc::new(AtomicBool::new(false)))
+            .content_search_start("b".into(), Arc::new(AtomicBool::new(false)))
+            .with_content_search_result(1, ContentSearchResult::default());
+        assert!(app.content_search().running);
     }
 
     #[test]
-    fn search_confirm_exits_to_normal() {
+    fn content_search_stop_while_running_sets_cancel_flag() {
         let (_tmp, app) = setup_app();
-        let app = app.with_mode(AppMode::Search(String::new()));
-        let app = app.search_push_char('a');
-        let app = app.search_confirm();
+        let cancel = Arc::new(AtomicBool::new(false));
+        let app = app
+            .content_search_start("x".into(), cancel.clone())
+            .content_search_stop();
+        assert!(cancel.load(Ordering::Relaxed));
+        assert!(matches!(app.mode(), AppMode::ContentSearch));
+    }
+
+    #[test]
+    fn content_search_cancelled_result_keeps_partial_matches() {
+        let (tmp, app) = setup_app();
+        let result = ContentSearchResult {
+            matches: vec![content_match(&tmp.path().join("alpha.txt"), 1)],
+            files_scanned: 1,
+            total_matches: 1,
+            cancelled: true,
+        };
+        let app = app
+            .content_search_start("aaa".into(), Arc::new(AtomicBool::new(false)))
+            .with_content_search_result(1, result);
+        let search = app.content_search();
+        assert!(!search.running);
+        assert_eq!(search.results.len(), 1);
+        assert_eq!(search.status_line(), "stopped — 1 files, 1 matches");
+        // Once finished, stop closes the view.
+        let app = app.content_search_stop();
         assert!(matches!(app.mode(), AppMode::Normal));
-        assert!(app.search_results().is_empty());
-        assert_eq!(app.search_selected(), 0);
     }
 
     #[test]
-    fn search_confirm_empty_results_returns_to_normal() {
-        let (_tmp, app) = setup_app();
-        let app = app.with_mode(AppMode::Search("zzz".to_string()));
-        let app = app.search_confirm();
+    fn content_search_confirm_selects_matched_file() {
+        let (tmp, app) = setup_app();
+        let inside = tmp.path().join("gamma").join("inside.txt");
+        let result = ContentSearchResult {
+            matches: vec![
+                content_match(&tmp.path().join("alpha.txt"), 1),
+                content_match(&inside, 1),
+            ],
+            files_scanned: 2,
+            total_matches: 2,
+            cancelled: false,
+        };
+        let app = app
+            .content_search_start("x".into(), Arc::new(AtomicBool::new(false)))
+            .with_content_search_result(1, result)
+            .content_search_move_down()
+            .content_search_move_down()
+            .content_search_confirm();
         assert!(matches!(app.mode(), AppMode::Normal));
+        assert_eq!(
+            app.panel().current_dir(),
+            tmp.path().join("gamma").canonicalize().unwrap()
+        );
+        assert_eq!(app.panel().selected_entry().unwrap().name(), "inside.txt");
     }
 
+    // =====================================================
+    // Git changes tests
+    // =====================================================
+
     #[test]
-    fn search_confirm_navigates_to_directory() {
+    fn load_git_changes_outside_repo_sets_status() {
         let (_tmp, app) = setup_app();
-        let original_dir = app.panel().current_dir().to_path_buf();
-        let app = app.with_mode(AppMode::Search(String::new()));
-        // Search for "gamma" (the subdirectory)
-        let app = app.search_push_char('g');
-        let app = app.search_push_char('a');
-        let app = app.search_push_char('m');
-        if !app.search_results().is_empty() && app.search_results()[0].entry().is_dir() {
-            let app = app.search_confirm();
-            assert!(matches!(app.mode(), AppMode::Normal));
-            // Should have navigated into gamma directory
-            assert_ne!(app.panel().current_dir(), original_dir.as_path());
-        }
+        let app = app.load_git_changes();
+        assert!(matches!(app.mode(), AppMode::Normal));
+        assert_eq!(app.status_message(), Some("Not inside a git repository"));
     }
 
     #[test]
-    fn search_push_char_resets_selected_to_zero() {
-        let (_tmp, app) = setup_app();
-        let app = app.with_mode(AppMode::Search(String::new()));
-        let app = app.search_push_char('a');
-        if app.search_results().len() > 1 {
-            let app = app.search_move_down();
-            assert_eq!(app.search_selected(), 1);
-            // New char should reset selection
-            let app = app.search_push_char('l');
-            assert_eq!(app.search_selected(), 0);
-        }
+    fn load_git_changes_lists_untracked_files() {
+        let (tmp, app) = setup_app();
+        git2::Repository::init(tmp.path()).unwrap();
+        let app = app
+            .with_repo_roots_invalidated(&[tmp.path().join(".git")])
+            .load_git_changes();
+        assert!(matches!(app.mode(), AppMode::GitChanges));
+        let names: Vec<String> = app
+            .git_changes()
+            .iter()
+            .map(|(p, _)| p.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        assert!(names.contains(&"alpha.txt".to_string()));
+        assert!(app
+            .git_changes()
+            .iter()
+            .all(|(_, s)| *s == GitFileStatus::Untracked));
     }
 
     #[test]
-    fn search_push_char_not_in_search_mode_is_noop() {
-        let (_tmp, app) = setup_app();
-        let dir = app.panel().current_dir().to_path_buf();
-        let app = app.search_push_char('a');
-        // Should not change anything since not in search mode
+    fn git_changes_confirm_reveals_file() {
+        let (tmp, app) = setup_app();
+        let inside = tmp
+            .path()
+            .canonicalize()
+            .unwrap()
+            .join("gamma")
+            .join("inside.txt");
+        let app = App {
+            mode: AppMode::GitChanges,
+            git_changes: vec![
+                (tmp.path().join("alpha.txt"), GitFileStatus::Modified),
+                (inside.clone(), GitFileStatus::Untracked),
+            ],
+            ..app
+        };
+        let app = app.git_changes_move_down().git_changes_confirm();
         assert!(matches!(app.mode(), AppMode::Normal));
-        assert_eq!(app.panel().current_dir(), dir.as_path());
+        assert!(app.git_changes().is_empty());
+        assert_eq!(app.panel().current_dir(), inside.parent().unwrap());
+        assert_eq!(app.panel().selected_entry().unwrap().name(), "inside.txt");
+    }
+
+    #[test]
+    fn git_changes_cursor_stays_in_bounds() {
+        let (tmp, app) = setup_app();
+        let app = App {
+            git_changes: vec![(tmp.path().join("alpha.txt"), GitFileStatus::Modified)],
+            ..app
+        };
+        let app = app.git_changes_move_down().git_changes_move_down();
+        assert_eq!(app.git_changes_selected(), 0);
+        let app = app.git_changes_move_up();
+        assert_eq!(app.git_changes_selected(), 0);
     }
 
     // =====================================================
-    // Bookmark tests
+    // Quit confirmation tests
     // =====================================================
 
     #[test]
-    fn app_new_has_bookmarks() {
+    fn request_quit_when_idle_quits() {
         let (_tmp, app) = setup_app();
-        // bookmarks() should return a valid Bookmarks (possibly empty)
-        let _bm = app.bookmarks();
+        let app = App {
+            confirm_quit_if_busy: true,
+            ..app
+        };
+        assert!(app.request_quit().should_quit());
     }
 
     #[test]
-    fn bookmark_add_saves_current_dir() {
+    fn request_quit_when_busy_asks_for_confirmation() {
         let (_tmp, app) = setup_app();
-        let dir = app.panel().current_dir().to_path_buf();
-        let app = app.with_mode(AppMode::BookmarkAdd("test_bm".to_string()));
-        let app = app.bookmark_add("test_bm");
-
-        assert!(matches!(app.mode(), AppMode::Normal));
-        assert!(app.bookmarks().contains("test_bm"));
-        assert_eq!(app.bookmarks().get("test_bm"), Some(&dir));
-        assert!(app.status_message().is_some());
+        let app = App {
+            confirm_quit_if_busy: true,
+            ..app
+        }
+        .with_operation_started();
+        let app = app.request_quit();
+        assert!(!app.should_quit());
+        assert!(matches!(app.mode(), AppMode::Confirm(ConfirmAction::Quit)));
     }
 
     #[test]
-    fn bookmark_add_empty_label_shows_error() {
+    fn request_quit_when_busy_without_flag_quits() {
         let (_tmp, app) = setup_app();
-        let app = app.with_mode(AppMode::BookmarkAdd(String::new()));
-        let app = app.bookmark_add("");
-
-        assert!(matches!(app.mode(), AppMode::Normal));
-        assert!(app.status_message().unwrap().contains("empty"));
+        let app = App {
+            confirm_quit_if_busy: false,
+            ..app
+        }
+        .with_operation_started();
+        assert!(app.request_quit().should_quit());
     }
 
     #[test]
-    fn bookmark_jump_navigates_to_path() {
+    fn request_delete_of_file_with_dirs_only_scope_deletes_at_once() {
         let (tmp, app) = setup_app();
-        let subdir = tmp.path().join("gamma");
-        // Add a bookmark pointing to the gamma subdir
-        let bookmarks = app.bookmarks().clone().with_bookmark("sub", &subdir);
-        // Create app with this bookmark
         let app = App {
-            bookmarks,
-            mode: AppMode::BookmarkList { selected: 0 },
+            confirm_scope: ConfirmScope::DirsOnly,
             ..app
         };
-        // Find which index "sub" is at
-        let labels: Vec<String> = app.bookmarks().iter().map(|(k, _)| k.clone()).collect();
-        let idx = labels.iter().position(|l| l == "sub").unwrap();
-        let app = app.bookmark_jump(idx);
+
+        let app = select(app, "alpha.txt").request_delete();
 
         assert!(matches!(app.mode(), AppMode::Normal));
-        assert!(app.status_message().unwrap().contains("Jumped"));
+        assert!(!tmp.path().join("alpha.txt").exists());
     }
 
     #[test]
-    fn bookmark_jump_invalid_index_returns_to_normal() {
-        let (_tmp, app) = setup_app();
-        let app = app.with_mode(AppMode::BookmarkList { selected: 0 });
-        // Jump to index 999 — should be out of bounds for empty bookmarks
-        let app = app.bookmark_jump(999);
-        assert!(matches!(app.mode(), AppMode::Normal));
+    fn request_delete_of_dir_with_dirs_only_scope_asks() {
+        let (tmp, app) = setup_app();
+        let app = App {
+            confirm_scope: ConfirmScope::DirsOnly,
+            ..app
+        };
+
+        let app = select(app, "gamma").request_delete();
+
+        assert!(matches!(
+            app.mode(),
+            AppMode::Confirm(ConfirmAction::Delete(paths)) if paths.len() == 1
+        ));
+        assert!(tmp.path().join("gamma").exists());
     }
 
     #[test]
-    fn bookmark_delete_removes_entry() {
-        let (_tmp, app) = setup_app();
-        let bookmarks = app
-            .bookmarks()
-            .clone()
-            .with_bookmark("to_delete", "/tmp/del");
+    fn request_delete_with_none_scope_never_asks() {
+        let (tmp, app) = setup_app();
         let app = App {
-            bookmarks,
-            mode: AppMode::BookmarkList { selected: 0 },
+            confirm_scope: ConfirmScope::None,
             ..app
         };
-        let labels: Vec<String> = app.bookmarks().iter().map(|(k, _)| k.clone()).collect();
-        let idx = labels.iter().position(|l| l == "to_delete").unwrap();
-        let app = app.bookmark_delete(idx);
 
-        assert!(!app.bookmarks().contains("to_delete"));
-        assert!(app.status_message().unwrap().contains("removed"));
+        let app = select(app, "gamma").request_delete();
+
+        assert!(matches!(app.mode(), AppMode::Normal));
+        assert!(!tmp.path().join("gamma").exists());
     }
 
     #[test]
-    fn bookmark_delete_invalid_index_is_noop() {
+    fn operations_counter_tracks_start_and_finish() {
         let (_tmp, app) = setup_app();
-        let app = app.with_mode(AppMode::BookmarkList { selected: 0 });
-        let count_before = app.bookmarks().len();
-        let app = app.bookmark_delete(999);
-        assert_eq!(app.bookmarks().len(), count_before);
+        let app = app.with_operation_started().with_operation_started();
+        assert_eq!(app.in_flight_ops(), 2);
+        let app = app.with_operation_finished();
+        assert_eq!(app.in_flight_ops(), 1);
+        let app = app.with_operation_finished().with_operation_finished();
+        assert_eq!(app.in_flight_ops(), 0);
     }
 
     #[test]
-    fn bookmark_delete_adjusts_selected_index() {
+    fn request_quit_after_operations_finish_quits() {
         let (_tmp, app) = setup_app();
-        let bookmarks = app
-            .bookmarks()
-            .clone()
-            .with_bookmark("aaa", "/aaa")
-            .with_bookmark("bbb", "/bbb");
         let app = App {
-            bookmarks,
-            mode: AppMode::BookmarkList { selected: 1 },
+            confirm_quit_if_busy: true,
             ..app
-        };
-        // Delete last item (index 1) — selected should adjust
-        let app = app.bookmark_delete(1);
-        if let AppMode::BookmarkList { selected } = app.mode() {
-            assert!(*selected < app.bookmarks().len() || app.bookmarks().is_empty());
         }
+        .with_operation_started()
+        .with_operation_finished();
+        assert!(app.request_quit().should_quit());
     }
 
     #[test]
-    fn bookmark_mode_transitions() {
-        let (_tmp, app) = setup_app();
-        // Enter BookmarkAdd mode
-        let app = app.with_mode(AppMode::BookmarkAdd(String::new()));
-        assert!(matches!(app.mode(), AppMode::BookmarkAdd(_)));
+    fn duplicate_in_place_selects_the_copy() {
+        let (tmp, app) = setup_app();
 
-        // Exit back to normal
-        let app = app.with_mode(AppMode::Normal);
-        assert!(matches!(app.mode(), AppMode::Normal));
+        let app = select(app, "alpha.txt").duplicate_in_place();
 
-        // Enter BookmarkList mode
-        let app = app.with_mode(AppMode::BookmarkList { selected: 0 });
-        assert!(matches!(app.mode(), AppMode::BookmarkList { .. }));
+        assert_eq!(
+            app.panel().selected_entry().unwrap().name(),
+            "alpha copy.txt"
+        );
+        assert_eq!(
+            fs::read_to_string(tmp.path().join("alpha copy.txt")).unwrap(),
+            "aaa"
+        );
+        assert_eq!(app.status_message(), Some("Duplicated as alpha copy.txt"));
+        assert_eq!(app.status_level(), NotificationLevel::Success);
     }
 
     // =====================================================
-    // Recent files tests
+    // Operation log tests
     // =====================================================
 
+    fn select(app: App, name: &str) -> App {
+        let idx = app
+            .panel()
+            .entries()
+            .iter()
+            .position(|e| e.name() == name)
+            .unwrap();
+        let panel = app.panel().clone().with_cursor_to(idx);
+        app.with_panel(panel)
+    }
+
     #[test]
-    fn load_recent_files_enters_recent_mode() {
-        let (_tmp, app) = setup_app();
-        let app = app.load_recent_files();
-        assert!(matches!(app.mode(), AppMode::RecentFiles));
+    fn rename_confirm_renames_and_logs() {
+        let (tmp, app) = setup_app();
+        let app = select(app, "alpha.txt")
+            .with_mode(AppMode::Rename(String::new()))
+            .rename_push_char('z')
+            .rename_push_char('x')
+            .rename_pop_char()
+            .rename_confirm();
+        assert!(matches!(app.mode(), AppMode::Normal));
+        assert!(tmp.path().join("z").exists());
+        assert!(!tmp.path().join("alpha.txt").exists());
+        assert_eq!(app.panel().selected_entry().unwrap().name(), "z");
+
+        let entry = app.operation_log().entries().back().unwrap();
+        assert_eq!(entry.kind, OperationKind::Rename);
+        assert!(entry.result.is_ok());
+        assert_eq!(entry.targets[1].file_name().unwrap(), "z");
     }
 
     #[test]
-    fn load_recent_files_populates_results() {
-        let (_tmp, app) = setup_app();
-        let app = app.load_recent_files();
-        // setup_app creates alpha.txt, beta.txt, and gamma/inside.txt
-        assert!(!app.recent_results().is_empty());
+    fn rename_onto_existing_name_asks_first() {
+        let (tmp, app) = setup_app();
+        let app = select(app, "alpha.txt")
+            .with_mode(AppMode::Rename("beta.txt".to_string()))
+            .rename_confirm();
+        assert!(matches!(
+            app.mode(),
+            AppMode::Confirm(ConfirmAction::RenameOverwrite { name, .. }) if name == "beta.txt"
+        ));
+        assert_eq!(
+            fs::read_to_string(tmp.path().join("beta.txt")).unwrap(),
+            "bb"
+        );
+        assert!(app.operation_log().entries().is_empty());
+
+        let AppMode::Confirm(ConfirmAction::RenameOverwrite { src, name }) = app.mode().clone()
+        else {
+            unreachable!()
+        };
+        let app = app.rename_overwrite(src, &name);
+        assert!(matches!(app.mode(), AppMode::Normal));
+        assert!(!tmp.path().join("alpha.txt").exists());
+        assert_eq!(
+            fs::read_to_string(tmp.path().join("beta.txt")).unwrap(),
+            "aaa"
+        );
+        assert_eq!(app.status_message(), Some("Renamed to beta.txt"));
     }
 
     #[test]
-    fn load_recent_files_resets_selected() {
-        let (_tmp, app) = setup_app();
-        let app = app.load_recent_files();
-        assert_eq!(app.recent_selected(), 0);
+    fn rename_without_confirm_overwrite_replaces_directly() {
+        let (tmp, app) = setup_app();
+        let app = App {
+            confirm_overwrite: false,
+            ..app
+        };
+        let app = select(app, "alpha.txt")
+            .with_mode(AppMode::Rename("beta.txt".to_string()))
+            .rename_confirm();
+        assert!(matches!(app.mode(), AppMode::Normal));
+        assert_eq!(
+            fs::read_to_string(tmp.path().join("beta.txt")).unwrap(),
+            "aaa"
+        );
     }
 
     #[test]
-    fn recent_move_down_increments_selected() {
-        let (_tmp, app) = setup_app();
-        let app = app.load_recent_files();
-        if app.recent_results().len() > 1 {
-            let app = app.recent_move_down();
-            assert_eq!(app.recent_selected(), 1);
-        }
+    fn rename_and_delete_append_typed_entries() {
+        let (tmp, app) = setup_app();
+        let app = select(app, "alpha.txt")
+            .with_mode(AppMode::Rename("renamed.txt".to_string()))
+            .rename_confirm();
+        let app = app.delete_paths(&[tmp.path().join("beta.txt")]);
+
+        assert!(!tmp.path().join("beta.txt").exists());
+        assert_eq!(app.status_message(), Some("Deleted 1 item(s)"));
+        assert_eq!(app.status_level(), NotificationLevel::Success);
+        let kinds: Vec<OperationKind> = app
+            .operation_log()
+            .entries()
+            .iter()
+            .map(|e| e.kind)
+            .collect();
+        assert_eq!(kinds, vec![OperationKind::Rename, OperationKind::Delete]);
+        assert!(app
+            .operation_log()
+            .entries()
+            .iter()
+            .all(|e| e.result.is_ok()));
     }
 
     #[test]
-    fn recent_move_up_decrements_selected() {
-        let (_tmp, app) = setup_app();
-        let app = app.load_recent_files();
-        if app.recent_results().len() > 1 {
-            let app = app.recent_move_down();
-            assert_eq!(app.recent_selected(), 1);
-            let app = app.recent_move_up();
-            assert_eq!(app.recent_selected(), 0);
-        }
+    fn failed_delete_logs_error() {
+        let (tmp, app) = setup_app();
+        let app = app.delete_paths(&[tmp.path().join("missing.txt")]);
+        let entry = app.operation_log().entries().back().unwrap();
+        assert_eq!(entry.kind, OperationKind::Delete);
+        assert!(entry.result.is_err());
+        assert!(app.status_message().unwrap().starts_with("Delete failed"));
+        assert_eq!(app.status_level(), NotificationLevel::Error);
     }
 
     #[test]
-    fn recent_move_up_at_zero_stays_zero() {
-        let (_tmp, app) = setup_app();
-        let app = app.load_recent_files();
-        let app = app.recent_move_up();
-        assert_eq!(app.recent_selected(), 0);
+    fn operation_log_cap_evicts_oldest() {
+        let (tmp, app) = setup_app();
+        let app = App {
+            operation_log: OperationLog::with_capacity(2),
+            ..app
+        };
+        let app = app
+            .delete_paths(&[tmp.path().join("alpha.txt")])
+            .delete_paths(&[tmp.path().join("beta.txt")])
+            .delete_paths(&[tmp.path().join("gamma")]);
+        let targets: Vec<&Path> = app
+            .operation_log()
+            .entries()
+            .iter()
+            .map(|e| e.targets[0].as_path())
+            .collect();
+        assert_eq!(
+            targets,
+            vec![tmp.path().join("beta.txt"), tmp.path().join("gamma")]
+        );
     }
 
     #[test]
-    fn recent_move_down_empty_results_stays_zero() {
-        let tmp = TempDir::new().unwrap();
-        let app = App::new(tmp.path()).unwrap();
-        let app = app.load_recent_files();
-        assert!(app.recent_results().is_empty());
-        let app = app.recent_move_down();
-        assert_eq!(app.recent_selected(), 0);
+    fn operation_log_scroll_is_clamped() {
+        let (tmp, app) = setup_app();
+        let app = app
+            .delete_paths(&[tmp.path().join("alpha.txt")])
+            .delete_paths(&[tmp.path().join("beta.txt")])
+            .with_mode(AppMode::OperationLog { scroll: 0 });
+        let app = app.operation_log_scroll_down().operation_log_scroll_down();
+        assert!(matches!(app.mode(), AppMode::OperationLog { scroll: 1 }));
+        let app = app.operation_log_scroll_up().operation_log_scroll_up();
+        assert!(matches!(app.mode(), AppMode::OperationLog { scroll: 0 }));
     }
 
-    #[test]
-    fn recent_confirm_exits_to_normal() {
-        let (_tmp, app) = setup_app();
-        let app = app.load_recent_files();
-        let app = app.recent_confirm();
-        assert!(matches!(app.mode(), AppMode::Normal));
-        assert!(app.recent_results().is_empty());
-        assert_eq!(app.recent_selected(), 0);
-    }
+    // =====================================================
+    // Terminal selection tests
+    // =====================================================
+
+    use crate::terminal_emu::selection::Selection;
 
     #[test]
-    fn recent_confirm_navigates_to_parent_dir() {
+    fn terminal_selection_moved_updates_cursor() {
         let (_tmp, app) = setup_app();
-        let app = app.load_recent_files();
-        // Find inside.txt (nested file) in results
-        let nested_idx = app
-            .recent_results()
-            .iter()
-            .position(|e| e.name() == "inside.txt");
-        if let Some(idx) = nested_idx {
-            let mut app = app;
-            for _ in 0..idx {
-                app = app.recent_move_down();
+        let app = app.with_mode(AppMode::TerminalSelect(Selection::new((1, 1))));
+        let app = app.terminal_selection_moved(1, 3, 10, 40);
+        match app.mode() {
+            AppMode::TerminalSelect(sel) => {
+                assert_eq!(sel.anchor, (1, 1));
+                assert_eq!(sel.cursor, (2, 4));
             }
-            let app = app.recent_confirm();
-            assert!(matches!(app.mode(), AppMode::Normal));
-            // Should have navigated into gamma dir
-            assert!(app.panel().current_dir().ends_with("gamma"));
+            other => panic!("unexpected mode {other:?}"),
         }
     }
 
     #[test]
-    fn recent_confirm_empty_results_returns_to_normal() {
-        let tmp = TempDir::new().unwrap();
-        let app = App::new(tmp.path()).unwrap();
-        let app = app.load_recent_files();
-        let app = app.recent_confirm();
-        assert!(matches!(app.mode(), AppMode::Normal));
+    fn terminal_selection_restarted_moves_anchor() {
+        let (_tmp, app) = setup_app();
+        let app = app
+            .with_mode(AppMode::TerminalSelect(Selection::new((0, 0))))
+            .terminal_selection_moved(2, 2, 10, 40)
+            .terminal_selection_restarted();
+        assert!(matches!(
+            app.mode(),
+            AppMode::TerminalSelect(sel) if *sel == Selection::new((2, 2))
+        ));
     }
 
     #[test]
-    fn recent_mode_transition() {
+    fn terminal_selection_moved_outside_select_mode_is_noop() {
         let (_tmp, app) = setup_app();
-        let app = app.with_mode(AppMode::RecentFiles);
-        assert!(matches!(app.mode(), AppMode::RecentFiles));
-        let app = app.with_mode(AppMode::Normal);
+        let app = app.terminal_selection_moved(1, 1, 10, 40);
         assert!(matches!(app.mode(), AppMode::Normal));
     }
 
     // =====================================================
-    // Duplicate files tests (cache-based)
+    // Terminal cwd follow tests
     // =====================================================
 
-    use trefm_core::{CachedDuplicateGroup, CachedFileInfo, DuplicateCache};
-
-    fn sample_duplicate_cache() -> DuplicateCache {
-        DuplicateCache {
-            groups: vec![CachedDuplicateGroup {
-                size: 17,
-                hash: "abc".to_string(),
-                files: vec![
-                    CachedFileInfo {
-                        path: std::path::PathBuf::from("/tmp/file_a.txt"),
-                        name: "file_a.txt".to_string(),
-                        size: 17,
-                    },
-                    CachedFileInfo {
-                        path: std::path::PathBuf::from("/tmp/file_b.txt"),
-                        name: "file_b.txt".to_string(),
-                        size: 17,
-                    },
-                ],
-            }],
-            scanned_at: None,
-            scan_root: None,
-        }
-    }
-
     #[test]
-    fn show_duplicate_files_enters_mode() {
-        let (_tmp, app) = setup_app();
-        let app = app.with_duplicate_cache(sample_duplicate_cache());
-        let app = app.show_duplicate_files();
-        assert!(matches!(app.mode(), AppMode::DuplicateFiles));
-        assert_eq!(app.duplicate_selected(), 0);
+    fn follow_terminal_cwd_navigates_panel() {
+        let (tmp, app) = setup_app();
+        let gamma = tmp.path().join("gamma");
+        let app = app.follow_terminal_cwd(&gamma);
+        assert_eq!(app.panel().current_dir(), gamma.canonicalize().unwrap());
+        assert!(app
+            .panel()
+            .entries()
+            .iter()
+            .any(|e| e.name() == "inside.txt"));
     }
 
     #[test]
-    fn show_duplicate_files_shows_cached_results() {
-        let (_tmp, app) = setup_app();
-        let app = app.with_duplicate_cache(sample_duplicate_cache());
-        let app = app.show_duplicate_files();
-        assert!(!app.duplicate_results().is_empty());
-        assert_eq!(app.duplicate_results()[0].files.len(), 2);
+    fn follow_terminal_cwd_same_dir_keeps_cursor() {
+        let (tmp, app) = setup_app();
+        let panel = app.panel().clone().with_cursor_to(2);
+        let app = app.with_panel(panel);
+        let app = app.follow_terminal_cwd(tmp.path());
+        assert_eq!(app.panel().selected_index(), 2);
     }
 
     #[test]
-    fn duplicate_move_down_increments() {
-        let (_tmp, app) = setup_app();
-        let app = app
-            .with_duplicate_cache(sample_duplicate_cache())
-            .show_duplicate_files();
-        let app = app.duplicate_move_down();
-        assert_eq!(app.duplicate_selected(), 1);
+    fn follow_terminal_cwd_ignores_missing_dir() {
+        let (tmp, app) = setup_app();
+        let before = app.panel().current_dir().to_path_buf();
+        let app = app.follow_terminal_cwd(&tmp.path().join("nope"));
+        assert_eq!(app.panel().current_dir(), before);
     }
 
+    // =====================================================
+    // Run command tests
+    // =====================================================
+
     #[test]
-    fn duplicate_move_up_decrements() {
+    fn run_command_argv_uses_selected_path() {
         let (_tmp, app) = setup_app();
-        let app = app
-            .with_duplicate_cache(sample_duplicate_cache())
-            .show_duplicate_files();
-        let app = app.duplicate_move_down();
-        assert_eq!(app.duplicate_selected(), 1);
-        let app = app.duplicate_move_up();
-        assert_eq!(app.duplicate_selected(), 0);
+        let selected = app.panel().selected_entry().unwrap().path().to_path_buf();
+        let argv = app.run_command_argv("wc -l {}").unwrap();
+        assert_eq!(argv, ["wc", "-l", &selected.to_string_lossy()]);
     }
 
     #[test]
-    fn duplicate_move_up_at_zero_stays() {
+    fn run_command_argv_rejects_empty_template() {
         let (_tmp, app) = setup_app();
-        let app = app
-            .with_duplicate_cache(sample_duplicate_cache())
-            .show_duplicate_files();
-        let app = app.duplicate_move_up();
-        assert_eq!(app.duplicate_selected(), 0);
+        let err = app.run_command_argv("  ").unwrap_err();
+        assert!(err.contains("empty command"));
     }
 
+    #[cfg(unix)]
     #[test]
-    fn duplicate_move_down_empty_stays() {
+    fn request_execute_refuses_non_executable_file() {
         let (_tmp, app) = setup_app();
-        let app = app.show_duplicate_files();
-        assert!(app.duplicate_results().is_empty());
-        let app = app.duplicate_move_down();
-        assert_eq!(app.duplicate_selected(), 0);
-    }
+        let app = app.handle_command(Command::CursorDown);
+        assert_eq!(app.panel().selected_entry().unwrap().name(), "alpha.txt");
 
-    #[test]
-    fn duplicate_confirm_exits_to_normal() {
-        let (tmp, app) = setup_app();
-        let cache = DuplicateCache {
-            groups: vec![CachedDuplicateGroup {
-                size: 3,
-                hash: "h".to_string(),
-                files: vec![
-                    CachedFileInfo {
-                        path: tmp.path().join("alpha.txt"),
-                        name: "alpha.txt".to_string(),
-                        size: 3,
-                    },
-                    CachedFileInfo {
-                        path: tmp.path().join("beta.txt"),
-                        name: "beta.txt".to_string(),
-                        size: 3,
-                    },
-                ],
-            }],
-            scanned_at: None,
-            scan_root: None,
-        };
-        let app = app.with_duplicate_cache(cache).show_duplicate_files();
-        let app = app.duplicate_confirm();
+        let app = app.request_execute();
         assert!(matches!(app.mode(), AppMode::Normal));
-        assert_eq!(app.duplicate_selected(), 0);
+        assert_eq!(app.status_message(), Some("alpha.txt is not executable"));
     }
 
+    #[cfg(unix)]
     #[test]
-    fn duplicate_confirm_navigates_to_parent() {
+    fn request_execute_asks_before_running() {
+        use std::os::unix::fs::PermissionsExt;
         let (tmp, app) = setup_app();
-        let cache = DuplicateCache {
-            groups: vec![CachedDuplicateGroup {
-                size: 3,
-                hash: "h".to_string(),
-                files: vec![
-                    CachedFileInfo {
-                        path: tmp.path().join("alpha.txt"),
-                        name: "alpha.txt".to_string(),
-                        size: 3,
-                    },
-                    CachedFileInfo {
-                        path: tmp.path().join("beta.txt"),
-                        name: "beta.txt".to_string(),
-                        size: 3,
-                    },
-                ],
-            }],
-            scanned_at: None,
-            scan_root: None,
-        };
-        let app = app.with_duplicate_cache(cache).show_duplicate_files();
-        let app = app.duplicate_confirm();
-        assert!(matches!(app.mode(), AppMode::Normal));
-        assert_eq!(
-            app.panel().current_dir().canonicalize().unwrap(),
-            tmp.path().canonicalize().unwrap()
-        );
+        let script = tmp.path().join("aaa.sh");
+        fs::write(&script, "#!/bin/sh\necho hi\n").unwrap();
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+        let app = app.handle_refresh().handle_command(Command::CursorDown);
+
+        let app = app.request_execute();
+        match app.mode() {
+            AppMode::Confirm(ConfirmAction::Execute(path)) => {
+                assert_eq!(path.file_name().unwrap(), "aaa.sh");
+            }
+            other => panic!("unexpected mode: {other:?}"),
+        }
     }
 
     #[test]
-    fn duplicate_confirm_empty_results_returns_normal() {
+    fn command_output_single_line_goes_to_status() {
         let (_tmp, app) = setup_app();
-        let app = app.show_duplicate_files();
-        let app = app.duplicate_confirm();
+        let output = CommandOutput {
+            code: Some(0),
+            lines: vec!["3 alpha.txt".to_string()],
+        };
+        let app = app.with_command_output("wc -l {}".to_string(), output);
         assert!(matches!(app.mode(), AppMode::Normal));
+        assert_eq!(app.status_message(), Some("wc -l {}: 3 alpha.txt"));
     }
 
     #[test]
-    fn duplicate_mode_transition() {
+    fn pager_scroll_applies_to_log_viewer() {
         let (_tmp, app) = setup_app();
-        let app = app.with_mode(AppMode::DuplicateFiles);
-        assert!(matches!(app.mode(), AppMode::DuplicateFiles));
-        let app = app.with_mode(AppMode::Normal);
+        assert_eq!(app.pager_scroll(), None);
+        let app = app.with_pager_scroll(3);
         assert!(matches!(app.mode(), AppMode::Normal));
-    }
 
-    #[test]
-    fn with_duplicate_cache_replaces_cache() {
-        let (_tmp, app) = setup_app();
-        assert!(app.duplicate_cache().is_empty());
-        let app = app.with_duplicate_cache(sample_duplicate_cache());
-        assert!(!app.duplicate_cache().is_empty());
-        assert_eq!(app.duplicate_cache().groups.len(), 1);
-    }
+        let app = app
+            .with_mode(AppMode::LogViewer { scroll: 0 })
+            .with_log_lines("Log".to_string(), vec!["a".to_string(), "b".to_string()], 1);
+        assert_eq!(app.pager_scroll(), Some(1));
+        assert_eq!(app.pager_title(), Some("Log"));
+        assert!(app.pager_file().is_none());
 
-    #[test]
-    fn with_scan_status_updates_status() {
-        let (_tmp, app) = setup_app();
-        assert_eq!(app.scan_status(), &ScanStatus::Idle);
-        let app = app.with_scan_status(ScanStatus::Scanning);
-        assert_eq!(app.scan_status(), &ScanStatus::Scanning);
+        let app = app.with_pager_scroll(0);
+        assert!(matches!(app.mode(), AppMode::LogViewer { scroll: 0 }));
+        assert_eq!(app.pager_lines(), ["a", "b"]);
     }
 
     #[test]
-    fn duplicate_delete_selected_enters_confirm() {
+    fn command_output_many_lines_opens_pager() {
         let (_tmp, app) = setup_app();
-        let app = app
-            .with_duplicate_cache(sample_duplicate_cache())
-            .show_duplicate_files();
-        let app = app.duplicate_delete_selected();
-        match app.mode() {
-            AppMode::Confirm(ConfirmAction::DeleteDuplicate(path)) => {
-                assert!(path.to_str().unwrap().contains("file_a.txt"));
-            }
-            other => panic!("Expected Confirm(DeleteDuplicate), got {other:?}"),
-        }
+        let output = CommandOutput {
+            code: Some(1),
+            lines: vec!["a".to_string(), "b".to_string()],
+        };
+        let app = app.with_command_output("cat {}".to_string(), output);
+        assert!(matches!(app.mode(), AppMode::Pager { scroll: 0 }));
+        assert_eq!(app.pager_lines(), ["a", "b"]);
+        assert_eq!(app.pager_title(), Some("$ cat {} [exit 1]"));
+        assert!(app.pager_file().is_none());
     }
 
     #[test]
-    fn duplicate_delete_selected_empty_is_noop() {
+    fn command_output_does_not_interrupt_other_modes() {
         let (_tmp, app) = setup_app();
-        let app = app.show_duplicate_files();
-        let app = app.duplicate_delete_selected();
-        assert!(matches!(app.mode(), AppMode::DuplicateFiles));
+        let app = app.with_mode(AppMode::Help);
+        let output = CommandOutput {
+            code: Some(0),
+            lines: vec!["a".to_string(), "b".to_string()],
+        };
+        let app = app.with_command_output("ls".to_string(), output);
+        assert!(matches!(app.mode(), AppMode::Help));
+        assert_eq!(app.status_message(), Some("ls: 2 lines of output"));
     }
 }
@@ -1,24 +1,90 @@
-//! Background duplicate file scanning and cache management.
+//! Background duplicate file scanning, cache management, content search and
+//! one-shot commands.
 //!
-//! Provides asynchronous scanning via [`spawn_duplicate_scanner`] and
-//! periodic re-scanning via [`spawn_periodic_scanner`]. Results are
-//! communicated to the main event loop through an unbounded mpsc channel.
+//! Provides asynchronous scanning via [`spawn_duplicate_scanner`],
+//! periodic re-scanning via [`spawn_periodic_scanner`], one-off scans of a
+//! single directory via [`spawn_local_duplicate_scan`], recursive
+//! content search via [`spawn_content_search`], and commands run on a file
+//! via [`spawn_run_command`]. Results are communicated
+//! to the main event loop through unbounded mpsc channels.
 
-use std::collections::HashSet;
-use std::path::PathBuf;
-use std::time::Duration;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use tokio::sync::mpsc::UnboundedSender;
-use trefm_core::DuplicateCache;
+use trefm_core::config::settings::ScannerConfig;
+use trefm_core::fs::command::{run_command, CommandOutput};
+use trefm_core::fs::search::{
+    search_file_contents, ContentSearchOptions, ContentSearchResult, SearchProgress,
+};
+use trefm_core::fs::{scan_duplicates, DuplicateScanOptions, MountTable};
+use trefm_core::{CoreResult, DuplicateCache};
 
 /// Messages sent from background scan tasks to the main event loop.
 pub enum ScanMessage {
     ScanStarted,
     ScanComplete(DuplicateCache),
     ScanError(String),
-    ValidationComplete(DuplicateCache),
+    /// Generation `base` of the cache, with vanished files dropped.
+    ValidationComplete {
+        base: u64,
+        cache: DuplicateCache,
+    },
+    /// Generation `base` of the cache, updated for the watcher-reported
+    /// `changed` paths.
+    IncrementalComplete {
+        base: u64,
+        changed: Vec<PathBuf>,
+        cache: DuplicateCache,
+    },
+    /// A directory-local scan finished. Its result is never saved.
+    LocalScanComplete {
+        id: u64,
+        cache: DuplicateCache,
+    },
+    LocalScanError {
+        id: u64,
+        message: String,
+    },
 }
 
+/// Messages sent from a background content search to the main event loop.
+///
+/// Every message carries the id of the search that produced it so results
+/// of a superseded search can be discarded.
+pub enum ContentSearchMessage {
+    Progress {
+        id: u64,
+        progress: SearchProgress,
+    },
+    Complete {
+        id: u64,
+        result: ContentSearchResult,
+    },
+    Error {
+        id: u64,
+        message: String,
+    },
+}
+
+/// Result of a command started with [`spawn_run_command`]. `command` is the
+/// template as typed, for display.
+pub enum CommandMessage {
+    Finished {
+        command: String,
+        output: CommandOutput,
+    },
+    Failed {
+        command: String,
+        message: String,
+    },
+}
+
+/// Minimum time between two progress messages of a content search.
+const CONTENT_SEARCH_PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
 /// Current status of the background scanner.
 #[derive(Debug, Clone, PartialEq)]
 pub enum ScanStatus {
@@ -35,30 +101,22 @@ pub fn default_scan_root() -> PathBuf {
 
 /// Returns the path to the duplicate cache file.
 pub fn cache_path() -> PathBuf {
-    let config_dir = std::env::var("HOME")
-        .map(PathBuf::from)
-        .unwrap_or_else(|_| PathBuf::from("/"))
-        .join(".config")
-        .join("trefm");
-    config_dir.join("duplicates.json")
-}
-
-/// Returns the set of directory names to exclude from scanning.
-pub fn excluded_dirs() -> HashSet<&'static str> {
-    [
-        ".git",
-        "node_modules",
-        "target",
-        ".build",
-        "Library",
-        ".Trash",
-        ".cache",
-        "__pycache__",
-        "venv",
-        ".venv",
-    ]
-    .into_iter()
-    .collect()
+    trefm_core::paths::cache_dir().join("duplicates.json")
+}
+
+/// Returns the path to the duplicate ignore list.
+pub fn ignore_list_path() -> PathBuf {
+    trefm_core::paths::data_dir().join("dup-ignore.toml")
+}
+
+/// Network mount points to leave out of a scan, read fresh each time so
+/// shares mounted after startup are skipped too.
+fn excluded_roots(skip_network_mounts: bool) -> Vec<PathBuf> {
+    if skip_network_mounts {
+        MountTable::load().network_mount_points()
+    } else {
+        Vec::new()
+    }
 }
 
 /// Spawns a background duplicate file scanner.
@@ -66,14 +124,15 @@ pub fn excluded_dirs() -> HashSet<&'static str> {
 /// Sends [`ScanMessage::ScanStarted`] immediately, then runs the scan
 /// in a blocking thread and sends the result as [`ScanMessage::ScanComplete`]
 /// or [`ScanMessage::ScanError`].
-pub fn spawn_duplicate_scanner(scan_root: PathBuf, tx: UnboundedSender<ScanMessage>) {
+pub fn spawn_duplicate_scanner(
+    scan_root: PathBuf,
+    scanner: ScannerConfig,
+    tx: UnboundedSender<ScanMessage>,
+) {
     tokio::task::spawn_blocking(move || {
         let _ = tx.send(ScanMessage::ScanStarted);
-        let exclusions = excluded_dirs();
-        match trefm_core::find_duplicate_files_with_exclusions(&scan_root, 20, true, &exclusions) {
-            Ok(groups) => {
-                let mut cache = DuplicateCache::from(groups);
-                cache.scan_root = Some(scan_root);
+        match scan_under(&scan_root, &scanner) {
+            Ok(cache) => {
                 let _ = tx.send(ScanMessage::ScanComplete(cache));
             }
             Err(e) => {
@@ -83,11 +142,70 @@ pub fn spawn_duplicate_scanner(scan_root: PathBuf, tx: UnboundedSender<ScanMessa
     });
 }
 
-/// Spawns a background cache validator that checks whether cached files still exist.
-pub fn spawn_cache_validator(cache: DuplicateCache, tx: UnboundedSender<ScanMessage>) {
+/// Scans the subtree under `root` for duplicates, skipping the
+/// directories named by `scanner` and, if it says so, network mounts.
+pub fn scan_under(root: &Path, scanner: &ScannerConfig) -> CoreResult<DuplicateCache> {
+    let exclusions = scanner.exclusion_set();
+    scan_duplicates(
+        root,
+        20,
+        true,
+        &exclusions,
+        &DuplicateScanOptions {
+            excluded_roots: excluded_roots(scanner.skip_network_mounts),
+            algo: scanner.hash_algo,
+            threads: scanner.threads,
+            min_size: scanner.min_dup_size,
+            max_memory: scanner.memory_budget(),
+        },
+    )
+}
+
+/// Spawns a one-off duplicate scan of `root`, reported as exactly one
+/// [`ScanMessage::LocalScanComplete`] or [`ScanMessage::LocalScanError`]
+/// carrying `id`. The caller keeps the result out of the cache file.
+pub fn spawn_local_duplicate_scan(
+    id: u64,
+    root: PathBuf,
+    scanner: ScannerConfig,
+    tx: UnboundedSender<ScanMessage>,
+) {
     tokio::task::spawn_blocking(move || {
-        let validated = cache.validate();
-        let _ = tx.send(ScanMessage::ValidationComplete(validated));
+        let msg = match scan_under(&root, &scanner) {
+            Ok(cache) => ScanMessage::LocalScanComplete { id, cache },
+            Err(e) => ScanMessage::LocalScanError {
+                id,
+                message: format!("{e}"),
+            },
+        };
+        let _ = tx.send(msg);
+    });
+}
+
+/// Spawns a background cache validator that checks whether cached files
+/// still exist. `base` is the cache's generation, echoed in the result.
+pub fn spawn_cache_validator(cache: DuplicateCache, base: u64, tx: UnboundedSender<ScanMessage>) {
+    tokio::task::spawn_blocking(move || {
+        let cache = cache.validate();
+        let _ = tx.send(ScanMessage::ValidationComplete { base, cache });
+    });
+}
+
+/// Spawns a background incremental update of the cache for `changed` paths.
+/// `base` is the cache's generation, echoed in the result.
+pub fn spawn_incremental_update(
+    cache: DuplicateCache,
+    base: u64,
+    changed: Vec<PathBuf>,
+    tx: UnboundedSender<ScanMessage>,
+) {
+    tokio::task::spawn_blocking(move || {
+        let cache = cache.update_incremental(&changed);
+        let _ = tx.send(ScanMessage::IncrementalComplete {
+            base,
+            changed,
+            cache,
+        });
     });
 }
 
@@ -95,6 +213,7 @@ pub fn spawn_cache_validator(cache: DuplicateCache, tx: UnboundedSender<ScanMess
 pub fn spawn_periodic_scanner(
     scan_root: PathBuf,
     interval: Duration,
+    scanner: ScannerConfig,
     tx: UnboundedSender<ScanMessage>,
 ) {
     tokio::spawn(async move {
@@ -105,14 +224,11 @@ pub fn spawn_periodic_scanner(
             ticker.tick().await;
             let root = scan_root.clone();
             let sender = tx.clone();
+            let scanner = scanner.clone();
             tokio::task::spawn_blocking(move || {
                 let _ = sender.send(ScanMessage::ScanStarted);
-                let exclusions = excluded_dirs();
-                match trefm_core::find_duplicate_files_with_exclusions(&root, 20, true, &exclusions)
-                {
-                    Ok(groups) => {
-                        let mut cache = DuplicateCache::from(groups);
-                        cache.scan_root = Some(root);
+                match scan_under(&root, &scanner) {
+                    Ok(cache) => {
                         let _ = sender.send(ScanMessage::ScanComplete(cache));
                     }
                     Err(e) => {
@@ -124,6 +240,59 @@ pub fn spawn_periodic_scanner(
     });
 }
 
+/// Spawns a recursive content search under `root`.
+///
+/// Progress is throttled to one [`ContentSearchMessage::Progress`] per
+/// 100 ms. Setting `cancel` stops the walk; the partial result still
+/// arrives as [`ContentSearchMessage::Complete`].
+pub fn spawn_content_search(
+    id: u64,
+    root: PathBuf,
+    query: String,
+    options: ContentSearchOptions,
+    cancel: Arc<AtomicBool>,
+    tx: UnboundedSender<ContentSearchMessage>,
+) {
+    tokio::task::spawn_blocking(move || {
+        let mut last_sent = Instant::now();
+        let progress_tx = tx.clone();
+        let outcome = search_file_contents(&root, &query, &options, &cancel, |progress| {
+            if last_sent.elapsed() >= CONTENT_SEARCH_PROGRESS_INTERVAL {
+                last_sent = Instant::now();
+                let _ = progress_tx.send(ContentSearchMessage::Progress { id, progress });
+            }
+        });
+        let msg = match outcome {
+            Ok(result) => ContentSearchMessage::Complete { id, result },
+            Err(e) => ContentSearchMessage::Error {
+                id,
+                message: format!("{e}"),
+            },
+        };
+        let _ = tx.send(msg);
+    });
+}
+
+/// Runs `argv` in `cwd` on a blocking thread and reports the captured
+/// output as a single [`CommandMessage`].
+pub fn spawn_run_command(
+    command: String,
+    argv: Vec<String>,
+    cwd: PathBuf,
+    tx: UnboundedSender<CommandMessage>,
+) {
+    tokio::task::spawn_blocking(move || {
+        let msg = match run_command(&argv, &cwd) {
+            Ok(output) => CommandMessage::Finished { command, output },
+            Err(e) => CommandMessage::Failed {
+                command,
+                message: format!("{e}"),
+            },
+        };
+        let _ = tx.send(msg);
+    });
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -146,8 +315,9 @@ mod tests {
     }
 
     #[test]
-    fn excluded_dirs_contains_expected() {
-        let dirs = excluded_dirs();
+    fn default_scanner_exclusions_contain_expected() {
+        let scanner = ScannerConfig::default();
+        let dirs = scanner.exclusion_set();
         assert!(dirs.contains(".git"));
         assert!(dirs.contains("node_modules"));
         assert!(dirs.contains("target"));
@@ -156,6 +326,52 @@ mod tests {
         assert!(dirs.contains("__pycache__"));
     }
 
+    #[test]
+    fn scan_under_finds_groups_under_root_only() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let root = tmp.path().join("here");
+        std::fs::create_dir_all(root.join("sub")).unwrap();
+        std::fs::write(root.join("a.txt"), "same content").unwrap();
+        std::fs::write(root.join("sub/b.txt"), "same content").unwrap();
+        std::fs::write(root.join("c.txt"), "different").unwrap();
+        std::fs::write(tmp.path().join("outside.txt"), "same content").unwrap();
+        let global_before = std::fs::read(cache_path()).ok();
+
+        let cache = scan_under(&root, &ScannerConfig::default()).unwrap();
+
+        assert_eq!(cache.groups.len(), 1);
+        let mut names: Vec<&str> = cache.groups[0]
+            .files
+            .iter()
+            .map(|f| f.name.as_str())
+            .collect();
+        names.sort_unstable();
+        assert_eq!(names, ["a.txt", "b.txt"]);
+        assert_eq!(cache.scan_root.as_deref(), Some(root.as_path()));
+        assert_eq!(std::fs::read(cache_path()).ok(), global_before);
+    }
+
+    #[test]
+    fn scan_under_skips_configured_dirs() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir_all(tmp.path().join("dist")).unwrap();
+        std::fs::write(tmp.path().join("a.txt"), "same content").unwrap();
+        std::fs::write(tmp.path().join("dist/a.txt"), "same content").unwrap();
+        let scanner = ScannerConfig {
+            excluded_dirs: vec!["dist".to_string()],
+            ..ScannerConfig::default()
+        };
+
+        assert_eq!(
+            scan_under(tmp.path(), &ScannerConfig::default())
+                .unwrap()
+                .groups
+                .len(),
+            1
+        );
+        assert!(scan_under(tmp.path(), &scanner).unwrap().groups.is_empty());
+    }
+
     #[test]
     fn scan_status_eq() {
         assert_eq!(ScanStatus::Idle, ScanStatus::Idle);
@@ -3,7 +3,9 @@ use trefm_core::action::Action;
 use trefm_core::config::keymap::Keymap;
 use trefm_core::event::Command;
 
-use crate::app::AppMode;
+use trefm_core::fs::conflict::ConflictChoice;
+
+use crate::app::{AppMode, ConfirmAction};
 
 /// Actions that can result from a key press.
 #[derive(Debug)]
@@ -20,10 +22,56 @@ pub enum InputAction {
     CursorBottom,
     /// Navigate to home directory (~).
     GoHome,
+    /// Go to the real location of the selected symlink (J).
+    FollowSymlink,
     /// Request delete of currently selected file(s).
     RequestDelete,
+    /// Copy the selected entry next to itself (N).
+    DuplicateInPlace,
+    /// Ask to run the selected executable file.
+    RequestExecute,
+    /// Copy the selected directory's tree to the clipboard (E).
+    CopyTree,
+    /// Copy the selected path relative to the git repository root (K).
+    CopyGitPath,
     /// User confirmed the pending action.
     ConfirmApproved,
+    /// Put the selected entry on the clipboard.
+    Yank,
+    /// Paste the clipboard into the current directory.
+    Paste,
+    /// Answer the pending paste conflict.
+    PasteResolve(ConflictChoice),
+    /// Copy the selected entry into the other panel's directory.
+    CopyToOtherPanel,
+    /// Move the selected entry into the other panel's directory.
+    MoveToOtherPanel,
+    /// Exchange the left and right panels.
+    SwapPanels,
+    /// Open the active panel's directory in the other panel.
+    SyncPanels,
+    /// Diff the selected file against the same-named file in the other panel.
+    DiffOtherPanel,
+    /// Step the active panel's file category filter.
+    CycleCategoryFilter,
+    /// Reverse the current sort direction.
+    ToggleSortDirection,
+    /// Reverse the displayed entries without re-sorting.
+    ReverseListing,
+    /// Toggle listing only directories in the active panel.
+    ToggleDirsOnly,
+    /// Pin or unpin the selected entry at the top of its directory.
+    TogglePin,
+    /// Start typing a name filter for the active panel.
+    StartFilter,
+    /// Append a character to the name filter.
+    FilterChar(char),
+    /// Remove the last character from the name filter.
+    FilterBackspace,
+    /// Stop typing and keep the name filter.
+    FilterAccept,
+    /// Drop the name filter.
+    FilterClear,
     /// Append a character to the search query.
     SearchChar(char),
     /// Remove the last character from the search query.
@@ -54,6 +102,16 @@ pub enum InputAction {
     RecentUp,
     /// Confirm the selected recent file.
     RecentConfirm,
+    /// Append a character to the recent directories query.
+    RecentDirsChar(char),
+    /// Remove the last character from the recent directories query.
+    RecentDirsBackspace,
+    /// Move selection down in the recent directories list.
+    RecentDirsDown,
+    /// Move selection up in the recent directories list.
+    RecentDirsUp,
+    /// Go to the selected recent directory.
+    RecentDirsConfirm,
     /// Move selection down in duplicate files list.
     DuplicateDown,
     /// Move selection up in duplicate files list.
@@ -62,6 +120,52 @@ pub enum InputAction {
     DuplicateConfirm,
     /// Delete the selected duplicate file.
     DuplicateDelete,
+    /// Add the selected file's group to the duplicate ignore list.
+    DuplicateIgnore,
+    /// Cycle the duplicate group sort order.
+    DuplicateSort,
+    /// Scan the active panel's directory for duplicates.
+    ScanDuplicatesHere,
+    /// Append a character to the content search query.
+    ContentSearchChar(char),
+    /// Remove the last character from the content search query.
+    ContentSearchBackspace,
+    /// Start searching file contents for the typed query.
+    ContentSearchStart,
+    /// Move selection down in content search results.
+    ContentSearchDown,
+    /// Move selection up in content search results.
+    ContentSearchUp,
+    /// Jump to the selected content search match.
+    ContentSearchConfirm,
+    /// Stop the running content search, or close the results when done.
+    ContentSearchStop,
+    /// Move selection down in git changes list.
+    GitChangesDown,
+    /// Move selection up in git changes list.
+    GitChangesUp,
+    /// Jump to the selected changed file.
+    GitChangesConfirm,
+    /// Append a character to the command template.
+    RunCommandChar(char),
+    /// Remove the last character from the command template.
+    RunCommandBackspace,
+    /// Run the typed command on the selected file.
+    RunCommandSubmit,
+    /// Scroll the operation log down.
+    OperationLogDown,
+    /// Scroll the operation log up.
+    OperationLogUp,
+    /// Scroll the notifications down.
+    NotificationsDown,
+    /// Scroll the notifications up.
+    NotificationsUp,
+    /// Type a character into the new name in rename mode.
+    RenameChar(char),
+    /// Delete last character of the new name.
+    RenameBackspace,
+    /// Rename the selected entry to the typed name.
+    RenameConfirm,
     /// Move down in sort select popup.
     SortSelectDown,
     /// Move up in sort select popup.
@@ -124,14 +228,40 @@ pub enum InputAction {
     TerminalToggle,
     /// Unfocus the terminal (return to Normal mode).
     TerminalUnfocus,
+    /// Scroll the terminal view back through its history.
+    TerminalScrollUp,
+    /// Scroll the terminal view towards the live screen.
+    TerminalScrollDown,
+    /// Start selecting terminal text at the terminal cursor.
+    TerminalSelectStart,
+    /// Move the terminal selection cursor by (rows, cols).
+    TerminalSelectMove(i32, i32),
+    /// Restart the terminal selection at its cursor.
+    TerminalSelectRestart,
+    /// Copy the selected terminal text to the clipboard.
+    TerminalSelectYank,
     /// Create a new tab.
     TabNew,
+    /// Open the selected directory in a new tab.
+    OpenInNewTab,
     /// Close current tab.
     TabClose,
+    /// Close every tab except the current one.
+    TabCloseOthers,
     /// Switch to next tab.
     TabNext,
     /// Switch to previous tab.
     TabPrev,
+    /// Type a character into the tab name in rename-tab mode.
+    RenameTabChar(char),
+    /// Delete the last character of the tab name.
+    RenameTabBackspace,
+    /// Set the typed tab name (empty resets to the directory name).
+    RenameTabConfirm,
+    /// Copy the active tab into the other panel's tab group.
+    TabCopyToOtherPanel,
+    /// Move the active tab into the other panel's tab group.
+    TabMoveToOtherPanel,
     /// Select a specific tab by index (0-based).
     TabSelect(usize),
     /// No action for this key.
@@ -165,18 +295,31 @@ pub fn handle_key(
     match mode {
         AppMode::Normal => handle_normal_key(key, state, keymap),
         AppMode::Search(_) => handle_search_key(key),
+        AppMode::Filter(_) => handle_filter_key(key),
         AppMode::Rename(_) => handle_rename_key(key),
+        AppMode::RenameTab(_) => handle_rename_tab_key(key),
+        AppMode::Confirm(ConfirmAction::PasteConflict(_)) => handle_paste_conflict_key(key),
         AppMode::Confirm(_) => handle_confirm_key(key),
         AppMode::Help => handle_help_key(key),
         AppMode::BookmarkAdd(_) => handle_bookmark_add_key(key),
         AppMode::BookmarkList { .. } => handle_bookmark_list_key(key),
         AppMode::RecentFiles => handle_recent_key(key),
+        AppMode::RecentDirs { .. } => handle_recent_dirs_key(key),
         AppMode::DuplicateFiles => handle_duplicate_key(key),
+        AppMode::ContentSearchInput(_) => handle_content_search_input_key(key),
+        AppMode::ContentSearch => handle_content_search_key(key),
+        AppMode::GitChanges => handle_git_changes_key(key),
+        AppMode::OperationLog { .. } => handle_operation_log_key(key),
+        AppMode::Notifications { .. } => handle_notifications_key(key),
+        AppMode::RunCommand(_) => handle_run_command_key(key),
         AppMode::SortSelect { .. } => handle_sort_select_key(key),
-        AppMode::Pager { .. } => handle_pager_key(key, state),
+        AppMode::Pager { .. } | AppMode::LogViewer { .. } | AppMode::Diff { .. } => {
+            handle_pager_key(key, state)
+        }
         AppMode::CommandPalette { .. } => handle_command_palette_key(key),
         AppMode::RemoteConnect => handle_remote_connect_key(key),
         AppMode::Terminal => handle_terminal_key(key),
+        AppMode::TerminalSelect(_) => handle_terminal_select_key(key),
     }
 }
 
@@ -192,18 +335,40 @@ fn action_to_input_action(action: Action) -> InputAction {
         Action::EnterDir | Action::Open => InputAction::Command(Command::Enter),
         Action::GoBack => InputAction::Command(Command::GoBack),
         Action::GoForward => InputAction::Command(Command::GoForward),
+        Action::FollowSymlink => InputAction::FollowSymlink,
         Action::Refresh => InputAction::Command(Command::Refresh),
-        Action::Copy => InputAction::None,  // TODO: implement yank
-        Action::Paste => InputAction::None, // TODO: implement paste
+        Action::Copy => InputAction::Yank,
+        Action::Paste => InputAction::Paste,
         Action::Delete => InputAction::RequestDelete,
+        Action::DuplicateInPlace => InputAction::DuplicateInPlace,
         Action::Rename => InputAction::EnterMode(AppMode::Rename(String::new())),
         Action::ToggleHidden => InputAction::Command(Command::ToggleHidden),
         Action::Search => InputAction::EnterMode(AppMode::Search(String::new())),
         Action::SortCycle => InputAction::EnterMode(AppMode::SortSelect { selected: 0 }),
+        Action::ToggleSortDirection => InputAction::ToggleSortDirection,
+        Action::ReverseListing => InputAction::ReverseListing,
+        Action::CategoryFilterCycle => InputAction::CycleCategoryFilter,
+        Action::ToggleDirsOnly => InputAction::ToggleDirsOnly,
+        Action::FilterPanel => InputAction::StartFilter,
+        Action::TogglePin => InputAction::TogglePin,
         Action::BookmarkAdd => InputAction::EnterMode(AppMode::BookmarkAdd(String::new())),
         Action::BookmarkGo => InputAction::EnterMode(AppMode::BookmarkList { selected: 0 }),
         Action::RecentFiles => InputAction::EnterMode(AppMode::RecentFiles),
+        Action::RecentDirs => InputAction::EnterMode(AppMode::RecentDirs {
+            query: String::new(),
+            selected: 0,
+        }),
         Action::DuplicateFiles => InputAction::EnterMode(AppMode::DuplicateFiles),
+        Action::DuplicateFilesHere => InputAction::ScanDuplicatesHere,
+        Action::ContentSearch => InputAction::EnterMode(AppMode::ContentSearchInput(String::new())),
+        Action::GitChanges => InputAction::EnterMode(AppMode::GitChanges),
+        Action::OperationLog => InputAction::EnterMode(AppMode::OperationLog { scroll: 0 }),
+        Action::Notifications => InputAction::EnterMode(AppMode::Notifications { scroll: 0 }),
+        Action::LogViewer => InputAction::EnterMode(AppMode::LogViewer { scroll: 0 }),
+        Action::RunCommand => InputAction::EnterMode(AppMode::RunCommand(String::new())),
+        Action::ExecuteFile => InputAction::RequestExecute,
+        Action::CopyTree => InputAction::CopyTree,
+        Action::CopyGitPath => InputAction::CopyGitPath,
         Action::Pager => InputAction::EnterPager,
         Action::EditFile => InputAction::EditFile,
         Action::Help => InputAction::EnterMode(AppMode::Help),
@@ -218,11 +383,21 @@ fn action_to_input_action(action: Action) -> InputAction {
         Action::PanelToggleDual => InputAction::PanelToggleDual,
         Action::PanelFocusLeft => InputAction::PanelFocus(0),
         Action::PanelFocusRight => InputAction::PanelFocus(1),
+        Action::CopyToOtherPanel => InputAction::CopyToOtherPanel,
+        Action::MoveToOtherPanel => InputAction::MoveToOtherPanel,
+        Action::SwapPanels => InputAction::SwapPanels,
+        Action::SyncPanels => InputAction::SyncPanels,
+        Action::DiffOtherPanel => InputAction::DiffOtherPanel,
         // Tab
         Action::TabNew => InputAction::TabNew,
+        Action::OpenInNewTab => InputAction::OpenInNewTab,
         Action::TabClose => InputAction::TabClose,
+        Action::TabCloseOthers => InputAction::TabCloseOthers,
         Action::TabNext => InputAction::TabNext,
         Action::TabPrev => InputAction::TabPrev,
+        Action::TabRename => InputAction::EnterMode(AppMode::RenameTab(String::new())),
+        Action::TabCopyToOtherPanel => InputAction::TabCopyToOtherPanel,
+        Action::TabMoveToOtherPanel => InputAction::TabMoveToOtherPanel,
         Action::TabSelect1 => InputAction::TabSelect(0),
         Action::TabSelect2 => InputAction::TabSelect(1),
         Action::TabSelect3 => InputAction::TabSelect(2),
@@ -283,12 +458,33 @@ fn handle_normal_key(
                 None => InputAction::None,
             }
         }
-        _ => InputAction::None,
+        // Named keys (Home, F5, ...) as the classic profile binds them
+        code => match named_key(code).and_then(|name| keymap.action_for_key(&name)) {
+            Some(action) => action_to_input_action(action),
+            None => InputAction::None,
+        },
     };
 
     (action, new_state)
 }
 
+/// The keymap name of a non-character key, e.g. `"Home"` or `"F5"`.
+fn named_key(code: KeyCode) -> Option<String> {
+    let name = match code {
+        KeyCode::Home => "Home",
+        KeyCode::End => "End",
+        KeyCode::PageUp => "PageUp",
+        KeyCode::PageDown => "PageDown",
+        KeyCode::Backspace => "Backspace",
+        KeyCode::Delete => "Delete",
+        KeyCode::Insert => "Insert",
+        KeyCode::Esc => "Esc",
+        KeyCode::F(n) => return Some(format!("F{n}")),
+        _ => return None,
+    };
+    Some(name.to_string())
+}
+
 fn handle_search_key(key: KeyEvent) -> (InputAction, InputState) {
     let new_state = InputState::new();
     let action = match key.code {
@@ -303,10 +499,39 @@ fn handle_search_key(key: KeyEvent) -> (InputAction, InputState) {
     (action, new_state)
 }
 
+fn handle_filter_key(key: KeyEvent) -> (InputAction, InputState) {
+    let new_state = InputState::new();
+    let action = match key.code {
+        KeyCode::Esc => InputAction::FilterClear,
+        KeyCode::Enter => InputAction::FilterAccept,
+        KeyCode::Backspace => InputAction::FilterBackspace,
+        KeyCode::Down => InputAction::Command(Command::CursorDown),
+        KeyCode::Up => InputAction::Command(Command::CursorUp),
+        KeyCode::Char(c) => InputAction::FilterChar(c),
+        _ => InputAction::None,
+    };
+    (action, new_state)
+}
+
 fn handle_rename_key(key: KeyEvent) -> (InputAction, InputState) {
     let new_state = InputState::new();
     let action = match key.code {
         KeyCode::Esc => InputAction::EnterMode(AppMode::Normal),
+        KeyCode::Enter => InputAction::RenameConfirm,
+        KeyCode::Backspace => InputAction::RenameBackspace,
+        KeyCode::Char(c) => InputAction::RenameChar(c),
+        _ => InputAction::None,
+    };
+    (action, new_state)
+}
+
+fn handle_rename_tab_key(key: KeyEvent) -> (InputAction, InputState) {
+    let new_state = InputState::new();
+    let action = match key.code {
+        KeyCode::Esc => InputAction::EnterMode(AppMode::Normal),
+        KeyCode::Enter => InputAction::RenameTabConfirm,
+        KeyCode::Backspace => InputAction::RenameTabBackspace,
+        KeyCode::Char(c) => InputAction::RenameTabChar(c),
         _ => InputAction::None,
     };
     (action, new_state)
@@ -324,6 +549,20 @@ fn handle_confirm_key(key: KeyEvent) -> (InputAction, InputState) {
     (action, new_state)
 }
 
+fn handle_paste_conflict_key(key: KeyEvent) -> (InputAction, InputState) {
+    let new_state = InputState::new();
+    let action = match key.code {
+        KeyCode::Char('o') => InputAction::PasteResolve(ConflictChoice::Overwrite),
+        KeyCode::Char('s') => InputAction::PasteResolve(ConflictChoice::Skip),
+        KeyCode::Char('r') => InputAction::PasteResolve(ConflictChoice::Rename),
+        KeyCode::Char('O') => InputAction::PasteResolve(ConflictChoice::OverwriteAll),
+        KeyCode::Char('S') => InputAction::PasteResolve(ConflictChoice::SkipAll),
+        KeyCode::Esc | KeyCode::Char('q') => InputAction::EnterMode(AppMode::Normal),
+        _ => InputAction::None,
+    };
+    (action, new_state)
+}
+
 fn handle_help_key(key: KeyEvent) -> (InputAction, InputState) {
     let new_state = InputState::new();
     let action = match key.code {
@@ -372,6 +611,20 @@ fn handle_recent_key(key: KeyEvent) -> (InputAction, InputState) {
     (action, new_state)
 }
 
+fn handle_recent_dirs_key(key: KeyEvent) -> (InputAction, InputState) {
+    let new_state = InputState::new();
+    let action = match key.code {
+        KeyCode::Esc => InputAction::EnterMode(AppMode::Normal),
+        KeyCode::Enter => InputAction::RecentDirsConfirm,
+        KeyCode::Backspace => InputAction::RecentDirsBackspace,
+        KeyCode::Down => InputAction::RecentDirsDown,
+        KeyCode::Up => InputAction::RecentDirsUp,
+        KeyCode::Char(c) => InputAction::RecentDirsChar(c),
+        _ => InputAction::None,
+    };
+    (action, new_state)
+}
+
 fn handle_duplicate_key(key: KeyEvent) -> (InputAction, InputState) {
     let new_state = InputState::new();
     let action = match key.code {
@@ -380,6 +633,80 @@ fn handle_duplicate_key(key: KeyEvent) -> (InputAction, InputState) {
         KeyCode::Char('k') | KeyCode::Up => InputAction::DuplicateUp,
         KeyCode::Enter | KeyCode::Char('l') => InputAction::DuplicateConfirm,
         KeyCode::Char('d') => InputAction::DuplicateDelete,
+        KeyCode::Char('i') => InputAction::DuplicateIgnore,
+        KeyCode::Char('s') => InputAction::DuplicateSort,
+        _ => InputAction::None,
+    };
+    (action, new_state)
+}
+
+fn handle_content_search_input_key(key: KeyEvent) -> (InputAction, InputState) {
+    let new_state = InputState::new();
+    let action = match key.code {
+        KeyCode::Esc => InputAction::EnterMode(AppMode::Normal),
+        KeyCode::Enter => InputAction::ContentSearchStart,
+        KeyCode::Backspace => InputAction::ContentSearchBackspace,
+        KeyCode::Char(c) => InputAction::ContentSearchChar(c),
+        _ => InputAction::None,
+    };
+    (action, new_state)
+}
+
+fn handle_content_search_key(key: KeyEvent) -> (InputAction, InputState) {
+    let new_state = InputState::new();
+    let action = match key.code {
+        KeyCode::Esc | KeyCode::Char('q') => InputAction::ContentSearchStop,
+        KeyCode::Char('j') | KeyCode::Down => InputAction::ContentSearchDown,
+        KeyCode::Char('k') | KeyCode::Up => InputAction::ContentSearchUp,
+        KeyCode::Enter | KeyCode::Char('l') => InputAction::ContentSearchConfirm,
+        _ => InputAction::None,
+    };
+    (action, new_state)
+}
+
+fn handle_git_changes_key(key: KeyEvent) -> (InputAction, InputState) {
+    let new_state = InputState::new();
+    let action = match key.code {
+        KeyCode::Esc | KeyCode::Char('q') => InputAction::EnterMode(AppMode::Normal),
+        KeyCode::Char('j') | KeyCode::Down => InputAction::GitChangesDown,
+        KeyCode::Char('k') | KeyCode::Up => InputAction::GitChangesUp,
+        KeyCode::Enter | KeyCode::Char('l') => InputAction::GitChangesConfirm,
+        _ => InputAction::None,
+    };
+    (action, new_state)
+}
+
+fn handle_run_command_key(key: KeyEvent) -> (InputAction, InputState) {
+    let new_state = InputState::new();
+    let action = match key.code {
+        KeyCode::Esc => InputAction::EnterMode(AppMode::Normal),
+        KeyCode::Enter => InputAction::RunCommandSubmit,
+        KeyCode::Backspace => InputAction::RunCommandBackspace,
+        KeyCode::Char(c) => InputAction::RunCommandChar(c),
+        _ => InputAction::None,
+    };
+    (action, new_state)
+}
+
+fn handle_operation_log_key(key: KeyEvent) -> (InputAction, InputState) {
+    let new_state = InputState::new();
+    let action = match key.code {
+        KeyCode::Esc | KeyCode::Char('q') => InputAction::EnterMode(AppMode::Normal),
+        KeyCode::Char('j') | KeyCode::Down => InputAction::OperationLogDown,
+        KeyCode::Char('k') | KeyCode::Up => InputAction::OperationLogUp,
+        _ => InputAction::None,
+    };
+    (action, new_state)
+}
+
+fn handle_notifications_key(key: KeyEvent) -> (InputAction, InputState) {
+    let new_state = InputState::new();
+    let action = match key.code {
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('m') => {
+            InputAction::EnterMode(AppMode::Normal)
+        }
+        KeyCode::Char('j') | KeyCode::Down => InputAction::NotificationsDown,
+        KeyCode::Char('k') | KeyCode::Up => InputAction::NotificationsUp,
         _ => InputAction::None,
     };
     (action, new_state)
@@ -455,20 +782,47 @@ fn handle_remote_connect_key(key: KeyEvent) -> (InputAction, InputState) {
 
 fn handle_terminal_key(key: KeyEvent) -> (InputAction, InputState) {
     let new_state = InputState::new();
-    // Ctrl+t to unfocus terminal (same key as toggle)
+    // Ctrl+t to unfocus terminal (same key as toggle), Ctrl+s to select text
     if key.modifiers.contains(KeyModifiers::CONTROL) {
-        if let KeyCode::Char('t') = key.code {
-            return (InputAction::TerminalUnfocus, new_state);
+        match key.code {
+            KeyCode::Char('t') => return (InputAction::TerminalUnfocus, new_state),
+            KeyCode::Char('s') => return (InputAction::TerminalSelectStart, new_state),
+            _ => {}
         }
     }
     // Esc also unfocuses
     if key.code == KeyCode::Esc {
         return (InputAction::TerminalUnfocus, new_state);
     }
+    // Shift+PageUp/PageDown scroll the history instead of reaching the shell
+    if key.modifiers.contains(KeyModifiers::SHIFT) {
+        match key.code {
+            KeyCode::PageUp => return (InputAction::TerminalScrollUp, new_state),
+            KeyCode::PageDown => return (InputAction::TerminalScrollDown, new_state),
+            _ => {}
+        }
+    }
     // Everything else goes to the PTY
     (InputAction::TerminalInput(key), new_state)
 }
 
+fn handle_terminal_select_key(key: KeyEvent) -> (InputAction, InputState) {
+    let new_state = InputState::new();
+    let action = match key.code {
+        KeyCode::Esc | KeyCode::Char('q') => InputAction::EnterMode(AppMode::Terminal),
+        KeyCode::Char('h') | KeyCode::Left => InputAction::TerminalSelectMove(0, -1),
+        KeyCode::Char('j') | KeyCode::Down => InputAction::TerminalSelectMove(1, 0),
+        KeyCode::Char('k') | KeyCode::Up => InputAction::TerminalSelectMove(-1, 0),
+        KeyCode::Char('l') | KeyCode::Right => InputAction::TerminalSelectMove(0, 1),
+        KeyCode::Char('0') | KeyCode::Home => InputAction::TerminalSelectMove(0, i32::MIN),
+        KeyCode::Char('$') | KeyCode::End => InputAction::TerminalSelectMove(0, i32::MAX),
+        KeyCode::Char('v') => InputAction::TerminalSelectRestart,
+        KeyCode::Char('y') | KeyCode::Enter => InputAction::TerminalSelectYank,
+        _ => InputAction::None,
+    };
+    (action, new_state)
+}
+
 /// Converts an `Action` to `InputAction` — public for use by the palette confirm logic.
 pub fn resolve_action(action: Action) -> InputAction {
     action_to_input_action(action)
@@ -477,8 +831,8 @@ pub fn resolve_action(action: Action) -> InputAction {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::app::ConfirmAction;
     use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyEventState, KeyModifiers};
+    use trefm_core::config::keymap::KeymapProfile;
 
     fn key(code: KeyCode) -> KeyEvent {
         KeyEvent {
@@ -653,6 +1007,137 @@ mod tests {
         assert!(matches!(action, InputAction::RequestDelete));
     }
 
+    #[test]
+    fn normal_capital_n_duplicates_in_place() {
+        let state = InputState::new();
+        let km = default_keymap();
+        let (action, _) = handle_key(key(KeyCode::Char('N')), &AppMode::Normal, &state, &km);
+        assert!(matches!(action, InputAction::DuplicateInPlace));
+    }
+
+    #[test]
+    fn normal_y_yanks() {
+        let state = InputState::new();
+        let km = default_keymap();
+        let (action, _) = handle_key(key(KeyCode::Char('y')), &AppMode::Normal, &state, &km);
+        assert!(matches!(action, InputAction::Yank));
+    }
+
+    #[test]
+    fn normal_capital_p_pastes() {
+        let state = InputState::new();
+        let km = default_keymap();
+        let (action, _) = handle_key(key(KeyCode::Char('P')), &AppMode::Normal, &state, &km);
+        assert!(matches!(action, InputAction::Paste));
+    }
+
+    #[test]
+    fn normal_capital_y_and_m_transfer_to_other_panel() {
+        let state = InputState::new();
+        let km = default_keymap();
+        let (action, _) = handle_key(key(KeyCode::Char('Y')), &AppMode::Normal, &state, &km);
+        assert!(matches!(action, InputAction::CopyToOtherPanel));
+        let (action, _) = handle_key(key(KeyCode::Char('M')), &AppMode::Normal, &state, &km);
+        assert!(matches!(action, InputAction::MoveToOtherPanel));
+    }
+
+    #[test]
+    fn normal_capital_s_toggles_sort_direction() {
+        let state = InputState::new();
+        let km = default_keymap();
+        let (action, _) = handle_key(key(KeyCode::Char('S')), &AppMode::Normal, &state, &km);
+        assert!(matches!(action, InputAction::ToggleSortDirection));
+    }
+
+    #[test]
+    fn normal_capital_i_reverses_listing() {
+        let state = InputState::new();
+        let km = default_keymap();
+        let (action, _) = handle_key(key(KeyCode::Char('I')), &AppMode::Normal, &state, &km);
+        assert!(matches!(action, InputAction::ReverseListing));
+    }
+
+    #[test]
+    fn normal_f_cycles_category_filter() {
+        let state = InputState::new();
+        let km = default_keymap();
+        let (action, _) = handle_key(key(KeyCode::Char('f')), &AppMode::Normal, &state, &km);
+        assert!(matches!(action, InputAction::CycleCategoryFilter));
+    }
+
+    #[test]
+    fn normal_capital_o_toggles_dirs_only() {
+        let state = InputState::new();
+        let km = default_keymap();
+        let (action, _) = handle_key(key(KeyCode::Char('O')), &AppMode::Normal, &state, &km);
+        assert!(matches!(action, InputAction::ToggleDirsOnly));
+    }
+
+    #[test]
+    fn normal_pipe_starts_filter() {
+        let state = InputState::new();
+        let km = default_keymap();
+        let (action, _) = handle_key(key(KeyCode::Char('|')), &AppMode::Normal, &state, &km);
+        assert!(matches!(action, InputAction::StartFilter));
+    }
+
+    #[test]
+    fn filter_mode_keys() {
+        let state = InputState::new();
+        let km = default_keymap();
+        let mode = AppMode::Filter("al".to_string());
+        let (action, _) = handle_key(key(KeyCode::Char('j')), &mode, &state, &km);
+        assert!(matches!(action, InputAction::FilterChar('j')));
+        let (action, _) = handle_key(key(KeyCode::Backspace), &mode, &state, &km);
+        assert!(matches!(action, InputAction::FilterBackspace));
+        let (action, _) = handle_key(key(KeyCode::Enter), &mode, &state, &km);
+        assert!(matches!(action, InputAction::FilterAccept));
+        let (action, _) = handle_key(key(KeyCode::Esc), &mode, &state, &km);
+        assert!(matches!(action, InputAction::FilterClear));
+        let (action, _) = handle_key(key(KeyCode::Down), &mode, &state, &km);
+        assert!(matches!(action, InputAction::Command(Command::CursorDown)));
+    }
+
+    #[test]
+    fn normal_capital_h_scans_duplicates_here() {
+        let state = InputState::new();
+        let km = default_keymap();
+        let (action, _) = handle_key(key(KeyCode::Char('H')), &AppMode::Normal, &state, &km);
+        assert!(matches!(action, InputAction::ScanDuplicatesHere));
+    }
+
+    #[test]
+    fn normal_capital_j_follows_symlink() {
+        let state = InputState::new();
+        let km = default_keymap();
+        let (action, _) = handle_key(key(KeyCode::Char('J')), &AppMode::Normal, &state, &km);
+        assert!(matches!(action, InputAction::FollowSymlink));
+    }
+
+    #[test]
+    fn normal_capital_e_copies_tree() {
+        let state = InputState::new();
+        let km = default_keymap();
+        let (action, _) = handle_key(key(KeyCode::Char('E')), &AppMode::Normal, &state, &km);
+        assert!(matches!(action, InputAction::CopyTree));
+    }
+
+    #[test]
+    fn normal_capital_k_copies_git_path() {
+        let state = InputState::new();
+        let km = default_keymap();
+        let (action, _) = handle_key(key(KeyCode::Char('K')), &AppMode::Normal, &state, &km);
+        assert!(matches!(action, InputAction::CopyGitPath));
+    }
+
+    #[test]
+    fn normal_star_toggles_pin() {
+        let state = InputState::new();
+        let km = default_keymap();
+        let (action, _) = handle_key(key(KeyCode::Char('*')), &AppMode::Normal, &state, &km);
+        assert!(matches!(action, InputAction::TogglePin));
+    }
+
     #[test]
     fn normal_s_opens_sort_select() {
         let state = InputState::new();
@@ -697,7 +1182,32 @@ mod tests {
     fn normal_unknown_key_none() {
         let state = InputState::new();
         let km = default_keymap();
-        let (action, _) = handle_key(key(KeyCode::Char('z')), &AppMode::Normal, &state, &km);
+        let (action, _) = handle_key(key(KeyCode::Char('Z')), &AppMode::Normal, &state, &km);
+        assert!(matches!(action, InputAction::None));
+    }
+
+    #[test]
+    fn normal_named_keys_follow_classic_profile() {
+        let state = InputState::new();
+        let km = Keymap::for_profile(KeymapProfile::Classic);
+
+        let (action, _) = handle_key(key(KeyCode::Home), &AppMode::Normal, &state, &km);
+        assert!(matches!(action, InputAction::CursorTop));
+        let (action, _) = handle_key(key(KeyCode::Backspace), &AppMode::Normal, &state, &km);
+        assert!(matches!(action, InputAction::Command(Command::GoUp)));
+        let (action, _) = handle_key(key(KeyCode::F(5)), &AppMode::Normal, &state, &km);
+        assert!(matches!(action, InputAction::CopyToOtherPanel));
+        let (action, _) = handle_key(key(KeyCode::F(10)), &AppMode::Normal, &state, &km);
+        assert!(matches!(action, InputAction::Quit));
+        let (action, _) = handle_key(key(KeyCode::Char('j')), &AppMode::Normal, &state, &km);
+        assert!(matches!(action, InputAction::None));
+    }
+
+    #[test]
+    fn normal_named_keys_unbound_in_vim_profile() {
+        let state = InputState::new();
+        let km = default_keymap();
+        let (action, _) = handle_key(key(KeyCode::F(5)), &AppMode::Normal, &state, &km);
         assert!(matches!(action, InputAction::None));
     }
 
@@ -802,6 +1312,36 @@ j = "cursor_up"
         assert!(matches!(action, InputAction::EnterMode(AppMode::Normal)));
     }
 
+    #[test]
+    fn rename_typing_and_confirm() {
+        let state = InputState::new();
+        let km = default_keymap();
+        let mode = AppMode::Rename("name".to_string());
+        let (action, _) = handle_key(key(KeyCode::Char('x')), &mode, &state, &km);
+        assert!(matches!(action, InputAction::RenameChar('x')));
+        let (action, _) = handle_key(key(KeyCode::Backspace), &mode, &state, &km);
+        assert!(matches!(action, InputAction::RenameBackspace));
+        let (action, _) = handle_key(key(KeyCode::Enter), &mode, &state, &km);
+        assert!(matches!(action, InputAction::RenameConfirm));
+    }
+
+    #[test]
+    fn capital_t_renames_tab() {
+        let state = InputState::new();
+        let km = default_keymap();
+        let (action, _) = handle_key(key(KeyCode::Char('T')), &AppMode::Normal, &state, &km);
+        assert!(matches!(
+            action,
+            InputAction::EnterMode(AppMode::RenameTab(ref name)) if name.is_empty()
+        ));
+
+        let mode = AppMode::RenameTab("wip".to_string());
+        let (action, _) = handle_key(key(KeyCode::Char('x')), &mode, &state, &km);
+        assert!(matches!(action, InputAction::RenameTabChar('x')));
+        let (action, _) = handle_key(key(KeyCode::Enter), &mode, &state, &km);
+        assert!(matches!(action, InputAction::RenameTabConfirm));
+    }
+
     // --- Confirm mode ---
 
     #[test]
@@ -856,6 +1396,50 @@ j = "cursor_up"
         assert!(matches!(action, InputAction::EnterMode(AppMode::Normal)));
     }
 
+    // --- Paste conflict mode ---
+
+    fn paste_conflict_mode() -> AppMode {
+        let queue = trefm_core::fs::conflict::PasteQueue::new(vec![], std::path::Path::new("/"));
+        AppMode::Confirm(ConfirmAction::PasteConflict(queue))
+    }
+
+    #[test]
+    fn paste_conflict_keys_map_to_choices() {
+        let state = InputState::new();
+        let km = default_keymap();
+        let cases = [
+            ('o', ConflictChoice::Overwrite),
+            ('s', ConflictChoice::Skip),
+            ('r', ConflictChoice::Rename),
+            ('O', ConflictChoice::OverwriteAll),
+            ('S', ConflictChoice::SkipAll),
+        ];
+        for (c, expected) in cases {
+            let (action, _) =
+                handle_key(key(KeyCode::Char(c)), &paste_conflict_mode(), &state, &km);
+            assert!(
+                matches!(action, InputAction::PasteResolve(choice) if choice == expected),
+                "key {c} should resolve to {expected:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn paste_conflict_esc_cancels() {
+        let state = InputState::new();
+        let km = default_keymap();
+        let (action, _) = handle_key(key(KeyCode::Esc), &paste_conflict_mode(), &state, &km);
+        assert!(matches!(action, InputAction::EnterMode(AppMode::Normal)));
+    }
+
+    #[test]
+    fn paste_conflict_y_does_not_approve() {
+        let state = InputState::new();
+        let km = default_keymap();
+        let (action, _) = handle_key(key(KeyCode::Char('y')), &paste_conflict_mode(), &state, &km);
+        assert!(matches!(action, InputAction::None));
+    }
+
     // --- Help mode ---
 
     #[test]
@@ -1267,6 +1851,231 @@ j = "cursor_up"
         assert!(matches!(action, InputAction::None));
     }
 
+    // --- ContentSearch modes ---
+
+    #[test]
+    fn normal_shift_f_enters_content_search_input() {
+        let state = InputState::new();
+        let km = default_keymap();
+        let (action, _) = handle_key(key(KeyCode::Char('F')), &AppMode::Normal, &state, &km);
+        assert!(matches!(
+            action,
+            InputAction::EnterMode(AppMode::ContentSearchInput(ref q)) if q.is_empty()
+        ));
+    }
+
+    #[test]
+    fn content_search_input_keys() {
+        let state = InputState::new();
+        let km = default_keymap();
+        let mode = AppMode::ContentSearchInput("fo".to_string());
+        let (action, _) = handle_key(key(KeyCode::Char('o')), &mode, &state, &km);
+        assert!(matches!(action, InputAction::ContentSearchChar('o')));
+        let (action, _) = handle_key(key(KeyCode::Backspace), &mode, &state, &km);
+        assert!(matches!(action, InputAction::ContentSearchBackspace));
+        let (action, _) = handle_key(key(KeyCode::Enter), &mode, &state, &km);
+        assert!(matches!(action, InputAction::ContentSearchStart));
+        let (action, _) = handle_key(key(KeyCode::Esc), &mode, &state, &km);
+        assert!(matches!(action, InputAction::EnterMode(AppMode::Normal)));
+    }
+
+    #[test]
+    fn content_search_results_keys() {
+        let state = InputState::new();
+        let km = default_keymap();
+        let mode = AppMode::ContentSearch;
+        let (action, _) = handle_key(key(KeyCode::Char('j')), &mode, &state, &km);
+        assert!(matches!(action, InputAction::ContentSearchDown));
+        let (action, _) = handle_key(key(KeyCode::Up), &mode, &state, &km);
+        assert!(matches!(action, InputAction::ContentSearchUp));
+        let (action, _) = handle_key(key(KeyCode::Enter), &mode, &state, &km);
+        assert!(matches!(action, InputAction::ContentSearchConfirm));
+        let (action, _) = handle_key(key(KeyCode::Esc), &mode, &state, &km);
+        assert!(matches!(action, InputAction::ContentSearchStop));
+        let (action, _) = handle_key(key(KeyCode::Char('q')), &mode, &state, &km);
+        assert!(matches!(action, InputAction::ContentSearchStop));
+    }
+
+    // --- GitChanges mode ---
+
+    #[test]
+    fn normal_c_enters_git_changes() {
+        let state = InputState::new();
+        let km = default_keymap();
+        let (action, _) = handle_key(key(KeyCode::Char('c')), &AppMode::Normal, &state, &km);
+        assert!(matches!(
+            action,
+            InputAction::EnterMode(AppMode::GitChanges)
+        ));
+    }
+
+    #[test]
+    fn git_changes_keys() {
+        let state = InputState::new();
+        let km = default_keymap();
+        let mode = AppMode::GitChanges;
+        let (action, _) = handle_key(key(KeyCode::Char('j')), &mode, &state, &km);
+        assert!(matches!(action, InputAction::GitChangesDown));
+        let (action, _) = handle_key(key(KeyCode::Char('k')), &mode, &state, &km);
+        assert!(matches!(action, InputAction::GitChangesUp));
+        let (action, _) = handle_key(key(KeyCode::Enter), &mode, &state, &km);
+        assert!(matches!(action, InputAction::GitChangesConfirm));
+        let (action, _) = handle_key(key(KeyCode::Esc), &mode, &state, &km);
+        assert!(matches!(action, InputAction::EnterMode(AppMode::Normal)));
+    }
+
+    // --- RunCommand mode ---
+
+    #[test]
+    fn normal_bang_enters_run_command() {
+        let state = InputState::new();
+        let km = default_keymap();
+        let (action, _) = handle_key(key(KeyCode::Char('!')), &AppMode::Normal, &state, &km);
+        assert!(matches!(
+            action,
+            InputAction::EnterMode(AppMode::RunCommand(ref t)) if t.is_empty()
+        ));
+    }
+
+    #[test]
+    fn run_command_keys() {
+        let state = InputState::new();
+        let km = default_keymap();
+        let mode = AppMode::RunCommand("wc".to_string());
+        let (action, _) = handle_key(key(KeyCode::Char('q')), &mode, &state, &km);
+        assert!(matches!(action, InputAction::RunCommandChar('q')));
+        let (action, _) = handle_key(key(KeyCode::Backspace), &mode, &state, &km);
+        assert!(matches!(action, InputAction::RunCommandBackspace));
+        let (action, _) = handle_key(key(KeyCode::Enter), &mode, &state, &km);
+        assert!(matches!(action, InputAction::RunCommandSubmit));
+        let (action, _) = handle_key(key(KeyCode::Esc), &mode, &state, &km);
+        assert!(matches!(action, InputAction::EnterMode(AppMode::Normal)));
+    }
+
+    // --- OperationLog mode ---
+
+    #[test]
+    fn normal_capital_l_enters_operation_log() {
+        let state = InputState::new();
+        let km = default_keymap();
+        let (action, _) = handle_key(key(KeyCode::Char('L')), &AppMode::Normal, &state, &km);
+        assert!(matches!(
+            action,
+            InputAction::EnterMode(AppMode::OperationLog { scroll: 0 })
+        ));
+    }
+
+    #[test]
+    fn operation_log_keys() {
+        let state = InputState::new();
+        let km = default_keymap();
+        let mode = AppMode::OperationLog { scroll: 0 };
+        let (action, _) = handle_key(key(KeyCode::Char('j')), &mode, &state, &km);
+        assert!(matches!(action, InputAction::OperationLogDown));
+        let (action, _) = handle_key(key(KeyCode::Up), &mode, &state, &km);
+        assert!(matches!(action, InputAction::OperationLogUp));
+        let (action, _) = handle_key(key(KeyCode::Char('q')), &mode, &state, &km);
+        assert!(matches!(action, InputAction::EnterMode(AppMode::Normal)));
+    }
+
+    // --- RecentDirs mode ---
+
+    #[test]
+    fn normal_z_enters_recent_dirs() {
+        let state = InputState::new();
+        let km = default_keymap();
+        let (action, _) = handle_key(key(KeyCode::Char('z')), &AppMode::Normal, &state, &km);
+        assert!(matches!(
+            action,
+            InputAction::EnterMode(AppMode::RecentDirs { ref query, selected: 0 }) if query.is_empty()
+        ));
+    }
+
+    #[test]
+    fn recent_dirs_keys() {
+        let state = InputState::new();
+        let km = default_keymap();
+        let mode = AppMode::RecentDirs {
+            query: String::new(),
+            selected: 0,
+        };
+        let (action, _) = handle_key(key(KeyCode::Char('j')), &mode, &state, &km);
+        assert!(matches!(action, InputAction::RecentDirsChar('j')));
+        let (action, _) = handle_key(key(KeyCode::Down), &mode, &state, &km);
+        assert!(matches!(action, InputAction::RecentDirsDown));
+        let (action, _) = handle_key(key(KeyCode::Backspace), &mode, &state, &km);
+        assert!(matches!(action, InputAction::RecentDirsBackspace));
+        let (action, _) = handle_key(key(KeyCode::Enter), &mode, &state, &km);
+        assert!(matches!(action, InputAction::RecentDirsConfirm));
+        let (action, _) = handle_key(key(KeyCode::Esc), &mode, &state, &km);
+        assert!(matches!(action, InputAction::EnterMode(AppMode::Normal)));
+    }
+
+    #[test]
+    fn diff_mode_scrolls_like_pager() {
+        let state = InputState::new();
+        let km = default_keymap();
+        let mode = AppMode::Diff { scroll: 0 };
+        let (action, _) = handle_key(key(KeyCode::Char('j')), &mode, &state, &km);
+        assert!(matches!(action, InputAction::PagerDown));
+        let (action, _) = handle_key(key(KeyCode::Char('G')), &mode, &state, &km);
+        assert!(matches!(action, InputAction::PagerBottom));
+        let (action, _) = handle_key(key(KeyCode::Char('q')), &mode, &state, &km);
+        assert!(matches!(action, InputAction::EnterMode(AppMode::Normal)));
+    }
+
+    // --- Notifications mode ---
+
+    #[test]
+    fn normal_m_enters_notifications() {
+        let state = InputState::new();
+        let km = default_keymap();
+        let (action, _) = handle_key(key(KeyCode::Char('m')), &AppMode::Normal, &state, &km);
+        assert!(matches!(
+            action,
+            InputAction::EnterMode(AppMode::Notifications { scroll: 0 })
+        ));
+    }
+
+    #[test]
+    fn notifications_keys() {
+        let state = InputState::new();
+        let km = default_keymap();
+        let mode = AppMode::Notifications { scroll: 0 };
+        let (action, _) = handle_key(key(KeyCode::Char('j')), &mode, &state, &km);
+        assert!(matches!(action, InputAction::NotificationsDown));
+        let (action, _) = handle_key(key(KeyCode::Up), &mode, &state, &km);
+        assert!(matches!(action, InputAction::NotificationsUp));
+        let (action, _) = handle_key(key(KeyCode::Char('m')), &mode, &state, &km);
+        assert!(matches!(action, InputAction::EnterMode(AppMode::Normal)));
+    }
+
+    // --- LogViewer mode ---
+
+    #[test]
+    fn normal_capital_v_enters_log_viewer() {
+        let state = InputState::new();
+        let km = default_keymap();
+        let (action, _) = handle_key(key(KeyCode::Char('V')), &AppMode::Normal, &state, &km);
+        assert!(matches!(
+            action,
+            InputAction::EnterMode(AppMode::LogViewer { scroll: 0 })
+        ));
+    }
+
+    #[test]
+    fn log_viewer_uses_pager_keys() {
+        let state = InputState::new();
+        let km = default_keymap();
+        let mode = AppMode::LogViewer { scroll: 0 };
+        let (action, _) = handle_key(key(KeyCode::Char('j')), &mode, &state, &km);
+        assert!(matches!(action, InputAction::PagerDown));
+        let (action, _) = handle_key(key(KeyCode::Char('G')), &mode, &state, &km);
+        assert!(matches!(action, InputAction::PagerBottom));
+        let (action, _) = handle_key(key(KeyCode::Esc), &mode, &state, &km);
+        assert!(matches!(action, InputAction::EnterMode(AppMode::Normal)));
+    }
+
     // --- InputState ---
 
     #[test]
@@ -1393,6 +2202,32 @@ j = "cursor_up"
         assert!(matches!(action, InputAction::DuplicateDelete));
     }
 
+    #[test]
+    fn duplicate_i_ignores_group() {
+        let state = InputState::new();
+        let km = default_keymap();
+        let (action, _) = handle_key(
+            key(KeyCode::Char('i')),
+            &AppMode::DuplicateFiles,
+            &state,
+            &km,
+        );
+        assert!(matches!(action, InputAction::DuplicateIgnore));
+    }
+
+    #[test]
+    fn duplicate_s_cycles_sort() {
+        let state = InputState::new();
+        let km = default_keymap();
+        let (action, _) = handle_key(
+            key(KeyCode::Char('s')),
+            &AppMode::DuplicateFiles,
+            &state,
+            &km,
+        );
+        assert!(matches!(action, InputAction::DuplicateSort));
+    }
+
     #[test]
     fn duplicate_unhandled_key_returns_none() {
         let state = InputState::new();
@@ -1427,6 +2262,90 @@ j = "cursor_up"
         ));
     }
 
+    // --- Terminal mode ---
+
+    #[test]
+    fn terminal_shift_page_up_scrolls_back() {
+        let state = InputState::new();
+        let km = default_keymap();
+        let (action, _) = handle_key(
+            key_with_mod(KeyCode::PageUp, KeyModifiers::SHIFT),
+            &AppMode::Terminal,
+            &state,
+            &km,
+        );
+        assert!(matches!(action, InputAction::TerminalScrollUp));
+    }
+
+    #[test]
+    fn terminal_shift_page_down_scrolls_forward() {
+        let state = InputState::new();
+        let km = default_keymap();
+        let (action, _) = handle_key(
+            key_with_mod(KeyCode::PageDown, KeyModifiers::SHIFT),
+            &AppMode::Terminal,
+            &state,
+            &km,
+        );
+        assert!(matches!(action, InputAction::TerminalScrollDown));
+    }
+
+    #[test]
+    fn terminal_plain_page_up_goes_to_pty() {
+        let state = InputState::new();
+        let km = default_keymap();
+        let (action, _) = handle_key(key(KeyCode::PageUp), &AppMode::Terminal, &state, &km);
+        assert!(matches!(action, InputAction::TerminalInput(_)));
+    }
+
+    #[test]
+    fn terminal_ctrl_s_starts_selection() {
+        let state = InputState::new();
+        let km = default_keymap();
+        let (action, _) = handle_key(
+            key_with_mod(KeyCode::Char('s'), KeyModifiers::CONTROL),
+            &AppMode::Terminal,
+            &state,
+            &km,
+        );
+        assert!(matches!(action, InputAction::TerminalSelectStart));
+    }
+
+    // --- TerminalSelect mode ---
+
+    fn select_mode() -> AppMode {
+        AppMode::TerminalSelect(crate::terminal_emu::selection::Selection::new((0, 0)))
+    }
+
+    #[test]
+    fn terminal_select_hjkl_moves() {
+        let state = InputState::new();
+        let km = default_keymap();
+        for (c, delta) in [('h', (0, -1)), ('j', (1, 0)), ('k', (-1, 0)), ('l', (0, 1))] {
+            let (action, _) = handle_key(key(KeyCode::Char(c)), &select_mode(), &state, &km);
+            assert!(
+                matches!(action, InputAction::TerminalSelectMove(r, c) if (r, c) == delta),
+                "key {c}"
+            );
+        }
+    }
+
+    #[test]
+    fn terminal_select_y_yanks() {
+        let state = InputState::new();
+        let km = default_keymap();
+        let (action, _) = handle_key(key(KeyCode::Char('y')), &select_mode(), &state, &km);
+        assert!(matches!(action, InputAction::TerminalSelectYank));
+    }
+
+    #[test]
+    fn terminal_select_esc_returns_to_terminal() {
+        let state = InputState::new();
+        let km = default_keymap();
+        let (action, _) = handle_key(key(KeyCode::Esc), &select_mode(), &state, &km);
+        assert!(matches!(action, InputAction::EnterMode(AppMode::Terminal)));
+    }
+
     // --- Command Palette mode ---
 
     #[test]
@@ -0,0 +1,164 @@
+//! Size-based rotating log file.
+//!
+//! [`RotatingFile`] appends to one file. When a write would take the file
+//! past `max_bytes`, the current file becomes `<name>.1`, `<name>.1`
+//! becomes `<name>.2`, and so on; anything past `<name>.<max_files>` is
+//! deleted. Wrap it in a `Mutex` to hand it to `tracing_subscriber`.
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+pub struct RotatingFile {
+    path: PathBuf,
+    file: File,
+    size: u64,
+    /// `0` disables rotation.
+    max_bytes: u64,
+    max_files: usize,
+}
+
+impl RotatingFile {
+    /// Opens `path` for appending, creating it and its parent directory if needed.
+    pub fn open(path: &Path, max_bytes: u64, max_files: usize) -> io::Result<Self> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = open_append(path)?;
+        let size = file.metadata()?.len();
+        Ok(Self {
+            path: path.to_path_buf(),
+            file,
+            size,
+            max_bytes,
+            max_files,
+        })
+    }
+
+    /// Path of the `n`th rotated file (`trefm.log.1`, `trefm.log.2`, …).
+    fn rotated(&self, n: usize) -> PathBuf {
+        let mut name = self.path.clone().into_os_string();
+        name.push(format!(".{n}"));
+        PathBuf::from(name)
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        if self.max_files == 0 {
+            self.file = File::create(&self.path)?;
+        } else {
+            let _ = std::fs::remove_file(self.rotated(self.max_files));
+            for n in (1..self.max_files).rev() {
+                let from = self.rotated(n);
+                if from.exists() {
+                    std::fs::rename(&from, self.rotated(n + 1))?;
+                }
+            }
+            std::fs::rename(&self.path, self.rotated(1))?;
+            self.file = open_append(&self.path)?;
+        }
+        self.size = 0;
+        Ok(())
+    }
+}
+
+impl Write for RotatingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.max_bytes > 0 && self.size > 0 && self.size + buf.len() as u64 > self.max_bytes {
+            self.rotate()?;
+        }
+        let written = self.file.write(buf)?;
+        self.size += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+fn open_append(path: &Path) -> io::Result<File> {
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn read(path: &Path) -> String {
+        std::fs::read_to_string(path).unwrap()
+    }
+
+    #[test]
+    fn rotates_when_write_would_exceed_max_size() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("trefm.log");
+        let mut log = RotatingFile::open(&path, 10, 3).unwrap();
+
+        log.write_all(b"12345").unwrap();
+        log.write_all(b"6789").unwrap();
+        assert_eq!(read(&path), "123456789");
+        assert!(!log.rotated(1).exists());
+
+        log.write_all(b"ab").unwrap();
+        assert_eq!(read(&path), "ab");
+        assert_eq!(read(&log.rotated(1)), "123456789");
+    }
+
+    #[test]
+    fn keeps_only_max_files_rotated_files() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("trefm.log");
+        let mut log = RotatingFile::open(&path, 4, 2).unwrap();
+
+        for line in ["aaaa", "bbbb", "cccc", "dddd"] {
+            log.write_all(line.as_bytes()).unwrap();
+        }
+
+        assert_eq!(read(&path), "dddd");
+        assert_eq!(read(&log.rotated(1)), "cccc");
+        assert_eq!(read(&log.rotated(2)), "bbbb");
+        assert!(!log.rotated(3).exists());
+    }
+
+    #[test]
+    fn existing_size_counts_towards_limit() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("trefm.log");
+        std::fs::write(&path, "old log").unwrap();
+
+        let mut log = RotatingFile::open(&path, 8, 1).unwrap();
+        log.write_all(b"new").unwrap();
+
+        assert_eq!(read(&path), "new");
+        assert_eq!(read(&log.rotated(1)), "old log");
+    }
+
+    #[test]
+    fn zero_max_files_truncates_in_place() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("trefm.log");
+        let mut log = RotatingFile::open(&path, 4, 0).unwrap();
+
+        log.write_all(b"aaaa").unwrap();
+        log.write_all(b"bb").unwrap();
+
+        assert_eq!(read(&path), "bb");
+        assert!(!log.rotated(1).exists());
+    }
+
+    #[test]
+    fn zero_max_size_never_rotates() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("trefm.log");
+        let mut log = RotatingFile::open(&path, 0, 3).unwrap();
+
+        for _ in 0..10 {
+            log.write_all(b"0123456789").unwrap();
+        }
+
+        assert_eq!(read(&path).len(), 100);
+        assert!(!log.rotated(1).exists());
+    }
+}
@@ -0,0 +1,264 @@
+//! Follows the log file for the in-app log viewer.
+//!
+//! [`LogTail`] remembers how far into the file it has read, so each
+//! [`LogTail::poll`] only reads what was appended since. It holds at most
+//! `max_lines` lines, dropping the oldest as new ones arrive. When the file
+//! shrinks (rotated or truncated), reading starts over from the top.
+//! A poll never reads more than about `max_lines` lines' worth of bytes:
+//! when more than that is pending (a large file opened for the first
+//! time), reading skips ahead and starts at the first full line of the
+//! tail.
+
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+/// Bytes read per call while polling.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Bytes allowed per kept line when bounding a poll.
+const BYTES_PER_LINE: u64 = 512;
+
+pub struct LogTail {
+    path: PathBuf,
+    /// Bytes of the file consumed so far.
+    offset: u64,
+    /// Bytes after the last newline, waiting for the rest of their line.
+    partial: Vec<u8>,
+    /// Set after skipping ahead: the bytes up to the next newline belong to
+    /// a line whose start was never read.
+    skip_line: bool,
+    lines: VecDeque<String>,
+    max_lines: usize,
+}
+
+impl LogTail {
+    /// Lines kept in memory by the log viewer.
+    pub const DEFAULT_MAX_LINES: usize = 5000;
+
+    /// Creates a tail of `path`; nothing is read until the first [`poll`](Self::poll).
+    pub fn new(path: &Path, max_lines: usize) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            offset: 0,
+            partial: Vec::new(),
+            skip_line: false,
+            lines: VecDeque::new(),
+            max_lines: max_lines.max(1),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Complete lines read so far, oldest first.
+    pub fn lines(&self) -> &VecDeque<String> {
+        &self.lines
+    }
+
+    /// Reads lines appended since the last call. Returns `true` if any arrived.
+    ///
+    /// A file that does not exist (yet) reads as empty.
+    pub fn poll(&mut self) -> io::Result<bool> {
+        let mut file = match File::open(&self.path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(false),
+            Err(e) => return Err(e),
+        };
+        let len = file.metadata()?.len();
+        if len < self.offset {
+            self.offset = 0;
+            self.partial.clear();
+            self.skip_line = false;
+        }
+        if len == self.offset {
+            return Ok(false);
+        }
+
+        let start = len.saturating_sub(self.max_bytes()).max(self.offset);
+        if start > self.offset {
+            // Resume one byte early, so a cut right after a newline drops nothing
+            self.offset = start - 1;
+            self.partial.clear();
+            self.skip_line = true;
+        }
+
+        file.seek(SeekFrom::Start(self.offset))?;
+        let mut chunk = vec![0; CHUNK_SIZE];
+        let mut added = false;
+        while self.offset < len {
+            let want = CHUNK_SIZE.min((len - self.offset) as usize);
+            let read = file.read(&mut chunk[..want])?;
+            if read == 0 {
+                break;
+            }
+            self.offset += read as u64;
+            added |= self.consume(&chunk[..read]);
+        }
+        Ok(added)
+    }
+
+    /// Most bytes read by one poll.
+    fn max_bytes(&self) -> u64 {
+        self.max_lines as u64 * BYTES_PER_LINE
+    }
+
+    /// Splits `data` into lines, joining it to the pending partial line.
+    /// Returns `true` if a complete line was kept.
+    fn consume(&mut self, data: &[u8]) -> bool {
+        let mut added = false;
+        let mut rest = data;
+        while let Some(pos) = rest.iter().position(|&b| b == b'\n') {
+            if self.skip_line {
+                self.skip_line = false;
+            } else {
+                self.partial.extend_from_slice(&rest[..pos]);
+                let line = String::from_utf8_lossy(&self.partial)
+                    .trim_end_matches('\r')
+                    .to_string();
+                self.partial.clear();
+                self.push(line);
+                added = true;
+            }
+            rest = &rest[pos + 1..];
+        }
+        if !self.skip_line {
+            self.partial.extend_from_slice(rest);
+        }
+        added
+    }
+
+    fn push(&mut self, line: String) {
+        if self.lines.len() == self.max_lines {
+            self.lines.pop_front();
+        }
+        self.lines.push_back(line);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    fn append(path: &Path, text: &str) {
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .unwrap();
+        file.write_all(text.as_bytes()).unwrap();
+    }
+
+    fn lines(tail: &LogTail) -> Vec<&str> {
+        tail.lines().iter().map(String::as_str).collect()
+    }
+
+    #[test]
+    fn appended_lines_appear_on_next_poll() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("trefm.log");
+        append(&path, "one\ntwo\n");
+
+        let mut tail = LogTail::new(&path, 100);
+        assert!(tail.poll().unwrap());
+        assert_eq!(lines(&tail), ["one", "two"]);
+
+        assert!(!tail.poll().unwrap());
+
+        append(&path, "three\n");
+        assert!(tail.poll().unwrap());
+        assert_eq!(lines(&tail), ["one", "two", "three"]);
+    }
+
+    #[test]
+    fn partial_line_waits_for_newline() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("trefm.log");
+        append(&path, "first\nsec");
+
+        let mut tail = LogTail::new(&path, 100);
+        tail.poll().unwrap();
+        assert_eq!(lines(&tail), ["first"]);
+
+        append(&path, "ond\r\n");
+        assert!(tail.poll().unwrap());
+        assert_eq!(lines(&tail), ["first", "second"]);
+    }
+
+    #[test]
+    fn memory_cap_drops_oldest_lines() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("trefm.log");
+        append(&path, "1\n2\n3\n");
+
+        let mut tail = LogTail::new(&path, 3);
+        tail.poll().unwrap();
+        append(&path, "4\n5\n");
+        tail.poll().unwrap();
+
+        assert_eq!(lines(&tail), ["3", "4", "5"]);
+    }
+
+    #[test]
+    fn shrunk_file_is_read_from_the_start() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("trefm.log");
+        append(&path, "before rotation\n");
+
+        let mut tail = LogTail::new(&path, 100);
+        tail.poll().unwrap();
+        std::fs::write(&path, "new\n").unwrap();
+
+        assert!(tail.poll().unwrap());
+        assert_eq!(lines(&tail), ["before rotation", "new"]);
+    }
+
+    #[test]
+    fn large_file_is_read_from_the_tail() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("trefm.log");
+        let head = "x".repeat(1024 * 1024);
+        append(&path, &format!("{head}\none\ntwo\n"));
+
+        let mut tail = LogTail::new(&path, 10);
+        assert!(tail.poll().unwrap());
+        // 10 lines allow 5 KiB, so the cut falls inside the long first line
+        assert_eq!(lines(&tail), ["one", "two"]);
+
+        append(&path, "next\n");
+        assert!(tail.poll().unwrap());
+        assert_eq!(tail.lines().back().map(String::as_str), Some("next"));
+    }
+
+    #[test]
+    fn cap_at_a_line_boundary_keeps_that_line() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("trefm.log");
+        // 1 line allows 512 bytes, so the cut falls right after "z\n"
+        let line = "y".repeat(511);
+        append(&path, &format!("z\n{line}\n"));
+        assert_eq!(std::fs::metadata(&path).unwrap().len(), 514);
+
+        let mut tail = LogTail::new(&path, 1);
+        assert!(tail.poll().unwrap());
+        assert_eq!(lines(&tail), [line.as_str()]);
+    }
+
+    #[test]
+    fn missing_file_reads_as_empty() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("trefm.log");
+
+        let mut tail = LogTail::new(&path, 100);
+        assert!(!tail.poll().unwrap());
+        assert!(tail.lines().is_empty());
+
+        append(&path, "created\n");
+        assert!(tail.poll().unwrap());
+        assert_eq!(lines(&tail), ["created"]);
+    }
+}
@@ -8,17 +8,22 @@ mod background;
 mod icons;
 mod image_preview;
 mod input;
+mod log_file;
+mod log_tail;
+mod notifications;
+mod oplog;
 mod render;
 mod terminal_emu;
 mod ui;
 mod watcher;
 
-use std::io;
+use std::io::{self, Write};
 use std::panic;
 use std::path::Path;
+use std::sync::atomic::AtomicBool;
 use std::sync::mpsc as std_mpsc;
-use std::sync::Arc;
-use std::time::Duration;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use crossterm::{
     event::{self, DisableBracketedPaste, EnableBracketedPaste, Event},
@@ -27,17 +32,24 @@ use crossterm::{
 };
 use ratatui::{backend::CrosstermBackend, Terminal};
 use tokio::sync::mpsc;
+use trefm_core::config::settings::{Config, LoggingConfig, ScannerConfig};
+use trefm_core::config::validate::ConfigError;
+use trefm_core::config::TemplateWrite;
 use trefm_core::DuplicateCache;
 use trefm_core::{RemoteSession, SftpConfig};
 
+use trefm_core::fs::search::ContentSearchOptions;
 use trefm_core::nav::filter::{SortDirection, SortField};
 
-use crate::app::{App, AppMode, ConfirmAction};
+use crate::app::{App, AppMode, ConfirmAction, Launch};
 use crate::background::{
-    cache_path, default_scan_root, spawn_cache_validator, spawn_duplicate_scanner,
-    spawn_periodic_scanner, ScanMessage, ScanStatus,
+    cache_path, default_scan_root, spawn_cache_validator, spawn_content_search,
+    spawn_duplicate_scanner, spawn_incremental_update, spawn_local_duplicate_scan,
+    spawn_periodic_scanner, spawn_run_command, CommandMessage, ContentSearchMessage, ScanMessage,
+    ScanStatus,
 };
 use crate::input::{handle_key, resolve_action, InputAction, InputState};
+use crate::oplog::OperationKind;
 use crate::render::render;
 use crate::watcher::{DirWatcher, WatchMessage};
 
@@ -86,19 +98,141 @@ fn install_panic_hook() {
     }));
 }
 
-#[tokio::main]
-async fn main() -> anyhow::Result<()> {
-    // Initialize tracing (logs to file to avoid terminal interference)
+/// `default.toml` in `./config` if present, else in the user config directory.
+fn config_path() -> std::path::PathBuf {
+    let cfg_dir = if Path::new("config").exists() {
+        std::path::PathBuf::from("config")
+    } else {
+        trefm_core::paths::config_dir()
+    };
+    cfg_dir.join("default.toml")
+}
+
+/// Loads the config leniently; the second value describes what a strict
+/// load rejected, if anything. A missing file is not a problem.
+fn load_config(path: &Path) -> (Config, Option<String>) {
+    match Config::load_strict(path) {
+        Ok(config) => (config, None),
+        Err(ConfigError::Read { ref source, .. }) if source.kind() == io::ErrorKind::NotFound => {
+            (Config::default(), None)
+        }
+        Err(e) => (
+            Config::load(path).unwrap_or_default(),
+            Some(format!("{}: {e}", path.display())),
+        ),
+    }
+}
+
+/// `trefm-tui --check-config`: validates the config file and exits.
+fn check_config() -> ! {
+    let path = config_path();
+    match Config::load_strict(&path) {
+        Ok(_) => {
+            println!("{}: ok", path.display());
+            std::process::exit(0);
+        }
+        Err(e) => {
+            eprintln!("{}: {e}", path.display());
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Sends tracing output to the `[logging]` file so it never reaches the terminal.
+fn init_logging(logging: &LoggingConfig) -> anyhow::Result<()> {
+    let parsed = logging.level.parse::<tracing::Level>();
+    let level = *parsed.as_ref().unwrap_or(&tracing::Level::DEBUG);
+    let file = log_file::RotatingFile::open(
+        Path::new(&logging.file),
+        logging.max_size_mb.saturating_mul(1024 * 1024),
+        logging.max_files,
+    )?;
     tracing_subscriber::fmt()
-        .with_writer(|| {
-            std::fs::OpenOptions::new()
-                .create(true)
-                .append(true)
-                .open("/tmp/trefm.log")
-                .expect("failed to open log file")
-        })
-        .with_max_level(tracing::Level::DEBUG)
+        .with_writer(Mutex::new(file))
+        .with_ansi(false)
+        .with_max_level(level)
         .init();
+    if parsed.is_err() {
+        tracing::warn!("Unknown log level {:?}, using debug", logging.level);
+    }
+    Ok(())
+}
+
+/// Writes one default config file into a directory.
+type TemplateWriter = fn(&Path) -> trefm_core::CoreResult<std::path::PathBuf>;
+
+/// Writes one default config file into a directory, replacing what is there.
+type ForcedTemplateWriter = fn(&Path) -> trefm_core::CoreResult<TemplateWrite>;
+
+/// `trefm-tui --init`: writes default config files that don't exist yet.
+///
+/// With `--force`, existing files are replaced too; edited ones are backed
+/// up to `*.bak` first.
+fn init_config(force: bool) -> ! {
+    let dir = trefm_core::paths::config_dir();
+    let writers: [(&str, TemplateWriter, ForcedTemplateWriter); 3] = [
+        (
+            "default.toml",
+            Config::write_default,
+            Config::write_default_forced,
+        ),
+        (
+            "keymap.toml",
+            trefm_core::Keymap::write_default,
+            trefm_core::Keymap::write_default_forced,
+        ),
+        (
+            "theme.toml",
+            trefm_core::Theme::write_default,
+            trefm_core::Theme::write_default_forced,
+        ),
+    ];
+    let mut failed = false;
+    for (name, write, write_forced) in writers {
+        let result = if force {
+            write_forced(&dir)
+        } else {
+            write(&dir).map(TemplateWrite::Created)
+        };
+        match result {
+            Ok(TemplateWrite::Created(path)) => println!("created {}", path.display()),
+            Ok(TemplateWrite::Unchanged(path)) => {
+                println!("kept    {} (same as the default)", path.display())
+            }
+            Ok(TemplateWrite::Replaced { path, backup }) => println!(
+                "replaced {} (old copy in {})",
+                path.display(),
+                backup.display()
+            ),
+            Err(trefm_core::CoreError::AlreadyExists(path)) => println!(
+                "kept    {} (already exists; --force replaces it)",
+                path.display()
+            ),
+            Err(e) => {
+                eprintln!("{name}: {e}");
+                failed = true;
+            }
+        }
+    }
+    std::process::exit(i32::from(failed));
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    match std::env::args().nth(1).as_deref() {
+        Some("--check-config") => check_config(),
+        Some("--init") => init_config(std::env::args().nth(2).as_deref() == Some("--force")),
+        _ => {}
+    }
+
+    let (config, config_warning) = load_config(&config_path());
+    if let Err(e) = init_logging(&config.logging) {
+        eprintln!("Failed to open log file {}: {e}", config.logging.file);
+        std::process::exit(1);
+    }
+    if let Some(ref warning) = config_warning {
+        tracing::warn!("Invalid config, falling back to lenient load: {warning}");
+    }
 
     install_panic_hook();
 
@@ -118,7 +252,7 @@ async fn main() -> anyhow::Result<()> {
         .map(std::path::PathBuf::from)
         .unwrap_or_else(|| std::env::current_dir().expect("failed to get current directory"));
 
-    let result = run_app(&mut terminal, &start_dir, picker).await;
+    let result = run_app(&mut terminal, &start_dir, picker, config, config_warning).await;
 
     restore_terminal(&mut terminal)?;
 
@@ -134,6 +268,8 @@ async fn run_app(
     terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
     start_dir: &std::path::Path,
     picker: Option<ratatui_image::picker::Picker>,
+    config: Config,
+    config_warning: Option<String>,
 ) -> anyhow::Result<()> {
     let (scan_tx, mut scan_rx) = mpsc::unbounded_channel::<ScanMessage>();
     let cache_file = cache_path();
@@ -143,43 +279,49 @@ async fn run_app(
     let cache = DuplicateCache::load(&cache_file);
 
     let mut app = App::new(start_dir)?;
+    if let Some(warning) = config_warning {
+        app = app.with_status(format!("Config: {warning}"));
+    }
     let mut input_state = InputState::new();
     let mut image_state = picker.map(image_preview::ImagePreviewState::new);
 
     let (remote_tx, mut remote_rx) = mpsc::unbounded_channel::<RemoteMessage>();
     let mut remote_session: Option<Arc<RemoteSession>> = None;
 
+    let (search_tx, mut search_rx) = mpsc::unbounded_channel::<ContentSearchMessage>();
+
+    let (command_tx, mut command_rx) = mpsc::unbounded_channel::<CommandMessage>();
+
     let (terminal_tx, mut terminal_rx) = mpsc::unbounded_channel::<terminal_emu::TerminalMessage>();
     let mut terminal_emu: Option<terminal_emu::TerminalEmulator> = None;
 
-    let terminal_config = {
-        let cfg_dir = if std::path::Path::new("config").exists() {
-            std::path::PathBuf::from("config")
-        } else {
-            std::env::var("HOME")
-                .map(std::path::PathBuf::from)
-                .unwrap_or_else(|_| std::path::PathBuf::from("/"))
-                .join(".config")
-                .join("trefm")
-        };
-        trefm_core::config::settings::Config::load(&cfg_dir.join("default.toml"))
-            .map(|c| c.terminal)
-            .unwrap_or_default()
-    };
+    let terminal_config = config.terminal.clone();
+    let log_path = std::path::PathBuf::from(&config.logging.file);
+    let mut log_tail: Option<log_tail::LogTail> = None;
+    let poll_interval = config.watcher.poll_interval();
+    let scanner = config.scanner.clone();
 
-    if !cache.is_empty() {
+    // A cache hashed with a different algorithm is dropped; the scan below rebuilds it.
+    if !cache.is_empty() && !cache.is_stale_for(scanner.hash_algo) {
         app = app.with_duplicate_cache(cache.clone());
-        spawn_cache_validator(cache, scan_tx.clone());
+        spawn_cache_validator(cache, app.duplicate_generation(), scan_tx.clone());
     }
 
     // Always start a background scan
-    spawn_duplicate_scanner(scan_root.clone(), scan_tx.clone());
+    spawn_duplicate_scanner(scan_root.clone(), scanner.clone(), scan_tx.clone());
 
     // Periodic re-scan every 30 minutes
-    spawn_periodic_scanner(scan_root, Duration::from_secs(1800), scan_tx.clone());
+    spawn_periodic_scanner(
+        scan_root,
+        Duration::from_secs(1800),
+        scanner.clone(),
+        scan_tx.clone(),
+    );
 
     // Set up file watcher
     let (watch_tx, watch_rx) = std_mpsc::channel::<WatchMessage>();
+    let poll_tx = watch_tx.clone();
+    let mut last_poll = Instant::now();
     let mut dir_watcher = DirWatcher::new(watch_tx).ok();
     if let Some(ref mut w) = dir_watcher {
         let _ = w.watch(app.panel().current_dir());
@@ -190,30 +332,108 @@ async fn run_app(
         // 1. Drain background scan messages
         while let Ok(msg) = scan_rx.try_recv() {
             app = match msg {
-                ScanMessage::ScanStarted => app.with_scan_status(ScanStatus::Scanning),
+                ScanMessage::ScanStarted => app
+                    .with_operation_started()
+                    .with_scan_status(ScanStatus::Scanning),
                 ScanMessage::ScanComplete(new_cache) => {
                     new_cache.save(&cache_file);
-                    app.with_duplicate_cache(new_cache)
+                    app.with_operation_finished()
+                        .with_duplicate_cache(new_cache)
                         .with_scan_status(ScanStatus::Idle)
                 }
-                ScanMessage::ValidationComplete(validated) => {
-                    validated.save(&cache_file);
-                    app.with_duplicate_cache(validated)
+                ScanMessage::ValidationComplete { base, cache } => {
+                    if base == app.duplicate_generation() {
+                        cache.save(&cache_file);
+                    }
+                    app.with_derived_duplicates(base, cache)
+                }
+                ScanMessage::IncrementalComplete {
+                    base,
+                    changed,
+                    cache,
+                } => {
+                    if base == app.duplicate_generation() {
+                        cache.save(&cache_file);
+                    } else {
+                        // The cache was replaced meanwhile; redo the update on it
+                        spawn_incremental_update(
+                            app.duplicate_cache().clone(),
+                            app.duplicate_generation(),
+                            changed,
+                            scan_tx.clone(),
+                        );
+                    }
+                    app.with_derived_duplicates(base, cache)
                 }
                 ScanMessage::ScanError(e) => {
                     tracing::error!("Scan error: {e}");
-                    app.with_scan_status(ScanStatus::Idle)
+                    app.with_operation_finished()
+                        .with_scan_status(ScanStatus::Idle)
+                }
+                ScanMessage::LocalScanComplete { id, cache } => app
+                    .with_operation_finished()
+                    .with_local_duplicates(id, cache),
+                ScanMessage::LocalScanError { id, message } => app
+                    .with_operation_finished()
+                    .with_local_duplicate_error(id, message),
+            };
+        }
+
+        // 1a. Drain content search messages
+        while let Ok(msg) = search_rx.try_recv() {
+            app = match msg {
+                ContentSearchMessage::Progress { id, progress } => {
+                    app.with_content_search_progress(id, progress)
                 }
+                // Every spawned search ends with exactly one Complete or Error,
+                // stale or not, so the in-flight count is settled here.
+                ContentSearchMessage::Complete { id, result } => app
+                    .with_operation_finished()
+                    .with_content_search_result(id, result),
+                ContentSearchMessage::Error { id, message } => app
+                    .with_operation_finished()
+                    .with_content_search_error(id, message),
+            };
+        }
+
+        // 1b. Drain finished commands
+        while let Ok(msg) = command_rx.try_recv() {
+            app = match msg {
+                CommandMessage::Finished { command, output } => app
+                    .with_operation_finished()
+                    .with_command_output(command, output),
+                CommandMessage::Failed { command, message } => app
+                    .with_operation_finished()
+                    .with_status(format!("{command}: {message}")),
             };
         }
 
-        // 2. Drain file watcher messages
+        // 2. Poll fallback for filesystems that don't deliver watch events
+        if let Some(interval) = poll_interval {
+            if last_poll.elapsed() >= interval {
+                last_poll = Instant::now();
+                if !app.is_remote() {
+                    let _ = poll_tx.send(WatchMessage::Changed(Vec::new()));
+                }
+            }
+        }
+
+        // 2a. Drain file watcher messages
         while let Ok(msg) = watch_rx.try_recv() {
             match msg {
-                WatchMessage::Changed => {
+                WatchMessage::Changed(paths) => {
+                    app = app.with_repo_roots_invalidated(&paths);
                     if let Ok(new_panel) = app.panel().refresh() {
                         app = app.with_panel(new_panel);
                     }
+                    if !paths.is_empty() && !app.duplicate_cache().is_empty() {
+                        spawn_incremental_update(
+                            app.duplicate_cache().clone(),
+                            app.duplicate_generation(),
+                            paths,
+                            scan_tx.clone(),
+                        );
+                    }
                     if let Some(ref mut img) = image_state {
                         img.invalidate();
                     }
@@ -245,7 +465,7 @@ async fn run_app(
                         .with_mode(AppMode::Normal)
                         .with_remote_context(Some(remote_ctx))
                         .with_remote_directory(std::path::PathBuf::from(&home_dir), initial_entries)
-                        .with_status(format!("Connected to {label}"));
+                        .with_success(format!("Connected to {label}"));
                 }
                 RemoteMessage::ConnectionFailed(err) => {
                     let mut form = app.connect_form().clone();
@@ -275,7 +495,18 @@ async fn run_app(
             match msg {
                 terminal_emu::TerminalMessage::Output(bytes) => {
                     if let Some(ref mut emu) = terminal_emu {
-                        emu.screen.process(&bytes);
+                        let reported = emu.screen.process(&bytes);
+                        if let Some(dir) = reported.filter(|_| terminal_config.follow_shell_cwd) {
+                            app = app.follow_terminal_cwd(&dir);
+                            // Don't echo a `cd` back to the shell that just moved.
+                            let current_dir = app.panel().current_dir().to_path_buf();
+                            if current_dir != prev_dir {
+                                if let Some(ref mut w) = dir_watcher {
+                                    let _ = w.watch(&current_dir);
+                                }
+                                prev_dir = current_dir;
+                            }
+                        }
                     }
                 }
                 terminal_emu::TerminalMessage::Exited(_) => {
@@ -288,20 +519,55 @@ async fn run_app(
             }
         }
 
+        // 2d. Follow the log file while the log viewer is open
+        if matches!(app.mode(), AppMode::LogViewer { .. }) {
+            let opened = log_tail.is_none();
+            let tail = log_tail.get_or_insert_with(|| {
+                log_tail::LogTail::new(&log_path, log_tail::LogTail::DEFAULT_MAX_LINES)
+            });
+            match tail.poll() {
+                Ok(changed) if changed || opened => {
+                    // Open at the end and stick to it unless the user has scrolled up.
+                    let height = terminal.size()?.height.saturating_sub(1) as usize;
+                    let bottom = |len: usize| len.saturating_sub(height);
+                    let scroll = app.pager_scroll().unwrap_or(0);
+                    let scroll = if opened || scroll >= bottom(app.pager_lines().len()) {
+                        bottom(tail.lines().len())
+                    } else {
+                        scroll
+                    };
+                    let title = format!("Log: {}", tail.path().display());
+                    let lines = tail.lines().iter().cloned().collect();
+                    app = app.with_log_lines(title, lines, scroll);
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    app = app
+                        .with_mode(AppMode::Normal)
+                        .with_status(format!("Cannot read log: {e}"));
+                }
+            }
+        } else {
+            log_tail = None;
+        }
+
         // Resize terminal if needed (before render)
         if let Some(ref mut emu) = terminal_emu {
             if app.terminal_visible() {
                 let size = terminal.size().unwrap_or_default();
-                let term_rows = ((size.height as f32) * 0.3).max(3.0) as u16;
-                let term_rows = term_rows.saturating_sub(2).max(1);
-                let term_cols = size.width.saturating_sub(2).max(1);
+                let (term_cols, term_rows) =
+                    app.terminal_layout().pty_size(size.width, size.height);
                 emu.resize(term_cols, term_rows);
             }
         }
 
+        app = app.with_expired_status_cleared(Instant::now());
+
         // 3. Render
-        let term_screen = terminal_emu.as_ref().map(|e| e.screen.screen());
-        terminal.draw(|f| render(f, &app, image_state.as_mut(), term_screen))?;
+        {
+            let term_view = terminal_emu.as_mut().map(|e| e.screen.view());
+            terminal.draw(|f| render(f, &app, image_state.as_mut(), term_view.as_deref()))?;
+        }
 
         if app.should_quit() {
             break;
@@ -334,6 +600,24 @@ async fn run_app(
                     }
                     InputAction::EnterMode(AppMode::RecentFiles) => app.load_recent_files(),
                     InputAction::EnterMode(AppMode::DuplicateFiles) => app.show_duplicate_files(),
+                    InputAction::ScanDuplicatesHere => {
+                        scan_duplicates_here(app, &scanner, &scan_tx)
+                    }
+                    InputAction::EnterMode(AppMode::ContentSearchInput(_)) if app.is_remote() => {
+                        app.with_status("Content search not supported in remote mode".to_string())
+                    }
+                    InputAction::EnterMode(AppMode::RunCommand(_)) if app.is_remote() => {
+                        app.with_status("Run command not supported in remote mode".to_string())
+                    }
+                    InputAction::EnterMode(AppMode::RecentDirs { .. }) if app.is_remote() => app
+                        .with_status("Recent directories not supported in remote mode".to_string()),
+                    InputAction::EnterMode(AppMode::GitChanges) => {
+                        if app.is_remote() {
+                            app.with_status("Git changes not supported in remote mode".to_string())
+                        } else {
+                            app.load_git_changes()
+                        }
+                    }
                     InputAction::EnterMode(AppMode::SortSelect { .. }) => {
                         let current_idx = match app.panel().sort_field() {
                             SortField::Name => 0,
@@ -353,7 +637,10 @@ async fn run_app(
                                     .with_remote_context(None)
                                     .with_mode(AppMode::Normal)
                                     .with_status("Disconnected from remote server".to_string());
-                                match crate::app::PanelState::from_dir(start_dir) {
+                                let show_hidden = disconnected.panel().show_hidden();
+                                match crate::app::PanelState::from_dir(start_dir)
+                                    .and_then(|panel| panel.with_show_hidden(show_hidden))
+                                {
                                     Ok(panel) => disconnected.with_panel(panel),
                                     Err(_) => disconnected,
                                 }
@@ -363,7 +650,7 @@ async fn run_app(
                         }
                         _ => app.with_mode(mode),
                     },
-                    InputAction::Quit => app.with_quit(),
+                    InputAction::Quit => app.request_quit(),
                     InputAction::CursorTop => {
                         let new_panel = app.panel().clone().with_cursor_top();
                         app.with_panel(new_panel)
@@ -386,14 +673,11 @@ async fn run_app(
                             }
                         }
                     }
-                    InputAction::RequestDelete => {
-                        if let Some(entry) = app.panel().selected_entry() {
-                            let path = entry.path().to_path_buf();
-                            app.with_mode(AppMode::Confirm(ConfirmAction::Delete(vec![path])))
-                        } else {
-                            app
-                        }
-                    }
+                    InputAction::RequestExecute => app.request_execute(),
+                    InputAction::CopyTree => copy_tree(app, terminal),
+                    InputAction::CopyGitPath => copy_git_path(app, terminal),
+                    InputAction::RequestDelete => app.request_delete(),
+                    InputAction::DuplicateInPlace => app.duplicate_in_place(),
                     InputAction::EditFile => {
                         if app.is_remote() {
                             app.with_status("Edit not supported in remote mode".to_string())
@@ -417,13 +701,49 @@ async fn run_app(
                             }
                         }
                     }
-                    InputAction::ConfirmApproved => handle_confirm_approved(app, &cache_file),
+                    InputAction::ConfirmApproved => match app.mode().clone() {
+                        AppMode::Confirm(ConfirmAction::Execute(path)) => {
+                            execute_file(app.with_mode(AppMode::Normal), &path, &command_tx)
+                        }
+                        _ => handle_confirm_approved(app, &cache_file),
+                    },
+                    InputAction::Yank => {
+                        if app.is_remote() {
+                            app.with_status("Yank not supported in remote mode".to_string())
+                        } else {
+                            app.yank_selected()
+                        }
+                    }
+                    InputAction::Paste => {
+                        if app.is_remote() {
+                            app.with_status("Paste not supported in remote mode".to_string())
+                        } else {
+                            app.paste()
+                        }
+                    }
+                    InputAction::PasteResolve(choice) => app.paste_resolve(choice),
+                    InputAction::CopyToOtherPanel => app.copy_to_other_panel(),
+                    InputAction::MoveToOtherPanel => app.move_to_other_panel(),
+                    InputAction::SwapPanels => app.with_swapped_panels(),
+                    InputAction::SyncPanels => app.with_synced_panels(),
+                    InputAction::DiffOtherPanel => app.enter_diff_with_other_panel(),
+                    InputAction::CycleCategoryFilter => app.cycle_category_filter(),
+                    InputAction::ToggleSortDirection => app.toggle_sort_direction(),
+                    InputAction::ReverseListing => app.reverse_listing(),
+                    InputAction::ToggleDirsOnly => app.toggle_dirs_only(),
+                    InputAction::StartFilter => app.start_filter(),
+                    InputAction::TogglePin => app.toggle_pin(),
+                    InputAction::FollowSymlink => app.follow_symlink(),
                     // Search actions
                     InputAction::SearchChar(c) => app.search_push_char(c),
                     InputAction::SearchBackspace => app.search_pop_char(),
                     InputAction::SearchDown => app.search_move_down(),
                     InputAction::SearchUp => app.search_move_up(),
                     InputAction::SearchConfirm => app.search_confirm(),
+                    InputAction::FilterChar(c) => app.filter_push_char(c),
+                    InputAction::FilterBackspace => app.filter_pop_char(),
+                    InputAction::FilterAccept => app.accept_filter(),
+                    InputAction::FilterClear => app.clear_filter(),
                     // Bookmark add actions
                     InputAction::BookmarkChar(c) => {
                         if let AppMode::BookmarkAdd(ref label) = app.mode().clone() {
@@ -491,11 +811,122 @@ async fn run_app(
                     InputAction::RecentDown => app.recent_move_down(),
                     InputAction::RecentUp => app.recent_move_up(),
                     InputAction::RecentConfirm => app.recent_confirm(),
+                    // Recent directories actions
+                    InputAction::RecentDirsChar(c) => app.recent_dirs_push_char(c),
+                    InputAction::RecentDirsBackspace => app.recent_dirs_pop_char(),
+                    InputAction::RecentDirsDown => app.recent_dirs_move_down(),
+                    InputAction::RecentDirsUp => app.recent_dirs_move_up(),
+                    InputAction::RecentDirsConfirm => app.recent_dirs_confirm(),
                     // Duplicate files actions
                     InputAction::DuplicateDown => app.duplicate_move_down(),
                     InputAction::DuplicateUp => app.duplicate_move_up(),
                     InputAction::DuplicateConfirm => app.duplicate_confirm(),
                     InputAction::DuplicateDelete => app.duplicate_delete_selected(),
+                    InputAction::DuplicateIgnore => app.duplicate_ignore_selected(),
+                    InputAction::DuplicateSort => app.duplicate_cycle_sort(),
+                    // Content search actions
+                    InputAction::ContentSearchChar(c) => {
+                        if let AppMode::ContentSearchInput(ref query) = app.mode().clone() {
+                            app.with_mode(AppMode::ContentSearchInput(format!("{query}{c}")))
+                        } else {
+                            app
+                        }
+                    }
+                    InputAction::ContentSearchBackspace => {
+                        if let AppMode::ContentSearchInput(ref query) = app.mode().clone() {
+                            let mut new_query = query.clone();
+                            new_query.pop();
+                            app.with_mode(AppMode::ContentSearchInput(new_query))
+                        } else {
+                            app
+                        }
+                    }
+                    InputAction::ContentSearchStart => match app.mode().clone() {
+                        AppMode::ContentSearchInput(query) if !query.is_empty() => {
+                            let cancel = Arc::new(AtomicBool::new(false));
+                            let root = app.panel().current_dir().to_path_buf();
+                            let options = ContentSearchOptions {
+                                show_hidden: app.panel().show_hidden(),
+                                exclude: app.search_exclude().clone(),
+                                ..ContentSearchOptions::default()
+                            };
+                            let app = app
+                                .content_search_start(query.clone(), cancel.clone())
+                                .with_operation_started();
+                            spawn_content_search(
+                                app.content_search().id,
+                                root,
+                                query,
+                                options,
+                                cancel,
+                                search_tx.clone(),
+                            );
+                            app
+                        }
+                        AppMode::ContentSearchInput(_) => app.with_mode(AppMode::Normal),
+                        _ => app,
+                    },
+                    InputAction::ContentSearchDown => app.content_search_move_down(),
+                    InputAction::ContentSearchUp => app.content_search_move_up(),
+                    InputAction::ContentSearchConfirm => app.content_search_confirm(),
+                    InputAction::ContentSearchStop => app.content_search_stop(),
+                    // Run command actions
+                    InputAction::RunCommandChar(c) => {
+                        if let AppMode::RunCommand(ref template) = app.mode().clone() {
+                            app.with_mode(AppMode::RunCommand(format!("{template}{c}")))
+                        } else {
+                            app
+                        }
+                    }
+                    InputAction::RunCommandBackspace => {
+                        if let AppMode::RunCommand(ref template) = app.mode().clone() {
+                            let mut new_template = template.clone();
+                            new_template.pop();
+                            app.with_mode(AppMode::RunCommand(new_template))
+                        } else {
+                            app
+                        }
+                    }
+                    InputAction::RunCommandSubmit => match app.mode().clone() {
+                        AppMode::RunCommand(template) => {
+                            let app = app.with_mode(AppMode::Normal);
+                            match app.run_command_argv(&template) {
+                                Ok(argv) => {
+                                    let cwd = app.panel().current_dir().to_path_buf();
+                                    spawn_run_command(
+                                        template.clone(),
+                                        argv,
+                                        cwd,
+                                        command_tx.clone(),
+                                    );
+                                    app.with_operation_started()
+                                        .with_status(format!("Running: {template}"))
+                                }
+                                Err(msg) => app.with_status(msg),
+                            }
+                        }
+                        _ => app,
+                    },
+                    // Git changes actions
+                    InputAction::GitChangesDown => app.git_changes_move_down(),
+                    InputAction::GitChangesUp => app.git_changes_move_up(),
+                    InputAction::GitChangesConfirm => app.git_changes_confirm(),
+                    // Operation log actions
+                    InputAction::OperationLogDown => app.operation_log_scroll_down(),
+                    InputAction::OperationLogUp => app.operation_log_scroll_up(),
+                    InputAction::NotificationsDown => app.notifications_scroll_down(),
+                    InputAction::NotificationsUp => app.notifications_scroll_up(),
+                    // Rename actions
+                    InputAction::RenameChar(c) => app.rename_push_char(c),
+                    InputAction::RenameBackspace => app.rename_pop_char(),
+                    InputAction::RenameConfirm => {
+                        if app.is_remote() {
+                            app.with_mode(AppMode::Normal)
+                                .with_status("Rename not supported in remote mode".to_string())
+                        } else {
+                            app.rename_confirm()
+                        }
+                    }
                     // Sort select actions
                     InputAction::SortSelectDown => {
                         if let AppMode::SortSelect { selected } = app.mode() {
@@ -522,21 +953,15 @@ async fn run_app(
                                 _ => SortField::Type,
                             };
                             let direction = app.panel().sort_direction();
-                            match app.panel().with_sort(field, direction) {
-                                Ok(new_panel) => {
-                                    let msg = format!(
-                                        "Sort: {:?} {:?}",
-                                        new_panel.sort_field(),
-                                        new_panel.sort_direction()
-                                    );
-                                    app.with_mode(AppMode::Normal)
-                                        .with_panel(new_panel)
-                                        .with_status(msg)
-                                }
-                                Err(e) => app
-                                    .with_mode(AppMode::Normal)
-                                    .with_status(format!("Error: {e}")),
-                            }
+                            let new_panel = app.panel().with_sort(field, direction);
+                            let msg = format!(
+                                "Sort: {:?} {:?}",
+                                new_panel.sort_field(),
+                                new_panel.sort_direction()
+                            );
+                            app.with_mode(AppMode::Normal)
+                                .with_sorted_panel(new_panel)
+                                .with_status(msg)
                         } else {
                             app
                         }
@@ -549,17 +974,10 @@ async fn run_app(
                                 2 => SortField::Date,
                                 _ => SortField::Type,
                             };
-                            match app.panel().with_sort(field, SortDirection::Ascending) {
-                                Ok(new_panel) => {
-                                    let msg = format!("Sort: {:?} Ascending", field);
-                                    app.with_mode(AppMode::Normal)
-                                        .with_panel(new_panel)
-                                        .with_status(msg)
-                                }
-                                Err(e) => app
-                                    .with_mode(AppMode::Normal)
-                                    .with_status(format!("Error: {e}")),
-                            }
+                            let new_panel = app.panel().with_sort(field, SortDirection::Ascending);
+                            app.with_mode(AppMode::Normal)
+                                .with_sorted_panel(new_panel)
+                                .with_status(format!("Sort: {:?} Ascending", field))
                         } else {
                             app
                         }
@@ -572,17 +990,10 @@ async fn run_app(
                                 2 => SortField::Date,
                                 _ => SortField::Type,
                             };
-                            match app.panel().with_sort(field, SortDirection::Descending) {
-                                Ok(new_panel) => {
-                                    let msg = format!("Sort: {:?} Descending", field);
-                                    app.with_mode(AppMode::Normal)
-                                        .with_panel(new_panel)
-                                        .with_status(msg)
-                                }
-                                Err(e) => app
-                                    .with_mode(AppMode::Normal)
-                                    .with_status(format!("Error: {e}")),
-                            }
+                            let new_panel = app.panel().with_sort(field, SortDirection::Descending);
+                            app.with_mode(AppMode::Normal)
+                                .with_sorted_panel(new_panel)
+                                .with_status(format!("Sort: {:?} Descending", field))
                         } else {
                             app
                         }
@@ -590,47 +1001,43 @@ async fn run_app(
                     // Pager actions
                     InputAction::EnterPager => app.enter_pager(),
                     InputAction::PagerDown => {
-                        if let AppMode::Pager { scroll } = app.mode() {
-                            let max = app.pager_lines().len().saturating_sub(1);
-                            let next = (*scroll + 1).min(max);
-                            app.with_mode(AppMode::Pager { scroll: next })
+                        if let Some(scroll) = app.pager_scroll() {
+                            let max = app.pager_len().saturating_sub(1);
+                            let next = (scroll + 1).min(max);
+                            app.with_pager_scroll(next)
                         } else {
                             app
                         }
                     }
                     InputAction::PagerUp => {
-                        if let AppMode::Pager { scroll } = *app.mode() {
-                            app.with_mode(AppMode::Pager {
-                                scroll: scroll.saturating_sub(1),
-                            })
+                        if let Some(scroll) = app.pager_scroll() {
+                            app.with_pager_scroll(scroll.saturating_sub(1))
                         } else {
                             app
                         }
                     }
                     InputAction::PagerHalfDown => {
-                        if let AppMode::Pager { scroll } = app.mode() {
+                        if let Some(scroll) = app.pager_scroll() {
                             let half = (terminal.size()?.height as usize) / 2;
-                            let max = app.pager_lines().len().saturating_sub(1);
-                            let next = (*scroll + half).min(max);
-                            app.with_mode(AppMode::Pager { scroll: next })
+                            let max = app.pager_len().saturating_sub(1);
+                            let next = (scroll + half).min(max);
+                            app.with_pager_scroll(next)
                         } else {
                             app
                         }
                     }
                     InputAction::PagerHalfUp => {
-                        if let AppMode::Pager { scroll } = *app.mode() {
+                        if let Some(scroll) = app.pager_scroll() {
                             let half = (terminal.size()?.height as usize) / 2;
-                            app.with_mode(AppMode::Pager {
-                                scroll: scroll.saturating_sub(half),
-                            })
+                            app.with_pager_scroll(scroll.saturating_sub(half))
                         } else {
                             app
                         }
                     }
-                    InputAction::PagerTop => app.with_mode(AppMode::Pager { scroll: 0 }),
+                    InputAction::PagerTop => app.with_pager_scroll(0),
                     InputAction::PagerBottom => {
-                        let max = app.pager_lines().len().saturating_sub(1);
-                        app.with_mode(AppMode::Pager { scroll: max })
+                        let max = app.pager_len().saturating_sub(1);
+                        app.with_pager_scroll(max)
                     }
                     // Command Palette actions
                     InputAction::CommandPaletteChar(c) => {
@@ -710,6 +1117,35 @@ async fn run_app(
                                         InputAction::EnterMode(AppMode::DuplicateFiles) => {
                                             app.show_duplicate_files()
                                         }
+                                        InputAction::ScanDuplicatesHere => {
+                                            scan_duplicates_here(app, &scanner, &scan_tx)
+                                        }
+                                        InputAction::EnterMode(AppMode::ContentSearchInput(_))
+                                            if app.is_remote() =>
+                                        {
+                                            app.with_status(
+                                                "Content search not supported in remote mode"
+                                                    .to_string(),
+                                            )
+                                        }
+                                        InputAction::EnterMode(AppMode::RunCommand(_))
+                                            if app.is_remote() =>
+                                        {
+                                            app.with_status(
+                                                "Run command not supported in remote mode"
+                                                    .to_string(),
+                                            )
+                                        }
+                                        InputAction::EnterMode(AppMode::GitChanges) => {
+                                            if app.is_remote() {
+                                                app.with_status(
+                                                    "Git changes not supported in remote mode"
+                                                        .to_string(),
+                                                )
+                                            } else {
+                                                app.load_git_changes()
+                                            }
+                                        }
                                         InputAction::EnterMode(AppMode::SortSelect { .. }) => {
                                             let current_idx = match app.panel().sort_field() {
                                                 SortField::Name => 0,
@@ -722,7 +1158,7 @@ async fn run_app(
                                             })
                                         }
                                         InputAction::EnterMode(mode) => app.with_mode(mode),
-                                        InputAction::Quit => app.with_quit(),
+                                        InputAction::Quit => app.request_quit(),
                                         InputAction::CursorTop => {
                                             let new_panel = app.panel().clone().with_cursor_top();
                                             app.with_panel(new_panel)
@@ -732,16 +1168,11 @@ async fn run_app(
                                                 app.panel().clone().with_cursor_bottom();
                                             app.with_panel(new_panel)
                                         }
-                                        InputAction::RequestDelete => {
-                                            if let Some(entry) = app.panel().selected_entry() {
-                                                let path = entry.path().to_path_buf();
-                                                app.with_mode(AppMode::Confirm(
-                                                    ConfirmAction::Delete(vec![path]),
-                                                ))
-                                            } else {
-                                                app
-                                            }
-                                        }
+                                        InputAction::RequestExecute => app.request_execute(),
+                                        InputAction::CopyTree => copy_tree(app, terminal),
+                                        InputAction::CopyGitPath => copy_git_path(app, terminal),
+                                        InputAction::RequestDelete => app.request_delete(),
+                                        InputAction::DuplicateInPlace => app.duplicate_in_place(),
                                         InputAction::GoHome => match std::env::var("HOME") {
                                             Ok(home) => match app
                                                 .panel()
@@ -755,6 +1186,28 @@ async fn run_app(
                                             ),
                                         },
                                         InputAction::EnterPager => app.enter_pager(),
+                                        InputAction::Yank if !app.is_remote() => {
+                                            app.yank_selected()
+                                        }
+                                        InputAction::Paste if !app.is_remote() => app.paste(),
+                                        InputAction::CopyToOtherPanel => app.copy_to_other_panel(),
+                                        InputAction::MoveToOtherPanel => app.move_to_other_panel(),
+                                        InputAction::SwapPanels => app.with_swapped_panels(),
+                                        InputAction::SyncPanels => app.with_synced_panels(),
+                                        InputAction::DiffOtherPanel => {
+                                            app.enter_diff_with_other_panel()
+                                        }
+                                        InputAction::CycleCategoryFilter => {
+                                            app.cycle_category_filter()
+                                        }
+                                        InputAction::ToggleSortDirection => {
+                                            app.toggle_sort_direction()
+                                        }
+                                        InputAction::ReverseListing => app.reverse_listing(),
+                                        InputAction::ToggleDirsOnly => app.toggle_dirs_only(),
+                                        InputAction::StartFilter => app.start_filter(),
+                                        InputAction::TogglePin => app.toggle_pin(),
+                                        InputAction::FollowSymlink => app.follow_symlink(),
                                         InputAction::EditFile => {
                                             if app.is_remote() {
                                                 app.with_status(
@@ -813,13 +1266,14 @@ async fn run_app(
                                                     .with_mode(AppMode::Terminal)
                                             } else {
                                                 let size = terminal.size().unwrap_or_default();
-                                                let cols = size.width.max(1);
-                                                let rows =
-                                                    ((size.height as f32) * 0.3).max(1.0) as u16;
+                                                let (cols, rows) = app
+                                                    .terminal_layout()
+                                                    .pty_size(size.width, size.height);
                                                 match terminal_emu::TerminalEmulator::spawn(
                                                     app.panel().current_dir(),
                                                     cols,
                                                     rows,
+                                                    &terminal_config,
                                                     terminal_tx.clone(),
                                                 ) {
                                                     Ok(emu) => {
@@ -834,10 +1288,25 @@ async fn run_app(
                                             }
                                         }
                                         InputAction::TabNew => app.with_new_tab(),
+                                        InputAction::OpenInNewTab if app.is_remote() => app
+                                            .with_status(
+                                                "Open in new tab not supported in remote mode"
+                                                    .to_string(),
+                                            ),
+                                        InputAction::OpenInNewTab => app.open_in_new_tab(),
                                         InputAction::TabClose => app.with_close_tab(),
+                                        InputAction::TabCloseOthers => {
+                                            app.request_close_other_tabs()
+                                        }
                                         InputAction::TabNext => app.with_next_tab(),
                                         InputAction::TabPrev => app.with_prev_tab(),
                                         InputAction::TabSelect(idx) => app.with_select_tab(idx),
+                                        InputAction::TabCopyToOtherPanel => {
+                                            app.with_tab_copied_to_other_panel()
+                                        }
+                                        InputAction::TabMoveToOtherPanel => {
+                                            app.with_tab_moved_to_other_panel()
+                                        }
                                         _ => app,
                                     }
                                 }
@@ -869,10 +1338,20 @@ async fn run_app(
                     }
                     // Tab actions
                     InputAction::TabNew => app.with_new_tab(),
+                    InputAction::OpenInNewTab if app.is_remote() => {
+                        app.with_status("Open in new tab not supported in remote mode".to_string())
+                    }
+                    InputAction::OpenInNewTab => app.open_in_new_tab(),
                     InputAction::TabClose => app.with_close_tab(),
+                    InputAction::TabCloseOthers => app.request_close_other_tabs(),
                     InputAction::TabNext => app.with_next_tab(),
                     InputAction::TabPrev => app.with_prev_tab(),
                     InputAction::TabSelect(idx) => app.with_select_tab(idx),
+                    InputAction::RenameTabChar(c) => app.rename_tab_push_char(c),
+                    InputAction::RenameTabBackspace => app.rename_tab_pop_char(),
+                    InputAction::RenameTabConfirm => app.rename_tab_confirm(),
+                    InputAction::TabCopyToOtherPanel => app.with_tab_copied_to_other_panel(),
+                    InputAction::TabMoveToOtherPanel => app.with_tab_moved_to_other_panel(),
                     InputAction::CommandPaletteCancel => app.with_mode(AppMode::Normal),
                     // Remote connect form actions
                     InputAction::RemoteConnectChar(c) => {
@@ -973,12 +1452,13 @@ async fn run_app(
                         } else {
                             // No terminal yet → spawn + show + focus
                             let size = terminal.size().unwrap_or_default();
-                            let cols = size.width.max(1);
-                            let rows = ((size.height as f32) * 0.3).max(1.0) as u16;
+                            let (cols, rows) =
+                                app.terminal_layout().pty_size(size.width, size.height);
                             match terminal_emu::TerminalEmulator::spawn(
                                 app.panel().current_dir(),
                                 cols,
                                 rows,
+                                &terminal_config,
                                 terminal_tx.clone(),
                             ) {
                                 Ok(emu) => {
@@ -990,6 +1470,48 @@ async fn run_app(
                         }
                     }
                     InputAction::TerminalUnfocus => app.with_mode(AppMode::Normal),
+                    InputAction::TerminalScrollUp => {
+                        if let Some(ref mut emu) = terminal_emu {
+                            emu.scroll_page_up();
+                        }
+                        app
+                    }
+                    InputAction::TerminalScrollDown => {
+                        if let Some(ref mut emu) = terminal_emu {
+                            emu.scroll_page_down();
+                        }
+                        app
+                    }
+                    InputAction::TerminalSelectStart => match terminal_emu {
+                        Some(ref mut emu) => {
+                            let selection =
+                                terminal_emu::selection::Selection::new(emu.cursor_position());
+                            app.with_mode(AppMode::TerminalSelect(selection))
+                        }
+                        None => app,
+                    },
+                    InputAction::TerminalSelectMove(d_row, d_col) => match terminal_emu {
+                        Some(ref emu) => {
+                            let (rows, cols) = emu.rows_cols();
+                            app.terminal_selection_moved(d_row, d_col, rows, cols)
+                        }
+                        None => app,
+                    },
+                    InputAction::TerminalSelectRestart => app.terminal_selection_restarted(),
+                    InputAction::TerminalSelectYank => match (app.mode(), terminal_emu.as_mut()) {
+                        (AppMode::TerminalSelect(selection), Some(emu)) => {
+                            let text = emu.selection_text(selection);
+                            let app = app.with_mode(AppMode::Terminal);
+                            match copy_to_clipboard(terminal, &text) {
+                                Ok(()) => app.with_success(format!(
+                                    "Copied {} character(s)",
+                                    text.chars().count()
+                                )),
+                                Err(e) => app.with_status(format!("Copy failed: {e}")),
+                            }
+                        }
+                        _ => app,
+                    },
                     InputAction::TerminalInput(key_event) => {
                         if let Some(ref mut emu) = terminal_emu {
                             emu.write_key(key_event);
@@ -999,6 +1521,13 @@ async fn run_app(
                     InputAction::None => app,
                 };
 
+                // Start an external program the command asked for
+                let (launched, launch) = app.take_launch();
+                app = match launch {
+                    Some(launch) => run_launch(terminal, launched, launch),
+                    None => launched,
+                };
+
                 // Update watcher if directory changed
                 let current_dir = app.panel().current_dir().to_path_buf();
                 if current_dir != prev_dir {
@@ -1022,32 +1551,39 @@ async fn run_app(
         }
     }
 
+    app.save_recent_dirs();
     Ok(())
 }
 
+/// Starts a duplicate scan of the active panel's directory, unless the app
+/// refused to (remote mode).
+fn scan_duplicates_here(
+    app: App,
+    scanner: &ScannerConfig,
+    tx: &mpsc::UnboundedSender<ScanMessage>,
+) -> App {
+    let previous = app.local_duplicate_scan().map(|scan| scan.id);
+    let app = app.start_local_duplicate_scan();
+    if let Some(scan) = app
+        .local_duplicate_scan()
+        .filter(|scan| Some(scan.id) != previous)
+    {
+        spawn_local_duplicate_scan(scan.id, scan.root.clone(), scanner.clone(), tx.clone());
+    }
+    app
+}
+
 fn handle_confirm_approved(app: App, cache_file: &Path) -> App {
     match app.mode().clone() {
-        AppMode::Confirm(ConfirmAction::Delete(paths)) => {
-            for path in &paths {
-                if let Err(e) = trefm_core::delete_file(path) {
-                    return app
-                        .with_mode(AppMode::Normal)
-                        .with_status(format!("Delete failed: {e}"));
-                }
-            }
-            let msg = format!("Deleted {} item(s)", paths.len());
-            match app.panel().refresh() {
-                Ok(new_panel) => app
-                    .with_mode(AppMode::Normal)
-                    .with_panel(new_panel)
-                    .with_status(msg),
-                Err(e) => app
-                    .with_mode(AppMode::Normal)
-                    .with_status(format!("Error refreshing: {e}")),
-            }
-        }
+        AppMode::Confirm(ConfirmAction::Delete(paths)) => app.delete_paths(&paths),
         AppMode::Confirm(ConfirmAction::DeleteDuplicate(path)) => {
-            if let Err(e) = trefm_core::delete_file(&path) {
+            let result = trefm_core::delete_file(&path).map_err(|e| e.to_string());
+            let app = app.with_operation_logged(
+                OperationKind::Delete,
+                vec![path.clone()],
+                result.clone(),
+            );
+            if let Err(e) = result {
                 return app
                     .with_mode(AppMode::DuplicateFiles)
                     .with_status(format!("Delete failed: {e}"));
@@ -1059,13 +1595,38 @@ fn handle_confirm_approved(app: App, cache_file: &Path) -> App {
                 trefm_core::nfc_string(&path.file_name().unwrap_or_default().to_string_lossy())
             );
             app.with_duplicate_cache(new_cache)
+                .with_local_duplicate_removed(&path)
                 .with_mode(AppMode::DuplicateFiles)
-                .with_status(msg)
+                .with_success(msg)
+        }
+        AppMode::Confirm(ConfirmAction::Quit) => app.with_quit(),
+        AppMode::Confirm(ConfirmAction::CloseOtherTabs) => app.close_other_tabs(),
+        AppMode::Confirm(ConfirmAction::RenameOverwrite { src, name }) => {
+            app.rename_overwrite(src, &name)
+        }
+        AppMode::Confirm(ConfirmAction::EnterLargeDir { dir, landing }) => {
+            app.enter_dir(&dir, landing)
         }
         _ => app.with_mode(AppMode::Normal),
     }
 }
 
+/// Runs an approved executable in its own directory; the output is shown
+/// like that of a `!` command.
+fn execute_file(app: App, path: &Path, tx: &mpsc::UnboundedSender<CommandMessage>) -> App {
+    match trefm_core::fs::executable_argv(path) {
+        Ok(argv) => {
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            let command = format!("./{}", trefm_core::nfc_string(&name));
+            let cwd = path.parent().unwrap_or(Path::new("/")).to_path_buf();
+            spawn_run_command(command.clone(), argv, cwd, tx.clone());
+            app.with_operation_started()
+                .with_status(format!("Running: {command}"))
+        }
+        Err(e) => app.with_status(format!("Error: {e}")),
+    }
+}
+
 /// Suspends the TUI, launches `$EDITOR` (or `nvim`) on the given file, then resumes.
 fn launch_editor(
     terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
@@ -1094,6 +1655,96 @@ fn launch_editor(
     }
 }
 
+/// Sets the host terminal's clipboard to `text` with OSC 52.
+fn copy_to_clipboard(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    text: &str,
+) -> io::Result<()> {
+    let sequence = terminal_emu::selection::osc52_sequence(text);
+    let backend = terminal.backend_mut();
+    backend.write_all(sequence.as_bytes())?;
+    backend.flush()
+}
+
+/// Copies the selected directory's tree to the clipboard.
+fn copy_tree(app: App, terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> App {
+    match app.selected_tree_text() {
+        Ok(text) => match copy_to_clipboard(terminal, &text) {
+            Ok(()) => {
+                let lines = text.lines().count();
+                app.with_success(format!("Copied tree ({lines} lines)"))
+            }
+            Err(e) => app.with_status(format!("Copy failed: {e}")),
+        },
+        Err(msg) => app.with_status(msg),
+    }
+}
+
+/// Copies the selected entry's path relative to its git repository root.
+fn copy_git_path(app: App, terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> App {
+    match app.selected_git_path() {
+        Ok(path) => match copy_to_clipboard(terminal, &path) {
+            Ok(()) => app.with_success(format!("Copied {path}")),
+            Err(e) => app.with_status(format!("Copy failed: {e}")),
+        },
+        Err(msg) => app.with_status(msg),
+    }
+}
+
+/// Starts the program a [`Launch`] asks for and reports the outcome.
+fn run_launch(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    app: App,
+    launch: Launch,
+) -> App {
+    match launch {
+        Launch::Editor(path) => match launch_editor(terminal, &path) {
+            // Refresh after editor exits
+            Ok(()) => match app.panel().refresh() {
+                Ok(new_panel) => app.with_panel(new_panel),
+                Err(_) => app,
+            },
+            Err(e) => app.with_status(format!("Editor failed: {e}")),
+        },
+        Launch::System(path) => match open_with_system(&path) {
+            Ok(()) => app.with_status(format!("Opened {}", path.display())),
+            Err(e) => app.with_status(format!("Open failed: {e}")),
+        },
+        Launch::Detached(argv) => match spawn_detached(&argv) {
+            Ok(()) => app.with_status(format!("Started {}", argv[0])),
+            Err(e) => app.with_status(format!("Open failed: {e}")),
+        },
+    }
+}
+
+/// Opens `path` with the desktop's default application without waiting for it.
+fn open_with_system(path: &Path) -> io::Result<()> {
+    let opener = if cfg!(target_os = "macos") {
+        "open"
+    } else {
+        "xdg-open"
+    };
+    spawn_detached(&[opener.to_string(), path.to_string_lossy().into_owned()])
+}
+
+/// Starts `argv` with its standard streams closed and does not wait for it.
+fn spawn_detached(argv: &[String]) -> io::Result<()> {
+    let (program, args) = argv
+        .split_first()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "empty command"))?;
+    let mut child = std::process::Command::new(program)
+        .args(args)
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()?;
+    // Reap the opener once it exits so it does not linger as a zombie.
+    std::thread::spawn(move || {
+        let _ = child.wait();
+    });
+    Ok(())
+}
+
 fn handle_remote_command(
     app: App,
     cmd: trefm_core::event::Command,
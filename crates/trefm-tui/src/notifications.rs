@@ -0,0 +1,160 @@
+//! Recent status messages, kept so long ones can be read in full.
+//!
+//! Every status message is also pushed onto [`Notifications`] with a
+//! [`NotificationLevel`], which also picks the message's colour in the
+//! status bar. The stack keeps at most
+//! [`Notifications::DEFAULT_CAPACITY`] messages; once full, the oldest is
+//! dropped for every new one.
+
+use std::collections::VecDeque;
+use std::time::SystemTime;
+
+/// How serious a notification is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationLevel {
+    Info,
+    Success,
+    Warning,
+    Error,
+}
+
+impl NotificationLevel {
+    /// Short label shown in the notifications panel.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Info => "info",
+            Self::Success => "ok",
+            Self::Warning => "warn",
+            Self::Error => "error",
+        }
+    }
+
+    /// Level for a plain status message: `Error: …` and `… failed …` are
+    /// errors, `… not supported …` is a warning, anything else is info.
+    /// Successes are never guessed; callers mark them explicitly.
+    pub fn of_message(msg: &str) -> Self {
+        let lower = msg.to_lowercase();
+        if lower.starts_with("error") || lower.contains("failed") {
+            Self::Error
+        } else if lower.contains("not supported") {
+            Self::Warning
+        } else {
+            Self::Info
+        }
+    }
+}
+
+/// A single status message.
+#[derive(Debug, Clone)]
+pub struct Notification {
+    pub level: NotificationLevel,
+    pub message: String,
+    pub timestamp: SystemTime,
+}
+
+/// Bounded stack of recent notifications. Oldest entries come first.
+#[derive(Debug, Clone)]
+pub struct Notifications {
+    entries: VecDeque<Notification>,
+    capacity: usize,
+}
+
+impl Default for Notifications {
+    fn default() -> Self {
+        Self::with_capacity(Self::DEFAULT_CAPACITY)
+    }
+}
+
+impl Notifications {
+    /// Number of notifications retained by [`Notifications::default`].
+    pub const DEFAULT_CAPACITY: usize = 100;
+
+    /// Creates an empty stack that keeps at most `capacity` notifications.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            entries: VecDeque::new(),
+            capacity: capacity.max(1),
+        }
+    }
+
+    /// Pushes a notification stamped with the current time, evicting the
+    /// oldest one if the stack is full.
+    pub fn push(&mut self, level: NotificationLevel, message: String) {
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(Notification {
+            level,
+            message,
+            timestamp: SystemTime::now(),
+        });
+    }
+
+    pub fn entries(&self) -> &VecDeque<Notification> {
+        &self.entries
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn messages(stack: &Notifications) -> Vec<&str> {
+        stack.entries().iter().map(|n| n.message.as_str()).collect()
+    }
+
+    #[test]
+    fn push_appends_in_order() {
+        let mut stack = Notifications::default();
+        stack.push(NotificationLevel::Info, "Copied".to_string());
+        stack.push(NotificationLevel::Error, "Delete failed".to_string());
+
+        assert_eq!(messages(&stack), ["Copied", "Delete failed"]);
+        assert_eq!(stack.entries()[1].level, NotificationLevel::Error);
+    }
+
+    #[test]
+    fn full_stack_evicts_oldest() {
+        let mut stack = Notifications::with_capacity(2);
+        for msg in ["a", "b", "c"] {
+            stack.push(NotificationLevel::Info, msg.to_string());
+        }
+        assert_eq!(messages(&stack), ["b", "c"]);
+    }
+
+    #[test]
+    fn zero_capacity_keeps_one_notification() {
+        let mut stack = Notifications::with_capacity(0);
+        stack.push(NotificationLevel::Info, "a".to_string());
+        stack.push(NotificationLevel::Info, "b".to_string());
+        assert_eq!(messages(&stack), ["b"]);
+    }
+
+    #[test]
+    fn level_of_message() {
+        assert_eq!(
+            NotificationLevel::of_message("Error: permission denied"),
+            NotificationLevel::Error
+        );
+        assert_eq!(
+            NotificationLevel::of_message("Paste failed: disk full"),
+            NotificationLevel::Error
+        );
+        assert_eq!(
+            NotificationLevel::of_message("Filter not supported in remote mode"),
+            NotificationLevel::Warning
+        );
+        assert_eq!(
+            NotificationLevel::of_message("Deleted 2 item(s)"),
+            NotificationLevel::Info
+        );
+    }
+}
@@ -0,0 +1,138 @@
+//! Append-only history of file operations performed in this session.
+//!
+//! Each paste, move, delete or rename is recorded with its targets, outcome
+//! and time. The log keeps at most [`OperationLog::DEFAULT_CAPACITY`]
+//! entries; once full, the oldest entry is dropped for every new one.
+
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+/// The kind of file operation that was performed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OperationKind {
+    Copy,
+    Move,
+    Delete,
+    Rename,
+}
+
+impl OperationKind {
+    /// Short label shown in the log panel.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Copy => "copy",
+            Self::Move => "move",
+            Self::Delete => "delete",
+            Self::Rename => "rename",
+        }
+    }
+}
+
+/// A single recorded operation.
+#[derive(Debug, Clone)]
+pub struct OperationEntry {
+    pub kind: OperationKind,
+    /// Paths the operation acted on (for rename, copy and move: source, then destination).
+    pub targets: Vec<PathBuf>,
+    /// `Err` carries the error message shown to the user.
+    pub result: Result<(), String>,
+    pub timestamp: SystemTime,
+}
+
+/// Bounded, append-only operation history. Oldest entries come first.
+#[derive(Debug, Clone)]
+pub struct OperationLog {
+    entries: VecDeque<OperationEntry>,
+    capacity: usize,
+}
+
+impl Default for OperationLog {
+    fn default() -> Self {
+        Self::with_capacity(Self::DEFAULT_CAPACITY)
+    }
+}
+
+impl OperationLog {
+    /// Number of entries retained by [`OperationLog::default`].
+    pub const DEFAULT_CAPACITY: usize = 500;
+
+    /// Creates an empty log that keeps at most `capacity` entries.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            entries: VecDeque::new(),
+            capacity: capacity.max(1),
+        }
+    }
+
+    /// Appends an entry stamped with the current time, evicting the oldest
+    /// entry if the log is full.
+    pub fn record(
+        &mut self,
+        kind: OperationKind,
+        targets: Vec<PathBuf>,
+        result: Result<(), String>,
+    ) {
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(OperationEntry {
+            kind,
+            targets,
+            result,
+            timestamp: SystemTime::now(),
+        });
+    }
+
+    pub fn entries(&self) -> &VecDeque<OperationEntry> {
+        &self.entries
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_appends_in_order() {
+        let mut log = OperationLog::default();
+        log.record(OperationKind::Copy, vec![PathBuf::from("a")], Ok(()));
+        log.record(
+            OperationKind::Delete,
+            vec![PathBuf::from("b")],
+            Err("denied".to_string()),
+        );
+        assert_eq!(log.len(), 2);
+        assert_eq!(log.entries()[0].kind, OperationKind::Copy);
+        assert_eq!(log.entries()[1].kind, OperationKind::Delete);
+        assert_eq!(log.entries()[1].result, Err("denied".to_string()));
+    }
+
+    #[test]
+    fn full_log_evicts_oldest() {
+        let mut log = OperationLog::with_capacity(2);
+        for name in ["a", "b", "c"] {
+            log.record(OperationKind::Delete, vec![PathBuf::from(name)], Ok(()));
+        }
+        assert_eq!(log.len(), 2);
+        assert_eq!(log.entries()[0].targets, vec![PathBuf::from("b")]);
+        assert_eq!(log.entries()[1].targets, vec![PathBuf::from("c")]);
+    }
+
+    #[test]
+    fn zero_capacity_keeps_one_entry() {
+        let mut log = OperationLog::with_capacity(0);
+        log.record(OperationKind::Rename, vec![], Ok(()));
+        log.record(OperationKind::Copy, vec![], Ok(()));
+        assert_eq!(log.len(), 1);
+        assert_eq!(log.entries()[0].kind, OperationKind::Copy);
+    }
+}
@@ -26,17 +26,22 @@ fn visible_window(selected: usize, total: usize, max_visible: usize) -> (usize,
 use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::Paragraph;
+use trefm_core::config::settings::{NameTruncation, ResultPaths};
+use trefm_core::fs::{human_size, relative_display_path, FileCategory};
 use trefm_core::nav::filter::{SortDirection, SortField};
+use unicode_width::UnicodeWidthStr;
 
-use crate::app::{App, AppMode};
+use crate::app::{App, AppMode, ConfirmAction};
 use crate::image_preview::ImagePreviewState;
 use crate::ui::breadcrumb::render_breadcrumb;
 use crate::ui::command_palette::render_command_palette;
-use crate::ui::panel::render_file_list;
-use crate::ui::popup::render_popup;
+use crate::ui::highlight::{match_spans, shifted};
+use crate::ui::panel::{render_file_list, EntryMarkers};
+use crate::ui::popup::{self, render_popup, render_popup_lines};
 use crate::ui::preview::{highlight_lines_for_pager, render_preview};
 use crate::ui::remote_connect::render_remote_connect;
 use crate::ui::statusbar::{render_statusbar, StatusBarProps};
+use crate::ui::truncate::{fit_to_width, pad_left_to_width};
 
 /// Main render function — composes the full UI layout each frame.
 pub fn render(
@@ -46,30 +51,65 @@ pub fn render(
     terminal_screen: Option<&vt100::Screen>,
 ) {
     // Pager mode uses the entire screen
-    if let AppMode::Pager { scroll } = app.mode() {
-        render_pager(f, app, *scroll);
+    if let Some(scroll) = app.pager_scroll() {
+        if matches!(app.mode(), AppMode::Diff { .. }) {
+            render_diff(f, app, scroll);
+        } else {
+            render_pager(f, app, scroll);
+        }
         return;
     }
 
     let theme = app.theme();
-    let show_icons = app.show_icons();
+    let markers = app.entry_markers();
 
     if app.is_dual_mode() {
-        render_dual_panel_layout(f, app, theme, show_icons, terminal_screen);
+        render_dual_panel_layout(f, app, theme, markers, terminal_screen);
     } else {
-        render_single_panel_layout(f, app, theme, show_icons, image_state, terminal_screen);
+        render_single_panel_layout(f, app, theme, markers, image_state, terminal_screen);
     }
 
     // Render modal overlays based on mode
     match app.mode() {
         AppMode::Help => render_help_popup(f, theme),
         AppMode::Search(query) => render_search_overlay(f, app, query, theme),
+        AppMode::Confirm(ConfirmAction::PasteConflict(queue)) => {
+            render_paste_conflict_popup(f, queue, theme)
+        }
+        AppMode::Confirm(ConfirmAction::Quit) => {
+            render_quit_confirm_popup(f, app.in_flight_ops(), theme)
+        }
+        AppMode::Confirm(ConfirmAction::CloseOtherTabs) => render_close_other_tabs_popup(
+            f,
+            app.active_tab_group().tab_count().saturating_sub(1),
+            theme,
+        ),
+        AppMode::Confirm(ConfirmAction::Execute(path)) => render_execute_popup(f, path, theme),
+        AppMode::Confirm(ConfirmAction::EnterLargeDir { dir, .. }) => {
+            render_large_dir_popup(f, dir, app.large_dir_threshold(), theme)
+        }
+        AppMode::Confirm(ConfirmAction::RenameOverwrite { name, .. }) => {
+            render_rename_overwrite_popup(f, name, theme)
+        }
+        AppMode::Confirm(ConfirmAction::Delete(paths)) => {
+            render_delete_confirm_popup(f, paths, app.panel().current_dir(), theme)
+        }
         AppMode::Confirm(_) => render_confirm_popup(f, theme),
         AppMode::Rename(name) => render_rename_popup(f, name, theme),
+        AppMode::RenameTab(name) => render_rename_tab_popup(f, name, theme),
         AppMode::BookmarkAdd(label) => render_bookmark_add_popup(f, label, theme),
         AppMode::BookmarkList { selected } => render_bookmark_list_popup(f, app, *selected, theme),
         AppMode::RecentFiles => render_recent_overlay(f, app, theme),
+        AppMode::RecentDirs { query, selected } => {
+            render_recent_dirs_overlay(f, app, query, *selected, theme)
+        }
         AppMode::DuplicateFiles => render_duplicate_overlay(f, app, theme),
+        AppMode::ContentSearchInput(query) => render_content_search_input_popup(f, query, theme),
+        AppMode::ContentSearch => render_content_search_overlay(f, app, theme),
+        AppMode::GitChanges => render_git_changes_overlay(f, app, theme),
+        AppMode::OperationLog { scroll } => render_operation_log_overlay(f, app, *scroll, theme),
+        AppMode::Notifications { scroll } => render_notifications_overlay(f, app, *scroll, theme),
+        AppMode::RunCommand(template) => render_run_command_popup(f, template, theme),
         AppMode::SortSelect { selected } => render_sort_popup(f, app, *selected, theme),
         AppMode::CommandPalette { query, selected } => render_command_palette(
             f,
@@ -80,7 +120,13 @@ pub fn render(
             theme,
         ),
         AppMode::RemoteConnect => render_remote_connect(f, app.connect_form(), theme),
-        AppMode::Normal | AppMode::Pager { .. } | AppMode::Terminal => {}
+        AppMode::Normal
+        | AppMode::Filter(_)
+        | AppMode::Pager { .. }
+        | AppMode::LogViewer { .. }
+        | AppMode::Diff { .. }
+        | AppMode::Terminal
+        | AppMode::TerminalSelect(_) => {}
     }
 }
 
@@ -108,38 +154,17 @@ fn render_single_panel_layout(
     f: &mut Frame,
     app: &App,
     theme: &trefm_core::config::theme::Theme,
-    show_icons: bool,
+    markers: EntryMarkers,
     image_state: Option<&mut ImagePreviewState>,
     terminal_screen: Option<&vt100::Screen>,
 ) {
     let panel = app.panel();
     let terminal_visible = app.terminal_visible() && terminal_screen.is_some();
-    let terminal_focused = matches!(app.mode(), AppMode::Terminal);
-
-    // If terminal visible, split vertically: content | terminal | statusbar
-    let main_chunks = if terminal_visible {
-        Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Min(5),
-                Constraint::Percentage(30),
-                Constraint::Length(1),
-            ])
-            .split(f.area())
-    } else {
-        Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Min(0),
-                Constraint::Length(0),
-                Constraint::Length(1),
-            ])
-            .split(f.area())
-    };
+    let terminal_focused = matches!(app.mode(), AppMode::Terminal | AppMode::TerminalSelect(_));
 
-    let content_area = main_chunks[0];
-    let terminal_area = main_chunks[1];
-    let statusbar_area = main_chunks[2];
+    // body | statusbar, with the terminal (if visible) carved out of the body
+    let (content_area, terminal_area, statusbar_area) =
+        split_terminal(f.area(), app, terminal_visible);
 
     // Top-level horizontal split: file list (40%) | preview (60%)
     let horizontal = Layout::default()
@@ -188,8 +213,9 @@ fn render_single_panel_layout(
         "Files",
         app.git_statuses(),
         theme,
-        show_icons,
+        markers,
         true,
+        app.name_truncation(),
     );
 
     let status_props = StatusBarProps {
@@ -197,9 +223,14 @@ fn render_single_panel_layout(
         selected_index: panel.selected_index(),
         selected_entry: panel.selected_entry(),
         show_hidden: panel.show_hidden(),
+        category_filter: filter_label(panel),
+        name_filter: name_filter_label(app),
         status_message: app.status_message(),
+        status_level: app.status_level(),
         branch_info: app.branch_info(),
         remote_label: app.remote_context().map(|c| c.label.as_str()),
+        clipboard_count: app.clipboard().len(),
+        size_units: app.size_units(),
     };
     render_statusbar(f, statusbar_area, &status_props, theme);
 
@@ -211,8 +242,9 @@ fn render_single_panel_layout(
             horizontal[1],
             panel.selected_entry(),
             theme,
-            show_icons,
+            markers == EntryMarkers::Icons,
             image_state,
+            app.size_units(),
         );
     }
 
@@ -224,49 +256,67 @@ fn render_single_panel_layout(
                 terminal_area,
                 screen,
                 terminal_focused,
+                terminal_selection(app),
                 theme,
             );
         }
     }
 }
 
+/// Splits the screen into (content, terminal, statusbar). The terminal area
+/// is empty when the terminal is hidden.
+fn split_terminal(area: Rect, app: &App, terminal_visible: bool) -> (Rect, Rect, Rect) {
+    let vertical = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(1)])
+        .split(area);
+    let (content, terminal) = if terminal_visible {
+        app.terminal_layout().split(vertical[0])
+    } else {
+        (vertical[0], Rect::default())
+    };
+    (content, terminal, vertical[1])
+}
+
+/// The active terminal text selection, if any.
+fn terminal_selection(app: &App) -> Option<&crate::terminal_emu::selection::Selection> {
+    match app.mode() {
+        AppMode::TerminalSelect(selection) => Some(selection),
+        _ => None,
+    }
+}
+
 fn render_dual_panel_layout(
     f: &mut Frame,
     app: &App,
     theme: &trefm_core::config::theme::Theme,
-    show_icons: bool,
+    markers: EntryMarkers,
     terminal_screen: Option<&vt100::Screen>,
 ) {
     let is_left_active = app.active_panel_index() == 0;
     let terminal_visible = app.terminal_visible() && terminal_screen.is_some();
-    let terminal_focused = matches!(app.mode(), AppMode::Terminal);
+    let terminal_focused = matches!(app.mode(), AppMode::Terminal | AppMode::TerminalSelect(_));
+
+    // body | statusbar, with the terminal (if visible) carved out of the body
+    let (content_area, terminal_area, statusbar_area) =
+        split_terminal(f.area(), app, terminal_visible);
 
-    // Main vertical layout: content | terminal? | statusbar(1)
-    let main_vertical = if terminal_visible {
-        Layout::default()
+    // Panels (top) | inactive panel's preview (bottom, optional)
+    let (panels_area, preview_area) = if app.dual_preview() {
+        let vertical = Layout::default()
             .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Min(5),
-                Constraint::Percentage(30),
-                Constraint::Length(1),
-            ])
-            .split(f.area())
+            .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+            .split(content_area);
+        (vertical[0], Some(vertical[1]))
     } else {
-        Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Min(0),
-                Constraint::Length(0),
-                Constraint::Length(1),
-            ])
-            .split(f.area())
+        (content_area, None)
     };
 
     // Horizontal 50/50 split
     let horizontal = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
-        .split(main_vertical[0]);
+        .split(panels_area);
 
     // Left panel
     render_panel_column(
@@ -277,8 +327,9 @@ fn render_dual_panel_layout(
         app.left_branch_info(),
         app.tab_group(0),
         theme,
-        show_icons,
+        markers,
         is_left_active,
+        app.name_truncation(),
     );
 
     // Right panel
@@ -290,30 +341,53 @@ fn render_dual_panel_layout(
         app.right_branch_info(),
         app.tab_group(1),
         theme,
-        show_icons,
+        markers,
         !is_left_active,
+        app.name_truncation(),
     );
 
+    if let Some(area) = preview_area {
+        if app.is_remote() {
+            render_remote_no_preview(f, area, theme);
+        } else {
+            render_preview(
+                f,
+                area,
+                app.inactive_selected_entry(),
+                theme,
+                markers == EntryMarkers::Icons,
+                None,
+                app.size_units(),
+            );
+        }
+    }
+
     // Shared statusbar shows active panel info
     let status_props = StatusBarProps {
         entry_count: app.panel().entries().len(),
         selected_index: app.panel().selected_index(),
         selected_entry: app.panel().selected_entry(),
         show_hidden: app.panel().show_hidden(),
+        category_filter: filter_label(app.panel()),
+        name_filter: name_filter_label(app),
         status_message: app.status_message(),
+        status_level: app.status_level(),
         branch_info: app.branch_info(),
         remote_label: app.remote_context().map(|c| c.label.as_str()),
+        clipboard_count: app.clipboard().len(),
+        size_units: app.size_units(),
     };
-    render_statusbar(f, main_vertical[2], &status_props, theme);
+    render_statusbar(f, statusbar_area, &status_props, theme);
 
     // Render terminal panel if visible
     if terminal_visible {
         if let Some(screen) = terminal_screen {
             crate::terminal_emu::widget::render_terminal(
                 f.buffer_mut(),
-                main_vertical[1],
+                terminal_area,
                 screen,
                 terminal_focused,
+                terminal_selection(app),
                 theme,
             );
         }
@@ -331,8 +405,9 @@ fn render_panel_column(
     branch_info: Option<&trefm_core::git::branch::BranchInfo>,
     tab_group: &crate::app::TabGroup,
     theme: &trefm_core::config::theme::Theme,
-    show_icons: bool,
+    markers: EntryMarkers,
     is_active: bool,
+    name_truncation: NameTruncation,
 ) {
     let tab_count = tab_group.tab_count();
     let header_height = if tab_count > 1 { 2 } else { 1 };
@@ -361,8 +436,9 @@ fn render_panel_column(
         "Files",
         git_statuses,
         theme,
-        show_icons,
+        markers,
         is_active,
+        name_truncation,
     );
 }
 
@@ -372,26 +448,52 @@ fn render_help_popup(f: &mut Frame, theme: &trefm_core::config::theme::Theme) {
         "h/l      - Parent/Enter directory".to_owned(),
         "gg/G     - Jump to top/bottom".to_owned(),
         "Enter    - Open directory/file".to_owned(),
+        "J        - Go to symlink's real location".to_owned(),
         "~        - Go to home directory".to_owned(),
         ".        - Toggle hidden files".to_owned(),
         "/        - Fuzzy search".to_owned(),
         "s        - Sort (select field + direction)".to_owned(),
+        "S        - Reverse sort direction".to_owned(),
+        "I        - Reverse current listing".to_owned(),
+        "f        - Filter by file category".to_owned(),
+        "O        - Show directories only".to_owned(),
+        "|        - Filter the panel as you type".to_owned(),
+        "*        - Pin/unpin entry at the top".to_owned(),
         "p        - Full-screen preview (pager)".to_owned(),
         "e        - Edit in $EDITOR".to_owned(),
         "r        - Rename".to_owned(),
+        "N        - Duplicate in place (name copy)".to_owned(),
+        "y/P      - Yank / paste".to_owned(),
         "d        - Delete".to_owned(),
         "R        - Recently changed files".to_owned(),
+        "z        - Recent directories".to_owned(),
         "D        - Find duplicate files".to_owned(),
+        "H        - Find duplicates in current dir".to_owned(),
+        "F        - Search file contents".to_owned(),
+        "c        - Jump to git changed files".to_owned(),
+        "L        - Operation log".to_owned(),
+        "m        - Recent messages".to_owned(),
+        "V        - Log viewer".to_owned(),
+        "!        - Run command on file".to_owned(),
+        "X        - Execute selected file (asks first)".to_owned(),
+        "E        - Copy directory tree to clipboard".to_owned(),
+        "K        - Copy path relative to git root".to_owned(),
         "b        - Add bookmark".to_owned(),
         "'        - Open bookmarks".to_owned(),
         ":        - Command palette".to_owned(),
         "C        - Remote connect/disconnect".to_owned(),
         "Ctrl+t   - Toggle terminal".to_owned(),
+        "S-PgUp/Dn - Scroll terminal history".to_owned(),
+        "Ctrl+s   - Select terminal text (hjkl, v: restart, y: copy)".to_owned(),
         "Tab      - Toggle dual panel mode".to_owned(),
         "1/2      - Focus left/right panel (dual)".to_owned(),
+        "Y/M      - Copy/move to other panel (dual)".to_owned(),
+        "x/=      - Swap panels / sync other panel (dual)".to_owned(),
         "t        - New tab".to_owned(),
         "w        - Close tab".to_owned(),
+        "W        - Close other tabs".to_owned(),
         "]/[      - Next/previous tab".to_owned(),
+        "T        - Rename tab".to_owned(),
         "Alt+1~9  - Select tab directly".to_owned(),
         "q        - Quit".to_owned(),
         "?        - This help".to_owned(),
@@ -415,16 +517,17 @@ fn render_search_overlay(
     let max_visible = 15;
     let (win_start, win_end) = visible_window(selected, results.len(), max_visible);
 
-    let mut lines: Vec<String> = vec![
-        format!("/{query}_"),
-        format!("{match_count}/{total} matches"),
-        String::new(),
+    let mut lines: Vec<Line> = vec![
+        Line::from(format!("/{query}_")),
+        Line::from(format!("{match_count}/{total} matches")),
+        Line::from(""),
     ];
 
     if win_start > 0 {
-        lines.push(format!("  ... {win_start} more above"));
+        lines.push(Line::from(format!("  ... {win_start} more above")));
     }
 
+    let matched = popup::match_style(theme);
     for (i, m) in results
         .iter()
         .enumerate()
@@ -432,18 +535,30 @@ fn render_search_overlay(
         .take(win_end - win_start)
     {
         let marker = if i == selected { "> " } else { "  " };
-        lines.push(format!("{marker}{}", m.entry().name()));
+        let path = result_path(app, m.entry().path(), app.panel().current_dir());
+        // The indices are into the name, which ends the displayed path.
+        let name = m.entry().name();
+        let indices = match path.strip_suffix(name) {
+            Some(dir) => shifted(m.matched_indices(), dir.len()),
+            None => Vec::new(),
+        };
+        let mut spans = vec![Span::raw(marker)];
+        spans.extend(match_spans(&path, &indices, Style::default(), matched));
+        lines.push(Line::from(spans));
     }
 
     if win_end < results.len() {
-        lines.push(format!("  ... {} more below", results.len() - win_end));
+        lines.push(Line::from(format!(
+            "  ... {} more below",
+            results.len() - win_end
+        )));
     }
 
     if results.is_empty() && !query.is_empty() {
-        lines.push("  No matches found".to_owned());
+        lines.push(Line::from("  No matches found"));
     }
 
-    render_popup(f, "Search", &lines, theme);
+    render_popup_lines(f, "Search", lines, theme);
 }
 
 fn render_bookmark_add_popup(f: &mut Frame, label: &str, theme: &trefm_core::config::theme::Theme) {
@@ -496,6 +611,115 @@ fn render_confirm_popup(f: &mut Frame, theme: &trefm_core::config::theme::Theme)
     render_popup(f, "Confirm", &lines, theme);
 }
 
+fn render_delete_confirm_popup(
+    f: &mut Frame,
+    paths: &[std::path::PathBuf],
+    root: &std::path::Path,
+    theme: &trefm_core::config::theme::Theme,
+) {
+    let rows = popup::popup_body_rows(f.area());
+    let lines = popup::delete_confirm_lines(paths, root, rows);
+    render_popup(f, "Delete?", &lines, theme);
+}
+
+fn render_quit_confirm_popup(
+    f: &mut Frame,
+    in_flight: usize,
+    theme: &trefm_core::config::theme::Theme,
+) {
+    let lines = vec![
+        format!("{in_flight} background operation(s) still running"),
+        String::new(),
+        "y - Quit anyway".to_owned(),
+        "n - Keep running".to_owned(),
+    ];
+    render_popup(f, "Quit?", &lines, theme);
+}
+
+fn render_close_other_tabs_popup(
+    f: &mut Frame,
+    count: usize,
+    theme: &trefm_core::config::theme::Theme,
+) {
+    let lines = vec![
+        format!("Close {count} other tab(s)?"),
+        String::new(),
+        "y - Close them".to_owned(),
+        "n - Keep them".to_owned(),
+    ];
+    render_popup(f, "Close Tabs?", &lines, theme);
+}
+
+fn render_execute_popup(
+    f: &mut Frame,
+    path: &std::path::Path,
+    theme: &trefm_core::config::theme::Theme,
+) {
+    let name = trefm_core::nfc_string(&path.file_name().unwrap_or_default().to_string_lossy());
+    let lines = vec![
+        format!("Run {name}?"),
+        "Its output opens in the pager.".to_owned(),
+        String::new(),
+        "y - Run it".to_owned(),
+        "n - Cancel".to_owned(),
+    ];
+    render_popup(f, "Execute?", &lines, theme);
+}
+
+fn render_large_dir_popup(
+    f: &mut Frame,
+    path: &std::path::Path,
+    threshold: usize,
+    theme: &trefm_core::config::theme::Theme,
+) {
+    let name = trefm_core::nfc_string(&path.file_name().unwrap_or_default().to_string_lossy());
+    let lines = vec![
+        format!("{name} has more than {threshold} entries."),
+        "Reading it may take a while.".to_owned(),
+        String::new(),
+        "y - Open it".to_owned(),
+        "n - Cancel".to_owned(),
+    ];
+    render_popup(f, "Large Directory", &lines, theme);
+}
+
+fn render_rename_overwrite_popup(
+    f: &mut Frame,
+    name: &str,
+    theme: &trefm_core::config::theme::Theme,
+) {
+    let lines = vec![
+        format!("'{name}' already exists"),
+        "Renaming will replace it.".to_owned(),
+        String::new(),
+        "y - Replace it".to_owned(),
+        "n - Cancel".to_owned(),
+    ];
+    render_popup(f, "Overwrite?", &lines, theme);
+}
+
+fn render_paste_conflict_popup(
+    f: &mut Frame,
+    queue: &trefm_core::fs::conflict::PasteQueue,
+    theme: &trefm_core::config::theme::Theme,
+) {
+    let name = queue
+        .pending_conflict()
+        .and_then(|(_, dest)| dest.file_name())
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let lines = vec![
+        format!("'{name}' already exists"),
+        format!("{} item(s) left to paste", queue.remaining()),
+        String::new(),
+        "o - Overwrite    O - Overwrite all".to_owned(),
+        "s - Skip         S - Skip all".to_owned(),
+        "r - Rename (keep both)".to_owned(),
+        "Esc - Cancel paste".to_owned(),
+    ];
+    render_popup(f, "Paste Conflict", &lines, theme);
+}
+
 fn render_rename_popup(f: &mut Frame, name: &str, theme: &trefm_core::config::theme::Theme) {
     let lines = vec![
         format!("New name: {name}_"),
@@ -505,6 +729,15 @@ fn render_rename_popup(f: &mut Frame, name: &str, theme: &trefm_core::config::th
     render_popup(f, "Rename", &lines, theme);
 }
 
+fn render_rename_tab_popup(f: &mut Frame, name: &str, theme: &trefm_core::config::theme::Theme) {
+    let lines = vec![
+        format!("Tab name: {name}_"),
+        String::new(),
+        "Enter to confirm (empty resets), Esc to cancel".to_owned(),
+    ];
+    render_popup(f, "Rename Tab", &lines, theme);
+}
+
 fn render_recent_overlay(f: &mut Frame, app: &App, theme: &trefm_core::config::theme::Theme) {
     let results = app.recent_results();
     let selected = app.recent_selected();
@@ -519,33 +752,335 @@ fn render_recent_overlay(f: &mut Frame, app: &App, theme: &trefm_core::config::t
         lines.push(format!("  ... {win_start} more above"));
     }
 
-    for (i, entry) in results
+    let rows: Vec<(String, String)> = results[win_start..win_end]
+        .iter()
+        .map(|entry| {
+            let time_str = entry
+                .modified()
+                .map(format_time_ago)
+                .unwrap_or_else(|| "unknown".to_owned());
+            (result_path(app, entry.path(), base_dir), time_str)
+        })
+        .collect();
+    // Line the times up after the widest path that fits beside them.
+    let time_width = rows.iter().map(|(_, t)| t.width()).max().unwrap_or(0);
+    let path_room = popup::popup_body_cols(f.area()).saturating_sub(2 + 2 + time_width);
+    let path_width = rows
+        .iter()
+        .map(|(p, _)| p.width())
+        .max()
+        .unwrap_or(0)
+        .min(path_room);
+
+    for (i, (path, time_str)) in (win_start..).zip(&rows) {
+        let marker = if i == selected { "> " } else { "  " };
+        let path = fit_to_width(path, path_width, app.name_truncation());
+        lines.push(format!("{marker}{path}  {time_str}"));
+    }
+
+    if win_end < results.len() {
+        lines.push(format!("  ... {} more below", results.len() - win_end));
+    }
+
+    if results.is_empty() {
+        lines.push("  No recently changed files found".to_owned());
+    }
+
+    lines.push(String::new());
+    lines.push("Enter: jump | j/k: navigate | Esc: close".to_owned());
+
+    render_popup(f, "Recently Changed", &lines, theme);
+}
+
+fn render_content_search_input_popup(
+    f: &mut Frame,
+    query: &str,
+    theme: &trefm_core::config::theme::Theme,
+) {
+    let lines = vec![
+        format!("Search contents: {query}_"),
+        String::new(),
+        "Enter to search, Esc to cancel".to_owned(),
+    ];
+    render_popup(f, "Content Search", &lines, theme);
+}
+
+fn render_run_command_popup(
+    f: &mut Frame,
+    template: &str,
+    theme: &trefm_core::config::theme::Theme,
+) {
+    let lines = vec![
+        format!("$ {template}_"),
+        String::new(),
+        "{} is the selected path (appended if omitted)".to_owned(),
+        "Enter to run, Esc to cancel".to_owned(),
+    ];
+    render_popup(f, "Run Command", &lines, theme);
+}
+
+fn render_content_search_overlay(
+    f: &mut Frame,
+    app: &App,
+    theme: &trefm_core::config::theme::Theme,
+) {
+    let search = app.content_search();
+    let results = &search.results;
+    let selected = search.selected;
+    let base_dir = app.panel().current_dir();
+
+    let max_visible = 15;
+    let (win_start, win_end) = visible_window(selected, results.len(), max_visible);
+
+    let mut lines: Vec<String> = vec![
+        format!("\"{}\"  {}", search.query, search.status_line()),
+        String::new(),
+    ];
+
+    if win_start > 0 {
+        lines.push(format!("  ... {win_start} more above"));
+    }
+
+    for (i, m) in results
         .iter()
         .enumerate()
         .skip(win_start)
         .take(win_end - win_start)
     {
         let marker = if i == selected { "> " } else { "  " };
-        let rel_path = entry.path().strip_prefix(base_dir).unwrap_or(entry.path());
-        let time_str = entry
-            .modified()
-            .map(format_time_ago)
-            .unwrap_or_else(|| "unknown".to_owned());
-        lines.push(format!("{marker}{}  {time_str}", rel_path.display()));
+        let path = result_path(app, &m.path, base_dir);
+        lines.push(format!("{marker}{path}:{}  {}", m.line_number, m.line));
     }
 
     if win_end < results.len() {
         lines.push(format!("  ... {} more below", results.len() - win_end));
     }
 
-    if results.is_empty() {
-        lines.push("  No recently changed files found".to_owned());
+    if results.is_empty() && !search.running {
+        lines.push("  No matches found".to_owned());
+    }
+
+    lines.push(String::new());
+    let close_hint = if search.running {
+        "Esc: stop"
+    } else {
+        "Esc: close"
+    };
+    lines.push(format!("Enter: jump | j/k: navigate | {close_hint}"));
+
+    render_popup(f, "Content Search", &lines, theme);
+}
+
+fn render_git_changes_overlay(f: &mut Frame, app: &App, theme: &trefm_core::config::theme::Theme) {
+    let changes = app.git_changes();
+    let selected = app.git_changes_selected();
+    let base_dir = app.panel().current_dir();
+
+    let max_visible = 15;
+    let (win_start, win_end) = visible_window(selected, changes.len(), max_visible);
+
+    let mut lines: Vec<String> = vec![format!("{} changed file(s)", changes.len()), String::new()];
+
+    if win_start > 0 {
+        lines.push(format!("  ... {win_start} more above"));
+    }
+
+    for (i, (path, status)) in changes
+        .iter()
+        .enumerate()
+        .skip(win_start)
+        .take(win_end - win_start)
+    {
+        let marker = if i == selected { "> " } else { "  " };
+        let rel_path = path.strip_prefix(base_dir).unwrap_or(path);
+        lines.push(format!(
+            "{marker}{} {}",
+            status.short_code(),
+            rel_path.display()
+        ));
+    }
+
+    if win_end < changes.len() {
+        lines.push(format!("  ... {} more below", changes.len() - win_end));
+    }
+
+    if changes.is_empty() {
+        lines.push("  No uncommitted changes".to_owned());
     }
 
     lines.push(String::new());
     lines.push("Enter: jump | j/k: navigate | Esc: close".to_owned());
 
-    render_popup(f, "Recently Changed", &lines, theme);
+    render_popup(f, "Git Changes", &lines, theme);
+}
+
+fn render_operation_log_overlay(
+    f: &mut Frame,
+    app: &App,
+    scroll: usize,
+    theme: &trefm_core::config::theme::Theme,
+) {
+    let log = app.operation_log();
+    let max_visible = 15;
+
+    let mut lines: Vec<String> = vec![format!("{} operation(s)", log.len()), String::new()];
+
+    if scroll > 0 {
+        lines.push(format!("  ... {scroll} newer above"));
+    }
+
+    for entry in log.entries().iter().rev().skip(scroll).take(max_visible) {
+        let targets = entry
+            .targets
+            .iter()
+            .map(|p| p.display().to_string())
+            .collect::<Vec<_>>()
+            .join(" -> ");
+        let outcome = match &entry.result {
+            Ok(()) => "ok".to_owned(),
+            Err(e) => format!("failed: {e}"),
+        };
+        lines.push(format!(
+            "{}  {:<6} {targets} ({outcome})",
+            pad_left_to_width(&format_time_ago(entry.timestamp), 8),
+            entry.kind.label()
+        ));
+    }
+
+    let shown_end = (scroll + max_visible).min(log.len());
+    if shown_end < log.len() {
+        lines.push(format!("  ... {} older below", log.len() - shown_end));
+    }
+
+    if log.is_empty() {
+        lines.push("  No operations yet".to_owned());
+    }
+
+    lines.push(String::new());
+    lines.push("j/k: scroll | Esc: close".to_owned());
+
+    render_popup(f, "Operation Log", &lines, theme);
+}
+
+/// Recently visited directories matching `query`, with matched characters
+/// highlighted.
+fn render_recent_dirs_overlay(
+    f: &mut Frame,
+    app: &App,
+    query: &str,
+    selected: usize,
+    theme: &trefm_core::config::theme::Theme,
+) {
+    let matches = app.recent_dir_matches(query);
+
+    let max_visible = 15;
+    let (win_start, win_end) = visible_window(selected, matches.len(), max_visible);
+
+    let mut lines: Vec<Line> = vec![
+        Line::from(format!("z {query}_")),
+        Line::from(format!("{} director(ies)", matches.len())),
+        Line::from(""),
+    ];
+
+    if win_start > 0 {
+        lines.push(Line::from(format!("  ... {win_start} more above")));
+    }
+
+    let matched = popup::match_style(theme);
+    for (i, m) in matches
+        .iter()
+        .enumerate()
+        .skip(win_start)
+        .take(win_end - win_start)
+    {
+        let marker = if i == selected { "> " } else { "  " };
+        let path = m.path.to_string_lossy();
+        let mut spans = vec![Span::raw(marker)];
+        spans.extend(match_spans(
+            &path,
+            &m.matched_indices,
+            Style::default(),
+            matched,
+        ));
+        lines.push(Line::from(spans));
+    }
+
+    if win_end < matches.len() {
+        lines.push(Line::from(format!(
+            "  ... {} more below",
+            matches.len() - win_end
+        )));
+    }
+
+    if matches.is_empty() {
+        let empty = if query.is_empty() {
+            "  No other directories visited yet"
+        } else {
+            "  No matches found"
+        };
+        lines.push(Line::from(empty));
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from("Enter: go | Up/Down: navigate | Esc: close"));
+
+    render_popup_lines(f, "Recent Directories", lines, theme);
+}
+
+/// Recent status messages, newest first, each wrapped to the popup width.
+fn render_notifications_overlay(
+    f: &mut Frame,
+    app: &App,
+    scroll: usize,
+    theme: &trefm_core::config::theme::Theme,
+) {
+    let notifications = app.notifications();
+    let width = popup::popup_body_cols(f.area());
+
+    let mut lines: Vec<String> = vec![format!("{} message(s)", notifications.len()), String::new()];
+    if scroll > 0 {
+        lines.push(format!("  ... {scroll} newer above"));
+    }
+
+    // Leave room for the "older below" line and the footer.
+    let room = popup::popup_body_rows(f.area()).saturating_sub(lines.len() + 3);
+    let mut used = 0;
+    let mut shown = 0;
+    for entry in notifications.entries().iter().rev().skip(scroll) {
+        let prefix = format!(
+            "{}  {:<5} ",
+            pad_left_to_width(&format_time_ago(entry.timestamp), 8),
+            entry.level.label()
+        );
+        let body = popup::wrap_text(&entry.message, width.saturating_sub(prefix.width()));
+        if shown > 0 && used + body.len() > room {
+            break;
+        }
+        let indent = " ".repeat(prefix.width());
+        for (i, part) in body.iter().enumerate() {
+            let lead = if i == 0 { &prefix } else { &indent };
+            lines.push(format!("{lead}{part}"));
+        }
+        used += body.len();
+        shown += 1;
+    }
+
+    let shown_end = scroll + shown;
+    if shown_end < notifications.len() {
+        lines.push(format!(
+            "  ... {} older below",
+            notifications.len() - shown_end
+        ));
+    }
+
+    if notifications.is_empty() {
+        lines.push("  No messages yet".to_owned());
+    }
+
+    lines.push(String::new());
+    lines.push("j/k: scroll | Esc: close".to_owned());
+
+    render_popup(f, "Notifications", &lines, theme);
 }
 
 fn format_time_ago(time: SystemTime) -> String {
@@ -570,24 +1105,41 @@ fn format_time_ago(time: SystemTime) -> String {
     format!("{days}d ago")
 }
 
-fn format_size(bytes: u64) -> String {
-    if bytes >= 1024 * 1024 * 1024 {
-        format!("{:.1} GB", bytes as f64 / (1024.0 * 1024.0 * 1024.0))
-    } else if bytes >= 1024 * 1024 {
-        format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
-    } else if bytes >= 1024 {
-        format!("{:.1} KB", bytes as f64 / 1024.0)
+/// Status bar label for the panel's listing filter: `Dirs` while only
+/// directories are shown, else the category filter's label.
+fn filter_label(panel: &crate::app::PanelState) -> Option<&'static str> {
+    if panel.dirs_only() {
+        Some("Dirs")
     } else {
-        format!("{bytes} B")
+        panel.category_filter().map(FileCategory::label)
+    }
+}
+
+/// The active panel's name filter, shown while it is typed (even empty)
+/// and while it narrows the listing.
+fn name_filter_label(app: &App) -> Option<&str> {
+    match app.mode() {
+        AppMode::Filter(query) => Some(query),
+        _ => Some(app.panel().name_filter()).filter(|q| !q.is_empty()),
+    }
+}
+
+/// A result row's path, shortened to `root` unless `ui.result_paths` is absolute.
+fn result_path(app: &App, path: &std::path::Path, root: &std::path::Path) -> String {
+    match app.result_paths() {
+        ResultPaths::Relative => relative_display_path(path, root),
+        ResultPaths::Absolute => path.display().to_string(),
     }
 }
 
 fn render_duplicate_overlay(f: &mut Frame, app: &App, theme: &trefm_core::config::theme::Theme) {
     let results = app.duplicate_results();
     let selected = app.duplicate_selected();
-    let home_dir = std::env::var("HOME")
-        .map(std::path::PathBuf::from)
-        .unwrap_or_default();
+    let scan_root = app
+        .shown_duplicates()
+        .scan_root
+        .clone()
+        .unwrap_or_else(crate::background::default_scan_root);
 
     let total_groups = results.len();
     let total_files: usize = results.iter().map(|g| g.files.len()).sum();
@@ -596,12 +1148,18 @@ fn render_duplicate_overlay(f: &mut Frame, app: &App, theme: &trefm_core::config
         .map(|g| g.size * (g.files.len() as u64).saturating_sub(1))
         .sum();
 
-    let scan_info = match app.scan_status() {
-        crate::background::ScanStatus::Scanning => " (Scanning...)".to_owned(),
-        crate::background::ScanStatus::Idle => match app.duplicate_cache().scanned_at.as_deref() {
-            Some(ts) => format!(" (Last: {ts})"),
-            None => String::new(),
-        },
+    let scan_info = match (app.local_duplicate_scan(), app.scan_status()) {
+        (Some(scan), _) if scan.running => {
+            format!(" (Scanning {}...)", scan.root.display())
+        }
+        (Some(scan), _) => format!(" (Under {})", scan.root.display()),
+        (None, crate::background::ScanStatus::Scanning) => " (Scanning...)".to_owned(),
+        (None, crate::background::ScanStatus::Idle) => {
+            match app.duplicate_cache().scanned_at.as_deref() {
+                Some(ts) => format!(" (Last: {ts})"),
+                None => String::new(),
+            }
+        }
     };
 
     let max_visible = 15;
@@ -609,8 +1167,9 @@ fn render_duplicate_overlay(f: &mut Frame, app: &App, theme: &trefm_core::config
 
     let mut lines: Vec<String> = vec![
         format!(
-            "{total_groups} group(s), {total_files} total files, {} wasted{scan_info}",
-            format_size(wasted_bytes)
+            "{total_groups} group(s), {total_files} total files, {} wasted, by {}{scan_info}",
+            human_size(wasted_bytes, app.size_units()),
+            app.duplicate_sort().label()
         ),
         String::new(),
     ];
@@ -624,21 +1183,14 @@ fn render_duplicate_overlay(f: &mut Frame, app: &App, theme: &trefm_core::config
 
     let mut flat_entries: Vec<FlatEntry> = Vec::new();
     let mut flat_idx: usize = 0;
-    for group in results {
+    for group in &results {
         let header = format!(
             "── {} ({} files) ──",
-            format_size(group.size),
+            human_size(group.size, app.size_units()),
             group.files.len()
         );
         for (fi, file) in group.files.iter().enumerate() {
-            let display_path = if !home_dir.as_os_str().is_empty() {
-                match file.path.strip_prefix(&home_dir) {
-                    Ok(rel) => format!("~/{}", rel.display()),
-                    Err(_) => file.path.display().to_string(),
-                }
-            } else {
-                file.path.display().to_string()
-            };
+            let display_path = result_path(app, &file.path, &scan_root);
             flat_entries.push(FlatEntry {
                 group_header: if fi == 0 { Some(header.clone()) } else { None },
                 display_path,
@@ -673,7 +1225,10 @@ fn render_duplicate_overlay(f: &mut Frame, app: &App, theme: &trefm_core::config
     }
 
     lines.push(String::new());
-    lines.push("Enter: jump | d: delete | j/k: navigate | Esc: close".to_owned());
+    lines.push(
+        "Enter: jump | d: delete | i: ignore group | s: sort | j/k: navigate | Esc: close"
+            .to_owned(),
+    );
 
     render_popup(f, "Duplicate Files", &lines, theme);
 }
@@ -729,9 +1284,13 @@ fn render_pager(f: &mut Frame, app: &App, scroll: usize) {
 
     // Header line
     let file_name = app
-        .pager_file()
-        .and_then(|p| p.file_name())
-        .map(|n| trefm_core::nfc_string(&n.to_string_lossy()))
+        .pager_title()
+        .map(str::to_string)
+        .or_else(|| {
+            app.pager_file()
+                .and_then(|p| p.file_name())
+                .map(|n| trefm_core::nfc_string(&n.to_string_lossy()))
+        })
         .unwrap_or_else(|| "unknown".to_string());
     let header_text = format!(
         " {file_name} \u{2014} line {}/{}  (q: close, j/k: scroll, d/u: half page, gg/G: top/bottom)",
@@ -758,7 +1317,13 @@ fn render_pager(f: &mut Frame, app: &App, scroll: usize) {
     let (highlighted, theme_bg) = app
         .pager_file()
         .map(|path| highlight_lines_for_pager(path, visible_lines, syntax_theme))
-        .unwrap_or_else(|| (Vec::new(), None));
+        .unwrap_or_else(|| {
+            let plain = visible_lines
+                .iter()
+                .map(|l| vec![Span::raw(l.clone())])
+                .collect();
+            (plain, None)
+        });
 
     let line_number_width = total.to_string().len().max(3);
     let line_number_fg = trefm_core::config::theme::parse_color(&theme.preview.line_number_fg);
@@ -807,3 +1372,107 @@ fn render_pager(f: &mut Frame, app: &App, scroll: usize) {
     let paragraph = Paragraph::new(content).style(base_style);
     f.render_widget(paragraph, vertical[1]);
 }
+
+/// Full-screen side-by-side diff: the left panel's file on the left, the
+/// right panel's on the right. Changed lines use the git theme colours.
+fn render_diff(f: &mut Frame, app: &App, scroll: usize) {
+    let theme = app.theme();
+    let rows = app.diff_rows();
+    let total = rows.len();
+    let area = f.area();
+
+    let vertical = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Min(0),
+        ])
+        .split(area);
+
+    let file_name = app
+        .diff_paths()
+        .and_then(|(left, _)| left.file_name())
+        .map(|n| trefm_core::nfc_string(&n.to_string_lossy()))
+        .unwrap_or_else(|| "unknown".to_string());
+    let header_text = format!(
+        " {file_name} \u{2014} row {}/{}  (q: close, j/k: scroll, d/u: half page, gg/G: top/bottom)",
+        scroll + 1,
+        total
+    );
+    let header_style = Style::default()
+        .fg(Color::White)
+        .bg(Color::DarkGray)
+        .add_modifier(Modifier::BOLD);
+    let header = Paragraph::new(Line::from(Span::styled(header_text, header_style)))
+        .style(Style::default().bg(Color::DarkGray));
+    f.render_widget(header, vertical[0]);
+
+    // Each side gets half the width, less one column for the separator.
+    let side_width = (area.width as usize).saturating_sub(1) / 2;
+    let number_width = total.to_string().len().max(3);
+    let text_width = side_width.saturating_sub(number_width + 1);
+    let line_number_fg = trefm_core::config::theme::parse_color(&theme.preview.line_number_fg);
+    let removed_fg = trefm_core::config::theme::parse_color(&theme.git.deleted_fg);
+    let added_fg = trefm_core::config::theme::parse_color(&theme.git.added_fg);
+    let separator = Span::styled("\u{2502}", Style::default().fg(line_number_fg));
+
+    let (left_dir, right_dir) = app
+        .diff_paths()
+        .map(|(left, right)| {
+            let dir = |p: &std::path::Path| {
+                p.parent()
+                    .map(|d| trefm_core::nfc_string(&d.to_string_lossy()))
+                    .unwrap_or_default()
+            };
+            (dir(left), dir(right))
+        })
+        .unwrap_or_default();
+    let title_style = Style::default().add_modifier(Modifier::BOLD);
+    let titles = Line::from(vec![
+        Span::styled(
+            fit_to_width(&left_dir, side_width, NameTruncation::Middle),
+            title_style,
+        ),
+        separator.clone(),
+        Span::styled(
+            fit_to_width(&right_dir, side_width, NameTruncation::Middle),
+            title_style,
+        ),
+    ]);
+    f.render_widget(Paragraph::new(titles), vertical[1]);
+
+    let side = |cell: Option<&trefm_core::fs::DiffCell>, changed_fg: Color, changed: bool| {
+        let text_style = if changed {
+            Style::default().fg(changed_fg)
+        } else {
+            Style::default()
+        };
+        match cell {
+            Some(cell) => vec![
+                Span::styled(
+                    format!("{:>number_width$} ", cell.number),
+                    Style::default().fg(line_number_fg),
+                ),
+                Span::styled(
+                    fit_to_width(&cell.text, text_width, NameTruncation::End),
+                    text_style,
+                ),
+            ],
+            None => vec![Span::raw(" ".repeat(side_width))],
+        }
+    };
+
+    let content_height = vertical[2].height as usize;
+    let visible_end = (scroll + content_height).min(total);
+    let content: Vec<Line> = rows[scroll.min(visible_end)..visible_end]
+        .iter()
+        .map(|row| {
+            let mut spans = side(row.left.as_ref(), removed_fg, row.changed);
+            spans.push(separator.clone());
+            spans.extend(side(row.right.as_ref(), added_fg, row.changed));
+            Line::from(spans)
+        })
+        .collect();
+    f.render_widget(Paragraph::new(content), vertical[2]);
+}
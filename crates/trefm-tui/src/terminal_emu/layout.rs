@@ -0,0 +1,165 @@
+//! Placement of the embedded terminal panel on screen.
+//!
+//! The renderer and the PTY resize logic both go through
+//! [`TerminalLayout`] so the shell always sees the exact size it is drawn at.
+
+use ratatui::layout::Rect;
+use trefm_core::config::settings::{TerminalConfig, TerminalPosition};
+
+/// Smallest terminal panel: a border on each side plus one row/column.
+const MIN_TERMINAL: u16 = 3;
+/// Rows kept for the file panels when the terminal is at the bottom.
+const MIN_CONTENT_ROWS: u16 = 5;
+/// Columns kept for the file panels when the terminal is on the right.
+const MIN_CONTENT_COLS: u16 = 20;
+
+/// Where the terminal goes and how much of the screen it takes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TerminalLayout {
+    pub position: TerminalPosition,
+    pub ratio: f32,
+}
+
+impl Default for TerminalLayout {
+    fn default() -> Self {
+        Self::from_config(&TerminalConfig::default())
+    }
+}
+
+impl TerminalLayout {
+    pub fn from_config(config: &TerminalConfig) -> Self {
+        Self {
+            position: config.position,
+            ratio: config.size_ratio(),
+        }
+    }
+
+    /// Splits the area above the status bar into (content, terminal).
+    pub fn split(&self, body: Rect) -> (Rect, Rect) {
+        match self.position {
+            TerminalPosition::Bottom => {
+                let size = panel_size(body.height, self.ratio, MIN_CONTENT_ROWS);
+                let content = Rect {
+                    height: body.height - size,
+                    ..body
+                };
+                let terminal = Rect {
+                    y: body.y + content.height,
+                    height: size,
+                    ..body
+                };
+                (content, terminal)
+            }
+            TerminalPosition::Right => {
+                let size = panel_size(body.width, self.ratio, MIN_CONTENT_COLS);
+                let content = Rect {
+                    width: body.width - size,
+                    ..body
+                };
+                let terminal = Rect {
+                    x: body.x + content.width,
+                    width: size,
+                    ..body
+                };
+                (content, terminal)
+            }
+        }
+    }
+
+    /// PTY size as (cols, rows) for a `width` x `height` screen: the
+    /// terminal panel minus its border and the one-row status bar.
+    pub fn pty_size(&self, width: u16, height: u16) -> (u16, u16) {
+        let body = Rect::new(0, 0, width, height.saturating_sub(1));
+        let (_, terminal) = self.split(body);
+        (
+            terminal.width.saturating_sub(2).max(1),
+            terminal.height.saturating_sub(2).max(1),
+        )
+    }
+}
+
+/// Length of the terminal along the split axis: `total * ratio`, at least
+/// [`MIN_TERMINAL`], leaving `min_content` for the panels when possible and
+/// never more than `total`.
+fn panel_size(total: u16, ratio: f32, min_content: u16) -> u16 {
+    let wanted = (f32::from(total) * ratio).round() as u16;
+    let max = total.saturating_sub(min_content).max(MIN_TERMINAL);
+    wanted.clamp(MIN_TERMINAL, max).min(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layout(position: TerminalPosition, ratio: f32) -> TerminalLayout {
+        TerminalLayout { position, ratio }
+    }
+
+    #[test]
+    fn default_is_bottom_thirty_percent() {
+        let l = TerminalLayout::default();
+        assert_eq!(l.position, TerminalPosition::Bottom);
+        let (content, terminal) = l.split(Rect::new(0, 0, 100, 40));
+        assert_eq!(terminal, Rect::new(0, 28, 100, 12));
+        assert_eq!(content, Rect::new(0, 0, 100, 28));
+    }
+
+    #[test]
+    fn bottom_split_follows_ratio() {
+        let (content, terminal) =
+            layout(TerminalPosition::Bottom, 0.5).split(Rect::new(0, 1, 80, 30));
+        assert_eq!(terminal, Rect::new(0, 16, 80, 15));
+        assert_eq!(content, Rect::new(0, 1, 80, 15));
+    }
+
+    #[test]
+    fn right_split_follows_ratio() {
+        let (content, terminal) =
+            layout(TerminalPosition::Right, 0.4).split(Rect::new(0, 0, 100, 30));
+        assert_eq!(terminal, Rect::new(60, 0, 40, 30));
+        assert_eq!(content, Rect::new(0, 0, 60, 30));
+    }
+
+    #[test]
+    fn pty_size_excludes_border_and_status_bar() {
+        // 41 rows -> 40 body rows -> 12-row panel -> 10 inner rows
+        assert_eq!(
+            layout(TerminalPosition::Bottom, 0.3).pty_size(100, 41),
+            (98, 10)
+        );
+        // 100 cols -> 40-col panel -> 38 inner cols; 30 body rows -> 28 inner
+        assert_eq!(
+            layout(TerminalPosition::Right, 0.4).pty_size(100, 31),
+            (38, 28)
+        );
+    }
+
+    #[test]
+    fn small_ratio_keeps_minimum_terminal() {
+        let (_, terminal) = layout(TerminalPosition::Bottom, 0.1).split(Rect::new(0, 0, 80, 10));
+        assert_eq!(terminal.height, MIN_TERMINAL);
+    }
+
+    #[test]
+    fn large_ratio_leaves_room_for_content() {
+        let (content, terminal) =
+            layout(TerminalPosition::Bottom, 0.9).split(Rect::new(0, 0, 80, 20));
+        assert_eq!(content.height, MIN_CONTENT_ROWS);
+        assert_eq!(terminal.height, 15);
+
+        let (content, _) = layout(TerminalPosition::Right, 0.9).split(Rect::new(0, 0, 50, 20));
+        assert_eq!(content.width, MIN_CONTENT_COLS);
+    }
+
+    #[test]
+    fn tiny_screen_never_overflows() {
+        let (content, terminal) =
+            layout(TerminalPosition::Bottom, 0.3).split(Rect::new(0, 0, 10, 2));
+        assert_eq!(terminal.height, 2);
+        assert_eq!(content.height, 0);
+        assert_eq!(
+            layout(TerminalPosition::Bottom, 0.3).pty_size(10, 0),
+            (8, 1)
+        );
+    }
+}
@@ -1,11 +1,14 @@
+pub mod layout;
 pub mod pty;
 pub mod screen;
+pub mod selection;
 pub mod widget;
 
 use std::io::Write;
 
 use crossterm::event::KeyEvent;
 use tokio::sync::mpsc;
+use trefm_core::config::settings::TerminalConfig;
 
 /// Messages from the PTY reader thread to the main loop.
 pub enum TerminalMessage {
@@ -24,17 +27,21 @@ pub struct TerminalEmulator {
 }
 
 impl TerminalEmulator {
-    /// Spawns a shell and starts the PTY reader thread.
+    /// Spawns the configured shell and starts the PTY reader thread.
     pub fn spawn(
         cwd: &std::path::Path,
         cols: u16,
         rows: u16,
+        config: &TerminalConfig,
         tx: mpsc::UnboundedSender<TerminalMessage>,
     ) -> anyhow::Result<Self> {
-        let (master, reader) = pty::spawn_shell(cwd, cols, rows)?;
+        let env_shell = std::env::var("SHELL").ok();
+        let shell = pty::resolve_shell(&config.shell, env_shell.as_deref());
+        let (master, reader) =
+            pty::spawn_shell(cwd, cols, rows, &shell, &config.shell_args, &config.env)?;
         pty::spawn_pty_reader(reader, tx);
         let writer = master.take_writer().map_err(|e| anyhow::anyhow!("{e}"))?;
-        let screen = screen::ScreenState::new(rows, cols);
+        let screen = screen::ScreenState::new(rows, cols, config.scrollback_lines);
         Ok(Self {
             pty_master: master,
             screen,
@@ -43,10 +50,11 @@ impl TerminalEmulator {
         })
     }
 
-    /// Writes a key event to the PTY.
+    /// Writes a key event to the PTY, snapping the view back to the live screen.
     pub fn write_key(&mut self, key: KeyEvent) {
         let bytes = pty::key_to_bytes(key);
         if !bytes.is_empty() {
+            self.screen.scroll_to_bottom();
             let _ = self.writer.write_all(&bytes);
             let _ = self.writer.flush();
         }
@@ -58,6 +66,35 @@ impl TerminalEmulator {
         let _ = self.writer.flush();
     }
 
+    /// Scroll the view back by half a screen.
+    pub fn scroll_page_up(&mut self) {
+        self.screen.scroll_up(self.half_page());
+    }
+
+    /// Scroll the view forward by half a screen.
+    pub fn scroll_page_down(&mut self) {
+        self.screen.scroll_down(self.half_page());
+    }
+
+    /// Screen size as (rows, cols).
+    pub fn rows_cols(&self) -> (u16, u16) {
+        (self.last_size.1, self.last_size.0)
+    }
+
+    /// Position of the shell's cursor as (row, col).
+    pub fn cursor_position(&mut self) -> (u16, u16) {
+        self.screen.view().cursor_position()
+    }
+
+    /// Text inside `selection` on the screen as currently displayed.
+    pub fn selection_text(&mut self, selection: &selection::Selection) -> String {
+        selection::region_text(&self.screen.view(), selection)
+    }
+
+    fn half_page(&self) -> usize {
+        usize::from(self.last_size.1 / 2).max(1)
+    }
+
     /// Resize the PTY and virtual screen.
     pub fn resize(&mut self, cols: u16, rows: u16) {
         if (cols, rows) != self.last_size && cols > 0 && rows > 0 {
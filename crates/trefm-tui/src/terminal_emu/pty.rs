@@ -1,4 +1,6 @@
+use std::collections::BTreeMap;
 use std::io::Read;
+use std::path::Path;
 
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use portable_pty::{native_pty_system, CommandBuilder, MasterPty, PtySize};
@@ -6,11 +8,21 @@ use tokio::sync::mpsc;
 
 use super::TerminalMessage;
 
-/// Spawn a shell process in a PTY. Returns (master, reader).
+/// Shell used when neither the config nor `$SHELL` names one.
+#[cfg(windows)]
+const DEFAULT_SHELL: &str = "cmd.exe";
+#[cfg(not(windows))]
+const DEFAULT_SHELL: &str = "/bin/sh";
+
+/// Spawn `shell` with `args` in a PTY, adding `env` to the inherited
+/// environment. Returns (master, reader).
 pub fn spawn_shell(
-    cwd: &std::path::Path,
+    cwd: &Path,
     cols: u16,
     rows: u16,
+    shell: &str,
+    args: &[String],
+    env: &BTreeMap<String, String>,
 ) -> anyhow::Result<(Box<dyn MasterPty + Send>, Box<dyn Read + Send>)> {
     let pty_system = native_pty_system();
     let pair = pty_system
@@ -22,12 +34,7 @@ pub fn spawn_shell(
         })
         .map_err(|e| anyhow::anyhow!("Failed to open PTY: {e}"))?;
 
-    let shell = detect_shell();
-    let mut cmd = CommandBuilder::new(&shell);
-    cmd.cwd(cwd);
-    // Set TERM for proper escape sequence support
-    cmd.env("TERM", "xterm-256color");
-
+    let cmd = build_command(cwd, shell, args, env);
     pair.slave
         .spawn_command(cmd)
         .map_err(|e| anyhow::anyhow!("Failed to spawn shell: {e}"))?;
@@ -40,9 +47,40 @@ pub fn spawn_shell(
     Ok((pair.master, reader))
 }
 
-/// Detect the user's shell. Tries $SHELL, falls back to /bin/sh.
-fn detect_shell() -> String {
-    std::env::var("SHELL").unwrap_or_else(|_| "/bin/sh".to_string())
+/// Builds the shell command. The parent environment is inherited; `TERM`,
+/// `TREFM=1` and `TREFM_CWD` are set on top, then the user's `env` entries,
+/// which may override any of them.
+fn build_command(
+    cwd: &Path,
+    shell: &str,
+    args: &[String],
+    env: &BTreeMap<String, String>,
+) -> CommandBuilder {
+    let mut cmd = CommandBuilder::new(shell);
+    cmd.args(args);
+    cmd.cwd(cwd);
+    // Set TERM for proper escape sequence support
+    cmd.env("TERM", "xterm-256color");
+    cmd.env("TREFM", "1");
+    cmd.env("TREFM_CWD", cwd);
+    for (key, value) in env {
+        cmd.env(key, value);
+    }
+    cmd
+}
+
+/// Picks the shell to launch: the configured one unless it is `auto` or
+/// empty, then `env_shell` (`$SHELL`), then the platform default.
+pub fn resolve_shell(configured: &str, env_shell: Option<&str>) -> String {
+    let configured = configured.trim();
+    if !configured.is_empty() && configured != "auto" {
+        return configured.to_string();
+    }
+    env_shell
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .unwrap_or(DEFAULT_SHELL)
+        .to_string()
 }
 
 /// Spawns a blocking thread to read from PTY and send output via channel.
@@ -107,3 +145,51 @@ pub fn key_to_bytes(key: KeyEvent) -> Vec<u8> {
         _ => vec![],
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn command_includes_configured_env() {
+        let env = BTreeMap::from([
+            ("EDITOR".to_string(), "hx".to_string()),
+            ("TREFM".to_string(), "custom".to_string()),
+        ]);
+        let cmd = build_command(Path::new("/tmp"), "/bin/sh", &["-l".to_string()], &env);
+        assert_eq!(cmd.get_env("EDITOR"), Some("hx".as_ref()));
+        assert_eq!(cmd.get_env("TREFM"), Some("custom".as_ref()));
+        assert_eq!(cmd.get_env("TREFM_CWD"), Some("/tmp".as_ref()));
+        assert_eq!(cmd.get_env("TERM"), Some("xterm-256color".as_ref()));
+        assert_eq!(cmd.get_argv(), &["/bin/sh", "-l"]);
+    }
+
+    #[test]
+    fn command_inherits_parent_env() {
+        let cmd = build_command(Path::new("/tmp"), "/bin/sh", &[], &BTreeMap::new());
+        assert_eq!(cmd.get_env("TREFM"), Some("1".as_ref()));
+        if let Some(path) = std::env::var_os("PATH") {
+            assert_eq!(cmd.get_env("PATH"), Some(path.as_os_str()));
+        }
+    }
+
+    #[test]
+    fn configured_shell_wins() {
+        assert_eq!(
+            resolve_shell("/usr/bin/fish", Some("/bin/zsh")),
+            "/usr/bin/fish"
+        );
+    }
+
+    #[test]
+    fn auto_falls_back_to_env_shell() {
+        assert_eq!(resolve_shell("auto", Some("/bin/zsh")), "/bin/zsh");
+        assert_eq!(resolve_shell("", Some("/bin/zsh")), "/bin/zsh");
+    }
+
+    #[test]
+    fn missing_env_shell_uses_platform_default() {
+        assert_eq!(resolve_shell("auto", None), DEFAULT_SHELL);
+        assert_eq!(resolve_shell("auto", Some("  ")), DEFAULT_SHELL);
+    }
+}
@@ -1,27 +1,331 @@
+use std::ops::Deref;
+use std::path::PathBuf;
+
 /// Wrapper around vt100::Parser for managing the virtual terminal screen.
+///
+/// Lines that scroll off the top are kept in a scrollback buffer of at most
+/// `scrollback_len` lines. The view can be scrolled back through that
+/// history; an offset of 0 shows the live screen.
 pub struct ScreenState {
     parser: vt100::Parser,
+    /// Lines the view is scrolled back from the live screen.
+    scroll_offset: usize,
+    /// vt100 ignores OSC 7, so working-directory reports are picked out here.
+    osc7: Osc7Scanner,
 }
 
 impl ScreenState {
-    pub fn new(rows: u16, cols: u16) -> Self {
+    pub fn new(rows: u16, cols: u16, scrollback_len: usize) -> Self {
         Self {
-            parser: vt100::Parser::new(rows, cols, 0),
+            parser: vt100::Parser::new(rows, cols, scrollback_len),
+            scroll_offset: 0,
+            osc7: Osc7Scanner::default(),
         }
     }
 
-    /// Feed raw bytes from the PTY.
-    pub fn process(&mut self, bytes: &[u8]) {
+    /// Feed raw bytes from the PTY. Returns the last working directory the
+    /// shell reported via OSC 7 in these bytes, if any.
+    pub fn process(&mut self, bytes: &[u8]) -> Option<PathBuf> {
         self.parser.process(bytes);
+        self.osc7.feed(bytes)
     }
 
-    /// Access the current screen state.
-    pub fn screen(&self) -> &vt100::Screen {
-        self.parser.screen()
+    /// Returns the screen as it should be drawn, scrolled back by the current
+    /// offset. Rows `0..height` of the view are the visible window.
+    pub fn view(&mut self) -> ScreenView<'_> {
+        let (rows, cols) = self.parser.screen().size();
+        let offset = self.scroll_offset.min(usize::from(u16::MAX - rows));
+        if offset > 0 {
+            // vt100 0.15 only handles offsets up to the screen height, so the
+            // grid is grown by the offset while the view is alive.
+            self.parser.set_size(rows + offset as u16, cols);
+            self.parser.set_scrollback(offset);
+        }
+        ScreenView {
+            parser: &mut self.parser,
+            size: (rows, cols),
+        }
     }
 
     /// Resize the virtual screen.
     pub fn resize(&mut self, rows: u16, cols: u16) {
         self.parser.set_size(rows, cols);
+        self.scroll_offset = self.scroll_offset.min(self.history_len());
+    }
+
+    /// Scroll back into history by `lines`, stopping at the oldest retained line.
+    pub fn scroll_up(&mut self, lines: usize) {
+        let max = self.history_len();
+        self.scroll_offset = self.scroll_offset.saturating_add(lines).min(max);
+    }
+
+    /// Scroll towards the live screen by `lines`.
+    pub fn scroll_down(&mut self, lines: usize) {
+        self.scroll_offset = self.scroll_offset.saturating_sub(lines);
+    }
+
+    /// Return to the live screen.
+    pub fn scroll_to_bottom(&mut self) {
+        self.scroll_offset = 0;
+    }
+
+    /// Number of lines currently held in the scrollback buffer.
+    pub fn history_len(&mut self) -> usize {
+        // set_scrollback clamps to the buffer length without touching rows
+        self.parser.set_scrollback(usize::MAX);
+        let len = self.parser.screen().scrollback();
+        self.parser.set_scrollback(0);
+        len
+    }
+}
+
+/// A scrolled view of the terminal screen; restores the live screen on drop.
+pub struct ScreenView<'a> {
+    parser: &'a mut vt100::Parser,
+    size: (u16, u16),
+}
+
+impl Deref for ScreenView<'_> {
+    type Target = vt100::Screen;
+
+    fn deref(&self) -> &vt100::Screen {
+        self.parser.screen()
+    }
+}
+
+impl Drop for ScreenView<'_> {
+    fn drop(&mut self) {
+        if self.parser.screen().size() != self.size {
+            self.parser.set_scrollback(0);
+            self.parser.set_size(self.size.0, self.size.1);
+        }
+    }
+}
+
+/// Longest OSC 7 payload accepted; anything longer is dropped.
+const MAX_OSC7_LEN: usize = 4096;
+
+/// Where the scanner is within a potential `ESC ] 7 ; <uri> (BEL | ESC \\)`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum Osc7State {
+    #[default]
+    Ground,
+    Esc,
+    Bracket,
+    Seven,
+    Collect,
+    CollectEsc,
+}
+
+/// Byte-wise scanner for OSC 7 sequences; survives sequences split across
+/// PTY reads.
+#[derive(Debug, Default)]
+struct Osc7Scanner {
+    state: Osc7State,
+    payload: Vec<u8>,
+}
+
+impl Osc7Scanner {
+    fn feed(&mut self, bytes: &[u8]) -> Option<PathBuf> {
+        let mut found = None;
+        for &b in bytes {
+            self.state = match (self.state, b) {
+                (Osc7State::Collect, 0x07) | (Osc7State::CollectEsc, b'\\') => {
+                    if let Some(path) = std::str::from_utf8(&self.payload).ok().and_then(parse_osc7)
+                    {
+                        found = Some(path);
+                    }
+                    Osc7State::Ground
+                }
+                (Osc7State::Collect, 0x1b) => Osc7State::CollectEsc,
+                (Osc7State::Collect, _) if self.payload.len() < MAX_OSC7_LEN => {
+                    self.payload.push(b);
+                    Osc7State::Collect
+                }
+                (_, 0x1b) => Osc7State::Esc,
+                (Osc7State::Esc, b']') => Osc7State::Bracket,
+                (Osc7State::Bracket, b'7') => Osc7State::Seven,
+                (Osc7State::Seven, b';') => {
+                    self.payload.clear();
+                    Osc7State::Collect
+                }
+                _ => Osc7State::Ground,
+            };
+        }
+        found
+    }
+}
+
+/// Parses an OSC 7 payload (`file://host/path`, percent-encoded) into an
+/// absolute path. The host part is not checked.
+pub fn parse_osc7(uri: &str) -> Option<PathBuf> {
+    let rest = uri.strip_prefix("file://")?;
+    let path = &rest[rest.find('/')?..];
+    let decoded = percent_decode(path)?;
+    Some(PathBuf::from(decoded))
+}
+
+/// Decodes `%XX` escapes; returns `None` on malformed escapes or non-UTF-8.
+fn percent_decode(s: &str) -> Option<String> {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = s.get(i + 1..i + 3)?;
+            out.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(out).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feed_lines(screen: &mut ScreenState, count: usize) {
+        for i in 0..count {
+            screen.process(format!("line {i}\r\n").as_bytes());
+        }
+    }
+
+    fn top_row(screen: &mut ScreenState) -> String {
+        let view = screen.view();
+        (0..view.size().1)
+            .filter_map(|col| view.cell(0, col))
+            .map(|cell| cell.contents())
+            .collect::<String>()
+            .trim_end()
+            .to_string()
+    }
+
+    #[test]
+    fn scrollback_accumulates_lines_pushed_off_screen() {
+        let mut screen = ScreenState::new(5, 20, 100);
+        feed_lines(&mut screen, 3);
+        assert_eq!(screen.history_len(), 0);
+
+        feed_lines(&mut screen, 10);
+        // 13 lines plus the empty prompt row on a 5-row screen
+        assert_eq!(screen.history_len(), 9);
+    }
+
+    #[test]
+    fn scrollback_clamps_at_configured_maximum() {
+        let mut screen = ScreenState::new(5, 20, 20);
+        feed_lines(&mut screen, 100);
+        assert_eq!(screen.history_len(), 20);
+
+        screen.scroll_up(usize::MAX);
+        assert_eq!(screen.scroll_offset, 20);
+        assert_eq!(top_row(&mut screen), "line 76");
+    }
+
+    #[test]
+    fn scroll_up_and_down_move_the_view() {
+        let mut screen = ScreenState::new(5, 20, 100);
+        feed_lines(&mut screen, 30);
+        assert_eq!(top_row(&mut screen), "line 26");
+
+        screen.scroll_up(3);
+        assert_eq!(screen.scroll_offset, 3);
+        assert_eq!(top_row(&mut screen), "line 23");
+
+        screen.scroll_up(10);
+        assert_eq!(top_row(&mut screen), "line 13");
+
+        screen.scroll_down(12);
+        assert_eq!(screen.scroll_offset, 1);
+        screen.scroll_down(10);
+        assert_eq!(screen.scroll_offset, 0);
+        assert_eq!(top_row(&mut screen), "line 26");
+    }
+
+    #[test]
+    fn view_restores_live_screen_size() {
+        let mut screen = ScreenState::new(5, 20, 100);
+        feed_lines(&mut screen, 30);
+        screen.scroll_up(12);
+        {
+            let view = screen.view();
+            assert_eq!(view.scrollback(), 12);
+        }
+        assert_eq!(screen.parser.screen().size(), (5, 20));
+        assert_eq!(screen.parser.screen().scrollback(), 0);
+    }
+
+    #[test]
+    fn scroll_to_bottom_returns_to_live_screen() {
+        let mut screen = ScreenState::new(5, 20, 100);
+        feed_lines(&mut screen, 30);
+        screen.scroll_up(10);
+        screen.scroll_to_bottom();
+        assert_eq!(screen.scroll_offset, 0);
+    }
+
+    #[test]
+    fn zero_scrollback_keeps_no_history() {
+        let mut screen = ScreenState::new(5, 20, 0);
+        feed_lines(&mut screen, 30);
+        screen.scroll_up(5);
+        assert_eq!(screen.scroll_offset, 0);
+    }
+
+    #[test]
+    fn parse_osc7_reads_host_and_path() {
+        assert_eq!(
+            parse_osc7("file://myhost/home/user/src"),
+            Some(PathBuf::from("/home/user/src"))
+        );
+        assert_eq!(parse_osc7("file:///tmp"), Some(PathBuf::from("/tmp")));
+    }
+
+    #[test]
+    fn parse_osc7_decodes_percent_escapes() {
+        assert_eq!(
+            parse_osc7("file://host/tmp/my%20dir/%ED%95%9C"),
+            Some(PathBuf::from("/tmp/my dir/한"))
+        );
+    }
+
+    #[test]
+    fn parse_osc7_rejects_malformed_uris() {
+        assert_eq!(parse_osc7("http://host/tmp"), None);
+        assert_eq!(parse_osc7("file://host"), None);
+        assert_eq!(parse_osc7("file://host/bad%2"), None);
+        assert_eq!(parse_osc7("file://host/bad%zz"), None);
+    }
+
+    #[test]
+    fn process_reports_osc7_cwd() {
+        let mut screen = ScreenState::new(5, 20, 0);
+        assert_eq!(screen.process(b"plain output\r\n"), None);
+        assert_eq!(
+            screen.process(b"\x1b]7;file://host/srv/data\x07$ "),
+            Some(PathBuf::from("/srv/data"))
+        );
+        // ST terminator, last report wins
+        assert_eq!(
+            screen.process(b"\x1b]7;file://h/a\x07\x1b]7;file://h/b\x1b\\"),
+            Some(PathBuf::from("/b"))
+        );
+    }
+
+    #[test]
+    fn process_reports_osc7_split_across_reads() {
+        let mut screen = ScreenState::new(5, 20, 0);
+        assert_eq!(screen.process(b"\x1b]7;file://ho"), None);
+        assert_eq!(screen.process(b"st/var/lo"), None);
+        assert_eq!(screen.process(b"g\x07"), Some(PathBuf::from("/var/log")));
+    }
+
+    #[test]
+    fn other_osc_sequences_are_ignored() {
+        let mut screen = ScreenState::new(5, 20, 0);
+        assert_eq!(screen.process(b"\x1b]0;file://host/title\x07"), None);
     }
 }
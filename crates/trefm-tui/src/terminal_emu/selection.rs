@@ -0,0 +1,188 @@
+//! Rectangular text selection over the terminal screen.
+//!
+//! A [`Selection`] spans from a fixed anchor to a movable cursor; the
+//! selected region is the rectangle with those two cells as corners.
+//! [`region_text`] reads that rectangle out of a `vt100::Screen`, and
+//! [`osc52_sequence`] wraps the text for the host terminal's clipboard.
+
+use base64::Engine;
+
+/// A rectangular selection in screen coordinates (row, col).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Selection {
+    pub anchor: (u16, u16),
+    pub cursor: (u16, u16),
+}
+
+impl Selection {
+    /// Creates an empty selection with anchor and cursor at `pos`.
+    pub fn new(pos: (u16, u16)) -> Self {
+        Self {
+            anchor: pos,
+            cursor: pos,
+        }
+    }
+
+    /// Moves the cursor by the given deltas, clamped to a `rows` x `cols` screen.
+    pub fn moved(self, d_row: i32, d_col: i32, rows: u16, cols: u16) -> Self {
+        let clamp = |v: u16, d: i32, max: u16| {
+            i32::from(v)
+                .saturating_add(d)
+                .clamp(0, i32::from(max.saturating_sub(1))) as u16
+        };
+        Self {
+            cursor: (
+                clamp(self.cursor.0, d_row, rows),
+                clamp(self.cursor.1, d_col, cols),
+            ),
+            ..self
+        }
+    }
+
+    /// Drops the anchor at the cursor, starting a fresh selection there.
+    pub fn restarted(self) -> Self {
+        Self::new(self.cursor)
+    }
+
+    /// Returns `(top, left, bottom, right)`, all inclusive.
+    pub fn bounds(&self) -> (u16, u16, u16, u16) {
+        (
+            self.anchor.0.min(self.cursor.0),
+            self.anchor.1.min(self.cursor.1),
+            self.anchor.0.max(self.cursor.0),
+            self.anchor.1.max(self.cursor.1),
+        )
+    }
+
+    pub fn contains(&self, row: u16, col: u16) -> bool {
+        let (top, left, bottom, right) = self.bounds();
+        (top..=bottom).contains(&row) && (left..=right).contains(&col)
+    }
+}
+
+/// Extracts the text inside `selection`, one line per row with trailing
+/// blanks removed. Wide characters are taken from their first cell only.
+pub fn region_text(screen: &vt100::Screen, selection: &Selection) -> String {
+    let (top, left, bottom, right) = selection.bounds();
+    (top..=bottom)
+        .map(|row| {
+            let line: String = (left..=right)
+                .filter_map(|col| screen.cell(row, col))
+                .filter(|cell| !cell.is_wide_continuation())
+                .map(|cell| {
+                    let contents = cell.contents();
+                    if contents.is_empty() {
+                        " ".to_string()
+                    } else {
+                        contents
+                    }
+                })
+                .collect();
+            line.trim_end().to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Returns the OSC 52 escape sequence that sets the host terminal's
+/// clipboard to `text`.
+pub fn osc52_sequence(text: &str) -> String {
+    let encoded = base64::engine::general_purpose::STANDARD.encode(text);
+    format!("\x1b]52;c;{encoded}\x07")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn screen_with(text: &str) -> vt100::Parser {
+        let mut parser = vt100::Parser::new(4, 12, 0);
+        parser.process(text.as_bytes());
+        parser
+    }
+
+    #[test]
+    fn region_text_reads_rectangle() {
+        let parser = screen_with("hello world\r\nfoo bar baz\r\nlast line");
+        let sel = Selection {
+            anchor: (0, 6),
+            cursor: (1, 10),
+        };
+        assert_eq!(region_text(parser.screen(), &sel), "world\nr baz");
+    }
+
+    #[test]
+    fn region_text_is_independent_of_drag_direction() {
+        let parser = screen_with("hello world\r\nfoo bar baz");
+        let forward = Selection {
+            anchor: (0, 0),
+            cursor: (1, 2),
+        };
+        let backward = Selection {
+            anchor: (1, 2),
+            cursor: (0, 0),
+        };
+        assert_eq!(region_text(parser.screen(), &forward), "hel\nfoo");
+        assert_eq!(region_text(parser.screen(), &backward), "hel\nfoo");
+    }
+
+    #[test]
+    fn region_text_trims_trailing_blanks_but_keeps_inner_ones() {
+        let parser = screen_with("a  b\r\n\r\nc");
+        let sel = Selection {
+            anchor: (0, 0),
+            cursor: (2, 11),
+        };
+        assert_eq!(region_text(parser.screen(), &sel), "a  b\n\nc");
+    }
+
+    #[test]
+    fn region_text_single_cell() {
+        let parser = screen_with("xyz");
+        let sel = Selection::new((0, 1));
+        assert_eq!(region_text(parser.screen(), &sel), "y");
+    }
+
+    #[test]
+    fn region_text_skips_wide_continuation_cells() {
+        let parser = screen_with("한글ok");
+        let sel = Selection {
+            anchor: (0, 0),
+            cursor: (0, 5),
+        };
+        assert_eq!(region_text(parser.screen(), &sel), "한글ok");
+    }
+
+    #[test]
+    fn moved_clamps_to_screen() {
+        let sel = Selection::new((1, 1));
+        assert_eq!(sel.moved(-5, -5, 4, 12).cursor, (0, 0));
+        assert_eq!(sel.moved(10, 20, 4, 12).cursor, (3, 11));
+        assert_eq!(sel.moved(1, 2, 4, 12).anchor, (1, 1));
+        assert_eq!(sel.moved(0, i32::MIN, 4, 12).cursor, (1, 0));
+        assert_eq!(sel.moved(0, i32::MAX, 4, 12).cursor, (1, 11));
+    }
+
+    #[test]
+    fn restarted_anchors_at_cursor() {
+        let sel = Selection::new((0, 0)).moved(2, 3, 4, 12).restarted();
+        assert_eq!(sel, Selection::new((2, 3)));
+    }
+
+    #[test]
+    fn contains_checks_rectangle() {
+        let sel = Selection {
+            anchor: (2, 5),
+            cursor: (0, 1),
+        };
+        assert!(sel.contains(1, 3));
+        assert!(sel.contains(2, 5));
+        assert!(!sel.contains(3, 3));
+        assert!(!sel.contains(1, 6));
+    }
+
+    #[test]
+    fn osc52_wraps_base64_text() {
+        assert_eq!(osc52_sequence("hi"), "\x1b]52;c;aGk=\x07");
+    }
+}
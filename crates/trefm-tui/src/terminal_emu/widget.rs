@@ -6,12 +6,15 @@ use ratatui::{
 };
 use trefm_core::config::theme::{parse_color, Theme};
 
+use super::selection::Selection;
+
 /// Renders the terminal screen into a ratatui frame area.
 pub fn render_terminal(
     buf: &mut Buffer,
     area: Rect,
     screen: &vt100::Screen,
     focused: bool,
+    selection: Option<&Selection>,
     theme: &Theme,
 ) {
     let border_color = if focused {
@@ -20,9 +23,20 @@ pub fn render_terminal(
         parse_color(&theme.terminal.border_fg)
     };
 
+    // While scrolled back, show the offset and hide the live cursor
+    let scrolled = screen.scrollback();
+    let title = if selection.is_some() {
+        "[Terminal: select]".to_string()
+    } else if scrolled > 0 {
+        format!("[Terminal \u{2191}{scrolled}]")
+    } else {
+        "[Terminal]".to_string()
+    };
+    let show_cursor = focused && scrolled == 0 && selection.is_none();
+
     let block = Block::default()
         .borders(Borders::ALL)
-        .title("[Terminal]")
+        .title(title)
         .border_style(Style::default().fg(border_color));
 
     let inner = block.inner(area);
@@ -40,6 +54,10 @@ pub fn render_terminal(
                 continue;
             }
 
+            // Reverse video for the cursor when focused and for selected cells
+            let reversed = (show_cursor && row == cursor_row && col == cursor_col)
+                || selection.is_some_and(|s| s.contains(row, col));
+
             match cell {
                 Some(cell) => {
                     let fg = convert_color(cell.fgcolor());
@@ -56,8 +74,7 @@ pub fn render_terminal(
                         style = style.add_modifier(Modifier::UNDERLINED);
                     }
 
-                    // Reverse video for cursor position when focused
-                    if focused && row == cursor_row && col == cursor_col {
+                    if reversed {
                         style = style.add_modifier(Modifier::REVERSED);
                     }
 
@@ -71,7 +88,7 @@ pub fn render_terminal(
                 None => {
                     let buf_cell = &mut buf[(x, y)];
                     buf_cell.set_symbol(" ");
-                    if focused && row == cursor_row && col == cursor_col {
+                    if reversed {
                         buf_cell.set_style(Style::default().add_modifier(Modifier::REVERSED));
                     }
                 }
@@ -9,6 +9,7 @@ use ratatui::{
 };
 use trefm_core::config::theme::{parse_color, Theme};
 use trefm_core::git::branch::BranchInfo;
+use unicode_width::UnicodeWidthStr;
 
 /// Renders a breadcrumb trail for the given directory path.
 /// When `branch_info` is provided, the git branch is displayed right-aligned.
@@ -61,7 +62,7 @@ pub fn render_breadcrumb(
 
         // Calculate used width from path spans
         let path_width: usize = parts.iter().map(|s| s.width()).sum();
-        let branch_width = branch_text.len();
+        let branch_width = branch_text.width();
         let total_width = area.width as usize;
 
         let spacer_len = total_width.saturating_sub(path_width + branch_width);
@@ -5,14 +5,18 @@ use ratatui::{
     widgets::{Block, Borders, Clear, Paragraph},
     Frame,
 };
-use trefm_core::action::{ActionDescriptor, ActionRegistry};
+use trefm_core::action::{matched_name_indices, ActionDescriptor, ActionRegistry};
 use trefm_core::config::keymap::Keymap;
 use trefm_core::config::theme::{parse_color, Theme};
 
+use crate::ui::highlight::match_spans;
+use crate::ui::popup::match_style;
+
 /// Renders the command palette popup.
 ///
 /// Shows a fuzzy-searchable list of all available actions with their
-/// descriptions, categories, and current key bindings.
+/// descriptions, categories, and current key bindings. The characters of
+/// each name that the query matched are highlighted.
 pub fn render_command_palette(
     f: &mut Frame,
     query: &str,
@@ -66,6 +70,7 @@ pub fn render_command_palette(
 
     let (win_start, win_end) = visible_window(selected, results.len(), list_height);
 
+    let matched = match_style(theme);
     let mut lines: Vec<Line> = Vec::new();
     for (i, desc) in results
         .iter()
@@ -74,7 +79,8 @@ pub fn render_command_palette(
         .take(win_end - win_start)
     {
         let is_selected = i == selected;
-        let line = format_action_line(desc, keymap, is_selected);
+        let indices = matched_name_indices(desc, query);
+        let line = format_action_line(desc, keymap, is_selected, &indices, matched);
         lines.push(line);
     }
 
@@ -86,8 +92,15 @@ pub fn render_command_palette(
     f.render_widget(Paragraph::new(lines), layout[2]);
 }
 
-/// Formats a single action line for the palette list.
-fn format_action_line<'a>(desc: &ActionDescriptor, keymap: &Keymap, is_selected: bool) -> Line<'a> {
+/// Formats a single action line for the palette list, drawing the name's
+/// bytes at `name_indices` with `matched` on top of the name style.
+fn format_action_line<'a>(
+    desc: &ActionDescriptor,
+    keymap: &Keymap,
+    is_selected: bool,
+    name_indices: &[usize],
+    matched: Style,
+) -> Line<'a> {
     let marker = if is_selected { "> " } else { "  " };
     let name_style = if is_selected {
         Style::default()
@@ -106,12 +119,17 @@ fn format_action_line<'a>(desc: &ActionDescriptor, keymap: &Keymap, is_selected:
         .map(|keys| keys.join(", "))
         .unwrap_or_default();
 
-    let mut spans = vec![
-        Span::styled(marker.to_string(), name_style),
-        Span::styled(desc.name.to_string(), name_style),
+    let mut spans = vec![Span::styled(marker.to_string(), name_style)];
+    spans.extend(match_spans(
+        desc.name,
+        name_indices,
+        name_style,
+        name_style.patch(matched),
+    ));
+    spans.extend([
         Span::styled(format!("  [{}]", desc.category.label()), category_style),
         Span::styled(format!("  {}", desc.description), desc_style),
-    ];
+    ]);
 
     if !key_hint.is_empty() {
         spans.push(Span::styled(format!("  ({key_hint})"), key_style));
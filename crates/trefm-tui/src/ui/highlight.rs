@@ -0,0 +1,111 @@
+//! Highlighting the characters a fuzzy query matched.
+//!
+//! Matchers report byte offsets into the candidate text. [`match_spans`]
+//! splits the text into runs of matched and unmatched characters so a
+//! result list can draw the matched runs in their own style.
+
+use ratatui::style::Style;
+use ratatui::text::Span;
+
+/// `text` split into spans, the characters starting at `indices` (byte
+/// offsets, increasing) drawn with `matched` and the rest with `base`.
+/// Offsets that are out of range or not on a character boundary are
+/// ignored.
+pub fn match_spans(
+    text: &str,
+    indices: &[usize],
+    base: Style,
+    matched: Style,
+) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+    let mut run = String::new();
+    let mut run_matched = false;
+    let mut wanted = indices.iter().copied().peekable();
+    for (offset, ch) in text.char_indices() {
+        while wanted.next_if(|&i| i < offset).is_some() {}
+        let is_match = wanted.next_if_eq(&offset).is_some();
+        if is_match != run_matched && !run.is_empty() {
+            let style = if run_matched { matched } else { base };
+            spans.push(Span::styled(std::mem::take(&mut run), style));
+        }
+        run_matched = is_match;
+        run.push(ch);
+    }
+    if !run.is_empty() {
+        let style = if run_matched { matched } else { base };
+        spans.push(Span::styled(run, style));
+    }
+    spans
+}
+
+/// `indices` moved `offset` bytes along, for a matched name that is shown
+/// after other text — such as a file name at the end of its path.
+pub fn shifted(indices: &[usize], offset: usize) -> Vec<usize> {
+    indices.iter().map(|i| i + offset).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::style::Modifier;
+
+    fn runs(spans: &[Span]) -> Vec<(String, bool)> {
+        spans
+            .iter()
+            .map(|s| {
+                let bold = s.style.add_modifier.contains(Modifier::BOLD);
+                (s.content.to_string(), bold)
+            })
+            .collect()
+    }
+
+    fn bold() -> Style {
+        Style::default().add_modifier(Modifier::BOLD)
+    }
+
+    #[test]
+    fn consecutive_matches_form_one_run() {
+        let spans = match_spans("readme.md", &[0, 1, 2, 7], Style::default(), bold());
+
+        assert_eq!(
+            runs(&spans),
+            [
+                ("rea".to_string(), true),
+                ("dme.".to_string(), false),
+                ("m".to_string(), true),
+                ("d".to_string(), false),
+            ]
+        );
+    }
+
+    #[test]
+    fn wide_characters_are_kept_whole() {
+        let spans = match_spans("작업_노트.md", &[7, 10], Style::default(), bold());
+
+        assert_eq!(
+            runs(&spans),
+            [
+                ("작업_".to_string(), false),
+                ("노트".to_string(), true),
+                (".md".to_string(), false)
+            ]
+        );
+    }
+
+    #[test]
+    fn stray_offsets_are_ignored() {
+        let spans = match_spans("한a", &[1, 3, 40], Style::default(), bold());
+
+        assert_eq!(
+            runs(&spans),
+            [("한".to_string(), false), ("a".to_string(), true)]
+        );
+    }
+
+    #[test]
+    fn no_indices_give_one_plain_span() {
+        let spans = match_spans("notes.txt", &[], Style::default(), bold());
+
+        assert_eq!(runs(&spans), [("notes.txt".to_string(), false)]);
+    }
+}
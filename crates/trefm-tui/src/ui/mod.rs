@@ -4,6 +4,7 @@
 
 pub mod breadcrumb;
 pub mod command_palette;
+pub mod highlight;
 pub mod markdown;
 pub mod panel;
 pub mod popup;
@@ -11,3 +12,4 @@ pub mod preview;
 pub mod remote_connect;
 pub mod statusbar;
 pub mod tab_bar;
+pub mod truncate;
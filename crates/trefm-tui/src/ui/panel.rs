@@ -2,6 +2,9 @@
 //!
 //! Renders the main file list as a scrollable `List` widget
 //! with per-file git status icons (M/A/D/R/?/!) when status data is available.
+//! [`EntryMarkers`] decides how an entry's type is shown: a Nerd Font icon,
+//! a `/` before directories, or — in no-colour mode, where the type colours
+//! are gone — `/` after directories and `@` after symlinks.
 
 use std::collections::HashMap;
 use std::path::PathBuf;
@@ -13,15 +16,58 @@ use ratatui::{
     widgets::{Block, Borders, List, ListItem, ListState},
     Frame,
 };
+use trefm_core::config::settings::NameTruncation;
 use trefm_core::config::theme::{parse_color, Theme};
 use trefm_core::fs::entry::FileEntry;
 use trefm_core::git::status::GitFileStatus;
+use unicode_width::UnicodeWidthStr;
 
 use crate::icons::icon_for_entry;
+use crate::ui::truncate::truncate_name;
+
+/// Columns taken by the borders and the `> ` highlight symbol.
+const LIST_CHROME_WIDTH: usize = 4;
+
+/// How the file list marks an entry's type around its name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntryMarkers {
+    /// Nerd Font icon before the name.
+    Icons,
+    /// `/` before directories, a space before everything else.
+    Prefix,
+    /// Plain text after the name: `/` for directories, `@` for symlinks.
+    Suffix,
+}
+
+impl EntryMarkers {
+    /// Markers for the `[ui]` settings; no-colour mode overrides `show_icons`.
+    pub fn from_settings(show_icons: bool, no_color: bool) -> Self {
+        if no_color {
+            Self::Suffix
+        } else if show_icons {
+            Self::Icons
+        } else {
+            Self::Prefix
+        }
+    }
+
+    /// Text placed before and after `entry`'s name.
+    fn around(self, entry: &FileEntry) -> (&'static str, &'static str) {
+        match self {
+            Self::Icons => (icon_for_entry(entry), ""),
+            Self::Prefix if entry.is_dir() => ("/", ""),
+            Self::Prefix => (" ", ""),
+            Self::Suffix if entry.is_symlink() => ("", "@"),
+            Self::Suffix if entry.is_dir() => ("", "/"),
+            Self::Suffix => ("", ""),
+        }
+    }
+}
 
 /// Renders a file list panel with directory entries highlighted.
 /// Directories are shown in blue+bold; the selected item is reversed.
 /// When `git_statuses` is provided, a status icon is shown before each filename.
+/// Names wider than the list are shortened according to `name_truncation`.
 #[allow(clippy::too_many_arguments)]
 pub fn render_file_list(
     f: &mut Frame,
@@ -31,25 +77,24 @@ pub fn render_file_list(
     title: &str,
     git_statuses: Option<&HashMap<PathBuf, GitFileStatus>>,
     theme: &Theme,
-    show_icons: bool,
+    markers: EntryMarkers,
     is_active: bool,
+    name_truncation: NameTruncation,
 ) {
     let selected_color = parse_color(&theme.panel.selected_fg);
+    let list_width = (area.width as usize).saturating_sub(LIST_CHROME_WIDTH);
 
     let items: Vec<ListItem> = entries
         .iter()
         .map(|entry| {
             let git_span = git_status_span(entry, git_statuses, theme);
-            let icon_str = if show_icons {
-                icon_for_entry(entry)
-            } else if entry.is_dir() {
-                "/"
-            } else {
-                " "
-            };
-            let display = format!("{icon_str}{}", entry.name());
+            let (prefix, suffix) = markers.around(entry);
+            let name_width =
+                list_width.saturating_sub(git_span.width() + prefix.width() + suffix.width());
+            let name = truncate_name(entry.name(), name_width, name_truncation);
+            let display = format!("{prefix}{name}{suffix}");
 
-            let style = entry_style(entry, theme);
+            let style = entry_style(entry, lookup_status(entry, git_statuses), theme);
 
             ListItem::new(Line::from(vec![git_span, Span::styled(display, style)]))
         })
@@ -58,7 +103,7 @@ pub fn render_file_list(
     let border_color = if is_active {
         parse_color(&theme.panel.selected_fg)
     } else {
-        Color::DarkGray
+        parse_color(&theme.panel.inactive_border_fg)
     };
 
     let list = List::new(items)
@@ -83,8 +128,14 @@ pub fn render_file_list(
     f.render_stateful_widget(list, area, &mut state);
 }
 
-fn entry_style(entry: &FileEntry, theme: &Theme) -> Style {
-    if entry.is_dir() {
+/// Returns the name style for an entry. Git-ignored entries are dimmed
+/// regardless of their type.
+fn entry_style(entry: &FileEntry, git_status: GitFileStatus, theme: &Theme) -> Style {
+    if git_status == GitFileStatus::Ignored {
+        Style::default()
+            .fg(parse_color(&theme.git.ignored_fg))
+            .add_modifier(Modifier::DIM)
+    } else if entry.is_dir() {
         Style::default()
             .fg(parse_color(&theme.panel.dir_fg))
             .add_modifier(Modifier::BOLD)
@@ -97,32 +148,49 @@ fn entry_style(entry: &FileEntry, theme: &Theme) -> Style {
     }
 }
 
+/// Looks up an entry's git status, treating a missing map or key as unchanged.
+/// The `..` row never has one.
+fn lookup_status(
+    entry: &FileEntry,
+    git_statuses: Option<&HashMap<PathBuf, GitFileStatus>>,
+) -> GitFileStatus {
+    if entry.is_parent_link() {
+        return GitFileStatus::Unchanged;
+    }
+    git_statuses
+        .and_then(|statuses| statuses.get(entry.path()).copied())
+        .unwrap_or(GitFileStatus::Unchanged)
+}
+
 /// Returns a styled span for the git status icon of a file entry.
 fn git_status_span(
     entry: &FileEntry,
     git_statuses: Option<&HashMap<PathBuf, GitFileStatus>>,
     theme: &Theme,
 ) -> Span<'static> {
-    let Some(statuses) = git_statuses else {
+    if git_statuses.is_none() {
         return Span::raw(" ");
-    };
+    }
 
-    let status = statuses
-        .get(entry.path())
-        .copied()
-        .unwrap_or(GitFileStatus::Unchanged);
-
-    let (icon, color) = match status {
-        GitFileStatus::Modified => ("M", parse_color(&theme.git.modified_fg)),
-        GitFileStatus::Added => ("A", parse_color(&theme.git.added_fg)),
-        GitFileStatus::Deleted => ("D", parse_color(&theme.git.deleted_fg)),
-        GitFileStatus::Renamed => ("R", parse_color(&theme.git.renamed_fg)),
-        GitFileStatus::Untracked => ("?", parse_color(&theme.git.untracked_fg)),
-        GitFileStatus::Ignored => ("!", parse_color(&theme.git.ignored_fg)),
-        GitFileStatus::Unchanged => (" ", Color::Reset),
-    };
+    let status = lookup_status(entry, git_statuses);
+
+    Span::styled(
+        format!("{} ", status.short_code()),
+        Style::default().fg(git_status_color(status, theme)),
+    )
+}
 
-    Span::styled(format!("{icon} "), Style::default().fg(color))
+/// Theme colour used for a git status marker.
+fn git_status_color(status: GitFileStatus, theme: &Theme) -> Color {
+    match status {
+        GitFileStatus::Modified => parse_color(&theme.git.modified_fg),
+        GitFileStatus::Added => parse_color(&theme.git.added_fg),
+        GitFileStatus::Deleted => parse_color(&theme.git.deleted_fg),
+        GitFileStatus::Renamed => parse_color(&theme.git.renamed_fg),
+        GitFileStatus::Untracked => parse_color(&theme.git.untracked_fg),
+        GitFileStatus::Ignored => parse_color(&theme.git.ignored_fg),
+        GitFileStatus::Unchanged => Color::Reset,
+    }
 }
 
 #[cfg(test)]
@@ -272,11 +340,59 @@ mod tests {
         assert_eq!(span.content.as_ref(), "M ");
     }
 
+    #[test]
+    fn suffix_markers_tag_dirs() {
+        let tmp = TempDir::new().unwrap();
+        let dir = make_dir_entry(&tmp, "src");
+        let file = make_file_entry(&tmp, "notes.txt");
+
+        assert_eq!(EntryMarkers::Suffix.around(&dir), ("", "/"));
+        assert_eq!(EntryMarkers::Suffix.around(&file), ("", ""));
+        assert_eq!(EntryMarkers::Prefix.around(&dir), ("/", ""));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn suffix_markers_tag_symlinks() {
+        let tmp = TempDir::new().unwrap();
+        let dir = make_dir_entry(&tmp, "src");
+        let path = tmp.path().join("latest");
+        std::os::unix::fs::symlink(dir.path(), &path).unwrap();
+        let link = FileEntry::new(path.clone(), &stdfs::symlink_metadata(&path).unwrap());
+
+        assert_eq!(EntryMarkers::Suffix.around(&link), ("", "@"));
+    }
+
+    #[test]
+    fn no_color_overrides_icons() {
+        assert_eq!(
+            EntryMarkers::from_settings(true, true),
+            EntryMarkers::Suffix
+        );
+        assert_eq!(
+            EntryMarkers::from_settings(true, false),
+            EntryMarkers::Icons
+        );
+        assert_eq!(
+            EntryMarkers::from_settings(false, false),
+            EntryMarkers::Prefix
+        );
+    }
+
+    #[test]
+    fn plain_theme_leaves_entries_uncolored() {
+        let tmp = TempDir::new().unwrap();
+        let dir = make_dir_entry(&tmp, "mydir");
+        let style = entry_style(&dir, GitFileStatus::Unchanged, &Theme::plain());
+        assert_eq!(style.fg, Some(Color::Reset));
+        assert!(style.add_modifier.contains(Modifier::BOLD));
+    }
+
     #[test]
     fn entry_style_dir_is_bold_blue() {
         let tmp = TempDir::new().unwrap();
         let entry = make_dir_entry(&tmp, "mydir");
-        let style = entry_style(&entry, &default_theme());
+        let style = entry_style(&entry, GitFileStatus::Unchanged, &default_theme());
         assert_eq!(style.fg, Some(Color::Blue));
         assert!(style.add_modifier.contains(Modifier::BOLD));
     }
@@ -285,7 +401,17 @@ mod tests {
     fn entry_style_hidden_is_dark_gray() {
         let tmp = TempDir::new().unwrap();
         let entry = make_hidden_entry(&tmp);
-        let style = entry_style(&entry, &default_theme());
+        let style = entry_style(&entry, GitFileStatus::Unchanged, &default_theme());
+        assert_eq!(style.fg, Some(Color::DarkGray));
+    }
+
+    #[test]
+    fn entry_style_ignored_is_dimmed() {
+        let tmp = TempDir::new().unwrap();
+        let entry = make_dir_entry(&tmp, "target");
+        let style = entry_style(&entry, GitFileStatus::Ignored, &default_theme());
         assert_eq!(style.fg, Some(Color::DarkGray));
+        assert!(style.add_modifier.contains(Modifier::DIM));
+        assert!(!style.add_modifier.contains(Modifier::BOLD));
     }
 }
@@ -1,21 +1,29 @@
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
-    style::Style,
+    style::{Modifier, Style},
     text::Line,
     widgets::{Block, Borders, Clear, Paragraph},
     Frame,
 };
+use std::path::{Path, PathBuf};
+
 use trefm_core::config::theme::{parse_color, Theme};
+use trefm_core::fs::relative_display_path;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
 /// Renders a centered popup dialog with the given title and message lines.
 pub fn render_popup(f: &mut Frame, title: &str, lines: &[String], theme: &Theme) {
+    let content: Vec<Line> = lines.iter().map(|l| Line::from(l.as_str())).collect();
+    render_popup_lines(f, title, content, theme);
+}
+
+/// [`render_popup`] with lines that carry their own styling.
+pub fn render_popup_lines(f: &mut Frame, title: &str, content: Vec<Line>, theme: &Theme) {
     let area = centered_rect(50, 40, f.area());
     let border_fg = parse_color(&theme.popup.border_fg);
 
     f.render_widget(Clear, area);
 
-    let content: Vec<Line> = lines.iter().map(|l| Line::from(l.as_str())).collect();
-
     let popup = Paragraph::new(content).block(
         Block::default()
             .borders(Borders::ALL)
@@ -26,6 +34,96 @@ pub fn render_popup(f: &mut Frame, title: &str, lines: &[String], theme: &Theme)
     f.render_widget(popup, area);
 }
 
+/// Style for the characters a fuzzy query matched in a result list. Bold
+/// as well as coloured, so matches stand out in no-colour mode too.
+pub fn match_style(theme: &Theme) -> Style {
+    Style::default()
+        .fg(parse_color(&theme.popup.match_fg))
+        .add_modifier(Modifier::BOLD)
+}
+
+/// Number of message lines that fit inside a [`render_popup`] drawn on `area`.
+pub fn popup_body_rows(area: Rect) -> usize {
+    usize::from(centered_rect(50, 40, area).height.saturating_sub(2))
+}
+
+/// Number of columns a message line may use inside a [`render_popup`] drawn on `area`.
+pub fn popup_body_cols(area: Rect) -> usize {
+    usize::from(centered_rect(50, 40, area).width.saturating_sub(2))
+}
+
+/// Splits `text` into lines at most `width` columns wide, breaking at
+/// spaces and, for words longer than a line, inside the word.
+pub fn wrap_text(text: &str, width: usize) -> Vec<String> {
+    let width = width.max(1);
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    let mut line_width = 0;
+    for word in text.split(' ') {
+        let joined = if line.is_empty() {
+            word.width()
+        } else {
+            line_width + 1 + word.width()
+        };
+        if joined <= width {
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(word);
+            line_width = joined;
+            continue;
+        }
+        if !line.is_empty() {
+            lines.push(std::mem::take(&mut line));
+            line_width = 0;
+        }
+        for c in word.chars() {
+            let w = c.width().unwrap_or(0);
+            if line_width + w > width && !line.is_empty() {
+                lines.push(std::mem::take(&mut line));
+                line_width = 0;
+            }
+            line.push(c);
+            line_width += w;
+        }
+    }
+    if !line.is_empty() || lines.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+/// Lines for the delete confirmation: a count, then each target relative to
+/// `root`, then the y/n hints.
+///
+/// The whole popup is kept within `max_rows` lines. When the targets do not
+/// all fit, the last visible row says how many were left out.
+pub fn delete_confirm_lines(paths: &[PathBuf], root: &Path, max_rows: usize) -> Vec<String> {
+    let footer = [
+        String::new(),
+        "y - Yes, delete".to_owned(),
+        "n - No, cancel".to_owned(),
+    ];
+    let room = max_rows.saturating_sub(2 + footer.len()).max(1);
+    let shown = if paths.len() > room {
+        room - 1
+    } else {
+        paths.len()
+    };
+
+    let mut lines = vec![format!("Delete {} item(s)?", paths.len()), String::new()];
+    lines.extend(
+        paths[..shown]
+            .iter()
+            .map(|p| format!("  {}", relative_display_path(p, root))),
+    );
+    if shown < paths.len() {
+        lines.push(format!("  … and {} more", paths.len() - shown));
+    }
+    lines.extend(footer);
+    lines
+}
+
 /// Calculates a centered rectangle of the given percentage size within the parent area.
 fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
     let vertical = Layout::default()
@@ -46,3 +144,68 @@ fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
         ])
         .split(vertical[1])[1]
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn targets(count: usize) -> Vec<PathBuf> {
+        (0..count)
+            .map(|i| PathBuf::from(format!("/work/file{i}.txt")))
+            .collect()
+    }
+
+    #[test]
+    fn delete_confirm_lists_every_target_that_fits() {
+        let lines = delete_confirm_lines(&targets(3), Path::new("/work"), 20);
+
+        assert_eq!(lines[0], "Delete 3 item(s)?");
+        assert_eq!(&lines[2..5], ["  file0.txt", "  file1.txt", "  file2.txt"]);
+        assert_eq!(lines.last().unwrap(), "n - No, cancel");
+    }
+
+    #[test]
+    fn long_delete_confirm_is_truncated_to_fit() {
+        let lines = delete_confirm_lines(&targets(50), Path::new("/work"), 10);
+
+        assert_eq!(lines.len(), 10);
+        assert_eq!(lines[0], "Delete 50 item(s)?");
+        assert_eq!(
+            &lines[2..6],
+            ["  file0.txt", "  file1.txt", "  file2.txt", "  file3.txt"]
+        );
+        assert_eq!(lines[6], "  … and 46 more");
+    }
+
+    #[test]
+    fn targets_outside_root_stay_absolute() {
+        let paths = [PathBuf::from("/elsewhere/a.txt")];
+        let lines = delete_confirm_lines(&paths, Path::new("/work"), 20);
+
+        assert_eq!(lines[2], "  /elsewhere/a.txt");
+    }
+
+    #[test]
+    fn body_rows_exclude_the_border() {
+        assert_eq!(popup_body_rows(Rect::new(0, 0, 100, 50)), 18);
+        assert_eq!(popup_body_cols(Rect::new(0, 0, 100, 50)), 48);
+    }
+
+    #[test]
+    fn wrap_breaks_at_spaces() {
+        assert_eq!(
+            wrap_text("Paste failed: permission denied", 14),
+            ["Paste failed:", "permission", "denied"]
+        );
+        assert_eq!(wrap_text("short", 14), ["short"]);
+        assert_eq!(wrap_text("", 14), [""]);
+    }
+
+    #[test]
+    fn wrap_splits_words_longer_than_a_line() {
+        let lines = wrap_text("at /very/long/path/to/file.txt", 10);
+
+        assert_eq!(lines, ["at", "/very/long", "/path/to/f", "ile.txt"]);
+        assert!(lines.iter().all(|l| l.width() <= 10));
+    }
+}
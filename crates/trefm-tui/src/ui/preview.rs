@@ -19,8 +19,9 @@ use ratatui::{
 use crate::image_preview::ImagePreviewState;
 use syntect::highlighting::{FontStyle, ThemeSet};
 use syntect::parsing::SyntaxSet;
-use trefm_core::config::theme::{parse_color, Theme};
+use trefm_core::config::theme::{parse_color, Theme, NO_SYNTAX_THEME};
 use trefm_core::fs::entry::FileEntry;
+use trefm_core::fs::format::{human_size, SizeUnits};
 use trefm_core::fs::preview::{
     is_binary, is_image, is_pdf, read_directory_tree, read_image_info, read_pdf_info,
     read_text_preview,
@@ -54,6 +55,7 @@ pub fn render_preview(
     theme: &Theme,
     show_icons: bool,
     image_state: Option<&mut ImagePreviewState>,
+    size_units: SizeUnits,
 ) {
     let border_fg = parse_color(&theme.preview.border_fg);
 
@@ -64,23 +66,23 @@ pub fn render_preview(
         },
         Some(entry) if is_image(entry.path()) => {
             if let Some(img_state) = image_state {
-                render_image_with_metadata(f, area, entry, theme, img_state);
+                render_image_with_metadata(f, area, entry, theme, img_state, size_units);
                 return;
             }
             PreviewContent {
-                lines: render_image_preview(entry, theme),
+                lines: render_image_preview(entry, theme, size_units),
                 theme_bg: None,
             }
         }
         Some(entry) if is_pdf(entry.path()) => PreviewContent {
-            lines: render_pdf_preview(entry, theme),
+            lines: render_pdf_preview(entry, theme, size_units),
             theme_bg: None,
         },
         Some(entry) if is_markdown(entry.path()) => PreviewContent {
             lines: render_markdown_preview(entry, theme),
             theme_bg: None,
         },
-        Some(entry) => render_file_preview(entry, theme),
+        Some(entry) => render_file_preview(entry, theme, size_units),
         None => PreviewContent {
             lines: vec![Line::from(Span::styled(
                 "No file selected",
@@ -229,7 +231,11 @@ fn icon_for_extension(name: &str) -> &'static str {
 }
 
 /// Renders an image file preview showing metadata.
-fn render_image_preview(entry: &FileEntry, theme: &Theme) -> Vec<Line<'static>> {
+fn render_image_preview(
+    entry: &FileEntry,
+    theme: &Theme,
+    size_units: SizeUnits,
+) -> Vec<Line<'static>> {
     let dir_title_fg = parse_color(&theme.preview.dir_title_fg);
     let error_fg = parse_color(&theme.preview.error_fg);
 
@@ -253,7 +259,7 @@ fn render_image_preview(entry: &FileEntry, theme: &Theme) -> Vec<Line<'static>>
             lines.push(Line::from(format!("  Color:      {}", info.color_type)));
             lines.push(Line::from(format!(
                 "  File size:  {}",
-                format_preview_size(info.file_size)
+                human_size(info.file_size, size_units)
             )));
         }
         Err(e) => {
@@ -263,7 +269,7 @@ fn render_image_preview(entry: &FileEntry, theme: &Theme) -> Vec<Line<'static>>
             )));
             lines.push(Line::from(format!(
                 "  File size: {}",
-                format_preview_size(entry.size())
+                human_size(entry.size(), size_units)
             )));
         }
     }
@@ -272,7 +278,11 @@ fn render_image_preview(entry: &FileEntry, theme: &Theme) -> Vec<Line<'static>>
 }
 
 /// Renders a PDF file preview showing metadata.
-fn render_pdf_preview(entry: &FileEntry, theme: &Theme) -> Vec<Line<'static>> {
+fn render_pdf_preview(
+    entry: &FileEntry,
+    theme: &Theme,
+    size_units: SizeUnits,
+) -> Vec<Line<'static>> {
     let dir_title_fg = parse_color(&theme.preview.dir_title_fg);
     let error_fg = parse_color(&theme.preview.error_fg);
 
@@ -301,7 +311,7 @@ fn render_pdf_preview(entry: &FileEntry, theme: &Theme) -> Vec<Line<'static>> {
             }
             lines.push(Line::from(format!(
                 "  File size: {}",
-                format_preview_size(info.file_size)
+                human_size(info.file_size, size_units)
             )));
         }
         Err(e) => {
@@ -311,7 +321,7 @@ fn render_pdf_preview(entry: &FileEntry, theme: &Theme) -> Vec<Line<'static>> {
             )));
             lines.push(Line::from(format!(
                 "  File size: {}",
-                format_preview_size(entry.size())
+                human_size(entry.size(), size_units)
             )));
         }
     }
@@ -354,7 +364,7 @@ fn render_markdown_preview(entry: &FileEntry, theme: &Theme) -> Vec<Line<'static
 }
 
 /// Renders a file preview — syntax-highlighted text or binary message.
-fn render_file_preview(entry: &FileEntry, theme: &Theme) -> PreviewContent {
+fn render_file_preview(entry: &FileEntry, theme: &Theme, size_units: SizeUnits) -> PreviewContent {
     let path = entry.path();
     let error_fg = parse_color(&theme.preview.error_fg);
     let line_number_fg = parse_color(&theme.preview.line_number_fg);
@@ -365,7 +375,7 @@ fn render_file_preview(entry: &FileEntry, theme: &Theme) -> PreviewContent {
         Ok(true) => {
             return PreviewContent {
                 lines: vec![Line::from(Span::styled(
-                    format!("Binary file - {}", format_preview_size(entry.size())),
+                    format!("Binary file - {}", human_size(entry.size(), size_units)),
                     Style::default().fg(Color::DarkGray),
                 ))],
                 theme_bg: None,
@@ -449,6 +459,11 @@ fn highlight_lines(
     lines: &[String],
     syntax_theme_name: &str,
 ) -> (Vec<Vec<Span<'static>>>, Option<Color>) {
+    if syntax_theme_name == NO_SYNTAX_THEME {
+        let plain = lines.iter().map(|line| vec![Span::raw(line.clone())]);
+        return (plain.collect(), None);
+    }
+
     let ss = syntax_set();
     let ts = theme_set();
 
@@ -518,22 +533,6 @@ pub fn highlight_lines_for_pager(
     highlight_lines(path, lines, syntax_theme_name)
 }
 
-fn format_preview_size(bytes: u64) -> String {
-    const KB: u64 = 1024;
-    const MB: u64 = KB * 1024;
-    const GB: u64 = MB * 1024;
-
-    if bytes >= GB {
-        format!("{:.1} GB", bytes as f64 / GB as f64)
-    } else if bytes >= MB {
-        format!("{:.1} MB", bytes as f64 / MB as f64)
-    } else if bytes >= KB {
-        format!("{:.1} KB", bytes as f64 / KB as f64)
-    } else {
-        format!("{bytes} B")
-    }
-}
-
 /// Renders an actual image (via terminal graphics protocol) with compact metadata below.
 fn render_image_with_metadata(
     f: &mut Frame,
@@ -541,6 +540,7 @@ fn render_image_with_metadata(
     entry: &FileEntry,
     theme: &Theme,
     image_state: &mut ImagePreviewState,
+    size_units: SizeUnits,
 ) {
     let border_fg = parse_color(&theme.preview.border_fg);
     let block = Block::default()
@@ -555,7 +555,7 @@ fn render_image_with_metadata(
 
     if image_height < 3 {
         // Not enough space for image — fall back to metadata only
-        let lines = render_image_preview(entry, theme);
+        let lines = render_image_preview(entry, theme, size_units);
         let p = Paragraph::new(lines);
         f.render_widget(p, inner);
         return;
@@ -577,19 +577,23 @@ fn render_image_with_metadata(
         f.render_stateful_widget(widget, chunks[0], protocol);
     } else {
         // Decode failed — show metadata only
-        let lines = render_image_preview(entry, theme);
+        let lines = render_image_preview(entry, theme, size_units);
         let p = Paragraph::new(lines);
         f.render_widget(p, inner);
         return;
     }
 
     // Compact metadata below the image
-    let meta = build_compact_metadata(entry, theme);
+    let meta = build_compact_metadata(entry, theme, size_units);
     f.render_widget(Paragraph::new(meta), chunks[1]);
 }
 
 /// Builds a compact one-line metadata summary for display below the image.
-fn build_compact_metadata(entry: &FileEntry, theme: &Theme) -> Vec<Line<'static>> {
+fn build_compact_metadata(
+    entry: &FileEntry,
+    theme: &Theme,
+    size_units: SizeUnits,
+) -> Vec<Line<'static>> {
     let title_fg = parse_color(&theme.preview.dir_title_fg);
 
     let mut lines = vec![Line::from("")];
@@ -606,7 +610,7 @@ fn build_compact_metadata(entry: &FileEntry, theme: &Theme) -> Vec<Line<'static>
                     info.width,
                     info.height,
                     info.format,
-                    format_preview_size(info.file_size),
+                    human_size(info.file_size, size_units),
                 )),
             ]));
         }
@@ -614,7 +618,7 @@ fn build_compact_metadata(entry: &FileEntry, theme: &Theme) -> Vec<Line<'static>
             lines.push(Line::from(format!(
                 "{}  {}",
                 entry.name(),
-                format_preview_size(entry.size()),
+                human_size(entry.size(), size_units),
             )));
         }
     }
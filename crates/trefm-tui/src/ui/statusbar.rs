@@ -1,8 +1,9 @@
 //! Status bar rendering with git branch information and theme support.
 //!
 //! The status bar occupies a single row at the bottom of the terminal and
-//! shows the cursor position, selected file info, hidden-file indicator,
-//! git branch name with dirty marker, and an optional status message.
+//! shows the cursor position, selected file info, hidden-file, category-
+//! and name-filter indicators, git branch name with dirty marker, and an
+//! optional status message.
 
 use ratatui::{
     layout::Rect,
@@ -13,17 +14,29 @@ use ratatui::{
 };
 use trefm_core::config::theme::{parse_color, Theme};
 use trefm_core::fs::entry::FileEntry;
+use trefm_core::fs::format::{human_size, SizeUnits};
 use trefm_core::git::branch::BranchInfo;
 
+use crate::notifications::NotificationLevel;
+
 /// Data needed to render the status bar.
 pub struct StatusBarProps<'a> {
     pub entry_count: usize,
     pub selected_index: usize,
     pub selected_entry: Option<&'a FileEntry>,
     pub show_hidden: bool,
+    /// Label of the active category or directories-only filter, if any.
+    pub category_filter: Option<&'static str>,
+    /// Query of the panel's name filter, if one is set or being typed.
+    pub name_filter: Option<&'a str>,
     pub status_message: Option<&'a str>,
+    /// Severity of `status_message`, which picks its colour.
+    pub status_level: NotificationLevel,
     pub branch_info: Option<&'a BranchInfo>,
     pub remote_label: Option<&'a str>,
+    /// Number of paths waiting to be pasted.
+    pub clipboard_count: usize,
+    pub size_units: SizeUnits,
 }
 
 /// Renders the bottom status bar showing file count, git branch, and selected file info.
@@ -31,7 +44,12 @@ pub fn render_statusbar(f: &mut Frame, area: Rect, props: &StatusBarProps<'_>, t
     let bg = parse_color(&theme.statusbar.bg);
     let position_fg = parse_color(&theme.statusbar.position_fg);
     let hidden_fg = parse_color(&theme.statusbar.hidden_fg);
-    let message_fg = parse_color(&theme.statusbar.message_fg);
+    let message_fg = parse_color(match props.status_level {
+        NotificationLevel::Info => &theme.statusbar.message_fg,
+        NotificationLevel::Success => &theme.statusbar.success_fg,
+        NotificationLevel::Warning => &theme.statusbar.warning_fg,
+        NotificationLevel::Error => &theme.statusbar.error_fg,
+    });
 
     let position = if props.entry_count > 0 {
         format!(" {}/{}", props.selected_index + 1, props.entry_count)
@@ -45,12 +63,29 @@ pub fn render_statusbar(f: &mut Frame, area: Rect, props: &StatusBarProps<'_>, t
             if e.is_dir() {
                 format!("  [DIR] {}", e.name())
             } else {
-                format!("  {} ({})", e.name(), format_size(e.size()))
+                format!(
+                    "  {} ({})",
+                    e.name(),
+                    human_size(e.size(), props.size_units)
+                )
             }
         })
         .unwrap_or_default();
 
     let hidden_indicator = if props.show_hidden { " [H]" } else { "" };
+    let category_indicator = props
+        .category_filter
+        .map(|label| format!(" [{label}]"))
+        .unwrap_or_default();
+    let name_filter_indicator = props
+        .name_filter
+        .map(|query| format!(" [|{query}]"))
+        .unwrap_or_default();
+    let clipboard_indicator = if props.clipboard_count > 0 {
+        format!(" [Y:{}]", props.clipboard_count)
+    } else {
+        String::new()
+    };
 
     let branch_span = branch_info_span(props.branch_info, theme);
 
@@ -96,6 +131,21 @@ pub fn render_statusbar(f: &mut Frame, area: Rect, props: &StatusBarProps<'_>, t
                 .bg(bg)
                 .add_modifier(Modifier::BOLD),
         ),
+        Span::styled(
+            category_indicator,
+            Style::default()
+                .fg(hidden_fg)
+                .bg(bg)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(
+            name_filter_indicator,
+            Style::default()
+                .fg(hidden_fg)
+                .bg(bg)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(clipboard_indicator, Style::default().fg(position_fg).bg(bg)),
         remote_span,
         branch_span,
         status_span,
@@ -131,22 +181,6 @@ fn branch_info_span(info: Option<&BranchInfo>, theme: &Theme) -> Span<'static> {
     )
 }
 
-fn format_size(bytes: u64) -> String {
-    const KB: u64 = 1024;
-    const MB: u64 = KB * 1024;
-    const GB: u64 = MB * 1024;
-
-    if bytes >= GB {
-        format!("{:.1} GB", bytes as f64 / GB as f64)
-    } else if bytes >= MB {
-        format!("{:.1} MB", bytes as f64 / MB as f64)
-    } else if bytes >= KB {
-        format!("{:.1} KB", bytes as f64 / KB as f64)
-    } else {
-        format!("{bytes} B")
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -245,31 +279,4 @@ mod tests {
         assert!(content.contains("dev*"));
         assert_eq!(span.style.fg, Some(Color::Yellow));
     }
-
-    // --- format_size tests ---
-
-    #[test]
-    fn format_size_bytes() {
-        assert_eq!(format_size(0), "0 B");
-        assert_eq!(format_size(512), "512 B");
-        assert_eq!(format_size(1023), "1023 B");
-    }
-
-    #[test]
-    fn format_size_kilobytes() {
-        assert_eq!(format_size(1024), "1.0 KB");
-        assert_eq!(format_size(1536), "1.5 KB");
-    }
-
-    #[test]
-    fn format_size_megabytes() {
-        assert_eq!(format_size(1024 * 1024), "1.0 MB");
-        assert_eq!(format_size(5 * 1024 * 1024), "5.0 MB");
-    }
-
-    #[test]
-    fn format_size_gigabytes() {
-        assert_eq!(format_size(1024 * 1024 * 1024), "1.0 GB");
-        assert_eq!(format_size(2 * 1024 * 1024 * 1024), "2.0 GB");
-    }
 }
@@ -25,7 +25,7 @@ pub fn render_tab_bar(f: &mut Frame, area: Rect, tab_group: &TabGroup, theme: &T
         if i > 0 {
             spans.push(Span::raw(" "));
         }
-        let label = format!(" {} ", tab.label);
+        let label = format!(" {} ", tab.display_label());
         if i == active_idx {
             spans.push(Span::styled(
                 label,
@@ -0,0 +1,200 @@
+//! Shortening file names to a column width and padding them to it.
+//!
+//! Widths are measured in terminal columns and names are cut only between
+//! grapheme clusters, so CJK characters and emoji (two columns each) and
+//! combining marks are never split. When a wide character would straddle the
+//! limit it is dropped, leaving the result one column short rather than too
+//! long; [`fit_to_width`] pads that column back so the next one lines up.
+
+use trefm_core::config::settings::NameTruncation;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+const ELLIPSIS: &str = "…";
+
+/// Returns `name` shortened to at most `max_width` columns using `style`.
+///
+/// Names that already fit, and every name under [`NameTruncation::None`],
+/// are returned unchanged.
+pub fn truncate_name(name: &str, max_width: usize, style: NameTruncation) -> String {
+    if style == NameTruncation::None || name.width() <= max_width {
+        return name.to_owned();
+    }
+    if max_width == 0 {
+        return String::new();
+    }
+    match style {
+        NameTruncation::Middle => truncate_middle(name, max_width),
+        _ => truncate_end(name, max_width),
+    }
+}
+
+/// `text` followed by spaces up to `width` columns. Text at least that wide
+/// is returned unchanged.
+pub fn pad_to_width(text: &str, width: usize) -> String {
+    let pad = width.saturating_sub(text.width());
+    format!("{text}{}", " ".repeat(pad))
+}
+
+/// `text` after spaces up to `width` columns, for right-aligned columns
+/// such as sizes.
+pub fn pad_left_to_width(text: &str, width: usize) -> String {
+    let pad = width.saturating_sub(text.width());
+    format!("{}{text}", " ".repeat(pad))
+}
+
+/// `name` shortened with `style` and padded to exactly `width` columns, so
+/// whatever follows it starts at the same column on every row. Under
+/// [`NameTruncation::None`] a longer name is left to overflow.
+pub fn fit_to_width(name: &str, width: usize, style: NameTruncation) -> String {
+    pad_to_width(&truncate_name(name, width, style), width)
+}
+
+/// `head…`: as much of the start as fits.
+fn truncate_end(name: &str, max_width: usize) -> String {
+    let head = take_width(name.graphemes(true), max_width - 1);
+    format!("{head}{ELLIPSIS}")
+}
+
+/// `head…tail`, where the tail is the extension when there is one and
+/// otherwise the last part of the name. Falls back to [`truncate_end`]
+/// when the extension leaves no room for the start.
+fn truncate_middle(name: &str, max_width: usize) -> String {
+    let budget = max_width - 1;
+    let tail = match extension_start(name) {
+        Some(dot) => {
+            let ext = &name[dot..];
+            if ext.width() + 1 > budget {
+                return truncate_end(name, max_width);
+            }
+            ext.to_owned()
+        }
+        None => {
+            let mut tail: Vec<&str> = Vec::new();
+            let mut width = 0;
+            for g in name.graphemes(true).rev() {
+                if width + g.width() > budget / 2 {
+                    break;
+                }
+                width += g.width();
+                tail.push(g);
+            }
+            tail.into_iter().rev().collect()
+        }
+    };
+    let head = take_width(name.graphemes(true), budget - tail.width());
+    format!("{head}{ELLIPSIS}{tail}")
+}
+
+/// Byte index of the extension's dot; dotfiles like `.bashrc` have none.
+fn extension_start(name: &str) -> Option<usize> {
+    name.rfind('.')
+        .filter(|&dot| dot > 0 && dot + 1 < name.len())
+}
+
+/// Leading graphemes whose total width is at most `max_width`.
+fn take_width<'a>(graphemes: impl Iterator<Item = &'a str>, max_width: usize) -> String {
+    let mut out = String::new();
+    let mut width = 0;
+    for g in graphemes {
+        if width + g.width() > max_width {
+            break;
+        }
+        width += g.width();
+        out.push_str(g);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn short_names_are_untouched() {
+        for style in [NameTruncation::End, NameTruncation::Middle] {
+            assert_eq!(truncate_name("notes.md", 8, style), "notes.md");
+        }
+    }
+
+    #[test]
+    fn end_truncation_cuts_the_tail() {
+        assert_eq!(
+            truncate_name("quarterly-report.pdf", 10, NameTruncation::End),
+            "quarterly…"
+        );
+    }
+
+    #[test]
+    fn middle_truncation_keeps_the_extension() {
+        let short = truncate_name("quarterly-report-final.pdf", 12, NameTruncation::Middle);
+        assert_eq!(short, "quarter….pdf");
+        assert_eq!(short.width(), 12);
+    }
+
+    #[test]
+    fn middle_truncation_without_extension_keeps_both_ends() {
+        assert_eq!(
+            truncate_name("abcdefghijklmnop", 9, NameTruncation::Middle),
+            "abcd…mnop"
+        );
+    }
+
+    #[test]
+    fn long_extension_falls_back_to_end_truncation() {
+        assert_eq!(
+            truncate_name("a.verylongextension", 8, NameTruncation::Middle),
+            "a.veryl…"
+        );
+    }
+
+    #[test]
+    fn wide_characters_never_exceed_the_width() {
+        let name = "한국어파일이름입니다.txt";
+        for max in 1..name.width() {
+            for style in [NameTruncation::End, NameTruncation::Middle] {
+                let short = truncate_name(name, max, style);
+                assert!(short.width() <= max, "{style:?} {max}: {short}");
+            }
+        }
+        assert_eq!(truncate_name(name, 9, NameTruncation::End), "한국어파…");
+        assert_eq!(truncate_name(name, 10, NameTruncation::Middle), "한국….txt");
+    }
+
+    #[test]
+    fn columns_after_wide_names_line_up() {
+        let row = |name: &str| {
+            format!(
+                "{}  {}  {}",
+                fit_to_width(name, 12, NameTruncation::End),
+                pad_left_to_width("1.5 KiB", 9),
+                "2024-01-02"
+            )
+        };
+        let ascii = row("plain-name.txt");
+        let cjk = row("한국어파일이름.txt");
+
+        assert_eq!(ascii.width(), cjk.width());
+        for column in ["1.5 KiB", "2024-01-02"] {
+            let ascii_col = ascii[..ascii.find(column).unwrap()].width();
+            let cjk_col = cjk[..cjk.find(column).unwrap()].width();
+            assert_eq!(ascii_col, cjk_col, "{column}");
+        }
+        assert_eq!(cjk[..cjk.find("1.5 KiB").unwrap()].width(), 12 + 2 + 2);
+    }
+
+    #[test]
+    fn emoji_count_as_two_columns() {
+        assert_eq!(pad_to_width("📁", 4), "📁  ");
+        assert_eq!(pad_left_to_width("🎵", 3), " 🎵");
+        assert_eq!(fit_to_width("📁📁📁📁", 6, NameTruncation::End), "📁📁… ");
+    }
+
+    #[test]
+    fn none_keeps_the_full_name() {
+        assert_eq!(
+            truncate_name("quarterly-report.pdf", 5, NameTruncation::None),
+            "quarterly-report.pdf"
+        );
+    }
+}
@@ -12,8 +12,9 @@ use notify_debouncer_mini::{new_debouncer, DebouncedEventKind, Debouncer};
 /// Messages from the watcher to the main event loop.
 #[derive(Debug)]
 pub enum WatchMessage {
-    /// The watched directory contents changed.
-    Changed,
+    /// The watched directory contents changed. Carries the affected paths,
+    /// or nothing when the refresh was not triggered by a watch event.
+    Changed(Vec<PathBuf>),
     /// An error occurred while watching.
     Error(String),
 }
@@ -36,11 +37,13 @@ impl DirWatcher {
             move |result: Result<Vec<notify_debouncer_mini::DebouncedEvent>, notify::Error>| {
                 match result {
                     Ok(events) => {
-                        let has_change = events
-                            .iter()
-                            .any(|e| matches!(e.kind, DebouncedEventKind::Any));
-                        if has_change {
-                            let _ = tx.send(WatchMessage::Changed);
+                        let paths: Vec<PathBuf> = events
+                            .into_iter()
+                            .filter(|e| matches!(e.kind, DebouncedEventKind::Any))
+                            .map(|e| e.path)
+                            .collect();
+                        if !paths.is_empty() {
+                            let _ = tx.send(WatchMessage::Changed(paths));
                         }
                     }
                     Err(e) => {
@@ -126,6 +129,11 @@ mod tests {
             msg.is_ok(),
             "should receive a change notification after file creation"
         );
-        assert!(matches!(msg.unwrap(), WatchMessage::Changed));
+        match msg.unwrap() {
+            WatchMessage::Changed(paths) => {
+                assert!(paths.iter().any(|p| p.ends_with("new_file.txt")));
+            }
+            other => panic!("unexpected message: {other:?}"),
+        }
     }
 }
@@ -48,12 +48,7 @@ pub async fn login(
         // Single-user mode (legacy)
         if state.config.auth.password_hash.is_empty() {
             // Dev mode: skip all auth
-            let (token, expires_at) = jwt::create_token(
-                &state.config.auth.jwt_secret,
-                state.config.auth.jwt_ttl_hours,
-                "user",
-            )?;
-            return Ok(Json(AuthStepResponse::Complete { token, expires_at }));
+            return complete_login(&state, "user");
         }
 
         let hash = state.config.auth.password_hash.clone();
@@ -84,12 +79,7 @@ pub async fn login(
 
     if !has_webauthn && !has_discord {
         // No additional factors configured -> issue token directly
-        let (token, expires_at) = jwt::create_token(
-            &state.config.auth.jwt_secret,
-            state.config.auth.jwt_ttl_hours,
-            &username,
-        )?;
-        return Ok(Json(AuthStepResponse::Complete { token, expires_at }));
+        return complete_login(&state, &username);
     }
 
     // Create session for multi-step auth
@@ -276,13 +266,7 @@ pub async fn webauthn_verify(
     // No Discord -> complete
     state.session_store.remove(&body.session_id);
 
-    let (token, expires_at) = jwt::create_token(
-        &state.config.auth.jwt_secret,
-        state.config.auth.jwt_ttl_hours,
-        &username,
-    )?;
-
-    Ok(Json(AuthStepResponse::Complete { token, expires_at }))
+    complete_login(&state, &username)
 }
 
 pub async fn otp_verify(
@@ -323,13 +307,32 @@ pub async fn otp_verify(
     tracing::info!("OTP verified successfully for user: {username}");
     state.session_store.remove(&body.session_id);
 
-    let (token, expires_at) = jwt::create_token(
+    complete_login(&state, &username)
+}
+
+/// Issues the JWT and opens the login session that the auth middleware
+/// keeps alive on every request.
+fn complete_login(state: &AppState, username: &str) -> Result<Json<AuthStepResponse>, AppError> {
+    let issued = jwt::create_token(
         &state.config.auth.jwt_secret,
         state.config.auth.jwt_ttl_hours,
-        &username,
+        username,
     )?;
-
-    Ok(Json(AuthStepResponse::Complete { token, expires_at }))
+    state.login_sessions.create(
+        issued.jti,
+        AuthSession {
+            username: username.to_string(),
+            step: AuthStep::Complete,
+            created_at: Instant::now(),
+            otp_code: None,
+            otp_sent_at: None,
+            webauthn_state: None,
+        },
+    );
+    Ok(Json(AuthStepResponse::Complete {
+        token: issued.token,
+        expires_at: issued.expires_at,
+    }))
 }
 
 /// Constant-time byte comparison to prevent timing side-channel attacks on OTP.
@@ -373,6 +376,7 @@ pub async fn logout(
         state
             .revoked_tokens
             .insert(claims.jti.clone(), Instant::now());
+        state.login_sessions.remove(&claims.jti);
         tracing::info!(
             "Token revoked for user: {} (jti: {})",
             claims.sub,
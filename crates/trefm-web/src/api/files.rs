@@ -1,36 +1,124 @@
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+use axum::extract::multipart::{Field, MultipartError};
 use axum::extract::{Multipart, Query, State};
+use axum::http::StatusCode;
 use axum::response::Response;
 use axum::Json;
 
+use crate::audit::AuditAction;
 use crate::auth::middleware::AuthUser;
-use crate::dto::{DownloadQuery, FileEntryDto, ListDirQuery, ListDirResponse, UploadResponse};
+use crate::config::ServerConfig;
+use crate::dto::{
+    DownloadQuery, FileEntryDto, ListDirQuery, ListDirResponse, PreviewQuery, PreviewResponse,
+    UploadResponse,
+};
 use crate::error::AppError;
 use crate::state::AppState;
 
+/// Where one user's file requests may go: inside their root and, when
+/// `filesystem.allowed_roots` is configured, inside one of those as well.
+#[derive(Debug, Clone)]
+pub struct FileScope {
+    root: PathBuf,
+    allowed_roots: Vec<PathBuf>,
+}
+
+impl FileScope {
+    pub fn new(root: PathBuf, allowed_roots: Vec<PathBuf>) -> Self {
+        Self {
+            root,
+            allowed_roots,
+        }
+    }
+
+    pub fn for_user(config: &ServerConfig, username: &str) -> Self {
+        Self::new(
+            config.resolve_root(username).clone(),
+            config.filesystem.allowed_roots.clone(),
+        )
+    }
+
+    /// Directory used when a request names no path.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Checks an already canonicalized path. Because symlinks are resolved
+    /// before this runs, a link pointing out of the scope is refused too.
+    pub fn check(&self, canonical: &Path) -> Result<(), AppError> {
+        let canonical_root = self
+            .root
+            .canonicalize()
+            .map_err(|e| AppError::Internal(format!("Failed to resolve root: {e}")))?;
+        if !canonical.starts_with(&canonical_root) {
+            return Err(AppError::Forbidden("Access denied".to_string()));
+        }
+        if !self.allowed_roots.is_empty()
+            && !self
+                .allowed_roots
+                .iter()
+                .filter_map(|r| r.canonicalize().ok())
+                .any(|r| canonical.starts_with(r))
+        {
+            return Err(AppError::Forbidden("Access denied".to_string()));
+        }
+        Ok(())
+    }
+}
+
 pub async fn list_directory(
     user: AuthUser,
     State(state): State<AppState>,
     Query(query): Query<ListDirQuery>,
 ) -> Result<Json<ListDirResponse>, AppError> {
-    let root = state.config.resolve_root(&user.sub);
+    let scope = FileScope::for_user(&state.config, &user.sub);
+    let result = read_listing(&scope, query.path.as_deref());
+    let path = query.path.as_deref().unwrap_or_default();
+    state.audit.record(&user.sub, AuditAction::List, path, &result);
+    result.map(Json)
+}
+
+/// Lists `path` (or the scope's root when `None`), refusing anything outside
+/// `scope`. Shared by the REST endpoint and the `/ws/files` stream.
+pub fn read_listing(scope: &FileScope, path: Option<&str>) -> Result<ListDirResponse, AppError> {
+    let canonical = resolve_dir(scope, path)?;
+
+    let read_dir = std::fs::read_dir(&canonical)
+        .map_err(|e| AppError::Internal(format!("Failed to read directory: {e}")))?;
+
+    let mut entries: Vec<FileEntryDto> = read_dir
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| entry_dto(&entry.path()))
+        .collect();
+
+    // Sort: directories first, then alphabetical by name
+    entries.sort_by(|a, b| {
+        b.is_dir
+            .cmp(&a.is_dir)
+            .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+    });
 
-    let target = match &query.path {
-        Some(p) => std::path::PathBuf::from(p),
-        None => root.clone(),
+    Ok(ListDirResponse {
+        entries,
+        current_path: canonical.to_string_lossy().to_string(),
+    })
+}
+
+/// Canonicalizes `path` (or the scope's root when `None`) and checks that it
+/// is a directory inside `scope`.
+pub fn resolve_dir(scope: &FileScope, path: Option<&str>) -> Result<PathBuf, AppError> {
+    let target = match path {
+        Some(p) => PathBuf::from(p),
+        None => scope.root().to_path_buf(),
     };
 
     // Path traversal protection
     let canonical = target
         .canonicalize()
         .map_err(|_| AppError::NotFound(format!("Path not found: {}", target.display())))?;
-
-    let canonical_root = root
-        .canonicalize()
-        .map_err(|e| AppError::Internal(format!("Failed to resolve root path: {e}")))?;
-
-    if !canonical.starts_with(&canonical_root) {
-        return Err(AppError::NotFound("Access denied".to_string()));
-    }
+    scope.check(&canonical)?;
 
     if !canonical.is_dir() {
         return Err(AppError::NotFound(format!(
@@ -38,55 +126,26 @@ pub async fn list_directory(
             canonical.display()
         )));
     }
+    Ok(canonical)
+}
 
-    let read_dir = std::fs::read_dir(&canonical)
-        .map_err(|e| AppError::Internal(format!("Failed to read directory: {e}")))?;
-
-    let mut entries: Vec<FileEntryDto> = Vec::new();
-
-    for entry in read_dir {
-        let entry = match entry {
-            Ok(e) => e,
-            Err(_) => continue,
-        };
-
-        let name = entry.file_name().to_string_lossy().to_string();
-        let path = entry.path().to_string_lossy().to_string();
-
-        let metadata = entry.path().symlink_metadata();
-        let is_dir = entry.file_type().map(|ft| ft.is_dir()).unwrap_or(false);
-        let is_hidden = name.starts_with('.');
-        let is_symlink = metadata
-            .as_ref()
-            .map(|m| m.file_type().is_symlink())
-            .unwrap_or(false);
-        let size = if is_dir {
-            None
-        } else {
-            metadata.as_ref().map(|m| m.len()).ok()
-        };
-
-        entries.push(FileEntryDto {
-            name,
-            path,
-            is_dir,
-            is_hidden,
-            is_symlink,
-            size,
-        });
-    }
-
-    // Sort: directories first, then alphabetical by name
-    entries.sort_by(|a, b| {
-        b.is_dir
-            .cmp(&a.is_dir)
-            .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
-    });
+/// Builds the DTO for a single path without following symlinks.
+/// Returns `None` if the path no longer exists.
+pub fn entry_dto(path: &Path) -> Option<FileEntryDto> {
+    let metadata = path.symlink_metadata().ok()?;
+    let name = path.file_name()?.to_string_lossy().to_string();
+    let is_dir = metadata.is_dir();
+    let is_hidden = name.starts_with('.');
+    let size = if is_dir { None } else { Some(metadata.len()) };
 
-    Ok(Json(ListDirResponse {
-        entries,
-        current_path: canonical.to_string_lossy().to_string(),
-    }))
+    Some(FileEntryDto {
+        name,
+        path: path.to_string_lossy().to_string(),
+        is_dir,
+        is_hidden,
+        is_symlink: metadata.file_type().is_symlink(),
+        size,
+    })
 }
 
 pub async fn download_file(
@@ -94,100 +153,329 @@ pub async fn download_file(
     State(state): State<AppState>,
     Query(query): Query<DownloadQuery>,
 ) -> Result<Response, AppError> {
-    let root = state.config.resolve_root(&user.sub);
-    let target = std::path::PathBuf::from(&query.path);
+    let scope = FileScope::for_user(&state.config, &user.sub);
+    let result = match resolve_file_path(&scope, &query.path) {
+        Ok(path) => file_response(&path).await,
+        Err(e) => Err(e),
+    };
+    state.audit.record(&user.sub, AuditAction::Download, &query.path, &result);
+    result
+}
 
-    let canonical = target.canonicalize()
+pub async fn preview_file(
+    user: AuthUser,
+    State(state): State<AppState>,
+    Query(query): Query<PreviewQuery>,
+) -> Result<Json<PreviewResponse>, AppError> {
+    let scope = FileScope::for_user(&state.config, &user.sub);
+    let result = preview(&scope, &query).await;
+    state.audit.record(&user.sub, AuditAction::Preview, &query.path, &result);
+    result.map(Json)
+}
+
+async fn preview(scope: &FileScope, query: &PreviewQuery) -> Result<PreviewResponse, AppError> {
+    let path = resolve_file_path(scope, &query.path)?;
+    let offset = query.offset.unwrap_or(0);
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_PREVIEW_LINES)
+        .clamp(1, MAX_PREVIEW_LINES);
+    tokio::task::spawn_blocking(move || read_preview_range(&path, offset, limit))
+        .await
+        .map_err(|e| AppError::Internal(format!("Preview task failed: {e}")))?
+        .map_err(|e| AppError::Internal(format!("Failed to read file: {e}")))
+}
+
+/// Canonicalizes `requested` and checks that it is a file inside `scope`.
+fn resolve_file_path(scope: &FileScope, requested: &str) -> Result<PathBuf, AppError> {
+    let canonical = PathBuf::from(requested)
+        .canonicalize()
         .map_err(|_| AppError::NotFound("Path not found".to_string()))?;
-    let canonical_root = root.canonicalize()
-        .map_err(|e| AppError::Internal(format!("Failed to resolve root: {e}")))?;
-    if !canonical.starts_with(&canonical_root) {
-        return Err(AppError::NotFound("Access denied".to_string()));
-    }
+    scope.check(&canonical)?;
     if canonical.is_dir() {
-        return Err(AppError::NotFound("Cannot download a directory".to_string()));
+        return Err(AppError::NotFound("Path is a directory".to_string()));
     }
+    Ok(canonical)
+}
 
-    let metadata = tokio::fs::metadata(&canonical).await
+/// Streams `path` as an attachment.
+async fn file_response(path: &Path) -> Result<Response, AppError> {
+    let metadata = tokio::fs::metadata(path)
+        .await
         .map_err(|_| AppError::NotFound("File not found".to_string()))?;
-    let mime = mime_guess::from_path(&canonical).first_or_octet_stream();
-    let filename = canonical.file_name()
-        .unwrap_or_default().to_string_lossy().to_string();
+    let mime = download_mime(path);
+    let filename = path
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
 
-    let file = tokio::fs::File::open(&canonical).await
+    let file = tokio::fs::File::open(path)
+        .await
         .map_err(|e| AppError::Internal(format!("Failed to open file: {e}")))?;
     let stream = tokio_util::io::ReaderStream::new(file);
     let body = axum::body::Body::from_stream(stream);
 
-    Ok(Response::builder()
-        .header("Content-Type", mime.as_ref())
+    Response::builder()
+        .header("Content-Type", mime)
         .header("Content-Length", metadata.len())
-        .header("Content-Disposition", format!("attachment; filename=\"{}\"", filename))
+        .header("Content-Disposition", content_disposition(&filename))
         .body(body)
-        .unwrap())
+        .map_err(|e| AppError::Internal(format!("Failed to build response: {e}")))
+}
+
+/// Lines returned by `/files/preview` when no `limit` is given.
+const DEFAULT_PREVIEW_LINES: usize = 200;
+
+/// Upper bound for `/files/preview?limit=`.
+const MAX_PREVIEW_LINES: usize = 2000;
+
+/// Core's NUL-byte check; unreadable files count as binary.
+fn looks_binary(path: &Path) -> bool {
+    trefm_core::fs::preview::is_binary(path).unwrap_or(true)
+}
+
+/// MIME type from the extension; files with an unknown extension are
+/// served as plain text unless they look binary.
+fn download_mime(path: &Path) -> String {
+    match mime_guess::from_path(path).first() {
+        Some(mime) => mime.to_string(),
+        None if looks_binary(path) => "application/octet-stream".to_string(),
+        None => "text/plain; charset=utf-8".to_string(),
+    }
+}
+
+/// Reads lines `offset..offset + limit` of a text file. Binary files yield
+/// `is_binary` and no lines. Invalid UTF-8 is replaced, not rejected.
+fn read_preview_range(
+    path: &Path,
+    offset: usize,
+    limit: usize,
+) -> std::io::Result<PreviewResponse> {
+    let mut response = PreviewResponse {
+        path: path.to_string_lossy().to_string(),
+        is_binary: false,
+        lines: Vec::new(),
+        offset,
+        total_lines: 0,
+        is_truncated: false,
+    };
+    if looks_binary(path) {
+        response.is_binary = true;
+        return Ok(response);
+    }
+
+    let mut reader = BufReader::new(std::fs::File::open(path)?);
+    let mut buf = Vec::new();
+    loop {
+        buf.clear();
+        if reader.read_until(b'\n', &mut buf)? == 0 {
+            break;
+        }
+        let index = response.total_lines;
+        response.total_lines += 1;
+        if index >= offset && response.lines.len() < limit {
+            let line = String::from_utf8_lossy(&buf);
+            response
+                .lines
+                .push(line.trim_end_matches(['\n', '\r']).to_string());
+        }
+    }
+    response.is_truncated = offset + response.lines.len() < response.total_lines;
+    Ok(response)
+}
+
+/// `attachment` disposition with an ASCII fallback name and the exact
+/// UTF-8 name per RFC 6266 / RFC 5987.
+fn content_disposition(filename: &str) -> String {
+    let fallback: String = filename
+        .chars()
+        .map(|c| match c {
+            ' ' => ' ',
+            '"' | '\\' => '_',
+            c if c.is_ascii_graphic() => c,
+            _ => '_',
+        })
+        .collect();
+    let mut encoded = String::new();
+    for byte in filename.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'.' | b'-' | b'_' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    format!("attachment; filename=\"{fallback}\"; filename*=UTF-8''{encoded}")
 }
 
 pub async fn upload_file(
     user: AuthUser,
     State(state): State<AppState>,
-    mut multipart: Multipart,
+    multipart: Multipart,
 ) -> Result<Json<UploadResponse>, AppError> {
-    let root = state.config.resolve_root(&user.sub);
+    let scope = FileScope::for_user(&state.config, &user.sub);
+    let max_bytes = state.config.filesystem.max_upload_size_mb * 1024 * 1024;
+    let mut audit_path = String::new();
+    let result = receive_upload(&scope, multipart, max_bytes, &mut audit_path).await;
+    state.audit.record(&user.sub, AuditAction::Upload, &audit_path, &result);
+    result.map(Json)
+}
+
+/// Streams the `file` field into the directory named by the `path` field
+/// (or the scope's root), which must come first. `audit_path` is filled in
+/// with the requested destination as soon as it is known.
+async fn receive_upload(
+    scope: &FileScope,
+    mut multipart: Multipart,
+    max_bytes: usize,
+    audit_path: &mut String,
+) -> Result<UploadResponse, AppError> {
     let mut target_dir: Option<String> = None;
-    let mut file_data: Option<(String, Vec<u8>)> = None;
+    let mut uploaded: Option<UploadResponse> = None;
 
-    while let Some(field) = multipart.next_field().await
-        .map_err(|e| AppError::Internal(format!("Multipart error: {e}")))? {
+    while let Some(mut field) = multipart.next_field().await.map_err(multipart_error)? {
         match field.name() {
             Some("path") => {
-                target_dir = Some(field.text().await
-                    .map_err(|e| AppError::Internal(format!("Read error: {e}")))?);
+                if uploaded.is_some() {
+                    return Err(AppError::BadRequest(
+                        "path field must come before file".to_string(),
+                    ));
+                }
+                target_dir = Some(field.text().await.map_err(multipart_error)?);
             }
             Some("file") => {
-                let filename = field.file_name()
-                    .unwrap_or("upload").to_string();
-                let data = field.bytes().await
-                    .map_err(|e| AppError::Internal(format!("Read error: {e}")))?;
-                file_data = Some((filename, data.to_vec()));
+                if uploaded.is_some() {
+                    return Err(AppError::BadRequest("Only one file per upload".to_string()));
+                }
+                let raw_filename = field.file_name().unwrap_or("upload").to_string();
+                let dir = match &target_dir {
+                    Some(p) => PathBuf::from(p),
+                    None => scope.root().to_path_buf(),
+                };
+                *audit_path = dir.join(&raw_filename).to_string_lossy().to_string();
+
+                let sanitized = sanitize_filename(&raw_filename);
+                if sanitized.is_empty() {
+                    return Err(AppError::BadRequest("Invalid filename".to_string()));
+                }
+                let canonical_dir = dir
+                    .canonicalize()
+                    .map_err(|_| AppError::NotFound("Target directory not found".to_string()))?;
+                scope.check(&canonical_dir)?;
+                if !canonical_dir.is_dir() {
+                    return Err(AppError::NotFound("Target is not a directory".to_string()));
+                }
+
+                let dest = canonical_dir.join(&sanitized);
+                // Only the directory was scope-checked: never write through a
+                // link that happens to carry the uploaded file's name.
+                match tokio::fs::symlink_metadata(&dest).await {
+                    Ok(meta) if meta.file_type().is_symlink() => {
+                        return Err(AppError::Forbidden("Destination is a symlink".to_string()));
+                    }
+                    Ok(meta) if meta.is_dir() => {
+                        return Err(AppError::BadRequest(
+                            "Destination is a directory".to_string(),
+                        ));
+                    }
+                    _ => {}
+                }
+                let size = stream_to_file(&mut field, &canonical_dir, &dest, max_bytes).await?;
+                uploaded = Some(UploadResponse {
+                    success: true,
+                    path: dest.to_string_lossy().to_string(),
+                    filename: sanitized,
+                    size,
+                });
             }
             _ => {}
         }
     }
 
-    let (raw_filename, data) = file_data
-        .ok_or_else(|| AppError::Internal("Missing file field".to_string()))?;
+    uploaded.ok_or_else(|| AppError::BadRequest("Missing file field".to_string()))
+}
+
+/// Writes `field` chunk by chunk into a new temporary file in `dir`, then
+/// renames it to `dest`, replacing a file already there. `dest` is not
+/// touched until the upload is complete: if it fails or grows past
+/// `max_bytes`, only the temporary file is removed.
+async fn stream_to_file(
+    field: &mut Field<'_>,
+    dir: &Path,
+    dest: &Path,
+    max_bytes: usize,
+) -> Result<u64, AppError> {
+    use tokio::io::AsyncWriteExt;
 
-    let sanitized = sanitize_filename(&raw_filename);
-    if sanitized.is_empty() {
-        return Err(AppError::Internal("Invalid filename".to_string()));
+    let (mut file, temp) = create_upload_temp(dir, dest).await?;
+    let mut written = 0usize;
+    let result = async {
+        while let Some(chunk) = field.chunk().await.map_err(multipart_error)? {
+            written += chunk.len();
+            if written > max_bytes {
+                return Err(too_large());
+            }
+            file.write_all(&chunk)
+                .await
+                .map_err(|e| AppError::Internal(format!("Failed to write file: {e}")))?;
+        }
+        file.flush()
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to write file: {e}")))
     }
+    .await;
+    drop(file);
 
-    let dir = match &target_dir {
-        Some(p) => std::path::PathBuf::from(p),
-        None => root.clone(),
+    let result = match result {
+        Ok(()) => tokio::fs::rename(&temp, dest)
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to save file: {e}"))),
+        Err(e) => Err(e),
     };
-    let canonical_dir = dir.canonicalize()
-        .map_err(|_| AppError::NotFound("Target directory not found".to_string()))?;
-    let canonical_root = root.canonicalize()
-        .map_err(|e| AppError::Internal(format!("Failed to resolve root: {e}")))?;
-    if !canonical_dir.starts_with(&canonical_root) {
-        return Err(AppError::NotFound("Access denied".to_string()));
-    }
-    if !canonical_dir.is_dir() {
-        return Err(AppError::NotFound("Target is not a directory".to_string()));
-    }
-
-    let dest = canonical_dir.join(&sanitized);
-    let size = data.len() as u64;
-    tokio::fs::write(&dest, &data).await
-        .map_err(|e| AppError::Internal(format!("Failed to write file: {e}")))?;
-
-    Ok(Json(UploadResponse {
-        success: true,
-        path: dest.to_string_lossy().to_string(),
-        filename: sanitized,
-        size,
-    }))
+    if let Err(e) = result {
+        let _ = tokio::fs::remove_file(&temp).await;
+        return Err(e);
+    }
+    Ok(written as u64)
+}
+
+/// Creates a hidden file next to `dest` that did not exist before.
+/// Uploaded names never start with `.`, so it cannot clash with one.
+async fn create_upload_temp(
+    dir: &Path,
+    dest: &Path,
+) -> Result<(tokio::fs::File, PathBuf), AppError> {
+    let name = dest
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    for n in 0.. {
+        let temp = dir.join(format!(".{name}.upload{n}"));
+        match tokio::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&temp)
+            .await
+        {
+            Ok(file) => return Ok((file, temp)),
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(AppError::Internal(format!("Failed to create file: {e}"))),
+        }
+    }
+    unreachable!("unbounded range always yields a free name")
+}
+
+fn multipart_error(e: MultipartError) -> AppError {
+    if e.status() == StatusCode::PAYLOAD_TOO_LARGE {
+        too_large()
+    } else {
+        AppError::BadRequest(format!("Multipart error: {}", e.body_text()))
+    }
+}
+
+fn too_large() -> AppError {
+    AppError::TooLarge("Upload exceeds the size limit".to_string())
 }
 
 fn sanitize_filename(name: &str) -> String {
@@ -199,3 +487,450 @@ fn sanitize_filename(name: &str) -> String {
         .collect();
     name
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::StatusCode;
+    use axum::response::IntoResponse;
+
+    fn scope(root: &Path) -> FileScope {
+        FileScope::new(root.to_path_buf(), Vec::new())
+    }
+
+    fn state_with_root(root: &Path) -> AppState {
+        let mut config: ServerConfig = toml::from_str("").unwrap();
+        config.filesystem.root = root.to_path_buf();
+        AppState {
+            config: std::sync::Arc::new(config),
+            session_store: std::sync::Arc::new(crate::auth::session::SessionStore::new(600)),
+            login_sessions: std::sync::Arc::new(crate::auth::session::SessionStore::new(600)),
+            webauthn: None,
+            ws_tickets: Default::default(),
+            revoked_tokens: Default::default(),
+            audit: std::sync::Arc::new(crate::audit::AuditLog::memory()),
+            shutdown: Default::default(),
+        }
+    }
+
+    fn user(name: &str) -> AuthUser {
+        AuthUser {
+            sub: name.to_string(),
+            jti: String::new(),
+        }
+    }
+
+    async fn download(root: &Path, requested: &Path) -> Result<Response, AppError> {
+        let path = resolve_file_path(&scope(root), &requested.to_string_lossy())?;
+        file_response(&path).await
+    }
+
+    #[tokio::test]
+    async fn download_streams_file_with_headers() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        std::fs::write(tmp.path().join("notes.json"), br#"{"a":1}"#).unwrap();
+
+        let response = download(tmp.path(), &tmp.path().join("notes.json"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let headers = response.headers();
+        assert_eq!(headers["Content-Type"], "application/json");
+        assert_eq!(headers["Content-Length"], "7");
+        assert_eq!(
+            headers["Content-Disposition"],
+            "attachment; filename=\"notes.json\"; filename*=UTF-8''notes.json"
+        );
+        let body = axum::body::to_bytes(response.into_body(), 1024).await.unwrap();
+        assert_eq!(&body[..], br#"{"a":1}"#);
+    }
+
+    #[tokio::test]
+    async fn download_rejects_traversal_outside_root() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let root = tmp.path().join("root");
+        std::fs::create_dir(&root).unwrap();
+        std::fs::write(tmp.path().join("secret.txt"), "nope").unwrap();
+
+        let err = download(&root, &root.join("../secret.txt")).await.unwrap_err();
+        assert!(matches!(err, AppError::Forbidden(ref m) if m == "Access denied"));
+        assert_eq!(err.into_response().status(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn download_missing_file_is_not_found() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let err = download(tmp.path(), &tmp.path().join("missing.txt"))
+            .await
+            .unwrap_err();
+        assert_eq!(err.into_response().status(), StatusCode::NOT_FOUND);
+    }
+
+    #[test]
+    fn download_mime_sniffs_unknown_extensions() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let text = tmp.path().join("README");
+        let binary = tmp.path().join("blob");
+        std::fs::write(&text, "plain words").unwrap();
+        std::fs::write(&binary, [0u8, 1, 2]).unwrap();
+        assert_eq!(download_mime(&text), "text/plain; charset=utf-8");
+        assert_eq!(download_mime(&binary), "application/octet-stream");
+    }
+
+    #[test]
+    fn content_disposition_escapes_unsafe_names() {
+        assert_eq!(
+            content_disposition("my \"report\"\n.txt"),
+            "attachment; filename=\"my _report__.txt\"; filename*=UTF-8''my%20%22report%22%0A.txt"
+        );
+        assert_eq!(
+            content_disposition("보고서.pdf"),
+            "attachment; filename=\"___.pdf\"; filename*=UTF-8''%EB%B3%B4%EA%B3%A0%EC%84%9C.pdf"
+        );
+    }
+
+    #[test]
+    fn preview_returns_text_lines() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let file = tmp.path().join("a.txt");
+        std::fs::write(&file, "one\r\ntwo\nthree").unwrap();
+
+        let preview = read_preview_range(&file, 0, 10).unwrap();
+        assert!(!preview.is_binary);
+        assert_eq!(preview.lines, ["one", "two", "three"]);
+        assert_eq!(preview.total_lines, 3);
+        assert!(!preview.is_truncated);
+    }
+
+    #[test]
+    fn preview_flags_binary_without_lines() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let file = tmp.path().join("a.txt");
+        std::fs::write(&file, b"text\0more\n").unwrap();
+
+        let preview = read_preview_range(&file, 0, 10).unwrap();
+        assert!(preview.is_binary);
+        assert!(preview.lines.is_empty());
+        let json = serde_json::to_value(&preview).unwrap();
+        assert_eq!(json["is_binary"], true);
+        assert_eq!(json["lines"], serde_json::json!([]));
+    }
+
+    #[test]
+    fn preview_slices_by_offset_and_limit() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let file = tmp.path().join("n.txt");
+        let text: String = (1..=10).map(|i| format!("line {i}\n")).collect();
+        std::fs::write(&file, text).unwrap();
+
+        let preview = read_preview_range(&file, 3, 4).unwrap();
+        assert_eq!(preview.lines, ["line 4", "line 5", "line 6", "line 7"]);
+        assert_eq!(preview.offset, 3);
+        assert_eq!(preview.total_lines, 10);
+        assert!(preview.is_truncated);
+
+        let tail = read_preview_range(&file, 8, 4).unwrap();
+        assert_eq!(tail.lines, ["line 9", "line 10"]);
+        assert!(!tail.is_truncated);
+
+        let past_end = read_preview_range(&file, 50, 4).unwrap();
+        assert!(past_end.lines.is_empty());
+        assert_eq!(past_end.total_lines, 10);
+    }
+
+    #[test]
+    fn preview_path_must_be_a_file_in_root() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let err = resolve_file_path(&scope(tmp.path()), &tmp.path().to_string_lossy()).unwrap_err();
+        assert!(matches!(err, AppError::NotFound(ref m) if m == "Path is a directory"));
+    }
+
+    #[tokio::test]
+    async fn download_is_audited() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let file = tmp.path().join("report.txt");
+        std::fs::write(&file, "data").unwrap();
+        let state = state_with_root(tmp.path());
+        let requested = file.to_string_lossy().to_string();
+
+        let query = DownloadQuery { path: requested.clone() };
+        let response = download_file(user("alice"), State(state.clone()), Query(query))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let records = state.audit.records();
+        assert_eq!(records.len(), 1);
+        let record = &records[0];
+        assert_eq!(record.username, "alice");
+        assert_eq!(record.action, AuditAction::Download);
+        assert_eq!(record.path, requested);
+        assert_eq!(record.result, "ok");
+        assert_eq!(record.status, 200);
+        assert_eq!(record.error, None);
+        assert!(record.timestamp > 0);
+    }
+
+    #[tokio::test]
+    async fn rejected_traversal_is_audited() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let root = tmp.path().join("root");
+        std::fs::create_dir(&root).unwrap();
+        std::fs::write(tmp.path().join("secret.txt"), "nope").unwrap();
+        let state = state_with_root(&root);
+        let requested = root.join("../secret.txt").to_string_lossy().to_string();
+
+        let query = DownloadQuery { path: requested.clone() };
+        let err = download_file(user("mallory"), State(state.clone()), Query(query))
+            .await
+            .unwrap_err();
+        assert_eq!(err.status(), StatusCode::FORBIDDEN);
+
+        let records = state.audit.records();
+        assert_eq!(records.len(), 1);
+        let record = &records[0];
+        assert_eq!(record.username, "mallory");
+        assert_eq!(record.action, AuditAction::Download);
+        assert_eq!(record.path, requested);
+        assert_eq!(record.result, "error");
+        assert_eq!(record.status, 403);
+        assert_eq!(record.error.as_deref(), Some("Access denied"));
+    }
+
+    fn upload_request(
+        path: Option<&Path>,
+        filename: &str,
+        contents: &[u8],
+    ) -> axum::http::Request<axum::body::Body> {
+        const BOUNDARY: &str = "trefm-test-boundary";
+        let mut body = Vec::new();
+        if let Some(path) = path {
+            body.extend_from_slice(
+                format!(
+                    "--{BOUNDARY}\r\nContent-Disposition: form-data; name=\"path\"\r\n\r\n{}\r\n",
+                    path.display()
+                )
+                .as_bytes(),
+            );
+        }
+        body.extend_from_slice(
+            format!(
+                "--{BOUNDARY}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"{filename}\"\r\n\
+                 Content-Type: application/octet-stream\r\n\r\n"
+            )
+            .as_bytes(),
+        );
+        body.extend_from_slice(contents);
+        body.extend_from_slice(format!("\r\n--{BOUNDARY}--\r\n").as_bytes());
+        axum::http::Request::builder()
+            .method("POST")
+            .uri("/files/upload")
+            .header("content-type", format!("multipart/form-data; boundary={BOUNDARY}"))
+            .body(axum::body::Body::from(body))
+            .unwrap()
+    }
+
+    async fn upload(
+        state: AppState,
+        limit: usize,
+        req: axum::http::Request<axum::body::Body>,
+    ) -> (StatusCode, serde_json::Value) {
+        use tower::ServiceExt;
+        let router = crate::api::protected_router(limit).with_state(state);
+        let response = router.oneshot(req).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), 64 * 1024).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap_or_default())
+    }
+
+    #[tokio::test]
+    async fn upload_lands_in_target_directory() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let sub = tmp.path().join("sub");
+        std::fs::create_dir(&sub).unwrap();
+        let state = state_with_root(tmp.path());
+
+        let req = upload_request(Some(&sub), "notes.txt", b"hello upload");
+        let (status, body) = upload(state.clone(), 1024, req).await;
+
+        assert_eq!(status, StatusCode::OK);
+        let dest = sub.canonicalize().unwrap().join("notes.txt");
+        assert_eq!(body["path"], dest.to_string_lossy().as_ref());
+        assert_eq!(body["size"], 12);
+        assert_eq!(std::fs::read(&dest).unwrap(), b"hello upload");
+        assert_eq!(state.audit.records()[0].action, AuditAction::Upload);
+    }
+
+    #[tokio::test]
+    async fn upload_over_limit_is_rejected() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let state = state_with_root(tmp.path());
+
+        let req = upload_request(None, "big.bin", &[7u8; 4096]);
+        let (status, _) = upload(state, 1024, req).await;
+
+        assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+        assert!(!tmp.path().join("big.bin").exists());
+    }
+
+    #[tokio::test]
+    async fn stream_limit_removes_partial_file() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let req = upload_request(None, "big.bin", &[7u8; 4096]);
+        let multipart = <Multipart as axum::extract::FromRequest<()>>::from_request(req, &())
+            .await
+            .unwrap();
+        let mut audit_path = String::new();
+
+        let err = receive_upload(&scope(tmp.path()), multipart, 1000, &mut audit_path)
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, AppError::TooLarge(_)));
+        assert!(!tmp.path().join("big.bin").exists());
+        assert!(audit_path.ends_with("big.bin"));
+    }
+
+    /// An upload whose body stops before the closing boundary.
+    async fn truncated_upload_request(
+        filename: &str,
+        contents: &[u8],
+    ) -> axum::http::Request<axum::body::Body> {
+        let (parts, body) = upload_request(None, filename, contents).into_parts();
+        let body = axum::body::to_bytes(body, usize::MAX).await.unwrap();
+        let cut = body.len() - "\r\n--trefm-test-boundary--\r\n".len();
+        axum::http::Request::from_parts(parts, axum::body::Body::from(body.slice(..cut)))
+    }
+
+    #[tokio::test]
+    async fn upload_replaces_existing_file() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        std::fs::write(tmp.path().join("notes.txt"), b"old").unwrap();
+        let state = state_with_root(tmp.path());
+
+        let req = upload_request(None, "notes.txt", b"new contents");
+        let (status, _) = upload(state, 1024, req).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(
+            std::fs::read(tmp.path().join("notes.txt")).unwrap(),
+            b"new contents"
+        );
+        assert_eq!(std::fs::read_dir(tmp.path()).unwrap().count(), 1);
+    }
+
+    #[tokio::test]
+    async fn oversized_upload_keeps_existing_file() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        std::fs::write(tmp.path().join("big.bin"), b"original bytes").unwrap();
+        let state = state_with_root(tmp.path());
+
+        let req = upload_request(None, "big.bin", &[7u8; 4096]);
+        let (status, _) = upload(state, 1024, req).await;
+
+        assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+        assert_eq!(
+            std::fs::read(tmp.path().join("big.bin")).unwrap(),
+            b"original bytes"
+        );
+        assert_eq!(std::fs::read_dir(tmp.path()).unwrap().count(), 1);
+    }
+
+    #[tokio::test]
+    async fn aborted_upload_keeps_existing_file() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        std::fs::write(tmp.path().join("notes.txt"), b"original bytes").unwrap();
+        let state = state_with_root(tmp.path());
+
+        let req = truncated_upload_request("notes.txt", b"partial").await;
+        let (status, _) = upload(state, 1024, req).await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(
+            std::fs::read(tmp.path().join("notes.txt")).unwrap(),
+            b"original bytes"
+        );
+        assert_eq!(std::fs::read_dir(tmp.path()).unwrap().count(), 1);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn upload_through_symlink_is_refused() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let root = tmp.path().join("root");
+        std::fs::create_dir(&root).unwrap();
+        let outside = tmp.path().join("outside.txt");
+        std::fs::write(&outside, b"outside").unwrap();
+        std::os::unix::fs::symlink(&outside, root.join("notes.txt")).unwrap();
+        let state = state_with_root(&root);
+
+        let req = upload_request(None, "notes.txt", b"overwrite");
+        let (status, _) = upload(state, 1024, req).await;
+
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert_eq!(std::fs::read(&outside).unwrap(), b"outside");
+        assert!(root.join("notes.txt").is_symlink());
+        assert_eq!(std::fs::read_dir(&root).unwrap().count(), 1);
+    }
+
+    #[tokio::test]
+    async fn upload_traversal_target_is_refused() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let root = tmp.path().join("root");
+        std::fs::create_dir(&root).unwrap();
+        let state = state_with_root(&root);
+
+        let req = upload_request(Some(&root.join("..")), "evil.sh", b"echo pwned");
+        let (status, body) = upload(state, 1024, req).await;
+
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert_eq!(body["error"], "Access denied");
+        assert!(!tmp.path().join("evil.sh").exists());
+    }
+
+    #[test]
+    fn path_inside_allowed_root_is_accepted() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let shared = tmp.path().join("shared");
+        std::fs::create_dir(&shared).unwrap();
+        std::fs::write(shared.join("a.txt"), "a").unwrap();
+        let scope = FileScope::new(tmp.path().to_path_buf(), vec![shared.clone()]);
+
+        let path = resolve_file_path(&scope, &shared.join("a.txt").to_string_lossy()).unwrap();
+        assert_eq!(path, shared.join("a.txt").canonicalize().unwrap());
+        assert!(resolve_dir(&scope, shared.to_str()).is_ok());
+    }
+
+    #[test]
+    fn path_outside_allowed_roots_is_forbidden() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let shared = tmp.path().join("shared");
+        std::fs::create_dir(&shared).unwrap();
+        std::fs::write(tmp.path().join("private.txt"), "p").unwrap();
+        let scope = FileScope::new(tmp.path().to_path_buf(), vec![shared]);
+
+        let err = resolve_file_path(&scope, &tmp.path().join("private.txt").to_string_lossy())
+            .unwrap_err();
+        assert_eq!(err.into_response().status(), StatusCode::FORBIDDEN);
+        // The user's root itself is outside `shared`, so the default listing is refused too.
+        let err = read_listing(&scope, None).unwrap_err();
+        assert_eq!(err.into_response().status(), StatusCode::FORBIDDEN);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlink_escaping_the_root_is_forbidden() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let root = tmp.path().join("root");
+        std::fs::create_dir(&root).unwrap();
+        std::fs::write(tmp.path().join("secret.txt"), "nope").unwrap();
+        std::os::unix::fs::symlink(tmp.path().join("secret.txt"), root.join("link.txt")).unwrap();
+        std::os::unix::fs::symlink(tmp.path(), root.join("up")).unwrap();
+        let scope = FileScope::new(root.clone(), vec![root.clone()]);
+
+        let err = resolve_file_path(&scope, &root.join("link.txt").to_string_lossy()).unwrap_err();
+        assert_eq!(err.into_response().status(), StatusCode::FORBIDDEN);
+        let err = resolve_dir(&scope, root.join("up").to_str()).unwrap_err();
+        assert_eq!(err.into_response().status(), StatusCode::FORBIDDEN);
+    }
+}
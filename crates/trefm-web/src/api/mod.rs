@@ -1,9 +1,10 @@
 mod auth_handlers;
 pub mod files;
+mod search;
 
 use std::time::Instant;
 
-use axum::extract::State;
+use axum::extract::{DefaultBodyLimit, State};
 use axum::routing::{get, post};
 use tower_http::limit::RequestBodyLimitLayer;
 use axum::Json;
@@ -24,15 +25,23 @@ pub fn auth_router() -> Router<AppState> {
         .route("/auth/otp/verify", post(auth_handlers::otp_verify))
 }
 
+/// Body limit for every API route except uploads.
+pub const JSON_BODY_LIMIT: usize = 1024 * 1024;
+
 pub fn protected_router(upload_limit: usize) -> Router<AppState> {
+    // Uploads get their own configurable limit in place of axum's 2 MB default
     let upload_route = Router::new()
         .route("/files/upload", post(files::upload_file))
+        .layer(DefaultBodyLimit::disable())
         .layer(RequestBodyLimitLayer::new(upload_limit));
 
     Router::new()
         .route("/files", get(files::list_directory))
         .route("/files/download", get(files::download_file))
+        .route("/files/preview", get(files::preview_file))
+        .route("/files/search", get(search::search_files))
         .route("/ws/ticket", post(create_ws_ticket))
+        .layer(RequestBodyLimitLayer::new(JSON_BODY_LIMIT))
         .merge(upload_route)
 }
 
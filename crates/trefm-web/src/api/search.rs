@@ -0,0 +1,170 @@
+use std::sync::atomic::AtomicBool;
+
+use axum::extract::{Query, State};
+use axum::Json;
+use trefm_core::config::settings::SearchConfig;
+use trefm_core::fs::search::{search_file_contents, search_file_names, ContentSearchOptions};
+
+use crate::api::files::{resolve_dir, FileScope};
+use crate::audit::AuditAction;
+use crate::auth::middleware::AuthUser;
+use crate::dto::{
+    ContentMatchDto, NameMatchDto, SearchMatchDto, SearchMode, SearchQuery, SearchResponse,
+};
+use crate::error::AppError;
+use crate::state::AppState;
+
+/// Most matches returned by one `/files/search` request.
+const MAX_SEARCH_RESULTS: usize = 200;
+
+pub async fn search_files(
+    user: AuthUser,
+    State(state): State<AppState>,
+    Query(query): Query<SearchQuery>,
+) -> Result<Json<SearchResponse>, AppError> {
+    let scope = FileScope::for_user(&state.config, &user.sub);
+    let path = query.root.clone().unwrap_or_default();
+    let result = tokio::task::spawn_blocking(move || run_search(&scope, &query))
+        .await
+        .map_err(|e| AppError::Internal(format!("Search task failed: {e}")))
+        .and_then(|r| r);
+    state.audit.record(&user.sub, AuditAction::Search, &path, &result);
+    result.map(Json)
+}
+
+/// Runs a name or content search below `query.root` (default: the scope's
+/// root) with the core's default exclusions.
+fn run_search(scope: &FileScope, query: &SearchQuery) -> Result<SearchResponse, AppError> {
+    let dir = resolve_dir(scope, query.root.as_deref())?;
+    // One extra result tells whether the cap cut anything off.
+    let options = ContentSearchOptions {
+        max_results: MAX_SEARCH_RESULTS + 1,
+        exclude: SearchConfig::default().exclude_set(),
+        ..ContentSearchOptions::default()
+    };
+
+    let mut matches: Vec<SearchMatchDto> = match query.mode {
+        SearchMode::Name => search_file_names(&dir, &query.q, &options)
+            .map_err(|e| AppError::Internal(format!("Name search failed: {e}")))?
+            .into_iter()
+            .map(|m| {
+                SearchMatchDto::Name(NameMatchDto {
+                    path: m.entry().path().to_string_lossy().to_string(),
+                    name: m.entry().name().to_string(),
+                    is_dir: m.entry().is_dir(),
+                    score: m.score(),
+                })
+            })
+            .collect(),
+        SearchMode::Content => {
+            let cancel = AtomicBool::new(false);
+            search_file_contents(&dir, &query.q, &options, &cancel, |_| {})
+                .map_err(|e| AppError::Internal(format!("Content search failed: {e}")))?
+                .matches
+                .into_iter()
+                .map(|m| {
+                    SearchMatchDto::Content(ContentMatchDto {
+                        path: m.path.to_string_lossy().to_string(),
+                        line_number: m.line_number,
+                        snippet: m.line,
+                    })
+                })
+                .collect()
+        }
+    };
+
+    let truncated = matches.len() > MAX_SEARCH_RESULTS;
+    matches.truncate(MAX_SEARCH_RESULTS);
+    Ok(SearchResponse {
+        mode: query.mode,
+        root: dir.to_string_lossy().to_string(),
+        matches,
+        truncated,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn scope(root: &Path) -> FileScope {
+        FileScope::new(root.to_path_buf(), Vec::new())
+    }
+
+    fn query(q: &str, mode: SearchMode) -> SearchQuery {
+        SearchQuery {
+            root: None,
+            q: q.to_string(),
+            mode,
+        }
+    }
+
+    #[test]
+    fn name_search_orders_matches_by_score() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        std::fs::write(tmp.path().join("report.md"), "").unwrap();
+        std::fs::create_dir(tmp.path().join("old")).unwrap();
+        std::fs::write(tmp.path().join("old").join("r_e_p_o_r_t.md"), "").unwrap();
+        std::fs::create_dir(tmp.path().join("node_modules")).unwrap();
+        std::fs::write(tmp.path().join("node_modules").join("report.js"), "").unwrap();
+
+        let response = run_search(&scope(tmp.path()), &query("report", SearchMode::Name)).unwrap();
+        let json = serde_json::to_value(&response).unwrap();
+        assert_eq!(json["mode"], "name");
+        assert_eq!(json["truncated"], false);
+        let matches = json["matches"].as_array().unwrap();
+        assert_eq!(matches.len(), 2);
+        assert_eq!(matches[0]["name"], "report.md");
+        assert_eq!(matches[1]["name"], "r_e_p_o_r_t.md");
+        assert!(matches[0]["score"].as_i64() > matches[1]["score"].as_i64());
+    }
+
+    #[test]
+    fn content_search_returns_path_line_and_snippet() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let file = tmp.path().join("todo.txt");
+        std::fs::write(&file, "buy milk\n   call Alice about TODO list  \n").unwrap();
+
+        let response = run_search(&scope(tmp.path()), &query("todo", SearchMode::Content)).unwrap();
+        let json = serde_json::to_value(&response).unwrap();
+        assert_eq!(json["mode"], "content");
+        let matches = json["matches"].as_array().unwrap();
+        assert_eq!(matches.len(), 1);
+        let canonical = file.canonicalize().unwrap();
+        assert_eq!(matches[0]["path"], canonical.to_string_lossy().as_ref());
+        assert_eq!(matches[0]["line_number"], 2);
+        assert_eq!(matches[0]["snippet"], "call Alice about TODO list");
+    }
+
+    #[test]
+    fn search_root_must_stay_inside_user_root() {
+        let root = tempfile::TempDir::new().unwrap();
+        let other = tempfile::TempDir::new().unwrap();
+        let q = SearchQuery {
+            root: Some(other.path().to_string_lossy().to_string()),
+            ..query("x", SearchMode::Name)
+        };
+        let err = run_search(&scope(root.path()), &q).unwrap_err();
+        assert!(matches!(err, AppError::Forbidden(ref m) if m == "Access denied"));
+    }
+
+    #[test]
+    fn search_caps_results() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        for i in 0..MAX_SEARCH_RESULTS + 5 {
+            std::fs::write(tmp.path().join(format!("f{i}.log")), "").unwrap();
+        }
+        let response = run_search(&scope(tmp.path()), &query("log", SearchMode::Name)).unwrap();
+        assert_eq!(response.matches.len(), MAX_SEARCH_RESULTS);
+        assert!(response.truncated);
+    }
+
+    #[test]
+    fn mode_defaults_to_name() {
+        let q: SearchQuery = serde_json::from_str(r#"{"q":"a"}"#).unwrap();
+        assert_eq!(q.mode, SearchMode::Name);
+        let q: SearchQuery = serde_json::from_str(r#"{"q":"a","mode":"content"}"#).unwrap();
+        assert_eq!(q.mode, SearchMode::Content);
+    }
+}
@@ -0,0 +1,186 @@
+//! Audit trail for the file API.
+//!
+//! Every file handler reports what it did through [`AuditLog::record`] —
+//! successes and failures alike. Records go either to the `trefm_web::audit`
+//! tracing target or, when `audit.log_file` is set, to a JSON Lines file.
+
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+
+use crate::error::AppError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    List,
+    Download,
+    Preview,
+    Search,
+    Upload,
+}
+
+impl AuditAction {
+    fn as_str(self) -> &'static str {
+        match self {
+            AuditAction::List => "list",
+            AuditAction::Download => "download",
+            AuditAction::Preview => "preview",
+            AuditAction::Search => "search",
+            AuditAction::Upload => "upload",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AuditRecord {
+    /// Unix time in seconds.
+    pub timestamp: u64,
+    pub username: String,
+    pub action: AuditAction,
+    /// The path as the client sent it, before any resolution.
+    pub path: String,
+    /// `"ok"` or `"error"`.
+    pub result: &'static str,
+    /// HTTP status the request ended with.
+    pub status: u16,
+    /// Server-side error detail (not the client-facing message).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+enum AuditSink {
+    Tracing,
+    File(Mutex<File>),
+    #[cfg(test)]
+    Memory(Mutex<Vec<AuditRecord>>),
+}
+
+pub struct AuditLog {
+    sink: AuditSink,
+}
+
+impl AuditLog {
+    pub fn tracing() -> Self {
+        Self {
+            sink: AuditSink::Tracing,
+        }
+    }
+
+    /// Appends JSON Lines to `path`, creating the file if needed.
+    pub fn to_file(path: &Path) -> std::io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self {
+            sink: AuditSink::File(Mutex::new(file)),
+        })
+    }
+
+    #[cfg(test)]
+    pub fn memory() -> Self {
+        Self {
+            sink: AuditSink::Memory(Mutex::new(Vec::new())),
+        }
+    }
+
+    #[cfg(test)]
+    pub fn records(&self) -> Vec<AuditRecord> {
+        match &self.sink {
+            AuditSink::Memory(records) => records.lock().unwrap().clone(),
+            _ => Vec::new(),
+        }
+    }
+
+    pub fn record<T>(
+        &self,
+        username: &str,
+        action: AuditAction,
+        path: &str,
+        result: &Result<T, AppError>,
+    ) {
+        let (outcome, status, error) = match result {
+            Ok(_) => ("ok", 200, None),
+            Err(e) => ("error", e.status().as_u16(), Some(e.detail().to_string())),
+        };
+        self.write(AuditRecord {
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+            username: username.to_string(),
+            action,
+            path: path.to_string(),
+            result: outcome,
+            status,
+            error,
+        });
+    }
+
+    fn write(&self, record: AuditRecord) {
+        match &self.sink {
+            AuditSink::Tracing => {
+                tracing::info!(
+                    target: "trefm_web::audit",
+                    timestamp = record.timestamp,
+                    username = %record.username,
+                    action = record.action.as_str(),
+                    path = %record.path,
+                    result = record.result,
+                    status = record.status,
+                    error = record.error.as_deref(),
+                    "file api"
+                );
+            }
+            AuditSink::File(file) => {
+                let Ok(mut line) = serde_json::to_string(&record) else {
+                    return;
+                };
+                line.push('\n');
+                let mut file = file.lock().unwrap_or_else(|e| e.into_inner());
+                if let Err(e) = file.write_all(line.as_bytes()) {
+                    tracing::error!("Failed to write audit record: {e}");
+                }
+            }
+            #[cfg(test)]
+            AuditSink::Memory(records) => records.lock().unwrap().push(record),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_sink_writes_json_lines() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let path = tmp.path().join("audit.jsonl");
+        let log = AuditLog::to_file(&path).unwrap();
+
+        log.record::<()>("alice", AuditAction::Preview, "/srv/a.txt", &Ok(()));
+        log.record::<()>(
+            "bob",
+            AuditAction::Upload,
+            "/etc",
+            &Err(AppError::Forbidden("Access denied".to_string())),
+        );
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<serde_json::Value> = contents
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["username"], "alice");
+        assert_eq!(lines[0]["action"], "preview");
+        assert_eq!(lines[0]["result"], "ok");
+        assert!(lines[0].get("error").is_none());
+        assert_eq!(lines[1]["action"], "upload");
+        assert_eq!(lines[1]["status"], 403);
+        assert_eq!(lines[1]["error"], "Access denied");
+        assert!(lines[1]["timestamp"].as_u64().unwrap() > 0);
+    }
+}
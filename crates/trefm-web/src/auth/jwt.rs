@@ -10,7 +10,14 @@ pub struct Claims {
     pub jti: String,
 }
 
-pub fn create_token(jwt_secret: &str, ttl_hours: u64, username: &str) -> anyhow::Result<(String, u64)> {
+pub struct IssuedToken {
+    pub token: String,
+    pub expires_at: u64,
+    /// Token ID, also the key of the login session in `AppState::login_sessions`.
+    pub jti: String,
+}
+
+pub fn create_token(jwt_secret: &str, ttl_hours: u64, username: &str) -> anyhow::Result<IssuedToken> {
     let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
     let expires_at = now + ttl_hours * 3600;
 
@@ -26,7 +33,11 @@ pub fn create_token(jwt_secret: &str, ttl_hours: u64, username: &str) -> anyhow:
         &EncodingKey::from_secret(jwt_secret.as_bytes()),
     )?;
 
-    Ok((token, expires_at))
+    Ok(IssuedToken {
+        token,
+        expires_at,
+        jti: claims.jti,
+    })
 }
 
 pub fn verify_token(jwt_secret: &str, token: &str) -> anyhow::Result<Claims> {
@@ -43,6 +43,11 @@ impl FromRequestParts<AppState> for AuthUser {
             return Err(AppError::Auth("Token has been revoked".to_string()));
         }
 
+        // Sliding expiry: each authenticated request extends the session
+        if !state.login_sessions.touch(&claims.jti) {
+            return Err(AppError::Auth("Session expired".to_string()));
+        }
+
         Ok(AuthUser {
             sub: claims.sub,
             jti: claims.jti,
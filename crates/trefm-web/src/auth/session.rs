@@ -1,3 +1,4 @@
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 use dashmap::DashMap;
@@ -19,9 +20,31 @@ pub struct AuthSession {
     pub webauthn_state: Option<String>,
 }
 
+/// Time source for [`SessionStore`], replaceable in tests.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> Instant;
+}
+
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+struct StoredSession {
+    session: AuthSession,
+    last_active: Instant,
+}
+
+/// Sessions expire `ttl` after their last use (sliding), and never outlive
+/// `max_lifetime` from `created_at` no matter how active they are.
 pub struct SessionStore {
-    sessions: DashMap<String, AuthSession>,
+    sessions: DashMap<String, StoredSession>,
     ttl: Duration,
+    max_lifetime: Option<Duration>,
+    clock: Arc<dyn Clock>,
 }
 
 impl SessionStore {
@@ -29,12 +52,34 @@ impl SessionStore {
         Self {
             sessions: DashMap::new(),
             ttl: Duration::from_secs(ttl_seconds),
+            max_lifetime: None,
+            clock: Arc::new(SystemClock),
         }
     }
 
+    /// Caps how long a session lives, however often it is touched.
+    pub fn with_max_lifetime(mut self, seconds: u64) -> Self {
+        self.max_lifetime = Some(Duration::from_secs(seconds));
+        self
+    }
+
+    #[cfg(test)]
+    fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    fn is_expired(&self, stored: &StoredSession, now: Instant) -> bool {
+        now.saturating_duration_since(stored.last_active) > self.ttl
+            || self
+                .max_lifetime
+                .is_some_and(|max| now.saturating_duration_since(stored.session.created_at) > max)
+    }
+
     pub fn create(&self, session_id: String, session: AuthSession) {
         tracing::debug!("Session created: id={session_id}, step={:?}", session.step);
-        self.sessions.insert(session_id, session);
+        let last_active = self.clock.now();
+        self.sessions.insert(session_id, StoredSession { session, last_active });
         tracing::debug!("Session store size: {}", self.sessions.len());
     }
 
@@ -44,18 +89,36 @@ impl SessionStore {
             self.sessions.len()
         );
         let entry = self.sessions.get(session_id)?;
-        if entry.created_at.elapsed() > self.ttl {
+        if self.is_expired(&entry, self.clock.now()) {
             tracing::debug!("Session expired: id={session_id}");
             drop(entry);
             self.sessions.remove(session_id);
             return None;
         }
-        tracing::debug!("Session found: id={session_id}, step={:?}", entry.step);
-        Some(entry.clone())
+        tracing::debug!("Session found: id={session_id}, step={:?}", entry.session.step);
+        Some(entry.session.clone())
+    }
+
+    /// Marks the session as used now, pushing its idle expiry back.
+    /// Returns `false` (and drops the session) if it had already expired.
+    pub fn touch(&self, session_id: &str) -> bool {
+        let now = self.clock.now();
+        let Some(mut entry) = self.sessions.get_mut(session_id) else {
+            return false;
+        };
+        if self.is_expired(&entry, now) {
+            drop(entry);
+            self.sessions.remove(session_id);
+            return false;
+        }
+        entry.last_active = now;
+        true
     }
 
     pub fn update(&self, session_id: &str, session: AuthSession) {
-        self.sessions.insert(session_id.to_string(), session);
+        let last_active = self.clock.now();
+        self.sessions
+            .insert(session_id.to_string(), StoredSession { session, last_active });
     }
 
     pub fn remove(&self, session_id: &str) {
@@ -63,7 +126,99 @@ impl SessionStore {
     }
 
     pub fn cleanup_expired(&self) {
-        let ttl = self.ttl;
-        self.sessions.retain(|_, session| session.created_at.elapsed() <= ttl);
+        let now = self.clock.now();
+        self.sessions.retain(|_, stored| !self.is_expired(stored, now));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    struct ManualClock(Mutex<Instant>);
+
+    impl ManualClock {
+        fn advance(&self, secs: u64) {
+            *self.0.lock().unwrap() += Duration::from_secs(secs);
+        }
+    }
+
+    impl Clock for ManualClock {
+        fn now(&self) -> Instant {
+            *self.0.lock().unwrap()
+        }
+    }
+
+    fn store(ttl: u64, max: u64) -> (SessionStore, Arc<ManualClock>) {
+        let clock = Arc::new(ManualClock(Mutex::new(Instant::now())));
+        let store = SessionStore::new(ttl)
+            .with_max_lifetime(max)
+            .with_clock(clock.clone());
+        (store, clock)
+    }
+
+    fn session(created_at: Instant) -> AuthSession {
+        AuthSession {
+            username: "alice".to_string(),
+            step: AuthStep::Complete,
+            created_at,
+            otp_code: None,
+            otp_sent_at: None,
+            webauthn_state: None,
+        }
+    }
+
+    #[test]
+    fn untouched_session_expires_after_ttl() {
+        let (store, clock) = store(60, 600);
+        store.create("s".to_string(), session(clock.now()));
+        clock.advance(61);
+        assert!(store.get("s").is_none());
+        assert!(!store.touch("s"));
+    }
+
+    #[test]
+    fn touched_session_survives_past_original_ttl() {
+        let (store, clock) = store(60, 600);
+        store.create("s".to_string(), session(clock.now()));
+        for _ in 0..4 {
+            clock.advance(50);
+            assert!(store.touch("s"));
+        }
+        // 200s in: well past the 60s TTL, kept alive by the touches.
+        assert!(store.get("s").is_some());
+    }
+
+    #[test]
+    fn touched_session_still_ends_at_absolute_cap() {
+        let (store, clock) = store(60, 120);
+        store.create("s".to_string(), session(clock.now()));
+        clock.advance(50);
+        assert!(store.touch("s"));
+        clock.advance(50);
+        assert!(store.touch("s"));
+        clock.advance(50);
+        assert!(!store.touch("s"));
+        assert!(store.get("s").is_none());
+    }
+
+    #[test]
+    fn cleanup_drops_idle_and_capped_sessions() {
+        let (store, clock) = store(60, 120);
+        store.create("idle".to_string(), session(clock.now()));
+        store.create("busy".to_string(), session(clock.now()));
+        clock.advance(50);
+        store.touch("busy");
+        clock.advance(50);
+        store.cleanup_expired();
+        assert!(store.get("idle").is_none());
+        assert!(store.get("busy").is_some());
+    }
+
+    #[test]
+    fn unknown_session_cannot_be_touched() {
+        let (store, _) = store(60, 120);
+        assert!(!store.touch("missing"));
     }
 }
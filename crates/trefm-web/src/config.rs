@@ -23,6 +23,10 @@ pub struct ServerConfig {
     #[serde(default)]
     pub tls: TlsConfig,
     #[serde(default)]
+    pub audit: AuditConfig,
+    #[serde(default)]
+    pub cors: CorsConfig,
+    #[serde(default)]
     pub users: Vec<UserConfig>,
 }
 
@@ -32,6 +36,10 @@ pub struct FilesystemConfig {
     pub root: PathBuf,
     #[serde(default = "default_max_upload_size_mb")]
     pub max_upload_size_mb: usize,
+    /// When non-empty, file API paths must also fall inside one of these
+    /// directories (on top of the user's own root).
+    #[serde(default)]
+    pub allowed_roots: Vec<PathBuf>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -40,8 +48,12 @@ pub struct AuthConfig {
     pub jwt_secret: String,
     #[serde(default)]
     pub password_hash: String,
+    /// Absolute session lifetime (the JWT `exp`).
     #[serde(default = "default_jwt_ttl_hours")]
     pub jwt_ttl_hours: u64,
+    /// A session with no authenticated request for this long is logged out.
+    #[serde(default = "default_session_idle_minutes")]
+    pub session_idle_minutes: u64,
     #[serde(default)]
     pub discord_webhook_url: Option<String>,
     #[serde(default = "default_otp_ttl_seconds")]
@@ -56,6 +68,9 @@ pub struct AuthConfig {
 pub struct RateLimitConfig {
     #[serde(default = "default_login_rpm")]
     pub login_requests_per_minute: u32,
+    /// Per-user budget for the authenticated `/api` routes.
+    #[serde(default = "default_file_rpm")]
+    pub file_requests_per_minute: u32,
 }
 
 impl Default for AuthConfig {
@@ -64,6 +79,7 @@ impl Default for AuthConfig {
             jwt_secret: String::new(),
             password_hash: String::new(),
             jwt_ttl_hours: 24,
+            session_idle_minutes: default_session_idle_minutes(),
             discord_webhook_url: None,
             otp_ttl_seconds: 300,
             webauthn_rp_id: "localhost".to_string(),
@@ -73,16 +89,35 @@ impl Default for AuthConfig {
 }
 
 fn default_jwt_ttl_hours() -> u64 { 24 }
+fn default_session_idle_minutes() -> u64 { 120 }
 fn default_otp_ttl_seconds() -> u64 { 300 }
 fn default_webauthn_rp_id() -> String { "localhost".to_string() }
 fn default_login_rpm() -> u32 { 5 }
+fn default_file_rpm() -> u32 { 600 }
 
 impl Default for RateLimitConfig {
     fn default() -> Self {
-        Self { login_requests_per_minute: default_login_rpm() }
+        Self {
+            login_requests_per_minute: default_login_rpm(),
+            file_requests_per_minute: default_file_rpm(),
+        }
     }
 }
 
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct CorsConfig {
+    /// Origins allowed to call the API cross-origin. Empty means same-origin only.
+    #[serde(default)]
+    pub allowed_origins: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct AuditConfig {
+    /// JSON Lines file for audit records. When unset they go to the
+    /// `trefm_web::audit` tracing target.
+    pub log_file: Option<PathBuf>,
+}
+
 #[derive(Debug, Clone, Default, Deserialize)]
 pub struct TlsConfig {
     pub cert_path: Option<String>,
@@ -108,6 +143,7 @@ impl Default for FilesystemConfig {
         Self {
             root: default_root(),
             max_upload_size_mb: default_max_upload_size_mb(),
+            allowed_roots: Vec::new(),
         }
     }
 }
@@ -150,6 +186,8 @@ impl ServerConfig {
                 auth: AuthConfig::default(),
                 rate_limit: RateLimitConfig::default(),
                 tls: TlsConfig::default(),
+                audit: AuditConfig::default(),
+                cors: CorsConfig::default(),
                 users: Vec::new(),
             }
         };
@@ -182,6 +220,12 @@ impl ServerConfig {
             config.filesystem.root = PathBuf::from(root);
         }
 
+        if let Some(roots) = std::env::var_os("TREFM_ALLOWED_ROOTS") {
+            config.filesystem.allowed_roots = std::env::split_paths(&roots)
+                .filter(|p| !p.as_os_str().is_empty())
+                .collect();
+        }
+
         if let Ok(val) = std::env::var("TREFM_MAX_UPLOAD_SIZE_MB") {
             if let Ok(mb) = val.parse::<usize>() {
                 config.filesystem.max_upload_size_mb = mb;
@@ -192,6 +236,19 @@ impl ServerConfig {
             config.bind_addr = addr.parse()?;
         }
 
+        if let Ok(origins) = std::env::var("TREFM_CORS_ORIGINS") {
+            config.cors.allowed_origins = origins
+                .split(',')
+                .map(str::trim)
+                .filter(|o| !o.is_empty())
+                .map(String::from)
+                .collect();
+        }
+
+        if let Ok(path) = std::env::var("TREFM_AUDIT_LOG") {
+            config.audit.log_file = Some(PathBuf::from(path));
+        }
+
         if let Ok(cert) = std::env::var("TREFM_TLS_CERT") {
             config.tls.cert_path = Some(cert);
         }
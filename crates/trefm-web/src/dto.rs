@@ -7,6 +7,7 @@ pub struct LoginRequest {
     pub password: String,
 }
 
+#[allow(dead_code)]
 #[derive(Debug, Serialize)]
 pub struct LoginResponse {
     pub token: String,
@@ -71,6 +72,76 @@ pub struct DownloadQuery {
     pub path: String,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct PreviewQuery {
+    pub path: String,
+    pub offset: Option<usize>,
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PreviewResponse {
+    pub path: String,
+    pub is_binary: bool,
+    /// Lines starting at `offset`; empty for binary files.
+    pub lines: Vec<String>,
+    pub offset: usize,
+    pub total_lines: usize,
+    /// `true` when lines after this range remain.
+    pub is_truncated: bool,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchMode {
+    /// Fuzzy match on file and directory names.
+    #[default]
+    Name,
+    /// Case-insensitive substring match on file contents.
+    Content,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SearchQuery {
+    /// Directory to search; defaults to the user's root.
+    pub root: Option<String>,
+    pub q: String,
+    #[serde(default)]
+    pub mode: SearchMode,
+}
+
+#[derive(Debug, Serialize)]
+pub struct NameMatchDto {
+    pub path: String,
+    pub name: String,
+    pub is_dir: bool,
+    pub score: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ContentMatchDto {
+    pub path: String,
+    pub line_number: usize,
+    pub snippet: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum SearchMatchDto {
+    Name(NameMatchDto),
+    Content(ContentMatchDto),
+}
+
+#[derive(Debug, Serialize)]
+pub struct SearchResponse {
+    pub mode: SearchMode,
+    pub root: String,
+    /// Best match first for `name`, walk order for `content`.
+    pub matches: Vec<SearchMatchDto>,
+    /// `true` when more matches exist than were returned.
+    pub truncated: bool,
+}
+
 #[derive(Debug, Serialize)]
 pub struct UploadResponse {
     pub success: bool,
@@ -4,8 +4,11 @@ use serde::Serialize;
 
 #[derive(Debug)]
 pub enum AppError {
+    BadRequest(String),
     Auth(String),
+    Forbidden(String),
     NotFound(String),
+    TooLarge(String),
     Internal(String),
 }
 
@@ -15,11 +18,39 @@ struct ErrorBody {
     error: String,
 }
 
-impl IntoResponse for AppError {
-    fn into_response(self) -> Response {
-        let (status, message) = match &self {
-            AppError::Auth(msg) => (StatusCode::UNAUTHORIZED, msg.clone()),
-            AppError::NotFound(msg) => (StatusCode::NOT_FOUND, msg.clone()),
+impl AppError {
+    pub fn status(&self) -> StatusCode {
+        match self {
+            AppError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            AppError::Auth(_) => StatusCode::UNAUTHORIZED,
+            AppError::Forbidden(_) => StatusCode::FORBIDDEN,
+            AppError::NotFound(_) => StatusCode::NOT_FOUND,
+            AppError::TooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+            AppError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    /// The full message, including internal details. Server-side use only.
+    pub fn detail(&self) -> &str {
+        match self {
+            AppError::BadRequest(msg)
+            | AppError::Auth(msg)
+            | AppError::Forbidden(msg)
+            | AppError::NotFound(msg)
+            | AppError::TooLarge(msg)
+            | AppError::Internal(msg) => msg,
+        }
+    }
+
+    /// Status and client-facing message. Internal details are logged here
+    /// and replaced by a generic message.
+    fn status_and_message(&self) -> (StatusCode, String) {
+        match self {
+            AppError::BadRequest(msg)
+            | AppError::Auth(msg)
+            | AppError::Forbidden(msg)
+            | AppError::NotFound(msg)
+            | AppError::TooLarge(msg) => (self.status(), msg.clone()),
             AppError::Internal(msg) => {
                 // Log the real error server-side, return generic message to client
                 tracing::error!("Internal error: {}", msg);
@@ -28,7 +59,18 @@ impl IntoResponse for AppError {
                     "Internal server error".to_string(),
                 )
             }
-        };
+        }
+    }
+
+    /// Message safe to send to the client, e.g. over a WebSocket.
+    pub fn client_message(&self) -> String {
+        self.status_and_message().1
+    }
+}
+
+impl IntoResponse for AppError {
+    fn into_response(self) -> Response {
+        let (status, message) = self.status_and_message();
 
         let body = ErrorBody {
             success: false,
@@ -1,9 +1,11 @@
 mod api;
+mod audit;
 mod auth;
 mod config;
 mod dto;
 mod error;
 mod middleware;
+mod shutdown;
 mod state;
 mod static_files;
 mod ws;
@@ -12,8 +14,6 @@ use std::sync::Arc;
 
 use axum::middleware::from_fn;
 use tower_governor::{governor::GovernorConfigBuilder, key_extractor::SmartIpKeyExtractor, GovernorLayer};
-use axum::http::{header, Method};
-use tower_http::cors::CorsLayer;
 use tower_http::limit::RequestBodyLimitLayer;
 use tower_http::trace::TraceLayer;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
@@ -37,8 +37,13 @@ async fn main() -> anyhow::Result<()> {
     let tls_enabled = tls_config.cert_path.is_some() && tls_config.key_path.is_some();
     let rate_limit_rpm = config.rate_limit.login_requests_per_minute;
 
-    // Session store
+    // Session stores: pending multi-step logins, and logged-in sessions
+    // (sliding idle expiry, capped at the JWT lifetime)
     let session_store = Arc::new(crate::auth::session::SessionStore::new(600));
+    let login_sessions = Arc::new(
+        crate::auth::session::SessionStore::new(config.auth.session_idle_minutes * 60)
+            .with_max_lifetime(config.auth.jwt_ttl_hours * 3600),
+    );
 
     // WebAuthn (optional)
     let webauthn = if !config.auth.webauthn_rp_id.is_empty() {
@@ -61,43 +66,61 @@ async fn main() -> anyhow::Result<()> {
         None
     };
 
+    let audit = match &config.audit.log_file {
+        Some(path) => {
+            tracing::info!("Audit log: {}", path.display());
+            crate::audit::AuditLog::to_file(path)?
+        }
+        None => crate::audit::AuditLog::tracing(),
+    };
+
+    // Cancelled on SIGTERM/Ctrl+C
+    let shutdown_token = tokio_util::sync::CancellationToken::new();
+    shutdown::cancel_on(shutdown::signal(), shutdown_token.clone());
+
     let ws_tickets = Arc::new(dashmap::DashMap::new());
     let revoked_tokens = Arc::new(dashmap::DashMap::new());
 
     let state = AppState {
         config: Arc::new(config),
         session_store: session_store.clone(),
+        login_sessions: login_sessions.clone(),
         webauthn,
         ws_tickets: ws_tickets.clone(),
         revoked_tokens: revoked_tokens.clone(),
+        audit: Arc::new(audit),
+        shutdown: shutdown_token.clone(),
     };
 
     // Session + ticket + revoked token cleanup task
     let cleanup_store = session_store.clone();
+    let cleanup_logins = login_sessions.clone();
     let cleanup_tickets = ws_tickets.clone();
     let cleanup_revoked = revoked_tokens.clone();
     let jwt_ttl_hours = state.config.auth.jwt_ttl_hours;
-    tokio::spawn(async move {
-        let mut interval = tokio::time::interval(std::time::Duration::from_secs(60));
-        loop {
-            interval.tick().await;
+    let cleanup_task = shutdown::spawn_periodic(
+        std::time::Duration::from_secs(60),
+        shutdown_token.clone(),
+        move || {
             cleanup_store.cleanup_expired();
+            cleanup_logins.cleanup_expired();
             // Remove expired WebSocket tickets (>30s)
             cleanup_tickets.retain(|_, t: &mut crate::state::WsTicket| {
-                t.created_at.elapsed() < std::time::Duration::from_secs(30)
+                t.created_at.elapsed() < ws::TICKET_TTL
             });
             // Remove revoked tokens older than JWT TTL (expired tokens are rejected by jsonwebtoken)
             let max_age = std::time::Duration::from_secs(jwt_ttl_hours * 3600);
             cleanup_revoked.retain(|_, revoked_at: &mut std::time::Instant| {
                 revoked_at.elapsed() < max_age
             });
-        }
-    });
+        },
+    );
 
-    // CORS: same-origin only by default (no cross-origin requests allowed)
-    let cors = CorsLayer::new()
-        .allow_methods([Method::GET, Method::POST])
-        .allow_headers([header::CONTENT_TYPE, header::AUTHORIZATION]);
+    // CORS: same-origin only unless `cors.allowed_origins` lists other origins
+    let cors = middleware::cors::cors_layer(&state.config.cors.allowed_origins)?;
+    if !state.config.cors.allowed_origins.is_empty() {
+        tracing::info!("CORS origins: {}", state.config.cors.allowed_origins.join(", "));
+    }
 
     // Rate limit config (per-IP)
     let period_per_request = 60 / rate_limit_rpm.max(1);
@@ -110,29 +133,39 @@ async fn main() -> anyhow::Result<()> {
             .expect("Failed to build rate limit config"),
     );
 
-    // Rate limit + bot guard only on auth routes (not static files, API, or WebSocket)
+    // Strict per-IP rate limit + bot guard on auth routes (not static files or WebSocket)
     let auth_routes = api::auth_router()
+        .layer(RequestBodyLimitLayer::new(api::JSON_BODY_LIMIT))
         .layer(from_fn(middleware::bot_guard::bot_guard))
         .layer(GovernorLayer::<_, _, axum::body::Body>::new(governor_config));
 
     let upload_limit = state.config.filesystem.max_upload_size_mb * 1024 * 1024;
 
+    // Looser per-user limit on the authenticated API (files, search, WS tickets)
+    let file_governor_config = middleware::rate_limit::per_user_config(
+        state.config.rate_limit.file_requests_per_minute,
+        middleware::rate_limit::UserKeyExtractor::new(
+            &state.config.auth.jwt_secret,
+            state.config.has_auth(),
+        ),
+    );
+    let protected_routes = api::protected_router(upload_limit)
+        .layer(GovernorLayer::<_, _, axum::body::Body>::new(file_governor_config));
+
     let base_router = axum::Router::new()
-        .nest("/api", auth_routes.merge(api::protected_router(upload_limit)))
+        .nest("/api", auth_routes.merge(protected_routes))
         .nest("/ws", ws::router())
         .fallback(static_files::static_handler);
 
     let app = if tls_enabled {
         base_router
             .layer(from_fn(middleware::security_headers::security_headers_with_hsts))
-            .layer(RequestBodyLimitLayer::new(1024 * 1024))
             .layer(cors)
             .layer(TraceLayer::new_for_http())
             .with_state(state)
     } else {
         base_router
             .layer(from_fn(middleware::security_headers::security_headers))
-            .layer(RequestBodyLimitLayer::new(1024 * 1024))
             .layer(cors)
             .layer(TraceLayer::new_for_http())
             .with_state(state)
@@ -142,18 +175,38 @@ async fn main() -> anyhow::Result<()> {
         use axum_server::tls_rustls::RustlsConfig;
         let rustls_config = RustlsConfig::from_pem_file(cert, key).await?;
         tracing::info!("trefm-web listening on https://{}", bind_addr);
+        let handle = axum_server::Handle::new();
+        let token = shutdown_token.clone();
+        let shutdown_handle = handle.clone();
+        tokio::spawn(async move {
+            token.cancelled().await;
+            shutdown_handle.graceful_shutdown(Some(shutdown::GRACE_PERIOD));
+        });
         axum_server::bind_rustls(bind_addr, rustls_config)
+            .handle(handle)
             .serve(app.into_make_service_with_connect_info::<std::net::SocketAddr>())
             .await?;
     } else {
         let listener = tokio::net::TcpListener::bind(bind_addr).await?;
         tracing::info!("trefm-web listening on http://{}", bind_addr);
-        axum::serve(
+        let token = shutdown_token.clone();
+        let server = axum::serve(
             listener,
             app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
         )
-        .await?;
+        .with_graceful_shutdown(async move { token.cancelled().await });
+        // Don't let a stuck connection hold the process past the grace period
+        let token = shutdown_token.clone();
+        tokio::select! {
+            result = server => result?,
+            _ = async {
+                token.cancelled().await;
+                tokio::time::sleep(shutdown::GRACE_PERIOD).await;
+            } => tracing::warn!("Grace period elapsed, closing remaining connections"),
+        }
     }
 
+    let _ = cleanup_task.await;
+    tracing::info!("trefm-web stopped");
     Ok(())
 }
@@ -0,0 +1,102 @@
+use axum::http::{header, HeaderValue, Method};
+use tower_http::cors::{AllowOrigin, CorsLayer};
+
+/// Builds the CORS layer from `cors.allowed_origins`.
+///
+/// An empty list keeps the API same-origin only: no `Access-Control-Allow-Origin`
+/// header is ever sent. Each entry must be a bare `http(s)://host[:port]`
+/// origin; anything else (paths, wildcards, other schemes) fails startup.
+pub fn cors_layer(allowed_origins: &[String]) -> anyhow::Result<CorsLayer> {
+    let layer = CorsLayer::new()
+        .allow_methods([Method::GET, Method::POST])
+        .allow_headers([header::CONTENT_TYPE, header::AUTHORIZATION]);
+    if allowed_origins.is_empty() {
+        return Ok(layer);
+    }
+
+    let origins = allowed_origins
+        .iter()
+        .map(|origin| parse_origin(origin))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    Ok(layer.allow_origin(AllowOrigin::list(origins)))
+}
+
+fn parse_origin(origin: &str) -> anyhow::Result<HeaderValue> {
+    let url = url::Url::parse(origin)
+        .map_err(|e| anyhow::anyhow!("Invalid CORS origin {origin:?}: {e}"))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        anyhow::bail!("Invalid CORS origin {origin:?}: scheme must be http or https");
+    }
+    // An origin is scheme + host + port only; `Url` adds a "/" path to bare origins.
+    let bare = url.path() == "/" && url.query().is_none() && url.fragment().is_none();
+    if !bare || origin.ends_with('/') || url.host_str().is_none() {
+        anyhow::bail!("Invalid CORS origin {origin:?}: expected scheme://host[:port]");
+    }
+    Ok(HeaderValue::from_str(&url.origin().ascii_serialization())?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use axum::routing::get;
+    use axum::Router;
+    use tower::ServiceExt;
+
+    async fn allow_origin_for(layer: CorsLayer, origin: &str) -> Option<HeaderValue> {
+        let router = Router::new()
+            .route("/api/files", get(|| async { "ok" }))
+            .layer(layer);
+        let req = Request::builder()
+            .uri("/api/files")
+            .header(header::ORIGIN, origin)
+            .body(Body::empty())
+            .unwrap();
+        let response = router.oneshot(req).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        response
+            .headers()
+            .get(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+            .cloned()
+    }
+
+    #[tokio::test]
+    async fn configured_origins_are_allowed() {
+        let layer = cors_layer(&[
+            "https://ui.example.com".to_string(),
+            "http://localhost:5173".to_string(),
+        ])
+        .unwrap();
+
+        assert_eq!(
+            allow_origin_for(layer.clone(), "https://ui.example.com").await,
+            Some(HeaderValue::from_static("https://ui.example.com"))
+        );
+        assert_eq!(
+            allow_origin_for(layer.clone(), "http://localhost:5173").await,
+            Some(HeaderValue::from_static("http://localhost:5173"))
+        );
+        assert_eq!(allow_origin_for(layer, "https://evil.example.com").await, None);
+    }
+
+    #[tokio::test]
+    async fn empty_list_stays_same_origin() {
+        let layer = cors_layer(&[]).unwrap();
+        assert_eq!(allow_origin_for(layer, "https://ui.example.com").await, None);
+    }
+
+    #[test]
+    fn invalid_origins_fail_validation() {
+        for origin in [
+            "*",
+            "ui.example.com",
+            "ftp://ui.example.com",
+            "https://ui.example.com/app",
+            "https://ui.example.com/",
+            "https://ui.example.com?x=1",
+        ] {
+            assert!(cors_layer(&[origin.to_string()]).is_err(), "{origin}");
+        }
+    }
+}
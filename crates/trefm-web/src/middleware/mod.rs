@@ -1,3 +1,4 @@
 pub mod bot_guard;
+pub mod cors;
 pub mod rate_limit;
 pub mod security_headers;
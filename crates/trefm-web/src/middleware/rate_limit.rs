@@ -1,5 +1,156 @@
-// Rate limiting is configured inline in main.rs using tower_governor.
-// This module serves as documentation for the rate limit configuration.
+// Rate limiting uses tower_governor and is layered in main.rs.
 //
-// Configuration: `rate_limit.login_requests_per_minute` in ServerConfig (default: 5)
-// Uses SmartIpKeyExtractor for per-IP rate limiting.
+// Auth routes: `rate_limit.login_requests_per_minute` (default: 5), keyed per IP
+// with SmartIpKeyExtractor.
+// Protected API routes: `rate_limit.file_requests_per_minute` (default: 600),
+// keyed per user with UserKeyExtractor below.
+
+use std::sync::Arc;
+
+use axum::http::Request;
+use governor::middleware::NoOpMiddleware;
+use tower_governor::governor::{GovernorConfig, GovernorConfigBuilder};
+use tower_governor::key_extractor::{KeyExtractor, SmartIpKeyExtractor};
+use tower_governor::GovernorError;
+
+use crate::auth::jwt;
+
+/// Rate-limit bucket a request is charged to.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum RateKey {
+    User(String),
+    /// Requests without a valid token fall back to the client IP.
+    Ip(std::net::IpAddr),
+}
+
+/// Keys requests by the `sub` of their bearer token, so every user gets a
+/// bucket of their own no matter how many addresses they connect from.
+///
+/// Only the signature and expiry are checked here; revocation is left to the
+/// `AuthUser` extractor, which rejects the request right after.
+#[derive(Debug, Clone)]
+pub struct UserKeyExtractor {
+    jwt_secret: Arc<str>,
+    has_auth: bool,
+}
+
+impl UserKeyExtractor {
+    pub fn new(jwt_secret: &str, has_auth: bool) -> Self {
+        Self {
+            jwt_secret: Arc::from(jwt_secret),
+            has_auth,
+        }
+    }
+}
+
+impl KeyExtractor for UserKeyExtractor {
+    type Key = RateKey;
+
+    fn extract<T>(&self, req: &Request<T>) -> Result<Self::Key, GovernorError> {
+        if !self.has_auth {
+            return Ok(RateKey::User("anonymous".to_string()));
+        }
+        let sub = req
+            .headers()
+            .get("authorization")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "))
+            .and_then(|token| jwt::verify_token(&self.jwt_secret, token).ok())
+            .map(|claims| claims.sub);
+        match sub {
+            Some(sub) => Ok(RateKey::User(sub)),
+            None => SmartIpKeyExtractor.extract(req).map(RateKey::Ip),
+        }
+    }
+}
+
+/// Builds a limiter that replenishes `rpm` requests per minute per user and
+/// allows bursts of the same size.
+pub fn per_user_config(
+    rpm: u32,
+    extractor: UserKeyExtractor,
+) -> Arc<GovernorConfig<UserKeyExtractor, NoOpMiddleware>> {
+    let rpm = rpm.max(1);
+    Arc::new(
+        GovernorConfigBuilder::default()
+            .per_millisecond((60_000 / u64::from(rpm)).max(1))
+            .burst_size(rpm)
+            .key_extractor(extractor)
+            .finish()
+            .expect("Failed to build rate limit config"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::StatusCode;
+    use axum::routing::get;
+    use axum::Router;
+    use tower::ServiceExt;
+    use tower_governor::GovernorLayer;
+
+    const SECRET: &str = "test-secret";
+
+    fn limited_router(rpm: u32) -> Router {
+        let config = per_user_config(rpm, UserKeyExtractor::new(SECRET, true));
+        Router::new()
+            .route("/files", get(|| async { "ok" }))
+            .layer(GovernorLayer::<_, _, Body>::new(config))
+    }
+
+    async fn get_as(router: &Router, user: &str) -> StatusCode {
+        let token = jwt::create_token(SECRET, 1, user).unwrap().token;
+        let req = Request::builder()
+            .uri("/files")
+            .header("authorization", format!("Bearer {token}"))
+            .body(Body::empty())
+            .unwrap();
+        router.clone().oneshot(req).await.unwrap().status()
+    }
+
+    #[tokio::test]
+    async fn burst_beyond_limit_is_rejected() {
+        let router = limited_router(3);
+        for _ in 0..3 {
+            assert_eq!(get_as(&router, "alice").await, StatusCode::OK);
+        }
+        assert_eq!(get_as(&router, "alice").await, StatusCode::TOO_MANY_REQUESTS);
+    }
+
+    #[tokio::test]
+    async fn users_have_independent_buckets() {
+        let router = limited_router(2);
+        for _ in 0..2 {
+            assert_eq!(get_as(&router, "alice").await, StatusCode::OK);
+        }
+        assert_eq!(get_as(&router, "alice").await, StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(get_as(&router, "bob").await, StatusCode::OK);
+        assert_eq!(get_as(&router, "bob").await, StatusCode::OK);
+    }
+
+    #[test]
+    fn invalid_token_falls_back_to_ip() {
+        let extractor = UserKeyExtractor::new(SECRET, true);
+        let req = Request::builder()
+            .header("authorization", "Bearer not-a-jwt")
+            .header("x-forwarded-for", "10.0.0.7")
+            .body(())
+            .unwrap();
+        assert_eq!(
+            extractor.extract(&req).unwrap(),
+            RateKey::Ip("10.0.0.7".parse().unwrap())
+        );
+    }
+
+    #[test]
+    fn no_auth_shares_anonymous_bucket() {
+        let extractor = UserKeyExtractor::new(SECRET, false);
+        let req = Request::builder().body(()).unwrap();
+        assert_eq!(
+            extractor.extract(&req).unwrap(),
+            RateKey::User("anonymous".to_string())
+        );
+    }
+}
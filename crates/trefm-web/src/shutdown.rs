@@ -0,0 +1,116 @@
+//! Graceful shutdown.
+//!
+//! One [`CancellationToken`] is cancelled when SIGTERM or Ctrl+C arrives.
+//! The HTTP server stops accepting connections and drains in-flight
+//! requests, WebSocket handlers close their sockets, and background tasks
+//! such as the session cleanup loop return.
+
+use std::future::Future;
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+
+/// How long in-flight requests get to finish once shutdown starts.
+pub const GRACE_PERIOD: Duration = Duration::from_secs(10);
+
+/// Resolves on the first Ctrl+C or (on Unix) SIGTERM.
+pub async fn signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            tracing::error!("Failed to listen for Ctrl+C: {e}");
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut sigterm) => {
+                sigterm.recv().await;
+            }
+            Err(e) => {
+                tracing::error!("Failed to listen for SIGTERM: {e}");
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = terminate => {}
+    }
+}
+
+/// Cancels `token` once `signal` resolves.
+pub fn cancel_on<F>(signal: F, token: CancellationToken) -> JoinHandle<()>
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    tokio::spawn(async move {
+        signal.await;
+        tracing::info!("Shutdown requested, draining connections");
+        token.cancel();
+    })
+}
+
+/// Runs `tick` every `period` until `token` is cancelled.
+pub fn spawn_periodic<F>(period: Duration, token: CancellationToken, mut tick: F) -> JoinHandle<()>
+where
+    F: FnMut() + Send + 'static,
+{
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(period);
+        loop {
+            tokio::select! {
+                _ = token.cancelled() => break,
+                _ = interval.tick() => tick(),
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn signal_future_cancels_token() {
+        let token = CancellationToken::new();
+        let (tx, rx) = tokio::sync::oneshot::channel::<()>();
+        let handle = cancel_on(
+            async move {
+                let _ = rx.await;
+            },
+            token.clone(),
+        );
+
+        assert!(!token.is_cancelled());
+        tx.send(()).unwrap();
+        handle.await.unwrap();
+        assert!(token.is_cancelled());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn periodic_task_stops_on_cancellation() {
+        let token = CancellationToken::new();
+        let ticks = Arc::new(AtomicUsize::new(0));
+        let counter = ticks.clone();
+        let handle = spawn_periodic(Duration::from_secs(60), token.clone(), move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+
+        // First tick fires immediately, then one per period.
+        tokio::time::sleep(Duration::from_secs(121)).await;
+        assert_eq!(ticks.load(Ordering::SeqCst), 3);
+
+        token.cancel();
+        handle.await.unwrap();
+        tokio::time::sleep(Duration::from_secs(600)).await;
+        assert_eq!(ticks.load(Ordering::SeqCst), 3);
+    }
+}
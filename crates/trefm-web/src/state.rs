@@ -2,7 +2,9 @@ use std::sync::Arc;
 use std::time::Instant;
 
 use dashmap::DashMap;
+use tokio_util::sync::CancellationToken;
 
+use crate::audit::AuditLog;
 use crate::auth::session::SessionStore;
 use crate::auth::webauthn_manager::WebAuthnManager;
 use crate::config::ServerConfig;
@@ -17,9 +19,16 @@ pub struct WsTicket {
 #[derive(Clone)]
 pub struct AppState {
     pub config: Arc<ServerConfig>,
+    /// In-progress multi-step logins (password → WebAuthn/OTP).
     pub session_store: Arc<SessionStore>,
+    /// Logged-in sessions keyed by JWT `jti`; touched by the auth middleware.
+    pub login_sessions: Arc<SessionStore>,
     pub webauthn: Option<Arc<WebAuthnManager>>,
     pub ws_tickets: Arc<DashMap<String, WsTicket>>,
     /// Revoked JWT token IDs (jti). Tokens in this map are rejected by the auth middleware.
     pub revoked_tokens: Arc<DashMap<String, Instant>>,
+    /// Audit trail of file API requests.
+    pub audit: Arc<AuditLog>,
+    /// Cancelled on SIGTERM/Ctrl+C; long-lived tasks and sockets watch it.
+    pub shutdown: CancellationToken,
 }
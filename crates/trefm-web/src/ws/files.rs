@@ -0,0 +1,329 @@
+//! Directory listing stream.
+//!
+//! After the ticket check the client receives a `listing` for the requested
+//! directory, then `upsert`/`remove` events as its entries change. Sending
+//! `{"type":"navigate","path":...}` switches the stream to another directory.
+//! Every listing, including refused ones, is audited like `/api/files`.
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::extract::ws::{Message, WebSocket};
+use axum::extract::{Query, State, WebSocketUpgrade};
+use axum::response::IntoResponse;
+use futures::stream::SplitSink;
+use futures::{SinkExt, StreamExt};
+use notify_debouncer_mini::{new_debouncer, DebouncedEventKind, Debouncer};
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+
+use crate::api::files::{entry_dto, read_listing, FileScope};
+use crate::audit::{AuditAction, AuditLog};
+use crate::dto::{FileEntryDto, ListDirResponse};
+use crate::state::AppState;
+
+/// Same debounce window as the TUI's directory watcher.
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+#[derive(Deserialize)]
+pub struct FilesQuery {
+    /// Single-use ticket ID from `/api/ws/ticket`
+    ticket: Option<String>,
+    path: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ServerMessage {
+    /// Full listing of the directory being streamed.
+    Listing(ListDirResponse),
+    /// An entry was created or modified.
+    Upsert { entry: FileEntryDto },
+    /// An entry was deleted or moved away.
+    Remove { path: String },
+    Error { message: String },
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ClientMessage {
+    Navigate { path: String },
+}
+
+pub async fn files_handler(
+    State(state): State<AppState>,
+    Query(query): Query<FilesQuery>,
+    ws: WebSocketUpgrade,
+) -> impl IntoResponse {
+    let username = match super::consume_ticket(
+        &state.ws_tickets,
+        state.config.has_auth(),
+        query.ticket.as_deref(),
+    ) {
+        Ok(username) => username,
+        Err(status) => return status.into_response(),
+    };
+
+    let scope = FileScope::for_user(&state.config, &username);
+    let audit = state.audit.clone();
+    let shutdown = state.shutdown.clone();
+    ws.on_upgrade(move |socket| handle_files(socket, scope, query.path, audit, username, shutdown))
+        .into_response()
+}
+
+async fn handle_files(
+    socket: WebSocket,
+    scope: FileScope,
+    path: Option<String>,
+    audit: Arc<AuditLog>,
+    username: String,
+    shutdown: CancellationToken,
+) {
+    let lister = Lister {
+        scope: &scope,
+        audit: &audit,
+        username: &username,
+    };
+    let (mut ws_sender, mut ws_receiver) = socket.split();
+
+    let (watch_tx, mut watch_rx) = mpsc::unbounded_channel::<Vec<PathBuf>>();
+    let mut debouncer = match new_debouncer(
+        DEBOUNCE,
+        move |result: Result<Vec<notify_debouncer_mini::DebouncedEvent>, notify::Error>| {
+            if let Ok(events) = result {
+                let paths: Vec<PathBuf> = events
+                    .into_iter()
+                    .filter(|e| matches!(e.kind, DebouncedEventKind::Any))
+                    .map(|e| e.path)
+                    .collect();
+                if !paths.is_empty() {
+                    let _ = watch_tx.send(paths);
+                }
+            }
+        },
+    ) {
+        Ok(d) => d,
+        Err(e) => {
+            let message = format!("Failed to start watcher: {e}");
+            let _ = send(&mut ws_sender, &ServerMessage::Error { message }).await;
+            return;
+        }
+    };
+
+    let mut current: Option<PathBuf> = None;
+    let msg = lister.switch_to(path.as_deref(), &mut debouncer, &mut current);
+    if !send(&mut ws_sender, &msg).await {
+        return;
+    }
+
+    loop {
+        tokio::select! {
+            _ = shutdown.cancelled() => {
+                let _ = ws_sender.send(Message::Close(None)).await;
+                break;
+            }
+            Some(paths) = watch_rx.recv() => {
+                let Some(dir) = current.as_deref() else {
+                    continue;
+                };
+                for msg in change_events(dir, paths) {
+                    if !send(&mut ws_sender, &msg).await {
+                        return;
+                    }
+                }
+            }
+            ws_msg = ws_receiver.next() => {
+                match ws_msg {
+                    Some(Ok(Message::Text(text))) => {
+                        if let Ok(ClientMessage::Navigate { path }) = serde_json::from_str(&text) {
+                            let msg = lister.switch_to(Some(&path), &mut debouncer, &mut current);
+                            if !send(&mut ws_sender, &msg).await {
+                                return;
+                            }
+                        }
+                    }
+                    Some(Ok(Message::Close(_))) | None => break,
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+/// Lists directories for one connection, auditing each listing.
+struct Lister<'a> {
+    scope: &'a FileScope,
+    audit: &'a AuditLog,
+    username: &'a str,
+}
+
+impl Lister<'_> {
+    /// Lists `path` and moves the watch there. On error the previous
+    /// directory keeps streaming.
+    fn switch_to(
+        &self,
+        path: Option<&str>,
+        debouncer: &mut Debouncer<notify::RecommendedWatcher>,
+        current: &mut Option<PathBuf>,
+    ) -> ServerMessage {
+        let result = read_listing(self.scope, path);
+        self.audit.record(
+            self.username,
+            AuditAction::List,
+            path.unwrap_or_default(),
+            &result,
+        );
+        let listing = match result {
+            Ok(listing) => listing,
+            Err(e) => {
+                return ServerMessage::Error {
+                    message: e.client_message(),
+                }
+            }
+        };
+
+        let dir = PathBuf::from(&listing.current_path);
+        if current.as_deref() != Some(dir.as_path()) {
+            if let Some(prev) = current.take() {
+                let _ = debouncer.watcher().unwatch(&prev);
+            }
+            if let Err(e) = debouncer
+                .watcher()
+                .watch(&dir, notify::RecursiveMode::NonRecursive)
+            {
+                tracing::warn!("Failed to watch {}: {e}", dir.display());
+            }
+            *current = Some(dir);
+        }
+        ServerMessage::Listing(listing)
+    }
+}
+
+/// Turns watcher-reported paths into one event per direct child of `dir`.
+fn change_events(dir: &Path, paths: Vec<PathBuf>) -> Vec<ServerMessage> {
+    paths
+        .into_iter()
+        .filter(|p| p.parent() == Some(dir))
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .map(|p| match entry_dto(&p) {
+            Some(entry) => ServerMessage::Upsert { entry },
+            None => ServerMessage::Remove {
+                path: p.to_string_lossy().to_string(),
+            },
+        })
+        .collect()
+}
+
+/// Sends one JSON text frame. Returns `false` once the client is gone.
+async fn send(sender: &mut SplitSink<WebSocket, Message>, msg: &ServerMessage) -> bool {
+    let Ok(text) = serde_json::to_string(msg) else {
+        return true;
+    };
+    sender.send(Message::Text(text.into())).await.is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn initial_listing_frame() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        std::fs::write(tmp.path().join("b.txt"), "hello").unwrap();
+        std::fs::create_dir(tmp.path().join("a_dir")).unwrap();
+
+        let listing = read_listing(&FileScope::new(tmp.path().into(), Vec::new()), None).unwrap();
+        let frame = serde_json::to_value(ServerMessage::Listing(listing)).unwrap();
+
+        assert_eq!(frame["type"], "listing");
+        let canonical = tmp.path().canonicalize().unwrap();
+        assert_eq!(frame["current_path"], canonical.to_string_lossy().as_ref());
+        let entries = frame["entries"].as_array().unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0]["name"], "a_dir");
+        assert_eq!(entries[0]["is_dir"], true);
+        assert_eq!(entries[1]["name"], "b.txt");
+        assert_eq!(entries[1]["size"], 5);
+    }
+
+    #[test]
+    fn listing_outside_root_is_an_error_frame() {
+        let root = tempfile::TempDir::new().unwrap();
+        let other = tempfile::TempDir::new().unwrap();
+        let scope = FileScope::new(root.path().into(), Vec::new());
+        let err = read_listing(&scope, other.path().to_str()).unwrap_err();
+        let frame = serde_json::to_value(ServerMessage::Error {
+            message: err.client_message(),
+        })
+        .unwrap();
+        assert_eq!(frame["type"], "error");
+        assert_eq!(frame["message"], "Access denied");
+    }
+
+    #[test]
+    fn every_switch_is_audited() {
+        let root = tempfile::TempDir::new().unwrap();
+        let other = tempfile::TempDir::new().unwrap();
+        let scope = FileScope::new(root.path().into(), Vec::new());
+        let audit = AuditLog::memory();
+        let lister = Lister {
+            scope: &scope,
+            audit: &audit,
+            username: "alice",
+        };
+        let mut debouncer = new_debouncer(DEBOUNCE, |_| {}).unwrap();
+        let mut current = None;
+
+        let first = lister.switch_to(None, &mut debouncer, &mut current);
+        assert!(matches!(first, ServerMessage::Listing(_)));
+        let outside = other.path().to_string_lossy().to_string();
+        let refused = lister.switch_to(Some(&outside), &mut debouncer, &mut current);
+        assert!(matches!(refused, ServerMessage::Error { .. }));
+        assert_eq!(current, Some(root.path().canonicalize().unwrap()));
+
+        let records = audit.records();
+        assert_eq!(records.len(), 2);
+        assert!(records
+            .iter()
+            .all(|r| r.username == "alice" && r.action == AuditAction::List));
+        assert_eq!(records[0].path, "");
+        assert_eq!(records[0].result, "ok");
+        assert_eq!(records[1].path, outside);
+        assert_eq!(records[1].result, "error");
+        assert_eq!(records[1].status, 403);
+        assert_eq!(records[1].error.as_deref(), Some("Access denied"));
+    }
+
+    #[test]
+    fn change_events_upsert_and_remove_direct_children() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let dir = tmp.path().canonicalize().unwrap();
+        std::fs::write(dir.join("new.txt"), "x").unwrap();
+        let gone = dir.join("gone.txt");
+        let nested = dir.join("sub").join("deep.txt");
+
+        let events = change_events(&dir, vec![dir.join("new.txt"), gone.clone(), nested]);
+        let frames: Vec<_> = events
+            .iter()
+            .map(|m| serde_json::to_value(m).unwrap())
+            .collect();
+
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0]["type"], "remove");
+        assert_eq!(frames[0]["path"], gone.to_string_lossy().as_ref());
+        assert_eq!(frames[1]["type"], "upsert");
+        assert_eq!(frames[1]["entry"]["name"], "new.txt");
+    }
+
+    #[test]
+    fn navigate_message_parses() {
+        let msg: ClientMessage =
+            serde_json::from_str(r#"{"type":"navigate","path":"/tmp"}"#).unwrap();
+        let ClientMessage::Navigate { path } = msg;
+        assert_eq!(path, "/tmp");
+    }
+}
@@ -1,10 +1,98 @@
+mod files;
 mod terminal;
 
+use std::time::Duration;
+
+use axum::http::StatusCode;
 use axum::routing::get;
 use axum::Router;
+use dashmap::DashMap;
+
+use crate::state::{AppState, WsTicket};
 
-use crate::state::AppState;
+/// Tickets expire this long after `/api/ws/ticket` issued them.
+pub const TICKET_TTL: Duration = Duration::from_secs(30);
 
 pub fn router() -> Router<AppState> {
-    Router::new().route("/terminal", get(terminal::terminal_handler))
+    Router::new()
+        .route("/terminal", get(terminal::terminal_handler))
+        .route("/files", get(files::files_handler))
+}
+
+/// Resolves the user behind a WebSocket upgrade by consuming its single-use
+/// ticket. Without configured auth every connection is `anonymous`.
+fn consume_ticket(
+    tickets: &DashMap<String, WsTicket>,
+    has_auth: bool,
+    ticket: Option<&str>,
+) -> Result<String, StatusCode> {
+    if !has_auth {
+        return Ok("anonymous".to_string());
+    }
+    let ticket_id = ticket.ok_or(StatusCode::UNAUTHORIZED)?;
+    let (_, ticket) = tickets
+        .remove(ticket_id)
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+    if ticket.created_at.elapsed() > TICKET_TTL {
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+    Ok(ticket.username)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    fn tickets_with(id: &str, created_at: Instant) -> DashMap<String, WsTicket> {
+        let tickets = DashMap::new();
+        tickets.insert(
+            id.to_string(),
+            WsTicket {
+                username: "alice".to_string(),
+                created_at,
+            },
+        );
+        tickets
+    }
+
+    #[test]
+    fn valid_ticket_is_consumed_once() {
+        let tickets = tickets_with("t1", Instant::now());
+        assert_eq!(consume_ticket(&tickets, true, Some("t1")), Ok("alice".to_string()));
+        assert_eq!(
+            consume_ticket(&tickets, true, Some("t1")),
+            Err(StatusCode::UNAUTHORIZED)
+        );
+    }
+
+    #[test]
+    fn missing_or_unknown_ticket_is_rejected() {
+        let tickets = tickets_with("t1", Instant::now());
+        assert_eq!(consume_ticket(&tickets, true, None), Err(StatusCode::UNAUTHORIZED));
+        assert_eq!(
+            consume_ticket(&tickets, true, Some("nope")),
+            Err(StatusCode::UNAUTHORIZED)
+        );
+        assert!(tickets.contains_key("t1"));
+    }
+
+    #[test]
+    fn expired_ticket_is_rejected() {
+        let Some(old) = Instant::now().checked_sub(TICKET_TTL + Duration::from_secs(1)) else {
+            return;
+        };
+        let tickets = tickets_with("t1", old);
+        assert_eq!(
+            consume_ticket(&tickets, true, Some("t1")),
+            Err(StatusCode::UNAUTHORIZED)
+        );
+        assert!(tickets.is_empty());
+    }
+
+    #[test]
+    fn no_auth_allows_anonymous() {
+        let tickets = DashMap::new();
+        assert_eq!(consume_ticket(&tickets, false, None), Ok("anonymous".to_string()));
+    }
 }
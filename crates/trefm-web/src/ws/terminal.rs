@@ -7,9 +7,10 @@ use axum::response::IntoResponse;
 use base64::engine::general_purpose::STANDARD as BASE64;
 use base64::Engine;
 use futures::{SinkExt, StreamExt};
-use portable_pty::{native_pty_system, CommandBuilder, PtySize};
+use portable_pty::{native_pty_system, CommandBuilder, MasterPty, PtySize};
 use serde::Deserialize;
 use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
 
 use crate::state::AppState;
 
@@ -25,29 +26,18 @@ pub async fn terminal_handler(
     Query(query): Query<TerminalQuery>,
     ws: WebSocketUpgrade,
 ) -> impl IntoResponse {
-    let has_auth = state.config.has_auth();
-
-    let username = if has_auth {
-        // Validate single-use ticket (consume on use)
-        let ticket_id = match &query.ticket {
-            Some(t) => t,
-            None => return axum::http::StatusCode::UNAUTHORIZED.into_response(),
-        };
-        let (_, ticket) = match state.ws_tickets.remove(ticket_id) {
-            Some(t) => t,
-            None => return axum::http::StatusCode::UNAUTHORIZED.into_response(),
-        };
-        // Tickets expire after 30 seconds
-        if ticket.created_at.elapsed() > std::time::Duration::from_secs(30) {
-            return axum::http::StatusCode::UNAUTHORIZED.into_response();
-        }
-        ticket.username
-    } else {
-        "anonymous".to_string()
+    let username = match super::consume_ticket(
+        &state.ws_tickets,
+        state.config.has_auth(),
+        query.ticket.as_deref(),
+    ) {
+        Ok(username) => username,
+        Err(status) => return status.into_response(),
     };
 
     let cwd = resolve_cwd(&state, query.cwd.as_deref(), &username);
-    ws.on_upgrade(move |socket| handle_terminal(socket, cwd))
+    let shutdown = state.shutdown.clone();
+    ws.on_upgrade(move |socket| handle_terminal(socket, cwd, shutdown))
         .into_response()
 }
 
@@ -71,7 +61,11 @@ enum PtyEvent {
     Exit,
 }
 
-#[derive(Deserialize)]
+/// Largest accepted dimension in a `resize` frame, to keep a client from
+/// asking for a huge screen buffer.
+const MAX_DIMENSION: u16 = 500;
+
+#[derive(Debug, PartialEq, Eq, Deserialize)]
 #[serde(tag = "type")]
 enum ClientMessage {
     #[serde(rename = "input")]
@@ -80,7 +74,7 @@ enum ClientMessage {
     Resize { cols: u16, rows: u16 },
 }
 
-async fn handle_terminal(socket: WebSocket, cwd: std::path::PathBuf) {
+async fn handle_terminal(socket: WebSocket, cwd: std::path::PathBuf, shutdown: CancellationToken) {
     let (mut ws_sender, mut ws_receiver) = socket.split();
 
     // Spawn PTY
@@ -165,6 +159,10 @@ async fn handle_terminal(socket: WebSocket, cwd: std::path::PathBuf) {
 
     loop {
         tokio::select! {
+            _ = shutdown.cancelled() => {
+                let _ = ws_sender.send(Message::Close(None)).await;
+                break;
+            }
             pty_event = pty_rx.recv() => {
                 match pty_event {
                     Some(PtyEvent::Output(data)) => {
@@ -198,16 +196,10 @@ async fn handle_terminal(socket: WebSocket, cwd: std::path::PathBuf) {
                                     }
                                 }
                                 ClientMessage::Resize { cols, rows } => {
-                                    // Clamp dimensions to prevent resource exhaustion
-                                    let cols = cols.clamp(1, 500);
-                                    let rows = rows.clamp(1, 500);
                                     if let Ok(m) = master_for_resize.lock() {
-                                        let _ = m.resize(PtySize {
-                                            rows,
-                                            cols,
-                                            pixel_width: 0,
-                                            pixel_height: 0,
-                                        });
+                                        if let Err(e) = resize_pty(m.as_ref(), cols, rows) {
+                                            tracing::warn!("Failed to resize PTY: {e}");
+                                        }
                                     }
                                 }
                             }
@@ -225,3 +217,64 @@ async fn handle_terminal(socket: WebSocket, cwd: std::path::PathBuf) {
     // Cleanup: drop master which kills PTY
     drop(master);
 }
+
+/// Applies a `resize` frame to the PTY, clamping both dimensions to
+/// `1..=MAX_DIMENSION`.
+fn resize_pty(master: &dyn MasterPty, cols: u16, rows: u16) -> anyhow::Result<()> {
+    master.resize(PtySize {
+        rows: rows.clamp(1, MAX_DIMENSION),
+        cols: cols.clamp(1, MAX_DIMENSION),
+        pixel_width: 0,
+        pixel_height: 0,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn open_master() -> Box<dyn MasterPty + Send> {
+        native_pty_system()
+            .openpty(PtySize {
+                rows: 24,
+                cols: 80,
+                pixel_width: 0,
+                pixel_height: 0,
+            })
+            .expect("openpty")
+            .master
+    }
+
+    #[test]
+    fn resize_frame_parses() {
+        let msg: ClientMessage =
+            serde_json::from_str(r#"{"type":"resize","cols":132,"rows":43}"#).unwrap();
+        assert_eq!(msg, ClientMessage::Resize { cols: 132, rows: 43 });
+    }
+
+    #[test]
+    fn malformed_resize_frame_is_rejected() {
+        for frame in [
+            r#"{"type":"resize","cols":-1,"rows":2}"#,
+            r#"{"type":"resize","cols":80}"#,
+        ] {
+            assert!(serde_json::from_str::<ClientMessage>(frame).is_err());
+        }
+    }
+
+    #[test]
+    fn resize_applies_rows_and_cols_to_pty() {
+        let master = open_master();
+        resize_pty(master.as_ref(), 132, 43).unwrap();
+        let size = master.get_size().unwrap();
+        assert_eq!((size.cols, size.rows), (132, 43));
+    }
+
+    #[test]
+    fn resize_clamps_out_of_range_dimensions() {
+        let master = open_master();
+        resize_pty(master.as_ref(), 0, u16::MAX).unwrap();
+        let size = master.get_size().unwrap();
+        assert_eq!((size.cols, size.rows), (1, MAX_DIMENSION));
+    }
+}
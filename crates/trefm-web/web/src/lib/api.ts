@@ -134,8 +134,9 @@ export async function uploadFile(
   targetDir?: string
 ): Promise<UploadResponse> {
   const form = new FormData()
-  form.append('file', file)
+  // The server streams the file straight into `path`, so it must come first
   if (targetDir) form.append('path', targetDir)
+  form.append('file', file)
 
   const headers: Record<string, string> = {}
   if (authToken) {